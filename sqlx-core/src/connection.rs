@@ -6,7 +6,7 @@ use futures_core::future::BoxFuture;
 
 use crate::executor::Executor;
 use crate::pool::{Pool, PoolConnection};
-use crate::transaction::Transaction;
+use crate::transaction::{Transaction, TransactionOptions};
 use crate::url::Url;
 
 /// Represents a single database connection rather than a pool of database connections.
@@ -43,6 +43,23 @@ where
         Box::pin(Transaction::new(0, self))
     }
 
+    /// Starts a new transaction with the given options, e.g. its isolation level.
+    ///
+    /// ```rust,ignore
+    /// let mut tx = conn
+    ///     .begin_with(TransactionOptions::new().isolation(IsolationLevel::Serializable))
+    ///     .await?;
+    /// ```
+    fn begin_with(
+        self,
+        options: TransactionOptions,
+    ) -> BoxFuture<'static, crate::Result<Transaction<Self>>>
+    where
+        Self: Sized,
+    {
+        Box::pin(Transaction::with_options(self, options))
+    }
+
     /// Explicitly close this database connection.
     ///
     /// This method is **not required** for safe and consistent operation. However, it is
@@ -52,6 +69,14 @@ where
 
     /// Checks if a connection to the database is still valid.
     fn ping(&mut self) -> BoxFuture<crate::Result<()>>;
+
+    // Returns `false` if the connection holds something on the server that must not outlive
+    // its current user, e.g. a session-level lock whose release is still queued; a pool
+    // closes such a connection instead of taking it back
+    #[doc(hidden)]
+    fn is_reusable(&self) -> bool {
+        true
+    }
 }
 
 /// Represents a type that can directly establish a new connection.
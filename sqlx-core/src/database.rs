@@ -5,6 +5,7 @@ use crate::connection::Connect;
 use crate::cursor::HasCursor;
 use crate::error::DatabaseError;
 use crate::row::HasRow;
+use crate::transaction::TransactionOptions;
 use crate::types::TypeInfo;
 use crate::value::HasRawValue;
 
@@ -39,4 +40,8 @@ where
 
     /// The concrete `DatabaseError` type used to report errors from the database.
     type Error: DatabaseError + Send + Sync;
+
+    /// The statements that begin a transaction with the given options.
+    #[doc(hidden)]
+    fn begin_transaction(options: &TransactionOptions) -> crate::Result<Vec<String>>;
 }
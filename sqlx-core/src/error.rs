@@ -13,6 +13,10 @@ macro_rules! decode_err {
         crate::Error::Decode(format!($s, $($args)*).into())
     };
 
+    ($s:literal) => {
+        crate::Error::Decode($s.into())
+    };
+
     ($expr:expr) => {
         crate::Error::decode($expr)
     };
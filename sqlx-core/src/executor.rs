@@ -80,6 +80,14 @@ where
     /// will be prepared (and cached) before execution.
     fn into_parts(self) -> (&'q str, Option<DB::Arguments>);
 
+    /// Returns `true` if the prepared statement of the query should be cached and reused.
+    ///
+    /// Ignored for a query without `Arguments`, which is never prepared.
+    #[inline]
+    fn persistent(&self) -> bool {
+        true
+    }
+
     /// Returns the query string, without any parameters replaced.
     #[doc(hidden)]
     fn query_string(&self) -> &'q str;
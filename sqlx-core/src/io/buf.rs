@@ -88,7 +88,7 @@ impl<'a> Buf<'a> for &'a [u8] {
 
     fn get_i64<T: ByteOrder>(&mut self) -> io::Result<i64> {
         let val = T::read_i64(*self);
-        self.advance(4);
+        self.advance(8);
 
         Ok(val)
     }
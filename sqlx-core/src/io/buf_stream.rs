@@ -65,6 +65,23 @@ where
         }
     }
 
+    // Write out the buffer without holding on to it across polls, unlike `flush`;
+    // the written bytes are removed from the buffer as they are written
+    #[cfg(feature = "postgres")]
+    pub fn poll_flush_buf(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while !self.wbuf.is_empty() {
+            let written = ready!(Pin::new(&mut self.stream).poll_write(cx, &self.wbuf))?;
+
+            if written == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+
+            self.wbuf.drain(..written);
+        }
+
+        Pin::new(&mut self.stream).poll_flush(cx)
+    }
+
     #[inline]
     pub fn consume(&mut self, cnt: usize) {
         self.rbuf_rindex += cnt;
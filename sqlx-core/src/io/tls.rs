@@ -71,6 +71,18 @@ impl MaybeTlsStream {
         Ok(())
     }
 
+    // Returns the `tls-server-end-point` channel binding data (RFC 5929), a hash of
+    // the server's certificate, or `None` if the connection is not secured by TLS
+    #[cfg(feature = "postgres")]
+    pub fn tls_server_end_point(&self) -> crate::Result<Option<Vec<u8>>> {
+        match self.inner {
+            #[cfg(feature = "tls")]
+            Tls(ref conn) => Ok(conn.tls_server_end_point()?),
+
+            _ => Ok(None),
+        }
+    }
+
     pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
         match self.inner {
             NotTls(ref conn) => conn.shutdown(how),
@@ -1,10 +1,10 @@
 //! Core of SQLx, the rust SQL toolkit. Not intended to be used directly.
 
-// When compiling with support for SQLite we must allow some unsafe code in order to
+// When compiling with support for SQLite (or GSSAPI) we must allow some unsafe code in order to
 // interface with the inherently unsafe C module. This unsafe code is contained
-// to the sqlite module.
-#![cfg_attr(feature = "sqlite", deny(unsafe_code))]
-#![cfg_attr(not(feature = "sqlite"), forbid(unsafe_code))]
+// to the sqlite (and postgres::gss) module.
+#![cfg_attr(any(feature = "sqlite", feature = "gssapi"), deny(unsafe_code))]
+#![cfg_attr(not(any(feature = "sqlite", feature = "gssapi")), forbid(unsafe_code))]
 #![recursion_limit = "512"]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![cfg_attr(all(test, feature = "bench"), feature(test))]
@@ -53,6 +53,12 @@ pub mod query_as;
 
 pub mod types;
 
+#[cfg(any(
+    all(feature = "postgres", feature = "postgis"),
+    all(feature = "mysql", feature = "mysql-spatial")
+))]
+mod wkb;
+
 #[macro_use]
 pub mod row;
 
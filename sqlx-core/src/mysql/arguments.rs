@@ -2,6 +2,7 @@ use crate::arguments::Arguments;
 use crate::encode::{Encode, IsNull};
 use crate::mysql::type_info::MySqlTypeInfo;
 use crate::mysql::MySql;
+use crate::runtime::AsyncRead;
 use crate::types::Type;
 
 #[derive(Default)]
@@ -9,6 +10,53 @@ pub struct MySqlArguments {
     pub(crate) param_types: Vec<MySqlTypeInfo>,
     pub(crate) params: Vec<u8>,
     pub(crate) null_bitmap: Vec<u8>,
+    pub(crate) attributes: Vec<MySqlQueryAttribute>,
+
+    // Parameters whose values are streamed to the server with COM_STMT_SEND_LONG_DATA
+    // before the statement is executed; they have no value in `params`
+    pub(crate) long_data: Vec<(u16, Box<dyn AsyncRead + Send + Unpin>)>,
+}
+
+// A query attribute (MySQL 8.0.23+), sent after the parameters of the statement
+#[derive(Debug)]
+pub(crate) struct MySqlQueryAttribute {
+    pub(crate) name: String,
+    pub(crate) type_info: MySqlTypeInfo,
+
+    // The encoded value; `None` if the value is NULL
+    pub(crate) value: Option<Vec<u8>>,
+}
+
+impl MySqlArguments {
+    pub(crate) fn add_long_data<R>(&mut self, reader: R)
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+    {
+        let index = self.param_types.len();
+
+        self.param_types.push(<[u8] as Type<MySql>>::type_info());
+        self.null_bitmap.resize((index / 8) + 1, 0);
+        self.long_data.push((index as u16, Box::new(reader)));
+    }
+
+    pub(crate) fn add_attribute<T>(&mut self, name: &str, value: T)
+    where
+        T: Type<MySql>,
+        T: Encode<MySql>,
+    {
+        let mut buf = Vec::new();
+
+        let value = match value.encode_nullable(&mut buf) {
+            IsNull::Yes => None,
+            IsNull::No => Some(buf),
+        };
+
+        self.attributes.push(MySqlQueryAttribute {
+            name: name.to_owned(),
+            type_info: <T as Type<MySql>>::type_info(),
+            value,
+        });
+    }
 }
 
 impl Arguments for MySqlArguments {
@@ -0,0 +1,659 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use byteorder::LittleEndian;
+
+use crate::io::Buf;
+use crate::mysql::binlog::value::{self, MySqlBinlogValue};
+use crate::mysql::io::BufExt;
+
+// The types of the events that are decoded; the others are skipped
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.12/binlog__event_8h.html
+const QUERY_EVENT: u8 = 2;
+const ROTATE_EVENT: u8 = 4;
+const FORMAT_DESCRIPTION_EVENT: u8 = 15;
+const XID_EVENT: u8 = 16;
+const TABLE_MAP_EVENT: u8 = 19;
+const WRITE_ROWS_EVENT_V1: u8 = 23;
+const UPDATE_ROWS_EVENT_V1: u8 = 24;
+const DELETE_ROWS_EVENT_V1: u8 = 25;
+const WRITE_ROWS_EVENT: u8 = 30;
+const UPDATE_ROWS_EVENT: u8 = 31;
+const DELETE_ROWS_EVENT: u8 = 32;
+const GTID_LOG_EVENT: u8 = 33;
+
+// The length of the common header of every event
+const HEADER_LEN: usize = 19;
+
+// The length of the checksum that ends an event, if the binlog has checksums
+const CHECKSUM_LEN: usize = 4;
+
+// The types of the optional metadata of a table map that are decoded
+const SIGNEDNESS: u8 = 1;
+const COLUMN_NAME: u8 = 4;
+const SIMPLE_PRIMARY_KEY: u8 = 8;
+const PRIMARY_KEY_WITH_PREFIX: u8 = 9;
+
+/// A table, as described by the server before the first change to it in each transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MySqlBinlogTable {
+    /// The ID of the table, which the server may reuse for another table later.
+    pub id: u64,
+
+    pub schema: String,
+    pub name: String,
+    pub columns: Vec<MySqlBinlogColumn>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MySqlBinlogColumn {
+    /// The name of the column; only logged with `binlog_row_metadata = FULL` (MySQL 8.0.1
+    /// or later).
+    pub name: Option<String>,
+
+    /// The type of the column in the binlog, e.g. `15` for `VARCHAR` or `18` for `DATETIME`.
+    pub type_id: u8,
+
+    // The size, precision or real type of the column, depending on its type
+    pub(super) metadata: [u8; 2],
+
+    pub is_nullable: bool,
+
+    /// Is this an unsigned numeric column; only logged by MySQL 8.0.1 or later.
+    pub is_unsigned: bool,
+
+    /// Is this column part of the primary key; only logged by MySQL 8.0.1 or later.
+    pub is_key: bool,
+}
+
+impl MySqlBinlogTable {
+    /// Returns the index of the column with the given name.
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.columns
+            .iter()
+            .position(|column| column.name.as_deref() == Some(name))
+    }
+}
+
+/// A row streamed from the binlog, with a value for each column of its table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MySqlBinlogRow(pub Vec<MySqlBinlogValue>);
+
+impl MySqlBinlogRow {
+    /// Returns the value of the column at `index` or `None` if it is `NULL`, missing
+    /// or out of bounds.
+    pub fn get(&self, index: usize) -> Option<&MySqlBinlogValue> {
+        match self.0.get(index) {
+            Some(MySqlBinlogValue::Null) | Some(MySqlBinlogValue::Missing) | None => None,
+            Some(value) => Some(value),
+        }
+    }
+}
+
+/// An event streamed from the binlog.
+///
+/// A transaction starts with a [Gtid], then a [Query] of `BEGIN`, then its changes, and
+/// ends with a [Commit] (or a [Query] of `COMMIT` for tables that do not support transactions).
+/// Statements that are not logged as changes of rows, such as DDL, are streamed as a [Query].
+///
+/// [Gtid]: MySqlBinlogEvent::Gtid
+/// [Query]: MySqlBinlogEvent::Query
+/// [Commit]: MySqlBinlogEvent::Commit
+#[derive(Debug, Clone, PartialEq)]
+pub enum MySqlBinlogEvent {
+    /// The binlog file that the following events are read from; sent first and whenever
+    /// the server moves on to the next file.
+    Rotate { file: String, position: u64 },
+
+    /// The start of a transaction, with its global transaction identifier,
+    /// e.g. `3e11fa47-71ca-11e1-9e33-c80aa9429562:23`.
+    Gtid { gtid: String },
+
+    /// A statement that is logged as is.
+    Query {
+        /// The default database of the statement.
+        schema: String,
+        query: String,
+    },
+
+    Insert {
+        table: Arc<MySqlBinlogTable>,
+        after: MySqlBinlogRow,
+    },
+
+    Update {
+        table: Arc<MySqlBinlogTable>,
+        before: MySqlBinlogRow,
+        after: MySqlBinlogRow,
+    },
+
+    Delete {
+        table: Arc<MySqlBinlogTable>,
+        before: MySqlBinlogRow,
+    },
+
+    /// The end of a transaction.
+    Commit {
+        xid: u64,
+
+        /// The commit timestamp, in seconds since the UNIX epoch.
+        timestamp: u32,
+    },
+}
+
+// Decodes the events of a binlog, remembering the tables described so far
+#[derive(Default)]
+pub(super) struct Decoder {
+    tables: HashMap<u64, Arc<MySqlBinlogTable>>,
+
+    // Do events end with a checksum; not known until the first format description
+    checksum: Option<bool>,
+}
+
+impl Decoder {
+    // Decode an event, appending the changes that it holds to `events`
+    // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_replication_binlog_event.html
+    pub(super) fn decode(
+        &mut self,
+        event: &[u8],
+        events: &mut Vec<MySqlBinlogEvent>,
+    ) -> crate::Result<()> {
+        if event.len() < HEADER_LEN {
+            return Err(
+                protocol_err!("binlog: event of {} bytes is too short", event.len()).into(),
+            );
+        }
+
+        let mut header = event;
+
+        let timestamp = header.get_u32::<LittleEndian>()?;
+        let event_type = header.get_u8()?;
+
+        let checksum = match (event_type, self.checksum) {
+            // the format description tells if the events of the binlog file have checksums;
+            // it ends with the checksum algorithm (0 for none) and always has a checksum itself
+            (FORMAT_DESCRIPTION_EVENT, _) => {
+                if event.len() < HEADER_LEN + CHECKSUM_LEN + 1 {
+                    return Err(protocol_err!("binlog: format description is too short").into());
+                }
+
+                self.checksum = Some(event[event.len() - CHECKSUM_LEN - 1] != 0);
+
+                true
+            }
+
+            (_, Some(checksum)) => checksum,
+
+            // the first event is the rotate to the first binlog file, which has a checksum
+            // only if the binlog file does
+            (_, None) => event.len() >= HEADER_LEN + CHECKSUM_LEN && checksum_matches(event),
+        };
+
+        let body = if checksum {
+            if !checksum_matches(event) {
+                return Err(protocol_err!("binlog: checksum mismatch in event").into());
+            }
+
+            &event[HEADER_LEN..event.len() - CHECKSUM_LEN]
+        } else {
+            &event[HEADER_LEN..]
+        };
+
+        match event_type {
+            ROTATE_EVENT => {
+                let mut buf = body;
+                let position = buf.get_u64::<LittleEndian>()?;
+
+                events.push(MySqlBinlogEvent::Rotate {
+                    file: String::from_utf8_lossy(buf).into_owned(),
+                    position,
+                });
+            }
+
+            GTID_LOG_EVENT => {
+                let mut buf = body;
+
+                // flags
+                let _ = buf.get_u8()?;
+
+                let sid = buf.get_bytes(16)?;
+                let gno = buf.get_i64::<LittleEndian>()?;
+
+                events.push(MySqlBinlogEvent::Gtid {
+                    gtid: format!("{}:{}", format_uuid(sid), gno),
+                });
+            }
+
+            QUERY_EVENT => events.push(read_query(body)?),
+
+            XID_EVENT => {
+                let mut buf = body;
+
+                events.push(MySqlBinlogEvent::Commit {
+                    xid: buf.get_u64::<LittleEndian>()?,
+                    timestamp,
+                });
+            }
+
+            TABLE_MAP_EVENT => {
+                let table = read_table_map(body)?;
+
+                self.tables.insert(table.id, Arc::new(table));
+            }
+
+            WRITE_ROWS_EVENT_V1 | UPDATE_ROWS_EVENT_V1 | DELETE_ROWS_EVENT_V1
+            | WRITE_ROWS_EVENT | UPDATE_ROWS_EVENT | DELETE_ROWS_EVENT => {
+                self.read_rows(event_type, body, events)?;
+            }
+
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/classbinary__log_1_1Rows__event.html
+    fn read_rows(
+        &self,
+        event_type: u8,
+        mut buf: &[u8],
+        events: &mut Vec<MySqlBinlogEvent>,
+    ) -> crate::Result<()> {
+        let table_id = buf.get_uint::<LittleEndian>(6)?;
+
+        // flags
+        let _ = buf.get_u16::<LittleEndian>()?;
+
+        if event_type >= WRITE_ROWS_EVENT {
+            // extra data, which includes its own length
+            let len = buf.get_u16::<LittleEndian>()? as usize;
+            let _ = buf.get_bytes(len.saturating_sub(2))?;
+        }
+
+        let table = match self.tables.get(&table_id) {
+            Some(table) => Arc::clone(table),
+
+            None => {
+                return Err(protocol_err!("binlog: rows of unknown table {}", table_id).into());
+            }
+        };
+
+        let width = buf.get_uint_lenenc::<LittleEndian>()?.unwrap_or(0) as usize;
+
+        if width > table.columns.len() {
+            return Err(protocol_err!(
+                "binlog: rows of {} columns for table `{}` of {} columns",
+                width,
+                table.name,
+                table.columns.len()
+            )
+            .into());
+        }
+
+        // the columns that are logged in the before and after images of the rows
+        let present = read_bitmap(&mut buf, width)?;
+        let present_after = match event_type {
+            UPDATE_ROWS_EVENT_V1 | UPDATE_ROWS_EVENT => read_bitmap(&mut buf, width)?,
+            _ => present.clone(),
+        };
+
+        while !buf.is_empty() {
+            let row = read_row(&mut buf, &table, &present)?;
+
+            events.push(match event_type {
+                WRITE_ROWS_EVENT_V1 | WRITE_ROWS_EVENT => MySqlBinlogEvent::Insert {
+                    table: Arc::clone(&table),
+                    after: row,
+                },
+
+                UPDATE_ROWS_EVENT_V1 | UPDATE_ROWS_EVENT => MySqlBinlogEvent::Update {
+                    table: Arc::clone(&table),
+                    before: row,
+                    after: read_row(&mut buf, &table, &present_after)?,
+                },
+
+                _ => MySqlBinlogEvent::Delete {
+                    table: Arc::clone(&table),
+                    before: row,
+                },
+            });
+        }
+
+        Ok(())
+    }
+}
+
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.12/classbinary__log_1_1Query__event.html
+fn read_query(mut buf: &[u8]) -> crate::Result<MySqlBinlogEvent> {
+    // thread_id : int<4>, exec_time : int<4>
+    let _ = buf.get_bytes(8)?;
+
+    let schema_len = buf.get_u8()? as usize;
+
+    // error_code : int<2>
+    let _ = buf.get_u16::<LittleEndian>()?;
+
+    let status_vars_len = buf.get_u16::<LittleEndian>()? as usize;
+    let _ = buf.get_bytes(status_vars_len)?;
+
+    let schema = String::from_utf8_lossy(buf.get_bytes(schema_len)?).into_owned();
+
+    // the schema is followed by a NUL
+    let _ = buf.get_u8()?;
+
+    Ok(MySqlBinlogEvent::Query {
+        schema,
+        query: String::from_utf8_lossy(buf).into_owned(),
+    })
+}
+
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.12/classbinary__log_1_1Table__map__event.html
+fn read_table_map(mut buf: &[u8]) -> crate::Result<MySqlBinlogTable> {
+    let id = buf.get_uint::<LittleEndian>(6)?;
+
+    // flags
+    let _ = buf.get_u16::<LittleEndian>()?;
+
+    let schema_len = buf.get_u8()? as usize;
+    let schema = String::from_utf8_lossy(buf.get_bytes(schema_len)?).into_owned();
+    let _ = buf.get_u8()?;
+
+    let name_len = buf.get_u8()? as usize;
+    let name = String::from_utf8_lossy(buf.get_bytes(name_len)?).into_owned();
+    let _ = buf.get_u8()?;
+
+    let count = buf.get_uint_lenenc::<LittleEndian>()?.unwrap_or(0) as usize;
+    let types = buf.get_bytes(count)?;
+
+    let mut metadata = buf.get_bytes_lenenc::<LittleEndian>()?.unwrap_or_default();
+    let mut columns = Vec::with_capacity(count);
+
+    for &type_id in types {
+        let mut column_metadata = [0; 2];
+        let len = value::metadata_len(type_id);
+
+        column_metadata[..len].copy_from_slice(metadata.get_bytes(len)?);
+
+        columns.push(MySqlBinlogColumn {
+            name: None,
+            type_id,
+            metadata: column_metadata,
+            is_nullable: false,
+            is_unsigned: false,
+            is_key: false,
+        });
+    }
+
+    for (column, nullable) in columns.iter_mut().zip(read_bitmap(&mut buf, count)?) {
+        column.is_nullable = nullable;
+    }
+
+    // optional metadata, logged by MySQL 8.0.1 or later, as (type, length, value)
+    while !buf.is_empty() {
+        let kind = buf.get_u8()?;
+        let len = buf.get_uint_lenenc::<LittleEndian>()?.unwrap_or(0) as usize;
+        let mut value = buf.get_bytes(len)?;
+
+        match kind {
+            // a bit for each numeric column, most significant bit first
+            SIGNEDNESS => {
+                let numeric = columns
+                    .iter_mut()
+                    .filter(|column| value::is_numeric(column.type_id));
+
+                for (i, column) in numeric.enumerate() {
+                    column.is_unsigned = matches!(
+                        value.get(i / 8),
+                        Some(byte) if byte & (0x80 >> (i % 8)) != 0
+                    );
+                }
+            }
+
+            COLUMN_NAME => {
+                for column in &mut columns {
+                    column.name = value
+                        .get_str_lenenc::<LittleEndian>()?
+                        .map(ToOwned::to_owned);
+                }
+            }
+
+            SIMPLE_PRIMARY_KEY | PRIMARY_KEY_WITH_PREFIX => {
+                while !value.is_empty() {
+                    let index = value.get_uint_lenenc::<LittleEndian>()?.unwrap_or(0) as usize;
+
+                    if kind == PRIMARY_KEY_WITH_PREFIX {
+                        // the length of the prefix of the column that is indexed
+                        let _ = value.get_uint_lenenc::<LittleEndian>()?;
+                    }
+
+                    if let Some(column) = columns.get_mut(index) {
+                        column.is_key = true;
+                    }
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    Ok(MySqlBinlogTable {
+        id,
+        schema,
+        name,
+        columns,
+    })
+}
+
+// A row image: a bitmap of the columns that are `NULL` (of the columns that are logged),
+// then the values of the other columns
+fn read_row(
+    buf: &mut &[u8],
+    table: &MySqlBinlogTable,
+    present: &[bool],
+) -> crate::Result<MySqlBinlogRow> {
+    let count = present.iter().filter(|&&present| present).count();
+    let nulls = read_bitmap(buf, count)?;
+
+    let mut nulls = nulls.into_iter();
+    let mut values = Vec::with_capacity(table.columns.len());
+
+    for (i, column) in table.columns.iter().enumerate() {
+        let value = if !present.get(i).copied().unwrap_or(false) {
+            MySqlBinlogValue::Missing
+        } else if nulls.next().unwrap_or(false) {
+            MySqlBinlogValue::Null
+        } else {
+            value::read_value(buf, column)?
+        };
+
+        values.push(value);
+    }
+
+    Ok(MySqlBinlogRow(values))
+}
+
+// A bitmap of `len` bits, least significant bit first
+fn read_bitmap(buf: &mut &[u8], len: usize) -> crate::Result<Vec<bool>> {
+    let bytes = buf.get_bytes((len + 7) >> 3)?;
+
+    Ok((0..len)
+        .map(|i| bytes[i / 8] & (1 << (i % 8)) != 0)
+        .collect())
+}
+
+// Does the checksum at the end of an event match the rest of the event
+fn checksum_matches(event: &[u8]) -> bool {
+    let (data, mut checksum) = event.split_at(event.len() - CHECKSUM_LEN);
+
+    matches!(checksum.get_u32::<LittleEndian>(), Ok(checksum) if crc32(data) == checksum)
+}
+
+// CRC-32 (IEEE), as used by the binlog
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0_u32;
+
+    for &byte in data {
+        crc ^= u32::from(byte);
+
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+fn format_uuid(uuid: &[u8]) -> String {
+    let hex: String = uuid.iter().map(|byte| format!("{:02x}", byte)).collect();
+
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crc32, Decoder, MySqlBinlogEvent, MySqlBinlogRow, MySqlBinlogValue};
+
+    // Wrap the body of an event in a header and, optionally, a checksum
+    fn event(event_type: u8, body: &[u8], checksum: bool) -> Vec<u8> {
+        let len = 19 + body.len() + if checksum { 4 } else { 0 };
+
+        let mut event = Vec::new();
+        event.extend_from_slice(&1_580_000_000_u32.to_le_bytes());
+        event.push(event_type);
+        event.extend_from_slice(&1_u32.to_le_bytes());
+        event.extend_from_slice(&(len as u32).to_le_bytes());
+        event.extend_from_slice(&0_u32.to_le_bytes());
+        event.extend_from_slice(&0_u16.to_le_bytes());
+        event.extend_from_slice(body);
+
+        if checksum {
+            let crc = crc32(&event);
+            event.extend_from_slice(&crc.to_le_bytes());
+        }
+
+        event
+    }
+
+    #[test]
+    fn it_computes_checksums() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn it_decodes_row_events() {
+        let mut decoder = Decoder::default();
+        let mut events = Vec::new();
+
+        // the first rotate, with a checksum
+        decoder
+            .decode(
+                &event(4, b"\x04\x00\x00\x00\x00\x00\x00\x00binlog.000001", true),
+                &mut events,
+            )
+            .unwrap();
+
+        // a format description of a binlog with checksums (algorithm 1, CRC32)
+        let mut description = vec![4, 0];
+        description.extend_from_slice(&[0; 50 + 4]);
+        description.push(19);
+        description.extend_from_slice(&[0; 40]);
+        description.push(1);
+
+        decoder
+            .decode(&event(15, &description, true), &mut events)
+            .unwrap();
+
+        // `test`.`users` (id INT UNSIGNED PRIMARY KEY, name VARCHAR(10) NULL)
+        let table_map = b"\x2A\x00\x00\x00\x00\x00\x01\x00\
+            \x04test\x00\x05users\x00\
+            \x02\x03\x0F\x02\x28\x00\x02\
+            \x01\x01\x80\
+            \x04\x08\x02id\x04name\
+            \x08\x01\x00";
+
+        decoder
+            .decode(&event(19, table_map, true), &mut events)
+            .unwrap();
+
+        // UPDATE users SET name = NULL WHERE id = 7 (name was 'alice')
+        let update_rows = b"\x2A\x00\x00\x00\x00\x00\x01\x00\x02\x00\
+            \x02\x03\x03\
+            \x00\x07\x00\x00\x00\x05alice\
+            \x02\x07\x00\x00\x00";
+
+        decoder
+            .decode(&event(31, update_rows, true), &mut events)
+            .unwrap();
+
+        decoder
+            .decode(
+                &event(16, b"\x2A\x00\x00\x00\x00\x00\x00\x00", true),
+                &mut events,
+            )
+            .unwrap();
+
+        assert_eq!(events.len(), 3);
+
+        assert_eq!(
+            events[0],
+            MySqlBinlogEvent::Rotate {
+                file: "binlog.000001".to_owned(),
+                position: 4
+            }
+        );
+
+        match &events[1] {
+            MySqlBinlogEvent::Update {
+                table,
+                before,
+                after,
+            } => {
+                assert_eq!(table.schema, "test");
+                assert_eq!(table.name, "users");
+                assert_eq!(table.column_index("name"), Some(1));
+                assert!(table.columns[0].is_unsigned && table.columns[0].is_key);
+                assert!(!table.columns[0].is_nullable && table.columns[1].is_nullable);
+
+                assert_eq!(
+                    before,
+                    &MySqlBinlogRow(vec![
+                        MySqlBinlogValue::UInt(7),
+                        MySqlBinlogValue::Bytes(b"alice".to_vec())
+                    ])
+                );
+
+                assert_eq!(
+                    after,
+                    &MySqlBinlogRow(vec![MySqlBinlogValue::UInt(7), MySqlBinlogValue::Null])
+                );
+            }
+
+            event => panic!("unexpected event: {:?}", event),
+        }
+
+        assert_eq!(
+            events[2],
+            MySqlBinlogEvent::Commit {
+                xid: 42,
+                timestamp: 1_580_000_000
+            }
+        );
+
+        // a corrupt event is an error
+        let mut corrupt = event(16, b"\x2A\x00\x00\x00\x00\x00\x00\x00", true);
+        corrupt[19] = 0;
+
+        assert!(decoder.decode(&corrupt, &mut events).is_err());
+    }
+}
@@ -0,0 +1,253 @@
+//! Replication (change data capture) from the binary log of a MySQL server.
+
+use std::collections::VecDeque;
+
+use byteorder::LittleEndian;
+
+use crate::executor::Executor;
+use crate::io::BufMut;
+use crate::mysql::protocol::ComBinlogDumpGtid;
+use crate::mysql::MySqlConnection;
+
+mod event;
+mod value;
+
+pub use event::{MySqlBinlogColumn, MySqlBinlogEvent, MySqlBinlogRow, MySqlBinlogTable};
+pub use value::MySqlBinlogValue;
+
+// The largest payload of a packet; an event in a packet of this size continues in
+// the next packet
+const MAX_PAYLOAD_LEN: usize = 0xFF_FF_FF;
+
+/// A stream of the changes in the binary log (binlog) of the server, as sent to a replica.
+///
+/// Returned from [MySqlConnection::start_binlog]. The server must log changes of rows
+/// (`binlog_format = ROW`, the default) with GTIDs (`gtid_mode = ON`), and the user must
+/// have the `REPLICATION SLAVE` privilege.
+///
+/// ```rust,ignore
+/// let mut conn = MySqlConnection::connect("mysql://...").await?;
+///
+/// let mut stream = conn.start_binlog(1001, "").await?;
+///
+/// while let Some(event) = stream.next().await? {
+///     if let MySqlBinlogEvent::Insert { table, after } = event {
+///         println!("{}.{}: {:?}", table.schema, table.name, after);
+///     }
+/// }
+/// ```
+///
+/// The server sends the changes as they are committed and never ends the stream; the
+/// connection can not be used for anything else afterwards.
+pub struct MySqlBinlogStream<'c> {
+    conn: &'c mut MySqlConnection,
+    decoder: event::Decoder,
+
+    // events decoded but not yet returned, as a binlog event may change several rows
+    events: VecDeque<MySqlBinlogEvent>,
+    buf: Vec<u8>,
+
+    done: bool,
+}
+
+impl MySqlConnection {
+    /// Register as a replica with the given server ID and stream the changes in the binlog,
+    /// skipping the transactions in `gtid_set`.
+    ///
+    /// `server_id` must not be the ID of the server or of any other replica of it.
+    /// `gtid_set` is in the format of `@@gtid_executed`,
+    /// e.g. `3e11fa47-71ca-11e1-9e33-c80aa9429562:1-5`; pass the GTIDs of the transactions
+    /// that were already processed to resume the stream, or an empty string to stream the
+    /// binlog from the start.
+    pub async fn start_binlog(
+        &mut self,
+        server_id: u32,
+        gtid_set: &str,
+    ) -> crate::Result<MySqlBinlogStream<'_>> {
+        let gtid_set = encode_gtid_set(gtid_set)?;
+
+        // declare that we handle checksums, or the server refuses to send events that
+        // have them; the variable was renamed in MySQL 8.0.26
+        self.execute(
+            "SET @master_binlog_checksum = @@global.binlog_checksum, \
+             @source_binlog_checksum = @@global.binlog_checksum",
+        )
+        .await?;
+
+        self.stream.wait_until_ready().await?;
+        self.stream.is_ready = false;
+
+        // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_com_binlog_dump_gtid.html
+        self.stream
+            .send(
+                ComBinlogDumpGtid {
+                    server_id,
+                    gtid_set: &gtid_set,
+                },
+                true,
+            )
+            .await?;
+
+        Ok(MySqlBinlogStream {
+            conn: self,
+            decoder: event::Decoder::default(),
+            events: VecDeque::new(),
+            buf: Vec::new(),
+            done: false,
+        })
+    }
+}
+
+impl MySqlBinlogStream<'_> {
+    /// Wait for the next event.
+    ///
+    /// Returns `None` if the server ended the stream.
+    pub async fn next(&mut self) -> crate::Result<Option<MySqlBinlogEvent>> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Ok(Some(event));
+            }
+
+            if self.done {
+                return Ok(None);
+            }
+
+            let packet = self.conn.stream.receive().await?;
+
+            match packet[0] {
+                // an event, after an OK byte
+                0x00 => {
+                    let mut len = packet.len();
+
+                    self.buf.clear();
+                    self.buf.extend_from_slice(&packet[1..]);
+
+                    while len == MAX_PAYLOAD_LEN {
+                        let packet = self.conn.stream.receive().await?;
+
+                        len = packet.len();
+                        self.buf.extend_from_slice(packet);
+                    }
+
+                    let mut events = Vec::new();
+
+                    self.decoder.decode(&self.buf, &mut events)?;
+                    self.events.extend(events);
+                }
+
+                // EOF packet; the end of the binlog
+                0xFE if packet.len() < MAX_PAYLOAD_LEN => {
+                    self.conn.stream.is_ready = true;
+                    self.done = true;
+                }
+
+                0xFF => {
+                    self.done = true;
+
+                    return self.conn.stream.handle_err();
+                }
+
+                _ => {
+                    return self.conn.stream.handle_unexpected();
+                }
+            }
+        }
+    }
+}
+
+impl Drop for MySqlBinlogStream<'_> {
+    fn drop(&mut self) {
+        if !self.done {
+            // the server keeps sending events and has no command to stop;
+            // the connection can only be closed
+            let _ = self.conn.stream.shutdown();
+        }
+    }
+}
+
+// Encode a GTID set, e.g. `3e11fa47-71ca-11e1-9e33-c80aa9429562:1-5:7,...`, in its
+// binary format: the number of UUIDs, then each UUID and its intervals of transaction
+// numbers, each interval from its first number to after its last number
+fn encode_gtid_set(gtid_set: &str) -> crate::Result<Vec<u8>> {
+    let sids: Vec<&str> = gtid_set
+        .split(',')
+        .map(str::trim)
+        .filter(|sid| !sid.is_empty())
+        .collect();
+
+    let mut buf = Vec::new();
+    buf.put_u64::<LittleEndian>(sids.len() as u64);
+
+    for sid in sids {
+        let mut parts = sid.split(':');
+
+        let uuid = parts.next().unwrap_or_default().replace('-', "");
+
+        if uuid.len() != 32 || !uuid.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(protocol_err!("invalid GTID set: {:?}", gtid_set).into());
+        }
+
+        for i in 0..16 {
+            match u8::from_str_radix(&uuid[i * 2..i * 2 + 2], 16) {
+                Ok(byte) => buf.put_u8(byte),
+                Err(_) => return Err(protocol_err!("invalid GTID set: {:?}", gtid_set).into()),
+            }
+        }
+
+        let intervals: Vec<&str> = parts.collect();
+        buf.put_u64::<LittleEndian>(intervals.len() as u64);
+
+        for interval in intervals {
+            let mut bounds = interval.splitn(2, '-');
+
+            let start = bounds.next().unwrap_or_default();
+            let end = bounds.next().unwrap_or(start);
+
+            match (start.trim().parse::<u64>(), end.trim().parse::<u64>()) {
+                (Ok(start), Ok(end)) if start <= end => {
+                    buf.put_u64::<LittleEndian>(start);
+                    buf.put_u64::<LittleEndian>(end + 1);
+                }
+
+                _ => return Err(protocol_err!("invalid GTID set: {:?}", gtid_set).into()),
+            }
+        }
+    }
+
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::encode_gtid_set;
+
+    #[test]
+    fn it_encodes_gtid_sets() {
+        assert_eq!(encode_gtid_set("").unwrap(), vec![0; 8]);
+
+        let set = encode_gtid_set(
+            "3e11fa47-71ca-11e1-9e33-c80aa9429562:1-5:7,\n\
+             3E11FA47-71CA-11E1-9E33-C80AA9429563:2",
+        )
+        .unwrap();
+
+        let mut expected = vec![2, 0, 0, 0, 0, 0, 0, 0];
+        expected
+            .extend_from_slice(b"\x3e\x11\xfa\x47\x71\xca\x11\xe1\x9e\x33\xc8\x0a\xa9\x42\x95\x62");
+        expected.extend_from_slice(&2_u64.to_le_bytes());
+        expected.extend_from_slice(&1_u64.to_le_bytes());
+        expected.extend_from_slice(&6_u64.to_le_bytes());
+        expected.extend_from_slice(&7_u64.to_le_bytes());
+        expected.extend_from_slice(&8_u64.to_le_bytes());
+        expected
+            .extend_from_slice(b"\x3e\x11\xfa\x47\x71\xca\x11\xe1\x9e\x33\xc8\x0a\xa9\x42\x95\x63");
+        expected.extend_from_slice(&1_u64.to_le_bytes());
+        expected.extend_from_slice(&2_u64.to_le_bytes());
+        expected.extend_from_slice(&3_u64.to_le_bytes());
+
+        assert_eq!(set, expected);
+
+        assert!(encode_gtid_set("3e11fa47:1-5").is_err());
+        assert!(encode_gtid_set("3e11fa47-71ca-11e1-9e33-c80aa9429562:5-1").is_err());
+    }
+}
@@ -0,0 +1,467 @@
+use byteorder::{BigEndian, LittleEndian};
+
+use crate::io::Buf;
+use crate::mysql::binlog::MySqlBinlogColumn;
+
+// The types of columns in the binlog, which include some that are never sent to clients
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.12/binary__log__types_8h.html
+pub(super) const TINY: u8 = 1;
+pub(super) const SHORT: u8 = 2;
+pub(super) const LONG: u8 = 3;
+pub(super) const FLOAT: u8 = 4;
+pub(super) const DOUBLE: u8 = 5;
+pub(super) const NULL: u8 = 6;
+pub(super) const TIMESTAMP: u8 = 7;
+pub(super) const LONGLONG: u8 = 8;
+pub(super) const INT24: u8 = 9;
+pub(super) const DATE: u8 = 10;
+pub(super) const TIME: u8 = 11;
+pub(super) const DATETIME: u8 = 12;
+pub(super) const YEAR: u8 = 13;
+pub(super) const VARCHAR: u8 = 15;
+pub(super) const BIT: u8 = 16;
+pub(super) const TIMESTAMP2: u8 = 17;
+pub(super) const DATETIME2: u8 = 18;
+pub(super) const TIME2: u8 = 19;
+pub(super) const JSON: u8 = 245;
+pub(super) const NEWDECIMAL: u8 = 246;
+pub(super) const ENUM: u8 = 247;
+pub(super) const SET: u8 = 248;
+pub(super) const BLOB: u8 = 252;
+pub(super) const VAR_STRING: u8 = 253;
+pub(super) const STRING: u8 = 254;
+pub(super) const GEOMETRY: u8 = 255;
+
+/// The value of a single column in a [MySqlBinlogRow].
+///
+/// [MySqlBinlogRow]: struct.MySqlBinlogRow.html
+#[derive(Debug, Clone, PartialEq)]
+pub enum MySqlBinlogValue {
+    Null,
+
+    /// A column that was not logged, as the server logs only some of the columns of a
+    /// row unless `binlog_row_image` is `FULL`.
+    Missing,
+
+    /// A signed integer, or an unsigned integer if the server does not log signedness
+    /// (before MySQL 8.0.1).
+    Int(i64),
+
+    /// An unsigned integer, the year of a `YEAR`, or the index of an `ENUM` value or the
+    /// bits of a `SET` value.
+    UInt(u64),
+
+    Float(f32),
+    Double(f64),
+
+    /// A `DECIMAL`, `DATE`, `TIME` or `DATETIME` value in its text format,
+    /// e.g. `2020-01-31 12:00:00.5`.
+    Text(String),
+
+    /// A `TIMESTAMP` value, in microseconds since the UNIX epoch.
+    Timestamp(i64),
+
+    /// A string or `BLOB` (in the character set of its column), a `BIT` value (big-endian),
+    /// a geometry, or a `JSON` value in the binary format of MySQL.
+    Bytes(Vec<u8>),
+}
+
+// Returns the length of the metadata of a column in a table map
+pub(super) fn metadata_len(type_id: u8) -> usize {
+    match type_id {
+        FLOAT | DOUBLE | BLOB | GEOMETRY | JSON | TIMESTAMP2 | DATETIME2 | TIME2 => 1,
+        VARCHAR | VAR_STRING | BIT | NEWDECIMAL | STRING | ENUM | SET => 2,
+        _ => 0,
+    }
+}
+
+// Returns `true` for the types of the columns that the signedness of is logged
+pub(super) fn is_numeric(type_id: u8) -> bool {
+    matches!(
+        type_id,
+        TINY | SHORT | INT24 | LONG | LONGLONG | FLOAT | DOUBLE | NEWDECIMAL
+    )
+}
+
+// Read the value of a column from a row image
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.12/classbinary__log_1_1Rows__event.html
+pub(super) fn read_value(
+    buf: &mut &[u8],
+    column: &MySqlBinlogColumn,
+) -> crate::Result<MySqlBinlogValue> {
+    let meta = column.metadata;
+    let unsigned = column.is_unsigned;
+
+    let value = match column.type_id {
+        TINY if unsigned => MySqlBinlogValue::UInt(u64::from(buf.get_u8()?)),
+        TINY => MySqlBinlogValue::Int(i64::from(buf.get_i8()?)),
+
+        SHORT if unsigned => MySqlBinlogValue::UInt(u64::from(buf.get_u16::<LittleEndian>()?)),
+        SHORT => MySqlBinlogValue::Int(i64::from(buf.get_i16::<LittleEndian>()?)),
+
+        INT24 => {
+            let value = buf.get_u24::<LittleEndian>()?;
+
+            if unsigned {
+                MySqlBinlogValue::UInt(u64::from(value))
+            } else {
+                // sign-extend from 24 bits
+                MySqlBinlogValue::Int(i64::from(((value << 8) as i32) >> 8))
+            }
+        }
+
+        LONG if unsigned => MySqlBinlogValue::UInt(u64::from(buf.get_u32::<LittleEndian>()?)),
+        LONG => MySqlBinlogValue::Int(i64::from(buf.get_i32::<LittleEndian>()?)),
+
+        LONGLONG if unsigned => MySqlBinlogValue::UInt(buf.get_u64::<LittleEndian>()?),
+        LONGLONG => MySqlBinlogValue::Int(buf.get_i64::<LittleEndian>()?),
+
+        YEAR => match buf.get_u8()? {
+            0 => MySqlBinlogValue::UInt(0),
+            year => MySqlBinlogValue::UInt(1900 + u64::from(year)),
+        },
+
+        FLOAT => MySqlBinlogValue::Float(f32::from_bits(buf.get_u32::<LittleEndian>()?)),
+        DOUBLE => MySqlBinlogValue::Double(f64::from_bits(buf.get_u64::<LittleEndian>()?)),
+
+        NEWDECIMAL => MySqlBinlogValue::Text(read_decimal(buf, meta[0], meta[1])?),
+
+        DATE => {
+            let date = buf.get_u24::<LittleEndian>()?;
+
+            MySqlBinlogValue::Text(format!(
+                "{:04}-{:02}-{:02}",
+                date >> 9,
+                (date >> 5) & 0x0F,
+                date & 0x1F
+            ))
+        }
+
+        // decimal digits, e.g. 20200131120000
+        DATETIME => {
+            let value = buf.get_u64::<LittleEndian>()?;
+            let (date, time) = (value / 1_000_000, value % 1_000_000);
+
+            MySqlBinlogValue::Text(format!(
+                "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+                date / 10000,
+                date / 100 % 100,
+                date % 100,
+                time / 10000,
+                time / 100 % 100,
+                time % 100
+            ))
+        }
+
+        // decimal digits, e.g. 120000
+        TIME => {
+            let time = buf.get_u24::<LittleEndian>()?;
+
+            MySqlBinlogValue::Text(format!(
+                "{:02}:{:02}:{:02}",
+                time / 10000,
+                time / 100 % 100,
+                time % 100
+            ))
+        }
+
+        TIMESTAMP => {
+            MySqlBinlogValue::Timestamp(i64::from(buf.get_u32::<LittleEndian>()?) * 1_000_000)
+        }
+
+        TIMESTAMP2 => {
+            let seconds = i64::from(buf.get_u32::<BigEndian>()?);
+            let micros = read_fraction(buf, meta[0])?;
+
+            MySqlBinlogValue::Timestamp(seconds * 1_000_000 + micros)
+        }
+
+        DATETIME2 => {
+            // 1 bit sign (always set), 17 bits year * 13 + month, 5 bits day, 5 bits hour,
+            // 6 bits minute, 6 bits second
+            let value = buf.get_uint::<BigEndian>(5)? - 0x80_0000_0000;
+            let micros = read_fraction(buf, meta[0])?;
+
+            let (date, time) = (value >> 17, value & 0x1_FFFF);
+            let year_month = date >> 5;
+
+            MySqlBinlogValue::Text(format!(
+                "{:04}-{:02}-{:02} {:02}:{:02}:{:02}{}",
+                year_month / 13,
+                year_month % 13,
+                date & 0x1F,
+                time >> 12,
+                (time >> 6) & 0x3F,
+                time & 0x3F,
+                format_fraction(micros, meta[0])
+            ))
+        }
+
+        TIME2 => MySqlBinlogValue::Text(read_time2(buf, meta[0])?),
+
+        VARCHAR | VAR_STRING => {
+            let max_len = u16::from_le_bytes(meta);
+
+            MySqlBinlogValue::Bytes(read_string(buf, max_len)?.to_vec())
+        }
+
+        STRING | ENUM | SET => {
+            // the real type is in the first byte, along with the high bits of the
+            // maximum length (in bytes) of a CHAR column
+            let (real_type, len) = if meta[0] & 0x30 != 0x30 {
+                (
+                    meta[0] | 0x30,
+                    u16::from(meta[1]) | (u16::from((meta[0] & 0x30) ^ 0x30) << 4),
+                )
+            } else {
+                (meta[0], u16::from(meta[1]))
+            };
+
+            match real_type {
+                ENUM | SET => MySqlBinlogValue::UInt(buf.get_uint::<LittleEndian>(len as usize)?),
+                _ => MySqlBinlogValue::Bytes(read_string(buf, len)?.to_vec()),
+            }
+        }
+
+        BLOB | GEOMETRY | JSON => {
+            let len = buf.get_uint::<LittleEndian>(meta[0] as usize)?;
+
+            MySqlBinlogValue::Bytes(buf.get_bytes(len as usize)?.to_vec())
+        }
+
+        BIT => {
+            // the number of bits modulo 8, then the number of whole bytes
+            let len = usize::from(meta[1]) + if meta[0] > 0 { 1 } else { 0 };
+
+            MySqlBinlogValue::Bytes(buf.get_bytes(len)?.to_vec())
+        }
+
+        NULL => MySqlBinlogValue::Null,
+
+        type_id => {
+            return Err(protocol_err!("binlog: unsupported column type {}", type_id).into());
+        }
+    };
+
+    Ok(value)
+}
+
+// A string with a length prefix of 1 byte, or 2 bytes if it can be longer than 255 bytes
+fn read_string<'a>(buf: &mut &'a [u8], max_len: u16) -> crate::Result<&'a [u8]> {
+    let len = if max_len > 255 {
+        buf.get_u16::<LittleEndian>()? as usize
+    } else {
+        buf.get_u8()? as usize
+    };
+
+    Ok(buf.get_bytes(len)?)
+}
+
+// The fractional seconds of a temporal value, in microseconds; `fsp` is the number of
+// decimal digits, stored in 1 byte per 2 digits
+fn read_fraction(buf: &mut &[u8], fsp: u8) -> crate::Result<i64> {
+    let micros = match fsp {
+        1 | 2 => buf.get_u8()? as i64 * 10_000,
+        3 | 4 => buf.get_u16::<BigEndian>()? as i64 * 100,
+        5 | 6 => buf.get_u24::<BigEndian>()? as i64,
+        _ => 0,
+    };
+
+    Ok(micros)
+}
+
+fn format_fraction(micros: i64, fsp: u8) -> String {
+    if fsp == 0 {
+        return String::new();
+    }
+
+    let digits = format!("{:06}", micros);
+
+    format!(".{}", &digits[..(fsp as usize).min(6)])
+}
+
+// A `TIME` value: 1 bit sign, 1 bit unused, 10 bits hour, 6 bits minute, 6 bits second,
+// then the fraction; negative values are stored as the two's complement
+fn read_time2(buf: &mut &[u8], fsp: u8) -> crate::Result<String> {
+    const INT_OFFSET: i64 = 0x80_0000;
+
+    // the value as a whole, in units of 1/2^24 seconds of the packed time
+    let packed = match fsp {
+        1 | 2 => {
+            let mut int = buf.get_u24::<BigEndian>()? as i64 - INT_OFFSET;
+            let mut frac = buf.get_u8()? as i64;
+
+            if int < 0 && frac != 0 {
+                int += 1;
+                frac -= 0x100;
+            }
+
+            (int << 24) + frac * 10_000
+        }
+
+        3 | 4 => {
+            let mut int = buf.get_u24::<BigEndian>()? as i64 - INT_OFFSET;
+            let mut frac = buf.get_u16::<BigEndian>()? as i64;
+
+            if int < 0 && frac != 0 {
+                int += 1;
+                frac -= 0x1_0000;
+            }
+
+            (int << 24) + frac * 100
+        }
+
+        5 | 6 => buf.get_uint::<BigEndian>(6)? as i64 - (INT_OFFSET << 24),
+
+        _ => (buf.get_u24::<BigEndian>()? as i64 - INT_OFFSET) << 24,
+    };
+
+    let sign = if packed < 0 { "-" } else { "" };
+    let packed = packed.abs();
+    let time = packed >> 24;
+
+    Ok(format!(
+        "{}{:02}:{:02}:{:02}{}",
+        sign,
+        (time >> 12) & 0x3FF,
+        (time >> 6) & 0x3F,
+        time & 0x3F,
+        format_fraction(packed & 0xFF_FFFF, fsp)
+    ))
+}
+
+// A `DECIMAL`, stored as groups of 9 decimal digits in 4 bytes (and fewer bytes for
+// the leftover digits at either end), with the sign in the first bit
+// https://dev.mysql.com/doc/refman/8.0/en/precision-math-decimal-characteristics.html
+fn read_decimal(buf: &mut &[u8], precision: u8, scale: u8) -> crate::Result<String> {
+    // the number of bytes that stores a group of 0 to 8 digits
+    const GROUP_LEN: [usize; 9] = [0, 1, 1, 2, 2, 3, 3, 4, 4];
+
+    let integral = usize::from(precision.saturating_sub(scale));
+    let scale = usize::from(scale);
+
+    let len = integral / 9 * 4 + GROUP_LEN[integral % 9] + scale / 9 * 4 + GROUP_LEN[scale % 9];
+
+    let mut bytes = buf.get_bytes(len)?.to_vec();
+
+    if bytes.is_empty() {
+        return Ok("0".to_owned());
+    }
+
+    // a negative number is stored as the complement of its absolute value
+    let negative = bytes[0] & 0x80 == 0;
+    bytes[0] ^= 0x80;
+
+    if negative {
+        for byte in &mut bytes {
+            *byte = !*byte;
+        }
+    }
+
+    let mut bytes = &bytes[..];
+    let mut int = String::new();
+    let mut frac = String::new();
+
+    let read_group = |buf: &mut &[u8], digits: usize, out: &mut String| -> crate::Result<()> {
+        let len = if digits == 9 { 4 } else { GROUP_LEN[digits] };
+
+        if len > 0 {
+            let group = buf.get_uint::<BigEndian>(len)?;
+
+            out.push_str(&format!("{:01$}", group, digits));
+        }
+
+        Ok(())
+    };
+
+    read_group(&mut bytes, integral % 9, &mut int)?;
+
+    for _ in 0..integral / 9 {
+        read_group(&mut bytes, 9, &mut int)?;
+    }
+
+    for _ in 0..scale / 9 {
+        read_group(&mut bytes, 9, &mut frac)?;
+    }
+
+    read_group(&mut bytes, scale % 9, &mut frac)?;
+
+    let int = int.trim_start_matches('0');
+    let mut decimal = String::with_capacity(int.len() + frac.len() + 3);
+
+    if negative {
+        decimal.push('-');
+    }
+
+    decimal.push_str(if int.is_empty() { "0" } else { int });
+
+    if !frac.is_empty() {
+        decimal.push('.');
+        decimal.push_str(&frac);
+    }
+
+    Ok(decimal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_decimal, read_time2, read_value, MySqlBinlogValue, DATETIME2, INT24};
+    use crate::mysql::binlog::MySqlBinlogColumn;
+
+    fn column(type_id: u8, metadata: [u8; 2], is_unsigned: bool) -> MySqlBinlogColumn {
+        MySqlBinlogColumn {
+            name: None,
+            type_id,
+            metadata,
+            is_nullable: true,
+            is_unsigned,
+            is_key: false,
+        }
+    }
+
+    #[test]
+    fn it_reads_decimals() {
+        let mut buf = &b"\x81\x0D\xFB\x38\xD2\x04\xD2"[..];
+        assert_eq!(read_decimal(&mut buf, 14, 4).unwrap(), "1234567890.1234");
+        assert!(buf.is_empty());
+
+        let mut buf = &b"\x7E\xF2\x04\xC7\x2D\xFB\x2D"[..];
+        assert_eq!(read_decimal(&mut buf, 14, 4).unwrap(), "-1234567890.1234");
+
+        // DECIMAL(5, 2) 0.05
+        let mut buf = &b"\x80\x00\x05"[..];
+        assert_eq!(read_decimal(&mut buf, 5, 2).unwrap(), "0.05");
+    }
+
+    #[test]
+    fn it_reads_temporal_values() {
+        // DATETIME(3) '2020-01-31 12:34:56.789'
+        let mut buf = &b"\x99\xA5\x7E\xC8\xB8\x1E\xD2"[..];
+        let value = read_value(&mut buf, &column(DATETIME2, [3, 0], false)).unwrap();
+
+        assert_eq!(
+            value,
+            MySqlBinlogValue::Text("2020-01-31 12:34:56.789".to_owned())
+        );
+
+        // TIME '-01:02:03' and TIME(6) '838:59:59.000001'
+        let mut buf = &b"\x7F\xEF\x7D"[..];
+        assert_eq!(read_time2(&mut buf, 0).unwrap(), "-01:02:03");
+
+        let mut buf = &b"\xB4\x6E\xFB\x00\x00\x01"[..];
+        assert_eq!(read_time2(&mut buf, 6).unwrap(), "838:59:59.000001");
+    }
+
+    #[test]
+    fn it_reads_integers() {
+        let mut buf = &b"\xFF\xFF\xFF"[..];
+        let value = read_value(&mut buf, &column(INT24, [0, 0], false)).unwrap();
+
+        assert_eq!(value, MySqlBinlogValue::Int(-1));
+
+        let mut buf = &b"\xFF\xFF\xFF"[..];
+        let value = read_value(&mut buf, &column(INT24, [0, 0], true)).unwrap();
+
+        assert_eq!(value, MySqlBinlogValue::UInt(0xFF_FFFF));
+    }
+}
@@ -0,0 +1,153 @@
+use crate::url::Url;
+
+// The character sets that strings can be exchanged in, as they are decoded as UTF-8, and the
+// IDs of their default collations
+const CHARSETS: &[(&str, u8)] = &[("utf8mb4", 45), ("utf8mb3", 33), ("utf8", 33)];
+
+// The IDs of the collations of those character sets that are sent in the handshake
+// SELECT ID, COLLATION_NAME FROM INFORMATION_SCHEMA.COLLATIONS
+const COLLATIONS: &[(&str, u8)] = &[
+    ("utf8mb4_general_ci", 45),
+    ("utf8mb4_bin", 46),
+    ("utf8mb4_unicode_ci", 224),
+    ("utf8mb4_unicode_520_ci", 246),
+    ("utf8mb4_0900_ai_ci", 255),
+    ("utf8_general_ci", 33),
+    ("utf8mb3_general_ci", 33),
+    ("utf8_bin", 83),
+    ("utf8mb3_bin", 83),
+    ("utf8_unicode_ci", 192),
+    ("utf8mb3_unicode_ci", 192),
+];
+
+// The character set and collation of the connection, from the `charset` and `collation`
+// parameters
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Charset {
+    pub(crate) charset: String,
+    pub(crate) collation: Option<String>,
+}
+
+impl Charset {
+    pub(crate) fn from_url(url: &Url) -> crate::Result<Self> {
+        let charset = url.param("charset");
+        let collation = url.param("collation");
+
+        let charset = match (charset.as_deref(), collation.as_deref()) {
+            (Some(charset), _) => charset.to_owned(),
+
+            // the character set is the prefix of the name of a collation
+            (None, Some(collation)) => collation.split('_').next().unwrap_or("").to_owned(),
+
+            (None, None) => {
+                return Ok(Charset {
+                    charset: "utf8mb4".to_owned(),
+                    collation: Some("utf8mb4_unicode_ci".to_owned()),
+                });
+            }
+        };
+
+        if !CHARSETS.iter().any(|(name, _)| *name == charset) {
+            return Err(protocol_err!(
+                "unsupported `charset` value: {:?}; strings are decoded as UTF-8, so the \
+                 character set must be utf8mb4 or utf8",
+                charset
+            )
+            .into());
+        }
+
+        if let Some(collation) = &collation {
+            // the collation is interpolated into `SET NAMES`
+            if !collation.starts_with(&*charset)
+                || !collation
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+            {
+                return Err(protocol_err!(
+                    "invalid `collation` value: {:?}; expected a collation of {}",
+                    collation,
+                    charset
+                )
+                .into());
+            }
+        }
+
+        Ok(Charset {
+            charset,
+            collation: collation.map(|collation| collation.into_owned()),
+        })
+    }
+
+    // The ID of the collation sent in the handshake; a collation that is not known here is
+    // set afterwards by `SET NAMES`
+    pub(crate) fn collation_id(&self) -> u8 {
+        let collation = self.collation.as_deref().and_then(|collation| {
+            COLLATIONS
+                .iter()
+                .find(|(name, _)| *name == collation)
+                .map(|(_, id)| *id)
+        });
+
+        collation.unwrap_or_else(|| {
+            CHARSETS
+                .iter()
+                .find(|(name, _)| *name == self.charset)
+                .map_or(45, |(_, id)| *id)
+        })
+    }
+
+    pub(crate) fn set_names(&self) -> String {
+        match &self.collation {
+            Some(collation) => format!("NAMES {} COLLATE {}", self.charset, collation),
+            None => format!("NAMES {}", self.charset),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::Charset;
+    use crate::url::Url;
+
+    fn charset(url: &str) -> crate::Result<Charset> {
+        Charset::from_url(&Url::try_from(url).unwrap())
+    }
+
+    #[test]
+    fn it_reads_charset_and_collation() {
+        let default = charset("mysql://localhost/db").unwrap();
+
+        assert_eq!(default.collation_id(), 224);
+        assert_eq!(
+            default.set_names(),
+            "NAMES utf8mb4 COLLATE utf8mb4_unicode_ci"
+        );
+
+        let collation = charset("mysql://localhost/db?collation=utf8mb4_0900_ai_ci").unwrap();
+
+        assert_eq!(collation.collation_id(), 255);
+        assert_eq!(
+            collation.set_names(),
+            "NAMES utf8mb4 COLLATE utf8mb4_0900_ai_ci"
+        );
+
+        let charset_only = charset("mysql://localhost/db?charset=utf8").unwrap();
+
+        assert_eq!(charset_only.collation_id(), 33);
+        assert_eq!(charset_only.set_names(), "NAMES utf8");
+
+        // a collation that is not known is only set by `SET NAMES`
+        let unknown =
+            charset("mysql://localhost/db?charset=utf8mb4&collation=utf8mb4_de_pb_0900_ai_ci")
+                .unwrap();
+
+        assert_eq!(unknown.collation_id(), 45);
+
+        assert!(charset("mysql://localhost/db?charset=latin1").is_err());
+        assert!(charset("mysql://localhost/db?collation=latin1_swedish_ci").is_err());
+        assert!(charset("mysql://localhost/db?charset=utf8mb4&collation=utf8_bin").is_err());
+        assert!(charset("mysql://localhost/db?collation=utf8mb4_bin;DROP").is_err());
+    }
+}
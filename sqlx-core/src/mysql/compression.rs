@@ -0,0 +1,249 @@
+use byteorder::{ByteOrder, LittleEndian};
+
+use crate::mysql::protocol::Capabilities;
+use crate::url::Url;
+
+// Packets shorter than this are sent uncompressed, as by `libmysqlclient`
+const MIN_COMPRESS_LENGTH: usize = 50;
+
+// The largest payload of a compressed packet
+const MAX_PAYLOAD_LENGTH: usize = 0xFF_FF_FF;
+
+// The compression level of zlib, from 0 (none) to 10; 6 is the default of zlib
+const ZLIB_LEVEL: u8 = 6;
+
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_basic_compression.html
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum Compression {
+    Zlib,
+}
+
+impl Compression {
+    // The compression to use, from the `compression-algorithms` parameter: the first of the
+    // algorithms that the server supports, or none if `uncompressed` comes first
+    pub(crate) fn negotiate(
+        url: &Url,
+        server_capabilities: Capabilities,
+    ) -> crate::Result<Option<Self>> {
+        let algorithms = match url.param("compression-algorithms") {
+            Some(algorithms) => algorithms,
+            None => return Ok(None),
+        };
+
+        for algorithm in algorithms.split(',') {
+            match algorithm.trim() {
+                "zlib" | "uncompressed" => {}
+
+                // rejected rather than skipped, so that it is not mistaken for a server
+                // that does not support it
+                "zstd" => {
+                    return Err(protocol_err!(
+                        "`compression-algorithms` value \"zstd\" is not supported"
+                    )
+                    .into());
+                }
+
+                algorithm => {
+                    return Err(protocol_err!(
+                        "unknown `compression-algorithms` value: {:?}",
+                        algorithm
+                    )
+                    .into());
+                }
+            }
+        }
+
+        for algorithm in algorithms.split(',') {
+            match algorithm.trim() {
+                "zlib" if server_capabilities.contains(Capabilities::COMPRESS) => {
+                    return Ok(Some(Compression::Zlib));
+                }
+
+                "uncompressed" => {
+                    return Ok(None);
+                }
+
+                _ => {}
+            }
+        }
+
+        Err(protocol_err!(
+            "server supports none of the compression algorithms {:?}",
+            algorithms
+        )
+        .into())
+    }
+
+    // Wrap packets in compressed packets, which are numbered from `seq_no`; returns the
+    // sequence number of the next compressed packet
+    pub(crate) fn compress(self, packets: &[u8], mut seq_no: u8, buf: &mut Vec<u8>) -> u8 {
+        for chunk in packets.chunks(MAX_PAYLOAD_LENGTH) {
+            let header_offset = buf.len();
+            buf.extend_from_slice(&[0; 7]);
+
+            let compressed = if chunk.len() < MIN_COMPRESS_LENGTH {
+                None
+            } else {
+                Some(miniz_oxide::deflate::compress_to_vec_zlib(
+                    chunk, ZLIB_LEVEL,
+                ))
+                .filter(|compressed| compressed.len() < chunk.len())
+            };
+
+            // a length before compression of 0 means that the payload is not compressed
+            let uncompressed_len = match compressed {
+                Some(compressed) => {
+                    buf.extend_from_slice(&compressed);
+                    chunk.len()
+                }
+
+                None => {
+                    buf.extend_from_slice(chunk);
+                    0
+                }
+            };
+
+            let len = buf.len() - header_offset - 7;
+            let header = &mut buf[header_offset..];
+
+            LittleEndian::write_u24(header, len as u32);
+            header[3] = seq_no;
+            LittleEndian::write_u24(&mut header[4..], uncompressed_len as u32);
+
+            seq_no = seq_no.wrapping_add(1);
+        }
+
+        seq_no
+    }
+
+    // Append the packets in the payload of a compressed packet to `buf`
+    pub(crate) fn decompress(
+        self,
+        payload: &[u8],
+        uncompressed_len: usize,
+        buf: &mut Vec<u8>,
+    ) -> crate::Result<()> {
+        if uncompressed_len == 0 {
+            buf.extend_from_slice(payload);
+
+            return Ok(());
+        }
+
+        let packets = match miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(
+            payload,
+            uncompressed_len,
+        ) {
+            Ok(packets) => packets,
+
+            Err(error) => {
+                return Err(protocol_err!("failed to decompress packet: {}", error).into());
+            }
+        };
+
+        if packets.len() != uncompressed_len {
+            return Err(protocol_err!(
+                "expected a decompressed packet of {} bytes but got {} bytes",
+                uncompressed_len,
+                packets.len()
+            )
+            .into());
+        }
+
+        buf.extend_from_slice(&packets);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use byteorder::{ByteOrder, LittleEndian};
+
+    use super::Compression;
+    use crate::mysql::protocol::Capabilities;
+    use crate::url::Url;
+
+    fn negotiate(
+        url: &str,
+        server_capabilities: Capabilities,
+    ) -> crate::Result<Option<Compression>> {
+        Compression::negotiate(&Url::try_from(url).unwrap(), server_capabilities)
+    }
+
+    #[test]
+    fn it_negotiates_compression() {
+        let compress = Capabilities::COMPRESS;
+        let none = Capabilities::empty();
+
+        assert_eq!(negotiate("mysql://localhost/db", compress).unwrap(), None);
+
+        assert_eq!(
+            negotiate("mysql://localhost/db?compression-algorithms=zlib", compress).unwrap(),
+            Some(Compression::Zlib)
+        );
+
+        assert_eq!(
+            negotiate(
+                "mysql://localhost/db?compression-algorithms=zlib,uncompressed",
+                none
+            )
+            .unwrap(),
+            None
+        );
+
+        assert!(negotiate("mysql://localhost/db?compression-algorithms=zlib", none).is_err());
+        assert!(negotiate("mysql://localhost/db?compression-algorithms=lz4", compress).is_err());
+
+        let error = negotiate(
+            "mysql://localhost/db?compression-algorithms=zlib,zstd",
+            compress,
+        )
+        .unwrap_err();
+
+        assert!(error.to_string().contains("\"zstd\" is not supported"));
+    }
+
+    #[test]
+    fn it_compresses_and_decompresses_packets() {
+        let packets: Vec<u8> =
+            b"\x3d\x00\x00\x00\x03SELECT 'aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa'"
+                .to_vec();
+
+        let mut buf = Vec::new();
+        let seq_no = Compression::Zlib.compress(&packets, 3, &mut buf);
+
+        assert_eq!(seq_no, 4);
+        assert_eq!(buf[3], 3);
+        assert_eq!(LittleEndian::read_u24(&buf) as usize, buf.len() - 7);
+        assert_eq!(LittleEndian::read_u24(&buf[4..]) as usize, packets.len());
+        assert!(buf.len() < packets.len());
+
+        let mut decompressed = Vec::new();
+
+        Compression::Zlib
+            .decompress(&buf[7..], packets.len(), &mut decompressed)
+            .unwrap();
+
+        assert_eq!(decompressed, packets);
+
+        // short packets are not compressed
+        let mut buf = Vec::new();
+        Compression::Zlib.compress(b"\x01\x00\x00\x00\x0e", 0, &mut buf);
+
+        assert_eq!(buf, b"\x05\x00\x00\x00\x00\x00\x00\x01\x00\x00\x00\x0e");
+
+        let mut decompressed = Vec::new();
+
+        Compression::Zlib
+            .decompress(&buf[7..], 0, &mut decompressed)
+            .unwrap();
+
+        assert_eq!(decompressed, b"\x01\x00\x00\x00\x0e");
+
+        assert!(Compression::Zlib
+            .decompress(b"not zlib", 10, &mut decompressed)
+            .is_err());
+    }
+}
@@ -0,0 +1,73 @@
+use crate::url::Url;
+
+// The connection attributes sent in the handshake, which the server shows in
+// `performance_schema.session_connect_attrs`; the attributes of the `connection-attributes`
+// parameter (`key:value,key:value`) follow those that identify the client
+pub(crate) fn from_url(url: &Url) -> crate::Result<Vec<(String, String)>> {
+    let mut attrs = vec![
+        ("_client_name".to_owned(), "sqlx".to_owned()),
+        (
+            "_client_version".to_owned(),
+            env!("CARGO_PKG_VERSION").to_owned(),
+        ),
+        ("_os".to_owned(), std::env::consts::OS.to_owned()),
+        ("_platform".to_owned(), std::env::consts::ARCH.to_owned()),
+    ];
+
+    if let Some(value) = url.param("connection-attributes") {
+        for attr in value.split(',').filter(|attr| !attr.is_empty()) {
+            let mut parts = attr.splitn(2, ':');
+
+            match (parts.next(), parts.next()) {
+                (Some(key), Some(value)) if !key.is_empty() => {
+                    attrs.push((key.to_owned(), value.to_owned()));
+                }
+
+                _ => {
+                    return Err(protocol_err!(
+                        "invalid `connection-attributes` value: {:?}; expected `key:value`",
+                        attr
+                    )
+                    .into());
+                }
+            }
+        }
+    }
+
+    Ok(attrs)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::from_url;
+    use crate::url::Url;
+
+    fn attrs(url: &str) -> crate::Result<Vec<(String, String)>> {
+        from_url(&Url::try_from(url).unwrap())
+    }
+
+    #[test]
+    fn it_reads_connection_attributes() {
+        let default = attrs("mysql://localhost/db").unwrap();
+
+        assert_eq!(default[0], ("_client_name".to_owned(), "sqlx".to_owned()));
+        assert_eq!(default[1].0, "_client_version");
+
+        let custom =
+            attrs("mysql://localhost/db?connection-attributes=app:billing,url:http://x").unwrap();
+
+        assert_eq!(custom.len(), default.len() + 2);
+        assert_eq!(
+            &custom[default.len()..],
+            &[
+                ("app".to_owned(), "billing".to_owned()),
+                ("url".to_owned(), "http://x".to_owned()),
+            ]
+        );
+
+        assert!(attrs("mysql://localhost/db?connection-attributes=app").is_err());
+        assert!(attrs("mysql://localhost/db?connection-attributes=:billing").is_err());
+    }
+}
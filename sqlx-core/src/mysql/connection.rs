@@ -8,20 +8,23 @@ use sha1::Sha1;
 
 use crate::connection::{Connect, Connection};
 use crate::executor::Executor;
+use crate::mysql::charset::Charset;
+use crate::mysql::compression::Compression;
 use crate::mysql::protocol::{
     AuthPlugin, AuthSwitch, Capabilities, ComPing, Handshake, HandshakeResponse,
 };
+use crate::mysql::server_version::ServerVersion;
 use crate::mysql::stream::MySqlStream;
+use crate::mysql::time_zone::TimeZone;
 use crate::mysql::util::xor_eq;
+use crate::mysql::zero_dates::ZeroDates;
 
-use crate::mysql::{rsa, tls};
+use crate::mysql::{connect_attrs, rsa, tls};
 use crate::url::Url;
 
 // Size before a packet is split
 pub(super) const MAX_PACKET_SIZE: u32 = 1024;
 
-pub(super) const COLLATE_UTF8MB4_UNICODE_CI: u8 = 224;
-
 /// An asynchronous connection to a [`MySql`] database.
 ///
 /// The connection string expected by `MySqlConnection` should be a MySQL connection
@@ -37,7 +40,7 @@ pub(super) const COLLATE_UTF8MB4_UNICODE_CI: u8 = 224;
 /// <https://dev.mysql.com/doc/refman/8.0/en/connection-options.html#option_general_ssl-mode>
 ///
 /// ```text
-/// mysql://<user>[:<password>]@<host>[:<port>]/<database>[?ssl-mode=<ssl-mode>[&ssl-ca=<path>]]
+/// mysql://<user>[:<password>]@<host>[:<port>]/<database>[?ssl-mode=<ssl-mode>[&ssl-ca=<path>][&ssl-cert=<path>&ssl-key=<path>]]
 /// ```
 /// where
 /// ```text
@@ -57,12 +60,14 @@ pub(super) const COLLATE_UTF8MB4_UNICODE_CI: u8 = 224;
 ///
 /// Add `ssl-mode=REQUIRED` to your connection string to emit an error if the TLS upgrade fails.
 ///
-/// However, like with `mysql` the server certificate is **not** checked for validity by default.
+/// However, like with `mysql` the server certificate is **not** checked for validity by default,
+/// and `ssl-mode=REQUIRED` only guarantees that the connection is encrypted.
 ///
 /// Specifying `ssl-mode=VERIFY_CA` will cause the TLS upgrade to verify the server's SSL
 /// certificate against a local CA root certificate; this is not the system root certificate
 /// but is instead expected to be specified as a local path with the `ssl-ca` query parameter
-/// (percent-encoded so the URL remains valid).
+/// (percent-encoded so the URL remains valid). The file may be a bundle of several PEM
+/// certificates, such as a chain of intermediate and root certificates.
 ///
 /// If you're running MySQL locally it might look something like this (for `VERIFY_CA`):
 /// ```text
@@ -76,21 +81,165 @@ pub(super) const COLLATE_UTF8MB4_UNICODE_CI: u8 = 224;
 /// generate certificates automatically and they must always be passed in to enable TLS.
 ///
 /// If `ssl-ca` is not specified or the file cannot be read, then an error is returned.
-/// `ssl-ca` implies `ssl-mode=VERIFY_CA` so you only actually need to specify the former
-/// but you may prefer having both to be more explicit.
+/// As with `mysql`, `ssl-ca` without `ssl-mode` implies `ssl-mode=VERIFY_CA` (an upgrade that
+/// fails is an error rather than falling back to an unsecured connection), and with
+/// `ssl-mode=REQUIRED` the certificate is verified as well, so you only actually need to
+/// specify the former but you may prefer having both to be more explicit.
 ///
 /// If `ssl-mode=VERIFY_IDENTITY` is specified, in addition to checking the certificate as with
 /// `ssl-mode=VERIFY_CA`, the hostname in the connection string will be verified
 /// against the hostname in the server certificate, so they must be the same for the TLS
 /// upgrade to succeed. `ssl-ca` must still be specified.
+///
+/// A client certificate, for a user created with `REQUIRE X509`, is sent with the `ssl-cert`
+/// and `ssl-key` query parameters: the paths of the PEM certificate and of its PKCS #8 PEM
+/// private key, which must be set together.
+///
+/// ### Compression
+/// Like with the `--compression-algorithms` option of `mysql`, the `compression-algorithms`
+/// query parameter compresses the packets exchanged with the server, which saves bandwidth
+/// on slow links at the cost of CPU time. It is a comma-separated list of `zlib` and
+/// `uncompressed`; the first that the server supports is used, and connecting fails if the
+/// server supports none of them:
+///
+/// ```text
+/// mysql://<user>@<host>/<database>?compression-algorithms=zlib,uncompressed
+/// ```
+///
+/// By default, packets are not compressed. `zstd` is not supported yet, and connecting fails
+/// if it is in the list.
+///
+/// ### Zero Dates
+/// A zero date (`0000-00-00`, or `0000-00-00 00:00:00`), as found in legacy schemas, is not
+/// a valid date in Rust. Like the `zeroDateTimeBehavior` option of JDBC, the `zero-dates`
+/// query parameter sets how one is decoded:
+///
+/// ```text
+/// mysql://<user>@<host>/<database>?zero-dates=null
+/// ```
+///
+/// * `error` (the default): decoding a zero date into a date type is an error.
+/// * `null`: a zero date is `NULL`, so it decodes as `None` into an `Option`.
+/// * `sentinel`: a zero date is `0001-01-01` (at midnight).
+///
+/// ### Authentication
+/// The `mysql_native_password`, `caching_sha2_password` and `sha256_password` authentication
+/// plugins of MySQL and the `client_ed25519` plugin of MariaDB are supported.
+///
+/// The `mysql_clear_password` plugin, used by servers that check passwords against LDAP or
+/// PAM, sends the password unencrypted, so it is only used over TLS. Like with the
+/// `--enable-cleartext-plugin` option of `mysql`, the `enable-cleartext-plugin` query
+/// parameter allows it without TLS:
+///
+/// ```text
+/// mysql://<user>:<password>@<host>/<database>?enable-cleartext-plugin=true
+/// ```
+///
+/// ### Time Zone
+/// The session time zone is UTC by default, so that a `TIMESTAMP` is decoded as a
+/// `DateTime<Utc>` (or `OffsetDateTime`) and a bound `DateTime<Utc>` is stored as is.
+///
+/// The `timezone` query parameter sets another session time zone: a named time zone (which
+/// requires the time zone tables of the server), an offset (with `+` percent-encoded as
+/// `%2B`) or `SYSTEM`. `NOW()` and the conversion of `TIMESTAMP` values to and from strings
+/// then use that time zone:
+///
+/// ```text
+/// mysql://<user>@<host>/<database>?timezone=Europe%2FBerlin
+/// ```
+///
+/// A `TIMESTAMP` is then a local time of the session time zone, which is decoded and bound as
+/// a `NaiveDateTime` (or `PrimitiveDateTime`); decoding a `TIMESTAMP` into, or binding, a
+/// `DateTime<Utc>` or `OffsetDateTime` is an error, rather than silently shifting the value
+/// by the offset of the time zone.
+///
+/// ### Character Set
+/// Strings are exchanged in `utf8mb4` with the `utf8mb4_unicode_ci` collation by default.
+/// The `charset` and `collation` query parameters select another UTF-8 character set
+/// (`utf8mb4` or `utf8`) or collation, such as the `utf8mb4_0900_ai_ci` default of MySQL 8.0,
+/// to avoid "illegal mix of collations" errors when comparing with columns of the schema:
+///
+/// ```text
+/// mysql://<user>@<host>/<database>?collation=utf8mb4_0900_ai_ci
+/// ```
+///
+/// ### Connection Attributes
+/// The connection identifies itself to the server with the `_client_name` (`sqlx`),
+/// `_client_version`, `_os` and `_platform` connection attributes, which can be seen in
+/// `performance_schema.session_connect_attrs`. Like the `connectionAttributes` option of JDBC,
+/// the `connection-attributes` query parameter adds attributes of your own, as a
+/// comma-separated list of `key:value` pairs:
+///
+/// ```text
+/// mysql://<user>@<host>/<database>?connection-attributes=program_name:billing,env:prod
+/// ```
+///
+/// ### Multiple Statements
+/// A query (without arguments) may be a script of statements separated by semicolons, such as
+/// a schema dump or the definition of a stored routine.
+///
+/// The statements are run in order until one fails. The rows of the result sets of all of the
+/// statements are returned one after another; use [`MySqlCursor::next_with_index`] to tell
+/// which result set a row belongs to, or [`MySqlConnection::fetch_results`] to read the
+/// results one result set at a time, each with its own columns.
+///
+/// As this makes SQL injection much easier to exploit, it can be turned off with the
+/// `multi-statements` query parameter, in which case a query may only contain a single
+/// statement:
+///
+/// ```text
+/// mysql://<user>@<host>/<database>?multi-statements=false
+/// ```
+///
+/// ### RETURNING
+/// MariaDB 10.5+ supports a `RETURNING` clause on `INSERT`, `REPLACE` and `DELETE`, which
+/// returns rows like a `SELECT` (and so works with `query!` and `query_as!`); see
+/// [`MySqlConnection::supports_returning`]. MySQL does not.
+///
+/// ### Stored Procedures
+/// A `CALL` returns a result set for each statement of the procedure that returns rows.
+/// If the `CALL` is prepared (i.e. it has arguments), the values of the OUT and INOUT
+/// parameters that are passed as `?` follow in one more result set of a single row,
+/// whose columns are named after the parameters:
+///
+/// ```rust,ignore
+/// let mut cursor = sqlx::query("CALL add_one(?, ?)").bind(41).bind(0).fetch(&mut conn);
+///
+/// while let Some((index, row)) = cursor.next_with_index().await? {
+///     // ..
+/// }
+/// ```
+///
+/// A value must still be bound for such a parameter, but it is ignored for an OUT parameter.
+/// A parameter passed as a user variable (e.g. `CALL add_one(?, @sum)`) can be read
+/// afterwards with `SELECT @sum`.
+///
+/// [`MySqlCursor::next_with_index`]: struct.MySqlCursor.html#method.next_with_index
+/// [`MySqlConnection::fetch_results`]: struct.MySqlConnection.html#method.fetch_results
 pub struct MySqlConnection {
     pub(super) stream: MySqlStream,
     pub(super) is_ready: bool,
     pub(super) cache_statement: HashMap<Box<str>, u32>,
 
+    // A statement prepared for a non-persistent query, to be closed before the next command
+    pub(super) close_statement: Option<u32>,
+
+    // A statement with an open server-side cursor, to be reset (closing the cursor) before
+    // the next command
+    pub(super) reset_statement: Option<u32>,
+
     // Work buffer for the value ranges of the current row
     // This is used as the backing memory for each Row's value indexes
     pub(super) current_row_values: Vec<Option<Range<usize>>>,
+
+    // How zero dates are decoded, from the `zero-dates` parameter
+    pub(super) zero_dates: ZeroDates,
+
+    // The session time zone, from the `timezone` parameter
+    pub(super) time_zone: TimeZone,
+
+    // The version of the server, from the handshake
+    pub(super) server_version: ServerVersion,
 }
 
 fn to_asciz(s: &str) -> Vec<u8> {
@@ -134,6 +283,7 @@ async fn make_auth_response(
     plugin: &AuthPlugin,
     password: &str,
     nonce: &[u8],
+    allow_cleartext: bool,
 ) -> crate::Result<Vec<u8>> {
     if password.is_empty() {
         // Empty password should not be sent
@@ -141,15 +291,28 @@ async fn make_auth_response(
     }
 
     match plugin {
-        AuthPlugin::CachingSha2Password | AuthPlugin::MySqlNativePassword => {
-            Ok(plugin.scramble(password, nonce))
-        }
+        AuthPlugin::CachingSha2Password
+        | AuthPlugin::MySqlNativePassword
+        | AuthPlugin::ClientEd25519 => Ok(plugin.scramble(password, nonce)),
 
         AuthPlugin::Sha256Password => rsa_encrypt_with_nonce(stream, 0x01, password, nonce).await,
+
+        // https://dev.mysql.com/doc/refman/8.0/en/cleartext-pluggable-authentication.html
+        AuthPlugin::MySqlClearPassword if allow_cleartext => Ok(to_asciz(password)),
+
+        AuthPlugin::MySqlClearPassword => Err(protocol_err!(
+            "server requested the mysql_clear_password authentication plugin, which sends \
+             the password unencrypted; use TLS or set `enable-cleartext-plugin=true`"
+        )
+        .into()),
     }
 }
 
-async fn establish(stream: &mut MySqlStream, url: &Url) -> crate::Result<()> {
+async fn establish(
+    stream: &mut MySqlStream,
+    url: &Url,
+    charset: &Charset,
+) -> crate::Result<ServerVersion> {
     // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_connection_phase.html
     // https://mariadb.com/kb/en/connection/
 
@@ -157,12 +320,19 @@ async fn establish(stream: &mut MySqlStream, url: &Url) -> crate::Result<()> {
     // received from the database server.
 
     let handshake = Handshake::read(stream.receive().await?)?;
+    let server_version = ServerVersion::parse(&handshake.server_version);
     let mut auth_plugin = handshake.auth_plugin;
     let mut auth_plugin_data = handshake.auth_plugin_data;
 
     stream.capabilities &= handshake.server_capabilities;
     stream.capabilities |= Capabilities::PROTOCOL_41;
 
+    let compression = Compression::negotiate(url, handshake.server_capabilities)?;
+
+    if compression.is_some() {
+        stream.capabilities |= Capabilities::COMPRESS;
+    }
+
     log::trace!("using capability flags: {:?}", stream.capabilities);
 
     // Depending on the ssl-mode and capabilities we should upgrade
@@ -174,18 +344,39 @@ async fn establish(stream: &mut MySqlStream, url: &Url) -> crate::Result<()> {
     // that is immediately received.
 
     let password = &*url.password().unwrap_or_default();
-    let auth_response =
-        make_auth_response(stream, &auth_plugin, password, &auth_plugin_data).await?;
+
+    // The password may only be sent in clear text over TLS, unless explicitly enabled
+    let allow_cleartext = stream.is_tls()
+        || match url.param("enable-cleartext-plugin").as_deref() {
+            Some("true") => true,
+            Some("false") | None => false,
+
+            Some(value) => {
+                return Err(
+                    protocol_err!("unknown `enable-cleartext-plugin` value: {:?}", value).into(),
+                );
+            }
+        };
+
+    let auth_response = make_auth_response(
+        stream,
+        &auth_plugin,
+        password,
+        &auth_plugin_data,
+        allow_cleartext,
+    )
+    .await?;
 
     stream
         .send(
             HandshakeResponse {
-                client_collation: COLLATE_UTF8MB4_UNICODE_CI,
+                client_collation: charset.collation_id(),
                 max_packet_size: MAX_PACKET_SIZE,
                 username: &url.username().unwrap_or(Cow::Borrowed("root")),
                 database: url.database(),
                 auth_plugin: &auth_plugin,
                 auth_response: &auth_response,
+                attributes: &connect_attrs::from_url(url)?,
             },
             false,
         )
@@ -199,6 +390,9 @@ async fn establish(stream: &mut MySqlStream, url: &Url) -> crate::Result<()> {
         match packet[0] {
             // OK
             0x00 => {
+                // packets are compressed from now on
+                stream.compression = compression;
+
                 break;
             }
 
@@ -213,8 +407,14 @@ async fn establish(stream: &mut MySqlStream, url: &Url) -> crate::Result<()> {
                 auth_plugin = auth.auth_plugin;
                 auth_plugin_data = auth.auth_plugin_data;
 
-                let auth_response =
-                    make_auth_response(stream, &auth_plugin, password, &auth_plugin_data).await?;
+                let auth_response = make_auth_response(
+                    stream,
+                    &auth_plugin,
+                    password,
+                    &auth_plugin_data,
+                    allow_cleartext,
+                )
+                .await?;
 
                 stream.send(&*auth_response, false).await?;
             }
@@ -246,7 +446,7 @@ async fn establish(stream: &mut MySqlStream, url: &Url) -> crate::Result<()> {
         }
     }
 
-    Ok(())
+    Ok(server_version)
 }
 
 async fn close(mut stream: MySqlStream) -> crate::Result<()> {
@@ -276,15 +476,23 @@ async fn ping(stream: &mut MySqlStream) -> crate::Result<()> {
 impl MySqlConnection {
     pub(super) async fn new(url: std::result::Result<Url, url::ParseError>) -> crate::Result<Self> {
         let url = url?;
+        let zero_dates = ZeroDates::from_url(&url)?;
+        let charset = Charset::from_url(&url)?;
+        let time_zone = TimeZone::from_url(&url)?;
         let mut stream = MySqlStream::new(&url).await?;
 
-        establish(&mut stream, &url).await?;
+        let server_version = establish(&mut stream, &url, &charset).await?;
 
         let mut self_ = Self {
             stream,
             current_row_values: Vec::with_capacity(10),
             is_ready: true,
             cache_statement: HashMap::new(),
+            close_statement: None,
+            reset_statement: None,
+            zero_dates,
+            time_zone,
+            server_version,
         };
 
         // After the connection is established, we initialize by configuring a few
@@ -306,20 +514,38 @@ impl MySqlConnection {
         // --
 
         // Setting the time zone allows us to assume that the output
-        // from a TIMESTAMP field is UTC
+        // from a TIMESTAMP field is UTC, unless the `timezone` parameter selects another
 
         // --
 
         // https://mathiasbynens.be/notes/mysql-utf8mb4
+        // The character set and collation are `utf8mb4_unicode_ci` unless the `charset`
+        // or `collation` parameter selects another
+
+        // --
+
+        // This is a single statement, as multiple statements are not allowed
+        // with `multi-statements=false`
 
-        self_.execute(r#"
-SET sql_mode=(SELECT CONCAT(@@sql_mode, ',PIPES_AS_CONCAT,NO_ENGINE_SUBSTITUTION,NO_ZERO_DATE,NO_ZERO_IN_DATE'));
-SET time_zone = '+00:00';
-SET NAMES utf8mb4 COLLATE utf8mb4_unicode_ci;
-        "#).await?;
+        self_.execute(&*format!(r#"
+SET sql_mode=(SELECT CONCAT(@@sql_mode, ',PIPES_AS_CONCAT,NO_ENGINE_SUBSTITUTION,NO_ZERO_DATE,NO_ZERO_IN_DATE')),
+    {},
+    {}
+        "#, self_.time_zone.set_time_zone(), charset.set_names())).await?;
 
         Ok(self_)
     }
+
+    /// Returns `true` if the server is MariaDB rather than MySQL.
+    pub fn is_mariadb(&self) -> bool {
+        self.server_version.mariadb
+    }
+
+    /// Returns `true` if `INSERT`, `REPLACE` and `DELETE` statements can have a `RETURNING`
+    /// clause, which is the case for MariaDB 10.5+ (but not MySQL).
+    pub fn supports_returning(&self) -> bool {
+        self.server_version.supports_returning()
+    }
 }
 
 impl Connect for MySqlConnection {
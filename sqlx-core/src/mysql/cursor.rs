@@ -13,9 +13,14 @@ use crate::pool::Pool;
 pub struct MySqlCursor<'c, 'q> {
     source: ConnectionSource<'c, MySqlConnection>,
     query: Option<(&'q str, Option<MySqlArguments>)>,
+    persistent: bool,
     column_names: Arc<HashMap<Box<str>, u16>>,
     column_types: Vec<MySqlTypeInfo>,
     binary: bool,
+
+    // The index of the current result set, which is only ever more than 0
+    // for a query of multiple statements
+    result_index: usize,
 }
 
 impl crate::cursor::private::Sealed for MySqlCursor<'_, '_> {}
@@ -34,6 +39,8 @@ impl<'c, 'q> Cursor<'c, 'q> for MySqlCursor<'c, 'q> {
             column_names: Arc::default(),
             column_types: Vec::new(),
             binary: true,
+            result_index: 0,
+            persistent: query.persistent(),
             query: Some(query.into_parts()),
         }
     }
@@ -49,25 +56,40 @@ impl<'c, 'q> Cursor<'c, 'q> for MySqlCursor<'c, 'q> {
             column_names: Arc::default(),
             column_types: Vec::new(),
             binary: true,
+            result_index: 0,
+            persistent: query.persistent(),
             query: Some(query.into_parts()),
         }
     }
 
     fn next(&mut self) -> BoxFuture<crate::Result<Option<MySqlRow<'_>>>> {
-        Box::pin(next(self))
+        Box::pin(async move { Ok(next(self).await?.map(|(_, row)| row)) })
+    }
+}
+
+impl<'c, 'q> MySqlCursor<'c, 'q> {
+    /// Fetch the next row, along with the index of the result set that it belongs to.
+    ///
+    /// A query of multiple statements (see [`MySqlConnection`]) returns a result set for
+    /// each statement, in order; the statements that return no rows, such as `INSERT`,
+    /// still count towards the index. Returns `None` once every result set has been read.
+    ///
+    /// [`MySqlConnection`]: struct.MySqlConnection.html
+    pub async fn next_with_index(&mut self) -> crate::Result<Option<(usize, MySqlRow<'_>)>> {
+        next(self).await
     }
 }
 
 async fn next<'a, 'c: 'a, 'q: 'a>(
     cursor: &'a mut MySqlCursor<'c, 'q>,
-) -> crate::Result<Option<MySqlRow<'a>>> {
+) -> crate::Result<Option<(usize, MySqlRow<'a>)>> {
     let mut conn = cursor.source.resolve().await?;
 
     // The first time [next] is called we need to actually execute our
     // contained query. We guard against this happening on _all_ next calls
     // by using [Option::take] which replaces the potential value in the Option with `None
     let mut initial = if let Some((query, arguments)) = cursor.query.take() {
-        let statement = conn.run(query, arguments).await?;
+        let statement = conn.run(query, arguments, cursor.persistent).await?;
 
         // No statement ID = TEXT mode
         cursor.binary = statement.is_some();
@@ -93,6 +115,7 @@ async fn next<'a, 'c: 'a, 'q: 'a>(
 
                 if status.contains(Status::SERVER_MORE_RESULTS_EXISTS) {
                     // There is more to this query
+                    cursor.result_index += 1;
                     initial = true;
                 } else {
                     conn.is_ready = true;
@@ -151,9 +174,11 @@ async fn next<'a, 'c: 'a, 'q: 'a>(
                 let row = MySqlRow {
                     row,
                     names: Arc::clone(&cursor.column_names),
+                    zero_dates: conn.zero_dates,
+                    local_timestamps: !conn.time_zone.is_utc(),
                 };
 
-                return Ok(Some(row));
+                return Ok(Some((cursor.result_index, row)));
             }
 
             _ => {
@@ -2,6 +2,7 @@ use crate::cursor::HasCursor;
 use crate::database::Database;
 use crate::mysql::error::MySqlError;
 use crate::row::HasRow;
+use crate::transaction::TransactionOptions;
 use crate::value::HasRawValue;
 
 /// **MySQL** database driver.
@@ -20,6 +21,26 @@ impl Database for MySql {
     type RawBuffer = Vec<u8>;
 
     type Error = MySqlError;
+
+    fn begin_transaction(options: &TransactionOptions) -> crate::Result<Vec<String>> {
+        let mut stmts = Vec::new();
+
+        // sets the isolation level of the next transaction only
+        if let Some(level) = options.isolation {
+            stmts.push(format!(
+                "SET TRANSACTION ISOLATION LEVEL {}",
+                level.as_sql()
+            ));
+        }
+
+        stmts.push(if options.read_only {
+            "START TRANSACTION READ ONLY".to_owned()
+        } else {
+            "START TRANSACTION".to_owned()
+        });
+
+        Ok(stmts)
+    }
 }
 
 impl<'c> HasRow<'c> for MySql {
@@ -39,3 +60,32 @@ impl<'c> HasRawValue<'c> for MySql {
 
     type RawValue = super::MySqlValue<'c>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::MySql;
+    use crate::database::Database;
+    use crate::transaction::{IsolationLevel, TransactionOptions};
+
+    #[test]
+    fn it_begins_transactions_with_options() {
+        assert_eq!(
+            MySql::begin_transaction(&TransactionOptions::new()).unwrap(),
+            vec!["START TRANSACTION"]
+        );
+
+        assert_eq!(
+            MySql::begin_transaction(
+                &TransactionOptions::new()
+                    .isolation(IsolationLevel::RepeatableRead)
+                    .read_only(true)
+                    .deferrable(true)
+            )
+            .unwrap(),
+            vec![
+                "SET TRANSACTION ISOLATION LEVEL REPEATABLE READ",
+                "START TRANSACTION READ ONLY"
+            ]
+        );
+    }
+}
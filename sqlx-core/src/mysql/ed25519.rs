@@ -0,0 +1,205 @@
+use digest::Digest;
+use num_bigint::BigUint;
+use sha2::Sha512;
+
+// Ed25519 signatures for the `client_ed25519` authentication plugin of MariaDB, which
+// signs the nonce with a key derived from the password
+// https://mariadb.com/kb/en/authentication-plugin-ed25519/
+// https://tools.ietf.org/html/rfc8032#section-5.1
+
+// The prime of the field, 2^255 - 19
+fn p() -> BigUint {
+    (BigUint::from(1_u8) << 255) - BigUint::from(19_u8)
+}
+
+// The order of the base point, 2^252 + 27742317777372353535851937790883648493
+fn l() -> BigUint {
+    (BigUint::from(1_u8) << 252) + decimal("27742317777372353535851937790883648493")
+}
+
+// The constant of the curve, -121665/121666
+fn d() -> BigUint {
+    decimal("37095705934669439343138083508754565189542113879843219016388785533085940283555")
+}
+
+fn decimal(s: &str) -> BigUint {
+    BigUint::parse_bytes(s.as_bytes(), 10).unwrap()
+}
+
+// A point of the curve in extended coordinates (x = X/Z, y = Y/Z, x * y = T/Z)
+#[derive(Clone)]
+struct Point {
+    x: BigUint,
+    y: BigUint,
+    z: BigUint,
+    t: BigUint,
+}
+
+impl Point {
+    fn base() -> Self {
+        let x = decimal(
+            "15112221349535400772501151409588531511454012693041857206046113283949847762202",
+        );
+        let y = decimal(
+            "46316835694926478169428394003475163141307993866256225615783033603165251855960",
+        );
+        let t = (&x * &y) % p();
+
+        Point {
+            x,
+            y,
+            z: BigUint::from(1_u8),
+            t,
+        }
+    }
+
+    fn identity() -> Self {
+        Point {
+            x: BigUint::from(0_u8),
+            y: BigUint::from(1_u8),
+            z: BigUint::from(1_u8),
+            t: BigUint::from(0_u8),
+        }
+    }
+
+    // https://tools.ietf.org/html/rfc8032#section-5.1.4
+    fn add(&self, other: &Point) -> Point {
+        let p = p();
+        let sub = |a: &BigUint, b: &BigUint| (a + &p - b) % &p;
+
+        let a = (sub(&self.y, &self.x) * sub(&other.y, &other.x)) % &p;
+        let b = ((&self.y + &self.x) * (&other.y + &other.x)) % &p;
+        let c = (&self.t * BigUint::from(2_u8) * d() % &p * &other.t) % &p;
+        let d = (&self.z * BigUint::from(2_u8) * &other.z) % &p;
+
+        let e = sub(&b, &a);
+        let f = sub(&d, &c);
+        let g = (&d + &c) % &p;
+        let h = (&b + &a) % &p;
+
+        Point {
+            x: (&e * &f) % &p,
+            y: (&g * &h) % &p,
+            z: (&f * &g) % &p,
+            t: (&e * &h) % &p,
+        }
+    }
+
+    fn mul(&self, scalar: &BigUint) -> Point {
+        let mut result = Point::identity();
+
+        for i in (0..scalar.bits()).rev() {
+            result = result.add(&result);
+
+            if (scalar >> i) & BigUint::from(1_u8) == BigUint::from(1_u8) {
+                result = result.add(self);
+            }
+        }
+
+        result
+    }
+
+    // The y coordinate in little-endian, with the sign of x in the most significant bit
+    fn encode(&self) -> [u8; 32] {
+        let p = p();
+        let z_inv = self.z.modpow(&(&p - BigUint::from(2_u8)), &p);
+
+        let x = (&self.x * &z_inv) % &p;
+        let y = (&self.y * &z_inv) % &p;
+
+        let mut bytes = to_bytes(&y);
+
+        if x.to_bytes_le()[0] & 1 == 1 {
+            bytes[31] |= 0x80;
+        }
+
+        bytes
+    }
+}
+
+fn to_bytes(n: &BigUint) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    let le = n.to_bytes_le();
+
+    bytes[..le.len()].copy_from_slice(&le);
+    bytes
+}
+
+fn hash(parts: &[&[u8]]) -> BigUint {
+    let mut ctx = Sha512::new();
+
+    for part in parts {
+        ctx.input(part);
+    }
+
+    BigUint::from_bytes_le(&ctx.result())
+}
+
+// Sign `message` with the key derived from `password`; the private key is the hash of
+// the password instead of the hash of a 32-byte seed
+pub fn sign(password: &[u8], message: &[u8]) -> Vec<u8> {
+    let az = Sha512::digest(password);
+
+    let mut scalar = [0; 32];
+    scalar.copy_from_slice(&az[..32]);
+    scalar[0] &= 248;
+    scalar[31] &= 63;
+    scalar[31] |= 64;
+
+    let a = BigUint::from_bytes_le(&scalar);
+    let public_key = Point::base().mul(&a).encode();
+
+    let r = hash(&[&az[32..], message]) % l();
+    let big_r = Point::base().mul(&r).encode();
+
+    let k = hash(&[&big_r, &public_key, message]) % l();
+    let s = (r + k * a) % l();
+
+    let mut signature = big_r.to_vec();
+    signature.extend_from_slice(&to_bytes(&s));
+
+    signature
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sign;
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn it_signs() {
+        // https://tools.ietf.org/html/rfc8032#section-7.1 (TEST 1 and TEST 2), as the
+        // password of a 32-byte seed derives the same key
+        let signature = sign(
+            &hex("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60"),
+            b"",
+        );
+
+        assert_eq!(
+            signature,
+            hex(
+                "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e06522490155\
+                 5fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"
+            )
+        );
+
+        let signature = sign(
+            &hex("4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb"),
+            &hex("72"),
+        );
+
+        assert_eq!(
+            signature,
+            hex(
+                "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da\
+                 085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00"
+            )
+        );
+    }
+}
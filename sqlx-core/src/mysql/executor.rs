@@ -4,10 +4,14 @@ use crate::cursor::Cursor;
 use crate::describe::{Column, Describe};
 use crate::executor::{Execute, Executor, RefExecutor};
 use crate::mysql::protocol::{
-    self, ColumnDefinition, ComQuery, ComStmtExecute, ComStmtPrepare, ComStmtPrepareOk, FieldFlags,
-    Status,
+    self, ColumnDefinition, ComQuery, ComStmtClose, ComStmtExecute, ComStmtPrepare,
+    ComStmtPrepareOk, ComStmtReset, ComStmtSendLongData, FieldFlags,
 };
-use crate::mysql::{MySql, MySqlArguments, MySqlCursor, MySqlTypeInfo};
+use crate::mysql::{MySql, MySqlArguments, MySqlCursor, MySqlError, MySqlTypeInfo};
+use crate::runtime::AsyncReadExt;
+
+// The size of the chunks that the value of a long data parameter is sent in
+const LONG_DATA_CHUNK_SIZE: usize = 64 * 1024;
 
 impl super::MySqlConnection {
     // Creates a prepared statement for the passed query string
@@ -39,13 +43,20 @@ impl super::MySqlConnection {
 
     // Gets a cached prepared statement ID _or_ prepares the statement if not in the cache
     // At the end we should have [cache_statement] and [cache_statement_columns] filled
-    async fn get_or_prepare(&mut self, query: &str) -> crate::Result<u32> {
+    //
+    // A statement prepared for a non-persistent query is not cached and is closed before the
+    // next command is sent
+    async fn get_or_prepare(&mut self, query: &str, persistent: bool) -> crate::Result<u32> {
         if let Some(&id) = self.cache_statement.get(query) {
             Ok(id)
         } else {
             let stmt = self.prepare(query).await?;
 
-            self.cache_statement.insert(query.into(), stmt.statement_id);
+            if persistent {
+                self.cache_statement.insert(query.into(), stmt.statement_id);
+            } else {
+                self.close_statement = Some(stmt.statement_id);
+            }
 
             // COM_STMT_PREPARE returns the input columns
             // We make no use of that data, so cycle through and drop them
@@ -59,26 +70,132 @@ impl super::MySqlConnection {
         }
     }
 
+    // Streams the values of the long data parameters to the server, which holds them until
+    // the statement is executed
+    async fn send_long_data(
+        &mut self,
+        statement_id: u32,
+        arguments: &mut MySqlArguments,
+    ) -> crate::Result<()> {
+        let mut buf = vec![0; LONG_DATA_CHUNK_SIZE];
+
+        for (param_id, reader) in &mut arguments.long_data {
+            let param_id = *param_id;
+            let mut sent = false;
+
+            loop {
+                let read = match reader.read(&mut buf).await {
+                    Ok(read) => read,
+
+                    Err(error) => {
+                        // discard the data sent so far, so that it is not prepended to the
+                        // value of this parameter the next time the statement is executed
+                        self.stream
+                            .send(ComStmtReset { statement_id }, true)
+                            .await?;
+
+                        let is_err = self.stream.receive().await?[0] == 0xFF;
+                        self.stream.is_ready = true;
+
+                        if is_err {
+                            return self.stream.handle_err();
+                        }
+
+                        return Err(error.into());
+                    }
+                };
+
+                // an empty value is still sent, as the server expects no value for this
+                // parameter in COM_STMT_EXECUTE once it has received long data for it
+                if read > 0 || !sent {
+                    // the server does not respond to COM_STMT_SEND_LONG_DATA
+                    self.stream
+                        .send(
+                            ComStmtSendLongData {
+                                statement_id,
+                                param_id,
+                                data: &buf[..read],
+                            },
+                            true,
+                        )
+                        .await?;
+
+                    sent = true;
+                }
+
+                if read == 0 {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     pub(crate) async fn run(
         &mut self,
         query: &str,
         arguments: Option<MySqlArguments>,
+        persistent: bool,
+    ) -> crate::Result<Option<u32>> {
+        self.run_with_cursor(query, arguments, persistent, protocol::Cursor::NO_CURSOR)
+            .await
+    }
+
+    // Runs the query; a prepared statement is executed with `cursor`
+    pub(crate) async fn run_with_cursor(
+        &mut self,
+        query: &str,
+        arguments: Option<MySqlArguments>,
+        persistent: bool,
+        cursor: protocol::Cursor,
     ) -> crate::Result<Option<u32>> {
+        if let Some(arguments) = &arguments {
+            self.time_zone.check_arguments(arguments)?;
+        }
+
         self.stream.wait_until_ready().await?;
+
+        if let Some(statement_id) = self.reset_statement.take() {
+            // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_com_stmt_reset.html
+            // closes the cursor of a server-side cursor that was dropped before all of its
+            // rows were fetched
+            self.stream
+                .send(ComStmtReset { statement_id }, true)
+                .await?;
+
+            if self.stream.receive().await?[0] == 0xFF {
+                return self.stream.handle_err();
+            }
+        }
+
+        if let Some(statement_id) = self.close_statement.take() {
+            // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_com_stmt_close.html
+            // the server does not respond to COM_STMT_CLOSE
+            self.stream
+                .send(ComStmtClose { statement_id }, true)
+                .await?;
+        }
+
         self.stream.is_ready = false;
 
-        if let Some(arguments) = arguments {
-            let statement_id = self.get_or_prepare(query).await?;
+        if let Some(mut arguments) = arguments {
+            let statement_id = self.get_or_prepare(query, persistent).await?;
+
+            if !arguments.long_data.is_empty() {
+                self.send_long_data(statement_id, &mut arguments).await?;
+            }
 
             // https://dev.mysql.com/doc/dev/mysql-server/8.0.11/page_protocol_com_stmt_execute.html
             self.stream
                 .send(
                     ComStmtExecute {
-                        cursor: protocol::Cursor::NO_CURSOR,
+                        cursor,
                         statement_id,
                         params: &arguments.params,
                         null_bitmap: &arguments.null_bitmap,
                         param_types: &arguments.param_types,
+                        attributes: &arguments.attributes,
                     },
                     true,
                 )
@@ -94,38 +211,8 @@ impl super::MySqlConnection {
     }
 
     async fn affected_rows(&mut self) -> crate::Result<u64> {
-        let mut rows = 0;
-
-        loop {
-            let id = self.stream.receive().await?[0];
-
-            match id {
-                0x00 | 0xFE if self.stream.packet().len() < 0xFF_FF_FF => {
-                    // ResultSet row can begin with 0xfe byte (when using text protocol
-                    // with a field length > 0xffffff)
-
-                    let status = if let Some(eof) = self.stream.maybe_handle_eof()? {
-                        eof.status
-                    } else {
-                        let ok = self.stream.handle_ok()?;
-
-                        rows += ok.affected_rows;
-                        ok.status
-                    };
-
-                    if !status.contains(Status::SERVER_MORE_RESULTS_EXISTS) {
-                        self.is_ready = true;
-                        break;
-                    }
-                }
-
-                0xFF => {
-                    return self.stream.handle_err();
-                }
-
-                _ => {}
-            }
-        }
+        let rows = self.stream.skip_results(true).await?;
+        self.is_ready = true;
 
         Ok(rows)
     }
@@ -135,7 +222,25 @@ impl super::MySqlConnection {
     async fn do_describe(&mut self, query: &str) -> crate::Result<Describe<MySql>> {
         self.stream.wait_until_ready().await?;
 
-        let stmt = self.prepare(query).await?;
+        let stmt = match self.prepare(query).await {
+            Ok(stmt) => stmt,
+
+            // MySQL (and MariaDB before 10.5) fails to parse a `RETURNING` clause; this is
+            // most likely a query written for a newer version of MariaDB
+            Err(error)
+                if is_parse_error(&error)
+                    && has_returning(query)
+                    && !self.server_version.supports_returning() =>
+            {
+                return Err(protocol_err!(
+                    "`RETURNING` is only supported by MariaDB 10.5 or later: {}",
+                    error
+                )
+                .into());
+            }
+
+            Err(error) => return Err(error),
+        };
 
         let mut param_types = Vec::with_capacity(stmt.params as usize);
         let mut result_columns = Vec::with_capacity(stmt.columns as usize);
@@ -172,6 +277,24 @@ impl super::MySqlConnection {
     }
 }
 
+// ER_PARSE_ERROR
+fn is_parse_error(error: &crate::Error) -> bool {
+    match error {
+        crate::Error::Database(error) => matches!(
+            error.as_ref_err().downcast_ref::<MySqlError>(),
+            Some(error) if error.0.error_code == 1064
+        ),
+
+        _ => false,
+    }
+}
+
+fn has_returning(query: &str) -> bool {
+    query
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .any(|word| word.eq_ignore_ascii_case("RETURNING"))
+}
+
 impl Executor for super::MySqlConnection {
     type Database = MySql;
 
@@ -184,9 +307,10 @@ impl Executor for super::MySqlConnection {
     {
         log_execution!(query, {
             Box::pin(async move {
+                let persistent = query.persistent();
                 let (query, arguments) = query.into_parts();
 
-                self.run(query, arguments).await?;
+                self.run(query, arguments, persistent).await?;
                 self.affected_rows().await
             })
         })
@@ -0,0 +1,46 @@
+use crate::mysql::MySql;
+use crate::query::Query;
+use crate::query_as::QueryAs;
+use crate::runtime::AsyncRead;
+
+impl<'q> Query<'q, MySql> {
+    /// Bind a value for use with this SQL query that is streamed to the server from
+    /// `reader`, read to EOF, instead of being held in memory.
+    ///
+    /// The value is sent in chunks with `COM_STMT_SEND_LONG_DATA` before the query is
+    /// executed, as a `BLOB`; this is intended for inserting large `BLOB` or `TEXT` values.
+    ///
+    /// ```rust,ignore
+    /// let file = async_std::fs::File::open("video.mp4").await?;
+    ///
+    /// sqlx::query("INSERT INTO videos (name, data) VALUES (?, ?)")
+    ///     .bind("video.mp4")
+    ///     .bind_reader(file)
+    ///     .execute(&mut conn)
+    ///     .await?;
+    /// ```
+    ///
+    /// The size of the value is limited by the `max_allowed_packet` system variable of the
+    /// server.
+    pub fn bind_reader<R>(mut self, reader: R) -> Self
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+    {
+        self.arguments.add_long_data(reader);
+        self
+    }
+}
+
+impl<'q, O> QueryAs<'q, MySql, O> {
+    /// Bind a value for use with this SQL query that is streamed to the server from
+    /// `reader`.
+    ///
+    /// See [`Query::bind_reader`].
+    pub fn bind_reader<R>(mut self, reader: R) -> Self
+    where
+        R: AsyncRead + Send + Unpin + 'static,
+    {
+        self.arguments.add_long_data(reader);
+        self
+    }
+}
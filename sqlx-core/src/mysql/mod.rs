@@ -5,26 +5,41 @@ pub use connection::MySqlConnection;
 pub use cursor::MySqlCursor;
 pub use database::MySql;
 pub use error::MySqlError;
+pub use results::{MySqlResultSet, MySqlResults};
 pub use row::MySqlRow;
+pub use server_cursor::MySqlServerCursor;
 pub use type_info::MySqlTypeInfo;
 pub use value::{MySqlData, MySqlValue};
 
 mod arguments;
+pub mod binlog;
+mod charset;
+mod compression;
+mod connect_attrs;
 mod connection;
 mod cursor;
 mod database;
+mod ed25519;
 mod error;
 mod executor;
 mod io;
+mod long_data;
 mod protocol;
+mod query_attributes;
+mod results;
 mod row;
 mod rsa;
+mod server_cursor;
+mod server_version;
 mod stream;
+mod time_zone;
 mod tls;
 mod type_info;
 pub mod types;
 mod util;
 mod value;
+pub mod x;
+mod zero_dates;
 
 /// An alias for [`crate::pool::Pool`], specialized for **MySQL**.
 #[cfg_attr(docsrs, doc(cfg(feature = "mysql")))]
@@ -4,6 +4,7 @@ use memchr::memchr;
 use sha1::Sha1;
 use sha2::Sha256;
 
+use crate::mysql::ed25519;
 use crate::mysql::util::xor_eq;
 
 #[derive(Debug, PartialEq)]
@@ -11,6 +12,8 @@ pub enum AuthPlugin {
     MySqlNativePassword,
     CachingSha2Password,
     Sha256Password,
+    ClientEd25519,
+    MySqlClearPassword,
 }
 
 impl AuthPlugin {
@@ -19,6 +22,8 @@ impl AuthPlugin {
             Some("mysql_native_password") | None => Ok(AuthPlugin::MySqlNativePassword),
             Some("caching_sha2_password") => Ok(AuthPlugin::CachingSha2Password),
             Some("sha256_password") => Ok(AuthPlugin::Sha256Password),
+            Some("client_ed25519") => Ok(AuthPlugin::ClientEd25519),
+            Some("mysql_clear_password") => Ok(AuthPlugin::MySqlClearPassword),
 
             Some(s) => {
                 Err(protocol_err!("requires unimplemented authentication plugin: {}", s).into())
@@ -31,6 +36,8 @@ impl AuthPlugin {
             AuthPlugin::MySqlNativePassword => "mysql_native_password",
             AuthPlugin::CachingSha2Password => "caching_sha2_password",
             AuthPlugin::Sha256Password => "sha256_password",
+            AuthPlugin::ClientEd25519 => "client_ed25519",
+            AuthPlugin::MySqlClearPassword => "mysql_clear_password",
         }
     }
 
@@ -44,6 +51,9 @@ impl AuthPlugin {
             }
             AuthPlugin::CachingSha2Password => scramble_sha256(password, nonce).to_vec(),
 
+            // https://mariadb.com/kb/en/authentication-plugin-ed25519/
+            AuthPlugin::ClientEd25519 => ed25519::sign(password.as_bytes(), nonce),
+
             _ => unimplemented!(),
         }
     }
@@ -0,0 +1,41 @@
+use byteorder::LittleEndian;
+
+use crate::io::BufMut;
+use crate::mysql::protocol::{Capabilities, Encode};
+
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_com_binlog_dump_gtid.html
+#[derive(Debug)]
+pub struct ComBinlogDumpGtid<'a> {
+    pub server_id: u32,
+
+    // The GTID set of the transactions that are not to be sent, in its binary format
+    pub gtid_set: &'a [u8],
+}
+
+// The position is given by the GTID set instead of the binlog file and position
+const BINLOG_THROUGH_GTID: u16 = 0x04;
+
+impl Encode for ComBinlogDumpGtid<'_> {
+    fn encode(&self, buf: &mut Vec<u8>, _: Capabilities) {
+        // COM_BINLOG_DUMP_GTID : int<1>
+        buf.put_u8(0x1e);
+
+        // flags : int<2>
+        buf.put_u16::<LittleEndian>(BINLOG_THROUGH_GTID);
+
+        // server_id : int<4>
+        buf.put_u32::<LittleEndian>(self.server_id);
+
+        // binlog_filename_len : int<4>
+        // binlog_filename : string<len>
+        buf.put_u32::<LittleEndian>(0);
+
+        // binlog_pos : int<8>
+        buf.put_u64::<LittleEndian>(4);
+
+        // data_size : int<4>
+        // data : string<len>
+        buf.put_u32::<LittleEndian>(self.gtid_set.len() as u32);
+        buf.put_bytes(self.gtid_set);
+    }
+}
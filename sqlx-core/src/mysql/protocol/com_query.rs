@@ -1,18 +1,49 @@
+use byteorder::LittleEndian;
+
 use crate::io::BufMut;
+use crate::mysql::io::BufMutExt;
 use crate::mysql::protocol::{Capabilities, Encode};
 
-// https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_com_query.html
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.26/page_protocol_com_query.html
 #[derive(Debug)]
 pub struct ComQuery<'a> {
     pub query: &'a str,
 }
 
 impl Encode for ComQuery<'_> {
-    fn encode(&self, buf: &mut Vec<u8>, _: Capabilities) {
+    fn encode(&self, buf: &mut Vec<u8>, capabilities: Capabilities) {
         // COM_QUERY : int<1>
         buf.put_u8(0x03);
 
+        if capabilities.contains(Capabilities::QUERY_ATTRIBUTES) {
+            // parameter_count : int<lenenc>
+            // the attributes of a query are sent with COM_STMT_EXECUTE
+            buf.put_uint_lenenc::<LittleEndian, _>(0);
+
+            // parameter_set_count (always 1) : int<lenenc>
+            buf.put_uint_lenenc::<LittleEndian, _>(1);
+        }
+
         // query : string<EOF>
         buf.put_str(self.query);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ComQuery;
+    use crate::mysql::protocol::{Capabilities, Encode};
+
+    #[test]
+    fn it_encodes_com_query() {
+        let mut buf = Vec::new();
+        ComQuery { query: "SELECT 1" }.encode(&mut buf, Capabilities::empty());
+
+        assert_eq!(buf, b"\x03SELECT 1");
+
+        let mut buf = Vec::new();
+        ComQuery { query: "SELECT 1" }.encode(&mut buf, Capabilities::QUERY_ATTRIBUTES);
+
+        assert_eq!(buf, b"\x03\x00\x01SELECT 1");
+    }
+}
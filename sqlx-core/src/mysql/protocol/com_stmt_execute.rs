@@ -1,7 +1,9 @@
 use byteorder::LittleEndian;
 
 use crate::io::BufMut;
-use crate::mysql::protocol::{Capabilities, Encode};
+use crate::mysql::arguments::MySqlQueryAttribute;
+use crate::mysql::io::BufMutExt;
+use crate::mysql::protocol::{Capabilities, Encode, TypeId};
 use crate::mysql::type_info::MySqlTypeInfo;
 
 bitflags::bitflags! {
@@ -15,7 +17,7 @@ bitflags::bitflags! {
     }
 }
 
-// https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_com_stmt_execute.html
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.26/page_protocol_com_stmt_execute.html
 #[derive(Debug)]
 pub struct ComStmtExecute<'a> {
     pub statement_id: u32,
@@ -23,10 +25,18 @@ pub struct ComStmtExecute<'a> {
     pub params: &'a [u8],
     pub null_bitmap: &'a [u8],
     pub param_types: &'a [MySqlTypeInfo],
+    pub attributes: &'a [MySqlQueryAttribute],
 }
 
 impl Encode for ComStmtExecute<'_> {
-    fn encode(&self, buf: &mut Vec<u8>, _: Capabilities) {
+    fn encode(&self, buf: &mut Vec<u8>, capabilities: Capabilities) {
+        // query attributes are only sent to a server that supports them
+        let attributes = if capabilities.contains(Capabilities::QUERY_ATTRIBUTES) {
+            self.attributes
+        } else {
+            &[]
+        };
+
         // COM_STMT_EXECUTE : int<1>
         buf.put_u8(0x17);
 
@@ -34,28 +44,155 @@ impl Encode for ComStmtExecute<'_> {
         buf.put_u32::<LittleEndian>(self.statement_id);
 
         // cursor : int<1>
-        buf.put_u8(self.cursor.bits());
+        // PARAMETER_COUNT_AVAILABLE tells the server that the parameter count follows
+        // even if the statement has no parameters
+        buf.put_u8(if attributes.is_empty() {
+            self.cursor.bits()
+        } else {
+            self.cursor.bits() | 0x08
+        });
 
         // iterations (always 1) : int<4>
         buf.put_u32::<LittleEndian>(1);
 
-        if !self.param_types.is_empty() {
-            // null bitmap : byte<(param_count + 7)/8>
-            buf.put_bytes(self.null_bitmap);
+        if self.param_types.is_empty() && attributes.is_empty() {
+            return;
+        }
+
+        let count = self.param_types.len() + attributes.len();
+
+        if capabilities.contains(Capabilities::QUERY_ATTRIBUTES) {
+            // parameter_count : int<lenenc>
+            buf.put_uint_lenenc::<LittleEndian, _>(count as u64);
+        }
+
+        // null bitmap : byte<(param_count + 7)/8>
+        // the attributes follow the parameters
+        let mut null_bitmap = self.null_bitmap.to_vec();
+        null_bitmap.resize(((count - 1) / 8) + 1, 0);
+
+        for (i, attribute) in attributes.iter().enumerate() {
+            let index = self.param_types.len() + i;
+
+            if attribute.value.is_none() {
+                null_bitmap[index / 8] |= (1 << (index % 8)) as u8;
+            }
+        }
+
+        buf.put_bytes(&null_bitmap);
+
+        // send type to server (0 / 1) : byte<1>
+        buf.put_u8(1);
 
-            // send type to server (0 / 1) : byte<1>
-            buf.put_u8(1);
+        let types = self
+            .param_types
+            .iter()
+            .map(|ty| (ty, ""))
+            .chain(attributes.iter().map(|attr| (&attr.type_info, &*attr.name)));
 
-            for ty in self.param_types {
-                // field type : byte<1>
-                buf.put_u8(ty.id.0);
+        for (ty, name) in types {
+            // field type : byte<1>
+            // geometries are sent as BLOBs (in their internal format), as the server
+            // treats parameters of other types as text in the client character set
+            buf.put_u8(if ty.id == TypeId::GEOMETRY {
+                TypeId::TEXT.0
+            } else {
+                ty.id.0
+            });
 
-                // parameter flag : byte<1>
-                buf.put_u8(if ty.is_unsigned { 0x80 } else { 0 });
+            // parameter flag : byte<1>
+            buf.put_u8(if ty.is_unsigned { 0x80 } else { 0 });
+
+            if capabilities.contains(Capabilities::QUERY_ATTRIBUTES) {
+                // parameter name (empty for a parameter of the statement) : string<lenenc>
+                buf.put_str_lenenc::<LittleEndian>(name);
             }
+        }
 
-            // byte<n> binary parameter value
-            buf.put_bytes(self.params);
+        // byte<n> binary parameter value
+        buf.put_bytes(self.params);
+
+        for attribute in attributes {
+            if let Some(value) = &attribute.value {
+                buf.put_bytes(value);
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{ComStmtExecute, Cursor};
+    use crate::arguments::Arguments;
+    use crate::mysql::protocol::{Capabilities, Encode};
+    use crate::mysql::MySqlArguments;
+
+    #[test]
+    fn it_encodes_params() {
+        let mut arguments = MySqlArguments::default();
+        arguments.add(1_i32);
+
+        let mut buf = Vec::new();
+
+        ComStmtExecute {
+            statement_id: 1,
+            cursor: Cursor::NO_CURSOR,
+            params: &arguments.params,
+            null_bitmap: &arguments.null_bitmap,
+            param_types: &arguments.param_types,
+            attributes: &arguments.attributes,
+        }
+        .encode(&mut buf, Capabilities::empty());
+
+        assert_eq!(
+            buf,
+            [0x17, 1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0x03, 0, 1, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn it_encodes_query_attributes() {
+        let mut arguments = MySqlArguments::default();
+        arguments.add(1_i32);
+        arguments.add_attribute("a", "b");
+        arguments.add_attribute("n", Option::<i32>::None);
+
+        let execute = ComStmtExecute {
+            statement_id: 1,
+            cursor: Cursor::NO_CURSOR,
+            params: &arguments.params,
+            null_bitmap: &arguments.null_bitmap,
+            param_types: &arguments.param_types,
+            attributes: &arguments.attributes,
+        };
+
+        let mut buf = Vec::new();
+        execute.encode(&mut buf, Capabilities::QUERY_ATTRIBUTES);
+
+        #[rustfmt::skip]
+        assert_eq!(
+            buf,
+            [
+                0x17, 1, 0, 0, 0, 0x08, 1, 0, 0, 0,
+                // parameter count, null bitmap, new params bound
+                3, 0b100, 1,
+                // types and names
+                0x03, 0, 0,
+                0xfc, 0, 1, b'a',
+                0x03, 0, 1, b'n',
+                // values
+                1, 0, 0, 0,
+                1, b'b',
+            ]
+        );
+
+        // the attributes are dropped when the server does not support them
+        let mut buf = Vec::new();
+        execute.encode(&mut buf, Capabilities::empty());
+
+        assert_eq!(
+            buf,
+            [0x17, 1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0x03, 0, 1, 0, 0, 0]
+        );
+    }
+}
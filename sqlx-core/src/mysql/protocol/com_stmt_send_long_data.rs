@@ -0,0 +1,28 @@
+use byteorder::LittleEndian;
+
+use crate::io::BufMut;
+use crate::mysql::protocol::{Capabilities, Encode};
+
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_com_stmt_send_long_data.html
+#[derive(Debug)]
+pub struct ComStmtSendLongData<'a> {
+    pub statement_id: u32,
+    pub param_id: u16,
+    pub data: &'a [u8],
+}
+
+impl Encode for ComStmtSendLongData<'_> {
+    fn encode(&self, buf: &mut Vec<u8>, _: Capabilities) {
+        // COM_STMT_SEND_LONG_DATA : int<1>
+        buf.put_u8(0x18);
+
+        // statement_id : int<4>
+        buf.put_u32::<LittleEndian>(self.statement_id);
+
+        // param_id : int<2>
+        buf.put_u16::<LittleEndian>(self.param_id);
+
+        // data : string<EOF>
+        buf.put_bytes(self.data);
+    }
+}
@@ -14,6 +14,7 @@ pub struct HandshakeResponse<'a> {
     pub database: Option<&'a str>,
     pub auth_plugin: &'a AuthPlugin,
     pub auth_response: &'a [u8],
+    pub attributes: &'a [(String, String)],
 }
 
 impl Encode for HandshakeResponse<'_> {
@@ -68,5 +69,47 @@ impl Encode for HandshakeResponse<'_> {
             // client_plugin_name : string<NUL>
             buf.put_str_nul(self.auth_plugin.as_str());
         }
+
+        if capabilities.contains(Capabilities::CONNECT_ATTRS) {
+            let mut attributes = Vec::new();
+
+            for (key, value) in self.attributes {
+                attributes.put_str_lenenc::<LittleEndian>(key);
+                attributes.put_str_lenenc::<LittleEndian>(value);
+            }
+
+            // length of all key-values : int<lenenc>
+            // key, value : string<lenenc>
+            buf.put_bytes_lenenc::<LittleEndian>(&attributes);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HandshakeResponse;
+    use crate::mysql::protocol::{AuthPlugin, Capabilities, Encode};
+
+    #[test]
+    fn it_encodes_connection_attributes() {
+        let attributes = [("_client_name".to_owned(), "sqlx".to_owned())];
+
+        let mut buf = Vec::new();
+
+        HandshakeResponse {
+            max_packet_size: 1024,
+            client_collation: 224,
+            username: "root",
+            database: None,
+            auth_plugin: &AuthPlugin::MySqlNativePassword,
+            auth_response: &[],
+            attributes: &attributes,
+        }
+        .encode(
+            &mut buf,
+            Capabilities::MYSQL | Capabilities::PLUGIN_AUTH | Capabilities::CONNECT_ATTRS,
+        );
+
+        assert!(buf.ends_with(b"mysql_native_password\0\x12\x0c_client_name\x04sqlx"));
     }
 }
@@ -10,16 +10,26 @@ pub(crate) use field::FieldFlags;
 pub(crate) use r#type::TypeId;
 pub(crate) use status::Status;
 
+mod com_binlog_dump_gtid;
 mod com_ping;
 mod com_query;
+mod com_stmt_close;
 mod com_stmt_execute;
+mod com_stmt_fetch;
 mod com_stmt_prepare;
+mod com_stmt_reset;
+mod com_stmt_send_long_data;
 mod handshake;
 
+pub(crate) use com_binlog_dump_gtid::ComBinlogDumpGtid;
 pub(crate) use com_ping::ComPing;
 pub(crate) use com_query::ComQuery;
+pub(crate) use com_stmt_close::ComStmtClose;
 pub(crate) use com_stmt_execute::{ComStmtExecute, Cursor};
+pub(crate) use com_stmt_fetch::ComStmtFetch;
 pub(crate) use com_stmt_prepare::ComStmtPrepare;
+pub(crate) use com_stmt_reset::ComStmtReset;
+pub(crate) use com_stmt_send_long_data::ComStmtSendLongData;
 pub(crate) use handshake::Handshake;
 
 mod auth_switch;
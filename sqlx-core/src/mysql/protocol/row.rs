@@ -115,11 +115,12 @@ impl<'c> Row<'c> {
             } else {
                 let (offset, size) = match columns[column_idx].id {
                     TypeId::TINY_INT => (0, 1),
-                    TypeId::SMALL_INT => (0, 2),
-                    TypeId::INT | TypeId::FLOAT => (0, 4),
+                    TypeId::SMALL_INT | TypeId::YEAR => (0, 2),
+                    // MEDIUMINT is sent as 4 bytes, like INT
+                    TypeId::INT | TypeId::MEDIUM_INT | TypeId::FLOAT => (0, 4),
                     TypeId::BIG_INT | TypeId::DOUBLE => (0, 8),
 
-                    TypeId::DATE => (0, 5),
+                    TypeId::DATE => (0, 1 + buffer[index] as usize),
                     TypeId::TIME => (0, 1 + buffer[index] as usize),
 
                     TypeId::TIMESTAMP | TypeId::DATETIME => (0, 1 + buffer[index] as usize),
@@ -130,7 +131,10 @@ impl<'c> Row<'c> {
                     | TypeId::CHAR
                     | TypeId::TEXT
                     | TypeId::ENUM
-                    | TypeId::VAR_CHAR => {
+                    | TypeId::SET
+                    | TypeId::VAR_CHAR
+                    | TypeId::GEOMETRY
+                    | TypeId::BIT => {
                         let (len_size, len) = get_lenenc(&buffer[index..]);
 
                         (len_size, len.unwrap_or_default())
@@ -17,28 +17,40 @@ impl TypeId {
     // Enum
     pub const ENUM: TypeId = TypeId(247);
 
+    // Set: sent as a CHAR with the SET flag, and as comma-separated members in both protocols
+    pub const SET: TypeId = TypeId(248);
+
     // More Bytes
     pub const TINY_BLOB: TypeId = TypeId(249);
     pub const MEDIUM_BLOB: TypeId = TypeId(250);
     pub const LONG_BLOB: TypeId = TypeId(251);
 
-    // Numeric: TINYINT, SMALLINT, INT, BIGINT
+    // Numeric: TINYINT, SMALLINT, MEDIUMINT, INT, BIGINT
     pub const TINY_INT: TypeId = TypeId(1);
     pub const SMALL_INT: TypeId = TypeId(2);
+    pub const MEDIUM_INT: TypeId = TypeId(9);
     pub const INT: TypeId = TypeId(3);
     pub const BIG_INT: TypeId = TypeId(8);
-    // pub const MEDIUM_INT: TypeId = TypeId(9);
 
     // Numeric: FLOAT, DOUBLE
     pub const FLOAT: TypeId = TypeId(4);
     pub const DOUBLE: TypeId = TypeId(5);
     pub const NEWDECIMAL: TypeId = TypeId(246);
 
+    // Bits: BIT(n), sent as n bits in big-endian bytes
+    pub const BIT: TypeId = TypeId(16);
+
     // Date/Time: DATE, TIME, DATETIME, TIMESTAMP
     pub const DATE: TypeId = TypeId(10);
     pub const TIME: TypeId = TypeId(11);
     pub const DATETIME: TypeId = TypeId(12);
     pub const TIMESTAMP: TypeId = TypeId(7);
+
+    // Year: YEAR, sent as 2 bytes in the binary protocol, like SMALLINT
+    pub const YEAR: TypeId = TypeId(13);
+
+    // Spatial: GEOMETRY, POINT, LINESTRING, POLYGON, ...
+    pub const GEOMETRY: TypeId = TypeId(255);
 }
 
 impl Default for TypeId {
@@ -0,0 +1,46 @@
+use crate::encode::Encode;
+use crate::mysql::MySql;
+use crate::query::Query;
+use crate::query_as::QueryAs;
+use crate::types::Type;
+
+impl<'q> Query<'q, MySql> {
+    /// Attach a query attribute (MySQL 8.0.23+) to this query.
+    ///
+    /// Query attributes are metadata sent alongside the query (e.g., a trace ID); they are
+    /// not bound to the SQL but can be read on the server with
+    /// `mysql_query_attribute_string(name)` and are visible in `performance_schema`.
+    ///
+    /// ```rust,ignore
+    /// sqlx::query("SELECT * FROM users WHERE id = ?")
+    ///     .bind(id)
+    ///     .attribute("traceparent", traceparent)
+    ///     .fetch_one(&mut conn)
+    ///     .await?;
+    /// ```
+    ///
+    /// Attributes are silently dropped if the server does not support them (MariaDB or
+    /// MySQL before 8.0.23).
+    pub fn attribute<T>(mut self, name: &str, value: T) -> Self
+    where
+        T: Type<MySql>,
+        T: Encode<MySql>,
+    {
+        self.arguments.add_attribute(name, value);
+        self
+    }
+}
+
+impl<'q, O> QueryAs<'q, MySql, O> {
+    /// Attach a query attribute (MySQL 8.0.23+) to this query.
+    ///
+    /// See [`Query::attribute`].
+    pub fn attribute<T>(mut self, name: &str, value: T) -> Self
+    where
+        T: Type<MySql>,
+        T: Encode<MySql>,
+    {
+        self.arguments.add_attribute(name, value);
+        self
+    }
+}
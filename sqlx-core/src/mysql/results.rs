@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::describe::Column;
+use crate::executor::Execute;
+use crate::mysql::protocol::{ColumnCount, ColumnDefinition, FieldFlags, Row, Status};
+use crate::mysql::{MySql, MySqlArguments, MySqlConnection, MySqlRow, MySqlTypeInfo};
+
+/// The results of a query, one result set at a time.
+///
+/// Returned from [`MySqlConnection::fetch_results`].
+pub struct MySqlResults<'c, 'q> {
+    conn: &'c mut MySqlConnection,
+    query: Option<(&'q str, Option<MySqlArguments>)>,
+    persistent: bool,
+    binary: bool,
+
+    // The index of the current result set
+    index: Option<usize>,
+
+    columns: Vec<Column<MySql>>,
+    column_names: Arc<HashMap<Box<str>, u16>>,
+    column_types: Vec<MySqlTypeInfo>,
+    rows_affected: u64,
+
+    // Are there rows of the current result set left to read
+    in_rows: bool,
+
+    // Has the last result set been reached
+    done: bool,
+}
+
+/// A result set of a query, with its own columns.
+///
+/// Returned from [`MySqlResults::next`].
+pub struct MySqlResultSet<'r, 'c, 'q> {
+    results: &'r mut MySqlResults<'c, 'q>,
+}
+
+impl MySqlConnection {
+    /// Execute the query and return its results one result set at a time, each with its own
+    /// columns.
+    ///
+    /// This is for a `CALL` of a procedure that returns several result sets, or a query of
+    /// multiple statements (see [`MySqlConnection`]), where the rows of [`Executor::fetch`]
+    /// are flattened together:
+    ///
+    /// ```rust,ignore
+    /// let mut results = conn.fetch_results("CALL get_report()");
+    ///
+    /// while let Some(mut set) = results.next().await? {
+    ///     println!("{:?}", set.columns());
+    ///
+    ///     while let Some(row) = set.next().await? {
+    ///         // ...
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Every statement has a result set, including those that return no rows (such as
+    /// `INSERT`), whose [`rows_affected`] are known instead; the last result set of a `CALL`
+    /// is the status of the `CALL` itself.
+    ///
+    /// [`Executor::fetch`]: crate::executor::Executor::fetch
+    /// [`rows_affected`]: MySqlResultSet::rows_affected
+    pub fn fetch_results<'c, 'q, E>(&'c mut self, query: E) -> MySqlResults<'c, 'q>
+    where
+        E: Execute<'q, MySql>,
+    {
+        let persistent = query.persistent();
+
+        MySqlResults {
+            conn: self,
+            query: Some(query.into_parts()),
+            persistent,
+            binary: false,
+            index: None,
+            columns: Vec::new(),
+            column_names: Arc::default(),
+            column_types: Vec::new(),
+            rows_affected: 0,
+            in_rows: false,
+            done: false,
+        }
+    }
+}
+
+impl<'c, 'q> MySqlResults<'c, 'q> {
+    /// Advance to the next result set, returning `None` once every result set has been read.
+    ///
+    /// Any rows of the current result set that were not read are skipped.
+    pub async fn next(&mut self) -> crate::Result<Option<MySqlResultSet<'_, 'c, 'q>>> {
+        if let Some((query, arguments)) = self.query.take() {
+            let statement = match self.conn.run(query, arguments, self.persistent).await {
+                Ok(statement) => statement,
+
+                Err(error) => {
+                    self.done = true;
+                    return Err(error);
+                }
+            };
+
+            // No statement ID = TEXT mode
+            self.binary = statement.is_some();
+        } else {
+            while self.in_rows {
+                self.next_row().await?;
+            }
+
+            if self.done {
+                return Ok(None);
+            }
+        }
+
+        let stream = &mut self.conn.stream;
+
+        self.columns.clear();
+        self.column_types.clear();
+        self.rows_affected = 0;
+        self.index = Some(self.index.map_or(0, |index| index + 1));
+
+        match stream.receive().await?[0] {
+            // OK packet; a statement without a result set
+            0x00 => {
+                let ok = stream.handle_ok()?;
+
+                self.rows_affected = ok.affected_rows;
+                self.done = !ok.status.contains(Status::SERVER_MORE_RESULTS_EXISTS);
+                self.column_names = Arc::default();
+            }
+
+            0xFF => {
+                self.done = true;
+                return stream.handle_err();
+            }
+
+            _ => {
+                let cc = ColumnCount::read(stream.packet())?;
+                let mut column_names = HashMap::with_capacity(cc.columns as usize);
+
+                for i in 0..cc.columns {
+                    let column = ColumnDefinition::read(stream.receive().await?)?;
+
+                    self.column_types
+                        .push(MySqlTypeInfo::from_nullable_column_def(&column));
+
+                    if let Some(name) = column.name() {
+                        column_names.insert(name.to_owned().into_boxed_str(), i as u16);
+                    }
+
+                    self.columns.push(Column {
+                        type_info: MySqlTypeInfo::from_column_def(&column),
+                        name: column.column_alias.or(column.column),
+                        table_id: column.table_alias.or(column.table),
+                        non_null: Some(column.flags.contains(FieldFlags::NOT_NULL)),
+                    });
+                }
+
+                if cc.columns > 0 {
+                    stream.maybe_receive_eof().await?;
+                }
+
+                self.column_names = Arc::new(column_names);
+                self.in_rows = true;
+            }
+        }
+
+        Ok(Some(MySqlResultSet { results: self }))
+    }
+
+    async fn next_row(&mut self) -> crate::Result<Option<MySqlRow<'_>>> {
+        if !self.in_rows {
+            return Ok(None);
+        }
+
+        let conn = &mut *self.conn;
+        let packet_id = conn.stream.receive().await?[0];
+
+        match packet_id {
+            // EOF (or OK) packet; a row of the text protocol can start with 0xFE for a field
+            // length > 0xFFFFFF
+            0xFE if conn.stream.packet().len() < 0xFF_FF_FF => {
+                let status = if let Some(eof) = conn.stream.maybe_handle_eof()? {
+                    eof.status
+                } else {
+                    conn.stream.handle_ok()?.status
+                };
+
+                self.in_rows = false;
+                self.done = !status.contains(Status::SERVER_MORE_RESULTS_EXISTS);
+
+                Ok(None)
+            }
+
+            0xFF => {
+                self.in_rows = false;
+                self.done = true;
+
+                conn.stream.handle_err()
+            }
+
+            _ if !self.binary || packet_id == 0x00 => {
+                let row = Row::read(
+                    conn.stream.packet(),
+                    &self.column_types,
+                    &mut conn.current_row_values,
+                    self.binary,
+                )?;
+
+                Ok(Some(MySqlRow {
+                    row,
+                    names: Arc::clone(&self.column_names),
+                    zero_dates: conn.zero_dates,
+                    local_timestamps: !conn.time_zone.is_utc(),
+                }))
+            }
+
+            _ => conn.stream.handle_unexpected(),
+        }
+    }
+}
+
+impl MySqlResultSet<'_, '_, '_> {
+    /// The index of this result set among the results of the query.
+    pub fn index(&self) -> usize {
+        self.results.index.unwrap_or(0)
+    }
+
+    /// The columns of this result set; empty for a statement that returns no rows.
+    pub fn columns(&self) -> &[Column<MySql>] {
+        &self.results.columns
+    }
+
+    /// The number of rows affected by a statement that returns no rows.
+    pub fn rows_affected(&self) -> u64 {
+        self.results.rows_affected
+    }
+
+    /// Fetch the next row of this result set, returning `None` once all rows have been read.
+    pub async fn next(&mut self) -> crate::Result<Option<MySqlRow<'_>>> {
+        self.results.next_row().await
+    }
+}
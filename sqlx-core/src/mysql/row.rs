@@ -2,12 +2,17 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::mysql::protocol;
-use crate::mysql::{MySql, MySqlValue};
+use crate::mysql::zero_dates::ZeroDates;
+use crate::mysql::{MySql, MySqlData, MySqlValue};
 use crate::row::{ColumnIndex, Row};
 
 pub struct MySqlRow<'c> {
     pub(super) row: protocol::Row<'c>,
     pub(super) names: Arc<HashMap<Box<str>, u16>>,
+    pub(super) zero_dates: ZeroDates,
+
+    // Is the session time zone not UTC, from the `timezone` parameter
+    pub(super) local_timestamps: bool,
 }
 
 impl crate::row::private_row::Sealed for MySqlRow<'_> {}
@@ -27,12 +32,22 @@ impl<'c> Row<'c> for MySqlRow<'c> {
         let index = index.index(self)?;
         let column_ty = self.row.columns[index].clone();
         let buffer = self.row.get(index);
-        let value = match (self.row.binary, buffer) {
-            (_, None) => MySqlValue::null(),
-            (true, Some(buf)) => MySqlValue::binary(column_ty, buf),
-            (false, Some(buf)) => MySqlValue::text(column_ty, buf),
+        let data = buffer.map(|buf| {
+            if self.row.binary {
+                MySqlData::Binary(buf)
+            } else {
+                MySqlData::Text(buf)
+            }
+        });
+
+        let mut value = match data.and_then(|data| self.zero_dates.apply(&column_ty, data)) {
+            None => MySqlValue::null(),
+            Some(MySqlData::Binary(buf)) => MySqlValue::binary(column_ty, buf),
+            Some(MySqlData::Text(buf)) => MySqlValue::text(column_ty, buf),
         };
 
+        value.local_timestamps = self.local_timestamps;
+
         Ok(value)
     }
 }
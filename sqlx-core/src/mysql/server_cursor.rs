@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use futures_core::stream::BoxStream;
+
+use crate::executor::Execute;
+use crate::mysql::protocol::{
+    Capabilities, ColumnCount, ColumnDefinition, ComStmtFetch, Cursor, EofPacket, Row, Status,
+};
+use crate::mysql::stream::MySqlStream;
+use crate::mysql::{MySql, MySqlArguments, MySqlConnection, MySqlRow, MySqlTypeInfo};
+use crate::query::Query;
+use crate::query_as::QueryAs;
+use crate::row::FromRow;
+
+/// A cursor over the results of a prepared statement that are fetched from MySQL in
+/// chunks through `COM_STMT_FETCH`.
+///
+/// Returned from [`Query::fetch_cursor`].
+///
+/// MySQL materializes the result of the query on the server and only sends a chunk of rows
+/// when it is asked for one, instead of pushing every row to the client as soon as the query
+/// is executed.
+///
+/// Dropping the cursor before all rows are fetched closes the cursor before the next query
+/// is run on the connection.
+pub struct MySqlServerCursor<'c, 'q> {
+    conn: &'c mut MySqlConnection,
+    query: Option<(&'q str, MySqlArguments)>,
+    persistent: bool,
+    chunk_size: u32,
+
+    // the statement the cursor is open for, until its last row has been fetched
+    statement_id: Option<u32>,
+
+    column_names: Arc<HashMap<Box<str>, u16>>,
+    column_types: Vec<MySqlTypeInfo>,
+
+    // the rows of the current chunk and the index of the next row to return
+    rows: Vec<Vec<u8>>,
+    index: usize,
+}
+
+impl<'q> Query<'q, MySql> {
+    /// Execute the query using a server-side cursor, fetching `chunk_size` rows at a time.
+    ///
+    /// This lets a result set of any size be iterated over without the client
+    /// materializing all of it, or the server pushing rows faster than they are read.
+    ///
+    /// ```rust,ignore
+    /// let mut cursor = sqlx::query("SELECT * FROM events").fetch_cursor(&mut conn, 1000);
+    ///
+    /// while let Some(row) = cursor.next().await? {
+    ///     // ...
+    /// }
+    /// ```
+    ///
+    /// MySQL only opens a cursor for a `SELECT`; the rows of any other statement are
+    /// received all at once.
+    pub fn fetch_cursor<'c>(
+        self,
+        conn: &'c mut MySqlConnection,
+        chunk_size: u32,
+    ) -> MySqlServerCursor<'c, 'q> {
+        MySqlServerCursor::new(conn, self, chunk_size)
+    }
+}
+
+impl<'q, O> QueryAs<'q, MySql, O>
+where
+    O: Send + Unpin + for<'r> FromRow<'r, MySqlRow<'r>>,
+{
+    /// Execute the query using a server-side cursor, fetching `chunk_size` rows at a time.
+    ///
+    /// See [`Query::fetch_cursor`].
+    pub fn fetch_cursor<'c>(
+        self,
+        conn: &'c mut MySqlConnection,
+        chunk_size: u32,
+    ) -> BoxStream<'c, crate::Result<O>>
+    where
+        'q: 'c,
+        O: 'c,
+    {
+        let mut cursor = MySqlServerCursor::new(conn, self, chunk_size);
+
+        Box::pin(async_stream::try_stream! {
+            while let Some(row) = cursor.next().await? {
+                let obj = O::from_row(&row)?;
+
+                yield obj;
+            }
+        })
+    }
+}
+
+impl<'c, 'q> MySqlServerCursor<'c, 'q> {
+    fn new<E>(conn: &'c mut MySqlConnection, query: E, chunk_size: u32) -> Self
+    where
+        E: Execute<'q, MySql>,
+    {
+        let persistent = query.persistent();
+        let (query, arguments) = query.into_parts();
+
+        Self {
+            conn,
+            query: Some((query, arguments.unwrap_or_default())),
+            persistent,
+            chunk_size: chunk_size.max(1),
+            statement_id: None,
+            column_names: Arc::default(),
+            column_types: Vec::new(),
+            rows: Vec::new(),
+            index: 0,
+        }
+    }
+
+    /// Fetch the next row, returning `None` once all rows have been fetched.
+    pub async fn next(&mut self) -> crate::Result<Option<MySqlRow<'_>>> {
+        if let Err(error) = self.advance().await {
+            // read the remainder of the failed response so the connection is idle
+            self.conn.stream.wait_until_ready().await?;
+
+            return Err(error);
+        }
+
+        if self.index == self.rows.len() {
+            return Ok(None);
+        }
+
+        let row = Row::read(
+            &self.rows[self.index],
+            &self.column_types,
+            &mut self.conn.current_row_values,
+            true,
+        )?;
+
+        self.index += 1;
+
+        Ok(Some(MySqlRow {
+            row,
+            names: Arc::clone(&self.column_names),
+            zero_dates: self.conn.zero_dates,
+            local_timestamps: !self.conn.time_zone.is_utc(),
+        }))
+    }
+
+    // Ensure that there is a row to return in our buffer, unless the cursor is exhausted
+    async fn advance(&mut self) -> crate::Result<()> {
+        if let Some((query, arguments)) = self.query.take() {
+            self.open(query, arguments).await?;
+        }
+
+        if self.index < self.rows.len() {
+            return Ok(());
+        }
+
+        if self.statement_id.is_some() {
+            self.fetch().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn open(&mut self, query: &str, arguments: MySqlArguments) -> crate::Result<()> {
+        let statement_id = self
+            .conn
+            .run_with_cursor(query, Some(arguments), self.persistent, Cursor::READ_ONLY)
+            .await?
+            .expect("a prepared statement is executed with arguments");
+
+        let stream = &mut self.conn.stream;
+
+        match stream.receive().await?[0] {
+            // a statement without a result set
+            0x00 => {
+                stream.handle_ok()?;
+                return Ok(());
+            }
+
+            0xFF => {
+                return stream.handle_err();
+            }
+
+            _ => {}
+        }
+
+        let cc = ColumnCount::read(stream.packet())?;
+        let mut column_names = HashMap::with_capacity(cc.columns as usize);
+
+        for i in 0..cc.columns {
+            let column = ColumnDefinition::read(stream.receive().await?)?;
+
+            self.column_types
+                .push(MySqlTypeInfo::from_nullable_column_def(&column));
+
+            if let Some(name) = column.name() {
+                column_names.insert(name.to_owned().into_boxed_str(), i as u16);
+            }
+        }
+
+        self.column_names = Arc::new(column_names);
+
+        // the column definitions of a cursor end with an EOF packet, regardless of
+        // DEPRECATE_EOF, that tells us whether a cursor was opened
+        let packet_id = stream.receive().await?[0];
+
+        if packet_id == 0xFE {
+            let status = read_status(stream)?;
+
+            if status.contains(Status::SERVER_STATUS_CURSOR_EXISTS) {
+                self.statement_id = Some(statement_id);
+                return Ok(());
+            }
+
+            if stream.capabilities.contains(Capabilities::DEPRECATE_EOF) {
+                // the (empty) result set has ended
+                return Ok(());
+            }
+
+            // the rows follow the EOF packet that ends the column definitions
+            stream.is_ready = false;
+        } else {
+            // the rows follow the column definitions
+            self.rows.push(stream.packet().to_vec());
+        }
+
+        // no cursor was opened; the server sends all rows
+        self.read_rows().await?;
+
+        Ok(())
+    }
+
+    // Fetch the next chunk of rows into our buffer
+    async fn fetch(&mut self) -> crate::Result<()> {
+        let statement_id = self.statement_id.unwrap();
+
+        self.conn.stream.is_ready = false;
+        self.conn
+            .stream
+            .send(
+                ComStmtFetch {
+                    statement_id,
+                    rows: self.chunk_size,
+                },
+                true,
+            )
+            .await?;
+
+        self.rows.clear();
+        self.index = 0;
+
+        let status = self.read_rows().await?;
+
+        if status.contains(Status::SERVER_STATUS_LAST_ROW_SENT)
+            || !status.contains(Status::SERVER_STATUS_CURSOR_EXISTS)
+        {
+            // the server closes the cursor once its last row is sent
+            self.statement_id = None;
+        }
+
+        Ok(())
+    }
+
+    // Read rows into our buffer until the EOF packet that ends them
+    async fn read_rows(&mut self) -> crate::Result<Status> {
+        let stream = &mut self.conn.stream;
+
+        loop {
+            let packet_id = stream.receive().await?[0];
+
+            match packet_id {
+                0xFE if stream.packet().len() < 0xFF_FF_FF => {
+                    return read_status(stream);
+                }
+
+                0xFF => {
+                    return stream.handle_err();
+                }
+
+                0x00 => {
+                    self.rows.push(stream.packet().to_vec());
+                }
+
+                _ => {
+                    return stream.handle_unexpected();
+                }
+            }
+        }
+    }
+}
+
+// The status of an EOF packet; with DEPRECATE_EOF, a result set (but not the column
+// definitions of a cursor) ends with an OK packet instead
+fn read_status(stream: &mut MySqlStream) -> crate::Result<Status> {
+    if stream.packet().len() == 5 {
+        let eof = EofPacket::read(stream.packet())?;
+        stream.is_ready = !eof.status.contains(Status::SERVER_MORE_RESULTS_EXISTS);
+
+        Ok(eof.status)
+    } else {
+        Ok(stream.handle_ok()?.status)
+    }
+}
+
+impl Drop for MySqlServerCursor<'_, '_> {
+    fn drop(&mut self) {
+        if let Some(statement_id) = self.statement_id {
+            // the connection is always idle between chunks; the cursor is closed by resetting
+            // its statement before the next query is run
+            self.conn.reset_statement = Some(statement_id);
+        }
+    }
+}
@@ -0,0 +1,61 @@
+// The version of the server, from the handshake, e.g. `8.0.18` for MySQL or
+// `5.5.5-10.4.7-MariaDB-1:10.4.7+maria~bionic` for MariaDB (which prefixes its version with
+// `5.5.5-` for the replication protocol of older versions of MySQL)
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ServerVersion {
+    pub(crate) mariadb: bool,
+    pub(crate) major: u32,
+    pub(crate) minor: u32,
+}
+
+impl ServerVersion {
+    pub(crate) fn parse(version: &str) -> Self {
+        let mariadb = version.contains("MariaDB");
+        let version = if mariadb && version.starts_with("5.5.5-") {
+            &version[6..]
+        } else {
+            version
+        };
+
+        let mut numbers = version
+            .split(|c: char| !c.is_ascii_digit())
+            .map(|n| n.parse().unwrap_or(0));
+
+        ServerVersion {
+            mariadb,
+            major: numbers.next().unwrap_or(0),
+            minor: numbers.next().unwrap_or(0),
+        }
+    }
+
+    // `INSERT`, `REPLACE` and `DELETE` can have a `RETURNING` clause in MariaDB 10.5+
+    pub(crate) fn supports_returning(&self) -> bool {
+        self.mariadb && (self.major, self.minor) >= (10, 5)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ServerVersion;
+
+    #[test]
+    fn it_parses_server_versions() {
+        let mysql = ServerVersion::parse("8.0.18");
+
+        assert!(!mysql.mariadb);
+        assert_eq!((mysql.major, mysql.minor), (8, 0));
+        assert!(!mysql.supports_returning());
+
+        let mariadb = ServerVersion::parse("5.5.5-10.4.7-MariaDB-1:10.4.7+maria~bionic");
+
+        assert!(mariadb.mariadb);
+        assert_eq!((mariadb.major, mariadb.minor), (10, 4));
+        assert!(!mariadb.supports_returning());
+
+        assert!(ServerVersion::parse("10.5.8-MariaDB").supports_returning());
+        assert!(
+            ServerVersion::parse("5.5.5-11.0.2-MariaDB-1:11.0.2+maria~ubu2204")
+                .supports_returning()
+        );
+    }
+}
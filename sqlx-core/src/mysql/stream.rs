@@ -3,7 +3,10 @@ use std::net::Shutdown;
 use byteorder::{ByteOrder, LittleEndian};
 
 use crate::io::{Buf, BufMut, BufStream, MaybeTlsStream};
-use crate::mysql::protocol::{Capabilities, Encode, EofPacket, ErrPacket, OkPacket};
+use crate::mysql::compression::Compression;
+use crate::mysql::protocol::{
+    Capabilities, ColumnCount, Encode, EofPacket, ErrPacket, OkPacket, Status,
+};
 
 use crate::mysql::MySqlError;
 use crate::url::Url;
@@ -18,6 +21,10 @@ pub(crate) struct MySqlStream {
     // Put another way, are we still expecting an EOF or OK packet to terminate
     pub(super) is_ready: bool,
 
+    // Does the next packet start a result, as the last packet ended the previous result of
+    // a query with more results
+    result_start: bool,
+
     // Active capabilities
     pub(super) capabilities: Capabilities,
 
@@ -30,6 +37,15 @@ pub(crate) struct MySqlStream {
     // decoding
     packet_buf: Vec<u8>,
     packet_len: usize,
+
+    // Once authenticated, packets are sent and received in compressed packets, which
+    // have a sequence number of their own
+    pub(super) compression: Option<Compression>,
+    compressed_seq_no: u8,
+
+    // Packets decompressed from the compressed packets received, and how far they were read
+    inflated: Vec<u8>,
+    inflated_pos: usize,
 }
 
 impl MySqlStream {
@@ -47,7 +63,10 @@ impl MySqlStream {
             | Capabilities::PLUGIN_AUTH_LENENC_DATA
             | Capabilities::MULTI_STATEMENTS
             | Capabilities::MULTI_RESULTS
-            | Capabilities::PLUGIN_AUTH;
+            | Capabilities::PS_MULTI_RESULTS
+            | Capabilities::PLUGIN_AUTH
+            | Capabilities::CONNECT_ATTRS
+            | Capabilities::QUERY_ATTRIBUTES;
 
         if url.database().is_some() {
             capabilities |= Capabilities::CONNECT_WITH_DB;
@@ -57,6 +76,15 @@ impl MySqlStream {
             capabilities |= Capabilities::SSL;
         }
 
+        match url.param("multi-statements").as_deref() {
+            Some("false") => capabilities.remove(Capabilities::MULTI_STATEMENTS),
+            Some("true") | None => {}
+
+            Some(value) => {
+                return Err(protocol_err!("unknown `multi-statements` value: {:?}", value).into());
+            }
+        }
+
         Ok(Self {
             capabilities,
             stream: BufStream::new(stream),
@@ -64,6 +92,11 @@ impl MySqlStream {
             packet_len: 0,
             seq_no: 0,
             is_ready: true,
+            result_start: false,
+            compression: None,
+            compressed_seq_no: 0,
+            inflated: Vec::new(),
+            inflated_pos: 0,
         })
     }
 
@@ -82,6 +115,7 @@ impl MySqlStream {
     {
         if initial {
             self.seq_no = 0;
+            self.compressed_seq_no = 0;
         }
 
         self.write(packet);
@@ -90,6 +124,13 @@ impl MySqlStream {
 
     #[inline]
     pub(super) async fn flush(&mut self) -> crate::Result<()> {
+        if let Some(compression) = self.compression {
+            let packets = std::mem::take(self.stream.buffer_mut());
+
+            self.compressed_seq_no =
+                compression.compress(&packets, self.compressed_seq_no, self.stream.buffer_mut());
+        }
+
         Ok(self.stream.flush().await?)
     }
 
@@ -130,9 +171,14 @@ impl MySqlStream {
     }
 
     pub(super) async fn read(&mut self) -> crate::Result<()> {
+        self.result_start = false;
         self.packet_buf.clear();
         self.packet_len = 0;
 
+        if let Some(compression) = self.compression {
+            return self.read_compressed(compression).await;
+        }
+
         // Read the packet header which contains the length and the sequence number
         // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_basic_packets.html
         // https://mariadb.com/kb/en/library/0-packet/#standard-packet
@@ -154,12 +200,58 @@ impl MySqlStream {
 
         self.stream.consume(self.packet_len);
 
-        // TODO: Implement packet compression
         // TODO: Implement packet joining
 
         Ok(())
     }
 
+    // Read a packet out of the compressed packets, which may hold several packets or
+    // only part of one
+    // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_basic_compression_packet.html
+    async fn read_compressed(&mut self, compression: Compression) -> crate::Result<()> {
+        self.fill_inflated(compression, 4).await?;
+
+        let mut header = &self.inflated[self.inflated_pos..];
+
+        self.packet_len = header.get_uint::<LittleEndian>(3)? as usize;
+        self.seq_no = header.get_u8()?.wrapping_add(1);
+        self.inflated_pos += 4;
+
+        self.fill_inflated(compression, self.packet_len).await?;
+
+        let end = self.inflated_pos + self.packet_len;
+
+        self.packet_buf
+            .extend_from_slice(&self.inflated[self.inflated_pos..end]);
+        self.inflated_pos = end;
+
+        Ok(())
+    }
+
+    // Receive compressed packets until at least `cnt` bytes of packets are decompressed
+    async fn fill_inflated(&mut self, compression: Compression, cnt: usize) -> crate::Result<()> {
+        while self.inflated.len() - self.inflated_pos < cnt {
+            self.inflated.drain(..self.inflated_pos);
+            self.inflated_pos = 0;
+
+            let mut header = self.stream.peek(7_usize).await?;
+
+            let len = header.get_uint::<LittleEndian>(3)? as usize;
+            self.compressed_seq_no = header.get_u8()?.wrapping_add(1);
+            let uncompressed_len = header.get_uint::<LittleEndian>(3)? as usize;
+
+            self.stream.consume(7);
+
+            let payload = self.stream.peek(len).await?;
+
+            compression.decompress(payload, uncompressed_len, &mut self.inflated)?;
+
+            self.stream.consume(len);
+        }
+
+        Ok(())
+    }
+
     /// Returns a reference to the most recently received packet data.
     /// A call to `read` invalidates this buffer.
     #[inline]
@@ -177,9 +269,16 @@ impl MySqlStream {
         Ok(())
     }
 
+    // The stream is ready once the last result of a query ends; a query may return more than
+    // one result, e.g. a `CALL` returns a result for each statement of the procedure and for
+    // its OUT parameters
     pub(crate) fn maybe_handle_eof(&mut self) -> crate::Result<Option<EofPacket>> {
         if !self.capabilities.contains(Capabilities::DEPRECATE_EOF) && self.packet()[0] == 0xFE {
-            Ok(Some(EofPacket::read(self.packet())?))
+            let eof = EofPacket::read(self.packet())?;
+            self.is_ready = !eof.status.contains(Status::SERVER_MORE_RESULTS_EXISTS);
+            self.result_start = !self.is_ready;
+
+            Ok(Some(eof))
         } else {
             Ok(None)
         }
@@ -195,34 +294,73 @@ impl MySqlStream {
     }
 
     pub(crate) fn handle_ok(&mut self) -> crate::Result<OkPacket> {
-        self.is_ready = true;
-        OkPacket::read(self.packet())
+        let ok = OkPacket::read(self.packet())?;
+        self.is_ready = !ok.status.contains(Status::SERVER_MORE_RESULTS_EXISTS);
+        self.result_start = !self.is_ready;
+
+        Ok(ok)
     }
 
-    pub(crate) async fn wait_until_ready(&mut self) -> crate::Result<()> {
-        if !self.is_ready {
-            loop {
-                let packet_id = self.receive().await?[0];
-                match packet_id {
-                    0xFE if self.packet().len() < 0xFF_FF_FF => {
-                        // OK or EOF packet
-                        self.is_ready = true;
-                        break;
-                    }
+    // Read the remaining results of the last query and return the number of rows affected
+    //
+    // If `initial`, the next packet starts a result; otherwise the results are read from the
+    // middle of a result set (e.g. after a cursor was dropped)
+    pub(crate) async fn skip_results(&mut self, mut initial: bool) -> crate::Result<u64> {
+        let mut rows = 0;
+
+        while !self.is_ready {
+            let packet_id = self.receive().await?[0];
+
+            match packet_id {
+                // OK or EOF packet; a row of the binary protocol starts with 0x00 and a row
+                // of the text protocol can start with 0xFE (for a field length > 0xFFFFFF)
+                0x00 | 0xFE
+                    if self.packet().len() < 0xFF_FF_FF && (packet_id != 0x00 || initial) =>
+                {
+                    let status = if let Some(eof) = self.maybe_handle_eof()? {
+                        eof.status
+                    } else {
+                        let ok = self.handle_ok()?;
+
+                        rows += ok.affected_rows;
+                        ok.status
+                    };
+
+                    initial = status.contains(Status::SERVER_MORE_RESULTS_EXISTS);
+                }
+
+                0xFF => {
+                    return self.handle_err();
+                }
+
+                _ if initial => {
+                    // a result set; skip its column definitions, so that the
+                    // packets that follow are rows
+                    let cc = ColumnCount::read(self.packet())?;
 
-                    0xFF => {
-                        // ERR packet
-                        self.is_ready = true;
-                        return self.handle_err();
+                    for _ in 0..cc.columns {
+                        self.receive().await?;
                     }
 
-                    _ => {
-                        // Something else; skip
+                    if cc.columns > 0 {
+                        self.maybe_receive_eof().await?;
                     }
+
+                    initial = false;
+                }
+
+                _ => {
+                    // a row; skip
                 }
             }
         }
 
+        Ok(rows)
+    }
+
+    pub(crate) async fn wait_until_ready(&mut self) -> crate::Result<()> {
+        self.skip_results(self.result_start).await?;
+
         Ok(())
     }
 }
@@ -0,0 +1,144 @@
+use crate::mysql::protocol::TypeId;
+use crate::mysql::{MySqlArguments, MySqlValue};
+use crate::url::Url;
+use crate::value::RawValue;
+
+// The session time zone, from the `timezone` parameter
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum TimeZone {
+    // TIMESTAMP values are sent and received in UTC, as `DateTime<Utc>` or `OffsetDateTime`
+    Utc,
+
+    // A named time zone (e.g. `Europe/Berlin`), an offset (e.g. `+02:00`) or `SYSTEM`;
+    // TIMESTAMP values are local times of the zone, as `NaiveDateTime` or `PrimitiveDateTime`
+    Local(String),
+}
+
+impl TimeZone {
+    pub(crate) fn from_url(url: &Url) -> crate::Result<Self> {
+        match url.param("timezone").as_deref() {
+            None | Some("UTC") | Some("+00:00") => Ok(TimeZone::Utc),
+
+            // the time zone is interpolated into `SET time_zone`
+            Some(value)
+                if !value.is_empty()
+                    && value
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || "+-:/_".contains(c)) =>
+            {
+                Ok(TimeZone::Local(value.to_owned()))
+            }
+
+            Some(value) => Err(protocol_err!("invalid `timezone` value: {:?}", value).into()),
+        }
+    }
+
+    pub(crate) fn is_utc(&self) -> bool {
+        *self == TimeZone::Utc
+    }
+
+    pub(crate) fn set_time_zone(&self) -> String {
+        match self {
+            TimeZone::Utc => "time_zone = '+00:00'".to_owned(),
+            TimeZone::Local(zone) => format!("time_zone = '{}'", zone),
+        }
+    }
+
+    // Binding a `DateTime<Utc>` or `OffsetDateTime` (the types of TIMESTAMP) is an error
+    // when the server would read it as a local time of another time zone
+    pub(crate) fn check_arguments(&self, arguments: &MySqlArguments) -> crate::Result<()> {
+        if let TimeZone::Local(zone) = self {
+            for (index, ty) in arguments.param_types.iter().enumerate() {
+                let is_null = arguments.null_bitmap[index / 8] & (1 << (index % 8)) != 0;
+
+                if ty.id == TypeId::TIMESTAMP && !is_null {
+                    return Err(protocol_err!(
+                        "a UTC timestamp can not be bound in the session time zone {:?}; bind \
+                         a `NaiveDateTime` or `PrimitiveDateTime` in that time zone instead, or \
+                         connect with `timezone=UTC`",
+                        zone
+                    )
+                    .into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// The error when decoding a TIMESTAMP of a session that is not in UTC into a UTC type
+pub(crate) fn reject_local(value: &MySqlValue<'_>) -> crate::Result<()> {
+    let is_timestamp = matches!(value.type_info(), Some(ty) if ty.id == TypeId::TIMESTAMP);
+
+    if is_timestamp && value.local_timestamps {
+        return Err(decode_err!(
+            "TIMESTAMP is a local time of the session time zone (the `timezone` connection \
+             parameter); decode it as `NaiveDateTime` or `PrimitiveDateTime` instead, or \
+             connect with `timezone=UTC`"
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::TimeZone;
+    use crate::arguments::Arguments;
+    use crate::mysql::MySqlArguments;
+    use crate::url::Url;
+
+    fn time_zone(url: &str) -> crate::Result<TimeZone> {
+        TimeZone::from_url(&Url::try_from(url).unwrap())
+    }
+
+    #[test]
+    fn it_reads_time_zones() {
+        assert_eq!(time_zone("mysql://localhost/db").unwrap(), TimeZone::Utc);
+        assert_eq!(
+            time_zone("mysql://localhost/db?timezone=UTC").unwrap(),
+            TimeZone::Utc
+        );
+
+        let berlin = time_zone("mysql://localhost/db?timezone=Europe%2FBerlin").unwrap();
+
+        assert_eq!(berlin, TimeZone::Local("Europe/Berlin".to_owned()));
+        assert_eq!(berlin.set_time_zone(), "time_zone = 'Europe/Berlin'");
+
+        assert_eq!(
+            time_zone("mysql://localhost/db?timezone=%2B05:30")
+                .unwrap()
+                .set_time_zone(),
+            "time_zone = '+05:30'"
+        );
+
+        assert!(time_zone("mysql://localhost/db?timezone=UTC'%3B").is_err());
+    }
+
+    #[test]
+    fn it_rejects_utc_arguments_in_local_time_zones() {
+        let local = TimeZone::Local("SYSTEM".to_owned());
+
+        let mut arguments = MySqlArguments::default();
+        arguments.add(1_i32);
+        arguments.add(Option::<String>::None);
+
+        assert!(local.check_arguments(&arguments).is_ok());
+
+        #[cfg(feature = "chrono")]
+        {
+            let mut arguments = MySqlArguments::default();
+            arguments.add(Option::<chrono::DateTime<chrono::Utc>>::None);
+
+            assert!(local.check_arguments(&arguments).is_ok());
+
+            arguments.add(chrono::Utc::now());
+
+            assert!(local.check_arguments(&arguments).is_err());
+            assert!(TimeZone::Utc.check_arguments(&arguments).is_ok());
+        }
+    }
+}
@@ -9,23 +9,22 @@ pub(super) async fn upgrade_if_needed(stream: &mut MySqlStream, url: &Url) -> cr
     let ca_file = url.param("ssl-ca");
     let ssl_mode = url.param("ssl-mode");
 
+    // As with `mysql`, `ssl-ca` without an `ssl-mode` verifies the certificate of the server
+    let ssl_mode = match ssl_mode.as_deref() {
+        None if ca_file.is_some() => Some("VERIFY_CA"),
+        ssl_mode => ssl_mode,
+    };
+
     // https://dev.mysql.com/doc/refman/5.7/en/connection-options.html#option_general_ssl-mode
-    match ssl_mode.as_deref() {
+    match ssl_mode {
         Some("DISABLED") => {}
 
         #[cfg(feature = "tls")]
         Some("PREFERRED") | None if !stream.capabilities.contains(Capabilities::SSL) => {}
 
         #[cfg(feature = "tls")]
-        Some("PREFERRED") => {
-            if let Err(_error) = try_upgrade(stream, &url, None, true).await {
-                // TLS upgrade failed; fall back to a normal connection
-            }
-        }
-
-        #[cfg(feature = "tls")]
-        None => {
-            if let Err(_error) = try_upgrade(stream, &url, ca_file.as_deref(), true).await {
+        Some("PREFERRED") | None => {
+            if let Err(_error) = try_upgrade(stream, url, None, true).await {
                 // TLS upgrade failed; fall back to a normal connection
             }
         }
@@ -49,7 +48,7 @@ pub(super) async fn upgrade_if_needed(stream: &mut MySqlStream, url: &Url) -> cr
             try_upgrade(
                 stream,
                 url,
-                // false for both verify-ca and verify-full
+                // `REQUIRED` only verifies the certificate of the server if `ssl-ca` is set
                 ca_file.as_deref(),
                 // false for only verify-full
                 mode != "VERIFY_IDENTITY",
@@ -90,26 +89,52 @@ async fn try_upgrade(
     ca_file: Option<&str>,
     accept_invalid_hostnames: bool,
 ) -> crate::Result<()> {
+    use crate::mysql::charset::Charset;
     use crate::mysql::protocol::SslRequest;
     use crate::runtime::fs;
 
-    use async_native_tls::{Certificate, TlsConnector};
+    use async_native_tls::{Certificate, Identity, TlsConnector};
 
     let mut connector = TlsConnector::new()
         .danger_accept_invalid_certs(ca_file.is_none())
         .danger_accept_invalid_hostnames(accept_invalid_hostnames);
 
     if let Some(ca_file) = ca_file {
-        let root_cert = fs::read(ca_file).await?;
+        let bundle = fs::read(ca_file).await?;
+        let certificates = pem_certificates(&bundle);
 
-        connector = connector.add_root_certificate(Certificate::from_pem(&root_cert)?);
+        if certificates.is_empty() {
+            return Err(
+                tls_err!("no PEM certificates found in `ssl-ca` file {:?}", ca_file).into(),
+            );
+        }
+
+        for certificate in certificates {
+            connector = connector.add_root_certificate(Certificate::from_pem(certificate)?);
+        }
+    }
+
+    // the client certificate, for a user created with `REQUIRE X509` or `REQUIRE SUBJECT`
+    match (url.param("ssl-cert"), url.param("ssl-key")) {
+        (Some(cert_file), Some(key_file)) => {
+            let cert = fs::read(&*cert_file).await?;
+            let key = fs::read(&*key_file).await?;
+
+            connector = connector.identity(Identity::from_pkcs8(&cert, &key)?);
+        }
+
+        (None, None) => {}
+
+        _ => {
+            return Err(tls_err!("`ssl-cert` and `ssl-key` must be set together").into());
+        }
     }
 
     // send upgrade request and then immediately try TLS handshake
     stream
         .send(
             SslRequest {
-                client_collation: super::connection::COLLATE_UTF8MB4_UNICODE_CI,
+                client_collation: Charset::from_url(url)?.collation_id(),
                 max_packet_size: super::connection::MAX_PACKET_SIZE,
             },
             false,
@@ -121,3 +146,59 @@ async fn try_upgrade(
         .upgrade(url.host().unwrap_or("localhost"), connector)
         .await
 }
+
+// The certificates of a PEM bundle, which may hold a chain of intermediate and root
+// certificates
+#[cfg(feature = "tls")]
+fn pem_certificates(bundle: &[u8]) -> Vec<&[u8]> {
+    const BEGIN: &[u8] = b"-----BEGIN CERTIFICATE-----";
+    const END: &[u8] = b"-----END CERTIFICATE-----";
+
+    let find = |haystack: &[u8], needle: &[u8]| {
+        haystack
+            .windows(needle.len())
+            .position(|window| window == needle)
+    };
+
+    let mut certificates = Vec::new();
+    let mut rest = bundle;
+
+    while let Some(start) = find(rest, BEGIN) {
+        let end = match find(&rest[start..], END) {
+            Some(end) => start + end + END.len(),
+            None => break,
+        };
+
+        certificates.push(&rest[start..end]);
+        rest = &rest[end..];
+    }
+
+    certificates
+}
+
+#[cfg(all(test, feature = "tls"))]
+mod tests {
+    use super::pem_certificates;
+
+    #[test]
+    fn it_splits_pem_bundles() {
+        let bundle = b"# intermediate\n\
+            -----BEGIN CERTIFICATE-----\nMIIB\n-----END CERTIFICATE-----\n\
+            # root\n\
+            -----BEGIN CERTIFICATE-----\nMIIC\n-----END CERTIFICATE-----\n";
+
+        assert_eq!(
+            pem_certificates(bundle),
+            vec![
+                &b"-----BEGIN CERTIFICATE-----\nMIIB\n-----END CERTIFICATE-----"[..],
+                &b"-----BEGIN CERTIFICATE-----\nMIIC\n-----END CERTIFICATE-----"[..],
+            ]
+        );
+
+        // a truncated certificate is ignored
+        assert_eq!(
+            pem_certificates(b"-----BEGIN CERTIFICATE-----\nMIIB\n").len(),
+            0
+        );
+    }
+}
@@ -42,8 +42,15 @@ impl MySqlTypeInfo {
     }
 
     pub(crate) fn from_nullable_column_def(def: &ColumnDefinition) -> Self {
+        // A SET column is sent as a CHAR with the SET flag
+        let id = if def.flags.contains(FieldFlags::SET) {
+            TypeId::SET
+        } else {
+            def.type_id
+        };
+
         Self {
-            id: def.type_id,
+            id,
             is_unsigned: def.flags.contains(FieldFlags::UNSIGNED),
             is_binary: def.flags.contains(FieldFlags::BINARY),
             char_set: def.char_set,
@@ -62,6 +69,7 @@ impl MySqlTypeInfo {
     pub fn type_feature_gate(&self) -> Option<&'static str> {
         match self.id {
             TypeId::DATE | TypeId::TIME | TypeId::DATETIME | TypeId::TIMESTAMP => Some("chrono"),
+            TypeId::GEOMETRY => Some("mysql-spatial"),
             _ => None,
         }
     }
@@ -74,14 +82,18 @@ impl Display for MySqlTypeInfo {
 
             TypeId::TINY_INT if self.is_unsigned => f.write_str("TINYINT UNSIGNED"),
             TypeId::SMALL_INT if self.is_unsigned => f.write_str("SMALLINT UNSIGNED"),
+            TypeId::MEDIUM_INT if self.is_unsigned => f.write_str("MEDIUMINT UNSIGNED"),
             TypeId::INT if self.is_unsigned => f.write_str("INT UNSIGNED"),
             TypeId::BIG_INT if self.is_unsigned => f.write_str("BIGINT UNSIGNED"),
 
             TypeId::TINY_INT => f.write_str("TINYINT"),
             TypeId::SMALL_INT => f.write_str("SMALLINT"),
+            TypeId::MEDIUM_INT => f.write_str("MEDIUMINT"),
             TypeId::INT => f.write_str("INT"),
             TypeId::BIG_INT => f.write_str("BIGINT"),
 
+            TypeId::BIT => f.write_str("BIT"),
+
             TypeId::FLOAT => f.write_str("FLOAT"),
             TypeId::DOUBLE => f.write_str("DOUBLE"),
 
@@ -97,6 +109,11 @@ impl Display for MySqlTypeInfo {
             TypeId::TIME => f.write_str("TIME"),
             TypeId::DATETIME => f.write_str("DATETIME"),
             TypeId::TIMESTAMP => f.write_str("TIMESTAMP"),
+            TypeId::YEAR => f.write_str("YEAR"),
+
+            TypeId::SET => f.write_str("SET"),
+
+            TypeId::GEOMETRY => f.write_str("GEOMETRY"),
 
             id => write!(f, "<{:#x}>", id.0),
         }
@@ -132,12 +149,33 @@ impl PartialEq<MySqlTypeInfo> for MySqlTypeInfo {
             _ => {}
         }
 
+        match (self.id, other.id) {
+            // BIT is decoded as BIGINT UNSIGNED
+            (TypeId::BIT, TypeId::BIG_INT) => return other.is_unsigned,
+            (TypeId::BIG_INT, TypeId::BIT) => return self.is_unsigned,
+
+            // YEAR is decoded as SMALLINT UNSIGNED
+            (TypeId::YEAR, TypeId::SMALL_INT) => return other.is_unsigned,
+            (TypeId::SMALL_INT, TypeId::YEAR) => return self.is_unsigned,
+
+            // MEDIUMINT is decoded as INT
+            (TypeId::MEDIUM_INT, TypeId::INT) | (TypeId::INT, TypeId::MEDIUM_INT) => {
+                return self.is_unsigned == other.is_unsigned;
+            }
+
+            _ => {}
+        }
+
         if self.id.0 != other.id.0 {
             return false;
         }
 
         match self.id {
-            TypeId::TINY_INT | TypeId::SMALL_INT | TypeId::INT | TypeId::BIG_INT => {
+            TypeId::TINY_INT
+            | TypeId::SMALL_INT
+            | TypeId::MEDIUM_INT
+            | TypeId::INT
+            | TypeId::BIG_INT => {
                 return self.is_unsigned == other.is_unsigned;
             }
 
@@ -161,12 +199,18 @@ impl TypeInfo for MySqlTypeInfo {
 
         match self.id {
             // All integer types should be considered compatible
-            TypeId::TINY_INT | TypeId::SMALL_INT | TypeId::INT | TypeId::BIG_INT
+            TypeId::TINY_INT
+            | TypeId::SMALL_INT
+            | TypeId::MEDIUM_INT
+            | TypeId::INT
+            | TypeId::BIG_INT
                 if (self.is_unsigned == other.is_unsigned)
                     && match other.id {
-                        TypeId::TINY_INT | TypeId::SMALL_INT | TypeId::INT | TypeId::BIG_INT => {
-                            true
-                        }
+                        TypeId::TINY_INT
+                        | TypeId::SMALL_INT
+                        | TypeId::MEDIUM_INT
+                        | TypeId::INT
+                        | TypeId::BIG_INT => true,
 
                         _ => false,
                     } =>
@@ -224,6 +268,49 @@ impl TypeInfo for MySqlTypeInfo {
                 true
             }
 
+            // A SET is a string of comma-separated members, so it is compatible with text
+            // types in both directions
+            TypeId::SET
+                if matches!(
+                    other.id,
+                    TypeId::VAR_CHAR
+                        | TypeId::TEXT
+                        | TypeId::CHAR
+                        | TypeId::TINY_BLOB
+                        | TypeId::MEDIUM_BLOB
+                        | TypeId::LONG_BLOB
+                ) =>
+            {
+                true
+            }
+
+            TypeId::VAR_CHAR
+            | TypeId::TEXT
+            | TypeId::CHAR
+            | TypeId::TINY_BLOB
+            | TypeId::MEDIUM_BLOB
+            | TypeId::LONG_BLOB
+                if other.id == TypeId::SET =>
+            {
+                true
+            }
+
+            // BIT can be read as the bytes of its value
+            TypeId::BIT
+                if match other.id {
+                    TypeId::VAR_CHAR
+                    | TypeId::TEXT
+                    | TypeId::CHAR
+                    | TypeId::TINY_BLOB
+                    | TypeId::MEDIUM_BLOB
+                    | TypeId::LONG_BLOB => other.is_binary,
+
+                    _ => false,
+                } =>
+            {
+                true
+            }
+
             // FLOAT is compatible with DOUBLE
             TypeId::FLOAT | TypeId::DOUBLE
                 if match other.id {
@@ -1,13 +1,12 @@
 use bigdecimal::BigDecimal;
+use num_bigint::{BigInt, BigUint, Sign};
 
 use crate::decode::Decode;
 use crate::encode::Encode;
-use crate::io::Buf;
 use crate::mysql::protocol::TypeId;
-use crate::mysql::{MySql, MySqlData, MySqlTypeInfo, MySqlValue};
+use crate::mysql::types::decimal_str::{read_decimal, write_decimal};
+use crate::mysql::{MySql, MySqlTypeInfo, MySqlValue};
 use crate::types::Type;
-use crate::Error;
-use std::str::FromStr;
 
 impl Type<MySql> for BigDecimal {
     fn type_info() -> MySqlTypeInfo {
@@ -17,37 +16,32 @@ impl Type<MySql> for BigDecimal {
 
 impl Encode<MySql> for BigDecimal {
     fn encode(&self, buf: &mut Vec<u8>) {
-        let size = Encode::<MySql>::size_hint(self) - 1;
-        assert!(size <= std::u8::MAX as usize, "Too large size");
-        buf.push(size as u8);
-        let s = self.to_string();
-        buf.extend_from_slice(s.as_bytes());
-    }
-
-    fn size_hint(&self) -> usize {
-        let s = self.to_string();
-        s.as_bytes().len() + 1
+        write_decimal(buf, self);
     }
 }
 
 impl Decode<'_, MySql> for BigDecimal {
     fn decode(value: MySqlValue) -> crate::Result<Self> {
-        match value.try_get()? {
-            MySqlData::Binary(mut binary) => {
-                let _len = binary.get_u8()?;
-                let s = std::str::from_utf8(binary).map_err(Error::decode)?;
-                Ok(BigDecimal::from_str(s).map_err(Error::decode)?)
-            }
-            MySqlData::Text(s) => {
-                let s = std::str::from_utf8(s).map_err(Error::decode)?;
-                Ok(BigDecimal::from_str(s).map_err(Error::decode)?)
-            }
-        }
+        let mut digits = Vec::with_capacity(65);
+        let (negative, scale) = read_decimal(value, |digit| {
+            digits.push(digit);
+            Ok(())
+        })?;
+
+        let sign = if negative { Sign::Minus } else { Sign::Plus };
+        let digits = BigUint::from_radix_be(&digits, 10).unwrap();
+
+        Ok(BigDecimal::new(
+            BigInt::from_biguint(sign, digits),
+            scale as i64,
+        ))
     }
 }
 
 #[test]
 fn test_encode_decimal() {
+    use std::str::FromStr;
+
     let v: BigDecimal = BigDecimal::from_str("-1.05").unwrap();
     let mut buf: Vec<u8> = vec![];
     <BigDecimal as Encode<MySql>>::encode(&v, &mut buf);
@@ -89,4 +83,13 @@ fn test_decode_decimal() {
     ))
     .unwrap();
     assert_eq!(v.to_string(), "-90000");
+
+    // the 65 digits of the widest DECIMAL
+    let digits = "9".repeat(35) + "." + &"9".repeat(30);
+    let v = <BigDecimal as Decode<'_, MySql>>::decode(MySqlValue::text(
+        MySqlTypeInfo::new(TypeId::NEWDECIMAL),
+        digits.as_bytes(),
+    ))
+    .unwrap();
+    assert_eq!(v.to_string(), digits);
 }
@@ -8,6 +8,7 @@ use crate::encode::Encode;
 use crate::io::{Buf, BufMut};
 use crate::mysql::protocol::TypeId;
 use crate::mysql::type_info::MySqlTypeInfo;
+use crate::mysql::{time_zone, zero_dates};
 use crate::mysql::{MySql, MySqlData, MySqlValue};
 use crate::types::Type;
 use crate::Error;
@@ -27,6 +28,8 @@ impl Encode<MySql> for DateTime<Utc> {
 
 impl<'de> Decode<'de, MySql> for DateTime<Utc> {
     fn decode(value: MySqlValue<'de>) -> crate::Result<Self> {
+        time_zone::reject_local(&value)?;
+
         let naive: NaiveDateTime = Decode::<MySql>::decode(value)?;
 
         Ok(DateTime::from_utc(naive, Utc))
@@ -111,7 +114,10 @@ impl Encode<MySql> for NaiveDate {
 
 impl<'de> Decode<'de, MySql> for NaiveDate {
     fn decode(buf: MySqlValue<'de>) -> crate::Result<Self> {
-        match buf.try_get()? {
+        let data = buf.try_get()?;
+        zero_dates::reject(data)?;
+
+        match data {
             MySqlData::Binary(buf) => Ok(decode_date(&buf[1..])),
 
             MySqlData::Text(buf) => {
@@ -164,7 +170,10 @@ impl Encode<MySql> for NaiveDateTime {
 
 impl<'de> Decode<'de, MySql> for NaiveDateTime {
     fn decode(buf: MySqlValue<'de>) -> crate::Result<Self> {
-        match buf.try_get()? {
+        let data = buf.try_get()?;
+        zero_dates::reject(data)?;
+
+        match data {
             MySqlData::Binary(buf) => {
                 let len = buf[0];
                 let date = decode_date(&buf[1..]);
@@ -300,3 +309,18 @@ fn test_decode_date() {
             .unwrap();
     assert_eq!(date.to_string(), "2010-10-17");
 }
+
+#[test]
+fn test_decode_zero_date() {
+    let date = <NaiveDate as Decode<MySql>>::decode(MySqlValue::binary(
+        MySqlTypeInfo::new(TypeId::DATE),
+        &[0],
+    ));
+    assert!(date.is_err());
+
+    let date_time = <NaiveDateTime as Decode<MySql>>::decode(MySqlValue::text(
+        MySqlTypeInfo::new(TypeId::DATETIME),
+        b"0000-00-00 00:00:00",
+    ));
+    assert!(date_time.is_err());
+}
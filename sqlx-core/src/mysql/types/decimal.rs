@@ -0,0 +1,117 @@
+use rust_decimal::Decimal;
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::mysql::protocol::TypeId;
+use crate::mysql::types::decimal_str::{read_decimal, write_decimal};
+use crate::mysql::{MySql, MySqlTypeInfo, MySqlValue};
+use crate::types::Type;
+
+// The largest mantissa of a `Decimal`, 2^96 - 1
+const MAX_MANTISSA: u128 = (1 << 96) - 1;
+
+// The largest scale of a `Decimal`
+const MAX_SCALE: u32 = 28;
+
+impl Type<MySql> for Decimal {
+    fn type_info() -> MySqlTypeInfo {
+        MySqlTypeInfo::new(TypeId::NEWDECIMAL)
+    }
+}
+
+impl Encode<MySql> for Decimal {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        write_decimal(buf, self);
+    }
+}
+
+impl Decode<'_, MySql> for Decimal {
+    fn decode(value: MySqlValue) -> crate::Result<Self> {
+        // the digits are accumulated in a u128 and trimmed to fit a `Decimal` afterwards; a
+        // DECIMAL of up to 38 digits fits
+        let mut mantissa: u128 = 0;
+        let (negative, mut scale) = read_decimal(value, |digit| {
+            mantissa = mantissa
+                .checked_mul(10)
+                .and_then(|mantissa| mantissa.checked_add(digit as u128))
+                .ok_or_else(|| decode_err!("DECIMAL is out of range for `Decimal`"))?;
+
+            Ok(())
+        })?;
+
+        // trailing zeros of the fraction (e.g. of DECIMAL(65, 30)) do not change the value
+        while scale > MAX_SCALE {
+            let (quotient, remainder) = (mantissa / 10, mantissa % 10);
+
+            if remainder != 0 {
+                break;
+            }
+
+            mantissa = quotient;
+            scale -= 1;
+        }
+
+        if mantissa > MAX_MANTISSA || scale > MAX_SCALE {
+            return Err(decode_err!("DECIMAL is out of range for `Decimal`"));
+        }
+
+        Ok(Decimal::from_parts(
+            mantissa as u32,
+            (mantissa >> 32) as u32,
+            (mantissa >> 64) as u32,
+            negative,
+            scale,
+        ))
+    }
+}
+
+#[test]
+fn test_encode_decimal() {
+    use std::str::FromStr;
+
+    let mut buf = Vec::new();
+    Encode::<MySql>::encode(&Decimal::from_str("-1.05").unwrap(), &mut buf);
+    assert_eq!(buf, b"\x05-1.05");
+
+    let mut buf = Vec::new();
+    Encode::<MySql>::encode(&Decimal::from_str("0.00105").unwrap(), &mut buf);
+    assert_eq!(buf, b"\x070.00105");
+}
+
+#[test]
+fn test_decode_decimal() {
+    use std::str::FromStr;
+
+    let ty = MySqlTypeInfo::new(TypeId::NEWDECIMAL);
+
+    let v =
+        <Decimal as Decode<MySql>>::decode(MySqlValue::binary(ty.clone(), b"\x05-1.05")).unwrap();
+    assert_eq!(v, Decimal::from_str("-1.05").unwrap());
+    assert_eq!(v.scale(), 2);
+
+    let v = <Decimal as Decode<MySql>>::decode(MySqlValue::text(ty.clone(), b"-90000")).unwrap();
+    assert_eq!(v, Decimal::from_str("-90000").unwrap());
+
+    // the largest `Decimal`
+    let v = <Decimal as Decode<MySql>>::decode(MySqlValue::text(
+        ty.clone(),
+        b"79228162514264337593543950335",
+    ))
+    .unwrap();
+    assert_eq!(v, Decimal::MAX);
+
+    // trailing zeros beyond the largest scale are dropped
+    let digits = "1.".to_owned() + &"0".repeat(30);
+    let v = <Decimal as Decode<MySql>>::decode(MySqlValue::text(ty.clone(), digits.as_bytes()))
+        .unwrap();
+    assert_eq!(v, Decimal::from(1));
+
+    assert!(<Decimal as Decode<MySql>>::decode(MySqlValue::text(
+        ty.clone(),
+        b"79228162514264337593543950336",
+    ))
+    .is_err());
+
+    let digits = "0.".to_owned() + &"0".repeat(29) + "1";
+    assert!(<Decimal as Decode<MySql>>::decode(MySqlValue::text(ty, digits.as_bytes())).is_err());
+}
@@ -0,0 +1,142 @@
+use std::fmt::Display;
+use std::io::Write;
+
+use byteorder::LittleEndian;
+
+use crate::mysql::io::BufMutExt;
+use crate::mysql::{MySqlData, MySqlValue};
+
+// A DECIMAL is sent as a string of digits in both the text and the binary protocol;
+// these read and write that string directly, without an intermediate `String`
+
+// Read a DECIMAL, passing the value of each of its digits (from the most significant) to
+// `digit`; returns whether the value is negative and its scale
+pub(super) fn read_decimal(
+    value: MySqlValue<'_>,
+    mut digit: impl FnMut(u8) -> crate::Result<()>,
+) -> crate::Result<(bool, u32)> {
+    let buf = match value.try_get()? {
+        // len : int<lenenc>; a DECIMAL is at most 65 digits, so its length is a single byte
+        MySqlData::Binary(buf) => match buf.split_first() {
+            Some((&len, buf)) if len as usize == buf.len() => buf,
+
+            _ => {
+                return Err(decode_err!(
+                    "expected a length-prefixed DECIMAL but got {:?}",
+                    buf
+                ));
+            }
+        },
+
+        MySqlData::Text(buf) => buf,
+    };
+
+    let (negative, buf) = match buf.split_first() {
+        Some((b'-', rest)) => (true, rest),
+        _ => (false, buf),
+    };
+
+    let mut scale = None;
+    let mut digits = 0;
+
+    for &b in buf {
+        match b {
+            b'0'..=b'9' => {
+                digit(b - b'0')?;
+                digits += 1;
+
+                if let Some(scale) = &mut scale {
+                    *scale += 1;
+                }
+            }
+
+            b'.' if scale.is_none() => {
+                scale = Some(0);
+            }
+
+            _ => {
+                return Err(decode_err!(
+                    "unexpected {:?} in DECIMAL {:?}",
+                    b as char,
+                    String::from_utf8_lossy(buf)
+                ));
+            }
+        }
+    }
+
+    if digits == 0 {
+        return Err(decode_err!("expected a DECIMAL but got an empty value"));
+    }
+
+    Ok((negative, scale.unwrap_or(0)))
+}
+
+// Write a DECIMAL as a length-encoded string of its decimal (not scientific) notation
+pub(super) fn write_decimal(buf: &mut Vec<u8>, value: &impl Display) {
+    let start = buf.len();
+
+    // the length of the string, which is almost always less than 251 (a single byte)
+    buf.push(0);
+    write!(buf, "{}", value).expect("writing to a Vec can not fail");
+
+    let len = buf.len() - start - 1;
+
+    if len < 251 {
+        buf[start] = len as u8;
+    } else {
+        let s = buf.split_off(start + 1);
+        buf.truncate(start);
+        buf.put_bytes_lenenc::<LittleEndian>(&s);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_decimal, write_decimal};
+    use crate::mysql::protocol::TypeId;
+    use crate::mysql::{MySqlTypeInfo, MySqlValue};
+
+    fn read(value: MySqlValue<'_>) -> crate::Result<(bool, Vec<u8>, u32)> {
+        let mut digits = Vec::new();
+        let (negative, scale) = read_decimal(value, |digit| {
+            digits.push(digit);
+            Ok(())
+        })?;
+
+        Ok((negative, digits, scale))
+    }
+
+    #[test]
+    fn it_reads_decimals() {
+        let ty = MySqlTypeInfo::new(TypeId::NEWDECIMAL);
+
+        assert_eq!(
+            read(MySqlValue::binary(ty.clone(), b"\x05-1.05")).unwrap(),
+            (true, vec![1, 0, 5], 2)
+        );
+        assert_eq!(
+            read(MySqlValue::text(ty.clone(), b"10000")).unwrap(),
+            (false, vec![1, 0, 0, 0, 0], 0)
+        );
+
+        assert!(read(MySqlValue::binary(ty.clone(), b"\x05-1.0")).is_err());
+        assert!(read(MySqlValue::text(ty.clone(), b"1.0.5")).is_err());
+        assert!(read(MySqlValue::text(ty.clone(), b"1e5")).is_err());
+        assert!(read(MySqlValue::text(ty, b"-")).is_err());
+    }
+
+    #[test]
+    fn it_writes_decimals() {
+        let mut buf = Vec::new();
+        write_decimal(&mut buf, &"-1.05");
+
+        assert_eq!(buf, b"\x05-1.05");
+
+        // a string of 251 bytes or more has a longer length
+        let mut buf = vec![0xAA];
+        write_decimal(&mut buf, &"1".repeat(300));
+
+        assert_eq!(&buf[..4], &[0xAA, 0xFC, 0x2C, 0x01]);
+        assert_eq!(buf.len(), 4 + 300);
+    }
+}
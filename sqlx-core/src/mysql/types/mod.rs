@@ -7,16 +7,26 @@
 //! | `bool`                                | TINYINT(1)                                           |
 //! | `i8`                                  | TINYINT                                              |
 //! | `i16`                                 | SMALLINT                                             |
-//! | `i32`                                 | INT                                                  |
+//! | `i32`                                 | INT, MEDIUMINT                                       |
 //! | `i64`                                 | BIGINT                                               |
 //! | `u8`                                  | TINYINT UNSIGNED                                     |
-//! | `u16`                                 | SMALLINT UNSIGNED                                    |
-//! | `u32`                                 | INT UNSIGNED                                         |
-//! | `u64`                                 | BIGINT UNSIGNED                                      |
+//! | `u16`                                 | SMALLINT UNSIGNED, YEAR                              |
+//! | `u32`                                 | INT UNSIGNED, MEDIUMINT UNSIGNED                     |
+//! | `u64`                                 | BIGINT UNSIGNED, BIT                                 |
 //! | `f32`                                 | FLOAT                                                |
 //! | `f64`                                 | DOUBLE                                               |
 //! | `&str`, `String`                      | VARCHAR, CHAR, TEXT                                  |
-//! | `&[u8]`, `Vec<u8>`                    | VARBINARY, BINARY, BLOB                              |
+//! | `&[u8]`, `Vec<u8>`                    | VARBINARY, BINARY, BLOB, BIT                         |
+//! | `Vec<String>`                         | SET                                                  |
+//!
+//! An unsigned integer can be decoded from a column of a narrower unsigned type, e.g.
+//! `u64` from `INT UNSIGNED`; decoding a value that does not fit is an error.
+//!
+//! A `BIT(n)` value (of at most 64 bits) is decoded as a `u64`, or as its bytes in big-endian
+//! order into `&[u8]` or `Vec<u8>`.
+//!
+//! A `SET` value is decoded as its members, in the order of the definition of the `SET`;
+//! it can also be decoded as a `String` of the comma-separated members.
 //!
 //! ### [`chrono`](https://crates.io/crates/chrono)
 //!
@@ -47,6 +57,18 @@
 //! | Rust type                             | MySQL type(s)                                        |
 //! |---------------------------------------|------------------------------------------------------|
 //! | `bigdecimal::BigDecimal`              | DECIMAL                                              |
+//!
+//! ### [`rust_decimal`](https://crates.io/crates/rust_decimal)
+//! Requires the `decimal` Cargo feature flag.
+//!
+//! | Rust type                             | MySQL type(s)                                        |
+//! |---------------------------------------|------------------------------------------------------|
+//! | `rust_decimal::Decimal`               | DECIMAL                                              |
+//!
+//! A `DECIMAL` is sent as a string of digits, which is parsed directly into the mantissa and
+//! scale of a `BigDecimal` or `Decimal`. Decoding a value that does not fit a `Decimal`
+//! (more than 28 digits after the point, or a mantissa above 2^96) is an error.
+//!
 //! ### [`json`](https://crates.io/crates/json)
 //!
 //! Requires the `json` Cargo feature flag.
@@ -55,6 +77,17 @@
 //! |---------------------------------------|------------------------------------------------------|
 //! | `json::JsonValue`             | JSON
 //!
+//! ### [`geo-types`](https://crates.io/crates/geo-types)
+//!
+//! Requires the `mysql-spatial` Cargo feature flag.
+//!
+//! | Rust type                             | MySQL type(s)                                        |
+//! |---------------------------------------|------------------------------------------------------|
+//! | `geo_types::Geometry<f64>`            | GEOMETRY, POINT, LINESTRING, POLYGON, ...            |
+//! | [`MySqlGeometry`]                     | GEOMETRY, POINT, LINESTRING, POLYGON, ...            |
+//!
+//! [`MySqlGeometry`] carries the SRID of the value along with the geometry.
+//!
 //! # Nullable
 //!
 //! In addition, `Option<T>` is supported where `T` implements `Type`. An `Option<T>` represents
@@ -65,12 +98,19 @@ mod bool;
 mod bytes;
 mod float;
 mod int;
+mod set;
 mod str;
 mod uint;
 
+#[cfg(any(feature = "bigdecimal", feature = "decimal"))]
+mod decimal_str;
+
 #[cfg(feature = "bigdecimal")]
 mod bigdecimal;
 
+#[cfg(feature = "decimal")]
+mod decimal;
+
 #[cfg(feature = "chrono")]
 mod chrono;
 
@@ -80,6 +120,12 @@ mod time;
 #[cfg(feature = "json")]
 mod json;
 
+#[cfg(feature = "mysql-spatial")]
+mod spatial;
+
+#[cfg(feature = "mysql-spatial")]
+pub use spatial::MySqlGeometry;
+
 use crate::decode::Decode;
 use crate::mysql::{MySql, MySqlValue};
 
@@ -0,0 +1,75 @@
+use std::str::from_utf8;
+
+use byteorder::LittleEndian;
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::mysql::io::BufMutExt;
+use crate::mysql::protocol::TypeId;
+use crate::mysql::type_info::MySqlTypeInfo;
+use crate::mysql::{MySql, MySqlData, MySqlValue};
+use crate::types::Type;
+
+// A SET is sent as its members separated by commas (which a member can not contain), in
+// both protocols; the empty set is an empty string
+
+impl Type<MySql> for Vec<String> {
+    fn type_info() -> MySqlTypeInfo {
+        MySqlTypeInfo {
+            id: TypeId::SET,
+            is_binary: false,
+            is_unsigned: false,
+            char_set: 224, // utf8mb4_unicode_ci
+        }
+    }
+}
+
+impl Encode<MySql> for Vec<String> {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        buf.put_str_lenenc::<LittleEndian>(&self.join(","));
+    }
+}
+
+impl<'de> Decode<'de, MySql> for Vec<String> {
+    fn decode(value: MySqlValue<'de>) -> crate::Result<Self> {
+        let s = match value.try_get()? {
+            MySqlData::Binary(buf) | MySqlData::Text(buf) => {
+                from_utf8(buf).map_err(crate::Error::decode)?
+            }
+        };
+
+        if s.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        Ok(s.split(',').map(ToOwned::to_owned).collect())
+    }
+}
+
+#[test]
+fn test_encode_set() {
+    let mut buf = Vec::new();
+    Encode::<MySql>::encode(&vec!["a".to_owned(), "c".to_owned()], &mut buf);
+    assert_eq!(buf, b"\x03a,c");
+
+    let mut buf = Vec::new();
+    Encode::<MySql>::encode(&Vec::<String>::new(), &mut buf);
+    assert_eq!(buf, b"\x00");
+}
+
+#[test]
+fn test_decode_set() {
+    let set = <Vec<String> as Decode<MySql>>::decode(MySqlValue::text(
+        <Vec<String> as Type<MySql>>::type_info(),
+        b"a,c",
+    ))
+    .unwrap();
+    assert_eq!(set, vec!["a", "c"]);
+
+    let set = <Vec<String> as Decode<MySql>>::decode(MySqlValue::binary(
+        <Vec<String> as Type<MySql>>::type_info(),
+        b"",
+    ))
+    .unwrap();
+    assert!(set.is_empty());
+}
@@ -0,0 +1,156 @@
+use std::convert::TryInto;
+
+use byteorder::LittleEndian;
+use geo_types::Geometry;
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::mysql::io::BufMutExt;
+use crate::mysql::protocol::TypeId;
+use crate::mysql::type_info::MySqlTypeInfo;
+use crate::mysql::{MySql, MySqlData, MySqlValue};
+use crate::types::Type;
+use crate::wkb::{read_geometry, write_geometry};
+
+/// A MySQL `GEOMETRY` value (or `POINT`, `POLYGON`, etc.) with its spatial reference
+/// system identifier.
+///
+/// `geo_types::Geometry` can be used directly when the SRID is not needed; it is sent
+/// with an SRID of 0 and the SRID of a received value is discarded.
+///
+/// Values are transferred in the internal format of MySQL: the SRID followed by the
+/// geometry in WKB.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MySqlGeometry {
+    pub geometry: Geometry<f64>,
+
+    /// The spatial reference system identifier (e.g., `4326` for WGS 84), or `0` for
+    /// a plane without units.
+    pub srid: u32,
+}
+
+impl MySqlGeometry {
+    pub fn new(geometry: impl Into<Geometry<f64>>, srid: u32) -> Self {
+        Self {
+            geometry: geometry.into(),
+            srid,
+        }
+    }
+}
+
+impl Type<MySql> for MySqlGeometry {
+    fn type_info() -> MySqlTypeInfo {
+        MySqlTypeInfo {
+            id: TypeId::GEOMETRY,
+            is_binary: true,
+            is_unsigned: false,
+            char_set: 63, // binary
+        }
+    }
+}
+
+impl Type<MySql> for Geometry<f64> {
+    fn type_info() -> MySqlTypeInfo {
+        <MySqlGeometry as Type<MySql>>::type_info()
+    }
+}
+
+impl Encode<MySql> for MySqlGeometry {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        encode_geometry(buf, &self.geometry, self.srid);
+    }
+}
+
+impl Encode<MySql> for Geometry<f64> {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        encode_geometry(buf, self, 0);
+    }
+}
+
+impl<'de> Decode<'de, MySql> for MySqlGeometry {
+    fn decode(value: MySqlValue<'de>) -> crate::Result<Self> {
+        let buf = match value.try_get()? {
+            MySqlData::Binary(buf) | MySqlData::Text(buf) => buf,
+        };
+
+        if buf.len() < 4 {
+            return Err(decode_err!(
+                "expected a geometry of at least 4 bytes but got {} bytes",
+                buf.len()
+            ));
+        }
+
+        let srid = u32::from_le_bytes(buf[..4].try_into().unwrap());
+        let (geometry, _) = read_geometry(&mut &buf[4..])?;
+
+        Ok(MySqlGeometry { geometry, srid })
+    }
+}
+
+impl<'de> Decode<'de, MySql> for Geometry<f64> {
+    fn decode(value: MySqlValue<'de>) -> crate::Result<Self> {
+        MySqlGeometry::decode(value).map(|value| value.geometry)
+    }
+}
+
+fn encode_geometry(buf: &mut Vec<u8>, geometry: &Geometry<f64>, srid: u32) {
+    let mut value = srid.to_le_bytes().to_vec();
+    write_geometry(&mut value, geometry, None);
+
+    buf.put_bytes_lenenc::<LittleEndian>(&value);
+}
+
+#[cfg(test)]
+use geo_types::{LineString, Point, Polygon};
+
+#[test]
+fn test_encode_decode_point_with_srid() {
+    let point = MySqlGeometry::new(Point::new(1.5, -2.0), 4326);
+
+    let mut buf = Vec::new();
+    Encode::<MySql>::encode(&point, &mut buf);
+
+    // the SRID, then the WKB of POINT(1.5 -2)
+    assert_eq!(
+        hex::encode(&buf).to_uppercase(),
+        "19E61000000101000000000000000000F83F00000000000000C0"
+    );
+
+    let decoded = <MySqlGeometry as Decode<MySql>>::decode(MySqlValue::binary(
+        MySqlGeometry::type_info(),
+        &buf[1..],
+    ))
+    .unwrap();
+
+    assert_eq!(decoded, point);
+}
+
+#[test]
+fn test_decode_polygon() {
+    // SELECT ST_GeomFromText('POLYGON((0 0, 1 0, 1 1, 0 0))')
+    let buf = hex::decode(
+        "000000000103000000010000000400000000000000000000000000000000000000000000000000\
+         F03F0000000000000000000000000000F03F000000000000F03F00000000000000000000000000000000",
+    )
+    .unwrap();
+
+    let polygon = <Geometry<f64> as Decode<MySql>>::decode(MySqlValue::text(
+        MySqlGeometry::type_info(),
+        &buf,
+    ))
+    .unwrap();
+
+    assert_eq!(
+        polygon,
+        Geometry::Polygon(Polygon::new(
+            LineString::from(vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 0.0)]),
+            vec![],
+        ))
+    );
+
+    assert!(<Geometry<f64> as Decode<MySql>>::decode(MySqlValue::text(
+        MySqlGeometry::type_info(),
+        &buf[..3],
+    ))
+    .is_err());
+}
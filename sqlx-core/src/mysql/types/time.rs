@@ -9,6 +9,7 @@ use crate::encode::Encode;
 use crate::io::{Buf, BufMut};
 use crate::mysql::protocol::TypeId;
 use crate::mysql::type_info::MySqlTypeInfo;
+use crate::mysql::{time_zone, zero_dates};
 use crate::mysql::{MySql, MySqlData, MySqlValue};
 use crate::types::Type;
 
@@ -29,6 +30,8 @@ impl Encode<MySql> for OffsetDateTime {
 
 impl<'de> Decode<'de, MySql> for OffsetDateTime {
     fn decode(value: MySqlValue<'de>) -> crate::Result<Self> {
+        time_zone::reject_local(&value)?;
+
         let primitive: PrimitiveDateTime = Decode::<MySql>::decode(value)?;
 
         Ok(primitive.assume_utc())
@@ -124,7 +127,10 @@ impl Encode<MySql> for Date {
 
 impl<'de> Decode<'de, MySql> for Date {
     fn decode(value: MySqlValue<'de>) -> crate::Result<Self> {
-        match value.try_get()? {
+        let data = value.try_get()?;
+        zero_dates::reject(data)?;
+
+        match data {
             MySqlData::Binary(buf) => decode_date(&buf[1..]),
             MySqlData::Text(buf) => {
                 let s = from_utf8(buf).map_err(crate::Error::decode)?;
@@ -171,7 +177,10 @@ impl Encode<MySql> for PrimitiveDateTime {
 
 impl<'de> Decode<'de, MySql> for PrimitiveDateTime {
     fn decode(value: MySqlValue<'de>) -> crate::Result<Self> {
-        match value.try_get()? {
+        let data = value.try_get()?;
+        zero_dates::reject(data)?;
+
+        match data {
             MySqlData::Binary(buf) => {
                 let len = buf[0];
                 let date = decode_date(&buf[1..])?;
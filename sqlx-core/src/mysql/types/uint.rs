@@ -1,6 +1,7 @@
+use std::convert::TryInto;
 use std::str::from_utf8;
 
-use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use byteorder::{ByteOrder, LittleEndian, WriteBytesExt};
 
 use crate::decode::Decode;
 use crate::encode::Encode;
@@ -8,6 +9,7 @@ use crate::mysql::protocol::TypeId;
 use crate::mysql::type_info::MySqlTypeInfo;
 use crate::mysql::{MySql, MySqlData, MySqlValue};
 use crate::types::Type;
+use crate::value::RawValue;
 use crate::Error;
 
 impl Type<MySql> for u8 {
@@ -24,14 +26,7 @@ impl Encode<MySql> for u8 {
 
 impl<'de> Decode<'de, MySql> for u8 {
     fn decode(value: MySqlValue<'de>) -> crate::Result<Self> {
-        match value.try_get()? {
-            MySqlData::Binary(mut buf) => buf.read_u8().map_err(Into::into),
-
-            MySqlData::Text(s) => from_utf8(s)
-                .map_err(Error::decode)?
-                .parse()
-                .map_err(Error::decode),
-        }
+        decode_uint(value)?.try_into().map_err(Error::decode)
     }
 }
 
@@ -49,14 +44,7 @@ impl Encode<MySql> for u16 {
 
 impl<'de> Decode<'de, MySql> for u16 {
     fn decode(value: MySqlValue<'de>) -> crate::Result<Self> {
-        match value.try_get()? {
-            MySqlData::Binary(mut buf) => buf.read_u16::<LittleEndian>().map_err(Into::into),
-
-            MySqlData::Text(s) => from_utf8(s)
-                .map_err(Error::decode)?
-                .parse()
-                .map_err(Error::decode),
-        }
+        decode_uint(value)?.try_into().map_err(Error::decode)
     }
 }
 
@@ -74,14 +62,7 @@ impl Encode<MySql> for u32 {
 
 impl<'de> Decode<'de, MySql> for u32 {
     fn decode(value: MySqlValue<'de>) -> crate::Result<Self> {
-        match value.try_get()? {
-            MySqlData::Binary(mut buf) => buf.read_u32::<LittleEndian>().map_err(Into::into),
-
-            MySqlData::Text(s) => from_utf8(s)
-                .map_err(Error::decode)?
-                .parse()
-                .map_err(Error::decode),
-        }
+        decode_uint(value)?.try_into().map_err(Error::decode)
     }
 }
 
@@ -99,13 +80,87 @@ impl Encode<MySql> for u64 {
 
 impl<'de> Decode<'de, MySql> for u64 {
     fn decode(value: MySqlValue<'de>) -> crate::Result<Self> {
-        match value.try_get()? {
-            MySqlData::Binary(mut buf) => buf.read_u64::<LittleEndian>().map_err(Into::into),
+        decode_uint(value)
+    }
+}
 
-            MySqlData::Text(s) => from_utf8(s)
-                .map_err(Error::decode)?
-                .parse()
-                .map_err(Error::decode),
+// Decode an unsigned integer of any width; the column may be narrower than the Rust type
+// (e.g., `u64` from `INT UNSIGNED`), while a value that does not fit in a narrower Rust
+// type is an error instead of being truncated
+fn decode_uint(value: MySqlValue<'_>) -> crate::Result<u64> {
+    let is_bit = matches!(value.type_info(), Some(ty) if ty.id == TypeId::BIT);
+
+    match value.try_get()? {
+        // BIT(n) is sent as bytes in both protocols
+        MySqlData::Binary(buf) | MySqlData::Text(buf) if is_bit => decode_bit(buf),
+
+        MySqlData::Binary(buf) => {
+            if buf.is_empty() || buf.len() > 8 {
+                return Err(decode_err!(
+                    "expected an integer of 1 to 8 bytes but got {} bytes",
+                    buf.len()
+                ));
+            }
+
+            Ok(LittleEndian::read_uint(buf, buf.len()))
         }
+
+        MySqlData::Text(s) => from_utf8(s)
+            .map_err(Error::decode)?
+            .parse()
+            .map_err(Error::decode),
     }
 }
+
+// The value of a BIT(n) column, in big-endian bytes
+fn decode_bit(buf: &[u8]) -> crate::Result<u64> {
+    if buf.len() > 8 {
+        return Err(decode_err!(
+            "expected a BIT value of at most 8 bytes but got {} bytes",
+            buf.len()
+        ));
+    }
+
+    Ok(buf
+        .iter()
+        .fold(0, |value, &byte| (value << 8) | u64::from(byte)))
+}
+
+#[test]
+fn test_decode_bit() {
+    let ty = MySqlTypeInfo::unsigned(TypeId::BIT);
+
+    let value = <u64 as Decode<MySql>>::decode(MySqlValue::binary(ty.clone(), &[0x01, 0x05]));
+    assert_eq!(value.unwrap(), 261);
+
+    let value = <u64 as Decode<MySql>>::decode(MySqlValue::text(ty.clone(), &[0xFF; 8]));
+    assert_eq!(value.unwrap(), u64::MAX);
+
+    let value = <u64 as Decode<MySql>>::decode(MySqlValue::text(ty.clone(), &[]));
+    assert_eq!(value.unwrap(), 0);
+
+    assert!(<u64 as Decode<MySql>>::decode(MySqlValue::binary(ty, &[0; 9])).is_err());
+}
+
+#[test]
+fn test_decode_uint_of_other_widths() {
+    let ty = MySqlTypeInfo::unsigned(TypeId::BIG_INT);
+
+    let value = <u64 as Decode<MySql>>::decode(MySqlValue::binary(ty.clone(), &[0xFF; 8]));
+    assert_eq!(value.unwrap(), u64::MAX);
+
+    let value = <u64 as Decode<MySql>>::decode(MySqlValue::text(ty, b"18446744073709551615"));
+    assert_eq!(value.unwrap(), u64::MAX);
+
+    // INT UNSIGNED
+    let ty = MySqlTypeInfo::unsigned(TypeId::INT);
+
+    let value = <u64 as Decode<MySql>>::decode(MySqlValue::binary(ty.clone(), &[1, 1, 0, 0]));
+    assert_eq!(value.unwrap(), 257);
+
+    let value = <u8 as Decode<MySql>>::decode(MySqlValue::binary(ty.clone(), &[5, 0, 0, 0]));
+    assert_eq!(value.unwrap(), 5);
+
+    assert!(<u8 as Decode<MySql>>::decode(MySqlValue::binary(ty.clone(), &[1, 1, 0, 0])).is_err());
+    assert!(<u16 as Decode<MySql>>::decode(MySqlValue::text(ty, b"65536")).is_err());
+}
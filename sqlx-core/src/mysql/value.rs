@@ -2,7 +2,7 @@ use crate::error::UnexpectedNullError;
 use crate::mysql::{MySql, MySqlTypeInfo};
 use crate::value::RawValue;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum MySqlData<'c> {
     Binary(&'c [u8]),
     Text(&'c [u8]),
@@ -12,6 +12,9 @@ pub enum MySqlData<'c> {
 pub struct MySqlValue<'c> {
     type_info: Option<MySqlTypeInfo>,
     data: Option<MySqlData<'c>>,
+
+    // Is a TIMESTAMP a local time of the session time zone, rather than UTC
+    pub(crate) local_timestamps: bool,
 }
 
 impl<'c> MySqlValue<'c> {
@@ -35,6 +38,7 @@ impl<'c> MySqlValue<'c> {
         Self {
             type_info: None,
             data: None,
+            local_timestamps: false,
         }
     }
 
@@ -42,6 +46,7 @@ impl<'c> MySqlValue<'c> {
         Self {
             type_info: Some(type_info),
             data: Some(MySqlData::Binary(buf)),
+            local_timestamps: false,
         }
     }
 
@@ -49,6 +54,7 @@ impl<'c> MySqlValue<'c> {
         Self {
             type_info: Some(type_info),
             data: Some(MySqlData::Text(buf)),
+            local_timestamps: false,
         }
     }
 }
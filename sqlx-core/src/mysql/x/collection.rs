@@ -0,0 +1,258 @@
+use crate::mysql::x::protobuf::{put_bytes, put_message, put_uint};
+use crate::mysql::x::{
+    MySqlXConnection, MySqlXResult, MySqlXValue, CRUD_DELETE, CRUD_FIND, CRUD_INSERT,
+    SQL_STMT_EXECUTE,
+};
+
+// The data model of the CRUD messages of collections (rather than tables)
+const DOCUMENT: u64 = 1;
+
+// The types of `Expr`
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.12/structMysqlx_1_1Expr_1_1Expr.html
+const IDENT: u64 = 1;
+const LITERAL: u64 = 2;
+const OPERATOR: u64 = 5;
+
+// The types of `Scalar`
+const V_OCTETS: u64 = 4;
+const V_STRING: u64 = 8;
+
+// The content type of octets of a JSON document
+const JSON: u64 = 2;
+
+/// A collection of JSON documents in a schema, over the X Protocol.
+///
+/// Returned from [MySqlXConnection::collection] and [MySqlXConnection::create_collection].
+/// Each document has a unique `_id`, which the server generates for a document that is
+/// added without one.
+///
+/// ```rust,ignore
+/// let mut products = conn.collection("app", "products");
+///
+/// let result = products.add(r#"{"name": "tea", "price": 3.5}"#).await?;
+/// let id = &result.generated_ids[0];
+///
+/// let tea: Option<String> = products.find_by_id(id).await?;
+/// ```
+pub struct MySqlXCollection<'c> {
+    conn: &'c mut MySqlXConnection,
+    schema: String,
+    name: String,
+}
+
+impl MySqlXConnection {
+    /// Returns the collection `name` in `schema`, which must exist already.
+    pub fn collection(&mut self, schema: &str, name: &str) -> MySqlXCollection<'_> {
+        MySqlXCollection {
+            conn: self,
+            schema: schema.to_owned(),
+            name: name.to_owned(),
+        }
+    }
+
+    /// Create the collection `name` in `schema`, which must not exist yet, and return it.
+    pub async fn create_collection(
+        &mut self,
+        schema: &str,
+        name: &str,
+    ) -> crate::Result<MySqlXCollection<'_>> {
+        self.admin_command("create_collection", schema, name)
+            .await?;
+
+        Ok(self.collection(schema, name))
+    }
+
+    /// Drop the collection `name` in `schema`, with all of its documents.
+    pub async fn drop_collection(&mut self, schema: &str, name: &str) -> crate::Result<()> {
+        self.admin_command("drop_collection", schema, name).await
+    }
+
+    // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/mysqlx_protocol_xplugin.html
+    async fn admin_command(
+        &mut self,
+        command: &str,
+        schema: &str,
+        name: &str,
+    ) -> crate::Result<()> {
+        // StmtExecute { stmt, args, namespace }, with the arguments in an object
+        self.send(SQL_STMT_EXECUTE, |buf| {
+            put_bytes(buf, 1, command.as_bytes());
+            put_message(buf, 2, |buf| {
+                // Any { type: OBJECT, obj }
+                put_uint(buf, 1, 2);
+                put_message(buf, 3, |buf| {
+                    put_object_field(buf, "schema", schema);
+                    put_object_field(buf, "name", name);
+                });
+            });
+            put_bytes(buf, 3, b"mysqlx");
+        });
+
+        self.flush().await?;
+        self.read_result().await?;
+
+        Ok(())
+    }
+}
+
+impl MySqlXCollection<'_> {
+    /// Add a JSON document to the collection.
+    ///
+    /// If the document has no `_id`, the one that the server generates for it is in the
+    /// [generated_ids] of the result.
+    ///
+    /// [generated_ids]: crate::mysql::x::MySqlXResult::generated_ids
+    pub async fn add(&mut self, document: &str) -> crate::Result<MySqlXResult> {
+        let (schema, name) = (&self.schema, &self.name);
+
+        // Insert { collection, data_model, row: [TypedRow { field: [Expr] }] }
+        self.conn.send(CRUD_INSERT, |buf| {
+            put_collection(buf, 1, schema, name);
+            put_uint(buf, 2, DOCUMENT);
+            put_message(buf, 4, |buf| {
+                put_message(buf, 1, |buf| {
+                    put_literal(buf, document.as_bytes(), Some(JSON))
+                });
+            });
+        });
+
+        self.conn.flush().await?;
+        self.conn.read_result().await
+    }
+
+    /// Returns all of the documents of the collection, as JSON.
+    pub async fn find_all(&mut self) -> crate::Result<Vec<String>> {
+        self.find(None).await
+    }
+
+    /// Returns the document with the given `_id`, as JSON.
+    pub async fn find_by_id(&mut self, id: &str) -> crate::Result<Option<String>> {
+        Ok(self.find(Some(id)).await?.pop())
+    }
+
+    /// Remove the document with the given `_id` and return the number of documents removed.
+    pub async fn remove_by_id(&mut self, id: &str) -> crate::Result<u64> {
+        let (schema, name) = (&self.schema, &self.name);
+
+        // Delete { collection, data_model, criteria }
+        self.conn.send(CRUD_DELETE, |buf| {
+            put_collection(buf, 1, schema, name);
+            put_uint(buf, 2, DOCUMENT);
+            put_message(buf, 3, |buf| put_id_criteria(buf, id));
+        });
+
+        self.conn.flush().await?;
+
+        Ok(self.conn.read_result().await?.rows_affected)
+    }
+
+    async fn find(&mut self, id: Option<&str>) -> crate::Result<Vec<String>> {
+        let (schema, name) = (&self.schema, &self.name);
+
+        // Find { collection, data_model, criteria }
+        self.conn.send(CRUD_FIND, |buf| {
+            put_collection(buf, 2, schema, name);
+            put_uint(buf, 3, DOCUMENT);
+
+            if let Some(id) = id {
+                put_message(buf, 5, |buf| put_id_criteria(buf, id));
+            }
+        });
+
+        self.conn.flush().await?;
+
+        // the documents are in the one column, `doc`, of the rows
+        let rows = self.conn.read_result().await?.rows;
+
+        rows.into_iter()
+            .map(|row| match row.get(0) {
+                Some(MySqlXValue::Text(document)) => Ok(document.clone()),
+                value => {
+                    Err(protocol_err!("expected a JSON document; received {:?}", value).into())
+                }
+            })
+            .collect()
+    }
+}
+
+// Collection { name, schema }
+fn put_collection(buf: &mut Vec<u8>, field: u32, schema: &str, name: &str) {
+    put_message(buf, field, |buf| {
+        put_bytes(buf, 1, name.as_bytes());
+        put_bytes(buf, 2, schema.as_bytes());
+    });
+}
+
+// ObjectField { key, value: Any { type: SCALAR, scalar: V_STRING } }
+fn put_object_field(buf: &mut Vec<u8>, key: &str, value: &str) {
+    put_message(buf, 1, |buf| {
+        put_bytes(buf, 1, key.as_bytes());
+        put_message(buf, 2, |buf| {
+            put_uint(buf, 1, 1);
+            put_message(buf, 2, |buf| {
+                put_uint(buf, 1, V_STRING);
+                put_message(buf, 9, |buf| put_bytes(buf, 1, value.as_bytes()));
+            });
+        });
+    });
+}
+
+// Expr { type: LITERAL, literal: Scalar { type: V_OCTETS, v_octets: { value, content_type } } }
+fn put_literal(buf: &mut Vec<u8>, value: &[u8], content_type: Option<u64>) {
+    put_uint(buf, 1, LITERAL);
+    put_message(buf, 4, |buf| {
+        put_uint(buf, 1, V_OCTETS);
+        put_message(buf, 5, |buf| {
+            put_bytes(buf, 1, value);
+
+            if let Some(content_type) = content_type {
+                put_uint(buf, 2, content_type);
+            }
+        });
+    });
+}
+
+// Expr { type: OPERATOR, operator: { name: "==", param: [$._id, id] } }
+fn put_id_criteria(buf: &mut Vec<u8>, id: &str) {
+    put_uint(buf, 1, OPERATOR);
+    put_message(buf, 6, |buf| {
+        put_bytes(buf, 1, b"==");
+
+        // Expr { type: IDENT, identifier: { document_path: [{ type: MEMBER, value }] } }
+        put_message(buf, 2, |buf| {
+            put_uint(buf, 1, IDENT);
+            put_message(buf, 2, |buf| {
+                put_message(buf, 1, |buf| {
+                    put_uint(buf, 1, 1);
+                    put_bytes(buf, 2, b"_id");
+                });
+            });
+        });
+
+        put_message(buf, 2, |buf| put_literal(buf, id.as_bytes(), None));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::put_id_criteria;
+
+    #[test]
+    fn it_encodes_criteria_by_id() {
+        let mut buf = Vec::new();
+        put_id_criteria(&mut buf, "1");
+
+        #[rustfmt::skip]
+        let expected = [
+            0x08, 0x05, // type: OPERATOR
+            0x32, 0x20, // operator
+            0x0A, 0x02, b'=', b'=', // name
+            0x12, 0x0D, 0x08, 0x01, // param: IDENT
+            0x12, 0x09, 0x0A, 0x07, 0x08, 0x01, 0x12, 0x03, b'_', b'i', b'd', // $._id
+            0x12, 0x0B, 0x08, 0x02, // param: LITERAL
+            0x22, 0x07, 0x08, 0x04, 0x2A, 0x03, 0x0A, 0x01, b'1', // V_OCTETS
+        ];
+
+        assert_eq!(buf, expected);
+    }
+}
@@ -0,0 +1,83 @@
+use crate::mysql::x::{MySqlXConnection, MySqlXResult};
+
+/// A batch of SQL statements to be sent to the server in a single round-trip, over the
+/// X Protocol.
+///
+/// The server still executes the statements one after the other but the client does not
+/// wait for the result of a statement before sending the next one.
+///
+/// Unlike in a pipeline of Postgres, a statement that fails does not stop the statements
+/// after it; the server executes them all, and then the first error is returned.
+///
+/// ```rust,ignore
+/// let mut pipeline = conn.pipeline();
+///
+/// pipeline.push("INSERT INTO logs (message) VALUES ('foo')");
+/// pipeline.push("INSERT INTO logs (message) VALUES ('bar')");
+/// pipeline.push("SELECT COUNT(*) FROM logs");
+///
+/// let results: Vec<MySqlXResult> = pipeline.execute().await?;
+/// ```
+pub struct MySqlXPipeline<'c> {
+    conn: &'c mut MySqlXConnection,
+    statements: Vec<String>,
+}
+
+impl MySqlXConnection {
+    /// Create a new, empty, [MySqlXPipeline] on this connection.
+    pub fn pipeline(&mut self) -> MySqlXPipeline<'_> {
+        MySqlXPipeline {
+            conn: self,
+            statements: Vec::new(),
+        }
+    }
+}
+
+impl MySqlXPipeline<'_> {
+    /// Add a SQL statement to the end of the pipeline.
+    pub fn push(&mut self, sql: &str) -> &mut Self {
+        self.statements.push(sql.to_owned());
+        self
+    }
+
+    /// Returns the number of statements in the pipeline.
+    pub fn len(&self) -> usize {
+        self.statements.len()
+    }
+
+    /// Returns `true` if no statements have been added to the pipeline.
+    pub fn is_empty(&self) -> bool {
+        self.statements.is_empty()
+    }
+
+    /// Send all statements and return the result of each, in order.
+    pub async fn execute(self) -> crate::Result<Vec<MySqlXResult>> {
+        for sql in &self.statements {
+            self.conn.send_sql(sql);
+        }
+
+        self.conn.flush().await?;
+
+        let mut results = Vec::with_capacity(self.statements.len());
+        let mut first_error = None;
+
+        // read the result of every statement, even after an error, so the next message
+        // on the connection is the response to whatever is sent next
+        for _ in &self.statements {
+            match self.conn.read_result().await {
+                Ok(result) => results.push(result),
+
+                Err(crate::Error::Database(error)) => {
+                    first_error.get_or_insert(crate::Error::Database(error));
+                }
+
+                Err(error) => return Err(error),
+            }
+        }
+
+        match first_error {
+            Some(error) => Err(error),
+            None => Ok(results),
+        }
+    }
+}
@@ -0,0 +1,222 @@
+// The protocol buffers wire format, as much of it as the messages of the X Protocol use
+// https://developers.google.com/protocol-buffers/docs/encoding
+
+const VARINT: u8 = 0;
+const FIXED64: u8 = 1;
+const LEN: u8 = 2;
+const FIXED32: u8 = 5;
+
+pub(super) fn put_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+
+    buf.push(value as u8);
+}
+
+fn put_key(buf: &mut Vec<u8>, field: u32, wire_type: u8) {
+    put_varint(buf, (u64::from(field) << 3) | u64::from(wire_type));
+}
+
+pub(super) fn put_uint(buf: &mut Vec<u8>, field: u32, value: u64) {
+    put_key(buf, field, VARINT);
+    put_varint(buf, value);
+}
+
+pub(super) fn put_bytes(buf: &mut Vec<u8>, field: u32, value: &[u8]) {
+    put_key(buf, field, LEN);
+    put_varint(buf, value.len() as u64);
+    buf.extend_from_slice(value);
+}
+
+// Write a nested message, which is prefixed with its length
+pub(super) fn put_message(buf: &mut Vec<u8>, field: u32, message: impl FnOnce(&mut Vec<u8>)) {
+    let mut nested = Vec::new();
+    message(&mut nested);
+
+    put_bytes(buf, field, &nested);
+}
+
+pub(super) fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+// The value of a field of a message
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Field<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Fixed32(u32),
+    Bytes(&'a [u8]),
+}
+
+impl<'a> Field<'a> {
+    pub(super) fn as_u64(self) -> crate::Result<u64> {
+        match self {
+            Field::Varint(value) => Ok(value),
+            field => Err(protocol_err!("expected a varint field; received {:?}", field).into()),
+        }
+    }
+
+    pub(super) fn as_bytes(self) -> crate::Result<&'a [u8]> {
+        match self {
+            Field::Bytes(value) => Ok(value),
+            field => Err(protocol_err!("expected a bytes field; received {:?}", field).into()),
+        }
+    }
+
+    pub(super) fn as_str(self) -> crate::Result<&'a str> {
+        std::str::from_utf8(self.as_bytes()?)
+            .map_err(|_| protocol_err!("expected a UTF-8 string field").into())
+    }
+}
+
+// Iterate the fields of a message, in the order they were written
+pub(super) struct Fields<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Fields<'a> {
+    pub(super) fn new(buf: &'a [u8]) -> Self {
+        Self { buf }
+    }
+
+    fn get_varint(&mut self) -> crate::Result<u64> {
+        let mut value = 0_u64;
+
+        for (index, byte) in self.buf.iter().enumerate().take(10) {
+            value |= u64::from(byte & 0x7F) << (7 * index);
+
+            if byte & 0x80 == 0 {
+                self.buf = &self.buf[index + 1..];
+
+                return Ok(value);
+            }
+        }
+
+        Err(protocol_err!("invalid varint in protobuf message").into())
+    }
+
+    fn get_slice(&mut self, len: usize) -> crate::Result<&'a [u8]> {
+        if self.buf.len() < len {
+            return Err(protocol_err!("unexpected end of protobuf message").into());
+        }
+
+        let (slice, rest) = self.buf.split_at(len);
+        self.buf = rest;
+
+        Ok(slice)
+    }
+
+    fn get_field(&mut self) -> crate::Result<(u32, Field<'a>)> {
+        let key = self.get_varint()?;
+        let field = (key >> 3) as u32;
+
+        let value = match (key & 0x7) as u8 {
+            VARINT => Field::Varint(self.get_varint()?),
+
+            FIXED64 => {
+                let mut bytes = [0; 8];
+                bytes.copy_from_slice(self.get_slice(8)?);
+
+                Field::Fixed64(u64::from_le_bytes(bytes))
+            }
+
+            FIXED32 => {
+                let mut bytes = [0; 4];
+                bytes.copy_from_slice(self.get_slice(4)?);
+
+                Field::Fixed32(u32::from_le_bytes(bytes))
+            }
+
+            LEN => {
+                let len = self.get_varint()? as usize;
+
+                Field::Bytes(self.get_slice(len)?)
+            }
+
+            wire_type => {
+                return Err(protocol_err!(
+                    "unsupported wire type {} in protobuf message",
+                    wire_type
+                )
+                .into());
+            }
+        };
+
+        Ok((field, value))
+    }
+}
+
+impl<'a> Iterator for Fields<'a> {
+    type Item = crate::Result<(u32, Field<'a>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf.is_empty() {
+            return None;
+        }
+
+        let field = self.get_field();
+
+        if field.is_err() {
+            // stop after the first error
+            self.buf = &[];
+        }
+
+        Some(field)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{put_bytes, put_message, put_uint, unzigzag, Field, Fields};
+
+    #[test]
+    fn it_encodes_varints() {
+        let mut buf = Vec::new();
+        put_uint(&mut buf, 1, 300);
+
+        assert_eq!(buf, [0x08, 0xAC, 0x02]);
+    }
+
+    #[test]
+    fn it_decodes_zigzag_integers() {
+        assert_eq!(unzigzag(0), 0);
+        assert_eq!(unzigzag(1), -1);
+        assert_eq!(unzigzag(2), 1);
+        assert_eq!(unzigzag(u64::MAX - 1), i64::MAX);
+        assert_eq!(unzigzag(u64::MAX), i64::MIN);
+    }
+
+    #[test]
+    fn it_reads_the_fields_it_writes() {
+        let mut buf = Vec::new();
+        put_uint(&mut buf, 1, 7);
+        put_message(&mut buf, 2, |buf| put_bytes(buf, 1, b"abc"));
+        put_uint(&mut buf, 17, 3);
+
+        let fields = Fields::new(&buf)
+            .collect::<crate::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(
+            fields,
+            vec![
+                (1, Field::Varint(7)),
+                (2, Field::Bytes(&[0x0A, 0x03, b'a', b'b', b'c'])),
+                (17, Field::Varint(3)),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_rejects_truncated_messages() {
+        let mut buf = Vec::new();
+        put_bytes(&mut buf, 1, b"abc");
+        buf.pop();
+
+        assert!(Fields::new(&buf).next().unwrap().is_err());
+        assert!(Fields::new(&[0x80]).next().unwrap().is_err());
+    }
+}
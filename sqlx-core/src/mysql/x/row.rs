@@ -0,0 +1,468 @@
+use std::sync::Arc;
+
+use crate::mysql::x::protobuf::{unzigzag, Fields};
+
+// The types of columns in `Mysqlx.Resultset.ColumnMetaData`
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.12/structMysqlx_1_1Resultset_1_1ColumnMetaData.html
+pub(super) const SINT: u32 = 1;
+pub(super) const UINT: u32 = 2;
+pub(super) const DOUBLE: u32 = 5;
+pub(super) const FLOAT: u32 = 6;
+pub(super) const BYTES: u32 = 7;
+pub(super) const TIME: u32 = 10;
+pub(super) const DATETIME: u32 = 12;
+pub(super) const SET: u32 = 15;
+pub(super) const ENUM: u32 = 16;
+pub(super) const BIT: u32 = 17;
+pub(super) const DECIMAL: u32 = 18;
+
+// The collation of binary strings
+const BINARY: u64 = 63;
+
+// The content type of `BYTES` columns of `JSON` documents
+const JSON: u32 = 2;
+
+/// A column of the rows of a result over the X Protocol.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MySqlXColumn {
+    /// The name, or alias, of the column.
+    pub name: String,
+
+    /// The type of the column in the X Protocol, e.g. `7` for strings and `12` for `DATE`
+    /// and `DATETIME`.
+    pub type_id: u32,
+
+    // The collation of a string column, which is binary for `BLOB` and `BINARY`
+    pub(super) collation: u64,
+
+    // The number of digits of the fractional seconds of a `TIME` or `DATETIME` column
+    pub(super) fractional_digits: u32,
+
+    // What a `BYTES` column holds, e.g. `JSON` documents
+    pub(super) content_type: u32,
+}
+
+impl MySqlXColumn {
+    // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/structMysqlx_1_1Resultset_1_1ColumnMetaData.html
+    pub(super) fn decode(buf: &[u8]) -> crate::Result<Self> {
+        let mut column = MySqlXColumn {
+            name: String::new(),
+            type_id: 0,
+            collation: 0,
+            fractional_digits: 0,
+            content_type: 0,
+        };
+
+        for field in Fields::new(buf) {
+            match field? {
+                (1, value) => column.type_id = value.as_u64()? as u32,
+                (2, value) => column.name = value.as_str()?.to_owned(),
+                (8, value) => column.collation = value.as_u64()?,
+                (9, value) => column.fractional_digits = value.as_u64()? as u32,
+                (12, value) => column.content_type = value.as_u64()? as u32,
+
+                _ => {}
+            }
+        }
+
+        Ok(column)
+    }
+}
+
+/// The value of a single column in a [MySqlXRow].
+///
+/// [MySqlXRow]: struct.MySqlXRow.html
+#[derive(Debug, Clone, PartialEq)]
+pub enum MySqlXValue {
+    Null,
+
+    /// A signed integer.
+    Int(i64),
+
+    /// An unsigned integer or a `BIT` value.
+    UInt(u64),
+
+    Float(f32),
+    Double(f64),
+
+    /// A string, a `JSON` document, or an `ENUM`, `SET` (with its values separated by
+    /// commas), `DECIMAL`, `DATE`, `TIME` or `DATETIME` value in its text format,
+    /// e.g. `2020-01-31 12:00:00.5`.
+    Text(String),
+
+    /// A `BLOB` or `BINARY` value, or a geometry.
+    Bytes(Vec<u8>),
+}
+
+/// A row of a result over the X Protocol, or a document of a collection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MySqlXRow {
+    pub(super) columns: Arc<[MySqlXColumn]>,
+    pub(super) values: Vec<MySqlXValue>,
+}
+
+impl MySqlXRow {
+    pub fn columns(&self) -> &[MySqlXColumn] {
+        &self.columns
+    }
+
+    /// Returns the value of the column at `index` or `None` if it is `NULL` or out of bounds.
+    pub fn get(&self, index: usize) -> Option<&MySqlXValue> {
+        match self.values.get(index) {
+            Some(MySqlXValue::Null) | None => None,
+            Some(value) => Some(value),
+        }
+    }
+
+    /// Returns the value of the column with the given name or `None` if it is `NULL` or
+    /// there is no such column.
+    pub fn get_by_name(&self, name: &str) -> Option<&MySqlXValue> {
+        let index = self.columns.iter().position(|column| column.name == name)?;
+
+        self.get(index)
+    }
+
+    // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/structMysqlx_1_1Resultset_1_1Row.html
+    pub(super) fn decode(columns: &Arc<[MySqlXColumn]>, buf: &[u8]) -> crate::Result<Self> {
+        let mut values = Vec::with_capacity(columns.len());
+
+        for field in Fields::new(buf) {
+            let value = match field? {
+                (1, value) => value.as_bytes()?,
+                _ => continue,
+            };
+
+            let column = columns
+                .get(values.len())
+                .ok_or_else(|| protocol_err!("received more values than columns in a row"))?;
+
+            values.push(decode_value(column, value)?);
+        }
+
+        if values.len() != columns.len() {
+            return Err(protocol_err!(
+                "expected {} values in a row; received {}",
+                columns.len(),
+                values.len()
+            )
+            .into());
+        }
+
+        Ok(MySqlXRow {
+            columns: Arc::clone(columns),
+            values,
+        })
+    }
+}
+
+/// The result of a statement over the X Protocol.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MySqlXResult {
+    /// The rows returned by the statement, of all of its result sets.
+    pub rows: Vec<MySqlXRow>,
+
+    /// The number of rows inserted, updated or deleted.
+    pub rows_affected: u64,
+
+    /// The value generated for an `AUTO_INCREMENT` column by an `INSERT`.
+    pub last_insert_id: Option<u64>,
+
+    /// The `_id` of the documents added to a collection, of those that did not have one.
+    pub generated_ids: Vec<String>,
+}
+
+// Decode the value of a column of a row; an empty value is `NULL`
+// https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_mysqlx_protocol_messages.html
+fn decode_value(column: &MySqlXColumn, mut buf: &[u8]) -> crate::Result<MySqlXValue> {
+    if buf.is_empty() {
+        return Ok(MySqlXValue::Null);
+    }
+
+    Ok(match column.type_id {
+        SINT => MySqlXValue::Int(unzigzag(get_varint(&mut buf)?)),
+        UINT | BIT => MySqlXValue::UInt(get_varint(&mut buf)?),
+
+        FLOAT => MySqlXValue::Float(f32::from_le_bytes(get_array(buf)?)),
+        DOUBLE => MySqlXValue::Double(f64::from_le_bytes(get_array(buf)?)),
+
+        // strings have a trailing NUL byte, to tell an empty string from `NULL`
+        BYTES | ENUM => {
+            let bytes = &buf[..buf.len() - 1];
+
+            if column.collation == BINARY && column.content_type != JSON {
+                MySqlXValue::Bytes(bytes.to_vec())
+            } else {
+                MySqlXValue::Text(String::from_utf8_lossy(bytes).into_owned())
+            }
+        }
+
+        SET => MySqlXValue::Text(decode_set(buf)?),
+        DECIMAL => MySqlXValue::Text(decode_decimal(buf)?),
+
+        TIME => {
+            let sign = if get_u8(&mut buf)? == 1 { "-" } else { "" };
+            let mut parts = [0; 4];
+
+            // the trailing parts that are zero may be left out
+            for part in &mut parts {
+                if buf.is_empty() {
+                    break;
+                }
+
+                *part = get_varint(&mut buf)?;
+            }
+
+            MySqlXValue::Text(format!(
+                "{}{:02}:{:02}:{:02}{}",
+                sign,
+                parts[0],
+                parts[1],
+                parts[2],
+                fraction(parts[3], column.fractional_digits)
+            ))
+        }
+
+        DATETIME => {
+            let mut parts = [0; 7];
+            let mut len = 0;
+
+            while !buf.is_empty() && len < parts.len() {
+                parts[len] = get_varint(&mut buf)?;
+                len += 1;
+            }
+
+            let date = format!("{:04}-{:02}-{:02}", parts[0], parts[1], parts[2]);
+
+            // a `DATE` has only the first 3 parts
+            if len <= 3 {
+                MySqlXValue::Text(date)
+            } else {
+                MySqlXValue::Text(format!(
+                    "{} {:02}:{:02}:{:02}{}",
+                    date,
+                    parts[3],
+                    parts[4],
+                    parts[5],
+                    fraction(parts[6], column.fractional_digits)
+                ))
+            }
+        }
+
+        type_id => {
+            return Err(
+                protocol_err!("unsupported column type {} in X Protocol row", type_id).into(),
+            );
+        }
+    })
+}
+
+// The fractional seconds of a time, with as many digits as the column has
+fn fraction(micros: u64, digits: u32) -> String {
+    if digits == 0 {
+        return String::new();
+    }
+
+    let micros = format!("{:06}", micros);
+
+    format!(".{}", &micros[..(digits as usize).min(6)])
+}
+
+// A `SET` value is a sequence of its values, each prefixed with its length; a set with no
+// values is a single byte of 1
+fn decode_set(mut buf: &[u8]) -> crate::Result<String> {
+    if buf == [0x01] {
+        return Ok(String::new());
+    }
+
+    let mut values = Vec::new();
+
+    while !buf.is_empty() {
+        let len = get_varint(&mut buf)? as usize;
+
+        if buf.len() < len {
+            return Err(protocol_err!("unexpected end of SET value").into());
+        }
+
+        values.push(String::from_utf8_lossy(&buf[..len]));
+        buf = &buf[len..];
+    }
+
+    Ok(values.join(","))
+}
+
+// A `DECIMAL` value is its scale, then its digits in BCD (2 to a byte), ending with a sign
+// of `0xC` for positive or `0xD` for negative numbers
+fn decode_decimal(mut buf: &[u8]) -> crate::Result<String> {
+    let scale = get_u8(&mut buf)? as usize;
+
+    let mut digits = String::new();
+    let mut negative = None;
+
+    for nibble in buf.iter().flat_map(|byte| [byte >> 4, byte & 0x0F]) {
+        match nibble {
+            0..=9 => digits.push((b'0' + nibble) as char),
+
+            0x0B | 0x0D => {
+                negative = Some(true);
+                break;
+            }
+
+            _ => {
+                negative = Some(false);
+                break;
+            }
+        }
+    }
+
+    let negative =
+        negative.ok_or_else(|| protocol_err!("expected a sign at the end of a DECIMAL value"))?;
+
+    // the integer part has at least one digit
+    if digits.len() <= scale {
+        digits.insert_str(0, &"0".repeat(scale + 1 - digits.len()));
+    }
+
+    if scale > 0 {
+        digits.insert(digits.len() - scale, '.');
+    }
+
+    if negative {
+        digits.insert(0, '-');
+    }
+
+    Ok(digits)
+}
+
+fn get_u8(buf: &mut &[u8]) -> crate::Result<u8> {
+    let (&byte, rest) = buf
+        .split_first()
+        .ok_or_else(|| protocol_err!("unexpected end of X Protocol value"))?;
+
+    *buf = rest;
+
+    Ok(byte)
+}
+
+fn get_varint(buf: &mut &[u8]) -> crate::Result<u64> {
+    let mut value = 0_u64;
+
+    for shift in (0..64).step_by(7) {
+        let byte = get_u8(buf)?;
+        value |= u64::from(byte & 0x7F) << shift;
+
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+
+    Err(protocol_err!("invalid varint in X Protocol value").into())
+}
+
+fn get_array<A: Default + AsMut<[u8]>>(buf: &[u8]) -> crate::Result<A> {
+    let mut array = A::default();
+
+    if buf.len() != array.as_mut().len() {
+        return Err(protocol_err!("unexpected length of X Protocol value").into());
+    }
+
+    array.as_mut().copy_from_slice(buf);
+
+    Ok(array)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode_decimal, decode_set, decode_value, MySqlXColumn, MySqlXValue, BYTES, DATETIME,
+        DOUBLE, SINT, TIME,
+    };
+
+    fn column(type_id: u32, collation: u64, fractional_digits: u32) -> MySqlXColumn {
+        MySqlXColumn {
+            name: "c".into(),
+            type_id,
+            collation,
+            fractional_digits,
+            content_type: 0,
+        }
+    }
+
+    #[test]
+    fn it_decodes_numbers() {
+        assert_eq!(
+            decode_value(&column(SINT, 0, 0), &[0x03]).unwrap(),
+            MySqlXValue::Int(-2)
+        );
+        assert_eq!(
+            decode_value(&column(DOUBLE, 0, 0), &1.5_f64.to_le_bytes()).unwrap(),
+            MySqlXValue::Double(1.5)
+        );
+        assert_eq!(
+            decode_value(&column(SINT, 0, 0), &[]).unwrap(),
+            MySqlXValue::Null
+        );
+    }
+
+    #[test]
+    fn it_decodes_strings() {
+        assert_eq!(
+            decode_value(&column(BYTES, 255, 0), b"abc\0").unwrap(),
+            MySqlXValue::Text("abc".into())
+        );
+        assert_eq!(
+            decode_value(&column(BYTES, 255, 0), b"\0").unwrap(),
+            MySqlXValue::Text("".into())
+        );
+        assert_eq!(
+            decode_value(&column(BYTES, 63, 0), b"\x01\x02\0").unwrap(),
+            MySqlXValue::Bytes(vec![1, 2])
+        );
+
+        let json = MySqlXColumn {
+            content_type: 2,
+            ..column(BYTES, 63, 0)
+        };
+
+        assert_eq!(
+            decode_value(&json, b"{\"_id\": \"1\"}\0").unwrap(),
+            MySqlXValue::Text(r#"{"_id": "1"}"#.into())
+        );
+    }
+
+    #[test]
+    fn it_decodes_times() {
+        assert_eq!(
+            decode_value(&column(DATETIME, 0, 0), &[0xE4, 0x0F, 1, 31]).unwrap(),
+            MySqlXValue::Text("2020-01-31".into())
+        );
+        assert_eq!(
+            decode_value(
+                &column(DATETIME, 0, 1),
+                &[0xE4, 0x0F, 1, 31, 12, 0, 0, 0xA0, 0xC2, 0x1E]
+            )
+            .unwrap(),
+            MySqlXValue::Text("2020-01-31 12:00:00.5".into())
+        );
+        assert_eq!(
+            decode_value(&column(TIME, 0, 0), &[1, 0xC6, 0x06, 59, 59]).unwrap(),
+            MySqlXValue::Text("-838:59:59".into())
+        );
+    }
+
+    #[test]
+    fn it_decodes_decimals() {
+        assert_eq!(
+            decode_decimal(&[0x04, 0x12, 0x34, 0x01, 0xD0]).unwrap(),
+            "-12.3401"
+        );
+        assert_eq!(decode_decimal(&[0x02, 0x5C]).unwrap(), "0.05");
+        assert_eq!(decode_decimal(&[0x00, 0x12, 0x3C]).unwrap(), "123");
+        assert!(decode_decimal(&[0x02, 0x12]).is_err());
+    }
+
+    #[test]
+    fn it_decodes_sets() {
+        assert_eq!(decode_set(&[0x01]).unwrap(), "");
+        assert_eq!(decode_set(b"\x01a\x02bc").unwrap(), "a,bc");
+    }
+}
@@ -0,0 +1,125 @@
+use crate::mysql::protocol::TypeId;
+use crate::mysql::{MySqlData, MySqlTypeInfo};
+use crate::url::Url;
+
+// A zero date in the text protocol; a zero DATETIME or TIMESTAMP adds a zero time
+const ZERO_DATE: &[u8] = b"0000-00-00";
+
+// The sentinel that replaces zero dates, as with `zeroDateTimeBehavior=round` of JDBC
+const SENTINEL_TEXT_DATE: &[u8] = b"0001-01-01";
+const SENTINEL_TEXT_DATETIME: &[u8] = b"0001-01-01 00:00:00";
+
+// length : int<1>, year : int<2>, month : int<1>, day : int<1>
+const SENTINEL_BINARY: &[u8] = &[4, 1, 0, 1, 1];
+
+// How a zero date (`0000-00-00`), which is invalid in Rust, is decoded, from the
+// `zero-dates` parameter
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum ZeroDates {
+    // Decoding a zero date into a date type is an error
+    Error,
+
+    // A zero date is NULL
+    Null,
+
+    // A zero date is replaced with `0001-01-01` (at midnight)
+    Sentinel,
+}
+
+impl ZeroDates {
+    pub(crate) fn from_url(url: &Url) -> crate::Result<Self> {
+        match url.param("zero-dates").as_deref() {
+            Some("error") | None => Ok(ZeroDates::Error),
+            Some("null") => Ok(ZeroDates::Null),
+            Some("sentinel") => Ok(ZeroDates::Sentinel),
+
+            Some(value) => Err(protocol_err!("unknown `zero-dates` value: {:?}", value).into()),
+        }
+    }
+
+    // The value to decode in place of a value of a column; `None` if the value is NULL
+    pub(crate) fn apply<'c>(
+        self,
+        ty: &MySqlTypeInfo,
+        data: MySqlData<'c>,
+    ) -> Option<MySqlData<'c>> {
+        if self == ZeroDates::Error || !is_zero_date(ty, data) {
+            return Some(data);
+        }
+
+        match (self, data) {
+            (ZeroDates::Null, _) => None,
+
+            (_, MySqlData::Binary(_)) => Some(MySqlData::Binary(SENTINEL_BINARY)),
+            (_, MySqlData::Text(_)) if ty.id == TypeId::DATE => {
+                Some(MySqlData::Text(SENTINEL_TEXT_DATE))
+            }
+            (_, MySqlData::Text(_)) => Some(MySqlData::Text(SENTINEL_TEXT_DATETIME)),
+        }
+    }
+}
+
+fn is_zero_date(ty: &MySqlTypeInfo, data: MySqlData<'_>) -> bool {
+    match ty.id {
+        TypeId::DATE | TypeId::DATETIME | TypeId::TIMESTAMP => is_zero(data),
+        _ => false,
+    }
+}
+
+fn is_zero(data: MySqlData<'_>) -> bool {
+    match data {
+        // a date of all zeros is sent with a length of 0
+        MySqlData::Binary(buf) => buf.first() == Some(&0),
+        MySqlData::Text(buf) => buf.starts_with(ZERO_DATE),
+    }
+}
+
+// The error when decoding a zero date into a date type
+pub(crate) fn reject(data: MySqlData<'_>) -> crate::Result<()> {
+    if is_zero(data) {
+        return Err(decode_err!(
+            "zero date (0000-00-00) can not be decoded; set the `zero-dates` connection \
+             parameter to `null` or `sentinel` to decode it as NULL or as 0001-01-01"
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ZeroDates;
+    use crate::mysql::protocol::TypeId;
+    use crate::mysql::{MySqlData, MySqlTypeInfo};
+
+    #[test]
+    fn it_replaces_zero_dates() {
+        let date = MySqlTypeInfo::new(TypeId::DATE);
+        let datetime = MySqlTypeInfo::new(TypeId::DATETIME);
+        let text = MySqlTypeInfo::new(TypeId::VAR_CHAR);
+
+        let zero = MySqlData::Text(b"0000-00-00 00:00:00");
+
+        assert_eq!(ZeroDates::Error.apply(&datetime, zero), Some(zero));
+        assert_eq!(ZeroDates::Null.apply(&datetime, zero), None);
+        assert_eq!(
+            ZeroDates::Sentinel.apply(&datetime, zero),
+            Some(MySqlData::Text(b"0001-01-01 00:00:00"))
+        );
+
+        // a string that looks like a zero date is left alone
+        assert_eq!(ZeroDates::Null.apply(&text, zero), Some(zero));
+
+        let zero = MySqlData::Binary(&[0]);
+
+        assert_eq!(ZeroDates::Null.apply(&date, zero), None);
+        assert_eq!(
+            ZeroDates::Sentinel.apply(&date, zero),
+            Some(MySqlData::Binary(&[4, 1, 0, 1, 1]))
+        );
+
+        let not_zero = MySqlData::Binary(&[4, 0xda, 0x07, 10, 17]);
+
+        assert_eq!(ZeroDates::Null.apply(&date, not_zero), Some(not_zero));
+    }
+}
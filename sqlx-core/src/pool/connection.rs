@@ -6,6 +6,7 @@ use std::time::Instant;
 
 use super::inner::{DecrementSizeGuard, SharedPool};
 use crate::connection::{Connect, Connection};
+use crate::runtime::spawn;
 
 /// A connection checked out from [`Pool`][crate::pool::Pool].
 ///
@@ -97,9 +98,51 @@ where
     C: Connect,
 {
     fn drop(&mut self) {
-        if let Some(live) = self.live.take() {
+        let live = match self.live.take() {
+            Some(live) => live,
+            None => return,
+        };
+
+        // the connection holds something on the server that closing it releases
+        if !live.raw.is_reusable() {
+            let pool = Arc::clone(&self.pool);
+
+            spawn(async move {
+                let _ = live.float(&pool).into_idle().close().await;
+            });
+
+            return;
+        }
+
+        if self.pool.options().after_release.is_none() {
             self.pool.release(live.float(&self.pool));
+            return;
         }
+
+        // the callback is async, so the connection is released from a task
+        let pool = Arc::clone(&self.pool);
+
+        spawn(async move {
+            let mut floating = live.float(&pool);
+
+            let keep = match &pool.options().after_release {
+                Some(after_release) => after_release(floating.raw_mut()).await,
+                None => Ok(true),
+            };
+
+            match keep {
+                Ok(true) => pool.release(floating),
+
+                Ok(false) => {
+                    let _ = floating.into_idle().close().await;
+                }
+
+                Err(e) => {
+                    log::info!("after_release on connection returned error: {}", e);
+                    let _ = floating.into_idle().close().await;
+                }
+            }
+        });
     }
 }
 
@@ -111,6 +154,10 @@ impl<C> Live<C> {
         }
     }
 
+    pub fn raw_mut(&mut self) -> &mut C {
+        &mut self.raw
+    }
+
     pub fn into_idle(self) -> Idle<C> {
         Idle {
             live: self,
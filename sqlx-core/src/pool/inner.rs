@@ -1,9 +1,9 @@
 use std::cmp;
 use std::mem;
 use std::ptr;
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
-use std::sync::Arc;
-use std::time::Instant;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
 
 use crossbeam_queue::{ArrayQueue, SegQueue};
 use futures_core::task::{Poll, Waker};
@@ -17,22 +17,27 @@ use crate::{
 };
 
 use super::connection::{Floating, Idle, Live};
+use super::status::PoolStats;
 use super::Options;
 
+/// How often the pool checks that it has its minimum number of connections.
+const MIN_SIZE_PERIOD: Duration = Duration::from_secs(1);
+
 pub(crate) struct SharedPool<C> {
     url: String,
     idle_conns: ArrayQueue<Idle<C>>,
     waiters: SegQueue<Waker>,
     pub(super) size: AtomicU32,
     is_closed: AtomicBool,
-    options: Options,
+    options: Options<C>,
+    pub(super) stats: PoolStats,
 }
 
 impl<C> SharedPool<C>
 where
     C: Connection,
 {
-    pub fn options(&self) -> &Options {
+    pub fn options(&self) -> &Options<C> {
         &self.options
     }
 
@@ -93,7 +98,7 @@ where
             let new_size = self.size.compare_and_swap(size, size + 1, Ordering::AcqRel);
 
             if new_size == size {
-                return Some(DecrementSizeGuard::new(self));
+                return Some(DecrementSizeGuard::pending(self));
             }
 
             size = new_size;
@@ -108,6 +113,7 @@ where
     /// Returns an error if `deadline` elapses before we are woken.
     async fn wait_for_conn(&self, deadline: Instant) -> crate::Result<()> {
         let mut waker_pushed = false;
+        let _waiting = Waiting::new(&self.stats.waiters);
 
         timeout(
             deadline_as_timeout::<C::Database>(deadline)?,
@@ -132,29 +138,50 @@ impl<C> SharedPool<C>
 where
     C: Connect,
 {
-    pub(super) async fn new_arc(url: &str, options: Options) -> crate::Result<Arc<Self>> {
-        let mut pool = Self {
+    pub(super) async fn new_arc(url: &str, options: Options<C>) -> crate::Result<Arc<Self>> {
+        let pool = Self {
             url: url.to_owned(),
             idle_conns: ArrayQueue::new(options.max_size as usize),
             waiters: SegQueue::new(),
             size: AtomicU32::new(0),
             is_closed: AtomicBool::new(false),
             options,
+            stats: PoolStats::default(),
         };
 
-        pool.init_min_connections().await?;
+        pool.open_min_connections().await?;
 
         let pool = Arc::new(pool);
 
         spawn_reaper(&pool);
+        spawn_min_size_keeper(&pool);
 
         Ok(pool)
     }
 
     pub(super) async fn acquire<'s>(&'s self) -> crate::Result<Floating<'s, Live<C>>> {
         let start = Instant::now();
-        let deadline = start + self.options.connect_timeout;
+        let result = self
+            .acquire_until(start + self.options.connect_timeout)
+            .await;
+
+        match &result {
+            Ok(_) => self.stats.record_acquire_wait(start.elapsed()),
+
+            Err(Error::PoolTimedOut(_)) => {
+                self.stats.acquire_timeouts.fetch_add(1, Ordering::Relaxed);
+            }
+
+            Err(_) => {}
+        }
+
+        result
+    }
 
+    async fn acquire_until<'s>(
+        &'s self,
+        deadline: Instant,
+    ) -> crate::Result<Floating<'s, Live<C>>> {
         // Unless the pool has been closed ...
         while !self.is_closed() {
             // Attempt to immediately acquire a connection. This will return Some
@@ -184,20 +211,27 @@ where
         Err(Error::PoolClosed)
     }
 
-    // takes `&mut self` so this can only be called during init
-    async fn init_min_connections(&mut self) -> crate::Result<()> {
-        for _ in 0..self.options.min_size {
-            let deadline = Instant::now() + self.options.connect_timeout;
+    /// Open connections until the pool has at least `min_size` of them, as idle connections.
+    pub(super) async fn open_min_connections(&self) -> crate::Result<()> {
+        let deadline = Instant::now() + self.options.connect_timeout;
 
+        while self.size() < self.options.min_size {
             // this guard will prevent us from exceeding `max_size`
-            while let Some(guard) = self.try_increment_size() {
-                // [connect] will raise an error when past deadline
-                // [connect] returns None if its okay to retry
-                if let Some(conn) = self.connect(deadline, guard).await? {
-                    self.idle_conns
-                        .push(conn.into_idle().into_leakable())
-                        .expect("BUG: connection queue overflow in init_min_connections");
+            let guard = match self.try_increment_size() {
+                Some(guard) => guard,
+                None => break,
+            };
+
+            // [connect] will raise an error when past deadline
+            // [connect] returns None if its okay to retry
+            if let Some(conn) = self.connect(deadline, guard).await? {
+                // the pool may have been closed while we were connecting
+                if self.is_closed() {
+                    let _ = conn.into_idle().close().await;
+                    return Err(Error::PoolClosed);
                 }
+
+                self.release(conn);
             }
         }
 
@@ -215,10 +249,26 @@ where
 
         let timeout = super::deadline_as_timeout::<C::Database>(deadline)?;
 
+        let connect = async {
+            let mut raw = C::connect(&self.url).await?;
+
+            if let Some(on_connect) = &self.options.on_connect {
+                on_connect(&mut raw);
+            }
+
+            Ok(raw)
+        };
+
         // result here is `Result<Result<C, Error>, TimeoutError>`
-        match crate::runtime::timeout(timeout, C::connect(&self.url)).await {
+        match crate::runtime::timeout(timeout, connect).await {
             // successfully established connection
-            Ok(Ok(raw)) => Ok(Some(Floating::new_live(raw, guard))),
+            Ok(Ok(raw)) => {
+                self.stats
+                    .connections_created
+                    .fetch_add(1, Ordering::Relaxed);
+
+                Ok(Some(Floating::new_live(raw, guard.connected())))
+            }
 
             // an IO error while connecting is assumed to be the system starting up
             Ok(Err(crate::Error::Io(_))) => Ok(None),
@@ -241,14 +291,14 @@ where
 
 // NOTE: Function names here are bizzare. Helpful help would be appreciated.
 
-fn is_beyond_lifetime<C>(live: &Live<C>, options: &Options) -> bool {
+fn is_beyond_lifetime<C>(live: &Live<C>, options: &Options<C>) -> bool {
     // check if connection was within max lifetime (or not set)
     options
         .max_lifetime
         .map_or(false, |max| live.created.elapsed() > max)
 }
 
-fn is_beyond_idle<C>(idle: &Idle<C>, options: &Options) -> bool {
+fn is_beyond_idle<C>(idle: &Idle<C>, options: &Options<C>) -> bool {
     // if connection wasn't idle too long (or not set)
     options
         .idle_timeout
@@ -257,7 +307,7 @@ fn is_beyond_idle<C>(idle: &Idle<C>, options: &Options) -> bool {
 
 async fn check_conn<'s: 'p, 'p, C>(
     mut conn: Floating<'s, Idle<C>>,
-    options: &'p Options,
+    options: &'p Options<C>,
 ) -> Option<Floating<'s, Live<C>>>
 where
     C: Connection,
@@ -282,11 +332,30 @@ where
         }
     }
 
+    if let Some(before_acquire) = &options.before_acquire {
+        match before_acquire(conn.raw_mut()).await {
+            Ok(true) => {}
+
+            Ok(false) => {
+                let _ = conn.close().await;
+                return None;
+            }
+
+            Err(e) => {
+                log::info!("before_acquire on idle connection returned error: {}", e);
+                let _ = conn.close().await;
+                return None;
+            }
+        }
+    }
+
     // No need to re-connect; connection is alive or we don't care
     Some(conn.into_live())
 }
 
 /// if `max_lifetime` or `idle_timeout` is set, spawn a task that reaps senescent connections
+///
+/// The task only holds a weak reference to the pool, so that a dropped pool is freed.
 fn spawn_reaper<C>(pool: &Arc<SharedPool<C>>)
 where
     C: Connection,
@@ -299,10 +368,15 @@ where
         (None, None) => return,
     };
 
-    let pool = Arc::clone(&pool);
+    let pool: Weak<SharedPool<C>> = Arc::downgrade(pool);
 
     spawn(async move {
-        while !pool.is_closed.load(Ordering::Acquire) {
+        loop {
+            let pool = match pool.upgrade() {
+                Some(pool) if !pool.is_closed() => pool,
+                _ => break,
+            };
+
             // reap at most the current size minus the minimum idle
             let max_reaped = pool.size().saturating_sub(pool.options.min_size);
 
@@ -325,11 +399,48 @@ where
                 let _ = conn.close().await;
             }
 
+            // release the pool while sleeping, so that it can be dropped
+            drop(pool);
+
             sleep(period).await;
         }
     });
 }
 
+/// if `min_size` is set, spawn a task that replaces the connections that were closed
+///
+/// Like the reaper, the task only holds a weak reference and exits once the pool is dropped.
+fn spawn_min_size_keeper<C>(pool: &Arc<SharedPool<C>>)
+where
+    C: Connect,
+{
+    if pool.options.min_size == 0 {
+        return;
+    }
+
+    let pool: Weak<SharedPool<C>> = Arc::downgrade(pool);
+
+    spawn(async move {
+        loop {
+            match pool.upgrade() {
+                Some(pool) if !pool.is_closed() => {
+                    if let Err(e) = pool.open_min_connections().await {
+                        if pool.is_closed() {
+                            break;
+                        }
+
+                        log::warn!("failed to open the minimum connections of the pool: {}", e);
+                    }
+                }
+
+                _ => break,
+            }
+
+            sleep(MIN_SIZE_PERIOD).await;
+        }
+    });
+}
+
 /// RAII guard returned by `Pool::try_increment_size()` and others.
 ///
 /// Will decrement the pool size if dropped, to avoid semantically "leaking" connections
@@ -337,6 +448,10 @@ where
 pub(in crate::pool) struct DecrementSizeGuard<'a> {
     size: &'a AtomicU32,
     waiters: &'a SegQueue<Waker>,
+    closed: &'a AtomicU64,
+    // `false` while the connection is being opened, so that a failed attempt is not counted
+    // as a closed connection
+    connection: bool,
     dropped: bool,
 }
 
@@ -345,10 +460,29 @@ impl<'a> DecrementSizeGuard<'a> {
         Self {
             size: &pool.size,
             waiters: &pool.waiters,
+            closed: &pool.stats.connections_closed,
+            connection: true,
+            dropped: false,
+        }
+    }
+
+    /// A guard for a connection that is not opened yet.
+    pub fn pending<C>(pool: &'a SharedPool<C>) -> Self {
+        Self {
+            size: &pool.size,
+            waiters: &pool.waiters,
+            closed: &pool.stats.connections_closed,
+            connection: false,
             dropped: false,
         }
     }
 
+    /// Mark the connection of a [`pending`](#method.pending) guard as opened.
+    pub fn connected(mut self) -> Self {
+        self.connection = true;
+        self
+    }
+
     /// Return `true` if the internal references point to the same fields in `SharedPool`.
     pub fn same_pool<C>(&self, pool: &'a SharedPool<C>) -> bool {
         ptr::eq(self.size, &pool.size) && ptr::eq(self.waiters, &pool.waiters)
@@ -364,8 +498,27 @@ impl Drop for DecrementSizeGuard<'_> {
         assert!(!self.dropped, "double-dropped!");
         self.dropped = true;
         self.size.fetch_sub(1, Ordering::SeqCst);
+        if self.connection {
+            self.closed.fetch_add(1, Ordering::Relaxed);
+        }
         if let Ok(waker) = self.waiters.pop() {
             waker.wake();
         }
     }
 }
+
+/// RAII guard that counts a call to `Pool::acquire()` waiting for a connection.
+struct Waiting<'a>(&'a AtomicUsize);
+
+impl<'a> Waiting<'a> {
+    fn new(waiters: &'a AtomicUsize) -> Self {
+        waiters.fetch_add(1, Ordering::AcqRel);
+        Self(waiters)
+    }
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
@@ -8,7 +8,7 @@ use std::{
 
 use crate::connection::Connect;
 use crate::database::Database;
-use crate::transaction::Transaction;
+use crate::transaction::{Transaction, TransactionOptions};
 
 use self::inner::SharedPool;
 use self::options::Options;
@@ -17,9 +17,13 @@ mod connection;
 mod executor;
 mod inner;
 mod options;
+mod retry;
+mod status;
 
 pub use self::connection::PoolConnection;
 pub use self::options::Builder;
+pub use self::retry::RetryPolicy;
+pub use self::status::PoolStatus;
 
 /// A pool of database connections.
 pub struct Pool<C>(pub(crate) Arc<SharedPool<C>>);
@@ -39,7 +43,7 @@ where
         Self::builder().build(url).await
     }
 
-    async fn with_options(url: &str, options: Options) -> crate::Result<Self> {
+    async fn with_options(url: &str, options: Options<C>) -> crate::Result<Self> {
         let inner = SharedPool::<C>::new_arc(url, options).await?;
 
         Ok(Pool(inner))
@@ -69,6 +73,26 @@ where
         Ok(Transaction::new(0, self.acquire().await?).await?)
     }
 
+    /// Retrieves a new connection and immediately begins a new transaction with the
+    /// given options.
+    pub async fn begin_with(
+        &self,
+        options: TransactionOptions,
+    ) -> crate::Result<Transaction<PoolConnection<C>>> {
+        Transaction::with_options(self.acquire().await?, options).await
+    }
+
+    /// Opens connections until the pool has at least [`min_size`] of them, e.g. to have them
+    /// ready before serving traffic again after the database was restarted.
+    ///
+    /// The pool opens them as it is built, and in the background as connections are closed;
+    /// this waits for them, and returns an error if a connection fails to open.
+    ///
+    /// [`min_size`]: struct.Builder.html#method.min_size
+    pub async fn warm_up(&self) -> crate::Result<()> {
+        self.0.open_min_connections().await
+    }
+
     /// Ends the use of a connection pool. Prevents any new connections
     /// and will close all active connections when they are returned to the pool.
     ///
@@ -1,4 +1,6 @@
-use std::{marker::PhantomData, time::Duration};
+use std::{fmt, marker::PhantomData, time::Duration};
+
+use futures_core::future::BoxFuture;
 
 use super::Pool;
 use crate::connection::Connect;
@@ -7,7 +9,7 @@ use crate::database::Database;
 /// Builder for [Pool].
 pub struct Builder<C> {
     phantom: PhantomData<C>,
-    options: Options,
+    options: Options<C>,
 }
 
 impl<C> Builder<C>
@@ -35,6 +37,9 @@ where
                 idle_timeout: None,
                 // If true, test the health of a connection on acquire
                 test_on_acquire: true,
+                on_connect: None,
+                before_acquire: None,
+                after_release: None,
             },
         }
     }
@@ -57,8 +62,9 @@ where
     ///
     /// When the pool is built, this many connections will be automatically spun up.
     ///
-    /// If any connection is reaped by [`max_lifetime`] or [`idle_timeout`] and it brings
-    /// the connection count below this amount, a new connection will be opened to replace it.
+    /// If any connection is closed (e.g. reaped by [`max_lifetime`] or [`idle_timeout`], or
+    /// found broken) and it brings the connection count below this amount, a new connection
+    /// will be opened in the background to replace it, within about a second.
     ///
     /// [`max_lifetime`]: #method.max_lifetime
     /// [`idle_timeout`]: #method.idle_timeout
@@ -107,6 +113,68 @@ where
         self
     }
 
+    /// Set a callback to run on each idle connection before it is returned from
+    /// [`Pool::acquire`], e.g. to check its health or state. If it returns `Ok(false)` or an
+    /// error, the connection is closed and another one is acquired.
+    ///
+    /// It runs after [`test_on_acquire`](#method.test_on_acquire), and not on new connections.
+    ///
+    /// ```rust,ignore
+    /// let pool = PgPool::builder()
+    ///     .before_acquire(|conn| Box::pin(async move {
+    ///         let (in_recovery,): (bool,) = sqlx::query_as("SELECT pg_is_in_recovery()")
+    ///             .fetch_one(conn)
+    ///             .await?;
+    ///
+    ///         Ok(!in_recovery)
+    ///     }))
+    ///     .build(&url)
+    ///     .await?;
+    /// ```
+    pub fn before_acquire<F>(mut self, callback: F) -> Self
+    where
+        F: for<'c> Fn(&'c mut C) -> BoxFuture<'c, crate::Result<bool>> + Send + Sync + 'static,
+    {
+        self.options.before_acquire = Some(Box::new(callback));
+        self
+    }
+
+    /// Set a callback to run on each connection as it is returned to the pool, e.g. to reset
+    /// the session or to detect leaked state. If it returns `Ok(false)` or an error, the
+    /// connection is closed instead.
+    ///
+    /// As the connection is returned when a [`PoolConnection`] is dropped, the callback runs
+    /// in a new task; the connection is not idle until it is done.
+    ///
+    /// ```rust,ignore
+    /// let pool = PgPool::builder()
+    ///     .after_release(|conn| Box::pin(async move {
+    ///         conn.execute("DISCARD ALL").await?;
+    ///         Ok(true)
+    ///     }))
+    ///     .build(&url)
+    ///     .await?;
+    /// ```
+    ///
+    /// [`PoolConnection`]: struct.PoolConnection.html
+    pub fn after_release<F>(mut self, callback: F) -> Self
+    where
+        F: for<'c> Fn(&'c mut C) -> BoxFuture<'c, crate::Result<bool>> + Send + Sync + 'static,
+    {
+        self.options.after_release = Some(Box::new(callback));
+        self
+    }
+
+    // Set up each new connection before it is first used; set by the builder methods of
+    // the drivers, e.g. to share a cache
+    pub(crate) fn on_connect<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&mut C) + Send + Sync + 'static,
+    {
+        self.options.on_connect = Some(Box::new(callback));
+        self
+    }
+
     /// Spin up the connection pool.
     ///
     /// If [`min_size`] was set to a non-zero value, that many connections will be immediately
@@ -131,12 +199,36 @@ where
     }
 }
 
-#[derive(Debug)]
-pub(crate) struct Options {
+pub(crate) type OnConnect<C> = Box<dyn Fn(&mut C) + Send + Sync + 'static>;
+
+// Decides whether to keep a connection; see [Builder::before_acquire] and [Builder::after_release]
+pub(crate) type ConnectionCheck<C> =
+    Box<dyn for<'c> Fn(&'c mut C) -> BoxFuture<'c, crate::Result<bool>> + Send + Sync + 'static>;
+
+pub(crate) struct Options<C> {
     pub max_size: u32,
     pub connect_timeout: Duration,
     pub min_size: u32,
     pub max_lifetime: Option<Duration>,
     pub idle_timeout: Option<Duration>,
     pub test_on_acquire: bool,
+    pub on_connect: Option<OnConnect<C>>,
+    pub before_acquire: Option<ConnectionCheck<C>>,
+    pub after_release: Option<ConnectionCheck<C>>,
+}
+
+impl<C> fmt::Debug for Options<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Options")
+            .field("max_size", &self.max_size)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("min_size", &self.min_size)
+            .field("max_lifetime", &self.max_lifetime)
+            .field("idle_timeout", &self.idle_timeout)
+            .field("test_on_acquire", &self.test_on_acquire)
+            .field("on_connect", &self.on_connect.is_some())
+            .field("before_acquire", &self.before_acquire.is_some())
+            .field("after_release", &self.after_release.is_some())
+            .finish()
+    }
 }
@@ -0,0 +1,176 @@
+use std::time::Duration;
+
+use futures_core::future::BoxFuture;
+
+use super::{Pool, PoolConnection};
+use crate::connection::Connect;
+use crate::runtime::sleep;
+use crate::transaction::Transaction;
+
+/// How [`Pool::transaction_with_retry`] retries a transaction: how many times, and how long
+/// to wait before each retry.
+///
+/// The wait doubles after each retry, from [`initial_backoff`] up to [`max_backoff`].
+///
+/// [`initial_backoff`]: #method.initial_backoff
+/// [`max_backoff`]: #method.max_backoff
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy of 5 attempts, waiting 10 milliseconds before the first retry and at most
+    /// 1 second before any retry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how many times the transaction is run, at most, including the first time.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set how long to wait before the first retry.
+    pub fn initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Set how long to wait, at most, before any retry.
+    pub fn max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    // How long to wait before the given retry, the first being 1
+    fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2_u32.saturating_pow(retry - 1);
+
+        self.initial_backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+}
+
+impl<C> Pool<C>
+where
+    C: Connect,
+{
+    /// Run `f` in a transaction and commit it, running it again in a new transaction if
+    /// the database rolled it back because of concurrent transactions.
+    ///
+    /// A transaction is retried if it fails with a serialization failure (SQLSTATE `40001`,
+    /// which includes a MySQL deadlock, `1213`) or a Postgres deadlock (`40P01`), until
+    /// the `policy` runs out of attempts. Any other error is returned right away, after
+    /// rolling back the transaction.
+    ///
+    /// `f` must return a boxed future, which can borrow the transaction:
+    ///
+    /// ```rust,ignore
+    /// let balance = pool
+    ///     .transaction_with_retry(&RetryPolicy::new(), |tx| {
+    ///         Box::pin(async move {
+    ///             tx.execute("SET TRANSACTION ISOLATION LEVEL SERIALIZABLE").await?;
+    ///
+    ///             let (balance,): (i64,) = sqlx::query_as("SELECT balance FROM accounts")
+    ///                 .fetch_one(&mut *tx)
+    ///                 .await?;
+    ///
+    ///             Ok(balance)
+    ///         })
+    ///     })
+    ///     .await?;
+    /// ```
+    pub async fn transaction_with_retry<F, T>(
+        &self,
+        policy: &RetryPolicy,
+        mut f: F,
+    ) -> crate::Result<T>
+    where
+        F: for<'t> FnMut(&'t mut Transaction<PoolConnection<C>>) -> BoxFuture<'t, crate::Result<T>>,
+    {
+        let mut attempt = 1;
+
+        loop {
+            let error = match self.run_transaction(&mut f).await {
+                Ok(value) => return Ok(value),
+                Err(error) => error,
+            };
+
+            if attempt >= policy.max_attempts || !is_retryable(&error) {
+                return Err(error);
+            }
+
+            log::debug!("retrying transaction (attempt {}): {}", attempt + 1, error);
+
+            sleep(policy.backoff(attempt)).await;
+
+            attempt += 1;
+        }
+    }
+
+    async fn run_transaction<F, T>(&self, f: &mut F) -> crate::Result<T>
+    where
+        F: for<'t> FnMut(&'t mut Transaction<PoolConnection<C>>) -> BoxFuture<'t, crate::Result<T>>,
+    {
+        let mut tx = self.begin().await?;
+
+        match f(&mut tx).await {
+            Ok(value) => {
+                tx.commit().await?;
+
+                Ok(value)
+            }
+
+            Err(error) => {
+                // roll back explicitly, to return the connection to the pool
+                let _ = tx.rollback().await;
+
+                Err(error)
+            }
+        }
+    }
+}
+
+// Returns `true` for the errors that mean a transaction was rolled back because of
+// concurrent transactions, and would likely succeed if run again
+fn is_retryable(error: &crate::Error) -> bool {
+    match error {
+        crate::Error::Database(error) => matches!(error.code(), Some("40001") | Some("40P01")),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::RetryPolicy;
+
+    #[test]
+    fn it_backs_off_exponentially() {
+        let policy = RetryPolicy::new()
+            .initial_backoff(Duration::from_millis(100))
+            .max_backoff(Duration::from_millis(500));
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(4), Duration::from_millis(500));
+        assert_eq!(policy.backoff(100), Duration::from_millis(500));
+    }
+}
@@ -0,0 +1,143 @@
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+
+use super::Pool;
+use crate::connection::Connect;
+
+// The upper bounds of the buckets of the histogram of acquire waits; the last bucket has none
+const ACQUIRE_WAIT_BOUNDS: [Duration; 5] = [
+    Duration::from_millis(1),
+    Duration::from_millis(10),
+    Duration::from_millis(100),
+    Duration::from_secs(1),
+    Duration::from_secs(10),
+];
+
+/// The state of a [`Pool`], and counters since it was built, to export to a metrics system.
+///
+/// Returned by [`Pool::status`]. The numbers are read one at a time while the pool is in use,
+/// so they may not add up exactly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PoolStatus {
+    /// The number of connections of the pool, including those being opened.
+    pub size: u32,
+
+    /// The number of idle connections.
+    pub idle: usize,
+
+    /// The number of connections checked out of the pool.
+    pub in_use: u32,
+
+    /// The number of calls to [`Pool::acquire`] waiting for a connection.
+    pub waiters: usize,
+
+    /// The number of connections opened.
+    pub connections_created: u64,
+
+    /// The number of connections closed, e.g. after their [`max_lifetime`], or because
+    /// they were broken.
+    ///
+    /// [`max_lifetime`]: struct.Builder.html#method.max_lifetime
+    pub connections_closed: u64,
+
+    /// The number of calls to [`Pool::acquire`] that timed out.
+    pub acquire_timeouts: u64,
+
+    /// The number of calls to [`Pool::acquire`] that returned a connection, by how long they
+    /// waited for it: each bucket has its upper bound (exclusive), with the last one
+    /// unbounded, and the count of calls that waited for less than the bound but not less
+    /// than the one of the previous bucket.
+    pub acquire_wait: Vec<(Option<Duration>, u64)>,
+}
+
+// The counters of a pool
+#[derive(Default)]
+pub(super) struct PoolStats {
+    pub(super) waiters: AtomicUsize,
+    pub(super) connections_created: AtomicU64,
+    pub(super) connections_closed: AtomicU64,
+    pub(super) acquire_timeouts: AtomicU64,
+    acquire_wait: [AtomicU64; ACQUIRE_WAIT_BOUNDS.len() + 1],
+}
+
+impl PoolStats {
+    pub(super) fn record_acquire_wait(&self, wait: Duration) {
+        let bucket = ACQUIRE_WAIT_BOUNDS
+            .iter()
+            .position(|bound| wait < *bound)
+            .unwrap_or(ACQUIRE_WAIT_BOUNDS.len());
+
+        self.acquire_wait[bucket].fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn acquire_wait(&self) -> Vec<(Option<Duration>, u64)> {
+        let bounds = ACQUIRE_WAIT_BOUNDS.iter().copied().map(Some);
+
+        bounds
+            .chain(Some(None))
+            .zip(&self.acquire_wait)
+            .map(|(bound, count)| (bound, count.load(Ordering::Relaxed)))
+            .collect()
+    }
+}
+
+impl<C> Pool<C>
+where
+    C: Connect,
+{
+    /// Returns the state of the pool, and counters since it was built, e.g. to export them
+    /// to a metrics system.
+    ///
+    /// ```rust,ignore
+    /// let status = pool.status();
+    ///
+    /// gauge!("db.pool.in_use", status.in_use as f64);
+    /// counter!("db.pool.acquire_timeouts", status.acquire_timeouts);
+    /// ```
+    pub fn status(&self) -> PoolStatus {
+        let stats = &self.0.stats;
+        let size = self.0.size();
+        let idle = self.0.num_idle();
+
+        PoolStatus {
+            size,
+            idle,
+            in_use: size.saturating_sub(idle as u32),
+            waiters: stats.waiters.load(Ordering::Acquire),
+            connections_created: stats.connections_created.load(Ordering::Relaxed),
+            connections_closed: stats.connections_closed.load(Ordering::Relaxed),
+            acquire_timeouts: stats.acquire_timeouts.load(Ordering::Relaxed),
+            acquire_wait: stats.acquire_wait(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::PoolStats;
+
+    #[test]
+    fn it_buckets_acquire_waits() {
+        let stats = PoolStats::default();
+
+        stats.record_acquire_wait(Duration::from_micros(10));
+        stats.record_acquire_wait(Duration::from_millis(1));
+        stats.record_acquire_wait(Duration::from_millis(5));
+        stats.record_acquire_wait(Duration::from_secs(60));
+
+        assert_eq!(
+            stats.acquire_wait(),
+            vec![
+                (Some(Duration::from_millis(1)), 1),
+                (Some(Duration::from_millis(10)), 2),
+                (Some(Duration::from_millis(100)), 0),
+                (Some(Duration::from_secs(1)), 0),
+                (Some(Duration::from_secs(10)), 0),
+                (None, 1),
+            ]
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use std::borrow::BorrowMut;
+use std::fmt::{self, Display};
+use std::ops::{Deref, DerefMut};
+
+use sha2::{Digest, Sha256};
+
+use crate::executor::Executor;
+use crate::postgres::{PgConnection, PgQueryAs};
+use crate::query_as::query_as;
+
+/// A session-level [advisory lock](https://www.postgresql.org/docs/12/explicit-locking.html#ADVISORY-LOCKS).
+///
+/// Advisory locks have no meaning to Postgres itself; they are used by applications to
+/// coordinate access to some resource across connections (e.g., to only run database
+/// migrations from a single process at a time).
+///
+/// The lock is acquired on a connection, which is held by the returned
+/// [PgAdvisoryLockGuard] until the lock is released. A session-level lock outlives any
+/// transaction, and so would be kept by a connection returned to a pool; when the guard is
+/// dropped, the unlock is queued on the connection and sent before its next query, and a
+/// pooled connection is closed instead of being returned to the pool with the lock held.
+///
+/// ```rust,ignore
+/// let lock = PgAdvisoryLock::new("my-app-migrations");
+///
+/// let mut guard = lock.acquire(pool.acquire().await?).await?;
+///
+/// // the guard dereferences to the connection
+/// guard.execute("...").await?;
+///
+/// // release the lock and return the connection
+/// let conn = guard.release_now().await?;
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgAdvisoryLock {
+    key: PgAdvisoryLockKey,
+}
+
+/// The key of a [PgAdvisoryLock].
+///
+/// Postgres has two separate key spaces: a single 64-bit integer or two 32-bit integers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PgAdvisoryLockKey {
+    BigInt(i64),
+    IntPair(i32, i32),
+}
+
+/// A held [PgAdvisoryLock].
+///
+/// Dereferences to the connection the lock was acquired on. The lock is released when
+/// the guard is dropped, before the next query on the connection is run; if the connection
+/// was checked out from a pool, it is closed instead of being returned to the pool, which
+/// releases the lock. Use [PgAdvisoryLockGuard::release_now] to keep the connection.
+pub struct PgAdvisoryLockGuard<'lock, C>
+where
+    C: BorrowMut<PgConnection>,
+{
+    lock: &'lock PgAdvisoryLock,
+    conn: Option<C>,
+}
+
+impl PgAdvisoryLock {
+    /// Create an advisory lock identified by `name`.
+    ///
+    /// The key is derived from the first 8 bytes of the SHA-256 hash of the name.
+    pub fn new(name: impl AsRef<str>) -> Self {
+        let hash = Sha256::digest(name.as_ref().as_bytes());
+
+        let mut key = [0_u8; 8];
+        key.copy_from_slice(&hash[..8]);
+
+        Self::with_key(PgAdvisoryLockKey::BigInt(i64::from_le_bytes(key)))
+    }
+
+    /// Create an advisory lock with an explicit key.
+    pub fn with_key(key: PgAdvisoryLockKey) -> Self {
+        Self { key }
+    }
+
+    /// Returns the key of this lock.
+    pub fn key(&self) -> PgAdvisoryLockKey {
+        self.key
+    }
+
+    /// Acquire the lock on the given connection, waiting until it is released by any
+    /// other session holding it.
+    ///
+    /// `conn` can be a `&mut PgConnection` or a connection checked out from a pool.
+    pub async fn acquire<C>(&self, mut conn: C) -> crate::Result<PgAdvisoryLockGuard<'_, C>>
+    where
+        C: BorrowMut<PgConnection>,
+    {
+        conn.borrow_mut()
+            .execute(&*format!("SELECT pg_advisory_lock({})", self.key))
+            .await?;
+
+        Ok(PgAdvisoryLockGuard::new(self, conn))
+    }
+
+    /// Try to acquire the lock on the given connection without waiting.
+    ///
+    /// Returns the connection back, as `Ok(Err(conn))`, if the lock is held by another session.
+    pub async fn try_acquire<C>(
+        &self,
+        mut conn: C,
+    ) -> crate::Result<Result<PgAdvisoryLockGuard<'_, C>, C>>
+    where
+        C: BorrowMut<PgConnection>,
+    {
+        let (locked,): (bool,) = query_as(&format!("SELECT pg_try_advisory_lock({})", self.key))
+            .fetch_one(conn.borrow_mut())
+            .await?;
+
+        Ok(if locked {
+            Ok(PgAdvisoryLockGuard::new(self, conn))
+        } else {
+            Err(conn)
+        })
+    }
+}
+
+impl Display for PgAdvisoryLockKey {
+    // Formats the key as arguments to the `pg_advisory_*` functions
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PgAdvisoryLockKey::BigInt(key) => write!(f, "{}", key),
+            PgAdvisoryLockKey::IntPair(key1, key2) => write!(f, "{}, {}", key1, key2),
+        }
+    }
+}
+
+impl<'lock, C> PgAdvisoryLockGuard<'lock, C>
+where
+    C: BorrowMut<PgConnection>,
+{
+    fn new(lock: &'lock PgAdvisoryLock, conn: C) -> Self {
+        Self {
+            lock,
+            conn: Some(conn),
+        }
+    }
+
+    /// Release the lock immediately and return the connection.
+    pub async fn release_now(mut self) -> crate::Result<C> {
+        let mut conn = self.conn.take().expect(ERR_RELEASED);
+
+        let (released,): (bool,) =
+            query_as(&format!("SELECT pg_advisory_unlock({})", self.lock.key))
+                .fetch_one(conn.borrow_mut())
+                .await?;
+
+        if !released {
+            // the lock was released by the application behind our back
+            return Err(protocol_err!(
+                "advisory lock ({}) was not held by this session",
+                self.lock.key
+            )
+            .into());
+        }
+
+        Ok(conn)
+    }
+
+    /// Return the connection *without* releasing the lock.
+    ///
+    /// The lock will be held until it is released manually (with `pg_advisory_unlock`) or the
+    /// connection is closed.
+    pub fn leak(mut self) -> C {
+        self.conn.take().expect(ERR_RELEASED)
+    }
+}
+
+const ERR_RELEASED: &str = "(bug) advisory lock guard already released";
+
+impl<C> Deref for PgAdvisoryLockGuard<'_, C>
+where
+    C: BorrowMut<PgConnection>,
+{
+    type Target = PgConnection;
+
+    fn deref(&self) -> &Self::Target {
+        self.conn.as_ref().expect(ERR_RELEASED).borrow()
+    }
+}
+
+impl<C> DerefMut for PgAdvisoryLockGuard<'_, C>
+where
+    C: BorrowMut<PgConnection>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.conn.as_mut().expect(ERR_RELEASED).borrow_mut()
+    }
+}
+
+impl<C> Drop for PgAdvisoryLockGuard<'_, C>
+where
+    C: BorrowMut<PgConnection>,
+{
+    fn drop(&mut self) {
+        if let Some(mut conn) = self.conn.take() {
+            let conn = conn.borrow_mut();
+
+            conn.queue_query(&format!("SELECT pg_advisory_unlock({})", self.lock.key));
+            conn.pending_unlock = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PgAdvisoryLock, PgAdvisoryLockKey};
+
+    #[test]
+    fn it_derives_key_from_name() {
+        let lock = PgAdvisoryLock::new("sqlx");
+
+        assert_eq!(lock, PgAdvisoryLock::new("sqlx"));
+        assert_ne!(lock, PgAdvisoryLock::new("sqlx-2"));
+    }
+
+    #[test]
+    fn it_formats_key_as_arguments() {
+        assert_eq!(PgAdvisoryLockKey::BigInt(-5).to_string(), "-5");
+        assert_eq!(PgAdvisoryLockKey::IntPair(1, 2).to_string(), "1, 2");
+    }
+}
@@ -38,6 +38,31 @@ impl PgRawBuffer {
 
         Ok(())
     }
+
+    // Patch all remembered type holes with OIDs that were already looked up, for when
+    // Postgres can not be asked, e.g. in the middle of a `COPY`
+    pub(crate) fn patch_cached_type_holes(
+        &mut self,
+        connection: &mut PgConnection,
+    ) -> crate::Result<()> {
+        for (offset, name) in &self.type_holes {
+            let oid = match connection.cached_type_id(name) {
+                Some(oid) => oid,
+
+                None => {
+                    return Err(protocol_err!(
+                        "the OID of type {:?} is not known and can not be looked up during a COPY",
+                        &**name
+                    )
+                    .into());
+                }
+            };
+
+            NetworkEndian::write_u32(&mut self.inner[*offset..], oid);
+        }
+
+        Ok(())
+    }
 }
 
 impl Deref for PgRawBuffer {
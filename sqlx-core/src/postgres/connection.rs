@@ -7,16 +7,22 @@ use futures_core::future::BoxFuture;
 use futures_util::TryFutureExt;
 
 use crate::connection::{Connect, Connection};
+use crate::cursor::Cursor;
 use crate::executor::Executor;
+use crate::row::Row;
 
+#[cfg(feature = "gssapi")]
+use crate::postgres::gss;
 use crate::postgres::protocol::{
     Authentication, AuthenticationMd5, AuthenticationSasl, BackendKeyData, Message,
     PasswordMessage, StartupMessage, StatementId, Terminate,
 };
 use crate::postgres::row::Statement;
+use crate::postgres::sasl::{ChannelBinding, ChannelBindingMode};
+use crate::postgres::statement_cache::StatementCache;
 use crate::postgres::stream::PgStream;
 use crate::postgres::type_info::SharedStr;
-use crate::postgres::{sasl, tls};
+use crate::postgres::{sasl, srv, stream, tls, PgTypeCache};
 use crate::url::Url;
 
 /// An asynchronous connection to a [Postgres](struct.Postgres.html) database.
@@ -25,6 +31,61 @@ use crate::url::Url;
 /// string, as documented at
 /// <https://www.postgresql.org/docs/12/libpq-connect.html#LIBPQ-CONNSTRING>
 ///
+/// ### Multiple Hosts
+/// Like with `libpq`, several hosts may be given, each with an optional port; they are tried in
+/// order until a connection succeeds:
+///
+/// ```text
+/// postgresql://<host1>[:<port1>],<host2>[:<port2>]/<database>[?target_session_attrs=<attrs>]
+/// ```
+///
+/// The `target_session_attrs` query parameter skips servers that are not of the wanted kind:
+/// `any` (the default), `read-write`, `read-only`, `primary` or `standby`.
+///
+/// ### DNS SRV Records
+/// With the `srv=true` query parameter, the host is the name of the SRV records of the service
+/// rather than of a server, as in Consul or in Kubernetes. The servers of the records are
+/// tried in order of priority, and at random by weight among those of the same priority:
+///
+/// ```text
+/// postgresql://<user>@_postgresql._tcp.db.service.consul/<database>?srv=true
+/// ```
+///
+/// The records, like the addresses of a host, are looked up again whenever a connection is
+/// opened and are never cached, so that a pool follows servers as they move. Set the
+/// `max_lifetime` of the pool to also replace the connections that are already open.
+///
+/// The nameservers are read from `/etc/resolv.conf`; the name must be fully qualified.
+///
+/// ### Unix Domain Sockets
+/// Like with `libpq`, a host that is an absolute path is the directory of a Unix domain
+/// socket, which is named after the port (`.s.PGSQL.5432`). It may be given percent-encoded
+/// or as the `host` query parameter, with or without a user:
+///
+/// ```text
+/// postgresql://%2Fvar%2Frun%2Fpostgresql/<database>
+/// postgresql://<user>@/<database>?host=/var/run/postgresql
+/// ```
+///
+/// If no host is given, the socket is looked for in `/var/run/postgresql`, then in `/tmp`.
+/// TLS is not used over a socket, whatever the `sslmode`.
+///
+/// With `peer` authentication (or `ident`, over a socket), the server only accepts the user
+/// that the client runs as. The user defaults to the `USER` (or `LOGNAME`) environment
+/// variable, so it can be left out of the connection string.
+///
+/// ### Session Parameters
+/// Like with `libpq`, the `options` query parameter sets run-time parameters for the session
+/// when connecting, as command-line options of the server. Every connection opened from the
+/// same connection string, e.g. by a pool, is configured the same way:
+///
+/// ```text
+/// postgresql://<host>/<database>?options=-c%20statement_timeout%3D5s%20-c%20search_path%3Dapp
+/// ```
+///
+/// The options are separated by spaces; a space in a value must be escaped with a backslash.
+/// See <https://www.postgresql.org/docs/12/libpq-connect.html#LIBPQ-CONNECT-OPTIONS>.
+///
 /// ### TLS Support (requires `tls` feature)
 /// This connection type supports the same `sslmode` query parameter that `libpq` does in
 /// connection strings: <https://www.postgresql.org/docs/12/libpq-ssl.html>
@@ -79,48 +140,95 @@ use crate::url::Url;
 /// `sslmode=verify-ca`, the hostname in the connection string will be verified
 /// against the hostname in the server certificate, so they must be the same for the TLS
 /// upgrade to succeed.
+///
+/// ### Channel Binding
+/// When authenticating with SCRAM over TLS, the authentication is bound to the TLS connection
+/// (`SCRAM-SHA-256-PLUS`) if the server supports it. Like in `libpq`, this is controlled with
+/// the `channel_binding` query parameter: `disable`, `prefer` (the default) or `require`, which
+/// fails to connect if channel binding can not be used, including when the server asks for
+/// another authentication method (e.g. a cleartext or MD5 password) or for none at all.
+///
+/// ### GSSAPI (requires `gssapi` feature)
+/// If the server requests GSSAPI (Kerberos) authentication, the credentials are taken from the
+/// default credentials cache (e.g., as populated by `kinit`). The Kerberos service name defaults
+/// to `postgres` and can be changed with the `krbsrvname` query parameter. Over a Unix domain
+/// socket, the service principal is for the name of the local host.
 pub struct PgConnection {
     pub(super) stream: PgStream,
     pub(super) next_statement_id: u32,
     pub(super) is_ready: bool,
 
+    // [ReadyForQuery] messages still to be received, in addition to the one
+    // indicated by `is_ready`, for queries queued by dropped handles
+    pub(super) pending_ready_for_query: usize,
+
+    // Set while the unlock of a dropped [PgAdvisoryLockGuard] is queued; the lock is still
+    // held until it is sent
+    pub(super) pending_unlock: bool,
+
     // cache query -> statement ID
     pub(super) cache_statement_id: HashMap<Box<str>, StatementId>,
 
     // cache statement ID -> statement description
     pub(super) cache_statement: HashMap<StatementId, Arc<Statement>>,
 
+    // configuration and statistics of the statement cache
+    pub(super) statement_cache: StatementCache,
+
     // cache type name -> type OID
     pub(super) cache_type_oid: HashMap<SharedStr, u32>,
 
     // cache type OID -> type name
     pub(super) cache_type_name: HashMap<u32, SharedStr>,
 
+    // cache domain type OID -> base type OID
+    pub(super) cache_type_base: HashMap<u32, u32>,
+
+    // type cache shared with other connections, see `PgTypeCache`
+    pub(super) type_cache: Option<PgTypeCache>,
+
     // Work buffer for the value ranges of the current row
     // This is used as the backing memory for each Row's value indexes
     pub(super) current_row_values: Vec<Option<(u32, u32)>>,
 
-    // TODO: Find a use for these values. Perhaps in a debug impl of PgConnection?
-    #[allow(dead_code)]
-    process_id: u32,
+    // Used to open a new connection when cancelling the running query
+    pub(super) url: Arc<Url>,
 
-    #[allow(dead_code)]
-    secret_key: u32,
+    // The host and port connected to, out of those in the URL
+    pub(super) host: Arc<str>,
+    pub(super) port: u16,
+
+    // Identifies this connection's backend in a cancel request
+    pub(super) process_id: u32,
+    pub(super) secret_key: u32,
 }
 
 // https://www.postgresql.org/docs/12/protocol-flow.html#id-1.10.5.7.3
-async fn startup(stream: &mut PgStream, url: &Url) -> crate::Result<BackendKeyData> {
-    // Defaults to $USER@.../$USER
-    // and falls back to postgres@.../postgres
+#[cfg_attr(not(feature = "gssapi"), allow(unused_variables))]
+async fn startup(
+    stream: &mut PgStream,
+    url: &Url,
+    host: &str,
+    extra_params: &[(&str, &str)],
+) -> crate::Result<BackendKeyData> {
+    // Defaults to $USER@.../$USER (or $LOGNAME), the user that `peer` authentication
+    // expects, and falls back to postgres@.../postgres
     let username = url
         .username()
         .or_else(|| std::env::var("USER").map(Cow::Owned).ok())
+        .or_else(|| std::env::var("LOGNAME").map(Cow::Owned).ok())
         .unwrap_or(Cow::Borrowed("postgres"));
     let database = url.database().unwrap_or(&username);
 
+    // validated before connecting, whichever method the server asks for
+    let channel_binding = ChannelBindingMode::from_url(url)?;
+
+    // set once a SCRAM exchange bound to the TLS connection has finished
+    let mut is_bound = false;
+
     // See this doc for more runtime parameters
     // https://www.postgresql.org/docs/12/runtime-config-client.html
-    let params = &[
+    let mut params = vec![
         ("user", username.as_ref()),
         ("database", database),
         // Sets the display format for date and time values,
@@ -135,7 +243,16 @@ async fn startup(stream: &mut PgStream, url: &Url) -> crate::Result<BackendKeyDa
         ("client_encoding", "UTF-8"),
     ];
 
-    stream.write(StartupMessage { params });
+    // Session parameters to set on the server, e.g. `-c statement_timeout=5s`
+    let options = url.param("options");
+
+    if let Some(options) = &options {
+        params.push(("options", options));
+    }
+
+    params.extend_from_slice(extra_params);
+
+    stream.write(StartupMessage { params: &params });
     stream.flush().await?;
 
     let mut key_data = BackendKeyData {
@@ -147,10 +264,20 @@ async fn startup(stream: &mut PgStream, url: &Url) -> crate::Result<BackendKeyDa
         match stream.receive().await? {
             Message::Authentication => match Authentication::read(stream.buffer())? {
                 Authentication::Ok => {
-                    // do nothing. no password is needed to continue.
+                    // with a required channel binding, the server must have proven that it
+                    // is the end of the TLS connection (`trust` authentication proves nothing)
+                    if channel_binding == ChannelBindingMode::Require && !is_bound {
+                        return Err(protocol_err!(
+                            "channel binding is required, but the server authenticated the \
+                             client without channel binding"
+                        )
+                        .into());
+                    }
                 }
 
                 Authentication::CleartextPassword => {
+                    channel_binding.check_method("cleartext password")?;
+
                     stream.write(PasswordMessage::ClearText(
                         &url.password().unwrap_or_default(),
                     ));
@@ -159,6 +286,8 @@ async fn startup(stream: &mut PgStream, url: &Url) -> crate::Result<BackendKeyDa
                 }
 
                 Authentication::Md5Password => {
+                    channel_binding.check_method("MD5 password")?;
+
                     // TODO: Just reference the salt instead of returning a stack array
                     // TODO: Better way to make sure we skip the first 4 bytes here
                     let data = AuthenticationMd5::read(&stream.buffer()[4..])?;
@@ -197,13 +326,19 @@ async fn startup(stream: &mut PgStream, url: &Url) -> crate::Result<BackendKeyDa
                     }
 
                     if has_sasl || has_sasl_plus {
-                        // TODO: Handle -PLUS differently if we're in a TLS stream
+                        let binding =
+                            ChannelBinding::choose(stream, channel_binding, has_sasl_plus)?;
+                        let binds = matches!(binding, ChannelBinding::TlsServerEndPoint(_));
+
                         sasl::authenticate(
                             stream,
                             username.as_ref(),
                             &url.password().unwrap_or_default(),
+                            binding,
                         )
                         .await?;
+
+                        is_bound = binds;
                     } else {
                         return Err(protocol_err!(
                             "unsupported SASL auth mechanisms: {:?}",
@@ -213,6 +348,23 @@ async fn startup(stream: &mut PgStream, url: &Url) -> crate::Result<BackendKeyDa
                     }
                 }
 
+                #[cfg(feature = "gssapi")]
+                Authentication::Gss => {
+                    channel_binding.check_method("GSSAPI")?;
+
+                    gss::authenticate(stream, url, host).await?;
+                }
+
+                #[cfg(not(feature = "gssapi"))]
+                Authentication::Gss => {
+                    channel_binding.check_method("GSSAPI")?;
+
+                    return Err(protocol_err!(
+                        "GSSAPI authentication is unsupported; SQLx was compiled without `gssapi` feature"
+                    )
+                    .into());
+                }
+
                 auth => {
                     return Err(
                         protocol_err!("requested unsupported authentication: {:?}", auth).into(),
@@ -225,10 +377,6 @@ async fn startup(stream: &mut PgStream, url: &Url) -> crate::Result<BackendKeyDa
                 key_data = BackendKeyData::read(stream.buffer())?;
             }
 
-            Message::ParameterStatus => {
-                // do nothing. we do not care about the server values here.
-            }
-
             Message::ReadyForQuery => {
                 // done. connection is now fully established and can accept
                 // queries for execution.
@@ -255,23 +403,147 @@ async fn terminate(mut stream: PgStream) -> crate::Result<()> {
 
 impl PgConnection {
     pub(super) async fn new(url: std::result::Result<Url, url::ParseError>) -> crate::Result<Self> {
-        let url = url?;
-        let mut stream = PgStream::new(&url).await?;
+        Self::establish(url?, &[]).await
+    }
+
+    // Connect with additional startup parameters
+    //
+    // If the URL has multiple hosts, they are tried in order until a connection succeeds
+    // and matches `target_session_attrs`
+    pub(super) async fn establish(url: Url, extra_params: &[(&str, &str)]) -> crate::Result<Self> {
+        let target = TargetSessionAttrs::from_url(&url)?;
+        let url = Arc::new(url);
+
+        let mut error = None;
 
-        tls::request_if_needed(&mut stream, &url).await?;
-        let key_data = startup(&mut stream, &url).await?;
+        let mut hosts = stream::hosts(&url)?;
 
-        Ok(Self {
+        // the names and addresses of the hosts are resolved anew for every connection
+        if srv::is_enabled(&url)? {
+            hosts = srv::resolve(hosts).await?;
+        }
+
+        for (host, port) in hosts {
+            match Self::establish_host(&url, &host, port, extra_params, target).await {
+                Ok(conn) => return Ok(conn),
+                Err(e) => error = Some(e),
+            }
+        }
+
+        Err(error.expect("(bug) no hosts to connect to"))
+    }
+
+    async fn establish_host(
+        url: &Arc<Url>,
+        host: &str,
+        port: u16,
+        extra_params: &[(&str, &str)],
+        target: TargetSessionAttrs,
+    ) -> crate::Result<Self> {
+        let mut stream = PgStream::new(host, port).await?;
+
+        tls::request_if_needed(&mut stream, url, host).await?;
+        let key_data = startup(&mut stream, url, host, extra_params).await?;
+
+        let mut conn = Self {
             stream,
             current_row_values: Vec::with_capacity(10),
             next_statement_id: 1,
             is_ready: true,
+            pending_ready_for_query: 0,
+            pending_unlock: false,
             cache_type_oid: HashMap::new(),
             cache_type_name: HashMap::new(),
+            cache_type_base: HashMap::new(),
+            type_cache: None,
             cache_statement_id: HashMap::with_capacity(10),
             cache_statement: HashMap::with_capacity(10),
+            statement_cache: StatementCache::default(),
+            url: Arc::clone(url),
+            host: host.into(),
+            port,
             process_id: key_data.process_id,
             secret_key: key_data.secret_key,
+        };
+
+        if !target.matches(&mut conn).await? {
+            let _ = conn.close().await;
+
+            return Err(protocol_err!(
+                "server at {}:{} does not match target_session_attrs={}",
+                host,
+                port,
+                target.as_str()
+            )
+            .into());
+        }
+
+        Ok(conn)
+    }
+}
+
+// The kind of server to connect to, from the `target_session_attrs` parameter, as in libpq
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum TargetSessionAttrs {
+    Any,
+    ReadWrite,
+    ReadOnly,
+    Primary,
+    Standby,
+}
+
+impl TargetSessionAttrs {
+    fn from_url(url: &Url) -> crate::Result<Self> {
+        Ok(match url.param("target_session_attrs").as_deref() {
+            Some("any") | None => TargetSessionAttrs::Any,
+            Some("read-write") => TargetSessionAttrs::ReadWrite,
+            Some("read-only") => TargetSessionAttrs::ReadOnly,
+            Some("primary") => TargetSessionAttrs::Primary,
+            Some("standby") => TargetSessionAttrs::Standby,
+
+            Some(value) => {
+                return Err(
+                    protocol_err!("unknown `target_session_attrs` value: {:?}", value).into(),
+                );
+            }
+        })
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            TargetSessionAttrs::Any => "any",
+            TargetSessionAttrs::ReadWrite => "read-write",
+            TargetSessionAttrs::ReadOnly => "read-only",
+            TargetSessionAttrs::Primary => "primary",
+            TargetSessionAttrs::Standby => "standby",
+        }
+    }
+
+    async fn matches(self, conn: &mut PgConnection) -> crate::Result<bool> {
+        let query = match self {
+            TargetSessionAttrs::Any => return Ok(true),
+
+            TargetSessionAttrs::ReadWrite | TargetSessionAttrs::ReadOnly => {
+                "SHOW transaction_read_only"
+            }
+
+            TargetSessionAttrs::Primary | TargetSessionAttrs::Standby => {
+                "SELECT CASE WHEN pg_is_in_recovery() THEN 'on' ELSE 'off' END"
+            }
+        };
+
+        let mut cursor = conn.fetch(query);
+
+        let value = match cursor.next().await? {
+            Some(row) => row.try_get::<String, _>(0)? == "on",
+            None => return Err(protocol_err!("{}: no rows returned", query).into()),
+        };
+
+        while cursor.next().await?.is_some() {}
+
+        Ok(match self {
+            TargetSessionAttrs::ReadWrite | TargetSessionAttrs::Primary => !value,
+            _ => value,
         })
     }
 }
@@ -294,4 +566,8 @@ impl Connection for PgConnection {
     fn ping(&mut self) -> BoxFuture<crate::Result<()>> {
         Box::pin(Executor::execute(self, "SELECT 1").map_ok(|_| ()))
     }
+
+    fn is_reusable(&self) -> bool {
+        !self.pending_unlock
+    }
 }
@@ -0,0 +1,566 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use async_stream::try_stream;
+use byteorder::{ByteOrder, NetworkEndian};
+use futures_core::future::BoxFuture;
+use futures_core::stream::BoxStream;
+use futures_util::ready;
+
+use crate::arguments::Arguments;
+use crate::encode::Encode;
+use crate::io::BufMut;
+use crate::postgres::protocol::{
+    CommandComplete, CopyData, CopyDone, CopyFail, CopyResponse, Message, TypeFormat,
+};
+use crate::postgres::{PgArguments, PgConnection, Postgres};
+use crate::runtime::{AsyncRead, AsyncReadExt, AsyncWrite};
+use crate::types::Type;
+
+// Size of the chunks read from a source in [PgCopyIn::read_from]
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+// Size of the chunks of rows sent by [PgCopyInRows]
+const SEND_CHUNK_SIZE: usize = 64 * 1024;
+
+// https://www.postgresql.org/docs/current/sql-copy.html#id-1.9.3.55.9.4.5
+const BINARY_SIGNATURE: &[u8] = b"PGCOPY\n\xff\r\n\0";
+
+impl PgConnection {
+    /// Issue a `COPY FROM STDIN` statement and transition the connection to streaming data
+    /// to Postgres. This is a more efficient way to import data into Postgres as compared to
+    /// `INSERT` but requires one of a few specific data formats (text/CSV/binary).
+    ///
+    /// If `statement` is anything other than a `COPY ... FROM STDIN ...` command, an error is
+    /// returned.
+    ///
+    /// Command syntax: <https://www.postgresql.org/docs/current/sql-copy.html>
+    ///
+    /// ```rust,ignore
+    /// let mut copy = conn.copy_in_raw("COPY users (id, name) FROM STDIN").await?;
+    ///
+    /// copy.send(&b"1\tJohn\n2\tJane\n"[..]).await?;
+    ///
+    /// let rows = copy.finish().await?;
+    /// ```
+    ///
+    /// ### Note
+    /// [PgCopyIn::finish] or [PgCopyIn::abort] *must* be called when finished, or the writer
+    /// closed, or the connection will return an error the next time it is used.
+    pub async fn copy_in_raw(&mut self, statement: &str) -> crate::Result<PgCopyIn<'_>> {
+        self.run(statement, None, true).await?;
+
+        let response = match self.stream.receive().await? {
+            Message::CopyInResponse => CopyResponse::read(self.stream.buffer())?,
+
+            message => {
+                return Err(protocol_err!("copy_in: unexpected message: {:?}", message).into());
+            }
+        };
+
+        Ok(PgCopyIn {
+            conn: Some(self),
+            response,
+            closing: None,
+        })
+    }
+
+    /// Issue a `COPY TO STDOUT` statement and return a stream of the raw data
+    /// returned by Postgres.
+    ///
+    /// If `statement` is anything other than a `COPY ... TO STDOUT ...` command,
+    /// an error is returned.
+    ///
+    /// Command syntax: <https://www.postgresql.org/docs/current/sql-copy.html>
+    pub async fn copy_out_raw<'c>(
+        &'c mut self,
+        statement: &str,
+    ) -> crate::Result<BoxStream<'c, crate::Result<Vec<u8>>>> {
+        self.run(statement, None, true).await?;
+
+        match self.stream.receive().await? {
+            Message::CopyOutResponse => {}
+
+            message => {
+                return Err(protocol_err!("copy_out: unexpected message: {:?}", message).into());
+            }
+        }
+
+        Ok(Box::pin(try_stream! {
+            loop {
+                match self.stream.receive().await? {
+                    Message::CopyData => {
+                        yield self.stream.buffer().to_vec();
+                    }
+
+                    // Indicates that the COPY has finished, a `CommandComplete` and
+                    // `ReadyForQuery` will follow
+                    Message::CopyDone | Message::CommandComplete => {}
+
+                    Message::ReadyForQuery => {
+                        self.is_ready = true;
+                        break;
+                    }
+
+                    message => {
+                        let error: crate::Error =
+                            protocol_err!("copy_out: unexpected message: {:?}", message).into();
+
+                        Err(error)?;
+                    }
+                }
+            }
+        }))
+    }
+}
+
+/// A connection in streaming `COPY FROM STDIN` mode.
+///
+/// Created by [PgConnection::copy_in_raw].
+///
+/// This is also an [AsyncWrite], e.g. to copy into it with `io::copy`; each write is sent as
+/// a chunk of `COPY` data and closing the writer calls [PgCopyIn::finish], without returning
+/// the number of rows that were inserted.
+///
+/// ### Note
+/// [PgCopyIn::finish] or [PgCopyIn::abort] *must* be called when finished, or the writer
+/// closed. If this is dropped instead, the `COPY` is aborted and the connection will discard
+/// the resulting error before running the next query.
+#[must_use = "connection will error on next use if `.finish()` or `.abort()` is not called"]
+pub struct PgCopyIn<'c> {
+    conn: Option<&'c mut PgConnection>,
+    response: CopyResponse,
+
+    // The `finish` started by closing the writer
+    closing: Option<BoxFuture<'c, crate::Result<u64>>>,
+}
+
+impl<'c> PgCopyIn<'c> {
+    /// Returns `true` if Postgres is expecting data in text or CSV format.
+    pub fn is_textual(&self) -> bool {
+        matches!(self.response.format, TypeFormat::Text)
+    }
+
+    /// Returns the number of columns expected in the input.
+    pub fn num_columns(&self) -> usize {
+        self.response.columns.len()
+    }
+
+    /// Send a chunk of `COPY` data.
+    ///
+    /// The data does not need to be aligned to row boundaries; Postgres reassembles the
+    /// stream of chunks on its end.
+    pub async fn send(&mut self, data: impl AsRef<[u8]>) -> crate::Result<&mut Self> {
+        let conn = self.conn.as_deref_mut().expect(ERR_FINISHED);
+
+        conn.stream.write(CopyData(data.as_ref()));
+        conn.stream.flush().await?;
+
+        Ok(self)
+    }
+
+    /// Copy data directly from `source` to the database without requiring an intermediate
+    /// buffer to be held by the caller.
+    ///
+    /// `source` is read to EOF.
+    pub async fn read_from(
+        &mut self,
+        mut source: impl AsyncRead + Unpin,
+    ) -> crate::Result<&mut Self> {
+        let mut buf = vec![0; READ_CHUNK_SIZE];
+
+        loop {
+            let read = source.read(&mut buf).await?;
+
+            if read == 0 {
+                break;
+            }
+
+            self.send(&buf[..read]).await?;
+        }
+
+        Ok(self)
+    }
+
+    /// Signal that the `COPY` should be aborted; `msg` is reported by Postgres as the
+    /// reason for the failure.
+    ///
+    /// No rows are inserted into the target table.
+    pub async fn abort(mut self, msg: impl AsRef<str>) -> crate::Result<()> {
+        let conn = self.conn.take().expect(ERR_FINISHED);
+
+        conn.stream.write(CopyFail(msg.as_ref()));
+        conn.stream.flush().await?;
+
+        match conn.stream.receive().await {
+            // Postgres acknowledges `CopyFail` with a `query_canceled` error
+            Err(crate::Error::Database(error)) if error.code() == Some("57014") => {}
+
+            Err(error) => {
+                return Err(error);
+            }
+
+            Ok(message) => {
+                return Err(protocol_err!("copy_in: unexpected message: {:?}", message).into());
+            }
+        }
+
+        conn.wait_until_ready().await
+    }
+
+    /// Signal that the `COPY` has finished and return the number of rows
+    /// that were inserted.
+    pub async fn finish(mut self) -> crate::Result<u64> {
+        finish(self.conn.take().expect(ERR_FINISHED)).await
+    }
+}
+
+async fn finish(conn: &mut PgConnection) -> crate::Result<u64> {
+    conn.stream.write(CopyDone);
+    conn.stream.flush().await?;
+
+    let mut rows = 0;
+
+    loop {
+        match conn.stream.receive().await? {
+            Message::CommandComplete => {
+                rows = CommandComplete::read(conn.stream.buffer())?.affected_rows;
+            }
+
+            Message::ReadyForQuery => {
+                conn.is_ready = true;
+                break;
+            }
+
+            message => {
+                return Err(protocol_err!("copy_in: unexpected message: {:?}", message).into());
+            }
+        }
+    }
+
+    Ok(rows)
+}
+
+impl AsyncWrite for PgCopyIn<'_> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let conn = match self.conn.as_deref_mut() {
+            Some(conn) => conn,
+            None => return Poll::Ready(Err(io::Error::other("PgCopyIn already finished"))),
+        };
+
+        // The previous chunk is written out before the next one is accepted, so that
+        // at most one chunk is held in the write buffer
+        ready!(conn.stream.stream.poll_flush_buf(cx))?;
+
+        conn.stream.write(CopyData(buf));
+
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        match self.conn.as_deref_mut() {
+            Some(conn) => conn.stream.stream.poll_flush_buf(cx),
+            None => Poll::Ready(Ok(())),
+        }
+    }
+
+    #[cfg(feature = "runtime-async-std")]
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        poll_finish(&mut self, cx)
+    }
+
+    #[cfg(feature = "runtime-tokio")]
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        poll_finish(&mut self, cx)
+    }
+}
+
+// Drive the `finish` started by closing the writer to completion
+fn poll_finish(this: &mut PgCopyIn<'_>, cx: &mut Context) -> Poll<io::Result<()>> {
+    if this.closing.is_none() {
+        match this.conn.take() {
+            Some(conn) => this.closing = Some(Box::pin(finish(conn))),
+
+            // already closed
+            None => return Poll::Ready(Ok(())),
+        }
+    }
+
+    let res = ready!(this.closing.as_mut().unwrap().as_mut().poll(cx));
+    this.closing = None;
+
+    Poll::Ready(res.map(drop).map_err(|error| match error {
+        crate::Error::Io(error) => error,
+        error => io::Error::other(error),
+    }))
+}
+
+impl Drop for PgCopyIn<'_> {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            // Queue up a `CopyFail`; it is flushed before the next query is run
+            // and the resulting error is discarded
+            conn.stream
+                .write(CopyFail("PgCopyIn dropped without calling finish()"));
+        }
+    }
+}
+
+const ERR_FINISHED: &str = "(bug) PgCopyIn already finished";
+
+/// Builds the data for a binary `COPY FROM STDIN` out of Rust values.
+///
+/// Each row is a tuple (or any other type implementing [PgCopyRow]) whose fields implement
+/// [Encode]. Rows are serialized into the PostgreSQL
+/// [binary `COPY` format](https://www.postgresql.org/docs/current/sql-copy.html#id-1.9.3.55.9.4)
+/// as they are pushed; the file header and trailer are written automatically.
+///
+/// ```rust,ignore
+/// let rows = PgCopyInBuilder::new("COPY users (id, name) FROM STDIN (FORMAT binary)")
+///     .row((1_i32, "John"))
+///     .row((2_i32, "Jane"))
+///     .execute(&mut conn)
+///     .await?;
+/// ```
+///
+/// The rows pushed to the builder are held in memory until the `COPY` starts. To import
+/// more rows than should be held in memory at once, [start](PgCopyInBuilder::start) the
+/// `COPY` and push the rows to the returned [PgCopyInRows] as they are produced instead.
+///
+/// The OIDs of any user-defined types used inside of arrays or records are resolved before
+/// the `COPY` is started.
+pub struct PgCopyInBuilder<'q> {
+    statement: &'q str,
+    arguments: PgArguments,
+}
+
+impl<'q> PgCopyInBuilder<'q> {
+    /// Create a new builder for the given `COPY ... FROM STDIN (FORMAT binary)` statement.
+    pub fn new(statement: &'q str) -> Self {
+        let mut arguments = PgArguments::default();
+
+        // header: signature, flags field, and header extension area length
+        arguments.buffer.extend_from_slice(BINARY_SIGNATURE);
+        arguments.buffer.put_i32::<NetworkEndian>(0);
+        arguments.buffer.put_i32::<NetworkEndian>(0);
+
+        Self {
+            statement,
+            arguments,
+        }
+    }
+
+    /// Add a row to be copied.
+    pub fn row<R: PgCopyRow>(mut self, row: R) -> Self {
+        self.push(row);
+        self
+    }
+
+    /// Add a row to be copied.
+    pub fn push<R: PgCopyRow>(&mut self, row: R) {
+        push_row(&mut self.arguments, row);
+    }
+
+    /// Start the `COPY` on the given connection, sending the rows added so far, and return
+    /// a [PgCopyInRows] to push the rest of the rows to.
+    ///
+    /// ```rust,ignore
+    /// let mut copy = PgCopyInBuilder::new("COPY users (id, name) FROM STDIN (FORMAT binary)")
+    ///     .start(&mut conn)
+    ///     .await?;
+    ///
+    /// while let Some(user) = users.try_next().await? {
+    ///     copy.push((user.id, user.name)).await?;
+    /// }
+    ///
+    /// let rows = copy.finish().await?;
+    /// ```
+    pub async fn start(mut self, conn: &mut PgConnection) -> crate::Result<PgCopyInRows<'_>> {
+        // Type OIDs must be resolved before the connection enters COPY mode
+        self.arguments.buffer.patch_type_holes(conn).await?;
+
+        let copy = conn.copy_in_raw(self.statement).await?;
+
+        if copy.is_textual() {
+            copy.abort("expected binary COPY format").await?;
+
+            return Err(protocol_err!(
+                "copy_in: expected a binary COPY; add `(FORMAT binary)` to the statement"
+            )
+            .into());
+        }
+
+        let mut rows = PgCopyInRows {
+            copy,
+            arguments: self.arguments,
+        };
+
+        rows.send_buffered().await?;
+
+        Ok(rows)
+    }
+
+    /// Run the `COPY` on the given connection and return the number of rows
+    /// that were inserted.
+    pub async fn execute(self, conn: &mut PgConnection) -> crate::Result<u64> {
+        self.start(conn).await?.finish().await
+    }
+}
+
+/// A binary `COPY FROM STDIN` in progress, that rows are pushed to as they are produced.
+///
+/// Returned from [PgCopyInBuilder::start]. The encoded rows are sent to Postgres in chunks
+/// of 64 KiB, so the memory used does not grow with the number of rows.
+///
+/// The OID of a user-defined type used inside of an array or record can not be looked up
+/// once the `COPY` has started; pushing a row with a type that was not used on the
+/// connection before (e.g. in a row added to the [PgCopyInBuilder]) returns an error.
+///
+/// ### Note
+/// [PgCopyInRows::finish] or [PgCopyInRows::abort] *must* be called when finished. If this is
+/// dropped instead, the `COPY` is aborted as when a [PgCopyIn] is dropped.
+#[must_use = "connection will error on next use if `.finish()` or `.abort()` is not called"]
+pub struct PgCopyInRows<'c> {
+    copy: PgCopyIn<'c>,
+
+    // The rows encoded but not sent yet
+    arguments: PgArguments,
+}
+
+impl<'c> PgCopyInRows<'c> {
+    /// Add a row to be copied, sending the rows added before it if they fill a chunk.
+    pub async fn push<R: PgCopyRow>(&mut self, row: R) -> crate::Result<&mut Self> {
+        push_row(&mut self.arguments, row);
+
+        if self.arguments.buffer.len() >= SEND_CHUNK_SIZE {
+            self.send_buffered().await?;
+        }
+
+        Ok(self)
+    }
+
+    /// Signal that the `COPY` should be aborted; `msg` is reported by Postgres as the
+    /// reason for the failure.
+    ///
+    /// No rows are inserted into the target table.
+    pub async fn abort(self, msg: impl AsRef<str>) -> crate::Result<()> {
+        self.copy.abort(msg).await
+    }
+
+    /// Send the remaining rows, signal that the `COPY` has finished and return the number
+    /// of rows that were inserted.
+    pub async fn finish(mut self) -> crate::Result<u64> {
+        // trailer
+        self.arguments.buffer.put_i16::<NetworkEndian>(-1);
+
+        self.send_buffered().await?;
+        self.copy.finish().await
+    }
+
+    async fn send_buffered(&mut self) -> crate::Result<()> {
+        let conn = self.copy.conn.as_deref_mut().expect(ERR_FINISHED);
+        self.arguments.buffer.patch_cached_type_holes(conn)?;
+
+        for chunk in self.arguments.buffer.chunks(SEND_CHUNK_SIZE) {
+            self.copy.send(chunk).await?;
+        }
+
+        self.arguments = PgArguments::default();
+
+        Ok(())
+    }
+}
+
+// Encode a row at the end of `arguments`
+fn push_row<R: PgCopyRow>(arguments: &mut PgArguments, row: R) {
+    // Reserves space for the number of fields in the tuple
+    let pos = arguments.buffer.len();
+    arguments.buffer.put_i16::<NetworkEndian>(0);
+
+    let fields = arguments.types.len();
+    row.add_fields(arguments);
+    let fields = arguments.types.len() - fields;
+
+    NetworkEndian::write_i16(&mut arguments.buffer[pos..], fields as i16);
+}
+
+/// A row that can be written by [PgCopyInBuilder].
+///
+/// This is implemented for tuples of up to 9 values that implement [Encode]. There is no
+/// derive for structs; a struct implements it by adding its fields in the order of the
+/// columns of the `COPY`:
+///
+/// ```rust,ignore
+/// use sqlx::arguments::Arguments;
+/// use sqlx::postgres::{PgArguments, PgCopyRow};
+///
+/// struct User {
+///     id: i32,
+///     name: String,
+/// }
+///
+/// impl PgCopyRow for User {
+///     fn add_fields(self, arguments: &mut PgArguments) {
+///         arguments.add(self.id);
+///         arguments.add(self.name);
+///     }
+/// }
+/// ```
+pub trait PgCopyRow {
+    /// Add each field of this row, in order.
+    fn add_fields(self, arguments: &mut PgArguments);
+}
+
+macro_rules! impl_copy_row_for_tuple {
+    ($( ($idx:tt) -> $T:ident );+;) => {
+        impl<$($T,)+> PgCopyRow for ($($T,)+)
+        where
+            $($T: Type<Postgres> + Encode<Postgres>,)+
+        {
+            #[inline]
+            fn add_fields(self, arguments: &mut PgArguments) {
+                $(arguments.add(self.$idx);)+
+            }
+        }
+    };
+}
+
+impl_copy_row_for_tuple!((0) -> T1;);
+impl_copy_row_for_tuple!((0) -> T1; (1) -> T2;);
+impl_copy_row_for_tuple!((0) -> T1; (1) -> T2; (2) -> T3;);
+impl_copy_row_for_tuple!((0) -> T1; (1) -> T2; (2) -> T3; (3) -> T4;);
+impl_copy_row_for_tuple!((0) -> T1; (1) -> T2; (2) -> T3; (3) -> T4; (4) -> T5;);
+impl_copy_row_for_tuple!((0) -> T1; (1) -> T2; (2) -> T3; (3) -> T4; (4) -> T5; (5) -> T6;);
+impl_copy_row_for_tuple!(
+    (0) -> T1; (1) -> T2; (2) -> T3; (3) -> T4; (4) -> T5; (5) -> T6; (6) -> T7;
+);
+impl_copy_row_for_tuple!(
+    (0) -> T1; (1) -> T2; (2) -> T3; (3) -> T4; (4) -> T5; (5) -> T6; (6) -> T7; (7) -> T8;
+);
+impl_copy_row_for_tuple!(
+    (0) -> T1; (1) -> T2; (2) -> T3; (3) -> T4; (4) -> T5; (5) -> T6; (6) -> T7; (7) -> T8;
+    (8) -> T9;
+);
+
+#[cfg(test)]
+mod tests {
+    use super::PgCopyInBuilder;
+
+    #[test]
+    fn it_encodes_binary_copy_rows() {
+        let builder = PgCopyInBuilder::new("")
+            .row((1_i32, Some("ab")))
+            .row((2_i32, None::<&str>));
+
+        let mut expected = b"PGCOPY\n\xff\r\n\0\0\0\0\0\0\0\0\0".to_vec();
+        expected.extend_from_slice(b"\0\x02\0\0\0\x04\0\0\0\x01\0\0\0\x02ab");
+        expected.extend_from_slice(b"\0\x02\0\0\0\x04\0\0\0\x02\xff\xff\xff\xff");
+
+        assert_eq!(&**builder.arguments.buffer, &*expected);
+    }
+}
@@ -13,6 +13,7 @@ use crate::postgres::{PgArguments, PgConnection, PgRow, Postgres};
 pub struct PgCursor<'c, 'q> {
     source: ConnectionSource<'c, PgConnection>,
     query: Option<(&'q str, Option<PgArguments>)>,
+    persistent: bool,
     statement: Arc<Statement>,
 }
 
@@ -30,6 +31,7 @@ impl<'c, 'q> Cursor<'c, 'q> for PgCursor<'c, 'q> {
         Self {
             source: ConnectionSource::Pool(pool.clone()),
             statement: Arc::default(),
+            persistent: query.persistent(),
             query: Some(query.into_parts()),
         }
     }
@@ -43,6 +45,7 @@ impl<'c, 'q> Cursor<'c, 'q> for PgCursor<'c, 'q> {
         Self {
             source: ConnectionSource::ConnectionRef(conn),
             statement: Arc::default(),
+            persistent: query.persistent(),
             query: Some(query.into_parts()),
         }
     }
@@ -61,7 +64,7 @@ async fn next<'a, 'c: 'a, 'q: 'a>(
     // contained query. We guard against this happening on _all_ next calls
     // by using [Option::take] which replaces the potential value in the Option with `None
     if let Some((query, arguments)) = cursor.query.take() {
-        let statement = conn.run(query, arguments).await?;
+        let statement = conn.run(query, arguments, cursor.persistent).await?;
 
         // If there is a statement ID, this is a non-simple or prepared query
         if let Some(statement) = statement {
@@ -6,6 +6,7 @@ use crate::postgres::{
     PgArguments, PgConnection, PgCursor, PgError, PgRawBuffer, PgRow, PgTypeInfo, PgValue,
 };
 use crate::row::HasRow;
+use crate::transaction::TransactionOptions;
 use crate::value::HasRawValue;
 
 /// **Postgres** database driver.
@@ -24,6 +25,25 @@ impl Database for Postgres {
     type RawBuffer = PgRawBuffer;
 
     type Error = PgError;
+
+    fn begin_transaction(options: &TransactionOptions) -> crate::Result<Vec<String>> {
+        let mut stmt = String::from("BEGIN");
+
+        if let Some(level) = options.isolation {
+            stmt.push_str(" ISOLATION LEVEL ");
+            stmt.push_str(level.as_sql());
+        }
+
+        if options.read_only {
+            stmt.push_str(" READ ONLY");
+        }
+
+        if options.deferrable {
+            stmt.push_str(" DEFERRABLE");
+        }
+
+        Ok(vec![stmt])
+    }
 }
 
 impl<'a> HasRow<'a> for Postgres {
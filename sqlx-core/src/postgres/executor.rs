@@ -13,12 +13,11 @@ use crate::postgres::protocol::{
     self, CommandComplete, Message, ParameterDescription, ReadyForQuery, RowDescription,
     StatementId, TypeFormat, TypeId,
 };
-use crate::postgres::row::Column as StatementColumn;
 use crate::postgres::row::Statement;
 use crate::postgres::type_info::SharedStr;
 use crate::postgres::types::try_resolve_type_name;
 use crate::postgres::{
-    PgArguments, PgConnection, PgCursor, PgQueryAs, PgRow, PgTypeInfo, Postgres,
+    PgArguments, PgColumn, PgConnection, PgCursor, PgQueryAs, PgRow, PgTypeInfo, Postgres,
 };
 use crate::query_as::query_as;
 use crate::row::Row;
@@ -32,62 +31,85 @@ impl PgConnection {
         &mut self,
         query: &str,
         args: &PgArguments,
+        persistent: bool,
     ) -> crate::Result<StatementId> {
-        if let Some(&id) = self.cache_statement_id.get(query) {
+        if !persistent {
+            // The unnamed statement is replaced by the next one prepared; its description
+            // is kept only until then
+            let statement = self.prepare(StatementId::UNNAMED, query, args).await?;
+
+            self.cache_statement
+                .insert(StatementId::UNNAMED, Arc::new(statement));
+
+            Ok(StatementId::UNNAMED)
+        } else if let Some(&id) = self.cache_statement_id.get(query) {
+            self.touch_statement(id, true);
+
             Ok(id)
         } else {
             let id = StatementId(self.next_statement_id);
 
             self.next_statement_id += 1;
 
-            // Build a list of type OIDs from the type info array provided by PgArguments
-            // This may need to query Postgres for an OID of a user-defined type
+            let statement = self.prepare(id, query, args).await?;
 
-            let mut types = Vec::with_capacity(args.types.len());
+            // cache statement ID and statement description
+            self.cache_statement_id.insert(query.into(), id);
+            self.cache_statement.insert(id, Arc::new(statement));
 
-            for ty in &args.types {
-                types.push(if let Some(oid) = ty.id {
-                    oid.0
-                } else {
-                    self.get_type_id_by_name(&*ty.name).await?
-                });
-            }
+            self.touch_statement(id, false);
 
-            self.stream.write(protocol::Parse {
-                statement: id,
-                param_types: &*types,
-                query,
-            });
+            Ok(id)
+        }
+    }
 
-            // [Describe] will return the expected result columns and types
-            self.write_describe(protocol::Describe::Statement(id));
-            self.write_sync();
+    pub(super) async fn prepare(
+        &mut self,
+        id: StatementId,
+        query: &str,
+        args: &PgArguments,
+    ) -> crate::Result<Statement> {
+        // Build a list of type OIDs from the type info array provided by PgArguments
+        // This may need to query Postgres for an OID of a user-defined type
 
-            // Flush commands and handle ParseComplete and RowDescription
-            self.wait_until_ready().await?;
-            self.stream.flush().await?;
-            self.is_ready = false;
+        let mut types = Vec::with_capacity(args.types.len());
 
-            // wait for `ParseComplete`
-            match self.stream.receive().await? {
-                Message::ParseComplete => {}
-                message => {
-                    return Err(protocol_err!("run: unexpected message: {:?}", message).into());
-                }
-            }
+        for ty in &args.types {
+            types.push(if let Some(oid) = ty.id {
+                oid.0
+            } else {
+                self.get_type_id_by_name(&*ty.name).await?
+            });
+        }
 
-            // expecting a `ParameterDescription` next
-            let pd = self.expect_param_desc().await?;
+        self.stream.write(protocol::Parse {
+            statement: id,
+            param_types: &*types,
+            query,
+        });
 
-            // expecting a `RowDescription` next (or `NoData` for an empty statement)
-            let statement = self.expect_row_desc(pd).await?;
+        // [Describe] will return the expected result columns and types
+        self.write_describe(protocol::Describe::Statement(id));
+        self.write_sync();
 
-            // cache statement ID and statement description
-            self.cache_statement_id.insert(query.into(), id);
-            self.cache_statement.insert(id, Arc::new(statement));
+        // Flush commands and handle ParseComplete and RowDescription
+        self.wait_until_ready().await?;
+        self.stream.flush().await?;
+        self.is_ready = false;
 
-            Ok(id)
+        // wait for `ParseComplete`
+        match self.stream.receive().await? {
+            Message::ParseComplete => {}
+            message => {
+                return Err(protocol_err!("run: unexpected message: {:?}", message).into());
+            }
         }
+
+        // expecting a `ParameterDescription` next
+        let pd = self.expect_param_desc().await?;
+
+        // expecting a `RowDescription` next (or `NoData` for an empty statement)
+        self.expect_row_desc(pd).await
     }
 
     async fn parse_parameter_description(
@@ -131,7 +153,7 @@ impl PgConnection {
                 .get_type_info_by_oid(field.type_id.0, fetch_type_info)
                 .await?;
 
-            columns.push(StatementColumn {
+            columns.push(PgColumn {
                 type_info,
                 name,
                 format: type_format.unwrap_or(field.type_format),
@@ -222,7 +244,18 @@ impl PgConnection {
         self.stream.write(protocol::Sync);
     }
 
-    async fn wait_until_ready(&mut self) -> crate::Result<()> {
+    // Queue a simple query, from a `Drop` impl, to be sent before the next query is run;
+    // its result is discarded
+    pub(super) fn queue_query(&mut self, query: &str) {
+        if !self.is_ready {
+            self.pending_ready_for_query += 1;
+        }
+
+        self.stream.write(protocol::Query(query));
+        self.is_ready = false;
+    }
+
+    pub(super) async fn wait_until_ready(&mut self) -> crate::Result<()> {
         // depending on how the previous query finished we may need to continue
         // pulling messages from the stream until we receive a [ReadyForQuery] message
 
@@ -230,11 +263,39 @@ impl PgConnection {
         // the previous query
 
         if !self.is_ready {
+            // an abandoned operation may have left messages in the write buffer
+            // (e.g., the `CopyFail` from a dropped [PgCopyIn])
+            self.stream.flush().await?;
+
             loop {
-                if let Message::ReadyForQuery = self.stream.receive().await? {
-                    // we are now ready to go
-                    self.is_ready = true;
-                    break;
+                match self.stream.receive().await {
+                    Ok(Message::ReadyForQuery) if self.pending_ready_for_query > 0 => {
+                        self.pending_ready_for_query -= 1;
+                    }
+
+                    Ok(Message::ReadyForQuery) => {
+                        // we are now ready to go
+                        self.is_ready = true;
+                        self.pending_unlock = false;
+                        break;
+                    }
+
+                    Ok(_) => {}
+
+                    // Postgres acknowledges the `CopyFail` of a dropped [PgCopyIn] with a
+                    // `query_canceled` error
+                    Err(crate::Error::Database(error)) if error.code() == Some("57014") => {}
+
+                    // other errors of an abandoned or queued query (e.g., the unlock queued
+                    // by a dropped advisory lock guard) are not relevant to the query we are
+                    // about to run, but must not go unnoticed
+                    Err(crate::Error::Database(error)) => {
+                        log::warn!("error from a previous, abandoned, query: {}", error);
+                    }
+
+                    Err(error) => {
+                        return Err(error);
+                    }
                 }
             }
         }
@@ -252,12 +313,17 @@ impl PgConnection {
         &mut self,
         query: &str,
         arguments: Option<PgArguments>,
+        persistent: bool,
     ) -> crate::Result<Option<StatementId>> {
         let statement = if let Some(mut arguments) = arguments {
             // Check the statement cache for a statement ID that matches the given query
             // If it doesn't exist, we generate a new statement ID and write out [Parse] to the
             // connection command buffer
-            let statement = self.write_prepare(query, &arguments).await?;
+            let statement = self.write_prepare(query, &arguments, persistent).await?;
+
+            // Make room in the statement cache; the statement just prepared is the most
+            // recently used and is kept
+            self.evict_statements();
 
             // Next, [Bind] attaches the arguments to the statement and creates a named portal
             self.write_bind("", statement, &mut arguments).await?;
@@ -292,86 +358,129 @@ impl PgConnection {
         &'e mut self,
         query: &'q str,
     ) -> crate::Result<Describe<Postgres>> {
-        let statement_id = self.write_prepare(query, &Default::default()).await?;
+        let statement_id = self.write_prepare(query, &Default::default(), true).await?;
         let statement = &self.cache_statement[&statement_id];
         let columns = statement.columns.to_vec();
+        let params = statement.params.clone();
+
+        let result_columns = if columns.is_empty() {
+            // the outputs of a procedure are not described by Postgres
+            self.describe_call_outputs(query).await?.unwrap_or_default()
+        } else {
+            self.map_result_columns(columns).await?
+        };
 
         Ok(Describe {
-            param_types: statement
-                .params
+            param_types: params
                 .iter()
                 .map(|info| Some(info.clone()))
                 .collect::<Vec<_>>()
                 .into_boxed_slice(),
-            result_columns: self.map_result_columns(columns).await?.into_boxed_slice(),
+            result_columns: result_columns.into_boxed_slice(),
         })
     }
 
-    pub(crate) async fn get_type_id_by_name(&mut self, name: &str) -> crate::Result<u32> {
+    // The OID of a type that was already looked up, without asking Postgres
+    pub(crate) fn cached_type_id(&mut self, name: &str) -> Option<u32> {
         if let Some(oid) = self.cache_type_oid.get(name) {
-            return Ok(*oid);
+            return Some(*oid);
+        }
+
+        self.shared_type_oid(name)
+    }
+
+    pub(crate) async fn get_type_id_by_name(&mut self, name: &str) -> crate::Result<u32> {
+        if let Some(oid) = self.cached_type_id(name) {
+            return Ok(oid);
         }
 
+        // the name is matched case-insensitively but otherwise literally; `_` is a wildcard
+        // in a pattern and begins the name of every array type
+        let pattern = name
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+
         // language=SQL
         let (oid,): (u32,) = query_as(
             "
 SELECT oid FROM pg_catalog.pg_type WHERE typname ILIKE $1
                 ",
         )
-        .bind(name)
+        .bind(pattern)
         .fetch_one(&mut *self)
         .await?;
 
-        let shared = SharedStr::from(name.to_owned());
-
-        self.cache_type_oid.insert(shared.clone(), oid);
-        self.cache_type_name.insert(oid, shared.clone());
+        self.cache_type(SharedStr::from(name.to_owned()), oid);
 
         Ok(oid)
     }
 
     pub(crate) async fn get_type_info_by_oid(
         &mut self,
-        oid: u32,
+        mut oid: u32,
         fetch_type_info: bool,
     ) -> crate::Result<PgTypeInfo> {
-        if let Some(name) = try_resolve_type_name(oid) {
-            return Ok(PgTypeInfo::new(TypeId(oid), name));
-        }
+        loop {
+            // A domain is sent and received in the format of its base type, so it is resolved
+            // to the base type (and an array of a domain to the array of the base type)
+            if let Some(&base) = self.cache_type_base.get(&oid) {
+                oid = base;
+                continue;
+            }
 
-        if let Some(name) = self.cache_type_name.get(&oid) {
-            return Ok(PgTypeInfo::new(TypeId(oid), name));
-        }
+            if let Some(name) = try_resolve_type_name(oid) {
+                return Ok(PgTypeInfo::new(TypeId(oid), name));
+            }
+
+            if let Some(name) = self.cache_type_name.get(&oid) {
+                return Ok(PgTypeInfo::new(TypeId(oid), name));
+            }
+
+            if self.shared_type_name(oid) {
+                continue;
+            }
+
+            if !fetch_type_info {
+                // NOTE: The name isn't too important for the decode lifecycle of TEXT
+                return Ok(PgTypeInfo::new(TypeId(oid), SharedStr::Static("")));
+            }
 
-        let name = if fetch_type_info {
             // language=SQL
-            let (name,): (String,) = query_as(
+            let (name, base): (String, u32) = query_as(
                 "
-    SELECT UPPER(typname) FROM pg_catalog.pg_type WHERE oid = $1
-                    ",
+    SELECT UPPER(ty.typname), CASE
+        WHEN ty.typtype = 'd' THEN ty.typbasetype
+        WHEN elem.typtype = 'd' THEN base.typarray
+        ELSE 0
+    END::oid
+    FROM pg_catalog.pg_type ty
+    LEFT JOIN pg_catalog.pg_type elem ON elem.oid = ty.typelem AND ty.typcategory = 'A'
+    LEFT JOIN pg_catalog.pg_type base ON base.oid = elem.typbasetype
+    WHERE ty.oid = $1
+                ",
             )
             .bind(oid)
             .fetch_one(&mut *self)
             .await?;
 
+            if base != 0 {
+                self.cache_domain(oid, base);
+                continue;
+            }
+
             // Emplace the new type name <-> OID association in the cache
             let shared = SharedStr::from(name);
 
-            self.cache_type_oid.insert(shared.clone(), oid);
-            self.cache_type_name.insert(oid, shared.clone());
+            self.cache_type(shared.clone(), oid);
 
-            shared
-        } else {
-            // NOTE: The name isn't too important for the decode lifecycle of TEXT
-            SharedStr::Static("")
-        };
-
-        Ok(PgTypeInfo::new(TypeId(oid), name))
+            return Ok(PgTypeInfo::new(TypeId(oid), shared));
+        }
     }
 
     async fn map_result_columns(
         &mut self,
-        columns: Vec<StatementColumn>,
+        columns: Vec<PgColumn>,
     ) -> crate::Result<Vec<Column<Postgres>>> {
         if columns.is_empty() {
             return Ok(vec![]);
@@ -490,9 +599,10 @@ impl Executor for super::PgConnection {
     {
         log_execution!(query, {
             Box::pin(async move {
+                let persistent = query.persistent();
                 let (query, arguments) = query.into_parts();
 
-                self.run(query, arguments).await?;
+                self.run(query, arguments, persistent).await?;
                 self.affected_rows().await
             })
         })
@@ -0,0 +1,281 @@
+use serde::Deserialize;
+use serde_json::{Map, Value as JsonValue};
+
+use crate::cursor::Cursor;
+use crate::executor::Execute;
+use crate::postgres::{PgArguments, PgConnection, Postgres};
+use crate::row::Row;
+
+// https://www.postgresql.org/docs/12/sql-explain.html
+
+impl PgConnection {
+    /// Show the plan that Postgres chooses for a query, with `EXPLAIN`.
+    ///
+    /// ```rust,ignore
+    /// let plan = conn
+    ///     .explain(sqlx::query("SELECT * FROM users WHERE id = $1").bind(1_i64))
+    ///     .analyze(true)
+    ///     .format_json()
+    ///     .await?;
+    ///
+    /// println!("{} ms", plan.execution_time.unwrap_or_default());
+    /// ```
+    pub fn explain<'q, E>(&mut self, query: E) -> PgExplain<'_, 'q>
+    where
+        E: Execute<'q, Postgres>,
+    {
+        let (query, arguments) = query.into_parts();
+
+        PgExplain {
+            conn: self,
+            query,
+            arguments,
+            analyze: false,
+            verbose: false,
+            buffers: false,
+        }
+    }
+}
+
+/// A builder for the `EXPLAIN` of a query.
+///
+/// Created by [PgConnection::explain].
+#[must_use = "the query is not explained until `.format_json()` or `.format_text()` is called"]
+pub struct PgExplain<'c, 'q> {
+    conn: &'c mut PgConnection,
+    query: &'q str,
+    arguments: Option<PgArguments>,
+    analyze: bool,
+    verbose: bool,
+    buffers: bool,
+}
+
+impl PgExplain<'_, '_> {
+    /// Run the query to measure the actual times and row counts of the plan.
+    ///
+    /// The query is run for its side-effects too; explain an `INSERT`, `UPDATE` or `DELETE`
+    /// inside a transaction that is rolled back to discard them.
+    pub fn analyze(mut self, analyze: bool) -> Self {
+        self.analyze = analyze;
+        self
+    }
+
+    /// Include additional details, such as the output columns of each node of the plan.
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    /// Include the buffer usage of each node of the plan. Requires [`analyze`] before
+    /// Postgres 13.
+    ///
+    /// [`analyze`]: #method.analyze
+    pub fn buffers(mut self, buffers: bool) -> Self {
+        self.buffers = buffers;
+        self
+    }
+
+    /// Explain the query and return its plan.
+    pub async fn format_json(self) -> crate::Result<PgQueryPlan> {
+        let rows = self.run("JSON").await?;
+
+        // the plan is a JSON array with a single element
+        let plan: Vec<PgQueryPlan> =
+            serde_json::from_str(&rows.concat()).map_err(crate::Error::decode)?;
+
+        plan.into_iter()
+            .next()
+            .ok_or_else(|| protocol_err!("explain: no plan returned").into())
+    }
+
+    /// Explain the query and return its plan as the text that `psql` shows.
+    pub async fn format_text(self) -> crate::Result<String> {
+        Ok(self.run("TEXT").await?.join("\n"))
+    }
+
+    async fn run(self, format: &str) -> crate::Result<Vec<String>> {
+        let mut options = Vec::new();
+
+        if self.analyze {
+            options.push("ANALYZE");
+        }
+
+        if self.verbose {
+            options.push("VERBOSE");
+        }
+
+        if self.buffers {
+            options.push("BUFFERS");
+        }
+
+        options.push("FORMAT");
+
+        let statement = format!("EXPLAIN ({} {}) {}", options.join(", "), format, self.query);
+
+        // the statement is different for every query explained and is not worth caching
+        let mut cursor = match self.arguments {
+            Some(arguments) => crate::query::query(&statement)
+                .bind_all(arguments)
+                .persistent(false)
+                .fetch(&mut *self.conn),
+
+            None => crate::executor::Executor::fetch(&mut *self.conn, &*statement),
+        };
+
+        let mut rows = Vec::new();
+
+        while let Some(row) = cursor.next().await? {
+            // the `QUERY PLAN` column is `text`, or `json` for the JSON format
+            rows.push(row.try_get_unchecked::<String, _>(0)?);
+        }
+
+        Ok(rows)
+    }
+}
+
+/// The plan of a query, from [`PgExplain::format_json`].
+#[derive(Debug, Clone, Deserialize)]
+#[non_exhaustive]
+pub struct PgQueryPlan {
+    /// The root node of the plan.
+    #[serde(rename = "Plan")]
+    pub plan: PgPlanNode,
+
+    /// The time taken to plan the query, in milliseconds, with [`PgExplain::analyze`].
+    #[serde(rename = "Planning Time")]
+    pub planning_time: Option<f64>,
+
+    /// The time taken to run the query, in milliseconds, with [`PgExplain::analyze`].
+    #[serde(rename = "Execution Time")]
+    pub execution_time: Option<f64>,
+
+    /// Any other properties of the plan, such as `Triggers`.
+    #[serde(flatten)]
+    pub extra: Map<String, JsonValue>,
+}
+
+/// A node of the plan of a query; a step such as a scan of a table or a join, which takes
+/// the rows of its child nodes as input.
+#[derive(Debug, Clone, Deserialize)]
+#[non_exhaustive]
+pub struct PgPlanNode {
+    /// The kind of the node, e.g. `Seq Scan`, `Index Scan` or `Hash Join`.
+    #[serde(rename = "Node Type")]
+    pub node_type: String,
+
+    /// The table scanned, if this is a scan of a table.
+    #[serde(rename = "Relation Name")]
+    pub relation_name: Option<String>,
+
+    /// The alias of the table scanned, if this is a scan of a table.
+    #[serde(rename = "Alias")]
+    pub alias: Option<String>,
+
+    /// The index used, if this is a scan of an index.
+    #[serde(rename = "Index Name")]
+    pub index_name: Option<String>,
+
+    /// The estimated cost of returning the first row.
+    #[serde(rename = "Startup Cost")]
+    pub startup_cost: Option<f64>,
+
+    /// The estimated cost of returning all rows.
+    #[serde(rename = "Total Cost")]
+    pub total_cost: Option<f64>,
+
+    /// The estimated number of rows returned.
+    #[serde(rename = "Plan Rows")]
+    pub plan_rows: Option<f64>,
+
+    /// The estimated average width of the rows returned, in bytes.
+    #[serde(rename = "Plan Width")]
+    pub plan_width: Option<i64>,
+
+    /// The time taken to return the first row, in milliseconds, with [`PgExplain::analyze`].
+    #[serde(rename = "Actual Startup Time")]
+    pub actual_startup_time: Option<f64>,
+
+    /// The time taken to return all rows, in milliseconds, with [`PgExplain::analyze`].
+    #[serde(rename = "Actual Total Time")]
+    pub actual_total_time: Option<f64>,
+
+    /// The number of rows returned per loop, with [`PgExplain::analyze`].
+    #[serde(rename = "Actual Rows")]
+    pub actual_rows: Option<f64>,
+
+    /// The number of times the node was run, with [`PgExplain::analyze`].
+    #[serde(rename = "Actual Loops")]
+    pub actual_loops: Option<f64>,
+
+    /// The child nodes, whose rows are the input of this node.
+    #[serde(rename = "Plans", default)]
+    pub plans: Vec<PgPlanNode>,
+
+    /// Any other properties of the node, which depend on its kind and the options of the
+    /// `EXPLAIN` (e.g. `Filter`, `Join Type` or `Shared Hit Blocks`).
+    #[serde(flatten)]
+    pub extra: Map<String, JsonValue>,
+}
+
+impl PgPlanNode {
+    /// Returns an iterator over this node and all of its descendants, depth-first.
+    pub fn iter(&self) -> impl Iterator<Item = &PgPlanNode> {
+        let mut stack = vec![self];
+
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.plans.iter().rev());
+
+            Some(node)
+        })
+    }
+}
+
+#[test]
+fn test_deserialize_plan() {
+    let plan: Vec<PgQueryPlan> = serde_json::from_str(
+        r#"[{
+            "Plan": {
+                "Node Type": "Hash Join",
+                "Join Type": "Inner",
+                "Startup Cost": 1.05,
+                "Total Cost": 2.14,
+                "Plan Rows": 3,
+                "Plan Width": 68,
+                "Plans": [
+                    {"Node Type": "Seq Scan", "Relation Name": "a", "Alias": "a", "Plan Rows": 3},
+                    {"Node Type": "Hash", "Plans": [{"Node Type": "Seq Scan", "Relation Name": "b"}]}
+                ]
+            },
+            "Planning Time": 0.1,
+            "Triggers": []
+        }]"#,
+    )
+    .unwrap();
+
+    let plan = &plan[0];
+
+    assert_eq!(plan.planning_time, Some(0.1));
+    assert_eq!(plan.execution_time, None);
+    assert!(plan.extra.contains_key("Triggers"));
+
+    assert_eq!(plan.plan.node_type, "Hash Join");
+    assert_eq!(plan.plan.plan_rows, Some(3.0));
+    assert_eq!(plan.plan.extra["Join Type"], "Inner");
+
+    let nodes: Vec<_> = plan
+        .plan
+        .iter()
+        .map(|node| (&*node.node_type, node.relation_name.as_deref()))
+        .collect();
+
+    assert_eq!(
+        nodes,
+        vec![
+            ("Hash Join", None),
+            ("Seq Scan", Some("a")),
+            ("Hash", None),
+            ("Seq Scan", Some("b")),
+        ]
+    );
+}
@@ -0,0 +1,387 @@
+//! GSSAPI (Kerberos) authentication, using the system GSSAPI library.
+
+// This module binds to the C GSSAPI library (MIT Kerberos or Heimdal) and so must use some
+// unsafe code. The unsafe code is contained to this module.
+#![allow(unsafe_code)]
+
+use std::os::raw::c_void;
+use std::ptr;
+
+use crate::postgres::protocol::{Authentication, GssResponse, Message};
+use crate::postgres::stream::{is_socket_dir, PgStream};
+use crate::url::Url;
+
+type OmUint32 = u32;
+
+#[repr(C)]
+struct GssBufferDesc {
+    length: usize,
+    value: *mut c_void,
+}
+
+#[repr(C)]
+struct GssOidDesc {
+    length: OmUint32,
+    elements: *mut c_void,
+}
+
+type GssOid = *mut GssOidDesc;
+type GssName = *mut c_void;
+type GssCtx = *mut c_void;
+
+const GSS_S_COMPLETE: OmUint32 = 0;
+const GSS_S_CONTINUE_NEEDED: OmUint32 = 1;
+
+// `GSS_C_CALLING_ERROR_MASK << 24 | GSS_C_ROUTINE_ERROR_MASK << 16`
+const GSS_ERROR_MASK: OmUint32 = 0xFFFF_0000;
+
+const GSS_C_MUTUAL_FLAG: OmUint32 = 2;
+
+const GSS_C_GSS_CODE: i32 = 1;
+const GSS_C_MECH_CODE: i32 = 2;
+
+#[cfg_attr(not(target_os = "macos"), link(name = "gssapi_krb5"))]
+#[cfg_attr(target_os = "macos", link(name = "GSS", kind = "framework"))]
+extern "C" {
+    static GSS_C_NT_HOSTBASED_SERVICE: GssOid;
+
+    fn gss_import_name(
+        minor_status: *mut OmUint32,
+        input_name_buffer: *mut GssBufferDesc,
+        input_name_type: GssOid,
+        output_name: *mut GssName,
+    ) -> OmUint32;
+
+    fn gss_release_name(minor_status: *mut OmUint32, name: *mut GssName) -> OmUint32;
+
+    fn gss_init_sec_context(
+        minor_status: *mut OmUint32,
+        initiator_cred_handle: *mut c_void,
+        context_handle: *mut GssCtx,
+        target_name: GssName,
+        mech_type: GssOid,
+        req_flags: OmUint32,
+        time_req: OmUint32,
+        input_chan_bindings: *mut c_void,
+        input_token: *mut GssBufferDesc,
+        actual_mech_type: *mut GssOid,
+        output_token: *mut GssBufferDesc,
+        ret_flags: *mut OmUint32,
+        time_rec: *mut OmUint32,
+    ) -> OmUint32;
+
+    fn gss_release_buffer(minor_status: *mut OmUint32, buffer: *mut GssBufferDesc) -> OmUint32;
+
+    fn gss_display_status(
+        minor_status: *mut OmUint32,
+        status_value: OmUint32,
+        status_type: i32,
+        mech_type: GssOid,
+        message_context: *mut OmUint32,
+        status_string: *mut GssBufferDesc,
+    ) -> OmUint32;
+
+    fn gss_delete_sec_context(
+        minor_status: *mut OmUint32,
+        context_handle: *mut GssCtx,
+        output_token: *mut GssBufferDesc,
+    ) -> OmUint32;
+}
+
+// A client security context, established with the credentials in the default
+// credentials cache (e.g., the Kerberos ticket cache populated by `kinit`)
+struct GssContext {
+    target: GssName,
+    ctx: GssCtx,
+}
+
+// The handles are only used from the one task performing authentication
+unsafe impl Send for GssContext {}
+
+impl GssContext {
+    fn new(service: &str, host: &str) -> crate::Result<Self> {
+        let mut name = format!("{}@{}", service, host).into_bytes();
+
+        let mut buffer = GssBufferDesc {
+            length: name.len(),
+            value: name.as_mut_ptr() as *mut c_void,
+        };
+
+        let mut minor = 0;
+        let mut target = ptr::null_mut();
+
+        let major = unsafe {
+            gss_import_name(
+                &mut minor,
+                &mut buffer,
+                GSS_C_NT_HOSTBASED_SERVICE,
+                &mut target,
+            )
+        };
+
+        check("gss_import_name", major, minor)?;
+
+        Ok(Self {
+            target,
+            ctx: ptr::null_mut(),
+        })
+    }
+
+    // Process a token from the server (none for the first step) and return the token to send
+    // back, if any, and if the context is now fully established
+    fn step(&mut self, input: Option<&[u8]>) -> crate::Result<(Option<Vec<u8>>, bool)> {
+        let mut input = input.map(|input| GssBufferDesc {
+            length: input.len(),
+            value: input.as_ptr() as *mut c_void,
+        });
+
+        let mut output = GssBufferDesc {
+            length: 0,
+            value: ptr::null_mut(),
+        };
+
+        let mut minor = 0;
+
+        let major = unsafe {
+            gss_init_sec_context(
+                &mut minor,
+                ptr::null_mut(),
+                &mut self.ctx,
+                self.target,
+                ptr::null_mut(),
+                GSS_C_MUTUAL_FLAG,
+                0,
+                ptr::null_mut(),
+                input
+                    .as_mut()
+                    .map_or(ptr::null_mut(), |input| input as *mut _),
+                ptr::null_mut(),
+                &mut output,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            )
+        };
+
+        let token = if output.length > 0 {
+            let token = unsafe {
+                std::slice::from_raw_parts(output.value as *const u8, output.length).to_vec()
+            };
+
+            unsafe {
+                gss_release_buffer(&mut 0, &mut output);
+            }
+
+            Some(token)
+        } else {
+            None
+        };
+
+        check("gss_init_sec_context", major, minor)?;
+
+        Ok((token, major & !GSS_ERROR_MASK == GSS_S_COMPLETE))
+    }
+}
+
+impl Drop for GssContext {
+    fn drop(&mut self) {
+        unsafe {
+            if !self.ctx.is_null() {
+                gss_delete_sec_context(&mut 0, &mut self.ctx, ptr::null_mut());
+            }
+
+            gss_release_name(&mut 0, &mut self.target);
+        }
+    }
+}
+
+fn check(function: &str, major: OmUint32, minor: OmUint32) -> crate::Result<()> {
+    if major & GSS_ERROR_MASK != 0 {
+        return Err(protocol_err!(
+            "GSSAPI authentication failed: {}: {}: {}",
+            function,
+            display_status(major, GSS_C_GSS_CODE),
+            display_status(minor, GSS_C_MECH_CODE)
+        )
+        .into());
+    }
+
+    debug_assert!(matches!(
+        major & !GSS_ERROR_MASK,
+        GSS_S_COMPLETE | GSS_S_CONTINUE_NEEDED
+    ));
+
+    Ok(())
+}
+
+// Describe a major (GSS) or minor (mechanism) status code
+fn display_status(status: OmUint32, status_type: i32) -> String {
+    let mut messages = Vec::new();
+    let mut message_context = 0;
+
+    loop {
+        let mut buffer = GssBufferDesc {
+            length: 0,
+            value: ptr::null_mut(),
+        };
+
+        let major = unsafe {
+            gss_display_status(
+                &mut 0,
+                status,
+                status_type,
+                ptr::null_mut(),
+                &mut message_context,
+                &mut buffer,
+            )
+        };
+
+        if major & GSS_ERROR_MASK != 0 {
+            break;
+        }
+
+        let message = unsafe {
+            let message = std::slice::from_raw_parts(buffer.value as *const u8, buffer.length);
+            let message = String::from_utf8_lossy(message).into_owned();
+
+            gss_release_buffer(&mut 0, &mut buffer);
+
+            message
+        };
+
+        messages.push(message);
+
+        if message_context == 0 {
+            break;
+        }
+    }
+
+    if messages.is_empty() {
+        format!("status 0x{:08X}", status)
+    } else {
+        messages.join(", ")
+    }
+}
+
+// Performs the GSSAPI authentication exchange, after the server has requested it with
+// `AuthenticationGSS`, up until the context is established; the server then responds with
+// `AuthenticationOk` or an error.
+//
+// The service name is `postgres` unless set with the `krbsrvname` parameter, as in libpq.
+pub(super) async fn authenticate(
+    stream: &mut PgStream,
+    url: &Url,
+    host: &str,
+) -> crate::Result<()> {
+    let service = url.param("krbsrvname");
+    let host = principal_host(host)?;
+
+    let mut context = GssContext::new(service.as_deref().unwrap_or("postgres"), &host)?;
+    let mut input = None;
+
+    loop {
+        let (token, complete) = context.step(input.as_deref())?;
+
+        if let Some(token) = token {
+            stream.write(GssResponse(&token));
+            stream.flush().await?;
+        }
+
+        if complete {
+            return Ok(());
+        }
+
+        match stream.receive().await? {
+            Message::Authentication => match Authentication::read(stream.buffer())? {
+                // TODO: Better way to make sure we skip the first 4 bytes here
+                Authentication::GssContinue => {
+                    input = Some(stream.buffer()[4..].to_vec());
+                }
+
+                auth => {
+                    return Err(protocol_err!(
+                        "expected Authentication::GssContinue, but received {:?}",
+                        auth
+                    )
+                    .into());
+                }
+            },
+
+            message => {
+                return Err(protocol_err!(
+                    "expected Message::Authentication, but received {:?}",
+                    message
+                )
+                .into());
+            }
+        }
+    }
+}
+
+// The host of the service principal. A Unix domain socket is given by its directory instead
+// of a host name; the server is on this host then, so its name is used.
+fn principal_host(host: &str) -> crate::Result<String> {
+    if !is_socket_dir(host) {
+        return Ok(host.to_owned());
+    }
+
+    match local_hostname() {
+        Ok(name) => Ok(name),
+
+        Err(e) => Err(protocol_err!(
+            "GSSAPI authentication over the Unix domain socket in {:?} needs the name of \
+             this host, which is not known: {}",
+            host,
+            e
+        )
+        .into()),
+    }
+}
+
+#[cfg(unix)]
+fn local_hostname() -> std::io::Result<String> {
+    use std::io;
+
+    let mut buf = [0_u8; 256];
+
+    // the name is not terminated if it is truncated, so one byte is left for a NUL
+    let res = unsafe { libc::gethostname(buf.as_mut_ptr() as *mut libc::c_char, buf.len() - 1) };
+
+    if res != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(0);
+
+    if len == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "the host name is empty",
+        ));
+    }
+
+    String::from_utf8(buf[..len].to_vec())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(not(unix))]
+fn local_hostname() -> std::io::Result<String> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Other,
+        "Unix domain sockets are only supported on Unix",
+    ))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::principal_host;
+
+    #[test]
+    fn it_uses_the_local_hostname_for_unix_sockets() {
+        assert_eq!(principal_host("db.example.com").unwrap(), "db.example.com");
+
+        let host = principal_host("/var/run/postgresql").unwrap();
+
+        assert!(!host.is_empty());
+        assert!(!host.contains('/'));
+    }
+}
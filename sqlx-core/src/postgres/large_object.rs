@@ -0,0 +1,407 @@
+use std::io::{self, SeekFrom};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::future::BoxFuture;
+use futures_util::ready;
+
+use crate::postgres::{PgConnection, PgQueryAs};
+use crate::query_as::query_as;
+use crate::runtime::{AsyncRead, AsyncWrite};
+
+// Largest amount of data transferred by a single call to `loread` or `lowrite`
+const MAX_CHUNK_SIZE: usize = 256 * 1024;
+
+// https://github.com/postgres/postgres/blob/master/src/include/libpq/libpq-fs.h
+const INV_WRITE: i32 = 0x0002_0000;
+const INV_READ: i32 = 0x0004_0000;
+
+/// The mode to open a [PgLargeObject] in.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PgLargeObjectMode {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+/// An open PostgreSQL [large object](https://www.postgresql.org/docs/12/largeobjects.html).
+///
+/// The contents of a large object are streamed in chunks through [AsyncRead] and [AsyncWrite],
+/// so they never need to be held in memory at once.
+///
+/// Large object descriptors are only valid for the duration of a transaction; a large object
+/// must be opened and used inside of one.
+///
+/// ```rust,ignore
+/// let mut tx = conn.begin().await?;
+///
+/// let oid = tx.create_large_object().await?;
+/// let mut lo = tx.open_large_object(oid, PgLargeObjectMode::Write).await?;
+///
+/// io::copy(file, &mut lo).await?;
+///
+/// lo.close().await?;
+/// tx.commit().await?;
+/// ```
+pub struct PgLargeObject<'c> {
+    fd: i32,
+    state: State<'c>,
+
+    // data received by `loread` that did not fit in the buffer passed to `poll_read`
+    read_buf: Vec<u8>,
+    read_pos: usize,
+}
+
+enum State<'c> {
+    Idle(&'c mut PgConnection),
+    Reading(BoxFuture<'c, (&'c mut PgConnection, crate::Result<Vec<u8>>)>),
+    Writing(BoxFuture<'c, (&'c mut PgConnection, crate::Result<i32>)>),
+
+    // only observed if a future panics
+    Poisoned,
+}
+
+impl PgConnection {
+    /// Create a new, empty, large object and return its OID.
+    pub async fn create_large_object(&mut self) -> crate::Result<u32> {
+        let (oid,): (u32,) = query_as("SELECT lo_create(0)").fetch_one(self).await?;
+
+        Ok(oid)
+    }
+
+    /// Open the large object with the given OID.
+    ///
+    /// This must be done inside of a transaction.
+    pub async fn open_large_object(
+        &mut self,
+        oid: u32,
+        mode: PgLargeObjectMode,
+    ) -> crate::Result<PgLargeObject<'_>> {
+        let mode = match mode {
+            PgLargeObjectMode::Read => INV_READ,
+            PgLargeObjectMode::Write => INV_WRITE,
+            PgLargeObjectMode::ReadWrite => INV_READ | INV_WRITE,
+        };
+
+        let (fd,): (i32,) = query_as("SELECT lo_open($1, $2)")
+            .bind(oid)
+            .bind(mode)
+            .fetch_one(&mut *self)
+            .await?;
+
+        Ok(PgLargeObject {
+            fd,
+            state: State::Idle(self),
+            read_buf: Vec::new(),
+            read_pos: 0,
+        })
+    }
+
+    /// Delete the large object with the given OID.
+    pub async fn unlink_large_object(&mut self, oid: u32) -> crate::Result<()> {
+        let (_,): (i32,) = query_as("SELECT lo_unlink($1)")
+            .bind(oid)
+            .fetch_one(self)
+            .await?;
+
+        Ok(())
+    }
+}
+
+impl<'c> PgLargeObject<'c> {
+    /// Move the current position in the large object and return the new position.
+    pub async fn seek(&mut self, pos: SeekFrom) -> crate::Result<u64> {
+        // https://github.com/postgres/postgres/blob/master/src/include/libpq/libpq-fs.h
+        let (offset, whence) = match pos {
+            SeekFrom::Start(offset) => (offset as i64, 0),
+            SeekFrom::Current(offset) => (offset, 1),
+            SeekFrom::End(offset) => (offset, 2),
+        };
+
+        // unread data that was buffered is skipped over by a relative seek
+        let offset = if let SeekFrom::Current(_) = pos {
+            offset - (self.read_buf.len() - self.read_pos) as i64
+        } else {
+            offset
+        };
+
+        let fd = self.fd;
+        let conn = self.idle().await?;
+
+        let (pos,): (i64,) = query_as("SELECT lo_lseek64($1, $2, $3)")
+            .bind(fd)
+            .bind(offset)
+            .bind(whence)
+            .fetch_one(conn)
+            .await?;
+
+        self.read_buf.clear();
+        self.read_pos = 0;
+
+        Ok(pos as u64)
+    }
+
+    /// Return the current position in the large object.
+    pub async fn tell(&mut self) -> crate::Result<u64> {
+        self.seek(SeekFrom::Current(0)).await
+    }
+
+    /// Truncate (or extend, with zeroes) the large object to `len` bytes.
+    pub async fn truncate(&mut self, len: u64) -> crate::Result<()> {
+        let fd = self.fd;
+        let conn = self.idle().await?;
+
+        let (_,): (i32,) = query_as("SELECT lo_truncate64($1, $2)")
+            .bind(fd)
+            .bind(len as i64)
+            .fetch_one(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Close the large object descriptor.
+    ///
+    /// Descriptors are closed automatically at the end of the transaction; this is only
+    /// needed to release it earlier.
+    pub async fn close(mut self) -> crate::Result<()> {
+        let fd = self.fd;
+        let conn = self.idle().await?;
+
+        let (_,): (i32,) = query_as("SELECT lo_close($1)")
+            .bind(fd)
+            .fetch_one(conn)
+            .await?;
+
+        Ok(())
+    }
+
+    // Wait for any in-flight read or write to complete and return the connection
+    async fn idle(&mut self) -> crate::Result<&mut PgConnection> {
+        let result = match std::mem::replace(&mut self.state, State::Poisoned) {
+            State::Idle(conn) => {
+                self.state = State::Idle(conn);
+                Ok(())
+            }
+
+            State::Reading(fut) => {
+                let (conn, res) = fut.await;
+                self.state = State::Idle(conn);
+
+                // the data was never returned to the caller; discard it
+                res.map(|_| ())
+            }
+
+            State::Writing(fut) => {
+                let (conn, res) = fut.await;
+                self.state = State::Idle(conn);
+
+                res.map(|_| ())
+            }
+
+            State::Poisoned => Err(protocol_err!("(bug) large object operation panicked").into()),
+        };
+
+        result?;
+
+        match &mut self.state {
+            State::Idle(conn) => Ok(&mut **conn),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl AsyncRead for PgLargeObject<'_> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+
+        loop {
+            if this.read_pos < this.read_buf.len() {
+                let data = &this.read_buf[this.read_pos..];
+                let len = data.len().min(buf.len());
+
+                buf[..len].copy_from_slice(&data[..len]);
+                this.read_pos += len;
+
+                return Poll::Ready(Ok(len));
+            }
+
+            match std::mem::replace(&mut this.state, State::Poisoned) {
+                State::Idle(conn) => {
+                    if buf.is_empty() {
+                        this.state = State::Idle(conn);
+                        return Poll::Ready(Ok(0));
+                    }
+
+                    let fd = this.fd;
+                    let len = buf.len().min(MAX_CHUNK_SIZE) as i32;
+
+                    this.state = State::Reading(Box::pin(async move {
+                        let res = query_as("SELECT loread($1, $2)")
+                            .bind(fd)
+                            .bind(len)
+                            .fetch_one(&mut *conn)
+                            .await
+                            .map(|(data,): (Vec<u8>,)| data);
+
+                        (conn, res)
+                    }));
+                }
+
+                State::Reading(mut fut) => {
+                    let (conn, res) = match fut.as_mut().poll(cx) {
+                        Poll::Ready(ready) => ready,
+                        Poll::Pending => {
+                            this.state = State::Reading(fut);
+                            return Poll::Pending;
+                        }
+                    };
+
+                    this.state = State::Idle(conn);
+
+                    let data = res.map_err(into_io_error)?;
+
+                    if data.is_empty() {
+                        // end of the large object
+                        return Poll::Ready(Ok(0));
+                    }
+
+                    this.read_buf = data;
+                    this.read_pos = 0;
+                }
+
+                State::Writing(fut) => {
+                    this.state = State::Writing(fut);
+                    ready!(poll_write_complete(this, cx))?;
+                }
+
+                State::Poisoned => return Poll::Ready(Err(poisoned())),
+            }
+        }
+    }
+}
+
+impl AsyncWrite for PgLargeObject<'_> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+
+        match std::mem::replace(&mut this.state, State::Poisoned) {
+            State::Idle(conn) => {
+                // buffered data from a read is ahead of the position in postgres
+                // and there is no meaningful place to write it
+                if this.read_pos < this.read_buf.len() {
+                    this.state = State::Idle(conn);
+
+                    return Poll::Ready(Err(other_error(
+                        "cannot write to a large object with unread buffered data; seek first",
+                    )));
+                }
+
+                let fd = this.fd;
+                let data = buf[..buf.len().min(MAX_CHUNK_SIZE)].to_vec();
+
+                this.state = State::Writing(Box::pin(async move {
+                    let res = query_as("SELECT lowrite($1, $2)")
+                        .bind(fd)
+                        .bind(data)
+                        .fetch_one(&mut *conn)
+                        .await
+                        .map(|(written,): (i32,)| written);
+
+                    (conn, res)
+                }));
+
+                poll_write_complete(this, cx)
+            }
+
+            // a write started by a previous call; it is assumed that the caller is
+            // writing the same buffer again, as is required after `Poll::Pending`
+            state @ State::Writing(_) => {
+                this.state = state;
+
+                poll_write_complete(this, cx)
+            }
+
+            State::Reading(fut) => {
+                this.state = State::Reading(fut);
+
+                Poll::Ready(Err(other_error(
+                    "cannot write to a large object while a read is in progress",
+                )))
+            }
+
+            State::Poisoned => Poll::Ready(Err(poisoned())),
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+
+        if let State::Writing(_) = this.state {
+            ready!(poll_write_complete(this, cx))?;
+        }
+
+        Poll::Ready(Ok(()))
+    }
+
+    #[cfg(feature = "runtime-async-std")]
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+
+    #[cfg(feature = "runtime-tokio")]
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+// Drive an in-flight write to completion and return the number of bytes written
+fn poll_write_complete(this: &mut PgLargeObject<'_>, cx: &mut Context) -> Poll<io::Result<usize>> {
+    match std::mem::replace(&mut this.state, State::Poisoned) {
+        State::Writing(mut fut) => match fut.as_mut().poll(cx) {
+            Poll::Ready((conn, res)) => {
+                this.state = State::Idle(conn);
+
+                Poll::Ready(res.map(|written| written as usize).map_err(into_io_error))
+            }
+
+            Poll::Pending => {
+                this.state = State::Writing(fut);
+
+                Poll::Pending
+            }
+        },
+
+        state => {
+            this.state = state;
+
+            Poll::Ready(Ok(0))
+        }
+    }
+}
+
+fn into_io_error(error: crate::Error) -> io::Error {
+    match error {
+        crate::Error::Io(error) => error,
+        error => other_error(error),
+    }
+}
+
+fn poisoned() -> io::Error {
+    other_error("(bug) large object operation panicked")
+}
+
+fn other_error<E>(error: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::other(error)
+}
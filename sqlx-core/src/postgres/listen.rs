@@ -1,8 +1,9 @@
+use std::collections::VecDeque;
 use std::fmt::{self, Debug};
 use std::io;
+use std::sync::{Arc, Mutex};
 
 use async_stream::try_stream;
-use futures_channel::mpsc;
 use futures_core::future::BoxFuture;
 use futures_core::stream::Stream;
 
@@ -18,14 +19,34 @@ use crate::postgres::{PgConnection, PgCursor, Postgres};
 /// connection being used ever dies, this listener will detect that event, create a
 /// new connection, will re-subscribe to all of the originally specified channels, and will resume
 /// operations as normal.
+///
+/// Postgres does not keep notifications for a listener that is disconnected, so any sent
+/// while reconnecting are lost; [notifications_missed] reports when this may have happened.
+///
+/// [notifications_missed]: PgListener::notifications_missed
 pub struct PgListener {
     pool: Pool<PgConnection>,
     connection: Option<PoolConnection<PgConnection>>,
-    buffer_rx: mpsc::UnboundedReceiver<NotificationResponse<'static>>,
-    buffer_tx: Option<mpsc::UnboundedSender<NotificationResponse<'static>>>,
+    buffer: Arc<Mutex<NotificationBuffer>>,
     channels: Vec<String>,
+
+    // notifications may have been lost since `notifications_missed` was last called
+    missed: bool,
+}
+
+// Notifications received while the listener is used to execute queries, until they are
+// taken by `recv`
+pub(super) struct NotificationBuffer {
+    queue: VecDeque<NotificationResponse<'static>>,
+    capacity: usize,
+
+    // notifications were dropped because the buffer was full
+    overflowed: bool,
 }
 
+// The default capacity of the notification buffer
+const DEFAULT_BUFFER_CAPACITY: usize = 1024;
+
 /// An asynchronous notification from Postgres.
 pub struct PgNotification<'c>(NotificationResponse<'c>);
 
@@ -48,22 +69,50 @@ impl PgListener {
         let mut connection = pool.acquire().await?;
 
         // Setup a notification buffer
-        let (sender, receiver) = mpsc::unbounded();
-        connection.stream.notifications = Some(sender);
+        let buffer = Arc::new(Mutex::new(NotificationBuffer {
+            queue: VecDeque::new(),
+            capacity: DEFAULT_BUFFER_CAPACITY,
+            overflowed: false,
+        }));
+
+        connection.stream.notifications = Some(Arc::clone(&buffer));
 
         Ok(Self {
             pool: pool.clone(),
             connection: Some(connection),
-            buffer_rx: receiver,
-            buffer_tx: None,
+            buffer,
             channels: Vec::new(),
+            missed: false,
         })
     }
 
+    /// Sets the number of notifications that are kept while this listener is used to execute
+    /// queries, instead of waiting in [recv][Self::recv]. The default is 1024.
+    ///
+    /// Once the buffer is full, the oldest notifications are dropped.
+    pub fn set_buffer_capacity(&mut self, capacity: usize) {
+        let mut buffer = self.buffer.lock().unwrap();
+
+        buffer.capacity = capacity.max(1);
+
+        while buffer.queue.len() > buffer.capacity {
+            buffer.queue.pop_front();
+            buffer.overflowed = true;
+        }
+    }
+
+    /// Returns `true` if notifications may have been missed since the previous call, because
+    /// the connection was lost and re-established or the buffer was full.
+    pub fn notifications_missed(&mut self) -> bool {
+        let overflowed = std::mem::replace(&mut self.buffer.lock().unwrap().overflowed, false);
+
+        std::mem::replace(&mut self.missed, false) || overflowed
+    }
+
     /// Starts listening for notifications on a channel.
     pub async fn listen(&mut self, channel: &str) -> crate::Result<()> {
         self.connection()
-            .execute(&*format!("LISTEN {}", ident(channel)))
+            .execute(&*format!(r#"LISTEN "{}""#, ident(channel)))
             .await?;
 
         self.channels.push(channel.to_owned());
@@ -91,7 +140,7 @@ impl PgListener {
     /// Stops listening for notifications on a channel.
     pub async fn unlisten(&mut self, channel: &str) -> crate::Result<()> {
         self.connection()
-            .execute(&*format!("UNLISTEN {}", ident(channel)))
+            .execute(&*format!(r#"UNLISTEN "{}""#, ident(channel)))
             .await?;
 
         if let Some(pos) = self.channels.iter().position(|s| s == channel) {
@@ -114,7 +163,7 @@ impl PgListener {
     async fn connect_if_needed(&mut self) -> crate::Result<()> {
         if let None = self.connection {
             let mut connection = self.pool.acquire().await?;
-            connection.stream.notifications = self.buffer_tx.take();
+            connection.stream.notifications = Some(Arc::clone(&self.buffer));
 
             connection
                 .execute(&*build_listen_all_query(&self.channels))
@@ -135,7 +184,7 @@ impl PgListener {
     pub async fn recv(&mut self) -> crate::Result<PgNotification<'_>> {
         // Flush the buffer first, if anything
         // This would only fill up if this listener is used as a connection
-        if let Ok(Some(notification)) = self.buffer_rx.try_next() {
+        if let Some(notification) = self.buffer.lock().unwrap().queue.pop_front() {
             return Ok(PgNotification(notification));
         }
 
@@ -162,9 +211,9 @@ impl PgListener {
 
                 // The connection is dead, ensure that it is dropped,
                 // update self state, and loop to try again.
-                Err(crate::Error::Io(err)) if err.kind() == io::ErrorKind::ConnectionAborted => {
-                    self.buffer_tx = self.connection().stream.notifications.take();
+                Err(crate::Error::Io(err)) if is_connection_lost(&err) => {
                     self.connection = None;
+                    self.missed = true;
                 }
 
                 // Forward other errors
@@ -175,6 +224,16 @@ impl PgListener {
         }
     }
 
+    /// Receives the next notification and deserializes its payload from JSON.
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub async fn recv_as<T>(&mut self) -> crate::Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        self.recv().await?.payload_as()
+    }
+
     /// Consume this listener, returning a `Stream` of notifications.
     pub fn into_stream(
         mut self,
@@ -188,6 +247,17 @@ impl PgListener {
     }
 }
 
+impl Drop for PgListener {
+    fn drop(&mut self) {
+        if let Some(mut connection) = self.connection.take() {
+            // the connection is returned to the pool; stop buffering notifications and
+            // queue up an `UNLISTEN` to be run before it is next used
+            connection.stream.notifications = None;
+            connection.queue_query("UNLISTEN *");
+        }
+    }
+}
+
 impl Executor for PgListener {
     type Database = Postgres;
 
@@ -231,6 +301,40 @@ impl<'c> RefExecutor<'c> for &'c mut PgListener {
     }
 }
 
+impl PgConnection {
+    /// Sends a notification on a channel, to every connection listening on it.
+    ///
+    /// The channel name is used exactly as given, as [`PgListener::listen`] does; it is not
+    /// folded to lower case. A notification sent inside a transaction is only delivered
+    /// once the transaction is committed.
+    ///
+    /// The payload must be shorter than 8000 bytes (by default).
+    pub async fn notify(&mut self, channel: &str, payload: &str) -> crate::Result<()> {
+        // the channel and payload are bound, so neither has to be quoted
+        crate::query::query("SELECT pg_notify($1, $2)")
+            .bind(channel)
+            .bind(payload)
+            .execute(self)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Sends a notification on a channel, serializing the payload to JSON.
+    ///
+    /// The notification can be received with [`PgListener::recv_as`].
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub async fn notify_json<T>(&mut self, channel: &str, payload: &T) -> crate::Result<()>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        let payload = serde_json::to_string(payload).map_err(crate::Error::decode)?;
+
+        self.notify(channel, &payload).await
+    }
+}
+
 impl PgNotification<'_> {
     /// The process ID of the notifying backend process.
     #[inline]
@@ -252,6 +356,16 @@ impl PgNotification<'_> {
         self.0.payload.as_ref()
     }
 
+    /// Deserializes the payload of the notification from JSON.
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn payload_as<T>(&self) -> crate::Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        serde_json::from_str(self.payload()).map_err(crate::Error::decode)
+    }
+
     fn into_owned(self) -> PgNotification<'static> {
         PgNotification(self.0.into_owned())
     }
@@ -267,6 +381,27 @@ impl Debug for PgNotification<'_> {
     }
 }
 
+impl NotificationBuffer {
+    pub(super) fn push(&mut self, notification: NotificationResponse<'static>) {
+        if self.queue.len() >= self.capacity {
+            self.queue.pop_front();
+            self.overflowed = true;
+        }
+
+        self.queue.push_back(notification);
+    }
+}
+
+fn is_connection_lost(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::BrokenPipe
+            | io::ErrorKind::UnexpectedEof
+    )
+}
+
 fn ident(mut name: &str) -> String {
     // If the input string contains a NUL byte, we should truncate the
     // identifier.
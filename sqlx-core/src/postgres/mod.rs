@@ -1,29 +1,60 @@
 //! **Postgres** database and connection types.
 
+pub use advisory_lock::{PgAdvisoryLock, PgAdvisoryLockGuard, PgAdvisoryLockKey};
 pub use arguments::PgArguments;
 pub use buffer::PgRawBuffer;
+pub use cancel::PgCancelToken;
 pub use connection::PgConnection;
+pub use copy::{PgCopyIn, PgCopyInBuilder, PgCopyInRows, PgCopyRow};
 pub use cursor::PgCursor;
 pub use database::Postgres;
 pub use error::PgError;
+#[cfg(feature = "json")]
+pub use explain::{PgExplain, PgPlanNode, PgQueryPlan};
+pub use large_object::{PgLargeObject, PgLargeObjectMode};
 pub use listen::{PgListener, PgNotification};
-pub use row::PgRow;
+pub use notice::PgNotice;
+pub use pipeline::{PgPipeline, PgPipelineCursor};
+pub use protocol::Severity as PgSeverity;
+pub use replica_set::PgReplicaSet;
+pub use row::{PgColumn, PgRow};
+pub use server_cursor::PgServerCursor;
+pub use statement_cache::PgStatementCacheStats;
+pub use type_cache::PgTypeCache;
 pub use type_info::PgTypeInfo;
 pub use value::{PgData, PgValue};
 
+mod advisory_lock;
 mod arguments;
 mod buffer;
+mod cancel;
 mod connection;
+mod copy;
 mod cursor;
 mod database;
 mod error;
 mod executor;
+#[cfg(feature = "json")]
+mod explain;
+#[cfg(feature = "gssapi")]
+mod gss;
+mod large_object;
 mod listen;
+mod notice;
+mod pipeline;
+mod procedure;
 mod protocol;
+mod replica_set;
+pub mod replication;
 mod row;
 mod sasl;
+mod server_cursor;
+mod srv;
+mod statement_cache;
 mod stream;
 mod tls;
+pub(crate) mod two_phase;
+mod type_cache;
 mod type_info;
 pub mod types;
 mod value;
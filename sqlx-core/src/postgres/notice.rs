@@ -0,0 +1,57 @@
+use crate::postgres::protocol::Response;
+use crate::postgres::{PgConnection, PgSeverity};
+
+/// A notice or warning sent by Postgres, e.g., from `RAISE NOTICE` in PL/pgSQL.
+///
+/// Notices are not errors; the query that caused them continues. They are passed to the
+/// handler set with [PgConnection::set_notice_handler].
+#[derive(Debug)]
+pub struct PgNotice(pub(super) Response);
+
+pub(super) type NoticeHandler = Box<dyn Fn(PgNotice) + Send + Sync>;
+
+impl PgNotice {
+    pub fn severity(&self) -> PgSeverity {
+        self.0.severity
+    }
+
+    /// The SQLSTATE code of the notice.
+    pub fn code(&self) -> &str {
+        &self.0.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.0.message
+    }
+
+    pub fn detail(&self) -> Option<&str> {
+        self.0.detail.as_deref()
+    }
+
+    pub fn hint(&self) -> Option<&str> {
+        self.0.hint.as_deref()
+    }
+}
+
+impl PgConnection {
+    /// Set a function to be called with every notice received on this connection,
+    /// replacing any previous handler.
+    ///
+    /// The handler is called while the connection is reading the response to a query, and
+    /// so should not block. Without a handler, notices are discarded.
+    ///
+    /// ```rust,ignore
+    /// conn.set_notice_handler(|notice| log::info!("{}", notice.message()));
+    /// ```
+    pub fn set_notice_handler<F>(&mut self, handler: F)
+    where
+        F: Fn(PgNotice) + Send + Sync + 'static,
+    {
+        self.stream.notice_handler = Some(Box::new(handler));
+    }
+
+    /// Remove the handler set with [set_notice_handler][Self::set_notice_handler].
+    pub fn clear_notice_handler(&mut self) {
+        self.stream.notice_handler = None;
+    }
+}
@@ -0,0 +1,263 @@
+use std::sync::Arc;
+
+use crate::executor::Execute;
+use crate::postgres::protocol::{CommandComplete, DataRow, Message, ReadyForQuery, StatementId};
+use crate::postgres::row::Statement;
+use crate::postgres::{PgArguments, PgConnection, PgRow, Postgres};
+
+/// A batch of queries to be sent to Postgres in a single round-trip.
+///
+/// Each query is sent using the extended query protocol (as `Bind` and `Execute`
+/// messages) and the whole batch is followed by a single `Sync`. Postgres still executes
+/// the queries one after the other but the client does not wait for the results of a query
+/// before sending the next one.
+///
+/// Statements that have not been seen on this connection before are prepared (one
+/// round-trip each) before the batch is sent. The statement of a query that is not
+/// [`persistent`](crate::query::Query::persistent) is not cached; it is closed after the
+/// pipeline.
+///
+/// If a query fails, Postgres skips the remaining queries in the pipeline and the error is
+/// returned.
+///
+/// ```rust,ignore
+/// let mut pipeline = conn.pipeline();
+///
+/// pipeline.push(sqlx::query("INSERT INTO logs (message) VALUES ($1)").bind("foo"));
+/// pipeline.push(sqlx::query("INSERT INTO logs (message) VALUES ($1)").bind("bar"));
+/// pipeline.push("DELETE FROM logs WHERE message = 'baz'");
+///
+/// let affected: Vec<u64> = pipeline.execute().await?;
+/// ```
+pub struct PgPipeline<'c, 'q> {
+    conn: &'c mut PgConnection,
+    queries: Vec<(&'q str, PgArguments, bool)>,
+}
+
+/// The results of a [PgPipeline].
+///
+/// Returned from [PgPipeline::fetch].
+pub struct PgPipelineCursor<'c> {
+    conn: &'c mut PgConnection,
+
+    // description of each statement in the pipeline, in order
+    statements: Vec<Arc<Statement>>,
+
+    // index of the statement currently returning results
+    index: usize,
+
+    done: bool,
+
+    // the statements prepared for non-persistent queries are closed after the pipeline,
+    // which is followed by a second [ReadyForQuery]
+    closing: bool,
+}
+
+impl PgConnection {
+    /// Create a new, empty, [PgPipeline] on this connection.
+    pub fn pipeline<'q>(&mut self) -> PgPipeline<'_, 'q> {
+        PgPipeline {
+            conn: self,
+            queries: Vec::new(),
+        }
+    }
+}
+
+impl<'c, 'q> PgPipeline<'c, 'q> {
+    /// Add a query to the end of the pipeline.
+    pub fn push<E>(&mut self, query: E) -> &mut Self
+    where
+        E: Execute<'q, Postgres>,
+    {
+        let persistent = query.persistent();
+        let (query, arguments) = query.into_parts();
+
+        self.queries
+            .push((query, arguments.unwrap_or_default(), persistent));
+        self
+    }
+
+    /// Returns the number of queries in the pipeline.
+    pub fn len(&self) -> usize {
+        self.queries.len()
+    }
+
+    /// Returns `true` if no queries have been added to the pipeline.
+    pub fn is_empty(&self) -> bool {
+        self.queries.is_empty()
+    }
+
+    /// Send all queries and return the number of rows affected by each, in order.
+    pub async fn execute(self) -> crate::Result<Vec<u64>> {
+        let mut cursor = self.run().await?;
+        let mut affected = vec![0; cursor.statements.len()];
+
+        while !cursor.done {
+            match cursor.conn.stream.receive().await? {
+                Message::BindComplete | Message::DataRow => {}
+
+                Message::CommandComplete => {
+                    affected[cursor.index] =
+                        CommandComplete::read(cursor.conn.stream.buffer())?.affected_rows;
+
+                    cursor.index += 1;
+                }
+
+                Message::EmptyQueryResponse => {
+                    cursor.index += 1;
+                }
+
+                Message::ReadyForQuery => {
+                    let _ready = ReadyForQuery::read(cursor.conn.stream.buffer())?;
+
+                    cursor.finish();
+                }
+
+                message => {
+                    return Err(protocol_err!("pipeline: unexpected message: {:?}", message).into());
+                }
+            }
+        }
+
+        Ok(affected)
+    }
+
+    /// Send all queries and return a cursor over the rows returned by each.
+    pub async fn fetch(self) -> crate::Result<PgPipelineCursor<'c>> {
+        self.run().await
+    }
+
+    async fn run(self) -> crate::Result<PgPipelineCursor<'c>> {
+        let Self { conn, mut queries } = self;
+
+        let mut temporary = Vec::new();
+
+        let prepared = match prepare(conn, &mut queries, &mut temporary).await {
+            Ok(prepared) => prepared,
+
+            Err(error) => {
+                conn.queue_close_statements(&temporary);
+
+                return Err(error);
+            }
+        };
+
+        conn.wait_until_ready().await?;
+
+        let mut statements = Vec::with_capacity(prepared.len());
+
+        for ((id, statement), (_, mut arguments, _)) in prepared.into_iter().zip(queries) {
+            // all type holes were patched above; this will not need to query Postgres
+            conn.write_bind("", id, &mut arguments).await?;
+            conn.write_execute("", 0);
+
+            statements.push(statement);
+        }
+
+        conn.write_sync();
+        conn.is_ready = false;
+
+        // closed after the `Sync`, so that they are closed even if a query fails
+        conn.queue_close_statements(&temporary);
+
+        conn.stream.flush().await?;
+
+        Ok(PgPipelineCursor {
+            conn,
+            statements,
+            index: 0,
+            done: false,
+            closing: !temporary.is_empty(),
+        })
+    }
+}
+
+// Preparing a statement or resolving the OID of a type requires a round-trip so this must all
+// happen before we start writing the pipeline
+async fn prepare(
+    conn: &mut PgConnection,
+    queries: &mut [(&str, PgArguments, bool)],
+    temporary: &mut Vec<StatementId>,
+) -> crate::Result<Vec<(StatementId, Arc<Statement>)>> {
+    let mut prepared = Vec::with_capacity(queries.len());
+
+    for (query, arguments, persistent) in queries {
+        if *persistent {
+            let id = conn.write_prepare(query, arguments, true).await?;
+
+            prepared.push((id, Arc::clone(&conn.cache_statement[&id])));
+        } else {
+            // The queries of a pipeline can not share the unnamed statement, so each
+            // non-persistent one is prepared as its own statement, which is not cached
+            let id = StatementId(conn.next_statement_id);
+
+            conn.next_statement_id += 1;
+
+            let statement = conn.prepare(id, query, arguments).await?;
+            temporary.push(id);
+
+            prepared.push((id, Arc::new(statement)));
+        }
+
+        arguments.buffer.patch_type_holes(conn).await?;
+    }
+
+    Ok(prepared)
+}
+
+impl<'c> PgPipelineCursor<'c> {
+    /// Fetch the next row, along with the index of the query in the pipeline that
+    /// returned it.
+    ///
+    /// Returns `None` once every query in the pipeline has completed.
+    pub async fn next(&mut self) -> crate::Result<Option<(usize, PgRow<'_>)>> {
+        while !self.done {
+            match self.conn.stream.receive().await? {
+                Message::BindComplete => {}
+
+                Message::CommandComplete | Message::EmptyQueryResponse => {
+                    self.index += 1;
+                }
+
+                Message::ReadyForQuery => {
+                    let _ready = ReadyForQuery::read(self.conn.stream.buffer())?;
+
+                    self.finish();
+                }
+
+                Message::DataRow => {
+                    let data = DataRow::read(
+                        self.conn.stream.buffer(),
+                        &mut self.conn.current_row_values,
+                    )?;
+
+                    return Ok(Some((
+                        self.index,
+                        PgRow {
+                            statement: Arc::clone(&self.statements[self.index]),
+                            data,
+                        },
+                    )));
+                }
+
+                message => {
+                    return Err(protocol_err!("pipeline: unexpected message: {:?}", message).into());
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    // The pipeline is complete; the connection is ready unless statements are being closed,
+    // in which case the [ReadyForQuery] that follows is received before the next query
+    fn finish(&mut self) {
+        if self.closing {
+            self.conn.pending_ready_for_query -= 1;
+        } else {
+            self.conn.is_ready = true;
+        }
+
+        self.done = true;
+    }
+}
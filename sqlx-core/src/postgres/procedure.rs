@@ -0,0 +1,97 @@
+use crate::describe::Column;
+use crate::postgres::{PgConnection, PgQueryAs, Postgres};
+use crate::query_as::query_as;
+
+impl PgConnection {
+    // Describe the result row of `CALL my_proc(...)`
+    //
+    // Postgres describes a `CALL` statement as returning no data, but when executed it returns
+    // a single row of the `INOUT` (and, since Postgres 14, `OUT`) parameters of the procedure.
+    // These are looked up in `pg_proc` instead.
+    pub(super) async fn describe_call_outputs(
+        &mut self,
+        query: &str,
+    ) -> crate::Result<Option<Vec<Column<Postgres>>>> {
+        let procedure = match procedure_name(query) {
+            Some(procedure) => procedure,
+            None => return Ok(None),
+        };
+
+        // language=SQL
+        let outputs: Vec<(Option<String>, u32)> = query_as(
+            "
+SELECT arg.name, arg.type_id
+FROM pg_catalog.pg_proc proc,
+    UNNEST(proc.proargnames, proc.proargmodes, proc.proallargtypes)
+        WITH ORDINALITY AS arg(name, mode, type_id, idx)
+WHERE proc.oid = $1::text::regproc AND arg.mode IN ('b', 'o')
+ORDER BY arg.idx
+            ",
+        )
+        .bind(procedure)
+        .fetch_all(&mut *self)
+        .await?;
+
+        let mut columns = Vec::with_capacity(outputs.len());
+
+        for (name, type_id) in outputs {
+            columns.push(Column {
+                // unnamed parameters have an empty name
+                name: name.filter(|name| !name.is_empty()).map(Into::into),
+                table_id: None,
+                type_info: Some(self.get_type_info_by_oid(type_id, true).await?),
+                non_null: None,
+            });
+        }
+
+        Ok(Some(columns))
+    }
+}
+
+// Returns the (possibly qualified and quoted) name of the procedure in a `CALL` statement
+fn procedure_name(query: &str) -> Option<&str> {
+    let query = query.trim_start();
+
+    if query.len() < 5
+        || !query[..4].eq_ignore_ascii_case("call")
+        || !query[4..].starts_with(char::is_whitespace)
+    {
+        return None;
+    }
+
+    let name = &query[4..];
+    let mut quoted = false;
+
+    for (i, c) in name.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '(' if !quoted => return Some(name[..i].trim()).filter(|name| !name.is_empty()),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::procedure_name;
+
+    #[test]
+    fn it_finds_procedure_name() {
+        assert_eq!(procedure_name("CALL my_proc($1, $2)"), Some("my_proc"));
+        assert_eq!(
+            procedure_name("  call\n  public.my_proc ()"),
+            Some("public.my_proc")
+        );
+        assert_eq!(
+            procedure_name(r#"CALL "My (Proc)"($1)"#),
+            Some(r#""My (Proc)""#)
+        );
+
+        assert_eq!(procedure_name("SELECT my_proc($1)"), None);
+        assert_eq!(procedure_name("CALLED($1)"), None);
+        assert_eq!(procedure_name("CALL ()"), None);
+        assert_eq!(procedure_name("CALL my_proc"), None);
+    }
+}
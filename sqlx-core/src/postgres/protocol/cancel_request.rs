@@ -0,0 +1,42 @@
+use byteorder::NetworkEndian;
+
+use crate::io::BufMut;
+use crate::postgres::protocol::Write;
+
+/// Sent on a new connection, in place of a startup message, to request that the query
+/// currently running on another connection is cancelled.
+#[derive(Debug)]
+pub struct CancelRequest {
+    /// The process ID of the target backend.
+    pub process_id: u32,
+
+    /// The secret key for the target backend.
+    pub secret_key: u32,
+}
+
+impl Write for CancelRequest {
+    fn write(&self, buf: &mut Vec<u8>) {
+        // packet length: 16 bytes including self
+        buf.put_u32::<NetworkEndian>(16);
+        // 1234 in high 16 bits, 5678 in low 16
+        buf.put_u32::<NetworkEndian>((1234 << 16) | 5678);
+        buf.put_u32::<NetworkEndian>(self.process_id);
+        buf.put_u32::<NetworkEndian>(self.secret_key);
+    }
+}
+
+#[test]
+fn test_cancel_request() {
+    let mut buf = Vec::new();
+
+    CancelRequest {
+        process_id: 0x0102_0304,
+        secret_key: 0x0506_0708,
+    }
+    .write(&mut buf);
+
+    assert_eq!(
+        &buf,
+        b"\x00\x00\x00\x10\x04\xd2\x16.\x01\x02\x03\x04\x05\x06\x07\x08"
+    );
+}
@@ -0,0 +1,35 @@
+use crate::io::BufMut;
+use crate::postgres::protocol::Write;
+use byteorder::NetworkEndian;
+
+/// A chunk of `COPY` data. Sent by the frontend during `COPY FROM STDIN` and by
+/// the backend during `COPY TO STDOUT`.
+pub struct CopyData<'a>(pub &'a [u8]);
+
+impl Write for CopyData<'_> {
+    fn write(&self, buf: &mut Vec<u8>) {
+        buf.push(b'd');
+
+        // len + data
+        buf.put_i32::<NetworkEndian>((4 + self.0.len()) as i32);
+
+        buf.extend_from_slice(self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CopyData, Write};
+
+    const COPY_DATA: &[u8] = b"d\0\0\0\x0a1\tfoo\n";
+
+    #[test]
+    fn it_writes_copy_data() {
+        let mut buf = Vec::new();
+        let m = CopyData(b"1\tfoo\n");
+
+        m.write(&mut buf);
+
+        assert_eq!(buf, COPY_DATA);
+    }
+}
@@ -0,0 +1,35 @@
+use crate::io::BufMut;
+use crate::postgres::protocol::Write;
+use byteorder::NetworkEndian;
+
+/// Aborts a `COPY FROM STDIN` operation; the message is reported back in the
+/// resulting `ErrorResponse`.
+pub struct CopyFail<'a>(pub &'a str);
+
+impl Write for CopyFail<'_> {
+    fn write(&self, buf: &mut Vec<u8>) {
+        buf.push(b'f');
+
+        // len + message + nul
+        buf.put_i32::<NetworkEndian>((4 + self.0.len() + 1) as i32);
+
+        buf.put_str_nul(self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CopyFail, Write};
+
+    const COPY_FAIL: &[u8] = b"f\0\0\0\x0aabort\0";
+
+    #[test]
+    fn it_writes_copy_fail() {
+        let mut buf = Vec::new();
+        let m = CopyFail("abort");
+
+        m.write(&mut buf);
+
+        assert_eq!(buf, COPY_FAIL);
+    }
+}
@@ -0,0 +1,65 @@
+use crate::io::Buf;
+use crate::postgres::protocol::TypeFormat;
+use byteorder::NetworkEndian;
+
+/// The body of a `CopyInResponse`, `CopyOutResponse` or `CopyBothResponse` message; all share
+/// the same layout.
+#[derive(Debug)]
+pub(crate) struct CopyResponse {
+    /// The overall format of the `COPY`; `Text` for text or CSV and `Binary` for binary.
+    pub(crate) format: TypeFormat,
+
+    /// The format of each column. For a textual `COPY` these are always `Text`.
+    pub(crate) columns: Box<[TypeFormat]>,
+}
+
+impl CopyResponse {
+    pub(crate) fn read(mut buf: &[u8]) -> crate::Result<Self> {
+        let format = read_format(buf.get_i8()? as i16)?;
+        let cnt = buf.get_u16::<NetworkEndian>()? as usize;
+        let mut columns = Vec::with_capacity(cnt);
+
+        for _ in 0..cnt {
+            columns.push(read_format(buf.get_i16::<NetworkEndian>()?)?);
+        }
+
+        Ok(Self {
+            format,
+            columns: columns.into_boxed_slice(),
+        })
+    }
+}
+
+fn read_format(code: i16) -> crate::Result<TypeFormat> {
+    match code {
+        0 => Ok(TypeFormat::Text),
+        1 => Ok(TypeFormat::Binary),
+
+        code => Err(protocol_err!("unknown COPY format code: {}", code).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CopyResponse;
+    use crate::postgres::protocol::TypeFormat;
+
+    const COPY_RESPONSE_TEXT: &[u8] = b"\0\0\x02\0\0\0\0";
+    const COPY_RESPONSE_BINARY: &[u8] = b"\x01\0\x01\0\x01";
+
+    #[test]
+    fn it_reads_text_copy_response() {
+        let message = CopyResponse::read(COPY_RESPONSE_TEXT).unwrap();
+
+        assert!(matches!(message.format, TypeFormat::Text));
+        assert_eq!(message.columns.len(), 2);
+    }
+
+    #[test]
+    fn it_reads_binary_copy_response() {
+        let message = CopyResponse::read(COPY_RESPONSE_BINARY).unwrap();
+
+        assert!(matches!(message.format, TypeFormat::Binary));
+        assert!(matches!(message.columns[0], TypeFormat::Binary));
+    }
+}
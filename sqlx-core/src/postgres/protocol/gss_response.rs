@@ -0,0 +1,25 @@
+use byteorder::NetworkEndian;
+
+use crate::io::BufMut;
+use crate::postgres::protocol::Write;
+
+/// A GSSAPI token sent in response to `AuthenticationGSS` or `AuthenticationGSSContinue`.
+#[derive(Debug)]
+pub struct GssResponse<'a>(pub &'a [u8]);
+
+impl Write for GssResponse<'_> {
+    fn write(&self, buf: &mut Vec<u8>) {
+        buf.push(b'p');
+        buf.put_u32::<NetworkEndian>((4 + self.0.len()) as u32);
+        buf.extend_from_slice(self.0);
+    }
+}
+
+#[test]
+fn test_gss_response() {
+    let mut buf = Vec::new();
+
+    GssResponse(b"\x60\x82").write(&mut buf);
+
+    assert_eq!(&buf, b"p\x00\x00\x00\x06\x60\x82");
+}
@@ -10,11 +10,18 @@ pub use type_id::TypeId;
 
 // REQUESTS
 mod bind;
+mod cancel_request;
+mod close;
+mod copy_data;
+mod copy_done;
+mod copy_fail;
 mod describe;
 mod execute;
+mod gss_response;
 mod parse;
 mod password_message;
 mod query;
+mod replication;
 mod sasl;
 #[cfg_attr(not(feature = "tls"), allow(unused_imports, dead_code))]
 mod ssl_request;
@@ -24,11 +31,18 @@ mod sync;
 mod terminate;
 
 pub(crate) use bind::Bind;
+pub(crate) use cancel_request::CancelRequest;
+pub(crate) use close::Close;
+pub(crate) use copy_data::CopyData;
+pub(crate) use copy_done::CopyDone;
+pub(crate) use copy_fail::CopyFail;
 pub(crate) use describe::Describe;
 pub(crate) use execute::Execute;
+pub(crate) use gss_response::GssResponse;
 pub(crate) use parse::Parse;
 pub(crate) use password_message::PasswordMessage;
 pub(crate) use query::Query;
+pub(crate) use replication::{ReplicationMessage, StandbyStatusUpdate};
 pub(crate) use sasl::{hi, SaslInitialResponse, SaslResponse};
 #[cfg_attr(not(feature = "tls"), allow(unused_imports, dead_code))]
 pub(crate) use ssl_request::SslRequest;
@@ -41,6 +55,7 @@ pub(crate) use terminate::Terminate;
 mod authentication;
 mod backend_key_data;
 mod command_complete;
+mod copy_response;
 mod data_row;
 mod notification_response;
 mod parameter_description;
@@ -55,12 +70,14 @@ pub(crate) use authentication::{
 };
 pub(crate) use backend_key_data::BackendKeyData;
 pub(crate) use command_complete::CommandComplete;
+pub(crate) use copy_response::CopyResponse;
 pub(crate) use data_row::DataRow;
 pub(crate) use message::Message;
 pub(crate) use notification_response::NotificationResponse;
 pub(crate) use parameter_description::ParameterDescription;
-pub(crate) use ready_for_query::ReadyForQuery;
-pub(crate) use response::{Response, Severity};
+pub(crate) use ready_for_query::{ReadyForQuery, TransactionStatus};
+pub(crate) use response::Response;
+pub use response::Severity;
 pub(crate) use row_description::{Field, RowDescription};
 
 pub(crate) trait Write {
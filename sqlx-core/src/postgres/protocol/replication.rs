@@ -0,0 +1,162 @@
+use byteorder::NetworkEndian;
+
+use crate::io::{Buf, BufMut};
+use crate::postgres::protocol::Write;
+
+/// A message received inside of `CopyData` while streaming replication.
+#[derive(Debug)]
+pub(crate) enum ReplicationMessage<'a> {
+    /// WAL data; for logical replication this is a message from the output plugin.
+    XLogData {
+        /// The starting point of the WAL data in this message.
+        start: u64,
+
+        /// The current end of WAL on the server.
+        end: u64,
+
+        /// The server's system clock at the time of transmission, as microseconds
+        /// since midnight on 2000-01-01.
+        time: i64,
+
+        data: &'a [u8],
+    },
+
+    /// Sent periodically by the server to check that the client is still alive.
+    PrimaryKeepalive {
+        /// The current end of WAL on the server.
+        end: u64,
+
+        /// The server's system clock at the time of transmission.
+        time: i64,
+
+        /// The server requests an immediate [StandbyStatusUpdate] in response
+        /// to avoid a timeout disconnect.
+        reply: bool,
+    },
+}
+
+impl<'a> ReplicationMessage<'a> {
+    pub(crate) fn read(mut buf: &'a [u8]) -> crate::Result<Self> {
+        // https://www.postgresql.org/docs/12/protocol-replication.html
+        Ok(match buf.get_u8()? {
+            b'w' => ReplicationMessage::XLogData {
+                start: buf.get_u64::<NetworkEndian>()?,
+                end: buf.get_u64::<NetworkEndian>()?,
+                time: buf.get_i64::<NetworkEndian>()?,
+                data: buf,
+            },
+
+            b'k' => ReplicationMessage::PrimaryKeepalive {
+                end: buf.get_u64::<NetworkEndian>()?,
+                time: buf.get_i64::<NetworkEndian>()?,
+                reply: buf.get_u8()? != 0,
+            },
+
+            id => {
+                return Err(protocol_err!("unknown replication message: {:?}", id as char).into());
+            }
+        })
+    }
+}
+
+/// Sent (inside of `CopyData`) to report the WAL positions that have been processed
+/// by the client.
+#[derive(Debug)]
+pub(crate) struct StandbyStatusUpdate {
+    /// The location of the last WAL byte + 1 received and written to disk.
+    pub(crate) written: u64,
+
+    /// The location of the last WAL byte + 1 flushed to disk. The server may discard WAL
+    /// up to this point.
+    pub(crate) flushed: u64,
+
+    /// The location of the last WAL byte + 1 applied.
+    pub(crate) applied: u64,
+
+    /// The client's system clock at the time of transmission, as microseconds
+    /// since midnight on 2000-01-01.
+    pub(crate) time: i64,
+
+    /// Request the server to reply to this message immediately.
+    pub(crate) reply: bool,
+}
+
+impl Write for StandbyStatusUpdate {
+    fn write(&self, buf: &mut Vec<u8>) {
+        buf.push(b'd');
+
+        // len + tag + written + flushed + applied + time + reply
+        buf.put_i32::<NetworkEndian>(4 + 1 + 8 + 8 + 8 + 8 + 1);
+
+        buf.push(b'r');
+        buf.put_u64::<NetworkEndian>(self.written);
+        buf.put_u64::<NetworkEndian>(self.flushed);
+        buf.put_u64::<NetworkEndian>(self.applied);
+        buf.put_u64::<NetworkEndian>(self.time as u64);
+        buf.push(self.reply as u8);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReplicationMessage, StandbyStatusUpdate, Write};
+
+    const XLOG_DATA: &[u8] = b"w\0\0\0\0\x01\0\0\x10\0\0\0\0\x01\0\0\x20\0\0\0\0\0\0\0\x05B...";
+
+    const PRIMARY_KEEPALIVE: &[u8] = b"k\0\0\0\0\x01\0\0\x20\0\0\0\0\0\0\0\x05\x01";
+
+    #[test]
+    fn it_reads_xlog_data() {
+        let m = ReplicationMessage::read(XLOG_DATA).unwrap();
+
+        match m {
+            ReplicationMessage::XLogData {
+                start,
+                end,
+                time,
+                data,
+            } => {
+                assert_eq!(start, 0x0100_0010);
+                assert_eq!(end, 0x0100_0020);
+                assert_eq!(time, 5);
+                assert_eq!(data, b"B...");
+            }
+
+            m => panic!("unexpected message: {:?}", m),
+        }
+    }
+
+    #[test]
+    fn it_reads_primary_keepalive() {
+        let m = ReplicationMessage::read(PRIMARY_KEEPALIVE).unwrap();
+
+        match m {
+            ReplicationMessage::PrimaryKeepalive { end, time, reply } => {
+                assert_eq!(end, 0x0100_0020);
+                assert_eq!(time, 5);
+                assert!(reply);
+            }
+
+            m => panic!("unexpected message: {:?}", m),
+        }
+    }
+
+    #[test]
+    fn it_writes_standby_status_update() {
+        let mut buf = Vec::new();
+
+        StandbyStatusUpdate {
+            written: 1,
+            flushed: 2,
+            applied: 3,
+            time: 4,
+            reply: false,
+        }
+        .write(&mut buf);
+
+        assert_eq!(
+            buf,
+            &b"d\0\0\0\x26r\0\0\0\0\0\0\0\x01\0\0\0\0\0\0\0\x02\0\0\0\0\0\0\0\x03\0\0\0\0\0\0\0\x04\0"[..]
+        );
+    }
+}
@@ -3,8 +3,9 @@ use std::str::{self, FromStr};
 
 use crate::postgres::database::Postgres;
 
+/// The severity of an error or notice sent by Postgres.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub(crate) enum Severity {
+pub enum Severity {
     Panic,
     Fatal,
     Error,
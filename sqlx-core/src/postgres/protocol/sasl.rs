@@ -5,16 +5,19 @@ use byteorder::NetworkEndian;
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
 
-pub(crate) struct SaslInitialResponse<'a>(pub(crate) &'a str);
+pub(crate) struct SaslInitialResponse<'a> {
+    pub(crate) mechanism: &'a str,
+    pub(crate) response: &'a str,
+}
 
 impl<'a> Write for SaslInitialResponse<'a> {
     fn write(&self, buf: &mut Vec<u8>) {
-        let len = self.0.as_bytes().len() as u32;
+        let len = self.response.as_bytes().len() as u32;
         buf.push(b'p');
-        buf.put_u32::<NetworkEndian>(4u32 + len + 14u32 + 4u32);
-        buf.put_str_nul("SCRAM-SHA-256");
+        buf.put_u32::<NetworkEndian>(4u32 + self.mechanism.len() as u32 + 1 + 4u32 + len);
+        buf.put_str_nul(self.mechanism);
         buf.put_u32::<NetworkEndian>(len);
-        buf.extend_from_slice(self.0.as_bytes());
+        buf.extend_from_slice(self.response.as_bytes());
     }
 }
 
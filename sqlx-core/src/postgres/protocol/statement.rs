@@ -3,9 +3,14 @@ use std::io::Write as _;
 use crate::io::BufMut;
 use crate::postgres::protocol::Write;
 
-#[derive(Copy, Clone, PartialOrd, PartialEq, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialOrd, PartialEq, Eq, Hash)]
 pub struct StatementId(pub u32);
 
+impl StatementId {
+    // the unnamed statement, replaced by the next one prepared
+    pub(crate) const UNNAMED: StatementId = StatementId(0);
+}
+
 impl Write for StatementId {
     fn write(&self, buf: &mut Vec<u8>) {
         if self.0 != 0 {
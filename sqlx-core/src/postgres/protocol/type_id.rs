@@ -24,11 +24,14 @@ impl TypeId {
     pub(crate) const INT8: TypeId = TypeId(20);
 
     pub(crate) const OID: TypeId = TypeId(26);
+    pub(crate) const XID8: TypeId = TypeId(5069);
+    pub(crate) const PG_LSN: TypeId = TypeId(3220);
 
     pub(crate) const FLOAT4: TypeId = TypeId(700);
     pub(crate) const FLOAT8: TypeId = TypeId(701);
 
     pub(crate) const NUMERIC: TypeId = TypeId(1700);
+    pub(crate) const MONEY: TypeId = TypeId(790);
 
     pub(crate) const TEXT: TypeId = TypeId(25);
     pub(crate) const VARCHAR: TypeId = TypeId(1043);
@@ -40,6 +43,7 @@ impl TypeId {
     pub(crate) const TIME: TypeId = TypeId(1083);
     pub(crate) const TIMESTAMP: TypeId = TypeId(1114);
     pub(crate) const TIMESTAMPTZ: TypeId = TypeId(1184);
+    pub(crate) const INTERVAL: TypeId = TypeId(1186);
 
     pub(crate) const BYTEA: TypeId = TypeId(17);
 
@@ -59,6 +63,8 @@ impl TypeId {
     pub(crate) const ARRAY_INT8: TypeId = TypeId(1016);
 
     pub(crate) const ARRAY_OID: TypeId = TypeId(1028);
+    pub(crate) const ARRAY_XID8: TypeId = TypeId(271);
+    pub(crate) const ARRAY_PG_LSN: TypeId = TypeId(3221);
 
     pub(crate) const ARRAY_FLOAT4: TypeId = TypeId(1021);
     pub(crate) const ARRAY_FLOAT8: TypeId = TypeId(1022);
@@ -69,11 +75,13 @@ impl TypeId {
     pub(crate) const ARRAY_NAME: TypeId = TypeId(1003);
 
     pub(crate) const ARRAY_NUMERIC: TypeId = TypeId(1231);
+    pub(crate) const ARRAY_MONEY: TypeId = TypeId(791);
 
     pub(crate) const ARRAY_DATE: TypeId = TypeId(1182);
     pub(crate) const ARRAY_TIME: TypeId = TypeId(1183);
     pub(crate) const ARRAY_TIMESTAMP: TypeId = TypeId(1115);
     pub(crate) const ARRAY_TIMESTAMPTZ: TypeId = TypeId(1185);
+    pub(crate) const ARRAY_INTERVAL: TypeId = TypeId(1187);
 
     pub(crate) const ARRAY_BYTEA: TypeId = TypeId(1001);
 
@@ -86,6 +94,30 @@ impl TypeId {
 
     pub(crate) const JSON: TypeId = TypeId(114);
     pub(crate) const JSONB: TypeId = TypeId(3802);
+    pub(crate) const JSONPATH: TypeId = TypeId(4072);
+
+    pub(crate) const ARRAY_JSONPATH: TypeId = TypeId(4073);
+
+    // Text Search
+
+    pub(crate) const TSVECTOR: TypeId = TypeId(3614);
+    pub(crate) const TSQUERY: TypeId = TypeId(3615);
+
+    // Ranges
+
+    pub(crate) const INT4RANGE: TypeId = TypeId(3904);
+    pub(crate) const NUMRANGE: TypeId = TypeId(3906);
+    pub(crate) const TSRANGE: TypeId = TypeId(3908);
+    pub(crate) const TSTZRANGE: TypeId = TypeId(3910);
+    pub(crate) const DATERANGE: TypeId = TypeId(3912);
+    pub(crate) const INT8RANGE: TypeId = TypeId(3926);
+
+    pub(crate) const INT4MULTIRANGE: TypeId = TypeId(4451);
+    pub(crate) const NUMMULTIRANGE: TypeId = TypeId(4532);
+    pub(crate) const TSMULTIRANGE: TypeId = TypeId(4533);
+    pub(crate) const TSTZMULTIRANGE: TypeId = TypeId(4534);
+    pub(crate) const DATEMULTIRANGE: TypeId = TypeId(4535);
+    pub(crate) const INT8MULTIRANGE: TypeId = TypeId(4536);
 
     // Records
 
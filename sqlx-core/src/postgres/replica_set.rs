@@ -0,0 +1,204 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::executor::Executor;
+use crate::pool::PoolConnection;
+use crate::postgres::{PgConnection, PgPool, PgQueryAs};
+use crate::transaction::Transaction;
+
+// The status of a replica, as of its last health check
+struct Health {
+    checked_at: Instant,
+    healthy: bool,
+}
+
+struct Replica {
+    pool: PgPool,
+    health: Mutex<Option<Health>>,
+}
+
+/// A primary Postgres server and its hot-standby replicas, each with a connection pool.
+///
+/// Writes go to the primary, through [`writer`]. Reads go to a replica, through [`reader`],
+/// spreading them over the healthy replicas in turn. A replica is healthy if it can be
+/// connected to and, if [`max_lag`] is set, if it has replayed the WAL of the primary
+/// up to that long ago. The health of a replica is checked again once per
+/// [`health_check_interval`]; if no replica is healthy, reads go to the primary.
+///
+/// ```rust,ignore
+/// let db = PgReplicaSet::connect(
+///     "postgres://primary/app",
+///     &["postgres://replica-1/app", "postgres://replica-2/app"],
+/// )
+/// .await?
+/// .max_lag(Duration::from_secs(5));
+///
+/// sqlx::query("INSERT INTO users (name) VALUES ($1)")
+///     .bind("alice")
+///     .execute(db.writer())
+///     .await?;
+///
+/// let users: Vec<(String,)> = sqlx::query_as("SELECT name FROM users")
+///     .fetch_all(db.reader().await?)
+///     .await?;
+/// ```
+///
+/// As replication is asynchronous, a write may not be seen by a read from a replica right
+/// away; read from the [`writer`] where that matters.
+///
+/// [`writer`]: #method.writer
+/// [`reader`]: #method.reader
+/// [`max_lag`]: #method.max_lag
+/// [`health_check_interval`]: #method.health_check_interval
+pub struct PgReplicaSet {
+    primary: PgPool,
+    replicas: Vec<Replica>,
+    next: AtomicUsize,
+    max_lag: Option<Duration>,
+    health_check_interval: Duration,
+}
+
+impl PgReplicaSet {
+    /// Create a replica set from the pools of the primary and of each replica.
+    pub fn new(primary: PgPool, replicas: Vec<PgPool>) -> Self {
+        Self {
+            primary,
+            replicas: replicas
+                .into_iter()
+                .map(|pool| Replica {
+                    pool,
+                    health: Mutex::new(None),
+                })
+                .collect(),
+            next: AtomicUsize::new(0),
+            max_lag: None,
+            health_check_interval: Duration::from_secs(1),
+        }
+    }
+
+    /// Create a replica set with a pool of the default configuration for the primary and
+    /// each replica.
+    ///
+    /// No connection is opened until one is needed, so a replica that is down does not
+    /// prevent the replica set from being created.
+    pub async fn connect(primary: &str, replicas: &[&str]) -> crate::Result<Self> {
+        let primary = PgPool::new(primary).await?;
+        let mut pools = Vec::with_capacity(replicas.len());
+
+        for url in replicas {
+            pools.push(PgPool::new(url).await?);
+        }
+
+        Ok(Self::new(primary, pools))
+    }
+
+    /// Set how far behind the primary a replica may be and still be read from.
+    ///
+    /// Defaults to `None`; a replica is read from regardless of its lag.
+    pub fn max_lag(mut self, max_lag: impl Into<Option<Duration>>) -> Self {
+        self.max_lag = max_lag.into();
+        self
+    }
+
+    /// Set how often the health of a replica is checked. Defaults to 1 second.
+    pub fn health_check_interval(mut self, interval: Duration) -> Self {
+        self.health_check_interval = interval;
+        self
+    }
+
+    /// Returns the pool of the primary, for writes and for reads that must see the latest
+    /// writes.
+    pub fn writer(&self) -> &PgPool {
+        &self.primary
+    }
+
+    /// Returns the pool of the next healthy replica, or of the primary if no replica
+    /// is healthy.
+    pub async fn reader(&self) -> crate::Result<&PgPool> {
+        let len = self.replicas.len();
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+
+        for offset in 0..len {
+            let replica = &self.replicas[(start + offset) % len];
+
+            if self.is_healthy(replica).await {
+                return Ok(&replica.pool);
+            }
+        }
+
+        Ok(&self.primary)
+    }
+
+    /// Begin a read-only transaction on the next healthy replica, or on the primary if no
+    /// replica is healthy.
+    pub async fn begin_read(&self) -> crate::Result<Transaction<PoolConnection<PgConnection>>> {
+        let mut tx = self.reader().await?.begin().await?;
+
+        tx.execute("SET TRANSACTION READ ONLY").await?;
+
+        Ok(tx)
+    }
+
+    /// Begin a transaction on the primary.
+    pub async fn begin_write(&self) -> crate::Result<Transaction<PoolConnection<PgConnection>>> {
+        self.primary.begin().await
+    }
+
+    /// Close the pools of the primary and of every replica.
+    pub async fn close(&self) {
+        self.primary.close().await;
+
+        for replica in &self.replicas {
+            replica.pool.close().await;
+        }
+    }
+
+    async fn is_healthy(&self, replica: &Replica) -> bool {
+        if let Some(health) = &*replica.health.lock().unwrap() {
+            if health.checked_at.elapsed() < self.health_check_interval {
+                return health.healthy;
+            }
+        }
+
+        let healthy = match self.check(&replica.pool).await {
+            Ok(healthy) => healthy,
+
+            Err(error) => {
+                log::warn!("replica is unavailable: {}", error);
+
+                false
+            }
+        };
+
+        *replica.health.lock().unwrap() = Some(Health {
+            checked_at: Instant::now(),
+            healthy,
+        });
+
+        healthy
+    }
+
+    async fn check(&self, pool: &PgPool) -> crate::Result<bool> {
+        // the lag is the time since the last transaction replayed, unless all of the WAL
+        // received has been replayed; a server that is not in recovery has no lag
+        let (lag,): (Option<f64>,) = crate::query_as::query_as(
+            "SELECT CASE \
+                WHEN NOT pg_is_in_recovery() \
+                    OR pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() THEN 0 \
+                ELSE EXTRACT(EPOCH FROM now() - pg_last_xact_replay_timestamp())::float8 \
+            END",
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(match (self.max_lag, lag) {
+            (None, _) => true,
+
+            // nothing has been replayed yet
+            (Some(_), None) => false,
+
+            (Some(max_lag), Some(lag)) => lag <= max_lag.as_secs_f64(),
+        })
+    }
+}
@@ -0,0 +1,701 @@
+//! Logical replication (change data capture) using the `pgoutput`, `wal2json` or
+//! `test_decoding` plugins.
+
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::connection::Connection;
+use crate::cursor::Cursor;
+use crate::executor::Executor;
+use crate::postgres::protocol::{
+    CopyDone, CopyResponse, Message, ReplicationMessage, StandbyStatusUpdate,
+};
+use crate::postgres::PgConnection;
+use crate::row::Row;
+use crate::url::Url;
+
+mod pgoutput;
+mod test_decoding;
+#[cfg(feature = "json")]
+mod wal2json;
+
+pub use pgoutput::{PgRelation, PgRelationColumn, PgReplicationEvent, PgTuple, PgTupleValue};
+
+// Seconds from the UNIX epoch to the Postgres epoch (2000-01-01)
+const POSTGRES_EPOCH: u64 = 946_684_800;
+
+/// A position in the write-ahead log (a `pg_lsn`).
+///
+/// Formatted and parsed as two hexadecimal numbers separated by a slash, e.g. `16/B374D848`.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PgLsn(pub u64);
+
+impl Display for PgLsn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:X}/{:X}", self.0 >> 32, self.0 & 0xFFFF_FFFF)
+    }
+}
+
+impl FromStr for PgLsn {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let mut parts = s.splitn(2, '/');
+
+        match (parts.next(), parts.next()) {
+            (Some(hi), Some(lo)) => {
+                let hi = u32::from_str_radix(hi, 16).map_err(crate::Error::decode)?;
+                let lo = u32::from_str_radix(lo, 16).map_err(crate::Error::decode)?;
+
+                Ok(PgLsn((u64::from(hi) << 32) | u64::from(lo)))
+            }
+
+            _ => Err(decode_err!("invalid LSN: {:?}", s)),
+        }
+    }
+}
+
+/// The output plugin of a logical replication slot, which decodes the changes streamed
+/// from it.
+///
+/// Every plugin yields the same [PgReplicationEvent]s. `pgoutput` is built into Postgres
+/// and describes each table fully; the others are for servers where publications can not
+/// be created, such as some managed services:
+///
+///  * `wal2json` must be installed on the server. It does not report the OID or the replica
+///    identity of a table.
+///
+///  * `test_decoding` is included with Postgres. It does not report the OID, replica
+///    identity, key columns or type OIDs of a table (the type names are reported instead),
+///    nor the timestamp of a transaction before its commit.
+#[derive(Debug, Clone, Copy)]
+pub enum PgReplicationPlugin<'a> {
+    /// The `pgoutput` plugin, streaming the changes to the tables of the given publications.
+    PgOutput { publications: &'a [&'a str] },
+
+    /// The `wal2json` plugin (format version 2).
+    #[cfg(feature = "json")]
+    Wal2Json,
+
+    /// The `test_decoding` plugin.
+    TestDecoding,
+}
+
+impl PgReplicationPlugin<'_> {
+    fn name(&self) -> &'static str {
+        match self {
+            PgReplicationPlugin::PgOutput { .. } => "pgoutput",
+            #[cfg(feature = "json")]
+            PgReplicationPlugin::Wal2Json => "wal2json",
+            PgReplicationPlugin::TestDecoding => "test_decoding",
+        }
+    }
+
+    // The options of `START_REPLICATION` for this plugin
+    fn options(&self) -> String {
+        match self {
+            PgReplicationPlugin::PgOutput { publications } => {
+                let publications = publications
+                    .iter()
+                    .map(|name| quote_ident(name))
+                    .collect::<Vec<_>>()
+                    .join(",");
+
+                format!(
+                    "proto_version '1', publication_names '{}'",
+                    publications.replace('\'', "''")
+                )
+            }
+
+            #[cfg(feature = "json")]
+            PgReplicationPlugin::Wal2Json => "\"format-version\" '2', \"include-xids\" '1', \
+                 \"include-timestamp\" '1', \"include-lsn\" '1', \"include-type-oids\" '1', \
+                 \"include-pk\" '1'"
+                .to_owned(),
+
+            PgReplicationPlugin::TestDecoding => "\"include-xids\" '1', \
+                 \"include-timestamp\" '1', \"skip-empty-xacts\" '1'"
+                .to_owned(),
+        }
+    }
+}
+
+// The plugin a stream decodes the changes of
+#[derive(Debug, Clone, Copy)]
+enum Decoder {
+    PgOutput,
+    #[cfg(feature = "json")]
+    Wal2Json,
+    TestDecoding,
+}
+
+/// The result of `IDENTIFY_SYSTEM`.
+#[derive(Debug, Clone)]
+pub struct PgReplicationSystem {
+    /// The unique system identifier of the database cluster.
+    pub system_id: String,
+
+    /// The current timeline.
+    pub timeline: u32,
+
+    /// The current WAL flush location.
+    pub xlog_pos: PgLsn,
+
+    /// The database connected to.
+    pub database: Option<String>,
+}
+
+/// A connection in logical replication mode.
+///
+/// This is a regular Postgres connection, opened with `replication=database`, that accepts
+/// the [replication commands](https://www.postgresql.org/docs/12/protocol-replication.html).
+/// Postgres must be configured with `wal_level = logical` and the user must have the
+/// `REPLICATION` attribute.
+///
+/// ```rust,ignore
+/// let mut conn = PgReplicationConnection::connect("postgres://...").await?;
+///
+/// conn.create_slot("my_slot", false).await?;
+///
+/// let mut stream = conn
+///     .start_replication("my_slot", &["my_publication"], PgLsn::default())
+///     .await?;
+///
+/// while let Some(event) = stream.next().await? {
+///     if let PgReplicationEvent::Commit { end_lsn, .. } = event {
+///         stream.acknowledge(end_lsn).await?;
+///     }
+/// }
+/// ```
+pub struct PgReplicationConnection {
+    conn: PgConnection,
+}
+
+/// A stream of changes from a replication slot.
+///
+/// Returned from [PgReplicationConnection::start_replication].
+pub struct PgReplicationStream<'c> {
+    conn: &'c mut PgConnection,
+    decoder: Decoder,
+
+    // relations described by the server so far, by OID for `pgoutput` and by schema and
+    // name for the other plugins
+    relations: HashMap<u32, Arc<PgRelation>>,
+    named_relations: NamedRelations,
+
+    // the last WAL location received and the last location acknowledged by the client
+    received: u64,
+    acknowledged: u64,
+
+    done: bool,
+}
+
+impl PgReplicationConnection {
+    /// Open a new replication connection.
+    pub async fn connect<T>(url: T) -> crate::Result<Self>
+    where
+        T: TryInto<Url, Error = url::ParseError>,
+    {
+        let conn = PgConnection::establish(url.try_into()?, &[("replication", "database")]).await?;
+
+        Ok(Self { conn })
+    }
+
+    /// Explicitly close this connection.
+    pub async fn close(self) -> crate::Result<()> {
+        self.conn.close().await
+    }
+
+    /// Identify the server; this returns, among others, the current WAL location.
+    pub async fn identify_system(&mut self) -> crate::Result<PgReplicationSystem> {
+        let mut cursor = self.conn.fetch("IDENTIFY_SYSTEM");
+
+        let row = cursor
+            .next()
+            .await?
+            .ok_or_else(|| protocol_err!("IDENTIFY_SYSTEM: no rows returned"))?;
+
+        let system = PgReplicationSystem {
+            system_id: row.try_get(0)?,
+            timeline: row.try_get::<i32, _>(1)? as u32,
+            xlog_pos: row.try_get::<&str, _>(2)?.parse()?,
+            database: row.try_get(3)?,
+        };
+
+        while cursor.next().await?.is_some() {}
+
+        Ok(system)
+    }
+
+    /// Create a logical replication slot using the `pgoutput` plugin and return the LSN at
+    /// which the slot became consistent.
+    ///
+    /// A `temporary` slot is dropped when this connection is closed.
+    pub async fn create_slot(&mut self, slot: &str, temporary: bool) -> crate::Result<PgLsn> {
+        self.create_slot_with_plugin(
+            slot,
+            temporary,
+            PgReplicationPlugin::PgOutput { publications: &[] },
+        )
+        .await
+    }
+
+    /// Create a logical replication slot using the given plugin and return the LSN at
+    /// which the slot became consistent. The publications of `pgoutput` are not used
+    /// until streaming is started.
+    ///
+    /// A `temporary` slot is dropped when this connection is closed.
+    pub async fn create_slot_with_plugin(
+        &mut self,
+        slot: &str,
+        temporary: bool,
+        plugin: PgReplicationPlugin<'_>,
+    ) -> crate::Result<PgLsn> {
+        let command = format!(
+            "CREATE_REPLICATION_SLOT {} {}LOGICAL {} NOEXPORT_SNAPSHOT",
+            quote_ident(slot),
+            if temporary { "TEMPORARY " } else { "" },
+            plugin.name()
+        );
+
+        let mut cursor = self.conn.fetch(&*command);
+
+        let row = cursor
+            .next()
+            .await?
+            .ok_or_else(|| protocol_err!("CREATE_REPLICATION_SLOT: no rows returned"))?;
+
+        let lsn = row.try_get::<&str, _>(1)?.parse()?;
+
+        while cursor.next().await?.is_some() {}
+
+        Ok(lsn)
+    }
+
+    /// Drop a replication slot.
+    pub async fn drop_slot(&mut self, slot: &str) -> crate::Result<()> {
+        self.conn
+            .execute(&*format!("DROP_REPLICATION_SLOT {}", quote_ident(slot)))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Start streaming changes for the given publications from a slot.
+    ///
+    /// Streaming starts at `start`, or wherever the slot was last acknowledged if
+    /// that is later (pass `PgLsn::default()` to always resume from the slot).
+    pub async fn start_replication(
+        &mut self,
+        slot: &str,
+        publications: &[&str],
+        start: PgLsn,
+    ) -> crate::Result<PgReplicationStream<'_>> {
+        self.start_replication_with_plugin(
+            slot,
+            PgReplicationPlugin::PgOutput { publications },
+            start,
+        )
+        .await
+    }
+
+    /// Start streaming changes from a slot created with the given plugin.
+    ///
+    /// Streaming starts at `start`, or wherever the slot was last acknowledged if
+    /// that is later (pass `PgLsn::default()` to always resume from the slot).
+    pub async fn start_replication_with_plugin(
+        &mut self,
+        slot: &str,
+        plugin: PgReplicationPlugin<'_>,
+        start: PgLsn,
+    ) -> crate::Result<PgReplicationStream<'_>> {
+        let command = format!(
+            "START_REPLICATION SLOT {} LOGICAL {} ({})",
+            quote_ident(slot),
+            start,
+            plugin.options()
+        );
+
+        let decoder = match plugin {
+            PgReplicationPlugin::PgOutput { .. } => Decoder::PgOutput,
+            #[cfg(feature = "json")]
+            PgReplicationPlugin::Wal2Json => Decoder::Wal2Json,
+            PgReplicationPlugin::TestDecoding => Decoder::TestDecoding,
+        };
+
+        self.conn.run(&command, None, true).await?;
+
+        match self.conn.stream.receive().await? {
+            Message::CopyBothResponse => {
+                CopyResponse::read(self.conn.stream.buffer())?;
+            }
+
+            message => {
+                return Err(
+                    protocol_err!("start_replication: unexpected message: {:?}", message).into(),
+                );
+            }
+        }
+
+        Ok(PgReplicationStream {
+            conn: &mut self.conn,
+            decoder,
+            relations: HashMap::new(),
+            named_relations: HashMap::new(),
+            received: start.0,
+            acknowledged: start.0,
+            done: false,
+        })
+    }
+}
+
+impl PgReplicationStream<'_> {
+    /// Wait for the next change.
+    ///
+    /// Keepalive requests from the server are answered automatically with the last
+    /// [acknowledged][Self::acknowledge] location. Returns `None` if the server ended
+    /// the replication.
+    pub async fn next(&mut self) -> crate::Result<Option<PgReplicationEvent>> {
+        while !self.done {
+            match self.conn.stream.receive().await? {
+                Message::CopyData => {
+                    let event = match ReplicationMessage::read(self.conn.stream.buffer())? {
+                        ReplicationMessage::XLogData { start, data, .. } => {
+                            self.received = self.received.max(start);
+
+                            let lsn = PgLsn(start);
+
+                            match self.decoder {
+                                Decoder::PgOutput => pgoutput::decode(data, &mut self.relations)?,
+
+                                #[cfg(feature = "json")]
+                                Decoder::Wal2Json => {
+                                    wal2json::decode(data, lsn, &mut self.named_relations)?
+                                }
+
+                                Decoder::TestDecoding => {
+                                    test_decoding::decode(data, lsn, &mut self.named_relations)?
+                                }
+                            }
+                        }
+
+                        ReplicationMessage::PrimaryKeepalive { end, reply, .. } => {
+                            self.received = self.received.max(end);
+
+                            if reply {
+                                self.send_status().await?;
+                            }
+
+                            None
+                        }
+                    };
+
+                    if event.is_some() {
+                        return Ok(event);
+                    }
+                }
+
+                Message::CopyDone => {
+                    // the server ended replication; acknowledge and wait for it to finish
+                    self.conn.stream.write(CopyDone);
+                    self.conn.stream.flush().await?;
+
+                    self.finish().await?;
+                }
+
+                message => {
+                    return Err(
+                        protocol_err!("replication: unexpected message: {:?}", message).into(),
+                    );
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Report that all changes up to `lsn` have been processed; the server is free to
+    /// discard the WAL before it and will not send those changes again.
+    pub async fn acknowledge(&mut self, lsn: PgLsn) -> crate::Result<()> {
+        self.acknowledged = self.acknowledged.max(lsn.0);
+
+        self.send_status().await
+    }
+
+    /// Returns the latest WAL location seen from the server.
+    pub fn received(&self) -> PgLsn {
+        PgLsn(self.received)
+    }
+
+    /// Stop streaming and return the connection to the normal command mode.
+    pub async fn stop(mut self) -> crate::Result<()> {
+        if self.done {
+            return Ok(());
+        }
+
+        self.conn.stream.write(CopyDone);
+        self.conn.stream.flush().await?;
+
+        // discard any changes that were sent before the server received our [CopyDone]
+        loop {
+            match self.conn.stream.receive().await? {
+                Message::CopyData => {}
+                Message::CopyDone => break,
+
+                message => {
+                    return Err(protocol_err!("stop: unexpected message: {:?}", message).into());
+                }
+            }
+        }
+
+        self.finish().await
+    }
+
+    // Read the result of START_REPLICATION after both sides have sent [CopyDone]
+    async fn finish(&mut self) -> crate::Result<()> {
+        loop {
+            match self.conn.stream.receive().await? {
+                Message::CommandComplete | Message::RowDescription | Message::DataRow => {}
+
+                // the server may still send a keepalive after its [CopyDone]
+                Message::CopyData => {}
+
+                Message::ReadyForQuery => {
+                    self.conn.is_ready = true;
+                    self.done = true;
+
+                    return Ok(());
+                }
+
+                message => {
+                    return Err(protocol_err!(
+                        "replication: unexpected message after CopyDone: {:?}",
+                        message
+                    )
+                    .into());
+                }
+            }
+        }
+    }
+
+    async fn send_status(&mut self) -> crate::Result<()> {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now.as_micros() as i64 - (POSTGRES_EPOCH * 1_000_000) as i64)
+            .unwrap_or_default();
+
+        self.conn.stream.write(StandbyStatusUpdate {
+            written: self.received,
+            flushed: self.acknowledged,
+            applied: self.acknowledged,
+            time,
+            reply: false,
+        });
+
+        self.conn.stream.flush().await
+    }
+}
+
+impl Drop for PgReplicationStream<'_> {
+    fn drop(&mut self) {
+        if !self.done {
+            // the connection is still streaming changes and can not be recovered
+            // without reading them; it can only be closed
+            let _ = self.conn.stream.shutdown();
+        }
+    }
+}
+
+// Relations of the `wal2json` and `test_decoding` plugins, by schema and name
+type NamedRelations = HashMap<(String, String), Arc<PgRelation>>;
+
+// A column of a change from the `wal2json` or `test_decoding` plugins, which describe
+// the columns of a table along with each change instead of ahead of the changes
+struct NamedValue {
+    name: String,
+    type_name: String,
+    type_id: u32,
+    value: PgTupleValue,
+}
+
+// Returns the relation of a change from the `wal2json` or `test_decoding` plugins
+// The relation seen last for the table is reused if it has every column of the change
+// (and no other column if the change has the `complete` row); if not, the table was
+// altered or not seen yet, and a relation is created from the columns of the change
+fn named_relation(
+    relations: &mut NamedRelations,
+    namespace: &str,
+    name: &str,
+    values: &[NamedValue],
+    keys: &[String],
+    complete: bool,
+) -> Arc<PgRelation> {
+    let key = (namespace.to_owned(), name.to_owned());
+
+    if let Some(relation) = relations.get(&key) {
+        let has_columns = values.iter().all(|value| {
+            relation.columns.iter().any(|column| {
+                column.name == value.name && column.type_name.as_ref() == Some(&value.type_name)
+            })
+        });
+
+        if has_columns && (!complete || relation.columns.len() == values.len()) {
+            return Arc::clone(relation);
+        }
+    }
+
+    let relation = Arc::new(PgRelation {
+        id: 0,
+        namespace: namespace.to_owned(),
+        name: name.to_owned(),
+        replica_identity: 0,
+        columns: values
+            .iter()
+            .map(|value| PgRelationColumn {
+                name: value.name.clone(),
+                type_id: value.type_id,
+                type_name: Some(value.type_name.clone()),
+                type_modifier: -1,
+                is_key: keys.contains(&value.name),
+            })
+            .collect(),
+    });
+
+    relations.insert(key, Arc::clone(&relation));
+
+    relation
+}
+
+// Returns the relation of a truncated table, which is described by name only
+fn truncated_relation(
+    relations: &mut NamedRelations,
+    namespace: &str,
+    name: &str,
+) -> Arc<PgRelation> {
+    let key = (namespace.to_owned(), name.to_owned());
+
+    Arc::clone(relations.entry(key).or_insert_with(|| {
+        Arc::new(PgRelation {
+            id: 0,
+            namespace: namespace.to_owned(),
+            name: name.to_owned(),
+            replica_identity: 0,
+            columns: Vec::new(),
+        })
+    }))
+}
+
+// Arrange the values of a change in the order of the columns of its relation; a column
+// without a value gets `missing`
+fn named_tuple(
+    relation: &PgRelation,
+    mut values: Vec<NamedValue>,
+    missing: PgTupleValue,
+) -> PgTuple {
+    PgTuple(
+        relation
+            .columns
+            .iter()
+            .map(
+                |column| match values.iter().position(|value| value.name == column.name) {
+                    Some(index) => values.swap_remove(index).value,
+                    None => missing.clone(),
+                },
+            )
+            .collect(),
+    )
+}
+
+// Parse a `timestamptz` in the ISO format, e.g. `2020-01-02 03:04:05.678+00`, into
+// microseconds since the Postgres epoch
+fn parse_timestamp(s: &str) -> crate::Result<i64> {
+    parse_timestamp_parts(s).ok_or_else(|| decode_err!("invalid timestamp: {:?}", s))
+}
+
+fn parse_timestamp_parts(s: &str) -> Option<i64> {
+    let mut parts = s.splitn(2, ' ');
+    let (date, time) = (parts.next()?, parts.next()?);
+
+    let mut date = date.splitn(3, '-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+
+    // the offset from UTC follows the time, e.g. `+00`, `-08` or `+05:30`
+    let (time, offset) = time.split_at(time.rfind(['+', '-'])?);
+
+    let mut time = time.splitn(3, ':');
+    let hours: i64 = time.next()?.parse().ok()?;
+    let minutes: i64 = time.next()?.parse().ok()?;
+    let seconds: f64 = time.next()?.parse().ok()?;
+
+    let mut offset_parts = offset[1..].splitn(2, ':');
+    let mut offset_seconds = offset_parts.next()?.parse::<i64>().ok()? * 3600;
+
+    if let Some(offset_minutes) = offset_parts.next() {
+        offset_seconds += offset_minutes.parse::<i64>().ok()? * 60;
+    }
+
+    if offset.starts_with('-') {
+        offset_seconds = -offset_seconds;
+    }
+
+    let days = days_from_civil(year, month, day) - days_from_civil(2000, 1, 1);
+    let whole = days * 86_400 + hours * 3600 + minutes * 60 - offset_seconds;
+
+    Some(whole * 1_000_000 + (seconds * 1_000_000.0).round() as i64)
+}
+
+// The number of days from 1970-01-01 to the given date of the proleptic Gregorian calendar
+// http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+// Quote an identifier for use in a replication command
+fn quote_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_timestamp, PgLsn};
+
+    #[test]
+    fn it_formats_and_parses_lsn() {
+        let lsn: PgLsn = "16/B374D848".parse().unwrap();
+
+        assert_eq!(lsn, PgLsn(0x16_B374_D848));
+        assert_eq!(lsn.to_string(), "16/B374D848");
+        assert_eq!(PgLsn(0).to_string(), "0/0");
+
+        assert!("16B374D848".parse::<PgLsn>().is_err());
+        assert!("16/XYZ".parse::<PgLsn>().is_err());
+    }
+
+    #[test]
+    fn it_parses_timestamps() {
+        assert_eq!(parse_timestamp("2000-01-01 00:00:00+00").unwrap(), 0);
+        assert_eq!(
+            parse_timestamp("2000-01-01 01:00:00.5+01").unwrap(),
+            500_000
+        );
+        assert_eq!(parse_timestamp("1999-12-31 18:30:00-05:30").unwrap(), 0);
+
+        assert_eq!(
+            parse_timestamp("2020-03-01 12:34:56.789012+00").unwrap(),
+            636_381_296_789_012
+        );
+
+        assert!(parse_timestamp("2020-03-01").is_err());
+        assert!(parse_timestamp("2020-03-01 12:34:56").is_err());
+    }
+}
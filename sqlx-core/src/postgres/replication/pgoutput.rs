@@ -0,0 +1,428 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use byteorder::NetworkEndian;
+
+use crate::io::Buf;
+use crate::postgres::replication::PgLsn;
+
+/// A table, as described by the `pgoutput` plugin before the first change to it
+/// is streamed, or by the other plugins along with each change.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgRelation {
+    /// The OID of the table; `0` with plugins other than `pgoutput`.
+    pub id: u32,
+
+    pub namespace: String,
+    pub name: String,
+
+    /// The replica identity setting of the table; `d` (default, primary key), `n` (nothing),
+    /// `f` (all columns) or `i` (index); `0` with plugins other than `pgoutput`.
+    pub replica_identity: u8,
+
+    pub columns: Vec<PgRelationColumn>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgRelationColumn {
+    pub name: String,
+
+    /// The OID of the type of the column; `0` with the `test_decoding` plugin.
+    pub type_id: u32,
+
+    /// The name of the type of the column, e.g. `integer` or `character varying(30)`; not
+    /// sent by the `pgoutput` plugin.
+    pub type_name: Option<String>,
+
+    pub type_modifier: i32,
+
+    /// Is this column part of the replica identity (the key). Always `false` with the
+    /// `test_decoding` plugin.
+    pub is_key: bool,
+}
+
+impl PgRelation {
+    /// Returns the index of the column with the given name.
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|column| column.name == name)
+    }
+}
+
+/// The value of a single column in a [PgTuple].
+#[derive(Debug, Clone, PartialEq)]
+pub enum PgTupleValue {
+    Null,
+
+    /// A TOASTed value that was not changed; the actual value is not sent.
+    Unchanged,
+
+    /// The value in its text format.
+    Text(String),
+}
+
+/// A row streamed from a replication slot, with a value for each column of its relation.
+/// Values are in their text format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgTuple(pub Vec<PgTupleValue>);
+
+impl PgTuple {
+    /// Returns the value of the column at `index` or `None` if it is `NULL`, unchanged
+    /// or out of bounds.
+    pub fn get(&self, index: usize) -> Option<&str> {
+        match self.0.get(index) {
+            Some(PgTupleValue::Text(value)) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/// A change streamed from a logical replication slot.
+///
+/// Timestamps are microseconds since midnight on 2000-01-01 (UTC), as sent by Postgres.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PgReplicationEvent {
+    /// The start of a transaction. All following changes, until [Commit], belong to it.
+    ///
+    /// [Commit]: PgReplicationEvent::Commit
+    Begin {
+        /// The LSN of the commit record of the transaction; with plugins other than
+        /// `pgoutput`, which do not send it, the LSN of the start of the transaction.
+        final_lsn: PgLsn,
+
+        /// The commit timestamp of the transaction; `0` with the `test_decoding` plugin.
+        timestamp: i64,
+        xid: u32,
+    },
+
+    /// The end of a transaction.
+    Commit {
+        /// The LSN of the commit record; the end LSN with the `test_decoding` plugin.
+        lsn: PgLsn,
+
+        /// The end LSN of the transaction; acknowledge this to advance the slot
+        /// past the transaction.
+        end_lsn: PgLsn,
+
+        timestamp: i64,
+    },
+
+    Insert {
+        relation: Arc<PgRelation>,
+        new: PgTuple,
+    },
+
+    Update {
+        relation: Arc<PgRelation>,
+
+        /// The previous values of the key columns (or of all columns when the table
+        /// has `REPLICA IDENTITY FULL`). Not sent if the key did not change, except by
+        /// the `wal2json` plugin.
+        old: Option<PgTuple>,
+
+        new: PgTuple,
+    },
+
+    Delete {
+        relation: Arc<PgRelation>,
+
+        /// The values of the key columns (or of all columns when the table
+        /// has `REPLICA IDENTITY FULL`).
+        old: PgTuple,
+    },
+
+    /// The truncation of tables. `wal2json` sends one per table, without the options.
+    Truncate {
+        relations: Vec<Arc<PgRelation>>,
+        cascade: bool,
+        restart_identity: bool,
+    },
+}
+
+// Decode a `pgoutput` message
+// `Relation` messages are remembered in `relations` and do not produce an event
+// https://www.postgresql.org/docs/12/protocol-logicalrep-message-formats.html
+pub(super) fn decode(
+    mut buf: &[u8],
+    relations: &mut HashMap<u32, Arc<PgRelation>>,
+) -> crate::Result<Option<PgReplicationEvent>> {
+    let event = match buf.get_u8()? {
+        b'B' => PgReplicationEvent::Begin {
+            final_lsn: PgLsn(buf.get_u64::<NetworkEndian>()?),
+            timestamp: buf.get_i64::<NetworkEndian>()?,
+            xid: buf.get_u32::<NetworkEndian>()?,
+        },
+
+        b'C' => {
+            // flags; currently unused
+            let _ = buf.get_u8()?;
+
+            PgReplicationEvent::Commit {
+                lsn: PgLsn(buf.get_u64::<NetworkEndian>()?),
+                end_lsn: PgLsn(buf.get_u64::<NetworkEndian>()?),
+                timestamp: buf.get_i64::<NetworkEndian>()?,
+            }
+        }
+
+        b'R' => {
+            let relation = read_relation(buf)?;
+
+            relations.insert(relation.id, Arc::new(relation));
+
+            return Ok(None);
+        }
+
+        b'I' => {
+            let relation = get_relation(relations, buf.get_u32::<NetworkEndian>()?)?;
+
+            expect_tag(&mut buf, b'N')?;
+
+            PgReplicationEvent::Insert {
+                relation,
+                new: read_tuple(&mut buf)?,
+            }
+        }
+
+        b'U' => {
+            let relation = get_relation(relations, buf.get_u32::<NetworkEndian>()?)?;
+
+            let old = match buf.get_u8()? {
+                b'K' | b'O' => {
+                    let old = read_tuple(&mut buf)?;
+
+                    expect_tag(&mut buf, b'N')?;
+
+                    Some(old)
+                }
+
+                b'N' => None,
+
+                tag => {
+                    return Err(protocol_err!(
+                        "pgoutput: unexpected tuple type: {:?}",
+                        tag as char
+                    )
+                    .into());
+                }
+            };
+
+            PgReplicationEvent::Update {
+                relation,
+                old,
+                new: read_tuple(&mut buf)?,
+            }
+        }
+
+        b'D' => {
+            let relation = get_relation(relations, buf.get_u32::<NetworkEndian>()?)?;
+
+            match buf.get_u8()? {
+                b'K' | b'O' => {}
+
+                tag => {
+                    return Err(protocol_err!(
+                        "pgoutput: unexpected tuple type: {:?}",
+                        tag as char
+                    )
+                    .into());
+                }
+            }
+
+            PgReplicationEvent::Delete {
+                relation,
+                old: read_tuple(&mut buf)?,
+            }
+        }
+
+        b'T' => {
+            let len = buf.get_u32::<NetworkEndian>()?;
+            let options = buf.get_u8()?;
+            let mut truncated = Vec::with_capacity(len as usize);
+
+            for _ in 0..len {
+                truncated.push(get_relation(relations, buf.get_u32::<NetworkEndian>()?)?);
+            }
+
+            PgReplicationEvent::Truncate {
+                relations: truncated,
+                cascade: options & 1 != 0,
+                restart_identity: options & 2 != 0,
+            }
+        }
+
+        // Origin and Type messages are informational only
+        b'O' | b'Y' => {
+            return Ok(None);
+        }
+
+        tag => {
+            return Err(protocol_err!("pgoutput: unknown message: {:?}", tag as char).into());
+        }
+    };
+
+    Ok(Some(event))
+}
+
+fn read_relation(mut buf: &[u8]) -> crate::Result<PgRelation> {
+    let id = buf.get_u32::<NetworkEndian>()?;
+    let namespace = buf.get_str_nul()?.to_owned();
+    let name = buf.get_str_nul()?.to_owned();
+    let replica_identity = buf.get_u8()?;
+    let len = buf.get_u16::<NetworkEndian>()?;
+    let mut columns = Vec::with_capacity(len as usize);
+
+    for _ in 0..len {
+        let flags = buf.get_u8()?;
+
+        columns.push(PgRelationColumn {
+            is_key: flags & 1 != 0,
+            name: buf.get_str_nul()?.to_owned(),
+            type_id: buf.get_u32::<NetworkEndian>()?,
+            type_name: None,
+            type_modifier: buf.get_i32::<NetworkEndian>()?,
+        });
+    }
+
+    Ok(PgRelation {
+        id,
+        namespace,
+        name,
+        replica_identity,
+        columns,
+    })
+}
+
+fn read_tuple(buf: &mut &[u8]) -> crate::Result<PgTuple> {
+    let len = buf.get_u16::<NetworkEndian>()?;
+    let mut values = Vec::with_capacity(len as usize);
+
+    for _ in 0..len {
+        values.push(match buf.get_u8()? {
+            b'n' => PgTupleValue::Null,
+            b'u' => PgTupleValue::Unchanged,
+
+            b't' => {
+                let len = buf.get_u32::<NetworkEndian>()?;
+
+                PgTupleValue::Text(buf.get_str(len as usize)?.to_owned())
+            }
+
+            kind => {
+                return Err(protocol_err!(
+                    "pgoutput: unknown tuple value kind: {:?}",
+                    kind as char
+                )
+                .into());
+            }
+        });
+    }
+
+    Ok(PgTuple(values))
+}
+
+fn expect_tag(buf: &mut &[u8], expected: u8) -> crate::Result<()> {
+    match buf.get_u8()? {
+        tag if tag == expected => Ok(()),
+
+        tag => Err(protocol_err!(
+            "pgoutput: expected {:?}, got {:?}",
+            expected as char,
+            tag as char
+        )
+        .into()),
+    }
+}
+
+fn get_relation(
+    relations: &HashMap<u32, Arc<PgRelation>>,
+    id: u32,
+) -> crate::Result<Arc<PgRelation>> {
+    relations
+        .get(&id)
+        .cloned()
+        .ok_or_else(|| protocol_err!("pgoutput: change for unknown relation {}", id).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{decode, PgReplicationEvent, PgTuple, PgTupleValue};
+    use crate::postgres::replication::PgLsn;
+
+    const RELATION: &[u8] = b"R\0\0\x40\0public\0users\0d\0\x02\x01id\0\0\0\0\x17\xff\xff\xff\xff\0name\0\0\0\0\x19\xff\xff\xff\xff";
+
+    const INSERT: &[u8] = b"I\0\0\x40\0N\0\x02t\0\0\0\x011n";
+
+    const UPDATE: &[u8] = b"U\0\0\x40\0K\0\x02t\0\0\0\x011nN\0\x02t\0\0\0\x012u";
+
+    const BEGIN: &[u8] = b"B\0\0\0\0\x01\0\0\x10\0\0\0\0\0\0\0\x05\0\0\x02\0";
+
+    #[test]
+    fn it_decodes_relation_and_insert() {
+        let mut relations = HashMap::new();
+
+        assert!(decode(RELATION, &mut relations).unwrap().is_none());
+
+        let relation = &relations[&0x4000];
+
+        assert_eq!(relation.namespace, "public");
+        assert_eq!(relation.name, "users");
+        assert_eq!(relation.columns.len(), 2);
+        assert!(relation.columns[0].is_key);
+        assert_eq!(relation.columns[1].type_id, 25);
+        assert_eq!(relation.column_index("name"), Some(1));
+
+        match decode(INSERT, &mut relations).unwrap() {
+            Some(PgReplicationEvent::Insert { relation, new }) => {
+                assert_eq!(relation.name, "users");
+                assert_eq!(new.get(0), Some("1"));
+                assert_eq!(new.0[1], PgTupleValue::Null);
+            }
+
+            event => panic!("unexpected event: {:?}", event),
+        }
+    }
+
+    #[test]
+    fn it_decodes_update() {
+        let mut relations = HashMap::new();
+
+        decode(RELATION, &mut relations).unwrap();
+
+        match decode(UPDATE, &mut relations).unwrap() {
+            Some(PgReplicationEvent::Update { old, new, .. }) => {
+                assert_eq!(
+                    old,
+                    Some(PgTuple(vec![
+                        PgTupleValue::Text("1".into()),
+                        PgTupleValue::Null
+                    ]))
+                );
+
+                assert_eq!(new.get(0), Some("2"));
+                assert_eq!(new.0[1], PgTupleValue::Unchanged);
+            }
+
+            event => panic!("unexpected event: {:?}", event),
+        }
+    }
+
+    #[test]
+    fn it_decodes_begin() {
+        let event = decode(BEGIN, &mut HashMap::new()).unwrap();
+
+        assert_eq!(
+            event,
+            Some(PgReplicationEvent::Begin {
+                final_lsn: PgLsn(0x0100_0010),
+                timestamp: 5,
+                xid: 512,
+            })
+        );
+    }
+
+    #[test]
+    fn it_fails_on_unknown_relation() {
+        assert!(decode(INSERT, &mut HashMap::new()).is_err());
+    }
+}
@@ -0,0 +1,381 @@
+use std::str;
+
+use crate::postgres::replication::{
+    named_relation, named_tuple, parse_timestamp, truncated_relation, NamedRelations, NamedValue,
+    PgLsn, PgReplicationEvent, PgTupleValue,
+};
+
+// Decode a message of the `test_decoding` plugin, a line of text such as:
+//
+//   BEGIN 529
+//   table public.users: INSERT: id[integer]:1 name[text]:'Jane'
+//   table public.users: UPDATE: old-key: id[integer]:1 new-tuple: id[integer]:2 name[text]:null
+//   table public.users, public.posts: TRUNCATE: restart_seqs cascade
+//   COMMIT 529 (at 2020-01-02 03:04:05.678+00)
+//
+// https://github.com/postgres/postgres/blob/REL_12_STABLE/contrib/test_decoding/test_decoding.c
+pub(super) fn decode(
+    data: &[u8],
+    lsn: PgLsn,
+    relations: &mut NamedRelations,
+) -> crate::Result<Option<PgReplicationEvent>> {
+    let message = str::from_utf8(data).map_err(crate::Error::decode)?;
+
+    // messages emitted with `pg_logical_emit_message` are not changes
+    if message.starts_with("message:") {
+        return Ok(None);
+    }
+
+    match decode_change(message, lsn, relations) {
+        Some(event) => Ok(Some(event)),
+
+        None => Err(protocol_err!("test_decoding: unexpected message: {:?}", message).into()),
+    }
+}
+
+fn decode_change(
+    message: &str,
+    lsn: PgLsn,
+    relations: &mut NamedRelations,
+) -> Option<PgReplicationEvent> {
+    if let Some(xid) = message.strip_prefix("BEGIN ") {
+        return Some(PgReplicationEvent::Begin {
+            final_lsn: lsn,
+            timestamp: 0,
+            xid: xid.parse().ok()?,
+        });
+    }
+
+    if let Some(rest) = message.strip_prefix("COMMIT ") {
+        let timestamp = match rest.find(" (at ") {
+            Some(at) => parse_timestamp(rest[at + 5..].strip_suffix(')')?).ok()?,
+            None => 0,
+        };
+
+        // the end LSN is where the commit is streamed at
+        return Some(PgReplicationEvent::Commit {
+            lsn,
+            end_lsn: lsn,
+            timestamp,
+        });
+    }
+
+    let mut rest = message.strip_prefix("table ")?;
+    let mut tables = Vec::new();
+
+    loop {
+        let (namespace, after) = read_ident(rest)?;
+        let (name, after) = read_ident(after.strip_prefix('.')?)?;
+
+        tables.push((namespace, name));
+
+        match after.strip_prefix(", ") {
+            Some(after) => rest = after,
+
+            None => {
+                rest = after.strip_prefix(": ")?;
+                break;
+            }
+        }
+    }
+
+    let mut parts = rest.splitn(2, ": ");
+    let (action, rest) = (parts.next()?, parts.next().unwrap_or(""));
+
+    if action == "TRUNCATE" {
+        let flags: Vec<_> = rest.split(' ').collect();
+
+        return Some(PgReplicationEvent::Truncate {
+            relations: tables
+                .iter()
+                .map(|(namespace, name)| truncated_relation(relations, namespace, name))
+                .collect(),
+            cascade: flags.contains(&"cascade"),
+            restart_identity: flags.contains(&"restart_seqs"),
+        });
+    }
+
+    // changes other than a truncation are to a single table
+    let (namespace, name) = match &*tables {
+        [(namespace, name)] => (namespace, name),
+        _ => return None,
+    };
+
+    Some(match action {
+        "INSERT" => {
+            let new = read_tuple(rest)?.0;
+            let relation = named_relation(relations, namespace, name, &new, &[], true);
+
+            PgReplicationEvent::Insert {
+                new: named_tuple(&relation, new, PgTupleValue::Null),
+                relation,
+            }
+        }
+
+        "UPDATE" => {
+            let (old, new) = match rest.strip_prefix("old-key: ") {
+                Some(rest) => {
+                    let (old, rest) = read_tuple(rest)?;
+
+                    (Some(old), read_tuple(rest.strip_prefix("new-tuple: ")?)?.0)
+                }
+
+                None => (None, read_tuple(rest)?.0),
+            };
+
+            let relation = named_relation(relations, namespace, name, &new, &[], true);
+
+            PgReplicationEvent::Update {
+                old: old.map(|old| named_tuple(&relation, old, PgTupleValue::Null)),
+                new: named_tuple(&relation, new, PgTupleValue::Null),
+                relation,
+            }
+        }
+
+        "DELETE" => {
+            let old = read_tuple(rest)?.0;
+            let relation = named_relation(relations, namespace, name, &old, &[], false);
+
+            PgReplicationEvent::Delete {
+                old: named_tuple(&relation, old, PgTupleValue::Null),
+                relation,
+            }
+        }
+
+        _ => return None,
+    })
+}
+
+// Read the values of a tuple, up to the end of the message or to the `new-tuple:` of
+// an update
+fn read_tuple(mut rest: &str) -> Option<(Vec<NamedValue>, &str)> {
+    let mut values = Vec::new();
+
+    // the table has no replica identity or no columns
+    if let Some(after) = rest.strip_prefix("(no-tuple-data)") {
+        return Some((values, after.trim_start()));
+    }
+
+    while !rest.is_empty() && !rest.starts_with("new-tuple:") {
+        let (name, after) = read_ident(rest)?;
+        let after = after.strip_prefix('[')?;
+
+        // the name of an array type ends with `[]`
+        let end = after.find("]:")?;
+        let type_name = after[..end].to_owned();
+        let (value, after) = read_value(&after[end + 2..])?;
+
+        values.push(NamedValue {
+            name,
+            type_name,
+            type_id: 0,
+            value,
+        });
+
+        rest = after.strip_prefix(' ').unwrap_or(after);
+    }
+
+    Some((values, rest))
+}
+
+fn read_value(rest: &str) -> Option<(PgTupleValue, &str)> {
+    // bit strings are quoted as `B'0101'`
+    let quoted = rest.strip_prefix('\'').or_else(|| rest.strip_prefix("B'"));
+
+    if let Some(quoted) = quoted {
+        let (value, rest) = read_quoted(quoted, '\'')?;
+
+        return Some((PgTupleValue::Text(value), rest));
+    }
+
+    // numbers and booleans are not quoted
+    let end = rest.find(' ').unwrap_or(rest.len());
+    let (value, rest) = rest.split_at(end);
+
+    let value = match value {
+        "null" => PgTupleValue::Null,
+        "unchanged-toast-datum" => PgTupleValue::Unchanged,
+        "true" => PgTupleValue::Text("t".to_owned()),
+        "false" => PgTupleValue::Text("f".to_owned()),
+        _ => PgTupleValue::Text(value.to_owned()),
+    };
+
+    Some((value, rest))
+}
+
+// Read an identifier, which is quoted if it is not all lowercase letters, digits
+// and underscores
+fn read_ident(rest: &str) -> Option<(String, &str)> {
+    if let Some(quoted) = rest.strip_prefix('"') {
+        return read_quoted(quoted, '"');
+    }
+
+    let end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        .unwrap_or(rest.len());
+
+    if end == 0 {
+        return None;
+    }
+
+    Some((rest[..end].to_owned(), &rest[end..]))
+}
+
+// Read up to the closing `quote`, which is doubled where it is part of the text
+fn read_quoted(rest: &str, quote: char) -> Option<(String, &str)> {
+    let mut text = String::new();
+    let mut chars = rest.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        if c != quote {
+            text.push(c);
+        } else if let Some((_, next)) = chars.peek().copied().filter(|(_, next)| *next == quote) {
+            text.push(next);
+            chars.next();
+        } else {
+            return Some((text, &rest[index + 1..]));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::decode;
+    use crate::postgres::replication::{PgLsn, PgReplicationEvent, PgTuple, PgTupleValue};
+
+    const INSERT: &[u8] = br#"table public."User Accounts": INSERT: id[integer]:1 "full name"[character varying]:'it''s' ok[boolean]:true tags[text[]]:'{a,b}' bits[bit(4)]:B'0101' data[bytea]:null"#;
+
+    const UPDATE: &[u8] = br#"table public."User Accounts": UPDATE: old-key: id[integer]:1 new-tuple: id[integer]:2 "full name"[character varying]:'Jane' ok[boolean]:false tags[text[]]:unchanged-toast-datum bits[bit(4)]:null data[bytea]:null"#;
+
+    #[test]
+    fn it_decodes_transactions() {
+        let mut relations = HashMap::new();
+
+        assert_eq!(
+            decode(b"BEGIN 529", PgLsn(16), &mut relations).unwrap(),
+            Some(PgReplicationEvent::Begin {
+                final_lsn: PgLsn(16),
+                timestamp: 0,
+                xid: 529,
+            })
+        );
+
+        assert_eq!(
+            decode(
+                b"COMMIT 529 (at 2000-01-01 00:00:01.5+00)",
+                PgLsn(32),
+                &mut relations
+            )
+            .unwrap(),
+            Some(PgReplicationEvent::Commit {
+                lsn: PgLsn(32),
+                end_lsn: PgLsn(32),
+                timestamp: 1_500_000,
+            })
+        );
+
+        assert!(decode(
+            b"message: transactional: 1 prefix: x, sz: 1 content:y",
+            PgLsn(0),
+            &mut relations
+        )
+        .unwrap()
+        .is_none());
+
+        assert!(decode(b"ROLLBACK 529", PgLsn(0), &mut relations).is_err());
+    }
+
+    #[test]
+    fn it_decodes_insert_and_update() {
+        let mut relations = HashMap::new();
+
+        let inserted = match decode(INSERT, PgLsn(0), &mut relations).unwrap() {
+            Some(PgReplicationEvent::Insert { relation, new }) => {
+                assert_eq!(relation.namespace, "public");
+                assert_eq!(relation.name, "User Accounts");
+                assert_eq!(relation.column_index("full name"), Some(1));
+                assert_eq!(relation.columns[3].type_name.as_deref(), Some("text[]"));
+
+                assert_eq!(
+                    new,
+                    PgTuple(vec![
+                        PgTupleValue::Text("1".into()),
+                        PgTupleValue::Text("it's".into()),
+                        PgTupleValue::Text("t".into()),
+                        PgTupleValue::Text("{a,b}".into()),
+                        PgTupleValue::Text("0101".into()),
+                        PgTupleValue::Null,
+                    ])
+                );
+
+                relation
+            }
+
+            event => panic!("unexpected event: {:?}", event),
+        };
+
+        match decode(UPDATE, PgLsn(0), &mut relations).unwrap() {
+            Some(PgReplicationEvent::Update { relation, old, new }) => {
+                // the relation is the same as long as the columns are
+                assert!(std::sync::Arc::ptr_eq(&relation, &inserted));
+
+                assert_eq!(old.unwrap().get(0), Some("1"));
+                assert_eq!(new.get(0), Some("2"));
+                assert_eq!(new.get(2), Some("f"));
+                assert_eq!(new.0[3], PgTupleValue::Unchanged);
+            }
+
+            event => panic!("unexpected event: {:?}", event),
+        }
+    }
+
+    #[test]
+    fn it_decodes_delete_and_truncate() {
+        let mut relations = HashMap::new();
+
+        decode(INSERT, PgLsn(0), &mut relations).unwrap();
+
+        match decode(
+            br#"table public."User Accounts": DELETE: id[integer]:1"#,
+            PgLsn(0),
+            &mut relations,
+        )
+        .unwrap()
+        {
+            Some(PgReplicationEvent::Delete { relation, old }) => {
+                // the values of the columns that are not part of the key are not sent
+                assert_eq!(relation.columns.len(), 6);
+                assert_eq!(old.get(0), Some("1"));
+                assert_eq!(old.0[1], PgTupleValue::Null);
+            }
+
+            event => panic!("unexpected event: {:?}", event),
+        }
+
+        match decode(
+            br#"table public."User Accounts", public.posts: TRUNCATE: restart_seqs"#,
+            PgLsn(0),
+            &mut relations,
+        )
+        .unwrap()
+        {
+            Some(PgReplicationEvent::Truncate {
+                relations,
+                cascade,
+                restart_identity,
+            }) => {
+                assert_eq!(relations.len(), 2);
+                assert_eq!(relations[0].columns.len(), 6);
+                assert_eq!(relations[1].name, "posts");
+                assert!(!cascade);
+                assert!(restart_identity);
+            }
+
+            event => panic!("unexpected event: {:?}", event),
+        }
+    }
+}
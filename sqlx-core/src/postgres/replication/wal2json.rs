@@ -0,0 +1,319 @@
+use serde::Deserialize;
+use serde_json::value::RawValue;
+
+use crate::postgres::replication::{
+    named_relation, named_tuple, parse_timestamp, truncated_relation, NamedRelations, NamedValue,
+    PgLsn, PgReplicationEvent, PgTupleValue,
+};
+
+// A message of the `wal2json` plugin in format version 2, a JSON object per change
+// https://github.com/eulerto/wal2json#format-version-2
+#[derive(Deserialize)]
+struct Message<'a> {
+    action: String,
+    xid: Option<u32>,
+    timestamp: Option<String>,
+    lsn: Option<String>,
+    nextlsn: Option<String>,
+    schema: Option<String>,
+    table: Option<String>,
+
+    // the new values of an insert or update
+    #[serde(default, borrow)]
+    columns: Vec<Column<'a>>,
+
+    // the old values of the key of an update or delete
+    #[serde(default, borrow)]
+    identity: Vec<Column<'a>>,
+
+    #[serde(default)]
+    pk: Vec<Key>,
+}
+
+#[derive(Deserialize)]
+struct Column<'a> {
+    name: String,
+
+    #[serde(rename = "type")]
+    type_name: String,
+
+    #[serde(default)]
+    typeoid: u32,
+
+    #[serde(borrow)]
+    value: Option<&'a RawValue>,
+}
+
+#[derive(Deserialize)]
+struct Key {
+    name: String,
+}
+
+// Decode a message of the `wal2json` plugin
+// Unchanged TOAST values are left out of the columns of an update
+pub(super) fn decode(
+    data: &[u8],
+    lsn: PgLsn,
+    relations: &mut NamedRelations,
+) -> crate::Result<Option<PgReplicationEvent>> {
+    let message: Message<'_> = serde_json::from_slice(data).map_err(crate::Error::decode)?;
+
+    let timestamp = match &message.timestamp {
+        Some(timestamp) => parse_timestamp(timestamp)?,
+        None => 0,
+    };
+
+    let event = match &*message.action {
+        "B" => PgReplicationEvent::Begin {
+            final_lsn: lsn,
+            timestamp,
+            xid: message.xid.unwrap_or_default(),
+        },
+
+        "C" => PgReplicationEvent::Commit {
+            lsn: parse_lsn(&message.lsn, lsn)?,
+            end_lsn: parse_lsn(&message.nextlsn, lsn)?,
+            timestamp,
+        },
+
+        "I" | "U" | "D" | "T" => {
+            let (namespace, name) = match (&message.schema, &message.table) {
+                (Some(namespace), Some(name)) => (namespace, name),
+
+                _ => {
+                    return Err(protocol_err!("wal2json: change without a table").into());
+                }
+            };
+
+            let keys: Vec<_> = message.pk.into_iter().map(|key| key.name).collect();
+            let new = read_values(message.columns)?;
+            let old = read_values(message.identity)?;
+
+            match &*message.action {
+                "I" => {
+                    let relation = named_relation(relations, namespace, name, &new, &keys, true);
+
+                    PgReplicationEvent::Insert {
+                        new: named_tuple(&relation, new, PgTupleValue::Null),
+                        relation,
+                    }
+                }
+
+                "U" => {
+                    let relation = named_relation(relations, namespace, name, &new, &keys, false);
+
+                    PgReplicationEvent::Update {
+                        old: if old.is_empty() {
+                            None
+                        } else {
+                            Some(named_tuple(&relation, old, PgTupleValue::Null))
+                        },
+                        new: named_tuple(&relation, new, PgTupleValue::Unchanged),
+                        relation,
+                    }
+                }
+
+                "D" => {
+                    let relation = named_relation(relations, namespace, name, &old, &keys, false);
+
+                    PgReplicationEvent::Delete {
+                        old: named_tuple(&relation, old, PgTupleValue::Null),
+                        relation,
+                    }
+                }
+
+                _ => PgReplicationEvent::Truncate {
+                    relations: vec![truncated_relation(relations, namespace, name)],
+                    cascade: false,
+                    restart_identity: false,
+                },
+            }
+        }
+
+        // messages emitted with `pg_logical_emit_message` are not changes
+        "M" => {
+            return Ok(None);
+        }
+
+        action => {
+            return Err(protocol_err!("wal2json: unknown action: {:?}", action).into());
+        }
+    };
+
+    Ok(Some(event))
+}
+
+fn read_values(columns: Vec<Column<'_>>) -> crate::Result<Vec<NamedValue>> {
+    columns
+        .into_iter()
+        .map(|column| {
+            let value = match column.value.map(RawValue::get) {
+                None => PgTupleValue::Null,
+
+                // booleans are sent as JSON; in their text format they are `t` and `f`
+                Some("true") => PgTupleValue::Text("t".to_owned()),
+                Some("false") => PgTupleValue::Text("f".to_owned()),
+
+                Some(raw) if raw.starts_with('"') => {
+                    PgTupleValue::Text(serde_json::from_str(raw).map_err(crate::Error::decode)?)
+                }
+
+                // numbers are sent in their text format, without loss of precision
+                Some(raw) => PgTupleValue::Text(raw.to_owned()),
+            };
+
+            Ok(NamedValue {
+                name: column.name,
+                type_name: column.type_name,
+                type_id: column.typeoid,
+                value,
+            })
+        })
+        .collect()
+}
+
+fn parse_lsn(lsn: &Option<String>, default: PgLsn) -> crate::Result<PgLsn> {
+    match lsn {
+        Some(lsn) => lsn.parse(),
+        None => Ok(default),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::decode;
+    use crate::postgres::replication::{PgLsn, PgReplicationEvent, PgTuple, PgTupleValue};
+
+    const INSERT: &[u8] = br#"{"action":"I","xid":529,"timestamp":"2000-01-01 00:00:01+00","lsn":"0/16B2460","schema":"public","table":"users","columns":[{"name":"id","type":"integer","typeoid":23,"value":1},{"name":"name","type":"character varying(30)","typeoid":1043,"value":"Jane \"J\" Doe"},{"name":"ok","type":"boolean","typeoid":16,"value":true},{"name":"balance","type":"numeric","typeoid":1700,"value":12345678901234567890.123}],"pk":[{"name":"id","type":"integer","typeoid":23}]}"#;
+
+    const UPDATE: &[u8] = br#"{"action":"U","xid":530,"schema":"public","table":"users","columns":[{"name":"id","type":"integer","typeoid":23,"value":2},{"name":"ok","type":"boolean","typeoid":16,"value":false},{"name":"balance","type":"numeric","typeoid":1700,"value":null}],"identity":[{"name":"id","type":"integer","typeoid":23,"value":1}],"pk":[{"name":"id","type":"integer","typeoid":23}]}"#;
+
+    #[test]
+    fn it_decodes_transactions() {
+        let mut relations = HashMap::new();
+
+        assert_eq!(
+            decode(
+                br#"{"action":"B","xid":529,"timestamp":"2000-01-01 00:00:01+00","nextlsn":"0/16B2520"}"#,
+                PgLsn(16),
+                &mut relations
+            )
+            .unwrap(),
+            Some(PgReplicationEvent::Begin {
+                final_lsn: PgLsn(16),
+                timestamp: 1_000_000,
+                xid: 529,
+            })
+        );
+
+        assert_eq!(
+            decode(
+                br#"{"action":"C","xid":529,"timestamp":"2000-01-01 00:00:01+00","lsn":"0/16B24F0","nextlsn":"0/16B2520"}"#,
+                PgLsn(32),
+                &mut relations
+            )
+            .unwrap(),
+            Some(PgReplicationEvent::Commit {
+                lsn: PgLsn(0x16B_24F0),
+                end_lsn: PgLsn(0x16B_2520),
+                timestamp: 1_000_000,
+            })
+        );
+
+        assert!(decode(br#"{"action":"X"}"#, PgLsn(0), &mut relations).is_err());
+    }
+
+    #[test]
+    fn it_decodes_insert_and_update() {
+        let mut relations = HashMap::new();
+
+        let inserted = match decode(INSERT, PgLsn(0), &mut relations).unwrap() {
+            Some(PgReplicationEvent::Insert { relation, new }) => {
+                assert_eq!(relation.name, "users");
+                assert!(relation.columns[0].is_key);
+                assert!(!relation.columns[1].is_key);
+                assert_eq!(relation.columns[1].type_id, 1043);
+
+                assert_eq!(
+                    new,
+                    PgTuple(vec![
+                        PgTupleValue::Text("1".into()),
+                        PgTupleValue::Text("Jane \"J\" Doe".into()),
+                        PgTupleValue::Text("t".into()),
+                        PgTupleValue::Text("12345678901234567890.123".into()),
+                    ])
+                );
+
+                relation
+            }
+
+            event => panic!("unexpected event: {:?}", event),
+        };
+
+        match decode(UPDATE, PgLsn(0), &mut relations).unwrap() {
+            Some(PgReplicationEvent::Update { relation, old, new }) => {
+                assert!(std::sync::Arc::ptr_eq(&relation, &inserted));
+
+                assert_eq!(
+                    old,
+                    Some(PgTuple(vec![
+                        PgTupleValue::Text("1".into()),
+                        PgTupleValue::Null,
+                        PgTupleValue::Null,
+                        PgTupleValue::Null,
+                    ]))
+                );
+
+                // the name is TOASTed and was not changed
+                assert_eq!(
+                    new,
+                    PgTuple(vec![
+                        PgTupleValue::Text("2".into()),
+                        PgTupleValue::Unchanged,
+                        PgTupleValue::Text("f".into()),
+                        PgTupleValue::Null,
+                    ])
+                );
+            }
+
+            event => panic!("unexpected event: {:?}", event),
+        }
+    }
+
+    #[test]
+    fn it_decodes_delete_and_truncate() {
+        let mut relations = HashMap::new();
+
+        match decode(
+            br#"{"action":"D","schema":"public","table":"users","identity":[{"name":"id","type":"integer","typeoid":23,"value":1}]}"#,
+            PgLsn(0),
+            &mut relations,
+        )
+        .unwrap()
+        {
+            Some(PgReplicationEvent::Delete { relation, old }) => {
+                assert_eq!(relation.columns.len(), 1);
+                assert_eq!(old.get(0), Some("1"));
+            }
+
+            event => panic!("unexpected event: {:?}", event),
+        }
+
+        match decode(
+            br#"{"action":"T","schema":"public","table":"users"}"#,
+            PgLsn(0),
+            &mut relations,
+        )
+        .unwrap()
+        {
+            Some(PgReplicationEvent::Truncate { relations, .. }) => {
+                assert_eq!(relations[0].name, "users");
+                assert_eq!(relations[0].columns.len(), 1);
+            }
+
+            event => panic!("unexpected event: {:?}", event),
+        }
+    }
+}
@@ -11,8 +11,10 @@ use crate::row::{ColumnIndex, Row};
 // For Postgres, each column has an OID and a format (binary or text)
 // For simple (unprepared) queries, format will always be text
 // For prepared queries, format will _almost_ always be binary
+
+/// A column of the rows returned by a query, from [`PgRow::columns`].
 #[derive(Clone, Debug)]
-pub(crate) struct Column {
+pub struct PgColumn {
     pub(crate) name: Option<SharedStr>,
     pub(crate) type_info: PgTypeInfo,
     pub(crate) format: TypeFormat,
@@ -20,6 +22,34 @@ pub(crate) struct Column {
     pub(crate) column_id: i16,
 }
 
+impl PgColumn {
+    /// The name of the column, if it has one.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// The type of the column.
+    pub fn type_info(&self) -> &PgTypeInfo {
+        &self.type_info
+    }
+
+    /// The OID of the table that the column was taken from, if it is a column of a table
+    /// (and not, e.g., an expression).
+    pub fn table_oid(&self) -> Option<u32> {
+        self.table_id
+    }
+
+    /// The attribute number of the column in its table, the `attnum` of its row in
+    /// `pg_attribute`, if it is a column of a table.
+    pub fn attnum(&self) -> Option<i16> {
+        if self.table_id.is_some() && self.column_id != 0 {
+            Some(self.column_id)
+        } else {
+            None
+        }
+    }
+}
+
 // A statement description containing the column information used to
 // properly decode data
 #[derive(Default)]
@@ -31,7 +61,7 @@ pub(crate) struct Statement {
     pub(crate) names: HashMap<SharedStr, usize>,
 
     // all columns
-    pub(crate) columns: Box<[Column]>,
+    pub(crate) columns: Box<[PgColumn]>,
 }
 
 pub struct PgRow<'c> {
@@ -42,6 +72,13 @@ pub struct PgRow<'c> {
     pub(super) statement: Arc<Statement>,
 }
 
+impl PgRow<'_> {
+    /// The columns of the row, in order.
+    pub fn columns(&self) -> &[PgColumn] {
+        &self.statement.columns
+    }
+}
+
 impl crate::row::private_row::Sealed for PgRow<'_> {}
 
 impl<'c> Row<'c> for PgRow<'c> {
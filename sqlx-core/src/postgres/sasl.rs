@@ -6,8 +6,8 @@ use crate::postgres::protocol::{
     hi, Authentication, AuthenticationSaslContinue, Message, SaslInitialResponse, SaslResponse,
 };
 use crate::postgres::stream::PgStream;
+use crate::url::Url;
 
-static GS2_HEADER: &'static str = "n,,";
 static CHANNEL_ATTR: &'static str = "c";
 static USERNAME_ATTR: &'static str = "n";
 static CLIENT_PROOF_ATTR: &'static str = "p";
@@ -40,15 +40,122 @@ fn nonce() -> String {
     format!("{}={}", NONCE_ATTR, nonce)
 }
 
+// The channel binding used during authentication, as announced by the GS2 header
+// https://tools.ietf.org/html/rfc5802#section-6
+#[derive(Debug, PartialEq)]
+pub(super) enum ChannelBinding {
+    // "n": the client does not support channel binding
+    Unsupported,
+
+    // "y": the client supports channel binding but the server did not offer it
+    NotOffered,
+
+    // "p": `tls-server-end-point` (RFC 5929), the hash of the server's certificate
+    TlsServerEndPoint(Vec<u8>),
+}
+
+// The `channel_binding` connection parameter, which accepts the same values as in libpq:
+// `disable`, `prefer` (the default) or `require`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum ChannelBindingMode {
+    Disable,
+    Prefer,
+    Require,
+}
+
+impl ChannelBindingMode {
+    pub(super) fn from_url(url: &Url) -> crate::Result<Self> {
+        match url.param("channel_binding").as_deref() {
+            Some("disable") => Ok(ChannelBindingMode::Disable),
+            Some("prefer") | None => Ok(ChannelBindingMode::Prefer),
+            Some("require") => Ok(ChannelBindingMode::Require),
+
+            Some(mode) => Err(protocol_err!("unknown `channel_binding` value: {:?}", mode).into()),
+        }
+    }
+
+    // Check that the server may authenticate the client with `method`, which is not SCRAM;
+    // as in libpq, a required channel binding rules out all other methods, so that a server
+    // (or man-in-the-middle) can not fall back to one without it
+    pub(super) fn check_method(self, method: &str) -> crate::Result<()> {
+        if self == ChannelBindingMode::Require {
+            return Err(protocol_err!(
+                "channel binding is required, but the server requested {} authentication",
+                method
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+impl ChannelBinding {
+    // Choose the channel binding of a SCRAM exchange
+    pub(super) fn choose(
+        stream: &PgStream,
+        mode: ChannelBindingMode,
+        offered_by_server: bool,
+    ) -> crate::Result<Self> {
+        if mode == ChannelBindingMode::Disable {
+            return Ok(ChannelBinding::Unsupported);
+        }
+
+        let binding = match stream.stream.tls_server_end_point()? {
+            Some(data) if offered_by_server => ChannelBinding::TlsServerEndPoint(data),
+            Some(_) => ChannelBinding::NotOffered,
+            None => ChannelBinding::Unsupported,
+        };
+
+        if mode == ChannelBindingMode::Require
+            && !matches!(binding, ChannelBinding::TlsServerEndPoint(_))
+        {
+            return Err(protocol_err!(
+                "channel binding is required but is not supported by the server or connection \
+                 (channel binding requires TLS)"
+            )
+            .into());
+        }
+
+        Ok(binding)
+    }
+
+    fn mechanism(&self) -> &'static str {
+        match self {
+            ChannelBinding::TlsServerEndPoint(_) => "SCRAM-SHA-256-PLUS",
+            _ => "SCRAM-SHA-256",
+        }
+    }
+
+    fn gs2_header(&self) -> &'static str {
+        match self {
+            ChannelBinding::Unsupported => "n,,",
+            ChannelBinding::NotOffered => "y,,",
+            ChannelBinding::TlsServerEndPoint(_) => "p=tls-server-end-point,,",
+        }
+    }
+
+    // channel-binding = "c=" base64(gs2-header [cbind-data])
+    fn attr(&self) -> String {
+        let mut data = self.gs2_header().as_bytes().to_vec();
+
+        if let ChannelBinding::TlsServerEndPoint(cbind_data) = self {
+            data.extend_from_slice(cbind_data);
+        }
+
+        format!("{}={}", CHANNEL_ATTR, base64::encode(&data))
+    }
+}
+
 // Performs authenticiton using Simple Authentication Security Layer (SASL) which is what
 // Postgres uses
 pub(super) async fn authenticate<T: AsRef<str>>(
     stream: &mut PgStream,
     username: T,
     password: T,
+    binding: ChannelBinding,
 ) -> crate::Result<()> {
-    // channel-binding = "c=" base64
-    let channel_binding = format!("{}={}", CHANNEL_ATTR, base64::encode(GS2_HEADER));
+    let channel_binding = binding.attr();
     // "n=" saslname ;; Usernames are prepared using SASLprep.
     let username = format!("{}={}", USERNAME_ATTR, username.as_ref());
     // nonce = "r=" c-nonce [s-nonce] ;; Second part provided by server.
@@ -58,11 +165,14 @@ pub(super) async fn authenticate<T: AsRef<str>>(
     // client-first-message-bare = [reserved-mext ","] username "," nonce ["," extensions]
     let client_first_message = format!(
         "{gs2_header}{client_first_message_bare}",
-        gs2_header = GS2_HEADER,
+        gs2_header = binding.gs2_header(),
         client_first_message_bare = client_first_message_bare
     );
 
-    stream.write(SaslInitialResponse(&client_first_message));
+    stream.write(SaslInitialResponse {
+        mechanism: binding.mechanism(),
+        response: &client_first_message,
+    });
     stream.flush().await?;
 
     let server_first_message = stream.receive().await?;
@@ -127,7 +237,7 @@ pub(super) async fn authenticate<T: AsRef<str>>(
             let mut mac =
                 Hmac::<Sha256>::new_varkey(&server_key).expect("HMAC can take key of any size");
             mac.input(&auth_message.as_bytes());
-            let _server_signature = mac.result().code();
+            let server_signature = mac.result().code();
 
             // client-final-message = client-final-message-without-proof "," proof
             let client_final_message = format!(
@@ -140,10 +250,37 @@ pub(super) async fn authenticate<T: AsRef<str>>(
             stream.write(SaslResponse(&client_final_message));
             stream.flush().await?;
 
-            let _server_final_response = stream.receive().await?;
-            // todo: assert that this was SaslFinal?
+            // server-final-message = "v=" base64(ServerSignature), which proves that the
+            // server knows the password too (and, with channel binding, that it is the end
+            // of the TLS connection)
+            match stream.receive().await? {
+                Message::Authentication => {}
+
+                message => {
+                    return Err(protocol_err!(
+                        "Expected Message::Authentication, but received {:?}",
+                        message
+                    )
+                    .into());
+                }
+            }
+
+            match Authentication::read(stream.buffer())? {
+                Authentication::SaslFinal => {}
+
+                auth => {
+                    return Err(protocol_err!(
+                        "Expected Authentication::SaslFinal, but received {:?}",
+                        auth
+                    )
+                    .into());
+                }
+            }
 
-            Ok(())
+            let server_final_message = std::str::from_utf8(&stream.buffer()[4..])
+                .map_err(|_| protocol_err!("SaslFinal response was not a valid utf8 string"))?;
+
+            verify_server_signature(server_final_message, &server_signature)
         } else {
             Err(protocol_err!(
                 "Expected Authentication::SaslContinue, but received {:?}",
@@ -157,3 +294,59 @@ pub(super) async fn authenticate<T: AsRef<str>>(
         ))?
     }
 }
+
+// Check the server signature of a server-final-message, which may have extensions after it
+fn verify_server_signature(server_final_message: &str, expected: &[u8]) -> crate::Result<()> {
+    let signature = server_final_message
+        .split(',')
+        .find(|attr| attr.starts_with("v="))
+        .and_then(|attr| base64::decode(&attr[2..]).ok());
+
+    match signature {
+        Some(signature) if signature == expected => Ok(()),
+
+        _ => Err(protocol_err!(
+            "invalid server signature in SCRAM server-final-message: {:?}",
+            server_final_message
+        )
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{verify_server_signature, ChannelBinding, ChannelBindingMode};
+
+    #[test]
+    fn it_encodes_channel_binding() {
+        assert_eq!(ChannelBinding::Unsupported.attr(), "c=biws");
+        assert_eq!(ChannelBinding::NotOffered.attr(), "c=eSws");
+
+        let binding = ChannelBinding::TlsServerEndPoint(vec![1, 2, 3]);
+
+        assert_eq!(binding.mechanism(), "SCRAM-SHA-256-PLUS");
+        assert_eq!(
+            binding.attr(),
+            format!(
+                "c={}",
+                base64::encode(b"p=tls-server-end-point,,\x01\x02\x03")
+            )
+        );
+    }
+
+    #[test]
+    fn it_verifies_server_signatures() {
+        let message = format!("v={}", base64::encode(b"signature"));
+
+        assert!(verify_server_signature(&message, b"signature").is_ok());
+        assert!(verify_server_signature(&message, b"other").is_err());
+        assert!(verify_server_signature("e=invalid-proof", b"signature").is_err());
+    }
+
+    #[test]
+    fn it_rejects_other_methods_when_channel_binding_is_required() {
+        assert!(ChannelBindingMode::Prefer.check_method("MD5").is_ok());
+        assert!(ChannelBindingMode::Disable.check_method("MD5").is_ok());
+        assert!(ChannelBindingMode::Require.check_method("MD5").is_err());
+    }
+}
@@ -0,0 +1,274 @@
+use std::sync::Arc;
+
+use futures_core::stream::BoxStream;
+
+use crate::executor::{Execute, Executor};
+use crate::postgres::protocol::{DataRow, Message, RowDescription, TransactionStatus};
+use crate::postgres::row::Statement;
+use crate::postgres::{PgArguments, PgConnection, PgRow, Postgres};
+use crate::query::Query;
+use crate::query_as::QueryAs;
+use crate::row::FromRow;
+
+// There can only be one server-side cursor in use at any one time on a connection
+// (as it is mutably borrowed) so a fixed name is used
+const CURSOR_NAME: &str = "_sqlx_cursor";
+
+/// A cursor over the results of a query that are fetched from Postgres in chunks
+/// through `DECLARE CURSOR` and `FETCH`.
+///
+/// Returned from [`Query::fetch_cursor`].
+///
+/// Postgres only keeps one chunk of rows in flight, regardless of how large the result of
+/// the query is. If the connection is not in a transaction, one is started for the lifetime
+/// of the cursor (a cursor can not exist outside of a transaction).
+///
+/// Dropping the cursor before all rows are fetched closes the cursor (and rolls back the
+/// transaction it started, if any) before the next query is run on the connection.
+pub struct PgServerCursor<'c, 'q> {
+    conn: &'c mut PgConnection,
+    query: Option<(&'q str, PgArguments)>,
+    chunk_size: u32,
+
+    // did we start the transaction the cursor lives in
+    owns_transaction: bool,
+
+    // is the cursor open on the server
+    open: bool,
+
+    // has the last chunk been fetched
+    done: bool,
+
+    statement: Arc<Statement>,
+
+    // the data rows of the current chunk and the index of the next row to return
+    rows: Vec<Vec<u8>>,
+    index: usize,
+}
+
+impl<'q> Query<'q, Postgres> {
+    /// Execute the query using a server-side cursor, fetching `chunk_size` rows at a time.
+    ///
+    /// This lets a result set of any size be iterated over without either Postgres or
+    /// the client materializing all of it.
+    ///
+    /// ```rust,ignore
+    /// let mut cursor = sqlx::query("SELECT * FROM events").fetch_cursor(&mut conn, 1000);
+    ///
+    /// while let Some(row) = cursor.next().await? {
+    ///     // ...
+    /// }
+    /// ```
+    pub fn fetch_cursor<'c>(
+        self,
+        conn: &'c mut PgConnection,
+        chunk_size: u32,
+    ) -> PgServerCursor<'c, 'q> {
+        PgServerCursor::new(conn, self, chunk_size)
+    }
+}
+
+impl<'q, O> QueryAs<'q, Postgres, O>
+where
+    O: Send + Unpin + for<'r> FromRow<'r, PgRow<'r>>,
+{
+    /// Execute the query using a server-side cursor, fetching `chunk_size` rows at a time.
+    ///
+    /// See [`Query::fetch_cursor`].
+    pub fn fetch_cursor<'c>(
+        self,
+        conn: &'c mut PgConnection,
+        chunk_size: u32,
+    ) -> BoxStream<'c, crate::Result<O>>
+    where
+        'q: 'c,
+        O: 'c,
+    {
+        let mut cursor = PgServerCursor::new(conn, self, chunk_size);
+
+        Box::pin(async_stream::try_stream! {
+            while let Some(row) = cursor.next().await? {
+                let obj = O::from_row(&row)?;
+
+                yield obj;
+            }
+        })
+    }
+}
+
+impl<'c, 'q> PgServerCursor<'c, 'q> {
+    fn new<E>(conn: &'c mut PgConnection, query: E, chunk_size: u32) -> Self
+    where
+        E: Execute<'q, Postgres>,
+    {
+        let (query, arguments) = query.into_parts();
+
+        Self {
+            conn,
+            query: Some((query, arguments.unwrap_or_default())),
+            chunk_size: chunk_size.max(1),
+            owns_transaction: false,
+            open: false,
+            done: false,
+            statement: Arc::default(),
+            rows: Vec::new(),
+            index: 0,
+        }
+    }
+
+    /// Fetch the next row, returning `None` once all rows have been fetched.
+    pub async fn next(&mut self) -> crate::Result<Option<PgRow<'_>>> {
+        if let Err(error) = self.advance().await {
+            // read the remainder of the failed response so the connection is idle for
+            // any cleanup queued on drop
+            self.conn.wait_until_ready().await?;
+
+            return Err(error);
+        }
+
+        if self.index == self.rows.len() {
+            return Ok(None);
+        }
+
+        let data = DataRow::read(&self.rows[self.index], &mut self.conn.current_row_values)?;
+
+        self.index += 1;
+
+        Ok(Some(PgRow {
+            statement: Arc::clone(&self.statement),
+            data,
+        }))
+    }
+
+    // Ensure that there is a row to return in our buffer, unless the cursor is exhausted
+    async fn advance(&mut self) -> crate::Result<()> {
+        if let Some((query, arguments)) = self.query.take() {
+            self.declare(query, arguments).await?;
+        }
+
+        if self.index < self.rows.len() {
+            return Ok(());
+        }
+
+        if self.open && !self.done {
+            self.fetch().await?;
+        }
+
+        if self.index == self.rows.len() {
+            self.close().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn declare(&mut self, query: &str, arguments: PgArguments) -> crate::Result<()> {
+        // make sure the transaction status is current
+        self.conn.wait_until_ready().await?;
+
+        if self.conn.stream.transaction_status == TransactionStatus::Idle {
+            self.conn.execute("BEGIN").await?;
+            self.owns_transaction = true;
+        }
+
+        let declare = format!(
+            "DECLARE {} BINARY NO SCROLL CURSOR FOR {}",
+            CURSOR_NAME, query
+        );
+
+        self.conn
+            .execute(crate::query::query(&declare).bind_all(arguments))
+            .await?;
+
+        self.open = true;
+
+        Ok(())
+    }
+
+    // Fetch the next chunk of rows into our buffer
+    // This reads all messages up to [ReadyForQuery] so the connection is idle between chunks
+    async fn fetch(&mut self) -> crate::Result<()> {
+        let fetch = format!("FETCH FORWARD {} FROM {}", self.chunk_size, CURSOR_NAME);
+
+        self.conn.run(&fetch, None, true).await?;
+
+        self.rows.clear();
+        self.index = 0;
+
+        let mut description = None;
+
+        loop {
+            match self.conn.stream.receive().await? {
+                Message::CommandComplete => {}
+
+                Message::RowDescription => {
+                    description = Some(RowDescription::read(self.conn.stream.buffer())?);
+                }
+
+                Message::DataRow => {
+                    self.rows.push(self.conn.stream.buffer().to_vec());
+                }
+
+                Message::ReadyForQuery => {
+                    self.conn.is_ready = true;
+                    break;
+                }
+
+                message => {
+                    return Err(
+                        protocol_err!("fetch_cursor: unexpected message: {:?}", message).into(),
+                    );
+                }
+            }
+        }
+
+        self.done = (self.rows.len() as u32) < self.chunk_size;
+
+        // the columns are described by the first chunk
+        // this needs to wait until the connection is idle as it may need to query postgres
+        if let Some(description) = description {
+            if self.statement.columns.is_empty() {
+                self.statement = Arc::new(
+                    self.conn
+                        .parse_row_description(description, Default::default(), None, true)
+                        .await?,
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn close(&mut self) -> crate::Result<()> {
+        if self.owns_transaction {
+            self.owns_transaction = false;
+            self.open = false;
+
+            // closes the cursor along with the transaction
+            self.conn.execute("COMMIT").await?;
+        } else if self.open {
+            self.open = false;
+
+            self.conn
+                .execute(&*format!("CLOSE {}", CURSOR_NAME))
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for PgServerCursor<'_, '_> {
+    fn drop(&mut self) {
+        if self.open || self.owns_transaction {
+            // the connection is always idle between chunks; queue up a query to clean up that
+            // will be flushed, and its result discarded, before the next query is run
+            let cleanup = if self.owns_transaction {
+                "ROLLBACK".to_owned()
+            } else {
+                format!("CLOSE {}", CURSOR_NAME)
+            };
+
+            self.conn.queue_query(&cleanup);
+        }
+    }
+}
@@ -0,0 +1,503 @@
+use std::fs;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use byteorder::{BigEndian, ByteOrder};
+
+use crate::runtime::{timeout, AsyncReadExt, AsyncWriteExt, TcpStream, UdpSocket};
+use crate::url::Url;
+
+// How long to wait for the answer of a nameserver before trying the next one
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+// The size of the UDP responses accepted, advertised with EDNS0 so that a long list of
+// records is not truncated to 512 bytes; a response that is truncated anyway is asked
+// for again over TCP
+const MAX_RESPONSE_SIZE: u16 = 4096;
+
+const TYPE_SRV: u16 = 33;
+const TYPE_OPT: u16 = 41;
+const CLASS_IN: u16 = 1;
+
+// A record of the service, as in RFC 2782
+#[derive(Debug, Clone, PartialEq)]
+struct Record {
+    priority: u16,
+    weight: u16,
+    port: u16,
+    target: String,
+}
+
+// Returns `true` if the hosts of the URL are the names of SRV records, from the `srv`
+// parameter
+pub(super) fn is_enabled(url: &Url) -> crate::Result<bool> {
+    match url.param("srv").as_deref() {
+        Some("true") => Ok(true),
+        Some("false") | None => Ok(false),
+
+        Some(value) => Err(protocol_err!("unknown `srv` value: {:?}", value).into()),
+    }
+}
+
+// Look up the SRV records of each of the names, returning the hosts and ports of the
+// servers in the order they should be tried
+pub(super) async fn resolve(names: Vec<(String, u16)>) -> crate::Result<Vec<(String, u16)>> {
+    let nameservers = nameservers();
+    let mut hosts = Vec::new();
+
+    for (name, _) in names {
+        let records = lookup(&nameservers, &name).await?;
+
+        hosts.extend(
+            order(records)
+                .into_iter()
+                .map(|record| (record.target, record.port)),
+        );
+    }
+
+    if hosts.is_empty() {
+        return Err(protocol_err!("no servers found in the SRV records").into());
+    }
+
+    Ok(hosts)
+}
+
+async fn lookup(nameservers: &[SocketAddr], name: &str) -> crate::Result<Vec<Record>> {
+    let id = rand::random();
+    let query = encode_query(id, name)?;
+
+    let mut error = None;
+
+    for nameserver in nameservers {
+        match timeout(TIMEOUT, exchange(*nameserver, &query)).await {
+            Ok(Ok(response)) => return decode_response(id, name, &response),
+            Ok(Err(e)) => error = Some(e.into()),
+            Err(_) => error = Some(io::Error::from(io::ErrorKind::TimedOut).into()),
+        }
+    }
+
+    Err(error.expect("(bug) no nameservers to query"))
+}
+
+async fn exchange(nameserver: SocketAddr, query: &[u8]) -> io::Result<Vec<u8>> {
+    let response = exchange_udp(nameserver, query).await?;
+
+    if is_truncated(&response) {
+        return exchange_tcp(nameserver, query).await;
+    }
+
+    Ok(response)
+}
+
+async fn exchange_udp(nameserver: SocketAddr, query: &[u8]) -> io::Result<Vec<u8>> {
+    let local: SocketAddr = if nameserver.is_ipv4() {
+        ([0, 0, 0, 0], 0).into()
+    } else {
+        ([0; 16], 0).into()
+    };
+
+    #[cfg_attr(feature = "runtime-async-std", allow(unused_mut))]
+    let mut socket = UdpSocket::bind(local).await?;
+
+    socket.connect(nameserver).await?;
+    socket.send(query).await?;
+
+    let mut response = vec![0; MAX_RESPONSE_SIZE as usize];
+    let len = socket.recv(&mut response).await?;
+
+    response.truncate(len);
+
+    Ok(response)
+}
+
+// https://tools.ietf.org/html/rfc1035#section-4.2.2
+async fn exchange_tcp(nameserver: SocketAddr, query: &[u8]) -> io::Result<Vec<u8>> {
+    let mut stream = TcpStream::connect(nameserver).await?;
+
+    // over TCP, a message is prefixed with its length
+    let mut message = Vec::with_capacity(query.len() + 2);
+    message.extend_from_slice(&(query.len() as u16).to_be_bytes());
+    message.extend_from_slice(query);
+
+    stream.write_all(&message).await?;
+
+    let mut len = [0; 2];
+    stream.read_exact(&mut len).await?;
+
+    let mut response = vec![0; u16::from_be_bytes(len) as usize];
+    stream.read_exact(&mut response).await?;
+
+    Ok(response)
+}
+
+// Returns `true` if the response has the TC bit set, as it did not fit in a UDP message
+fn is_truncated(response: &[u8]) -> bool {
+    response.len() >= 4 && BigEndian::read_u16(&response[2..]) & 0x0200 != 0
+}
+
+// The nameservers of `/etc/resolv.conf`, or of the local host if there are none
+fn nameservers() -> Vec<SocketAddr> {
+    let nameservers: Vec<_> = fs::read_to_string("/etc/resolv.conf")
+        .map(|conf| parse_resolv_conf(&conf))
+        .unwrap_or_default();
+
+    if nameservers.is_empty() {
+        vec![SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 53)]
+    } else {
+        nameservers
+    }
+}
+
+fn parse_resolv_conf(conf: &str) -> Vec<SocketAddr> {
+    conf.lines()
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+
+            if words.next()? != "nameserver" {
+                return None;
+            }
+
+            // an IPv6 address may have a zone (`fe80::1%eth0`), which is left out
+            let address = words.next()?.split('%').next()?;
+
+            Some(SocketAddr::new(address.parse().ok()?, 53))
+        })
+        .collect()
+}
+
+// https://tools.ietf.org/html/rfc1035#section-4.1
+fn encode_query(id: u16, name: &str) -> crate::Result<Vec<u8>> {
+    let mut query = Vec::with_capacity(name.len() + 29);
+
+    query.extend_from_slice(&id.to_be_bytes());
+
+    // a standard query, with recursion desired
+    query.extend_from_slice(&0x0100_u16.to_be_bytes());
+
+    // 1 question and 1 additional record, the EDNS0 pseudo-record
+    query.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 1]);
+
+    for label in name.trim_end_matches('.').split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(protocol_err!("invalid SRV record name: {:?}", name).into());
+        }
+
+        query.push(label.len() as u8);
+        query.extend_from_slice(label.as_bytes());
+    }
+
+    query.push(0);
+    query.extend_from_slice(&TYPE_SRV.to_be_bytes());
+    query.extend_from_slice(&CLASS_IN.to_be_bytes());
+
+    // https://tools.ietf.org/html/rfc6891#section-6.1.2
+    query.push(0);
+    query.extend_from_slice(&TYPE_OPT.to_be_bytes());
+    query.extend_from_slice(&MAX_RESPONSE_SIZE.to_be_bytes());
+    query.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+
+    Ok(query)
+}
+
+fn decode_response(id: u16, name: &str, response: &[u8]) -> crate::Result<Vec<Record>> {
+    if is_truncated(response) {
+        return Err(protocol_err!("truncated DNS response for {:?}", name).into());
+    }
+
+    match decode_records(id, response) {
+        Some(Ok(records)) => Ok(records),
+
+        Some(Err(3)) => Err(protocol_err!("no SRV records found for {:?}", name).into()),
+
+        Some(Err(rcode)) => Err(protocol_err!(
+            "failed to look up the SRV records of {:?} (DNS response code {})",
+            name,
+            rcode
+        )
+        .into()),
+
+        None => Err(protocol_err!("invalid DNS response for {:?}", name).into()),
+    }
+}
+
+// Decode the SRV records of a response, or the response code of an error
+fn decode_records(id: u16, response: &[u8]) -> Option<Result<Vec<Record>, u16>> {
+    if response.len() < 12 || BigEndian::read_u16(response) != id {
+        return None;
+    }
+
+    let flags = BigEndian::read_u16(&response[2..]);
+
+    // not a response
+    if flags & 0x8000 == 0 {
+        return None;
+    }
+
+    if flags & 0x000F != 0 {
+        return Some(Err(flags & 0x000F));
+    }
+
+    let questions = BigEndian::read_u16(&response[4..]);
+    let answers = BigEndian::read_u16(&response[6..]);
+    let mut pos = 12;
+
+    for _ in 0..questions {
+        pos = read_name(response, pos)?.1 + 4;
+    }
+
+    let mut records = Vec::new();
+
+    for _ in 0..answers {
+        pos = read_name(response, pos)?.1;
+
+        let header = response.get(pos..pos + 10)?;
+        let type_ = BigEndian::read_u16(header);
+        let len = BigEndian::read_u16(&header[8..]) as usize;
+
+        pos += 10;
+
+        let data = response.get(pos..pos + len)?;
+
+        // a response may also have the CNAME records the name is an alias through
+        if type_ == TYPE_SRV && len >= 7 {
+            let target = read_name(response, pos + 6)?.0;
+
+            // a target of `.` means that the service is not available
+            if !target.is_empty() {
+                records.push(Record {
+                    priority: BigEndian::read_u16(data),
+                    weight: BigEndian::read_u16(&data[2..]),
+                    port: BigEndian::read_u16(&data[4..]),
+                    target,
+                });
+            }
+        }
+
+        pos += len;
+    }
+
+    Some(Ok(records))
+}
+
+// Read a domain name, which may end with a pointer to a name earlier in the message;
+// returns the name and the position after it
+fn read_name(message: &[u8], mut pos: usize) -> Option<(String, usize)> {
+    let mut name = String::new();
+    let mut end = None;
+
+    // each pointer must point backwards, so following them always ends
+    let mut limit = pos;
+
+    loop {
+        let len = *message.get(pos)? as usize;
+
+        if len & 0xC0 == 0xC0 {
+            let pointer = (BigEndian::read_u16(message.get(pos..pos + 2)?) & 0x3FFF) as usize;
+
+            if pointer >= limit {
+                return None;
+            }
+
+            end.get_or_insert(pos + 2);
+            limit = pointer;
+            pos = pointer;
+
+            continue;
+        }
+
+        if len == 0 {
+            return Some((name, end.unwrap_or(pos + 1)));
+        }
+
+        let label = message.get(pos + 1..pos + 1 + len)?;
+
+        if !name.is_empty() {
+            name.push('.');
+        }
+
+        name.push_str(std::str::from_utf8(label).ok()?);
+        pos += 1 + len;
+    }
+}
+
+// Order the records as in RFC 2782: by priority, and at random by weight within the same
+// priority, so that connections are spread over the servers in proportion to their weight
+fn order(mut records: Vec<Record>) -> Vec<Record> {
+    records.sort_by_key(|record| (record.priority, record.weight != 0));
+
+    let mut ordered = Vec::with_capacity(records.len());
+
+    while !records.is_empty() {
+        let priority = records[0].priority;
+        let same = records
+            .iter()
+            .take_while(|record| record.priority == priority)
+            .count();
+
+        let total: u32 = records[..same]
+            .iter()
+            .map(|record| u32::from(record.weight))
+            .sum();
+
+        let chosen = rand::random::<u32>() % (total + 1);
+        let mut sum = 0;
+
+        let index = records[..same]
+            .iter()
+            .position(|record| {
+                sum += u32::from(record.weight);
+                sum >= chosen
+            })
+            .unwrap_or(0);
+
+        ordered.push(records.remove(index));
+    }
+
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a response to the query of `_pg._tcp.db.local`, from `encode_query`, with a record
+    // whose target is compressed to point to the question, and one that is not available
+    fn response() -> Vec<u8> {
+        let mut response = encode_query(0x1234, "_pg._tcp.db.local").unwrap();
+
+        // a response, with 3 answers and no additional records
+        response[2..4].copy_from_slice(&0x8180_u16.to_be_bytes());
+        response[6..8].copy_from_slice(&3_u16.to_be_bytes());
+        response[10..12].copy_from_slice(&0_u16.to_be_bytes());
+        response.truncate(response.len() - 11);
+
+        for (priority, weight, port, target) in &[
+            (10_u16, 5_u16, 5432_u16, &b"\x07primary\xC0\x15"[..]),
+            (20, 0, 5433, b"\x07replica\x02db\x05local\x00"),
+            (30, 0, 5434, b"\x00"),
+        ] {
+            // the name of the record points to the question
+            response.extend_from_slice(&[0xC0, 12]);
+            response.extend_from_slice(&TYPE_SRV.to_be_bytes());
+            response.extend_from_slice(&CLASS_IN.to_be_bytes());
+            response.extend_from_slice(&60_u32.to_be_bytes());
+            response.extend_from_slice(&(6 + target.len() as u16).to_be_bytes());
+            response.extend_from_slice(&priority.to_be_bytes());
+            response.extend_from_slice(&weight.to_be_bytes());
+            response.extend_from_slice(&port.to_be_bytes());
+            response.extend_from_slice(target);
+        }
+
+        response
+    }
+
+    #[test]
+    fn it_encodes_queries() {
+        let query = encode_query(0x1234, "_pg._tcp.db.local.").unwrap();
+
+        assert_eq!(
+            &query[..12],
+            b"\x12\x34\x01\x00\x00\x01\x00\x00\x00\x00\x00\x01"
+        );
+        assert_eq!(&query[12..31], b"\x03_pg\x04_tcp\x02db\x05local\x00");
+        assert_eq!(&query[31..35], b"\x00\x21\x00\x01");
+        assert_eq!(
+            &query[35..],
+            b"\x00\x00\x29\x10\x00\x00\x00\x00\x00\x00\x00"
+        );
+
+        assert!(encode_query(0, "a..b").is_err());
+    }
+
+    #[test]
+    fn it_decodes_responses() {
+        let response = response();
+
+        assert_eq!(
+            decode_records(0x1234, &response),
+            Some(Ok(vec![
+                Record {
+                    priority: 10,
+                    weight: 5,
+                    port: 5432,
+                    target: "primary.db.local".into(),
+                },
+                Record {
+                    priority: 20,
+                    weight: 0,
+                    port: 5433,
+                    target: "replica.db.local".into(),
+                },
+            ]))
+        );
+
+        // a response to another query
+        assert_eq!(decode_records(0x4321, &response), None);
+
+        // a response truncated in the middle of a record
+        assert_eq!(
+            decode_records(0x1234, &response[..response.len() - 10]),
+            None
+        );
+
+        let mut not_found = response.clone();
+        not_found[3] = 0x83;
+
+        assert!(decode_response(0x1234, "_pg._tcp.db.local", &not_found)
+            .unwrap_err()
+            .to_string()
+            .contains("no SRV records found"));
+
+        // the TC bit is set on a response that did not fit in a UDP message
+        let mut truncated = response.clone();
+        truncated[2] |= 0x02;
+
+        assert!(!is_truncated(&response));
+        assert!(is_truncated(&truncated));
+
+        assert!(decode_response(0x1234, "_pg._tcp.db.local", &truncated)
+            .unwrap_err()
+            .to_string()
+            .contains("truncated DNS response"));
+    }
+
+    #[test]
+    fn it_orders_records_by_priority_and_weight() {
+        let record = |priority, weight, target: &str| Record {
+            priority,
+            weight,
+            port: 5432,
+            target: target.into(),
+        };
+
+        for _ in 0..10 {
+            let ordered = order(vec![
+                record(20, 0, "c"),
+                record(10, 1, "a"),
+                record(10, 0, "b"),
+                record(30, 100, "d"),
+            ]);
+
+            let mut targets: Vec<_> = ordered.iter().map(|r| &*r.target).collect();
+
+            // `a` and `b` have the same priority, so either may come first
+            targets[..2].sort();
+
+            assert_eq!(targets, ["a", "b", "c", "d"]);
+        }
+    }
+
+    #[test]
+    fn it_parses_resolv_conf() {
+        assert_eq!(
+            parse_resolv_conf(
+                "# comment\nsearch local\nnameserver 10.0.0.1\nnameserver fe80::1%eth0\nnameserver x\n"
+            ),
+            vec![
+                "10.0.0.1:53".parse().unwrap(),
+                "[fe80::1]:53".parse().unwrap()
+            ]
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::postgres::protocol::{self, StatementId};
+use crate::postgres::PgConnection;
+
+/// Statistics of the prepared statement cache of a [PgConnection], from
+/// [`PgConnection::statement_cache_stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PgStatementCacheStats {
+    /// The number of queries that used a statement already prepared on the connection.
+    pub hits: u64,
+
+    /// The number of queries that had to be prepared.
+    pub misses: u64,
+
+    /// The number of statements closed to keep the cache within its capacity and TTL, or by
+    /// [`PgConnection::clear_cached_statements`].
+    pub evictions: u64,
+
+    /// The number of statements currently prepared on the connection.
+    pub prepared: usize,
+}
+
+#[derive(Debug, Default)]
+pub(super) struct StatementCache {
+    capacity: Option<usize>,
+    ttl: Option<Duration>,
+
+    // statement ID -> when it was last used
+    last_used: HashMap<StatementId, Instant>,
+
+    stats: PgStatementCacheStats,
+}
+
+impl PgConnection {
+    /// Set the maximum number of prepared statements kept on this connection.
+    ///
+    /// When running a query would exceed it, the least recently used statements are closed.
+    /// Defaults to `None`, which keeps every statement until the connection is closed.
+    pub fn set_statement_cache_capacity(&mut self, capacity: impl Into<Option<usize>>) {
+        // the statement of the query being run is always kept
+        self.statement_cache.capacity = capacity.into().map(|capacity| capacity.max(1));
+    }
+
+    /// Set how long a prepared statement is kept on this connection without being used.
+    ///
+    /// Defaults to `None`, which keeps every statement until the connection is closed.
+    pub fn set_statement_cache_ttl(&mut self, ttl: impl Into<Option<Duration>>) {
+        self.statement_cache.ttl = ttl.into();
+    }
+
+    /// Close every statement prepared on this connection.
+    ///
+    /// The statements are closed on the server along with the next query.
+    pub fn clear_cached_statements(&mut self) {
+        let ids = self.statement_cache.last_used.keys().copied().collect();
+
+        self.close_statements(ids);
+    }
+
+    /// Returns the statistics of the prepared statement cache of this connection.
+    pub fn statement_cache_stats(&self) -> PgStatementCacheStats {
+        PgStatementCacheStats {
+            prepared: self.statement_cache.last_used.len(),
+            ..self.statement_cache.stats
+        }
+    }
+
+    // Record the use of a statement, which was just prepared if `hit` is false
+    pub(super) fn touch_statement(&mut self, id: StatementId, hit: bool) {
+        let cache = &mut self.statement_cache;
+
+        if hit {
+            cache.stats.hits += 1;
+        } else {
+            cache.stats.misses += 1;
+        }
+
+        cache.last_used.insert(id, Instant::now());
+    }
+
+    // Close the statements that are past the TTL or over the capacity of the cache, least
+    // recently used first
+    pub(super) fn evict_statements(&mut self) {
+        let cache = &self.statement_cache;
+
+        let mut used: Vec<_> = cache.last_used.iter().map(|(id, at)| (*at, *id)).collect();
+        used.sort_unstable_by_key(|(at, _)| *at);
+
+        let over_capacity = cache
+            .capacity
+            .map_or(0, |capacity| used.len().saturating_sub(capacity));
+
+        let ids: Vec<_> = used
+            .iter()
+            .enumerate()
+            .filter(|(index, (at, _))| {
+                *index < over_capacity || matches!(cache.ttl, Some(ttl) if at.elapsed() > ttl)
+            })
+            .map(|(_, (_, id))| *id)
+            .collect();
+
+        self.close_statements(ids);
+    }
+
+    // Forget the statements and queue closing them, to be sent before the next query is run;
+    // the result is discarded
+    fn close_statements(&mut self, ids: Vec<StatementId>) {
+        if ids.is_empty() {
+            return;
+        }
+
+        for id in &ids {
+            self.cache_statement.remove(id);
+            self.statement_cache.last_used.remove(id);
+        }
+
+        self.cache_statement_id.retain(|_, id| !ids.contains(id));
+        self.statement_cache.stats.evictions += ids.len() as u64;
+
+        self.queue_close_statements(&ids);
+    }
+
+    // Queue closing statements that are not (or no longer) in the cache, to be sent before
+    // the next query is run; the result is discarded
+    pub(super) fn queue_close_statements(&mut self, ids: &[StatementId]) {
+        if ids.is_empty() {
+            return;
+        }
+
+        for id in ids {
+            self.stream.write(protocol::Close::Statement(*id));
+        }
+
+        self.write_sync();
+
+        if !self.is_ready {
+            self.pending_ready_for_query += 1;
+        }
+
+        self.is_ready = false;
+    }
+}
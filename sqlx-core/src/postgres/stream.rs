@@ -1,54 +1,144 @@
+use std::borrow::Cow;
 use std::convert::TryInto;
 use std::net::Shutdown;
+#[cfg(unix)]
+use std::path::Path;
+use std::sync::{Arc, Mutex};
 
 use byteorder::NetworkEndian;
-use futures_channel::mpsc::UnboundedSender;
 
 use crate::io::{Buf, BufStream, MaybeTlsStream};
-use crate::postgres::protocol::{Message, NotificationResponse, Response, Write};
-use crate::postgres::PgError;
+use crate::postgres::listen::NotificationBuffer;
+use crate::postgres::notice::NoticeHandler;
+use crate::postgres::protocol::{
+    Message, NotificationResponse, ReadyForQuery, Response, TransactionStatus, Write,
+};
+use crate::postgres::{PgError, PgNotice};
 
 use crate::url::Url;
-use futures_util::SinkExt;
 
 pub struct PgStream {
     pub(super) stream: BufStream<MaybeTlsStream>,
-    pub(super) notifications: Option<UnboundedSender<NotificationResponse<'static>>>,
+    pub(super) notifications: Option<Arc<Mutex<NotificationBuffer>>>,
 
     // Most recently received message
     // Is referenced by our buffered stream
     // Is initialized to ReadyForQuery/0 at the start
     pub(super) message: (Message, u32),
+
+    // Transaction status reported by the most recent ReadyForQuery
+    pub(super) transaction_status: TransactionStatus,
+
+    // Called with non-error responses (notices and warnings)
+    pub(super) notice_handler: Option<NoticeHandler>,
 }
 
-impl PgStream {
-    pub(super) async fn new(url: &Url) -> crate::Result<Self> {
-        let host = url.host();
-        let port = url.port(5432);
-        #[cfg(unix)]
-        let stream = {
-            let host = host
-                .map(|host| {
-                    percent_encoding::percent_decode_str(host)
-                        .decode_utf8()
-                        .expect("percent-encoded hostname contained non-UTF-8 bytes")
-                })
-                .or_else(|| url.param("host"))
-                .unwrap_or("/var/run/postgresql".into());
-            if host.starts_with("/") {
-                let path = format!("{}/.s.PGSQL.{}", host, port);
-                MaybeTlsStream::connect_uds(&path).await?
+// Connect to a Unix domain socket in the first of these directories that has one for the port
+// if no host is given; where the socket is depends on how Postgres was built
+#[cfg(unix)]
+const DEFAULT_SOCKET_DIRS: &[&str] = &["/var/run/postgresql", "/tmp"];
+
+#[cfg(not(unix))]
+const DEFAULT_HOST: &str = "localhost";
+
+// The hosts to try to connect to, in order, as in libpq: the host of the URL (or the `host`
+// parameter) may be a comma-separated list, and the port of the URL (or the `port` parameter)
+// is either a single port for every host or a comma-separated list with one port for each
+pub(super) fn hosts(url: &Url) -> crate::Result<Vec<(String, u16)>> {
+    let hosts = url
+        .host()
+        .map(|host| {
+            percent_encoding::percent_decode_str(host)
+                .decode_utf8_lossy()
+                .into_owned()
+        })
+        .or_else(|| url.param("host").map(Cow::into_owned))
+        .unwrap_or_default();
+
+    let hosts: Vec<&str> = hosts.split(',').collect();
+
+    let ports = match url.param("port") {
+        Some(ports) => ports
+            .split(',')
+            .map(|port| {
+                if port.is_empty() {
+                    Ok(5432)
+                } else {
+                    port.parse()
+                        .map_err(|_| protocol_err!("invalid port: {:?}", port).into())
+                }
+            })
+            .collect::<crate::Result<Vec<u16>>>()?,
+
+        None => vec![url.port(5432)],
+    };
+
+    if ports.len() != 1 && ports.len() != hosts.len() {
+        return Err(protocol_err!(
+            "could not match {} port numbers to {} hosts",
+            ports.len(),
+            hosts.len()
+        )
+        .into());
+    }
+
+    Ok(hosts
+        .into_iter()
+        .enumerate()
+        .map(|(i, host)| {
+            let port = ports[i.min(ports.len() - 1)];
+
+            if host.is_empty() {
+                (default_host(port).to_owned(), port)
             } else {
-                MaybeTlsStream::connect(&host, port).await?
+                (host.to_owned(), port)
             }
+        })
+        .collect())
+}
+
+#[cfg(unix)]
+fn default_host(port: u16) -> &'static str {
+    DEFAULT_SOCKET_DIRS
+        .iter()
+        .find(|dir| Path::new(&socket_path(dir, port)).exists())
+        .unwrap_or(&DEFAULT_SOCKET_DIRS[0])
+}
+
+#[cfg(not(unix))]
+fn default_host(_port: u16) -> &'static str {
+    DEFAULT_HOST
+}
+
+// A host that is an absolute path is the directory of a Unix domain socket, which is
+// named after the port, as in libpq
+pub(super) fn is_socket_dir(host: &str) -> bool {
+    cfg!(unix) && host.starts_with('/')
+}
+
+#[cfg(unix)]
+fn socket_path(dir: &str, port: u16) -> String {
+    format!("{}/.s.PGSQL.{}", dir.trim_end_matches('/'), port)
+}
+
+impl PgStream {
+    pub(super) async fn new(host: &str, port: u16) -> crate::Result<Self> {
+        #[cfg(unix)]
+        let stream = if is_socket_dir(host) {
+            MaybeTlsStream::connect_uds(&socket_path(host, port)).await?
+        } else {
+            MaybeTlsStream::connect(host, port).await?
         };
+
         #[cfg(not(unix))]
-        let stream = MaybeTlsStream::connect(host.unwrap_or("localhost"), port).await?;
+        let stream = MaybeTlsStream::connect(host, port).await?;
 
         Ok(Self {
             notifications: None,
             stream: BufStream::new(stream),
             message: (Message::ReadyForQuery, 0),
+            transaction_status: TransactionStatus::Idle,
+            notice_handler: None,
         })
     }
 
@@ -110,20 +200,32 @@ impl PgStream {
                         return Err(crate::Error::Database(Box::new(PgError(response))));
                     }
 
-                    // TODO: Provide some way of receiving these non-critical
-                    //       notices from postgres
+                    if let Some(handler) = &self.notice_handler {
+                        handler(PgNotice(response));
+                    }
+
                     continue;
                 }
 
                 Message::NotificationResponse => {
-                    if let Some(buffer) = &mut self.notifications {
+                    if let Some(buffer) = &self.notifications {
                         let notification = NotificationResponse::read(self.stream.buffer())?;
 
-                        let _ = buffer.send(notification.into_owned()).await;
+                        buffer.lock().unwrap().push(notification.into_owned());
                         continue;
                     }
                 }
 
+                Message::ParameterStatus => {
+                    // the server reports changed parameters at any time, e.g. after a `SET`;
+                    // we do not track their values
+                    continue;
+                }
+
+                Message::ReadyForQuery => {
+                    self.transaction_status = ReadyForQuery::read(self.stream.buffer())?.status;
+                }
+
                 _ => {}
             }
 
@@ -140,3 +242,79 @@ impl PgStream {
         &self.stream.buffer()[..(self.message.1 as usize)]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::hosts;
+    use crate::url::Url;
+
+    fn parse(url: &str) -> crate::Result<Vec<(String, u16)>> {
+        hosts(&Url::try_from(url)?)
+    }
+
+    #[test]
+    fn it_parses_multiple_hosts() {
+        assert_eq!(
+            parse("postgres://localhost/db").unwrap(),
+            vec![("localhost".to_owned(), 5432)]
+        );
+
+        assert_eq!(
+            parse("postgres://host1,host2:5433/db").unwrap(),
+            vec![("host1".to_owned(), 5432), ("host2".to_owned(), 5433)]
+        );
+
+        assert_eq!(
+            parse("postgres://host1:5433,host2/db").unwrap(),
+            vec![("host1".to_owned(), 5433), ("host2".to_owned(), 5432)]
+        );
+
+        assert_eq!(
+            parse("postgres:///db?host=host1,host2&port=1,2").unwrap(),
+            vec![("host1".to_owned(), 1), ("host2".to_owned(), 2)]
+        );
+
+        assert_eq!(
+            parse("postgres:///db?host=host1,host2&port=5433").unwrap(),
+            vec![("host1".to_owned(), 5433), ("host2".to_owned(), 5433)]
+        );
+
+        assert!(parse("postgres://host1,host2/db?port=1,2,3").is_err());
+        assert!(parse("postgres://host1/db?port=x").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn it_parses_socket_dirs() {
+        assert_eq!(
+            parse("postgres:///db?host=/var/run/postgresql").unwrap(),
+            vec![("/var/run/postgresql".to_owned(), 5432)]
+        );
+
+        assert_eq!(
+            parse("postgres://user@/db?host=/tmp&port=5433").unwrap(),
+            vec![("/tmp".to_owned(), 5433)]
+        );
+
+        assert_eq!(
+            parse("postgres://user:password@%2Fvar%2Frun%2Fpostgresql/db").unwrap(),
+            vec![("/var/run/postgresql".to_owned(), 5432)]
+        );
+
+        assert_eq!(
+            parse("postgres:///db?host=/tmp,localhost").unwrap(),
+            vec![("/tmp".to_owned(), 5432), ("localhost".to_owned(), 5432)]
+        );
+
+        // a socket in one of the default directories is connected to if no host is given
+        let hosts = parse("postgres:///db").unwrap();
+
+        assert!(super::DEFAULT_SOCKET_DIRS.contains(&&*hosts[0].0));
+        assert!(super::is_socket_dir(&hosts[0].0));
+        assert!(!super::is_socket_dir("localhost"));
+
+        assert_eq!(super::socket_path("/tmp/", 5433), "/tmp/.s.PGSQL.5433");
+    }
+}
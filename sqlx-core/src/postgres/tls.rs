@@ -1,8 +1,17 @@
-use crate::postgres::stream::PgStream;
+use crate::postgres::stream::{is_socket_dir, PgStream};
 use crate::url::Url;
 
 #[cfg_attr(not(feature = "tls"), allow(unused_variables))]
-pub(crate) async fn request_if_needed(stream: &mut PgStream, url: &Url) -> crate::Result<()> {
+pub(crate) async fn request_if_needed(
+    stream: &mut PgStream,
+    url: &Url,
+    host: &str,
+) -> crate::Result<()> {
+    // As in libpq, TLS is not used over a Unix domain socket, which is local anyway
+    if is_socket_dir(host) {
+        return Ok(());
+    }
+
     // https://www.postgresql.org/docs/12/libpq-ssl.html#LIBPQ-SSL-SSLMODE-STATEMENTS
     match url.param("sslmode").as_deref() {
         Some("disable") | Some("allow") => {
@@ -12,7 +21,7 @@ pub(crate) async fn request_if_needed(stream: &mut PgStream, url: &Url) -> crate
         #[cfg(feature = "tls")]
         Some("prefer") | None => {
             // We default to [prefer] if TLS is compiled in
-            if !try_upgrade(stream, url, true, true).await? {
+            if !try_upgrade(stream, url, host, true, true).await? {
                 // TLS upgrade failed; fall back to a normal connection
             }
         }
@@ -30,6 +39,7 @@ pub(crate) async fn request_if_needed(stream: &mut PgStream, url: &Url) -> crate
             if !try_upgrade(
                 stream,
                 url,
+                host,
                 // false for both verify-ca and verify-full
                 mode == "require",
                 // false for only verify-full
@@ -65,11 +75,11 @@ pub(crate) async fn request_if_needed(stream: &mut PgStream, url: &Url) -> crate
 async fn try_upgrade(
     stream: &mut PgStream,
     url: &Url,
+    host: &str,
     accept_invalid_certs: bool,
     accept_invalid_host_names: bool,
 ) -> crate::Result<bool> {
     use async_native_tls::TlsConnector;
-    use std::borrow::Cow;
 
     stream.write(crate::postgres::protocol::SslRequest);
     stream.flush().await?;
@@ -106,12 +116,7 @@ async fn try_upgrade(
         }
     }
 
-    let host = url
-        .host()
-        .map(Cow::Borrowed)
-        .or_else(|| url.param("host"))
-        .unwrap_or("localhost".into());
-    stream.stream.upgrade(&host, connector).await?;
+    stream.stream.upgrade(host, connector).await?;
 
     Ok(true)
 }
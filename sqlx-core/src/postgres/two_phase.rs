@@ -0,0 +1,58 @@
+use crate::executor::Executor;
+use crate::postgres::PgConnection;
+
+impl PgConnection {
+    /// Commit a transaction that was prepared for two-phase commit with
+    /// [`Transaction::prepare_twophase`][crate::Transaction::prepare_twophase].
+    ///
+    /// The transaction does not need to have been prepared on this connection, or even
+    /// in this session. This can not be called from inside of a transaction.
+    pub async fn commit_prepared(&mut self, gid: &str) -> crate::Result<()> {
+        self.execute(&*format!("COMMIT PREPARED {}", quote_gid(gid)?))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Roll back a transaction that was prepared for two-phase commit with
+    /// [`Transaction::prepare_twophase`][crate::Transaction::prepare_twophase].
+    ///
+    /// The transaction does not need to have been prepared on this connection, or even
+    /// in this session. This can not be called from inside of a transaction.
+    pub async fn rollback_prepared(&mut self, gid: &str) -> crate::Result<()> {
+        self.execute(&*format!("ROLLBACK PREPARED {}", quote_gid(gid)?))
+            .await?;
+
+        Ok(())
+    }
+}
+
+// Quote a global transaction identifier as an escape string literal, which is read the same
+// whatever `standard_conforming_strings` is set to
+pub(crate) fn quote_gid(gid: &str) -> crate::Result<String> {
+    if gid.contains('\0') {
+        return Err(protocol_err!(
+            "the global transaction identifier {:?} contains a NUL character",
+            gid
+        )
+        .into());
+    }
+
+    Ok(format!(
+        "E'{}'",
+        gid.replace('\\', "\\\\").replace('\'', "''")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::quote_gid;
+
+    #[test]
+    fn it_quotes_gid() {
+        assert_eq!(quote_gid("tx-1").unwrap(), "E'tx-1'");
+        assert_eq!(quote_gid("it's").unwrap(), "E'it''s'");
+        assert_eq!(quote_gid("a\\'b").unwrap(), "E'a\\\\''b'");
+        assert!(quote_gid("a\0b").is_err());
+    }
+}
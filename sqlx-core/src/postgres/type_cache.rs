@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::pool::Builder;
+use crate::postgres::type_info::SharedStr;
+use crate::postgres::PgConnection;
+
+/// A cache of the OIDs of the types that connections resolve at runtime, e.g. the enums and
+/// composites of `#[derive(sqlx::Type)]`.
+///
+/// Each connection looks up these types with a catalog query the first time it uses them. A
+/// cache exported from one connection can be preloaded into new connections, or shared by
+/// them all, to skip those queries; a pool can share one with every connection it opens:
+///
+/// ```rust,ignore
+/// let cache = PgTypeCache::new();
+///
+/// let pool = PgPool::builder()
+///     .share_type_cache(cache)
+///     .build(&url)
+///     .await?;
+/// ```
+///
+/// OIDs differ between databases, so a cache must only be used with connections to the same
+/// database. A type that is dropped and created again gets a new OID; connections using a
+/// cache from before then must be reconnected with a new cache.
+///
+/// Clones refer to the same cache.
+#[derive(Debug, Clone, Default)]
+pub struct PgTypeCache(Arc<Mutex<TypeCache>>);
+
+#[derive(Debug, Default)]
+struct TypeCache {
+    // type name -> type OID
+    oids: HashMap<SharedStr, u32>,
+
+    // type OID -> type name
+    names: HashMap<u32, SharedStr>,
+
+    // domain type OID -> base type OID
+    bases: HashMap<u32, u32>,
+}
+
+impl PgTypeCache {
+    /// Create an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a type by name and OID, e.g. from a list saved earlier with [`types`].
+    ///
+    /// [`types`]: #method.types
+    pub fn insert(&self, name: impl Into<String>, oid: u32) {
+        let name = SharedStr::from(name.into());
+        let mut cache = self.lock();
+
+        cache.oids.insert(name.clone(), oid);
+        cache.names.insert(oid, name);
+    }
+
+    /// The names and OIDs of the types in the cache.
+    pub fn types(&self) -> Vec<(String, u32)> {
+        self.lock()
+            .oids
+            .iter()
+            .map(|(name, oid)| ((**name).to_owned(), *oid))
+            .collect()
+    }
+
+    /// The number of types in the cache.
+    pub fn len(&self) -> usize {
+        self.lock().oids.len()
+    }
+
+    /// Returns `true` if there are no types in the cache.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, TypeCache> {
+        // the maps are always left consistent, so a panic elsewhere does not poison them
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl PgConnection {
+    /// Returns a new [PgTypeCache] with the types resolved on this connection so far.
+    pub fn type_cache(&self) -> PgTypeCache {
+        let cache = PgTypeCache::new();
+
+        {
+            let mut inner = cache.lock();
+
+            inner.oids = self.cache_type_oid.clone();
+            inner.names = self.cache_type_name.clone();
+            inner.bases = self.cache_type_base.clone();
+        }
+
+        cache
+    }
+
+    /// Add the types in `cache` to the types resolved on this connection.
+    pub fn preload_type_cache(&mut self, cache: &PgTypeCache) {
+        let inner = cache.lock();
+
+        self.cache_type_oid
+            .extend(inner.oids.iter().map(|(name, oid)| (name.clone(), *oid)));
+
+        self.cache_type_name
+            .extend(inner.names.iter().map(|(oid, name)| (*oid, name.clone())));
+
+        self.cache_type_base.extend(inner.bases.iter());
+    }
+
+    /// Share `cache` with this connection: its types are preloaded, types added to it later
+    /// by other connections are used, and the types this connection resolves are added to it.
+    pub fn share_type_cache(&mut self, cache: PgTypeCache) {
+        self.preload_type_cache(&cache);
+        self.type_cache = Some(cache);
+    }
+
+    // Look up a type name in the shared cache, if any
+    pub(super) fn shared_type_oid(&mut self, name: &str) -> Option<u32> {
+        let (name, oid) = {
+            let cache = self.type_cache.as_ref()?.lock();
+            let (name, oid) = cache.oids.get_key_value(name)?;
+
+            (name.clone(), *oid)
+        };
+
+        self.cache_type_oid.insert(name.clone(), oid);
+        self.cache_type_name.insert(oid, name);
+
+        Some(oid)
+    }
+
+    // Look up a type OID in the shared cache, if any
+    pub(super) fn shared_type_name(&mut self, oid: u32) -> bool {
+        let (name, base) = match &self.type_cache {
+            Some(cache) => {
+                let cache = cache.lock();
+
+                (
+                    cache.names.get(&oid).cloned(),
+                    cache.bases.get(&oid).copied(),
+                )
+            }
+
+            None => return false,
+        };
+
+        if let Some(base) = base {
+            self.cache_type_base.insert(oid, base);
+        } else if let Some(name) = name {
+            self.cache_type_oid.insert(name.clone(), oid);
+            self.cache_type_name.insert(oid, name);
+        } else {
+            return false;
+        }
+
+        true
+    }
+
+    // Remember a type resolved on this connection
+    pub(super) fn cache_type(&mut self, name: SharedStr, oid: u32) {
+        if let Some(cache) = &self.type_cache {
+            let mut cache = cache.lock();
+
+            cache.oids.insert(name.clone(), oid);
+            cache.names.insert(oid, name.clone());
+        }
+
+        self.cache_type_oid.insert(name.clone(), oid);
+        self.cache_type_name.insert(oid, name);
+    }
+
+    // Remember the base type of a domain resolved on this connection
+    pub(super) fn cache_domain(&mut self, oid: u32, base: u32) {
+        if let Some(cache) = &self.type_cache {
+            cache.lock().bases.insert(oid, base);
+        }
+
+        self.cache_type_base.insert(oid, base);
+    }
+}
+
+impl Builder<PgConnection> {
+    /// Share `cache` with every connection the pool opens, as with
+    /// [`PgConnection::share_type_cache`].
+    ///
+    /// [`PgConnection::share_type_cache`]: ../postgres/struct.PgConnection.html#method.share_type_cache
+    pub fn share_type_cache(self, cache: PgTypeCache) -> Self {
+        self.on_connect(move |conn| conn.share_type_cache(cache.clone()))
+    }
+}
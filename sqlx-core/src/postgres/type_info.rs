@@ -36,6 +36,10 @@ impl PgTypeInfo {
 
     #[doc(hidden)]
     pub fn type_feature_gate(&self) -> Option<&'static str> {
+        if self.is_postgis() {
+            return Some("postgis");
+        }
+
         match self.id? {
             TypeId::DATE | TypeId::TIME | TypeId::TIMESTAMP | TypeId::TIMESTAMPTZ => Some("chrono"),
             TypeId::UUID => Some("uuid"),
@@ -47,6 +51,14 @@ impl PgTypeInfo {
             _ => None,
         }
     }
+
+    fn is_citext(&self) -> bool {
+        self.name.eq_ignore_ascii_case("citext")
+    }
+
+    fn is_postgis(&self) -> bool {
+        self.name.eq_ignore_ascii_case("geometry") || self.name.eq_ignore_ascii_case("geography")
+    }
 }
 
 impl Display for PgTypeInfo {
@@ -65,6 +77,27 @@ impl PartialEq<PgTypeInfo> for PgTypeInfo {
 
 impl TypeInfo for PgTypeInfo {
     fn compatible(&self, other: &Self) -> bool {
+        // `citext` is an extension type with an OID that is only known at runtime; it is
+        // compatible with the other text-like types
+        if self.is_citext() || other.is_citext() {
+            let other = if self.is_citext() { other } else { self };
+
+            return other.is_citext()
+                || matches!(
+                    other.id,
+                    Some(TypeId::VARCHAR)
+                        | Some(TypeId::TEXT)
+                        | Some(TypeId::BPCHAR)
+                        | Some(TypeId::NAME)
+                        | Some(TypeId::UNKNOWN)
+                );
+        }
+
+        // PostGIS `geography` values are sent and received in the same format as `geometry`
+        if self.is_postgis() && other.is_postgis() {
+            return true;
+        }
+
         if let (Some(self_id), Some(other_id)) = (self.id, other.id) {
             return match (self_id, other_id) {
                 (TypeId::CIDR, TypeId::INET)
@@ -4,9 +4,38 @@ use crate::database::Database;
 use crate::decode::Decode;
 use crate::encode::Encode;
 use crate::postgres::types::raw::{PgArrayDecoder, PgArrayEncoder};
-use crate::postgres::{PgRawBuffer, PgValue, Postgres};
+use crate::postgres::{PgRawBuffer, PgTypeInfo, PgValue, Postgres};
 use crate::types::Type;
 
+/// Provides the array type of a user-defined type, so that `Vec<T>` and `&[T]` can be used
+/// for arrays of it.
+///
+/// This is implemented by `#[derive(sqlx::Type)]` for composite types and enumerations, naming
+/// the array type `_` followed by the name of the type, as Postgres does.
+pub trait PgHasArrayType {
+    fn array_type_info() -> PgTypeInfo;
+}
+
+impl<T> Type<Postgres> for [T]
+where
+    T: PgHasArrayType,
+{
+    #[inline]
+    fn type_info() -> PgTypeInfo {
+        T::array_type_info()
+    }
+}
+
+impl<T> Type<Postgres> for Vec<T>
+where
+    T: PgHasArrayType,
+{
+    #[inline]
+    fn type_info() -> PgTypeInfo {
+        T::array_type_info()
+    }
+}
+
 impl<T> Encode<Postgres> for [T]
 where
     T: Encode<Postgres>,
@@ -0,0 +1,263 @@
+use std::collections::{BTreeMap, HashMap};
+use std::ops::{Deref, DerefMut};
+
+use byteorder::NetworkEndian;
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::io::{Buf, BufMut};
+use crate::postgres::{PgData, PgRawBuffer, PgTypeInfo, PgValue, Postgres};
+use crate::types::Type;
+
+// <https://www.postgresql.org/docs/12/hstore.html>
+
+/// A set of key/value pairs stored in a single value of the
+/// [`hstore`](https://www.postgresql.org/docs/12/hstore.html) extension type.
+///
+/// Keys are unique strings; values are strings or `NULL`.
+///
+/// `hstore` is not a built-in type and its OID differs between databases; it is looked up
+/// (and cached on the connection) the first time a value of this type is bound.
+/// `HashMap<String, Option<String>>` can be used interchangeably.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PgHstore(pub BTreeMap<String, Option<String>>);
+
+impl Deref for PgHstore {
+    type Target = BTreeMap<String, Option<String>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for PgHstore {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<BTreeMap<String, Option<String>>> for PgHstore {
+    fn from(map: BTreeMap<String, Option<String>>) -> Self {
+        Self(map)
+    }
+}
+
+impl std::iter::FromIterator<(String, Option<String>)> for PgHstore {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (String, Option<String>)>,
+    {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl Type<Postgres> for PgHstore {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("hstore")
+    }
+}
+
+impl Type<Postgres> for HashMap<String, Option<String>> {
+    fn type_info() -> PgTypeInfo {
+        <PgHstore as Type<Postgres>>::type_info()
+    }
+}
+
+impl Encode<Postgres> for PgHstore {
+    fn encode(&self, buf: &mut PgRawBuffer) {
+        encode_pairs(self.0.iter(), self.0.len(), buf);
+    }
+}
+
+impl Encode<Postgres> for HashMap<String, Option<String>> {
+    fn encode(&self, buf: &mut PgRawBuffer) {
+        encode_pairs(self.iter(), self.len(), buf);
+    }
+}
+
+impl<'de> Decode<'de, Postgres> for PgHstore {
+    fn decode(value: PgValue<'de>) -> crate::Result<Self> {
+        let mut map = PgHstore::default();
+
+        match value.try_get()? {
+            PgData::Binary(buf) => decode_binary(buf, |key, value| {
+                map.insert(key, value);
+            })?,
+
+            PgData::Text(s) => decode_text(s, |key, value| {
+                map.insert(key, value);
+            })?,
+        }
+
+        Ok(map)
+    }
+}
+
+impl<'de> Decode<'de, Postgres> for HashMap<String, Option<String>> {
+    fn decode(value: PgValue<'de>) -> crate::Result<Self> {
+        let mut map = HashMap::new();
+
+        match value.try_get()? {
+            PgData::Binary(buf) => decode_binary(buf, |key, value| {
+                map.insert(key, value);
+            })?,
+
+            PgData::Text(s) => decode_text(s, |key, value| {
+                map.insert(key, value);
+            })?,
+        }
+
+        Ok(map)
+    }
+}
+
+// The binary format is the number of pairs followed by each key and value, prefixed with
+// their length (a length of -1 is a `NULL` value)
+fn encode_pairs<'a>(
+    pairs: impl Iterator<Item = (&'a String, &'a Option<String>)>,
+    len: usize,
+    buf: &mut PgRawBuffer,
+) {
+    buf.put_i32::<NetworkEndian>(len as i32);
+
+    for (key, value) in pairs {
+        buf.put_i32::<NetworkEndian>(key.len() as i32);
+        buf.extend_from_slice(key.as_bytes());
+
+        match value {
+            Some(value) => {
+                buf.put_i32::<NetworkEndian>(value.len() as i32);
+                buf.extend_from_slice(value.as_bytes());
+            }
+
+            None => {
+                buf.put_i32::<NetworkEndian>(-1);
+            }
+        }
+    }
+}
+
+fn decode_binary(
+    mut buf: &[u8],
+    mut insert: impl FnMut(String, Option<String>),
+) -> crate::Result<()> {
+    let len = buf.get_i32::<NetworkEndian>()?;
+
+    for _ in 0..len {
+        let key_len = buf.get_i32::<NetworkEndian>()?;
+
+        if key_len < 0 {
+            return Err(decode_err!("hstore: unexpected NULL key"));
+        }
+
+        let key = buf.get_str(key_len as usize)?.to_owned();
+
+        let value = match buf.get_i32::<NetworkEndian>()? {
+            -1 => None,
+            len => Some(buf.get_str(len as usize)?.to_owned()),
+        };
+
+        insert(key, value);
+    }
+
+    Ok(())
+}
+
+// The text format is a comma-separated list of `"key"=>"value"` or `"key"=>NULL`, with
+// any `"` or `\` inside of the quotes escaped with a backslash
+fn decode_text(s: &str, mut insert: impl FnMut(String, Option<String>)) -> crate::Result<()> {
+    let mut s = s.trim_start();
+
+    while !s.is_empty() {
+        let (key, rest) =
+            read_quoted(s).ok_or_else(|| decode_err!("hstore: expected a quoted key: {:?}", s))?;
+
+        let rest = rest
+            .trim_start()
+            .strip_prefix("=>")
+            .ok_or_else(|| decode_err!("hstore: expected `=>` after key {:?}", key))?
+            .trim_start();
+
+        let (value, rest) = if rest.len() >= 4 && rest[..4].eq_ignore_ascii_case("NULL") {
+            (None, &rest[4..])
+        } else {
+            let (value, rest) = read_quoted(rest)
+                .ok_or_else(|| decode_err!("hstore: expected a value for key {:?}", key))?;
+
+            (Some(value), rest)
+        };
+
+        insert(key, value);
+
+        s = rest.trim_start();
+
+        if !s.is_empty() {
+            s = s
+                .strip_prefix(',')
+                .ok_or_else(|| decode_err!("hstore: expected `,` between pairs: {:?}", s))?
+                .trim_start();
+        }
+    }
+
+    Ok(())
+}
+
+// Read a double-quoted string and return it, unescaped, and the remaining input
+fn read_quoted(s: &str) -> Option<(String, &str)> {
+    let s = s.strip_prefix('"')?;
+    let mut value = String::new();
+    let mut chars = s.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some((value, &s[i + 1..])),
+            '\\' => value.push(chars.next()?.1),
+            c => value.push(c),
+        }
+    }
+
+    None
+}
+
+#[test]
+fn test_decode_hstore_text() {
+    let map = PgHstore::decode(PgValue::from_str(
+        r#""a"=>"1", "b"=>NULL, "with \"quotes\""=>"back\\slash","c"=>"=>, ""#,
+    ))
+    .unwrap();
+
+    assert_eq!(map.len(), 4);
+    assert_eq!(map["a"], Some("1".to_owned()));
+    assert_eq!(map["b"], None);
+    assert_eq!(map["with \"quotes\""], Some("back\\slash".to_owned()));
+    assert_eq!(map["c"], Some("=>, ".to_owned()));
+
+    assert!(PgHstore::decode(PgValue::from_str("")).unwrap().is_empty());
+    assert!(PgHstore::decode(PgValue::from_str(r#""a"=>"1" "b"=>"2""#)).is_err());
+    assert!(PgHstore::decode(PgValue::from_str(r#""a"=>"1"#)).is_err());
+}
+
+#[test]
+fn test_encode_decode_hstore_binary() {
+    let map: PgHstore = vec![
+        ("a".to_owned(), Some("1".to_owned())),
+        ("b".to_owned(), None),
+    ]
+    .into_iter()
+    .collect();
+
+    let mut buf = PgRawBuffer::default();
+    Encode::<Postgres>::encode(&map, &mut buf);
+
+    assert_eq!(
+        &**buf,
+        b"\0\0\0\x02\0\0\0\x01a\0\0\0\x011\0\0\0\x01b\xff\xff\xff\xff"
+    );
+
+    let decoded: HashMap<String, Option<String>> =
+        Decode::<Postgres>::decode(PgValue::from_bytes(&buf)).unwrap();
+
+    assert_eq!(decoded.len(), 2);
+    assert_eq!(decoded["a"], Some("1".to_owned()));
+    assert_eq!(decoded["b"], None);
+}
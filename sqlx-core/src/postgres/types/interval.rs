@@ -0,0 +1,411 @@
+use std::convert::TryFrom;
+
+use byteorder::NetworkEndian;
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::io::{Buf, BufMut};
+use crate::postgres::protocol::TypeId;
+use crate::postgres::{PgData, PgRawBuffer, PgTypeInfo, PgValue, Postgres};
+use crate::types::Type;
+
+// <https://www.postgresql.org/docs/12/datatype-datetime.html#DATATYPE-INTERVAL-INPUT>
+
+const MICROSECONDS_PER_DAY: i64 = 24 * 60 * 60 * 1_000_000;
+
+/// A value of the `interval` type.
+///
+/// Postgres keeps the months, days and time of an interval separately, as the length of a
+/// month or a day (across a daylight saving time change) is only known when the interval is
+/// added to a date.
+///
+/// An interval can be converted to and from `std::time::Duration`, `chrono::Duration` and
+/// `time::Duration` if it has no months; a day is then taken to be 24 hours long.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PgInterval {
+    pub months: i32,
+    pub days: i32,
+    pub microseconds: i64,
+}
+
+impl PgInterval {
+    // The length of the interval in microseconds, if it has no months
+    fn duration_microseconds(&self) -> crate::Result<i64> {
+        if self.months != 0 {
+            return Err(decode_err!(
+                "interval: cannot convert an interval with months to a duration"
+            ));
+        }
+
+        (self.days as i64)
+            .checked_mul(MICROSECONDS_PER_DAY)
+            .and_then(|days| days.checked_add(self.microseconds))
+            .ok_or_else(|| decode_err!("interval: duration out of range"))
+    }
+}
+
+impl Type<Postgres> for PgInterval {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::INTERVAL, "INTERVAL")
+    }
+}
+
+impl Type<Postgres> for [PgInterval] {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::ARRAY_INTERVAL, "INTERVAL[]")
+    }
+}
+
+impl Type<Postgres> for Vec<PgInterval> {
+    fn type_info() -> PgTypeInfo {
+        <[PgInterval] as Type<Postgres>>::type_info()
+    }
+}
+
+impl Encode<Postgres> for PgInterval {
+    fn encode(&self, buf: &mut PgRawBuffer) {
+        buf.extend_from_slice(&self.microseconds.to_be_bytes());
+        buf.put_i32::<NetworkEndian>(self.days);
+        buf.put_i32::<NetworkEndian>(self.months);
+    }
+
+    fn size_hint(&self) -> usize {
+        16
+    }
+}
+
+impl<'de> Decode<'de, Postgres> for PgInterval {
+    fn decode(value: PgValue<'de>) -> crate::Result<Self> {
+        match value.try_get()? {
+            PgData::Binary(mut buf) => {
+                let microseconds = buf.get_i64::<NetworkEndian>()?;
+                let days = buf.get_i32::<NetworkEndian>()?;
+                let months = buf.get_i32::<NetworkEndian>()?;
+
+                Ok(PgInterval {
+                    months,
+                    days,
+                    microseconds,
+                })
+            }
+
+            PgData::Text(s) => {
+                let s = s.trim();
+
+                match s.strip_prefix('P') {
+                    Some(s) => parse_iso_8601(s),
+                    None => parse_postgres(s),
+                }
+                .ok_or_else(|| decode_err!("interval: unsupported format: {:?}", s))
+            }
+        }
+    }
+}
+
+// Parse an interval in the default `postgres` output style: `1 year -2 mons 3 days -04:05:06.5`
+fn parse_postgres(s: &str) -> Option<PgInterval> {
+    let mut interval = PgInterval::default();
+    let mut tokens = s.split_whitespace();
+
+    while let Some(token) = tokens.next() {
+        if token.contains(':') {
+            interval.microseconds = parse_time(token)?;
+            continue;
+        }
+
+        let value: i32 = token.parse().ok()?;
+
+        match tokens.next()? {
+            "year" | "years" => {
+                interval.months = value.checked_mul(12)?.checked_add(interval.months)?
+            }
+
+            "mon" | "mons" => interval.months = interval.months.checked_add(value)?,
+            "day" | "days" => interval.days = value,
+
+            _ => return None,
+        }
+    }
+
+    Some(interval)
+}
+
+// Parse the time of an interval: `[-]HH:MM:SS[.ffffff]`
+fn parse_time(s: &str) -> Option<i64> {
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(s) => (true, s),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    };
+
+    let mut parts = s.splitn(3, ':');
+
+    let hours: i64 = parts.next()?.parse().ok()?;
+    let minutes: i64 = parts.next()?.parse().ok()?;
+    let seconds = parse_seconds(parts.next()?)?;
+
+    let microseconds = hours
+        .checked_mul(60 * 60 * 1_000_000)?
+        .checked_add(minutes.checked_mul(60 * 1_000_000)?)?
+        .checked_add(seconds)?;
+
+    Some(if negative {
+        -microseconds
+    } else {
+        microseconds
+    })
+}
+
+// Parse (possibly fractional) seconds to microseconds
+fn parse_seconds(s: &str) -> Option<i64> {
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(s) => (true, s),
+        None => (false, s),
+    };
+
+    let (whole, fraction) = match s.find('.') {
+        Some(index) => (&s[..index], &s[index + 1..]),
+        None => (s, ""),
+    };
+
+    if fraction.len() > 6 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let whole: i64 = whole.parse().ok()?;
+    let fraction: i64 = format!("{:0<6}", fraction).parse().ok()?;
+
+    let microseconds = whole.checked_mul(1_000_000)?.checked_add(fraction)?;
+
+    Some(if negative {
+        -microseconds
+    } else {
+        microseconds
+    })
+}
+
+// Parse an interval in the `iso_8601` output style, without the leading `P`:
+// `1Y-2M3DT-4H5M6.5S`
+fn parse_iso_8601(s: &str) -> Option<PgInterval> {
+    let mut interval = PgInterval::default();
+    let mut time = false;
+    let mut s = s;
+
+    while !s.is_empty() {
+        if let Some(rest) = s.strip_prefix('T') {
+            time = true;
+            s = rest;
+            continue;
+        }
+
+        let end = s.find(|c: char| c.is_ascii_alphabetic())?;
+        let (value, unit) = (&s[..end], &s[end..=end]);
+
+        s = &s[end + 1..];
+
+        match (time, unit) {
+            (false, "Y") => {
+                let years: i32 = value.parse().ok()?;
+                interval.months = years.checked_mul(12)?.checked_add(interval.months)?;
+            }
+
+            (false, "M") => interval.months = interval.months.checked_add(value.parse().ok()?)?,
+            (false, "D") => interval.days = value.parse().ok()?,
+
+            (true, unit) => {
+                let microseconds = match unit {
+                    "H" => value
+                        .parse::<i64>()
+                        .ok()?
+                        .checked_mul(60 * 60 * 1_000_000)?,
+                    "M" => value.parse::<i64>().ok()?.checked_mul(60 * 1_000_000)?,
+                    "S" => parse_seconds(value)?,
+
+                    _ => return None,
+                };
+
+                interval.microseconds = interval.microseconds.checked_add(microseconds)?;
+            }
+
+            _ => return None,
+        }
+    }
+
+    Some(interval)
+}
+
+impl TryFrom<PgInterval> for std::time::Duration {
+    type Error = crate::Error;
+
+    /// Fails if the interval has months or is negative.
+    fn try_from(interval: PgInterval) -> crate::Result<Self> {
+        let microseconds = interval.duration_microseconds()?;
+
+        if microseconds < 0 {
+            return Err(decode_err!(
+                "interval: cannot convert a negative interval to a `std::time::Duration`"
+            ));
+        }
+
+        Ok(std::time::Duration::from_micros(microseconds as u64))
+    }
+}
+
+impl TryFrom<std::time::Duration> for PgInterval {
+    type Error = crate::Error;
+
+    /// Fails if the duration does not fit in an interval; any nanoseconds are truncated.
+    fn try_from(duration: std::time::Duration) -> crate::Result<Self> {
+        let microseconds = i64::try_from(duration.as_micros())
+            .map_err(|_| decode_err!("interval: duration out of range"))?;
+
+        Ok(PgInterval {
+            months: 0,
+            days: 0,
+            microseconds,
+        })
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<PgInterval> for chrono::Duration {
+    type Error = crate::Error;
+
+    /// Fails if the interval has months.
+    fn try_from(interval: PgInterval) -> crate::Result<Self> {
+        Ok(chrono::Duration::microseconds(
+            interval.duration_microseconds()?,
+        ))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<chrono::Duration> for PgInterval {
+    type Error = crate::Error;
+
+    /// Fails if the duration does not fit in an interval; any nanoseconds are truncated.
+    fn try_from(duration: chrono::Duration) -> crate::Result<Self> {
+        let microseconds = duration
+            .num_microseconds()
+            .ok_or_else(|| decode_err!("interval: duration out of range"))?;
+
+        Ok(PgInterval {
+            months: 0,
+            days: 0,
+            microseconds,
+        })
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<PgInterval> for time::Duration {
+    type Error = crate::Error;
+
+    /// Fails if the interval has months.
+    fn try_from(interval: PgInterval) -> crate::Result<Self> {
+        Ok(time::Duration::microseconds(
+            interval.duration_microseconds()?,
+        ))
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<time::Duration> for PgInterval {
+    type Error = crate::Error;
+
+    /// Fails if the duration does not fit in an interval; any nanoseconds are truncated.
+    fn try_from(duration: time::Duration) -> crate::Result<Self> {
+        let microseconds = i64::try_from(duration.whole_microseconds())
+            .map_err(|_| decode_err!("interval: duration out of range"))?;
+
+        Ok(PgInterval {
+            months: 0,
+            days: 0,
+            microseconds,
+        })
+    }
+}
+
+#[test]
+fn test_decode_interval_text() {
+    let interval = |months, days, microseconds| PgInterval {
+        months,
+        days,
+        microseconds,
+    };
+
+    for (text, expected) in &[
+        ("00:00:00", interval(0, 0, 0)),
+        (
+            "1 year 2 mons 3 days 04:05:06.789",
+            interval(14, 3, 14_706_789_000),
+        ),
+        ("-1 years +2 mons -3 days", interval(-10, -3, 0)),
+        ("1 day -00:00:00.5", interval(0, 1, -500_000)),
+        ("P1Y2M3DT4H5M6.789S", interval(14, 3, 14_706_789_000)),
+        ("P-1Y2M-3DT-4H-5M-6.5S", interval(-10, -3, -14_706_500_000)),
+        ("PT0S", interval(0, 0, 0)),
+    ] {
+        assert_eq!(
+            PgInterval::decode(PgValue::from_str(text)).unwrap(),
+            *expected,
+            "{}",
+            text
+        );
+    }
+
+    assert!(PgInterval::decode(PgValue::from_str("1 fortnight")).is_err());
+    assert!(PgInterval::decode(PgValue::from_str("@ 1 day ago")).is_err());
+}
+
+#[test]
+fn test_encode_decode_interval_binary() {
+    let interval = PgInterval {
+        months: 14,
+        days: -3,
+        microseconds: 1_500_000,
+    };
+
+    let mut buf = PgRawBuffer::default();
+    Encode::<Postgres>::encode(&interval, &mut buf);
+
+    assert_eq!(&**buf, b"\0\0\0\0\0\x16\xe3\x60\xff\xff\xff\xfd\0\0\0\x0e");
+
+    assert_eq!(
+        PgInterval::decode(PgValue::from_bytes(&buf)).unwrap(),
+        interval
+    );
+}
+
+#[test]
+fn test_interval_to_duration() {
+    use std::convert::TryInto;
+    use std::time::Duration;
+
+    let interval = PgInterval {
+        months: 0,
+        days: 1,
+        microseconds: 1_500_000,
+    };
+
+    let duration: Duration = interval.try_into().unwrap();
+    assert_eq!(duration, Duration::from_micros(86_401_500_000));
+
+    let interval: PgInterval = Duration::from_nanos(1_500_001_999).try_into().unwrap();
+    assert_eq!(interval.microseconds, 1_500_001);
+
+    let with_months = PgInterval {
+        months: 1,
+        days: 0,
+        microseconds: 0,
+    };
+
+    assert!(Duration::try_from(with_months).is_err());
+
+    let negative = PgInterval {
+        months: 0,
+        days: 0,
+        microseconds: -1,
+    };
+
+    assert!(Duration::try_from(negative).is_err());
+}
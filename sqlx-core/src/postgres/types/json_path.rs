@@ -0,0 +1,121 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::io::{Buf, BufMut};
+use crate::postgres::protocol::TypeId;
+use crate::postgres::{PgData, PgRawBuffer, PgTypeInfo, PgValue, Postgres};
+use crate::types::Type;
+
+// <https://www.postgresql.org/docs/12/datatype-json.html#DATATYPE-JSONPATH>
+
+// The version of the binary format of `jsonpath` (as of Postgres 12)
+const VERSION: u8 = 1;
+
+/// A value of the `jsonpath` type; an SQL/JSON path expression for querying `jsonb` values,
+/// e.g. `$.items[*] ? (@.price > 10)`.
+///
+/// The expression is kept in its text form and is validated by Postgres when bound.
+/// Postgres returns paths in a normalized form (e.g. `$."items"[*]?(@."price" > 10)`), which
+/// may differ from the text they were created from.
+///
+/// ```rust,ignore
+/// let names: Vec<(serde_json::Value,)> =
+///     sqlx::query_as("SELECT jsonb_path_query(data, $1) FROM products")
+///         .bind(PgJsonPath::new("$.name"))
+///         .fetch_all(&mut conn)
+///         .await?;
+/// ```
+///
+/// Requires Postgres 12 or later.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PgJsonPath(String);
+
+impl PgJsonPath {
+    /// Create a path expression from its text form.
+    pub fn new(path: impl Into<String>) -> Self {
+        Self(path.into())
+    }
+
+    /// Returns the text form of the path expression.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for PgJsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for PgJsonPath {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        Ok(Self::new(s))
+    }
+}
+
+impl Type<Postgres> for PgJsonPath {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::JSONPATH, "JSONPATH")
+    }
+}
+
+impl Type<Postgres> for [PgJsonPath] {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::ARRAY_JSONPATH, "JSONPATH[]")
+    }
+}
+
+impl Type<Postgres> for Vec<PgJsonPath> {
+    fn type_info() -> PgTypeInfo {
+        <[PgJsonPath] as Type<Postgres>>::type_info()
+    }
+}
+
+impl Encode<Postgres> for PgJsonPath {
+    fn encode(&self, buf: &mut PgRawBuffer) {
+        buf.put_u8(VERSION);
+        buf.extend_from_slice(self.0.as_bytes());
+    }
+}
+
+impl<'de> Decode<'de, Postgres> for PgJsonPath {
+    fn decode(value: PgValue<'de>) -> crate::Result<Self> {
+        match value.try_get()? {
+            // the binary format is a version byte followed by the text format
+            PgData::Binary(mut buf) => {
+                let version = buf.get_u8()?;
+
+                if version != VERSION {
+                    return Err(decode_err!(
+                        "unsupported jsonpath binary format version: {}",
+                        version
+                    ));
+                }
+
+                std::str::from_utf8(buf)
+                    .map(PgJsonPath::new)
+                    .map_err(crate::Error::decode)
+            }
+
+            PgData::Text(s) => Ok(PgJsonPath::new(s)),
+        }
+    }
+}
+
+#[test]
+fn test_encode_decode_json_path_binary() {
+    let path = PgJsonPath::new("$.a[*] ? (@ > 1)");
+
+    let mut buf = PgRawBuffer::default();
+    Encode::<Postgres>::encode(&path, &mut buf);
+
+    assert_eq!(&**buf, b"\x01$.a[*] ? (@ > 1)");
+    assert_eq!(PgJsonPath::decode(PgValue::from_bytes(&buf)).unwrap(), path);
+
+    assert!(PgJsonPath::decode(PgValue::from_bytes(b"\x02$")).is_err());
+}
@@ -0,0 +1,122 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use byteorder::{NetworkEndian, ReadBytesExt};
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::postgres::protocol::TypeId;
+use crate::postgres::{PgData, PgRawBuffer, PgTypeInfo, PgValue, Postgres};
+use crate::types::Type;
+use crate::Error;
+
+// <https://www.postgresql.org/docs/12/datatype-pg-lsn.html>
+
+/// A value of the `pg_lsn` type; a position in the write-ahead log.
+///
+/// Formatted as Postgres does, as two hexadecimal numbers of up to 8 digits each separated by
+/// a slash (e.g. `16/B374D848`). The difference of two positions is the number of bytes of
+/// WAL between them.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PgLsn(pub u64);
+
+impl Display for PgLsn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:X}/{:X}", self.0 >> 32, self.0 as u32)
+    }
+}
+
+impl FromStr for PgLsn {
+    type Err = Error;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        let parse = |s: &str| {
+            if s.is_empty() || s.len() > 8 {
+                return None;
+            }
+
+            u32::from_str_radix(s, 16).ok()
+        };
+
+        let mut parts = s.splitn(2, '/');
+
+        parts
+            .next()
+            .and_then(parse)
+            .zip(parts.next().and_then(parse))
+            .map(|(high, low)| PgLsn((high as u64) << 32 | low as u64))
+            .ok_or_else(|| decode_err!("pg_lsn: invalid value: {:?}", s))
+    }
+}
+
+impl From<u64> for PgLsn {
+    fn from(lsn: u64) -> Self {
+        PgLsn(lsn)
+    }
+}
+
+impl Type<Postgres> for PgLsn {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::PG_LSN, "PG_LSN")
+    }
+}
+
+impl Type<Postgres> for [PgLsn] {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::ARRAY_PG_LSN, "PG_LSN[]")
+    }
+}
+
+impl Type<Postgres> for Vec<PgLsn> {
+    fn type_info() -> PgTypeInfo {
+        <[PgLsn] as Type<Postgres>>::type_info()
+    }
+}
+
+impl Encode<Postgres> for PgLsn {
+    fn encode(&self, buf: &mut PgRawBuffer) {
+        buf.extend_from_slice(&self.0.to_be_bytes());
+    }
+}
+
+impl<'de> Decode<'de, Postgres> for PgLsn {
+    fn decode(value: PgValue<'de>) -> crate::Result<Self> {
+        match value.try_get()? {
+            PgData::Binary(mut buf) => buf
+                .read_u64::<NetworkEndian>()
+                .map(PgLsn)
+                .map_err(Error::decode),
+
+            PgData::Text(s) => s.parse(),
+        }
+    }
+}
+
+#[test]
+fn test_lsn_display_from_str() {
+    let lsn = PgLsn(0x16_B374_D848);
+
+    assert_eq!(lsn.to_string(), "16/B374D848");
+    assert_eq!("16/B374D848".parse::<PgLsn>().unwrap(), lsn);
+    assert_eq!("16/b374d848".parse::<PgLsn>().unwrap(), lsn);
+
+    assert_eq!(PgLsn(0).to_string(), "0/0");
+    assert_eq!(PgLsn(u64::MAX).to_string(), "FFFFFFFF/FFFFFFFF");
+
+    assert!("16".parse::<PgLsn>().is_err());
+    assert!("16/".parse::<PgLsn>().is_err());
+    assert!("1/100000000".parse::<PgLsn>().is_err());
+    assert!("1/-1".parse::<PgLsn>().is_err());
+}
+
+#[test]
+fn test_encode_decode_lsn_binary() {
+    let mut buf = PgRawBuffer::default();
+    Encode::<Postgres>::encode(&PgLsn(0x16_B374_D848), &mut buf);
+
+    assert_eq!(&**buf, b"\0\0\0\x16\xb3\x74\xd8\x48");
+    assert_eq!(
+        PgLsn::decode(PgValue::from_bytes(&buf)).unwrap(),
+        PgLsn(0x16_B374_D848)
+    );
+}
@@ -0,0 +1,232 @@
+use std::fmt::{self, Display};
+use std::ops::Deref;
+use std::str::FromStr;
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::io::{Buf, BufMut};
+use crate::postgres::{PgData, PgRawBuffer, PgTypeInfo, PgValue, Postgres};
+use crate::types::Type;
+
+// <https://www.postgresql.org/docs/12/ltree.html>
+
+// The version of the binary format of `ltree` and `lquery` (as of Postgres 13)
+const VERSION: u8 = 1;
+
+/// A label path stored in a value of the [`ltree`](https://www.postgresql.org/docs/12/ltree.html)
+/// extension type, e.g. `Top.Science.Astronomy`.
+///
+/// Each label must be non-empty and may only contain letters, digits, underscores and
+/// hyphens.
+///
+/// `ltree` is not a built-in type and its OID differs between databases; it is looked up
+/// (and cached on the connection) the first time a value of this type is bound.
+/// Binary transfer of `ltree` values requires Postgres 13 or later.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PgLtree {
+    labels: Vec<String>,
+}
+
+impl PgLtree {
+    /// Create an empty path.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a path from its labels.
+    pub fn from_labels<I, S>(labels: I) -> crate::Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut ltree = Self::new();
+
+        for label in labels {
+            ltree.push(label)?;
+        }
+
+        Ok(ltree)
+    }
+
+    /// Append a label to the end of the path.
+    pub fn push(&mut self, label: impl Into<String>) -> crate::Result<()> {
+        let label = label.into();
+
+        check_label(&label)?;
+
+        self.labels.push(label);
+
+        Ok(())
+    }
+
+    /// Remove the last label of the path and return it.
+    pub fn pop(&mut self) -> Option<String> {
+        self.labels.pop()
+    }
+
+    /// Returns the labels of the path.
+    pub fn into_labels(self) -> Vec<String> {
+        self.labels
+    }
+}
+
+impl Deref for PgLtree {
+    type Target = [String];
+
+    fn deref(&self) -> &Self::Target {
+        &self.labels
+    }
+}
+
+impl Display for PgLtree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.labels.join("."))
+    }
+}
+
+impl FromStr for PgLtree {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        if s.is_empty() {
+            return Ok(Self::new());
+        }
+
+        Self::from_labels(s.split('.'))
+    }
+}
+
+/// A pattern for matching [PgLtree] values, stored in a value of the `lquery` extension
+/// type, e.g. `*.Science.!Astronomy@`.
+///
+/// The pattern is kept in its text form and is validated by Postgres when used.
+///
+/// Binary transfer of `lquery` values requires Postgres 13 or later.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PgLquery(String);
+
+impl PgLquery {
+    /// Create a pattern from its text form.
+    pub fn new(pattern: impl Into<String>) -> Self {
+        Self(pattern.into())
+    }
+
+    /// Returns the text form of the pattern.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for PgLquery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for PgLquery {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        Ok(Self::new(s))
+    }
+}
+
+impl Type<Postgres> for PgLtree {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("ltree")
+    }
+}
+
+impl Type<Postgres> for PgLquery {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("lquery")
+    }
+}
+
+impl Encode<Postgres> for PgLtree {
+    fn encode(&self, buf: &mut PgRawBuffer) {
+        buf.put_u8(VERSION);
+        buf.extend_from_slice(self.to_string().as_bytes());
+    }
+}
+
+impl Encode<Postgres> for PgLquery {
+    fn encode(&self, buf: &mut PgRawBuffer) {
+        buf.put_u8(VERSION);
+        buf.extend_from_slice(self.0.as_bytes());
+    }
+}
+
+impl<'de> Decode<'de, Postgres> for PgLtree {
+    fn decode(value: PgValue<'de>) -> crate::Result<Self> {
+        decode_text(value)?.parse()
+    }
+}
+
+impl<'de> Decode<'de, Postgres> for PgLquery {
+    fn decode(value: PgValue<'de>) -> crate::Result<Self> {
+        decode_text(value).map(PgLquery::new)
+    }
+}
+
+// The binary format of both types is a version byte followed by the text format
+fn decode_text(value: PgValue<'_>) -> crate::Result<&str> {
+    match value.try_get()? {
+        PgData::Binary(mut buf) => {
+            let version = buf.get_u8()?;
+
+            if version != VERSION {
+                return Err(decode_err!(
+                    "unsupported ltree/lquery binary format version: {}",
+                    version
+                ));
+            }
+
+            std::str::from_utf8(buf).map_err(crate::Error::decode)
+        }
+
+        PgData::Text(s) => Ok(s),
+    }
+}
+
+fn check_label(label: &str) -> crate::Result<()> {
+    let valid = !label.is_empty()
+        && label
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(decode_err!("invalid ltree label: {:?}", label))
+    }
+}
+
+#[test]
+fn test_ltree_parse_and_display() {
+    let ltree: PgLtree = "Top.Science.Astronomy".parse().unwrap();
+
+    assert_eq!(&*ltree, &["Top", "Science", "Astronomy"]);
+    assert_eq!(ltree.to_string(), "Top.Science.Astronomy");
+
+    assert!("".parse::<PgLtree>().unwrap().is_empty());
+    assert!("Top..Science".parse::<PgLtree>().is_err());
+    assert!("Top.Sci ence".parse::<PgLtree>().is_err());
+    assert!(PgLtree::from_labels(vec!["a.b"]).is_err());
+}
+
+#[test]
+fn test_ltree_encode_decode_binary() {
+    let ltree = PgLtree::from_labels(vec!["Top", "Science"]).unwrap();
+
+    let mut buf = PgRawBuffer::default();
+    Encode::<Postgres>::encode(&ltree, &mut buf);
+
+    assert_eq!(&**buf, b"\x01Top.Science");
+
+    let decoded = PgLtree::decode(PgValue::from_bytes(&buf)).unwrap();
+
+    assert_eq!(decoded, ltree);
+
+    assert!(PgLtree::decode(PgValue::from_bytes(b"\x02Top")).is_err());
+}
@@ -10,8 +10,21 @@
 //! | `i64`                                 | BIGINT, BIGSERIAL, INT8                              |
 //! | `f32`                                 | REAL, FLOAT4                                         |
 //! | `f64`                                 | DOUBLE PRECISION, FLOAT8                             |
-//! | `&str`, `String`                      | VARCHAR, CHAR(N), TEXT, NAME                         |
+//! | `&str`, `String`                      | VARCHAR, CHAR(N), TEXT, NAME, CITEXT                 |
 //! | `&[u8]`, `Vec<u8>`                    | BYTEA                                                |
+//! | [`PgMoney`]                           | MONEY                                                |
+//! | [`PgInterval`]                        | INTERVAL                                             |
+//! | [`PgLsn`]                             | PG_LSN                                               |
+//! | [`PgXid8`]                            | XID8                                                 |
+//! | [`PgHstore`], `HashMap<String, Option<String>>` | HSTORE                                     |
+//! | [`PgLtree`]                           | LTREE                                                |
+//! | [`PgLquery`]                          | LQUERY                                               |
+//! | [`PgTsVector`]                        | TSVECTOR                                             |
+//! | [`PgTsQuery`]                         | TSQUERY                                              |
+//! | [`PgJsonPath`]                        | JSONPATH                                             |
+//!
+//! `&str` and `String` are sent to Postgres as `TEXT`; a parameter compared with a `CITEXT`
+//! column must be cast (`$1::citext`) for the comparison to be case-insensitive.
 //!
 //! ### [`chrono`](https://crates.io/crates/chrono)
 //!
@@ -52,6 +65,18 @@
 //! |---------------------------------------|------------------------------------------------------|
 //! | `ipnetwork::IpNetwork`                | INET, CIDR                                           |
 //!
+//! ### [`geo-types`](https://crates.io/crates/geo-types)
+//!
+//! Requires the `postgis` Cargo feature flag and the
+//! [PostGIS](https://postgis.net/) extension.
+//!
+//! | Rust type                             | Postgres type(s)                                     |
+//! |---------------------------------------|------------------------------------------------------|
+//! | `geo_types::Geometry<f64>`            | GEOMETRY, GEOGRAPHY                                  |
+//! | [`PgGeometry`]                        | GEOMETRY, GEOGRAPHY                                  |
+//!
+//! [`PgGeometry`] carries the SRID of the value along with the geometry.
+//!
 //! ### [`json`](https://crates.io/crates/serde_json)
 //!
 //! Requires the `json` Cargo feature flag.
@@ -98,6 +123,35 @@
 //!
 //! One-dimensional arrays are supported as `Vec<T>` or `&[T]` where `T` implements `Type`.
 //!
+//! Arrays of a user-defined type are supported if the type implements [`PgHasArrayType`],
+//! as composite types and enumerations using the derive for `Type` do; `Vec<InventoryItem>`
+//! maps to `inventory_item[]`.
+//!
+//! # [Ranges](https://www.postgresql.org/docs/current/rangetypes.html)
+//!
+//! Ranges are supported as [`PgRange<T>`], with bounds represented by `std::ops::Bound`, and
+//! the multiranges of Postgres 14 as [`PgMultirange<T>`].
+//!
+//! | Rust type                             | Postgres type(s)                                     |
+//! |---------------------------------------|------------------------------------------------------|
+//! | `PgRange<i32>`                        | INT4RANGE                                            |
+//! | `PgRange<i64>`                        | INT8RANGE                                            |
+//! | `PgRange<bigdecimal::BigDecimal>`     | NUMRANGE                                             |
+//! | `PgRange<chrono::NaiveDate>`          | DATERANGE                                            |
+//! | `PgRange<chrono::NaiveDateTime>`      | TSRANGE                                              |
+//! | `PgRange<chrono::DateTime<Utc>>`      | TSTZRANGE                                            |
+//! | `PgRange<time::Date>`                 | DATERANGE                                            |
+//! | `PgRange<time::PrimitiveDateTime>`    | TSRANGE                                              |
+//! | `PgRange<time::OffsetDateTime>`       | TSTZRANGE                                            |
+//!
+//! `PgMultirange<T>` maps to the multirange type of the corresponding range type, e.g.
+//! `PgMultirange<i32>` to INT4MULTIRANGE.
+//!
+//! # [Domains](https://www.postgresql.org/docs/current/domains.html)
+//!
+//! A domain (e.g. `CREATE DOMAIN email AS TEXT`) is treated as its base type; a column or
+//! parameter of type `email` is used with `String` and `&str`, and `email[]` with `Vec<String>`.
+//!
 //! # [Enumerations](https://www.postgresql.org/docs/current/datatype-enum.html)
 //!
 //! User-defined enumerations are supported through a derive for `Type`.
@@ -112,6 +166,8 @@
 //! enum Mood { Sad, Ok, Happy }
 //! ```
 //!
+//! The name of the type may also be given as `#[sqlx(type_name = "mood")]`.
+//!
 //! Rust enumerations may also be defined to be represented as an integer using `repr`.
 //! The following type expects a SQL type of `INTEGER` or `INT4` and will convert to/from the
 //! Rust enumeration.
@@ -136,14 +192,34 @@ mod array;
 mod bool;
 mod bytes;
 mod float;
+mod hstore;
 mod int;
+mod interval;
+mod json_path;
+mod lsn;
+mod ltree;
+mod money;
+mod range;
 mod record;
 mod str;
+mod text_search;
+mod xid8;
 
 // internal types used by other types to encode or decode related formats
 #[doc(hidden)]
 pub mod raw;
 
+pub use array::PgHasArrayType;
+pub use hstore::PgHstore;
+pub use interval::PgInterval;
+pub use json_path::PgJsonPath;
+pub use lsn::PgLsn;
+pub use ltree::{PgLquery, PgLtree};
+pub use money::PgMoney;
+pub use range::{PgMultirange, PgRange};
+pub use text_search::{PgTsLexeme, PgTsPosition, PgTsQuery, PgTsVector, PgTsWeight};
+pub use xid8::PgXid8;
+
 #[cfg(feature = "bigdecimal")]
 mod bigdecimal;
 
@@ -162,6 +238,12 @@ mod json;
 #[cfg(feature = "ipnetwork")]
 mod ipnetwork;
 
+#[cfg(feature = "postgis")]
+mod postgis;
+
+#[cfg(feature = "postgis")]
+pub use postgis::PgGeometry;
+
 // Implement `Decode` for all postgres types
 // The concept of a nullable `RawValue` is db-specific
 // `Type` is implemented generically at src/types.rs
@@ -190,11 +272,14 @@ pub(crate) fn try_resolve_type_name(oid: u32) -> Option<&'static str> {
         TypeId::INT8 => "INT8",
 
         TypeId::OID => "OID",
+        TypeId::XID8 => "XID8",
+        TypeId::PG_LSN => "PG_LSN",
 
         TypeId::FLOAT4 => "FLOAT4",
         TypeId::FLOAT8 => "FLOAT8",
 
         TypeId::NUMERIC => "NUMERIC",
+        TypeId::MONEY => "MONEY",
 
         TypeId::TEXT => "TEXT",
         TypeId::VARCHAR => "VARCHAR",
@@ -206,6 +291,7 @@ pub(crate) fn try_resolve_type_name(oid: u32) -> Option<&'static str> {
         TypeId::TIME => "TIME",
         TypeId::TIMESTAMP => "TIMESTAMP",
         TypeId::TIMESTAMPTZ => "TIMESTAMPTZ",
+        TypeId::INTERVAL => "INTERVAL",
 
         TypeId::BYTEA => "BYTEA",
 
@@ -223,6 +309,8 @@ pub(crate) fn try_resolve_type_name(oid: u32) -> Option<&'static str> {
         TypeId::ARRAY_INT8 => "INT8[]",
 
         TypeId::ARRAY_OID => "OID[]",
+        TypeId::ARRAY_XID8 => "XID8[]",
+        TypeId::ARRAY_PG_LSN => "PG_LSN[]",
 
         TypeId::ARRAY_FLOAT4 => "FLOAT4[]",
         TypeId::ARRAY_FLOAT8 => "FLOAT8[]",
@@ -233,11 +321,13 @@ pub(crate) fn try_resolve_type_name(oid: u32) -> Option<&'static str> {
         TypeId::ARRAY_NAME => "NAME[]",
 
         TypeId::ARRAY_NUMERIC => "NUMERIC[]",
+        TypeId::ARRAY_MONEY => "MONEY[]",
 
         TypeId::ARRAY_DATE => "DATE[]",
         TypeId::ARRAY_TIME => "TIME[]",
         TypeId::ARRAY_TIMESTAMP => "TIMESTAMP[]",
         TypeId::ARRAY_TIMESTAMPTZ => "TIMESTAMPTZ[]",
+        TypeId::ARRAY_INTERVAL => "INTERVAL[]",
 
         TypeId::ARRAY_BYTEA => "BYTEA[]",
 
@@ -248,6 +338,25 @@ pub(crate) fn try_resolve_type_name(oid: u32) -> Option<&'static str> {
 
         TypeId::JSON => "JSON",
         TypeId::JSONB => "JSONB",
+        TypeId::JSONPATH => "JSONPATH",
+        TypeId::ARRAY_JSONPATH => "JSONPATH[]",
+
+        TypeId::TSVECTOR => "TSVECTOR",
+        TypeId::TSQUERY => "TSQUERY",
+
+        TypeId::INT4RANGE => "INT4RANGE",
+        TypeId::NUMRANGE => "NUMRANGE",
+        TypeId::TSRANGE => "TSRANGE",
+        TypeId::TSTZRANGE => "TSTZRANGE",
+        TypeId::DATERANGE => "DATERANGE",
+        TypeId::INT8RANGE => "INT8RANGE",
+
+        TypeId::INT4MULTIRANGE => "INT4MULTIRANGE",
+        TypeId::NUMMULTIRANGE => "NUMMULTIRANGE",
+        TypeId::TSMULTIRANGE => "TSMULTIRANGE",
+        TypeId::TSTZMULTIRANGE => "TSTZMULTIRANGE",
+        TypeId::DATEMULTIRANGE => "DATEMULTIRANGE",
+        TypeId::INT8MULTIRANGE => "INT8MULTIRANGE",
 
         TypeId::RECORD => "RECORD",
         TypeId::ARRAY_RECORD => "RECORD[]",
@@ -0,0 +1,231 @@
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+
+use byteorder::{NetworkEndian, ReadBytesExt};
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::postgres::protocol::TypeId;
+use crate::postgres::{PgData, PgRawBuffer, PgTypeInfo, PgValue, Postgres};
+use crate::types::Type;
+use crate::Error;
+
+// <https://www.postgresql.org/docs/12/datatype-money.html>
+
+/// A value of the `money` type; an amount of currency in its smallest unit (e.g. cents).
+///
+/// The number of fractional digits of `money`, and so the unit of the amount, is given by the
+/// `lc_monetary` setting of the database. It is 2 for most locales, in which case
+/// `PgMoney(1234)` is `$12.34`.
+///
+/// Values are always decoded from the integer amount and never parsed according to the locale.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PgMoney(pub i64);
+
+impl PgMoney {
+    /// Convert the amount to a `BigDecimal`, with `scale` fractional digits.
+    #[cfg(feature = "bigdecimal")]
+    pub fn to_bigdecimal(self, scale: i64) -> bigdecimal::BigDecimal {
+        bigdecimal::BigDecimal::new(self.0.into(), scale)
+    }
+
+    /// Convert a `BigDecimal` to an amount with `scale` fractional digits; any further digits
+    /// are truncated.
+    ///
+    /// Returns `None` if the amount does not fit in `money`.
+    #[cfg(feature = "bigdecimal")]
+    pub fn from_bigdecimal(decimal: &bigdecimal::BigDecimal, scale: i64) -> Option<Self> {
+        use bigdecimal::ToPrimitive;
+
+        let (amount, _) = decimal.with_scale(scale).as_bigint_and_exponent();
+
+        amount.to_i64().map(PgMoney)
+    }
+
+    /// Convert the amount to a `Decimal`, with `scale` fractional digits.
+    ///
+    /// # Panics
+    ///
+    /// If `scale` is greater than 28.
+    #[cfg(feature = "decimal")]
+    pub fn to_decimal(self, scale: u32) -> rust_decimal::Decimal {
+        rust_decimal::Decimal::new(self.0, scale)
+    }
+
+    /// Convert a `Decimal` to an amount with `scale` fractional digits; any further digits
+    /// are truncated.
+    ///
+    /// Returns `None` if the amount does not fit in `money`.
+    #[cfg(feature = "decimal")]
+    pub fn from_decimal(decimal: rust_decimal::Decimal, scale: u32) -> Option<Self> {
+        use rust_decimal::prelude::ToPrimitive;
+
+        let factor = rust_decimal::Decimal::from(10_i64.checked_pow(scale)?);
+
+        decimal.checked_mul(factor)?.trunc().to_i64().map(PgMoney)
+    }
+}
+
+impl Add for PgMoney {
+    type Output = PgMoney;
+
+    /// # Panics
+    ///
+    /// On overflow.
+    fn add(self, rhs: PgMoney) -> PgMoney {
+        PgMoney(
+            self.0
+                .checked_add(rhs.0)
+                .expect("overflow adding money amounts"),
+        )
+    }
+}
+
+impl AddAssign for PgMoney {
+    fn add_assign(&mut self, rhs: PgMoney) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for PgMoney {
+    type Output = PgMoney;
+
+    /// # Panics
+    ///
+    /// On overflow.
+    fn sub(self, rhs: PgMoney) -> PgMoney {
+        PgMoney(
+            self.0
+                .checked_sub(rhs.0)
+                .expect("overflow subtracting money amounts"),
+        )
+    }
+}
+
+impl SubAssign for PgMoney {
+    fn sub_assign(&mut self, rhs: PgMoney) {
+        *self = *self - rhs;
+    }
+}
+
+impl From<i64> for PgMoney {
+    fn from(amount: i64) -> Self {
+        PgMoney(amount)
+    }
+}
+
+impl Type<Postgres> for PgMoney {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::MONEY, "MONEY")
+    }
+}
+
+impl Type<Postgres> for [PgMoney] {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::ARRAY_MONEY, "MONEY[]")
+    }
+}
+
+impl Type<Postgres> for Vec<PgMoney> {
+    fn type_info() -> PgTypeInfo {
+        <[PgMoney] as Type<Postgres>>::type_info()
+    }
+}
+
+impl Encode<Postgres> for PgMoney {
+    fn encode(&self, buf: &mut PgRawBuffer) {
+        buf.extend_from_slice(&self.0.to_be_bytes());
+    }
+}
+
+impl<'de> Decode<'de, Postgres> for PgMoney {
+    fn decode(value: PgValue<'de>) -> crate::Result<Self> {
+        match value.try_get()? {
+            PgData::Binary(mut buf) => buf
+                .read_i64::<NetworkEndian>()
+                .map(PgMoney)
+                .map_err(Error::decode),
+
+            // The text format depends on the locale (e.g. `$1,234.56`, `-1.234,56 €` or
+            // `($1,234.56)`), but its digits are always those of the amount
+            PgData::Text(s) => {
+                let negative = s.contains('-') || s.contains('(');
+                let mut amount: i64 = 0;
+
+                for digit in s.chars().filter_map(|c| c.to_digit(10)) {
+                    amount = amount
+                        .checked_mul(10)
+                        .and_then(|amount| {
+                            if negative {
+                                amount.checked_sub(digit as i64)
+                            } else {
+                                amount.checked_add(digit as i64)
+                            }
+                        })
+                        .ok_or_else(|| decode_err!("money: amount out of range: {:?}", s))?;
+                }
+
+                Ok(PgMoney(amount))
+            }
+        }
+    }
+}
+
+#[test]
+fn test_decode_money_text() {
+    for (text, amount) in &[
+        ("$1,234.56", 123456),
+        ("-$1,234.56", -123456),
+        ("($0.05)", -5),
+        ("1.234,56 €", 123456),
+        ("¥1,235", 1235),
+        ("-$92,233,720,368,547,758.08", i64::MIN),
+    ] {
+        assert_eq!(
+            PgMoney::decode(PgValue::from_str(text)).unwrap(),
+            PgMoney(*amount)
+        );
+    }
+
+    assert!(PgMoney::decode(PgValue::from_str("$92,233,720,368,547,758.08")).is_err());
+}
+
+#[test]
+fn test_encode_decode_money_binary() {
+    let mut buf = PgRawBuffer::default();
+    Encode::<Postgres>::encode(&PgMoney(-123456), &mut buf);
+
+    assert_eq!(&**buf, &(-123456_i64).to_be_bytes());
+    assert_eq!(
+        PgMoney::decode(PgValue::from_bytes(&buf)).unwrap(),
+        PgMoney(-123456)
+    );
+}
+
+#[test]
+#[cfg(feature = "bigdecimal")]
+fn test_money_bigdecimal() {
+    use std::str::FromStr;
+
+    let decimal = bigdecimal::BigDecimal::from_str("12.345").unwrap();
+
+    assert_eq!(PgMoney::from_bigdecimal(&decimal, 2), Some(PgMoney(1234)));
+    assert_eq!(
+        PgMoney(1234).to_bigdecimal(2),
+        bigdecimal::BigDecimal::from_str("12.34").unwrap()
+    );
+}
+
+#[test]
+#[cfg(feature = "decimal")]
+fn test_money_decimal() {
+    use std::str::FromStr;
+
+    let decimal = rust_decimal::Decimal::from_str("-12.345").unwrap();
+
+    assert_eq!(PgMoney::from_decimal(decimal, 2), Some(PgMoney(-1234)));
+    assert_eq!(
+        PgMoney(-1234).to_decimal(2),
+        rust_decimal::Decimal::from_str("-12.34").unwrap()
+    );
+    assert_eq!(PgMoney::from_decimal(decimal, 30), None);
+}
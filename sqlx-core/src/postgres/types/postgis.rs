@@ -0,0 +1,149 @@
+use geo_types::Geometry;
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::postgres::{PgData, PgRawBuffer, PgTypeInfo, PgValue, Postgres};
+use crate::types::Type;
+use crate::wkb::{read_geometry, write_geometry};
+
+/// A PostGIS `geometry` (or `geography`) value with its spatial reference system identifier.
+///
+/// `geo_types::Geometry` can be used directly when the SRID is not needed; it is sent
+/// without an SRID and any SRID of a received value is discarded.
+///
+/// Values are transferred as Extended WKB. Only the X and Y coordinates are kept; Z and M
+/// coordinates of a received value are discarded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgGeometry {
+    pub geometry: Geometry<f64>,
+
+    /// The spatial reference system identifier (e.g., `4326` for WGS 84).
+    pub srid: Option<u32>,
+}
+
+impl PgGeometry {
+    pub fn new(geometry: impl Into<Geometry<f64>>, srid: Option<u32>) -> Self {
+        Self {
+            geometry: geometry.into(),
+            srid,
+        }
+    }
+}
+
+impl Type<Postgres> for PgGeometry {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("geometry")
+    }
+}
+
+impl Type<Postgres> for Geometry<f64> {
+    fn type_info() -> PgTypeInfo {
+        <PgGeometry as Type<Postgres>>::type_info()
+    }
+}
+
+impl Encode<Postgres> for PgGeometry {
+    fn encode(&self, buf: &mut PgRawBuffer) {
+        write_geometry(buf, &self.geometry, self.srid);
+    }
+}
+
+impl Encode<Postgres> for Geometry<f64> {
+    fn encode(&self, buf: &mut PgRawBuffer) {
+        write_geometry(buf, self, None);
+    }
+}
+
+impl<'de> Decode<'de, Postgres> for PgGeometry {
+    fn decode(value: PgValue<'de>) -> crate::Result<Self> {
+        let (geometry, srid) = match value.try_get()? {
+            PgData::Binary(mut buf) => read_geometry(&mut buf)?,
+
+            // the text format is the hex-encoded binary format
+            PgData::Text(s) => {
+                let buf = hex::decode(s).map_err(crate::Error::decode)?;
+
+                read_geometry(&mut &*buf)?
+            }
+        };
+
+        Ok(PgGeometry { geometry, srid })
+    }
+}
+
+impl<'de> Decode<'de, Postgres> for Geometry<f64> {
+    fn decode(value: PgValue<'de>) -> crate::Result<Self> {
+        PgGeometry::decode(value).map(|value| value.geometry)
+    }
+}
+
+#[cfg(test)]
+use geo_types::{GeometryCollection, MultiPoint, MultiPolygon, Point, Polygon};
+
+#[test]
+fn test_encode_decode_point_with_srid() {
+    let point = PgGeometry::new(Point::new(1.5, -2.0), Some(4326));
+
+    let mut buf = PgRawBuffer::default();
+    Encode::<Postgres>::encode(&point, &mut buf);
+
+    // SELECT 'SRID=4326;POINT(1.5 -2)'::geometry
+    assert_eq!(
+        hex::encode(&**buf).to_uppercase(),
+        "0101000020E6100000000000000000F83F00000000000000C0"
+    );
+
+    assert_eq!(
+        PgGeometry::decode(PgValue::from_bytes(&buf)).unwrap(),
+        point
+    );
+}
+
+#[test]
+fn test_decode_text_and_big_endian() {
+    // SELECT 'LINESTRING(0 0, 1 1)'::geometry, in big-endian (XDR)
+    let line = PgGeometry::decode(PgValue::from_str(
+        "00000000020000000200000000000000000000000000000000\
+         3FF00000000000003FF0000000000000",
+    ))
+    .unwrap();
+
+    assert_eq!(line.srid, None);
+    assert_eq!(
+        line.geometry,
+        Geometry::LineString(vec![(0.0, 0.0), (1.0, 1.0)].into())
+    );
+}
+
+#[test]
+fn test_encode_decode_nested_geometries() {
+    let polygon = Polygon::new(
+        vec![(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 0.0)].into(),
+        vec![vec![(1.0, 1.0), (2.0, 1.0), (2.0, 2.0), (1.0, 1.0)].into()],
+    );
+
+    let geometry = Geometry::GeometryCollection(GeometryCollection(vec![
+        Geometry::MultiPolygon(MultiPolygon(vec![polygon])),
+        Geometry::MultiPoint(MultiPoint(vec![Point::new(1.0, 2.0)])),
+        Geometry::Point(Point::new(3.0, 4.0)),
+    ]));
+
+    let mut buf = PgRawBuffer::default();
+    Encode::<Postgres>::encode(&geometry, &mut buf);
+
+    let decoded = PgGeometry::decode(PgValue::from_bytes(&buf)).unwrap();
+
+    assert_eq!(decoded.srid, None);
+    assert_eq!(decoded.geometry, geometry);
+}
+
+#[test]
+fn test_decode_drops_z_coordinates() {
+    // SELECT 'POINT Z(1 2 3)'::geometry
+    let point = <Geometry<f64> as Decode<Postgres>>::decode(PgValue::from_str(
+        "0101000080000000000000F03F00000000000000400000000000000840",
+    ))
+    .unwrap();
+
+    assert_eq!(point, Geometry::Point(Point::new(1.0, 2.0)));
+}
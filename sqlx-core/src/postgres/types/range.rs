@@ -0,0 +1,574 @@
+use std::ops::RangeToInclusive;
+use std::ops::{Bound, Deref, DerefMut, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo};
+
+use byteorder::NetworkEndian;
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::io::{Buf, BufMut};
+use crate::postgres::protocol::TypeId;
+use crate::postgres::{PgData, PgRawBuffer, PgTypeInfo, PgValue, Postgres};
+use crate::types::Type;
+
+// <https://www.postgresql.org/docs/12/rangetypes.html>
+// <https://github.com/postgres/postgres/blob/master/src/include/utils/rangetypes.h>
+
+const RANGE_EMPTY: u8 = 0x01;
+const RANGE_LB_INC: u8 = 0x02;
+const RANGE_UB_INC: u8 = 0x04;
+const RANGE_LB_INF: u8 = 0x08;
+const RANGE_UB_INF: u8 = 0x10;
+
+/// A value of a range type, e.g. `int4range` or `tstzrange`.
+///
+/// Postgres normalizes ranges of discrete types (`int4range`, `int8range` and `daterange`)
+/// to an inclusive lower bound and an exclusive upper bound, and a range that contains no
+/// values (e.g. `[1,1)`) to `empty`.
+///
+/// ```rust,ignore
+/// let range: PgRange<i32> = (1..10).into();
+///
+/// sqlx::query("SELECT * FROM reservations WHERE seats && $1").bind(range)
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PgRange<T> {
+    /// A range that contains no values.
+    Empty,
+
+    NonEmpty {
+        start: Bound<T>,
+        end: Bound<T>,
+    },
+}
+
+impl<T> PgRange<T> {
+    /// Create a range from its lower and upper bounds.
+    pub fn new(start: Bound<T>, end: Bound<T>) -> Self {
+        PgRange::NonEmpty { start, end }
+    }
+
+    /// Returns `true` if this is the empty range.
+    pub fn is_empty(&self) -> bool {
+        matches!(self, PgRange::Empty)
+    }
+
+    /// Returns the lower bound of the range, or `None` if the range is empty.
+    pub fn start(&self) -> Option<Bound<&T>> {
+        match self {
+            PgRange::Empty => None,
+            PgRange::NonEmpty { start, .. } => Some(as_ref(start)),
+        }
+    }
+
+    /// Returns the upper bound of the range, or `None` if the range is empty.
+    pub fn end(&self) -> Option<Bound<&T>> {
+        match self {
+            PgRange::Empty => None,
+            PgRange::NonEmpty { end, .. } => Some(as_ref(end)),
+        }
+    }
+}
+
+impl<T> From<(Bound<T>, Bound<T>)> for PgRange<T> {
+    fn from((start, end): (Bound<T>, Bound<T>)) -> Self {
+        Self::new(start, end)
+    }
+}
+
+impl<T> From<Range<T>> for PgRange<T> {
+    fn from(range: Range<T>) -> Self {
+        Self::new(Bound::Included(range.start), Bound::Excluded(range.end))
+    }
+}
+
+impl<T> From<RangeInclusive<T>> for PgRange<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        let (start, end) = range.into_inner();
+
+        Self::new(Bound::Included(start), Bound::Included(end))
+    }
+}
+
+impl<T> From<RangeFrom<T>> for PgRange<T> {
+    fn from(range: RangeFrom<T>) -> Self {
+        Self::new(Bound::Included(range.start), Bound::Unbounded)
+    }
+}
+
+impl<T> From<RangeTo<T>> for PgRange<T> {
+    fn from(range: RangeTo<T>) -> Self {
+        Self::new(Bound::Unbounded, Bound::Excluded(range.end))
+    }
+}
+
+impl<T> From<RangeToInclusive<T>> for PgRange<T> {
+    fn from(range: RangeToInclusive<T>) -> Self {
+        Self::new(Bound::Unbounded, Bound::Included(range.end))
+    }
+}
+
+impl<T> From<RangeFull> for PgRange<T> {
+    fn from(_: RangeFull) -> Self {
+        Self::new(Bound::Unbounded, Bound::Unbounded)
+    }
+}
+
+/// A value of a multirange type, e.g. `int4multirange`; an ordered list of ranges that
+/// don't overlap.
+///
+/// Multirange types were added in Postgres 14.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PgMultirange<T>(pub Vec<PgRange<T>>);
+
+impl<T> Default for PgMultirange<T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<T> Deref for PgMultirange<T> {
+    type Target = Vec<PgRange<T>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for PgMultirange<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> From<Vec<PgRange<T>>> for PgMultirange<T> {
+    fn from(ranges: Vec<PgRange<T>>) -> Self {
+        Self(ranges)
+    }
+}
+
+impl<T> std::iter::FromIterator<PgRange<T>> for PgMultirange<T> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = PgRange<T>>,
+    {
+        Self(iter.into_iter().collect())
+    }
+}
+
+macro_rules! impl_range_type {
+    ($(#[$meta:meta])* $ty:ty, $range:ident, $multirange:ident) => {
+        $(#[$meta])*
+        impl Type<Postgres> for PgRange<$ty> {
+            fn type_info() -> PgTypeInfo {
+                PgTypeInfo::new(TypeId::$range, stringify!($range))
+            }
+        }
+
+        $(#[$meta])*
+        impl Type<Postgres> for PgMultirange<$ty> {
+            fn type_info() -> PgTypeInfo {
+                PgTypeInfo::new(TypeId::$multirange, stringify!($multirange))
+            }
+        }
+    };
+}
+
+impl_range_type!(i32, INT4RANGE, INT4MULTIRANGE);
+impl_range_type!(i64, INT8RANGE, INT8MULTIRANGE);
+
+impl_range_type!(
+    #[cfg(feature = "bigdecimal")]
+    bigdecimal::BigDecimal,
+    NUMRANGE,
+    NUMMULTIRANGE
+);
+
+impl_range_type!(
+    #[cfg(feature = "chrono")]
+    chrono::NaiveDate,
+    DATERANGE,
+    DATEMULTIRANGE
+);
+
+impl_range_type!(
+    #[cfg(feature = "chrono")]
+    chrono::NaiveDateTime,
+    TSRANGE,
+    TSMULTIRANGE
+);
+
+impl_range_type!(
+    #[cfg(feature = "chrono")]
+    chrono::DateTime<chrono::Utc>,
+    TSTZRANGE,
+    TSTZMULTIRANGE
+);
+
+impl_range_type!(
+    #[cfg(feature = "chrono")]
+    chrono::DateTime<chrono::Local>,
+    TSTZRANGE,
+    TSTZMULTIRANGE
+);
+
+impl_range_type!(
+    #[cfg(feature = "time")]
+    time::Date,
+    DATERANGE,
+    DATEMULTIRANGE
+);
+
+impl_range_type!(
+    #[cfg(feature = "time")]
+    time::PrimitiveDateTime,
+    TSRANGE,
+    TSMULTIRANGE
+);
+
+impl_range_type!(
+    #[cfg(feature = "time")]
+    time::OffsetDateTime,
+    TSTZRANGE,
+    TSTZMULTIRANGE
+);
+
+impl<T> Encode<Postgres> for PgRange<T>
+where
+    T: Encode<Postgres>,
+{
+    fn encode(&self, buf: &mut PgRawBuffer) {
+        let (start, end) = match self {
+            PgRange::Empty => {
+                buf.put_u8(RANGE_EMPTY);
+                return;
+            }
+
+            PgRange::NonEmpty { start, end } => (start, end),
+        };
+
+        let mut flags = 0;
+
+        flags |= match start {
+            Bound::Included(_) => RANGE_LB_INC,
+            Bound::Excluded(_) => 0,
+            Bound::Unbounded => RANGE_LB_INF,
+        };
+
+        flags |= match end {
+            Bound::Included(_) => RANGE_UB_INC,
+            Bound::Excluded(_) => 0,
+            Bound::Unbounded => RANGE_UB_INF,
+        };
+
+        buf.put_u8(flags);
+
+        // each finite bound is prefixed with its length
+        for bound in &[start, end] {
+            if let Bound::Included(value) | Bound::Excluded(value) = bound {
+                let len_index = buf.len();
+                buf.put_i32::<NetworkEndian>(0);
+
+                let start = buf.len();
+
+                value.encode(buf);
+
+                let len = (buf.len() - start) as i32;
+                buf[len_index..start].copy_from_slice(&len.to_be_bytes());
+            }
+        }
+    }
+}
+
+impl<T> Encode<Postgres> for PgMultirange<T>
+where
+    T: Encode<Postgres>,
+{
+    fn encode(&self, buf: &mut PgRawBuffer) {
+        buf.put_i32::<NetworkEndian>(self.0.len() as i32);
+
+        // each range is prefixed with its length
+        for range in &self.0 {
+            let len_index = buf.len();
+            buf.put_i32::<NetworkEndian>(0);
+
+            let start = buf.len();
+            range.encode(buf);
+
+            let len = (buf.len() - start) as i32;
+            buf[len_index..start].copy_from_slice(&len.to_be_bytes());
+        }
+    }
+}
+
+// Bounds in the text format may need to be unescaped, so the values of a range cannot borrow
+// from the data
+impl<'de, T> Decode<'de, Postgres> for PgRange<T>
+where
+    T: for<'r> Decode<'r, Postgres> + Type<Postgres>,
+{
+    fn decode(value: PgValue<'de>) -> crate::Result<Self> {
+        match value.try_get()? {
+            PgData::Binary(buf) => decode_binary(buf),
+
+            PgData::Text(s) => {
+                let (range, rest) = decode_text(s.trim_start())?;
+
+                if !rest.trim().is_empty() {
+                    return Err(decode_err!("range: unexpected input: {:?}", rest));
+                }
+
+                Ok(range)
+            }
+        }
+    }
+}
+
+impl<'de, T> Decode<'de, Postgres> for PgMultirange<T>
+where
+    T: for<'r> Decode<'r, Postgres> + Type<Postgres>,
+{
+    fn decode(value: PgValue<'de>) -> crate::Result<Self> {
+        let mut ranges = Vec::new();
+
+        match value.try_get()? {
+            PgData::Binary(mut buf) => {
+                let len = buf.get_i32::<NetworkEndian>()?;
+
+                for _ in 0..len {
+                    let range_len = buf.get_i32::<NetworkEndian>()?;
+
+                    if range_len < 0 || range_len as usize > buf.len() {
+                        return Err(decode_err!("multirange: invalid range length"));
+                    }
+
+                    let (range, rest) = buf.split_at(range_len as usize);
+
+                    ranges.push(decode_binary(range)?);
+                    buf = rest;
+                }
+            }
+
+            // `{[1,3),[5,7)}`
+            PgData::Text(s) => {
+                let mut s = s
+                    .trim()
+                    .strip_prefix('{')
+                    .and_then(|s| s.strip_suffix('}'))
+                    .ok_or_else(|| decode_err!("multirange: expected `{{..}}`: {:?}", s))?
+                    .trim_start();
+
+                while !s.is_empty() {
+                    let (range, rest) = decode_text(s)?;
+
+                    ranges.push(range);
+
+                    s = rest.trim_start();
+
+                    if !s.is_empty() {
+                        s = s
+                            .strip_prefix(',')
+                            .ok_or_else(|| decode_err!("multirange: expected `,`: {:?}", s))?
+                            .trim_start();
+                    }
+                }
+            }
+        }
+
+        Ok(PgMultirange(ranges))
+    }
+}
+
+fn decode_binary<T>(mut buf: &[u8]) -> crate::Result<PgRange<T>>
+where
+    T: for<'r> Decode<'r, Postgres> + Type<Postgres>,
+{
+    let flags = buf.get_u8()?;
+
+    if flags & RANGE_EMPTY != 0 {
+        return Ok(PgRange::Empty);
+    }
+
+    let mut decode_bound = |inf: u8, inc: u8| -> crate::Result<Bound<T>> {
+        if flags & inf != 0 {
+            return Ok(Bound::Unbounded);
+        }
+
+        let len = buf.get_i32::<NetworkEndian>()?;
+
+        if len < 0 || len as usize > buf.len() {
+            return Err(decode_err!("range: invalid bound length"));
+        }
+
+        let (value, rest) = buf.split_at(len as usize);
+        let value = T::decode(PgValue::bytes(T::type_info(), value))?;
+
+        buf = rest;
+
+        Ok(if flags & inc != 0 {
+            Bound::Included(value)
+        } else {
+            Bound::Excluded(value)
+        })
+    };
+
+    let start = decode_bound(RANGE_LB_INF, RANGE_LB_INC)?;
+    let end = decode_bound(RANGE_UB_INF, RANGE_UB_INC)?;
+
+    Ok(PgRange::new(start, end))
+}
+
+// Decode a range in the text format (`empty`, or e.g. `[1,10)` or `(,"2020-01-01 00:00:00")`)
+// and return it and the remaining input
+fn decode_text<T>(s: &str) -> crate::Result<(PgRange<T>, &str)>
+where
+    T: for<'r> Decode<'r, Postgres>,
+{
+    if s.len() >= 5 && s[..5].eq_ignore_ascii_case("empty") {
+        return Ok((PgRange::Empty, &s[5..]));
+    }
+
+    let start_inclusive = match s.chars().next() {
+        Some('[') => true,
+        Some('(') => false,
+        _ => return Err(decode_err!("range: expected `[` or `(`: {:?}", s)),
+    };
+
+    let (start, s) = read_bound(&s[1..])?;
+
+    let s = s
+        .strip_prefix(',')
+        .ok_or_else(|| decode_err!("range: expected `,`: {:?}", s))?;
+
+    let (end, s) = read_bound(s)?;
+
+    let end_inclusive = match s.chars().next() {
+        Some(']') => true,
+        Some(')') => false,
+        _ => return Err(decode_err!("range: expected `]` or `)`: {:?}", s)),
+    };
+
+    let bound = |value: Option<String>, inclusive: bool| -> crate::Result<Bound<T>> {
+        let value = match value {
+            // a missing value is an infinite bound
+            None => return Ok(Bound::Unbounded),
+
+            Some(value) => T::decode(PgValue::from_str(&value))?,
+        };
+
+        Ok(if inclusive {
+            Bound::Included(value)
+        } else {
+            Bound::Excluded(value)
+        })
+    };
+
+    let range = PgRange::new(bound(start, start_inclusive)?, bound(end, end_inclusive)?);
+
+    Ok((range, &s[1..]))
+}
+
+// Read a bound in the text format; a bound may be double-quoted, in which case `"` is doubled
+// and `\` escapes the next character
+fn read_bound(s: &str) -> crate::Result<(Option<String>, &str)> {
+    let mut value = String::new();
+    let mut quoted = false;
+    let mut chars = s.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek().map(|(_, c)| *c) == Some('"') => {
+                chars.next();
+                value.push('"');
+            }
+
+            '"' => quoted = !quoted,
+
+            '\\' => match chars.next() {
+                Some((_, c)) => value.push(c),
+                None => break,
+            },
+
+            ',' | ')' | ']' if !quoted => {
+                // an unquoted empty bound is infinite; `""` is an empty string
+                let value = if i == 0 { None } else { Some(value) };
+
+                return Ok((value, &s[i..]));
+            }
+
+            c => value.push(c),
+        }
+    }
+
+    Err(decode_err!("range: unterminated bound: {:?}", s))
+}
+
+fn as_ref<T>(bound: &Bound<T>) -> Bound<&T> {
+    match bound {
+        Bound::Included(value) => Bound::Included(value),
+        Bound::Excluded(value) => Bound::Excluded(value),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+#[test]
+fn test_decode_range_text() {
+    let range = PgRange::<i32>::decode(PgValue::from_str("[1,10)")).unwrap();
+    assert_eq!(range, PgRange::from(1..10));
+
+    let range = PgRange::<i32>::decode(PgValue::from_str("(,5]")).unwrap();
+    assert_eq!(range, PgRange::from(..=5));
+
+    let range = PgRange::<i32>::decode(PgValue::from_str("empty")).unwrap();
+    assert!(range.is_empty());
+
+    let range = PgRange::<String>::decode(PgValue::from_str(r#"["a ""b""","c\\d")"#)).unwrap();
+    assert_eq!(
+        range,
+        PgRange::new(
+            Bound::Included("a \"b\"".to_owned()),
+            Bound::Excluded("c\\d".to_owned())
+        )
+    );
+
+    assert!(PgRange::<i32>::decode(PgValue::from_str("[1,10")).is_err());
+    assert!(PgRange::<i32>::decode(PgValue::from_str("1,10)")).is_err());
+}
+
+#[test]
+fn test_decode_multirange_text() {
+    let multirange = PgMultirange::<i32>::decode(PgValue::from_str("{[1,3), [5,)}")).unwrap();
+    assert_eq!(multirange.0, vec![PgRange::from(1..3), PgRange::from(5..)]);
+
+    let multirange = PgMultirange::<i32>::decode(PgValue::from_str("{}")).unwrap();
+    assert!(multirange.is_empty());
+}
+
+#[test]
+fn test_encode_decode_range_binary() {
+    let range = PgRange::from(1_i32..=10);
+
+    let mut buf = PgRawBuffer::default();
+    Encode::<Postgres>::encode(&range, &mut buf);
+
+    assert_eq!(&**buf, b"\x06\0\0\0\x04\0\0\0\x01\0\0\0\x04\0\0\0\x0a");
+
+    assert_eq!(
+        PgRange::<i32>::decode(PgValue::from_bytes(&buf)).unwrap(),
+        range
+    );
+
+    let multirange = PgMultirange(vec![PgRange::Empty, PgRange::from(..5_i64)]);
+
+    let mut buf = PgRawBuffer::default();
+    Encode::<Postgres>::encode(&multirange, &mut buf);
+
+    assert_eq!(
+        &**buf,
+        b"\0\0\0\x02\0\0\0\x01\x01\0\0\0\x0d\x08\0\0\0\x08\0\0\0\0\0\0\0\x05"
+    );
+
+    assert_eq!(
+        PgMultirange::<i64>::decode(PgValue::from_bytes(&buf)).unwrap(),
+        multirange
+    );
+}
@@ -9,6 +9,8 @@ pub(crate) struct PgSequenceDecoder<'de> {
     data: PgData<'de>,
     len: usize,
     is_text_record: bool,
+    // a separator was consumed, so another (possibly empty) text value follows
+    has_next_text: bool,
     element_oid: Option<u32>,
 }
 
@@ -31,6 +33,7 @@ impl<'de> PgSequenceDecoder<'de> {
 
         Self {
             is_text_record,
+            has_next_text: false,
             element_oid,
             data,
             len: 0,
@@ -62,8 +65,10 @@ impl<'de> PgSequenceDecoder<'de> {
                     // the OID of the type is encoded next to each value
                     let element_oid = buf.get_u32::<BigEndian>()?;
                     let expected_ty = PgTypeInfo::new(TypeId(element_oid), "");
+                    let ty = T::type_info();
 
-                    if !expected_ty.compatible(&T::type_info()) {
+                    // a type known only by name (e.g. a nested composite) has no OID to compare
+                    if ty.id.is_some() && !expected_ty.compatible(&ty) {
                         return Err(crate::Error::mismatched_types::<Postgres, T>(expected_ty));
                     }
 
@@ -88,7 +93,7 @@ impl<'de> PgSequenceDecoder<'de> {
             }
 
             PgData::Text(ref mut s) => {
-                if s.is_empty() {
+                if s.is_empty() && !self.has_next_text {
                     return Ok(None);
                 }
 
@@ -150,7 +155,10 @@ impl<'de> PgSequenceDecoder<'de> {
                 // NOTE: We pass `0` as the type ID because we don't have a reasonable value
                 //       we could use. In TEXT mode, sequences aren't typed.
 
-                let value = T::decode(if end == Some(0) {
+                // an empty, unquoted value is a NULL (only found in records)
+                let is_empty = end.unwrap_or(s.len()) == 0;
+
+                let value = T::decode(if is_empty {
                     PgValue::null()
                 } else if !self.is_text_record && value == "NULL" {
                     // Yes, in arrays the text encoding of a NULL is just NULL
@@ -165,6 +173,8 @@ impl<'de> PgSequenceDecoder<'de> {
                     ""
                 };
 
+                self.has_next_text = end.is_some();
+
                 self.len += 1;
 
                 Ok(Some(value))
@@ -198,6 +208,48 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn it_decodes_text_null_fields() -> crate::Result<()> {
+        // select (1,null,null);
+        let data = "(1,,)";
+        let mut decoder = PgSequenceDecoder::from(data);
+
+        assert_eq!(decoder.decode::<Option<i32>>()?, Some(Some(1_i32)));
+        assert_eq!(decoder.decode::<Option<i32>>()?, Some(None));
+        assert_eq!(decoder.decode::<Option<i32>>()?, Some(None));
+        assert_eq!(decoder.decode::<Option<i32>>()?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_decodes_text_deeply_nested_record() -> crate::Result<()> {
+        // select ((1,'a "b", c'), null::int, (null::int,null::text), ((2,''),'x\y'));
+        let data = r#"("(1,""a """"b"""", c"")",,"(,)","(""(2,"""""""")"",""x\\\\y"")")"#;
+        let mut decoder = PgSequenceDecoder::from(data);
+
+        assert_eq!(
+            decoder.decode::<(i32, String)>()?,
+            Some((1, r#"a "b", c"#.to_owned()))
+        );
+
+        assert_eq!(decoder.decode::<Option<i32>>()?, Some(None));
+
+        assert_eq!(
+            decoder.decode::<(Option<i32>, Option<String>)>()?,
+            Some((None, None))
+        );
+
+        assert_eq!(
+            decoder.decode::<((i32, String), String)>()?,
+            Some(((2, String::new()), r"x\y".to_owned()))
+        );
+
+        assert_eq!(decoder.decode::<i32>()?, None);
+
+        Ok(())
+    }
+
     #[test]
     fn it_decodes_text_nested_sequence() -> crate::Result<()> {
         // select ((1,array[false,true]),array[(1,4),(5,2)]);
@@ -0,0 +1,817 @@
+use std::fmt::{self, Display, Write};
+
+use byteorder::NetworkEndian;
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::io::{Buf, BufMut};
+use crate::postgres::protocol::TypeId;
+use crate::postgres::{PgData, PgRawBuffer, PgTypeInfo, PgValue, Postgres};
+use crate::types::Type;
+
+// <https://www.postgresql.org/docs/12/datatype-textsearch.html>
+// <https://github.com/postgres/postgres/blob/master/src/include/tsearch/ts_type.h>
+
+// Operators of a `tsquery`
+const OP_NOT: u8 = 1;
+const OP_AND: u8 = 2;
+const OP_OR: u8 = 3;
+const OP_PHRASE: u8 = 4;
+
+// Kinds of items in a `tsquery`
+const QI_VAL: u8 = 1;
+const QI_OPR: u8 = 2;
+
+// The largest position of a lexeme in a `tsvector`
+const MAX_POSITION: u16 = 0x3FFF;
+
+/// The weight of a lexeme, used to mark lexemes from different parts of a document
+/// (e.g., the title or the body). `A` is the highest weight and `D` the default.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PgTsWeight {
+    A,
+    B,
+    C,
+    D,
+}
+
+/// A position of a lexeme in a document, as stored in a [PgTsVector].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PgTsPosition {
+    /// The position, from 1 to 16383.
+    pub position: u16,
+    pub weight: PgTsWeight,
+}
+
+/// A lexeme of a [PgTsVector] and its positions in the document, if any.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PgTsLexeme {
+    pub lexeme: String,
+    pub positions: Vec<PgTsPosition>,
+}
+
+/// A document prepared for full-text search; a `tsvector`.
+///
+/// Postgres keeps the lexemes sorted and unique, and the positions of each lexeme sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PgTsVector {
+    pub lexemes: Vec<PgTsLexeme>,
+}
+
+/// A full-text search query; a `tsquery`.
+///
+/// ```rust,ignore
+/// // 'fat' & ( 'rat' | 'cat':* )
+/// let query = PgTsQuery::and(
+///     PgTsQuery::lexeme("fat"),
+///     PgTsQuery::or(PgTsQuery::lexeme("rat"), PgTsQuery::prefix("cat")),
+/// );
+///
+/// sqlx::query("SELECT * FROM documents WHERE body @@ $1").bind(&query)
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PgTsQuery {
+    /// A query without any lexemes; it matches nothing.
+    Empty,
+
+    Lexeme {
+        lexeme: String,
+
+        /// Only match the lexeme with one of these weights; all weights match if empty.
+        weights: Vec<PgTsWeight>,
+
+        /// Match any lexeme starting with `lexeme`.
+        prefix: bool,
+    },
+
+    Not(Box<PgTsQuery>),
+    And(Box<PgTsQuery>, Box<PgTsQuery>),
+    Or(Box<PgTsQuery>, Box<PgTsQuery>),
+
+    /// Match `left` followed by `right`, `distance` positions later (`<->` is a distance of 1).
+    Phrase {
+        left: Box<PgTsQuery>,
+        right: Box<PgTsQuery>,
+        distance: u16,
+    },
+}
+
+impl PgTsQuery {
+    /// A query matching `lexeme` with any weight.
+    pub fn lexeme(lexeme: impl Into<String>) -> Self {
+        PgTsQuery::Lexeme {
+            lexeme: lexeme.into(),
+            weights: Vec::new(),
+            prefix: false,
+        }
+    }
+
+    /// A query matching any lexeme starting with `prefix`.
+    pub fn prefix(prefix: impl Into<String>) -> Self {
+        PgTsQuery::Lexeme {
+            lexeme: prefix.into(),
+            weights: Vec::new(),
+            prefix: true,
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(query: PgTsQuery) -> Self {
+        PgTsQuery::Not(Box::new(query))
+    }
+
+    pub fn and(left: PgTsQuery, right: PgTsQuery) -> Self {
+        PgTsQuery::And(Box::new(left), Box::new(right))
+    }
+
+    pub fn or(left: PgTsQuery, right: PgTsQuery) -> Self {
+        PgTsQuery::Or(Box::new(left), Box::new(right))
+    }
+
+    pub fn phrase(left: PgTsQuery, right: PgTsQuery, distance: u16) -> Self {
+        PgTsQuery::Phrase {
+            left: Box::new(left),
+            right: Box::new(right),
+            distance,
+        }
+    }
+
+    // How tightly the operator of this query binds, from `|` (lowest) to `!`
+    fn precedence(&self) -> u8 {
+        match self {
+            PgTsQuery::Or(..) => 1,
+            PgTsQuery::And(..) => 2,
+            PgTsQuery::Phrase { .. } => 3,
+            PgTsQuery::Empty | PgTsQuery::Lexeme { .. } | PgTsQuery::Not(_) => 4,
+        }
+    }
+
+    // The number of items in the binary format
+    fn len(&self) -> usize {
+        match self {
+            PgTsQuery::Empty => 0,
+            PgTsQuery::Lexeme { .. } => 1,
+            PgTsQuery::Not(query) => 1 + query.len(),
+
+            PgTsQuery::And(left, right)
+            | PgTsQuery::Or(left, right)
+            | PgTsQuery::Phrase { left, right, .. } => 1 + left.len() + right.len(),
+        }
+    }
+}
+
+impl PgTsWeight {
+    fn from_letter(letter: char) -> Option<Self> {
+        Some(match letter.to_ascii_uppercase() {
+            'A' => PgTsWeight::A,
+            'B' => PgTsWeight::B,
+            'C' => PgTsWeight::C,
+            'D' => PgTsWeight::D,
+            _ => return None,
+        })
+    }
+
+    fn letter(self) -> char {
+        match self {
+            PgTsWeight::A => 'A',
+            PgTsWeight::B => 'B',
+            PgTsWeight::C => 'C',
+            PgTsWeight::D => 'D',
+        }
+    }
+
+    // The weight stored in the 2 highest bits of a position in a `tsvector`
+    fn bits(self) -> u16 {
+        match self {
+            PgTsWeight::A => 3,
+            PgTsWeight::B => 2,
+            PgTsWeight::C => 1,
+            PgTsWeight::D => 0,
+        }
+    }
+
+    fn from_bits(bits: u16) -> Self {
+        match bits & 3 {
+            3 => PgTsWeight::A,
+            2 => PgTsWeight::B,
+            1 => PgTsWeight::C,
+            _ => PgTsWeight::D,
+        }
+    }
+
+    // The bit for this weight in the weight mask of a `tsquery` lexeme
+    fn mask(self) -> u8 {
+        1 << self.bits()
+    }
+}
+
+impl Type<Postgres> for PgTsVector {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::TSVECTOR, "TSVECTOR")
+    }
+}
+
+impl Type<Postgres> for PgTsQuery {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::TSQUERY, "TSQUERY")
+    }
+}
+
+impl Encode<Postgres> for PgTsVector {
+    fn encode(&self, buf: &mut PgRawBuffer) {
+        buf.put_i32::<NetworkEndian>(self.lexemes.len() as i32);
+
+        for lexeme in &self.lexemes {
+            buf.put_str_nul(&lexeme.lexeme);
+            buf.put_u16::<NetworkEndian>(lexeme.positions.len() as u16);
+
+            for position in &lexeme.positions {
+                buf.put_u16::<NetworkEndian>(
+                    position.weight.bits() << 14 | position.position.min(MAX_POSITION),
+                );
+            }
+        }
+    }
+}
+
+impl Encode<Postgres> for PgTsQuery {
+    fn encode(&self, buf: &mut PgRawBuffer) {
+        buf.put_i32::<NetworkEndian>(self.len() as i32);
+
+        encode_query(self, buf);
+    }
+}
+
+// Items are written in prefix order, with the right operand of an operator before the left
+fn encode_query(query: &PgTsQuery, buf: &mut Vec<u8>) {
+    match query {
+        PgTsQuery::Empty => {}
+
+        PgTsQuery::Lexeme {
+            lexeme,
+            weights,
+            prefix,
+        } => {
+            buf.put_u8(QI_VAL);
+            buf.put_u8(weights.iter().fold(0, |mask, weight| mask | weight.mask()));
+            buf.put_u8(*prefix as u8);
+            buf.put_str_nul(lexeme);
+        }
+
+        PgTsQuery::Not(query) => {
+            buf.put_u8(QI_OPR);
+            buf.put_u8(OP_NOT);
+
+            encode_query(query, buf);
+        }
+
+        PgTsQuery::And(left, right) | PgTsQuery::Or(left, right) => {
+            buf.put_u8(QI_OPR);
+            buf.put_u8(if let PgTsQuery::And(..) = query {
+                OP_AND
+            } else {
+                OP_OR
+            });
+
+            encode_query(right, buf);
+            encode_query(left, buf);
+        }
+
+        PgTsQuery::Phrase {
+            left,
+            right,
+            distance,
+        } => {
+            buf.put_u8(QI_OPR);
+            buf.put_u8(OP_PHRASE);
+            buf.put_i16::<NetworkEndian>(*distance as i16);
+
+            encode_query(right, buf);
+            encode_query(left, buf);
+        }
+    }
+}
+
+impl<'de> Decode<'de, Postgres> for PgTsVector {
+    fn decode(value: PgValue<'de>) -> crate::Result<Self> {
+        match value.try_get()? {
+            PgData::Binary(mut buf) => {
+                let len = buf.get_i32::<NetworkEndian>()?;
+                let mut lexemes = Vec::new();
+
+                for _ in 0..len {
+                    let lexeme = buf.get_str_nul()?.to_owned();
+                    let len = buf.get_u16::<NetworkEndian>()?;
+                    let mut positions = Vec::with_capacity(len as usize);
+
+                    for _ in 0..len {
+                        let position = buf.get_u16::<NetworkEndian>()?;
+
+                        positions.push(PgTsPosition {
+                            position: position & MAX_POSITION,
+                            weight: PgTsWeight::from_bits(position >> 14),
+                        });
+                    }
+
+                    lexemes.push(PgTsLexeme { lexeme, positions });
+                }
+
+                Ok(PgTsVector { lexemes })
+            }
+
+            PgData::Text(s) => parse_vector(s),
+        }
+    }
+}
+
+impl<'de> Decode<'de, Postgres> for PgTsQuery {
+    fn decode(value: PgValue<'de>) -> crate::Result<Self> {
+        match value.try_get()? {
+            PgData::Binary(mut buf) => {
+                let len = buf.get_i32::<NetworkEndian>()?;
+
+                if len == 0 {
+                    return Ok(PgTsQuery::Empty);
+                }
+
+                let query = decode_query(&mut buf, 0)?;
+
+                if !buf.is_empty() {
+                    return Err(decode_err!("tsquery: unexpected data after the query"));
+                }
+
+                Ok(query)
+            }
+
+            PgData::Text(s) => {
+                let mut parser = QueryParser { s: s.trim() };
+
+                if parser.s.is_empty() {
+                    return Ok(PgTsQuery::Empty);
+                }
+
+                let query = parser.or()?;
+
+                if !parser.s.is_empty() {
+                    return Err(decode_err!("tsquery: unexpected input: {:?}", parser.s));
+                }
+
+                Ok(query)
+            }
+        }
+    }
+}
+
+fn decode_query(buf: &mut &[u8], depth: usize) -> crate::Result<PgTsQuery> {
+    // Postgres limits the size of a query; this only guards against malformed input
+    if depth > 1000 {
+        return Err(decode_err!("tsquery: query is nested too deeply"));
+    }
+
+    match buf.get_u8()? {
+        QI_VAL => {
+            let mask = buf.get_u8()?;
+            let prefix = buf.get_u8()? != 0;
+            let lexeme = buf.get_str_nul()?.to_owned();
+
+            let weights = [PgTsWeight::A, PgTsWeight::B, PgTsWeight::C, PgTsWeight::D]
+                .iter()
+                .copied()
+                .filter(|weight| mask & weight.mask() != 0)
+                .collect();
+
+            Ok(PgTsQuery::Lexeme {
+                lexeme,
+                weights,
+                prefix,
+            })
+        }
+
+        QI_OPR => {
+            let operator = buf.get_u8()?;
+
+            if operator == OP_NOT {
+                return Ok(PgTsQuery::not(decode_query(buf, depth + 1)?));
+            }
+
+            let distance = if operator == OP_PHRASE {
+                buf.get_i16::<NetworkEndian>()? as u16
+            } else {
+                0
+            };
+
+            let right = decode_query(buf, depth + 1)?;
+            let left = decode_query(buf, depth + 1)?;
+
+            match operator {
+                OP_AND => Ok(PgTsQuery::and(left, right)),
+                OP_OR => Ok(PgTsQuery::or(left, right)),
+                OP_PHRASE => Ok(PgTsQuery::phrase(left, right, distance)),
+
+                operator => Err(decode_err!("tsquery: unknown operator: {}", operator)),
+            }
+        }
+
+        kind => Err(decode_err!("tsquery: unknown item type: {}", kind)),
+    }
+}
+
+// Parse the text format of a `tsvector`: `'lexeme':1A,2 'other'`
+fn parse_vector(s: &str) -> crate::Result<PgTsVector> {
+    let mut s = s.trim_start();
+    let mut lexemes = Vec::new();
+
+    while !s.is_empty() {
+        let (lexeme, rest) = read_quoted(s)?;
+        let mut positions = Vec::new();
+
+        s = rest;
+
+        if let Some(rest) = s.strip_prefix(':') {
+            s = rest;
+
+            loop {
+                let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+
+                let position = s[..end]
+                    .parse()
+                    .map_err(|_| decode_err!("tsvector: invalid position: {:?}", s))?;
+
+                s = &s[end..];
+
+                let weight = match s.chars().next().and_then(PgTsWeight::from_letter) {
+                    Some(weight) => {
+                        s = &s[1..];
+                        weight
+                    }
+
+                    None => PgTsWeight::D,
+                };
+
+                positions.push(PgTsPosition { position, weight });
+
+                match s.strip_prefix(',') {
+                    Some(rest) => s = rest,
+                    None => break,
+                }
+            }
+        }
+
+        lexemes.push(PgTsLexeme { lexeme, positions });
+
+        s = s.trim_start();
+    }
+
+    Ok(PgTsVector { lexemes })
+}
+
+// Read a single-quoted lexeme; `'` is doubled and `\` escapes the next character
+fn read_quoted(s: &str) -> crate::Result<(String, &str)> {
+    let quoted = s
+        .strip_prefix('\'')
+        .ok_or_else(|| decode_err!("text search: expected a quoted lexeme: {:?}", s))?;
+
+    let mut lexeme = String::new();
+    let mut chars = quoted.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\'' if chars.peek().map(|(_, c)| *c) == Some('\'') => {
+                chars.next();
+                lexeme.push('\'');
+            }
+
+            '\'' => return Ok((lexeme, &quoted[i + 1..])),
+
+            '\\' => match chars.next() {
+                Some((_, c)) => lexeme.push(c),
+                None => break,
+            },
+
+            c => lexeme.push(c),
+        }
+    }
+
+    Err(decode_err!("text search: unterminated lexeme: {:?}", s))
+}
+
+// Parses the text format of a `tsquery`
+//
+// Operators, from the lowest to the highest precedence: `|`, `&`, `<->` (or `<N>`) and `!`
+struct QueryParser<'a> {
+    s: &'a str,
+}
+
+impl QueryParser<'_> {
+    fn eat(&mut self, token: &str) -> bool {
+        match self.s.strip_prefix(token) {
+            Some(rest) => {
+                self.s = rest.trim_start();
+                true
+            }
+
+            None => false,
+        }
+    }
+
+    fn or(&mut self) -> crate::Result<PgTsQuery> {
+        let mut query = self.and()?;
+
+        while self.eat("|") {
+            query = PgTsQuery::or(query, self.and()?);
+        }
+
+        Ok(query)
+    }
+
+    fn and(&mut self) -> crate::Result<PgTsQuery> {
+        let mut query = self.phrase()?;
+
+        while self.eat("&") {
+            query = PgTsQuery::and(query, self.phrase()?);
+        }
+
+        Ok(query)
+    }
+
+    fn phrase(&mut self) -> crate::Result<PgTsQuery> {
+        let mut query = self.not()?;
+
+        while self.s.starts_with('<') {
+            let end = self
+                .s
+                .find('>')
+                .ok_or_else(|| decode_err!("tsquery: unterminated operator: {:?}", self.s))?;
+
+            let distance = match &self.s[1..end] {
+                "-" => 1,
+                distance => distance
+                    .parse()
+                    .map_err(|_| decode_err!("tsquery: invalid distance: {:?}", distance))?,
+            };
+
+            self.s = self.s[end + 1..].trim_start();
+
+            query = PgTsQuery::phrase(query, self.not()?, distance);
+        }
+
+        Ok(query)
+    }
+
+    fn not(&mut self) -> crate::Result<PgTsQuery> {
+        if self.eat("!") {
+            return Ok(PgTsQuery::not(self.not()?));
+        }
+
+        if self.eat("(") {
+            let query = self.or()?;
+
+            if !self.eat(")") {
+                return Err(decode_err!("tsquery: expected `)`: {:?}", self.s));
+            }
+
+            return Ok(query);
+        }
+
+        let (lexeme, rest) = read_quoted(self.s)?;
+        let mut weights = Vec::new();
+        let mut prefix = false;
+
+        self.s = rest;
+
+        if let Some(rest) = self.s.strip_prefix(':') {
+            self.s = rest;
+
+            while let Some(c) = self.s.chars().next() {
+                if c == '*' {
+                    prefix = true;
+                } else if let Some(weight) = PgTsWeight::from_letter(c) {
+                    weights.push(weight);
+                } else {
+                    break;
+                }
+
+                self.s = &self.s[1..];
+            }
+        }
+
+        self.s = self.s.trim_start();
+
+        Ok(PgTsQuery::Lexeme {
+            lexeme,
+            weights,
+            prefix,
+        })
+    }
+}
+
+// Write a lexeme in the text format
+fn write_quoted(f: &mut fmt::Formatter<'_>, lexeme: &str) -> fmt::Result {
+    f.write_char('\'')?;
+
+    for c in lexeme.chars() {
+        match c {
+            '\'' => f.write_str("''")?,
+            '\\' => f.write_str("\\\\")?,
+            c => f.write_char(c)?,
+        }
+    }
+
+    f.write_char('\'')
+}
+
+impl Display for PgTsVector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, lexeme) in self.lexemes.iter().enumerate() {
+            if i > 0 {
+                f.write_char(' ')?;
+            }
+
+            write_quoted(f, &lexeme.lexeme)?;
+
+            for (i, position) in lexeme.positions.iter().enumerate() {
+                f.write_char(if i == 0 { ':' } else { ',' })?;
+
+                write!(f, "{}", position.position)?;
+
+                if position.weight != PgTsWeight::D {
+                    f.write_char(position.weight.letter())?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for PgTsQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // an operand is parenthesized if it binds less tightly than its operator, or as tightly
+        // when on the right (operators are left-associative)
+        fn operand(
+            f: &mut fmt::Formatter<'_>,
+            query: &PgTsQuery,
+            parent: &PgTsQuery,
+            right: bool,
+        ) -> fmt::Result {
+            let precedence = query.precedence();
+
+            if precedence < parent.precedence() || (right && precedence == parent.precedence()) {
+                write!(f, "( {} )", query)
+            } else {
+                write!(f, "{}", query)
+            }
+        }
+
+        match self {
+            PgTsQuery::Empty => Ok(()),
+
+            PgTsQuery::Lexeme {
+                lexeme,
+                weights,
+                prefix,
+            } => {
+                write_quoted(f, lexeme)?;
+
+                if *prefix || !weights.is_empty() {
+                    f.write_char(':')?;
+                }
+
+                if *prefix {
+                    f.write_char('*')?;
+                }
+
+                for weight in weights {
+                    f.write_char(weight.letter())?;
+                }
+
+                Ok(())
+            }
+
+            PgTsQuery::Not(query) => {
+                f.write_char('!')?;
+                operand(f, query, self, false)
+            }
+
+            PgTsQuery::And(left, right) | PgTsQuery::Or(left, right) => {
+                operand(f, left, self, false)?;
+                f.write_str(if let PgTsQuery::And(..) = self {
+                    " & "
+                } else {
+                    " | "
+                })?;
+                operand(f, right, self, true)
+            }
+
+            PgTsQuery::Phrase {
+                left,
+                right,
+                distance,
+            } => {
+                operand(f, left, self, false)?;
+
+                if *distance == 1 {
+                    f.write_str(" <-> ")?;
+                } else {
+                    write!(f, " <{}> ", distance)?;
+                }
+
+                operand(f, right, self, true)
+            }
+        }
+    }
+}
+
+#[test]
+fn test_tsvector_text_round_trip() {
+    let text = "'a':1A,3 'it''s' 'rat':2C";
+    let vector = PgTsVector::decode(PgValue::from_str(text)).unwrap();
+
+    assert_eq!(vector.lexemes.len(), 3);
+    assert_eq!(vector.lexemes[1].lexeme, "it's");
+    assert!(vector.lexemes[1].positions.is_empty());
+    assert_eq!(
+        vector.lexemes[2].positions,
+        vec![PgTsPosition {
+            position: 2,
+            weight: PgTsWeight::C
+        }]
+    );
+
+    assert_eq!(vector.to_string(), text);
+}
+
+#[test]
+fn test_tsvector_binary_round_trip() {
+    let vector = PgTsVector::decode(PgValue::from_str("'cat':1,2B 'fat'")).unwrap();
+
+    let mut buf = PgRawBuffer::default();
+    Encode::<Postgres>::encode(&vector, &mut buf);
+
+    assert_eq!(&**buf, b"\0\0\0\x02cat\0\0\x02\0\x01\x80\x02fat\0\0\0");
+
+    assert_eq!(
+        PgTsVector::decode(PgValue::from_bytes(&buf)).unwrap(),
+        vector
+    );
+}
+
+#[test]
+fn test_tsquery_text_round_trip() {
+    let text = "'fat':AB & ( 'rat' | !'cat':* ) <2> 'dog'";
+    let query = PgTsQuery::decode(PgValue::from_str(text)).unwrap();
+
+    assert_eq!(
+        query,
+        PgTsQuery::and(
+            PgTsQuery::Lexeme {
+                lexeme: "fat".to_owned(),
+                weights: vec![PgTsWeight::A, PgTsWeight::B],
+                prefix: false,
+            },
+            PgTsQuery::phrase(
+                PgTsQuery::or(
+                    PgTsQuery::lexeme("rat"),
+                    PgTsQuery::not(PgTsQuery::prefix("cat"))
+                ),
+                PgTsQuery::lexeme("dog"),
+                2
+            )
+        )
+    );
+
+    assert_eq!(query.to_string(), text);
+
+    let text = "'a' <-> ( 'b' <-> 'c' ) | !( 'd' & 'e' )";
+    let query = PgTsQuery::decode(PgValue::from_str(text)).unwrap();
+
+    assert_eq!(query.to_string(), text);
+
+    assert_eq!(
+        PgTsQuery::decode(PgValue::from_str("")).unwrap(),
+        PgTsQuery::Empty
+    );
+
+    assert!(PgTsQuery::decode(PgValue::from_str("'a' & ")).is_err());
+    assert!(PgTsQuery::decode(PgValue::from_str("( 'a'")).is_err());
+}
+
+#[test]
+fn test_tsquery_binary_round_trip() {
+    // 'a' <-> !'b'
+    let query = PgTsQuery::phrase(
+        PgTsQuery::lexeme("a"),
+        PgTsQuery::not(PgTsQuery::lexeme("b")),
+        1,
+    );
+
+    let mut buf = PgRawBuffer::default();
+    Encode::<Postgres>::encode(&query, &mut buf);
+
+    assert_eq!(
+        &**buf,
+        b"\0\0\0\x04\x02\x04\0\x01\x02\x01\x01\0\0b\0\x01\0\0a\0"
+    );
+
+    assert_eq!(PgTsQuery::decode(PgValue::from_bytes(&buf)).unwrap(), query);
+}
@@ -0,0 +1,81 @@
+use std::fmt::{self, Display};
+
+use byteorder::{NetworkEndian, ReadBytesExt};
+
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::postgres::protocol::TypeId;
+use crate::postgres::{PgData, PgRawBuffer, PgTypeInfo, PgValue, Postgres};
+use crate::types::Type;
+use crate::Error;
+
+// <https://www.postgresql.org/docs/13/datatype-oid.html>
+
+/// A value of the `xid8` type of Postgres 13; a 64-bit transaction ID that, unlike `xid`,
+/// does not wrap around.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PgXid8(pub u64);
+
+impl Display for PgXid8 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl From<u64> for PgXid8 {
+    fn from(xid: u64) -> Self {
+        PgXid8(xid)
+    }
+}
+
+impl Type<Postgres> for PgXid8 {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::XID8, "XID8")
+    }
+}
+
+impl Type<Postgres> for [PgXid8] {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::new(TypeId::ARRAY_XID8, "XID8[]")
+    }
+}
+
+impl Type<Postgres> for Vec<PgXid8> {
+    fn type_info() -> PgTypeInfo {
+        <[PgXid8] as Type<Postgres>>::type_info()
+    }
+}
+
+impl Encode<Postgres> for PgXid8 {
+    fn encode(&self, buf: &mut PgRawBuffer) {
+        buf.extend_from_slice(&self.0.to_be_bytes());
+    }
+}
+
+impl<'de> Decode<'de, Postgres> for PgXid8 {
+    fn decode(value: PgValue<'de>) -> crate::Result<Self> {
+        match value.try_get()? {
+            PgData::Binary(mut buf) => buf
+                .read_u64::<NetworkEndian>()
+                .map(PgXid8)
+                .map_err(Error::decode),
+
+            PgData::Text(s) => s.parse().map(PgXid8).map_err(Error::decode),
+        }
+    }
+}
+
+#[test]
+fn test_decode_xid8() {
+    assert_eq!(
+        PgXid8::decode(PgValue::from_str("18446744073709551615")).unwrap(),
+        PgXid8(u64::MAX)
+    );
+
+    assert_eq!(
+        PgXid8::decode(PgValue::from_bytes(&[0, 0, 0, 0, 0, 0, 2, 0])).unwrap(),
+        PgXid8(512)
+    );
+
+    assert_eq!(PgXid8(512).to_string(), "512");
+}
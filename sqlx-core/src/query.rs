@@ -21,6 +21,7 @@ where
 {
     pub(crate) query: &'q str,
     pub(crate) arguments: DB::Arguments,
+    persistent: bool,
     database: PhantomData<DB>,
 }
 
@@ -52,6 +53,10 @@ where
         (self.query, Some(self.arguments))
     }
 
+    fn persistent(&self) -> bool {
+        self.persistent
+    }
+
     #[doc(hidden)]
     fn query_string(&self) -> &'q str {
         self.query
@@ -79,11 +84,23 @@ where
         self
     }
 
+    /// If `false`, the query is prepared each time it is run and its prepared statement is
+    /// not kept on the connection. Defaults to `true`.
+    ///
+    /// Useful for SQL that is generated at runtime and seldom run twice, which would otherwise
+    /// fill the statement cache, or when connecting through a pooler that may run each
+    /// transaction on a different server connection (e.g. PgBouncer in transaction mode).
+    pub fn persistent(mut self, value: bool) -> Self {
+        self.persistent = value;
+        self
+    }
+
     #[doc(hidden)]
     pub fn bind_all(self, arguments: DB::Arguments) -> Query<'q, DB> {
         Query {
             query: self.query,
             arguments,
+            persistent: self.persistent,
             database: PhantomData,
         }
     }
@@ -251,6 +268,7 @@ where
     Query {
         database: PhantomData,
         arguments: Default::default(),
+        persistent: true,
         query: sql,
     }
 }
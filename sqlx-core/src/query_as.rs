@@ -15,7 +15,8 @@ where
     DB: Database,
 {
     query: &'q str,
-    arguments: <DB as Database>::Arguments,
+    pub(crate) arguments: <DB as Database>::Arguments,
+    persistent: bool,
     database: PhantomData<DB>,
     output: PhantomData<O>,
 }
@@ -34,6 +35,16 @@ where
         self.arguments.add(value);
         self
     }
+
+    /// If `false`, the query is prepared each time it is run and its prepared statement is
+    /// not kept on the connection. Defaults to `true`.
+    ///
+    /// See [`Query::persistent`](struct.Query.html#method.persistent).
+    #[inline]
+    pub fn persistent(mut self, value: bool) -> Self {
+        self.persistent = value;
+        self
+    }
 }
 
 impl<'q, DB, O: Send> Execute<'q, DB> for QueryAs<'q, DB, O>
@@ -45,6 +56,11 @@ where
         (self.query, Some(self.arguments))
     }
 
+    #[inline]
+    fn persistent(&self) -> bool {
+        self.persistent
+    }
+
     #[inline]
     #[doc(hidden)]
     fn query_string(&self) -> &'q str {
@@ -63,6 +79,7 @@ where
     QueryAs {
         query: sql,
         arguments: Default::default(),
+        persistent: true,
         database: PhantomData,
         output: PhantomData,
     }
@@ -11,7 +11,8 @@ pub(crate) use async_std::{
     fs,
     future::timeout,
     io::prelude::ReadExt as AsyncReadExt,
-    io::{Read as AsyncRead, Write as AsyncWrite},
+    io::prelude::WriteExt as AsyncWriteExt,
+    io::{Read as AsyncRead, Seek as AsyncSeek, Write as AsyncWrite},
     net::TcpStream,
     task::sleep,
     task::spawn,
@@ -20,10 +21,13 @@ pub(crate) use async_std::{
 #[cfg(all(feature = "runtime-async-std", feature = "postgres", unix))]
 pub(crate) use async_std::os::unix::net::UnixStream;
 
+#[cfg(all(feature = "runtime-async-std", feature = "postgres"))]
+pub(crate) use async_std::net::UdpSocket;
+
 #[cfg(feature = "runtime-tokio")]
 pub(crate) use tokio::{
     fs,
-    io::{AsyncRead, AsyncReadExt, AsyncWrite},
+    io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncWrite, AsyncWriteExt},
     net::TcpStream,
     task::spawn,
     time::delay_for as sleep,
@@ -32,3 +36,6 @@ pub(crate) use tokio::{
 
 #[cfg(all(feature = "runtime-tokio", feature = "postgres", unix))]
 pub(crate) use tokio::net::UnixStream;
+
+#[cfg(all(feature = "runtime-tokio", feature = "postgres"))]
+pub(crate) use tokio::net::UdpSocket;
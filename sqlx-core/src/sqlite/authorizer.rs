@@ -0,0 +1,231 @@
+use core::ptr::null_mut;
+
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use libsqlite3_sys::{self as ffi, sqlite3_set_authorizer, SQLITE_DENY, SQLITE_IGNORE, SQLITE_OK};
+
+use crate::sqlite::SqliteConnection;
+
+// The boxed authorizer of a connection, kept in the connection while it is set
+pub(super) type Authorizer =
+    Box<dyn FnMut(&SqliteAuthorizerRequest<'_>) -> SqliteAuthorization + Send>;
+
+/// The kind of action that a statement being prepared performs.
+///
+/// See <https://www.sqlite.org/c3ref/c_alter_table.html> for the arguments of each action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SqliteAuthorizerAction {
+    CreateIndex,
+    CreateTable,
+    CreateTempIndex,
+    CreateTempTable,
+    CreateTempTrigger,
+    CreateTempView,
+    CreateTrigger,
+    CreateView,
+    Delete,
+    DropIndex,
+    DropTable,
+    DropTempIndex,
+    DropTempTable,
+    DropTempTrigger,
+    DropTempView,
+    DropTrigger,
+    DropView,
+    Insert,
+    Pragma,
+    Read,
+    Select,
+    Transaction,
+    Update,
+    Attach,
+    Detach,
+    AlterTable,
+    Reindex,
+    Analyze,
+    CreateVtable,
+    DropVtable,
+    Function,
+    Savepoint,
+    Recursive,
+
+    /// An action of a later version of SQLite, with its code.
+    Other(i32),
+}
+
+impl SqliteAuthorizerAction {
+    fn from_code(code: c_int) -> Self {
+        use SqliteAuthorizerAction::*;
+
+        match code {
+            ffi::SQLITE_CREATE_INDEX => CreateIndex,
+            ffi::SQLITE_CREATE_TABLE => CreateTable,
+            ffi::SQLITE_CREATE_TEMP_INDEX => CreateTempIndex,
+            ffi::SQLITE_CREATE_TEMP_TABLE => CreateTempTable,
+            ffi::SQLITE_CREATE_TEMP_TRIGGER => CreateTempTrigger,
+            ffi::SQLITE_CREATE_TEMP_VIEW => CreateTempView,
+            ffi::SQLITE_CREATE_TRIGGER => CreateTrigger,
+            ffi::SQLITE_CREATE_VIEW => CreateView,
+            ffi::SQLITE_DELETE => Delete,
+            ffi::SQLITE_DROP_INDEX => DropIndex,
+            ffi::SQLITE_DROP_TABLE => DropTable,
+            ffi::SQLITE_DROP_TEMP_INDEX => DropTempIndex,
+            ffi::SQLITE_DROP_TEMP_TABLE => DropTempTable,
+            ffi::SQLITE_DROP_TEMP_TRIGGER => DropTempTrigger,
+            ffi::SQLITE_DROP_TEMP_VIEW => DropTempView,
+            ffi::SQLITE_DROP_TRIGGER => DropTrigger,
+            ffi::SQLITE_DROP_VIEW => DropView,
+            ffi::SQLITE_INSERT => Insert,
+            ffi::SQLITE_PRAGMA => Pragma,
+            ffi::SQLITE_READ => Read,
+            ffi::SQLITE_SELECT => Select,
+            ffi::SQLITE_TRANSACTION => Transaction,
+            ffi::SQLITE_UPDATE => Update,
+            ffi::SQLITE_ATTACH => Attach,
+            ffi::SQLITE_DETACH => Detach,
+            ffi::SQLITE_ALTER_TABLE => AlterTable,
+            ffi::SQLITE_REINDEX => Reindex,
+            ffi::SQLITE_ANALYZE => Analyze,
+            ffi::SQLITE_CREATE_VTABLE => CreateVtable,
+            ffi::SQLITE_DROP_VTABLE => DropVtable,
+            ffi::SQLITE_FUNCTION => Function,
+            ffi::SQLITE_SAVEPOINT => Savepoint,
+            ffi::SQLITE_RECURSIVE => Recursive,
+
+            code => Other(code),
+        }
+    }
+}
+
+/// The decision of an authorizer on an action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqliteAuthorization {
+    /// Allow the action.
+    Allow,
+
+    /// Fail to prepare the statement with an error.
+    Deny,
+
+    /// Prepare the statement, but skip the action: a column that is read is `NULL`, a row
+    /// is not deleted, and so on. Other actions are denied.
+    Ignore,
+}
+
+/// An action of a statement that is being prepared, for an authorizer to allow or deny.
+///
+/// See [`SqliteConnection::set_authorizer`].
+pub struct SqliteAuthorizerRequest<'a> {
+    action: SqliteAuthorizerAction,
+    arguments: [Option<&'a str>; 2],
+    database: Option<&'a str>,
+    accessor: Option<&'a str>,
+}
+
+impl<'a> SqliteAuthorizerRequest<'a> {
+    /// Returns the kind of action.
+    pub fn action(&self) -> SqliteAuthorizerAction {
+        self.action
+    }
+
+    /// Returns the first argument of the action, e.g. the table of a `Read` or `Insert`,
+    /// or the name of a `Pragma`.
+    pub fn first_argument(&self) -> Option<&'a str> {
+        self.arguments[0]
+    }
+
+    /// Returns the second argument of the action, e.g. the column of a `Read` or `Update`,
+    /// or the value of a `Pragma`.
+    pub fn second_argument(&self) -> Option<&'a str> {
+        self.arguments[1]
+    }
+
+    /// Returns the name of the database of the action (e.g. `main` or `temp`), if any.
+    pub fn database(&self) -> Option<&'a str> {
+        self.database
+    }
+
+    /// Returns the name of the trigger or view that performs the action, if it is not
+    /// performed by the statement itself.
+    pub fn accessor(&self) -> Option<&'a str> {
+        self.accessor
+    }
+}
+
+impl SqliteConnection {
+    /// Sets an authorizer that is called for each action of a statement as it is prepared
+    /// on the connection, to allow or deny it.
+    ///
+    /// This allows to sandbox the SQL of untrusted sources, e.g. to deny access to the
+    /// tables of other tenants, or to any `PRAGMA`. A statement that is denied fails to
+    /// prepare. The authorizer must not use the connection. Setting an authorizer replaces
+    /// the previous one; statements prepared before it are authorized again when next run.
+    ///
+    /// ```rust,ignore
+    /// conn.set_authorizer(|request| match (request.action(), request.first_argument()) {
+    ///     (SqliteAuthorizerAction::Read, Some("secrets")) => SqliteAuthorization::Deny,
+    ///     (SqliteAuthorizerAction::Pragma, _) => SqliteAuthorization::Deny,
+    ///     _ => SqliteAuthorization::Allow,
+    /// });
+    /// ```
+    pub fn set_authorizer<F>(&mut self, authorizer: F)
+    where
+        F: FnMut(&SqliteAuthorizerRequest<'_>) -> SqliteAuthorization + Send + 'static,
+    {
+        let mut authorizer: Box<Authorizer> = Box::new(Box::new(authorizer));
+        let data = &mut *authorizer as *mut Authorizer as *mut c_void;
+
+        // https://www.sqlite.org/c3ref/set_authorizer.html
+
+        // The previous authorizer is dropped after SQLite stops calling it
+        unsafe {
+            let _ = sqlite3_set_authorizer(self.handle(), Some(call), data);
+        }
+
+        self.authorizer = Some(authorizer);
+    }
+
+    /// Removes the authorizer set by [`set_authorizer`](#method.set_authorizer).
+    pub fn clear_authorizer(&mut self) {
+        unsafe {
+            let _ = sqlite3_set_authorizer(self.handle(), None, null_mut());
+        }
+
+        self.authorizer = None;
+    }
+}
+
+unsafe extern "C" fn call(
+    data: *mut c_void,
+    action: c_int,
+    first: *const c_char,
+    second: *const c_char,
+    database: *const c_char,
+    accessor: *const c_char,
+) -> c_int {
+    let authorizer = &mut *(data as *mut Authorizer);
+
+    let request = SqliteAuthorizerRequest {
+        action: SqliteAuthorizerAction::from_code(action),
+        arguments: [text(first), text(second)],
+        database: text(database),
+        accessor: text(accessor),
+    };
+
+    // A panic must not unwind into SQLite, and denies the action
+    match catch_unwind(AssertUnwindSafe(|| authorizer(&request))) {
+        Ok(SqliteAuthorization::Allow) => SQLITE_OK,
+        Ok(SqliteAuthorization::Ignore) => SQLITE_IGNORE,
+        Ok(SqliteAuthorization::Deny) | Err(_) => SQLITE_DENY,
+    }
+}
+
+unsafe fn text<'a>(text: *const c_char) -> Option<&'a str> {
+    if text.is_null() {
+        None
+    } else {
+        CStr::from_ptr(text).to_str().ok()
+    }
+}
@@ -0,0 +1,387 @@
+use core::ptr::{null_mut, NonNull};
+
+use std::ffi::CString;
+use std::io::{self, SeekFrom};
+use std::os::raw::{c_int, c_void};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use futures_core::future::BoxFuture;
+use futures_util::ready;
+use libsqlite3_sys::{
+    sqlite3_blob, sqlite3_blob_bytes, sqlite3_blob_close, sqlite3_blob_open, sqlite3_blob_read,
+    sqlite3_blob_write, SQLITE_OK,
+};
+
+use crate::runtime::{AsyncRead, AsyncSeek, AsyncWrite};
+use crate::sqlite::connection::SqliteConnectionHandle;
+use crate::sqlite::worker::Worker;
+use crate::sqlite::{SqliteConnection, SqliteError};
+
+// Largest amount of data transferred by a single read or write on the worker
+const MAX_CHUNK_SIZE: usize = 256 * 1024;
+
+/// Thin wrapper around [sqlite3_blob] to impl `Send`.
+struct SqliteBlobHandle(NonNull<sqlite3_blob>);
+
+// A blob handle is used by one thread at a time, under the mutex of [SqliteBlob], in the same
+// way as the connection it belongs to. See the notes on [SqliteConnectionHandle].
+unsafe impl Send for SqliteBlobHandle {}
+
+/// An open BLOB of a row of a table, for [incremental I/O].
+///
+/// The BLOB is streamed in chunks through [AsyncRead], [AsyncWrite] and [AsyncSeek], on the
+/// thread of the connection, so it never needs to be held in memory at once. A BLOB can not
+/// change its size by being written to; write a `zeroblob(n)` of the needed size to the row
+/// first. The BLOB is closed on drop.
+///
+/// ```rust,ignore
+/// sqlx::query("INSERT INTO files (id, data) VALUES (?, zeroblob(?))")
+///     .bind(id)
+///     .bind(len)
+///     .execute(&mut conn)
+///     .await?;
+///
+/// let mut blob = conn.blob_open("files", "data", id).await?;
+///
+/// io::copy(file, &mut blob).await?;
+/// ```
+///
+/// [incremental I/O]: https://www.sqlite.org/c3ref/blob_open.html
+pub struct SqliteBlob<'c> {
+    // the connection is borrowed for as long as the BLOB is open
+    _conn: &'c mut SqliteConnection,
+    worker: Worker,
+    handle: Arc<Mutex<Option<SqliteBlobHandle>>>,
+    len: usize,
+    pos: u64,
+    state: State,
+}
+
+enum State {
+    Idle,
+    Reading(BoxFuture<'static, io::Result<Vec<u8>>>),
+    Writing(BoxFuture<'static, io::Result<usize>>),
+}
+
+impl SqliteConnection {
+    /// Opens the BLOB (or TEXT) in `column` of the row with `rowid` in `table` of the `main`
+    /// database, for reading and writing.
+    ///
+    /// See [`SqliteBlob`].
+    pub async fn blob_open(
+        &mut self,
+        table: &str,
+        column: &str,
+        rowid: i64,
+    ) -> crate::Result<SqliteBlob<'_>> {
+        let table = name(table)?;
+        let column = name(column)?;
+
+        let handle: SqliteConnectionHandle = self.handle;
+
+        let (blob, len) = self
+            .worker
+            .run(move || -> crate::Result<(SqliteBlobHandle, usize)> {
+                let mut blob = null_mut();
+
+                // https://www.sqlite.org/c3ref/blob_open.html
+                let status = unsafe {
+                    sqlite3_blob_open(
+                        handle.0.as_ptr(),
+                        b"main\0".as_ptr() as *const _,
+                        table.as_ptr(),
+                        column.as_ptr(),
+                        rowid,
+                        // read-write
+                        1,
+                        &mut blob,
+                    )
+                };
+
+                if status != SQLITE_OK {
+                    return Err(SqliteError::from_connection(handle.0.as_ptr()).into());
+                }
+
+                // https://www.sqlite.org/c3ref/blob_bytes.html
+                let len = unsafe { sqlite3_blob_bytes(blob) } as usize;
+
+                Ok((SqliteBlobHandle(NonNull::new(blob).unwrap()), len))
+            })
+            .await?;
+
+        Ok(SqliteBlob {
+            worker: self.worker.clone(),
+            _conn: self,
+            handle: Arc::new(Mutex::new(Some(blob))),
+            len,
+            pos: 0,
+            state: State::Idle,
+        })
+    }
+}
+
+impl SqliteBlob<'_> {
+    /// Returns the size of the BLOB in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the BLOB is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // The range of the BLOB that a read or write of `len` bytes at the position covers
+    fn chunk(&self, len: usize) -> (c_int, c_int) {
+        let remaining = (self.len as u64).saturating_sub(self.pos) as usize;
+        let len = len.min(remaining).min(MAX_CHUNK_SIZE);
+
+        (self.pos as c_int, len as c_int)
+    }
+
+    // Drive an in-flight read or write to completion; a completed read whose data was never
+    // returned to the caller does not move the position
+    fn poll_idle(&mut self, cx: &mut Context) -> Poll<io::Result<()>> {
+        match &mut self.state {
+            State::Idle => Poll::Ready(Ok(())),
+
+            State::Reading(fut) => {
+                let res = ready!(fut.as_mut().poll(cx));
+                self.state = State::Idle;
+
+                Poll::Ready(res.map(|_| ()))
+            }
+
+            State::Writing(fut) => {
+                let res = ready!(fut.as_mut().poll(cx));
+                self.state = State::Idle;
+
+                Poll::Ready(res.map(|written| self.pos += written as u64))
+            }
+        }
+    }
+
+    fn run<F, R>(&self, f: F) -> BoxFuture<'static, io::Result<R>>
+    where
+        F: FnOnce(&SqliteBlobHandle) -> io::Result<R> + Send + 'static,
+        R: Send + 'static,
+    {
+        let mut worker = self.worker.clone();
+        let handle = self.handle.clone();
+
+        Box::pin(async move {
+            worker
+                .run(move || match &*handle.lock().unwrap() {
+                    Some(blob) => f(blob),
+                    None => Err(other_error("BLOB is closed")),
+                })
+                .await
+        })
+    }
+}
+
+impl AsyncRead for SqliteBlob<'_> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+
+        if let State::Writing(_) = this.state {
+            ready!(this.poll_idle(cx))?;
+        }
+
+        if let State::Idle = this.state {
+            let (offset, len) = this.chunk(buf.len());
+
+            if len == 0 {
+                return Poll::Ready(Ok(0));
+            }
+
+            this.state = State::Reading(this.run(move |blob| {
+                let mut data = vec![0; len as usize];
+
+                // https://www.sqlite.org/c3ref/blob_read.html
+                let status = unsafe {
+                    sqlite3_blob_read(
+                        blob.0.as_ptr(),
+                        data.as_mut_ptr() as *mut c_void,
+                        len,
+                        offset,
+                    )
+                };
+
+                if status != SQLITE_OK {
+                    return Err(blob_error(status));
+                }
+
+                Ok(data)
+            }));
+        }
+
+        if let State::Reading(fut) = &mut this.state {
+            let data = ready!(fut.as_mut().poll(cx));
+            this.state = State::Idle;
+
+            // the buffer may be smaller than in the call that started the read
+            let data = data?;
+            let len = data.len().min(buf.len());
+
+            buf[..len].copy_from_slice(&data[..len]);
+            this.pos += len as u64;
+
+            return Poll::Ready(Ok(len));
+        }
+
+        unreachable!()
+    }
+}
+
+impl AsyncWrite for SqliteBlob<'_> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+
+        if let State::Reading(_) = this.state {
+            ready!(this.poll_idle(cx))?;
+        }
+
+        if let State::Idle = this.state {
+            let (offset, len) = this.chunk(buf.len());
+
+            if len == 0 {
+                // a BLOB can not grow
+                return Poll::Ready(Ok(0));
+            }
+
+            let data = buf[..len as usize].to_vec();
+
+            this.state = State::Writing(this.run(move |blob| {
+                // https://www.sqlite.org/c3ref/blob_write.html
+                let status = unsafe {
+                    sqlite3_blob_write(blob.0.as_ptr(), data.as_ptr() as *const c_void, len, offset)
+                };
+
+                if status != SQLITE_OK {
+                    return Err(blob_error(status));
+                }
+
+                Ok(len as usize)
+            }));
+        }
+
+        // a write started by a previous call; it is assumed that the caller is writing the
+        // same buffer again, as is required after `Poll::Pending`
+        let pos = this.pos;
+        ready!(this.poll_idle(cx))?;
+
+        Poll::Ready(Ok((this.pos - pos) as usize))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        self.poll_idle(cx)
+    }
+
+    #[cfg(feature = "runtime-async-std")]
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+
+    #[cfg(feature = "runtime-tokio")]
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+// The position is kept here, not in SQLite, so seeking only waits for an in-flight read or
+// write; a position past the end is allowed, and reads and writes there return `0`
+fn seek(blob: &mut SqliteBlob<'_>, pos: SeekFrom) -> io::Result<u64> {
+    let pos = match pos {
+        SeekFrom::Start(offset) => Some(offset),
+        SeekFrom::End(offset) => (blob.len as i64).checked_add(offset).map(|pos| pos as u64),
+        SeekFrom::Current(offset) => (blob.pos as i64).checked_add(offset).map(|pos| pos as u64),
+    };
+
+    match pos {
+        Some(pos) if (pos as i64) >= 0 => {
+            blob.pos = pos;
+
+            Ok(pos)
+        }
+
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "invalid seek to a negative or overflowing position",
+        )),
+    }
+}
+
+#[cfg(feature = "runtime-async-std")]
+impl AsyncSeek for SqliteBlob<'_> {
+    fn poll_seek(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+        pos: SeekFrom,
+    ) -> Poll<io::Result<u64>> {
+        ready!(self.poll_idle(cx))?;
+
+        Poll::Ready(seek(&mut self, pos))
+    }
+}
+
+#[cfg(feature = "runtime-tokio")]
+impl AsyncSeek for SqliteBlob<'_> {
+    fn start_seek(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+        pos: SeekFrom,
+    ) -> Poll<io::Result<()>> {
+        ready!(self.poll_idle(cx))?;
+
+        Poll::Ready(seek(&mut self, pos).map(|_| ()))
+    }
+
+    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<u64>> {
+        ready!(self.poll_idle(cx))?;
+
+        Poll::Ready(Ok(self.pos))
+    }
+}
+
+impl Drop for SqliteBlob<'_> {
+    fn drop(&mut self) {
+        // waits for a read or write that is running on the worker; one that has not started
+        // yet will find the BLOB closed
+        if let Some(blob) = self.handle.lock().unwrap().take() {
+            // https://www.sqlite.org/c3ref/blob_close.html
+            unsafe {
+                let _ = sqlite3_blob_close(blob.0.as_ptr());
+            }
+        }
+    }
+}
+
+fn name(name: &str) -> crate::Result<CString> {
+    CString::new(name).map_err(|_| protocol_err!("name {:?} contains a NUL", name).into())
+}
+
+// The error of a read or write; SQLITE_ABORT if the row was changed since the BLOB was opened
+fn blob_error(status: c_int) -> io::Error {
+    other_error(format!(
+        "BLOB read or write failed with SQLite error code {}",
+        status
+    ))
+}
+
+fn other_error<E>(error: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::other(error)
+}
@@ -0,0 +1,205 @@
+use core::ptr::null_mut;
+
+use std::os::raw::{c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::thread;
+use std::time::Duration;
+
+use libsqlite3_sys::sqlite3_busy_handler;
+use rand::{thread_rng, Rng};
+
+use crate::sqlite::SqliteConnection;
+
+// The boxed busy handler of a connection, kept in the connection while it is set
+pub(super) type BusyHandler = Box<dyn FnMut(u32) -> bool + Send>;
+
+/// A policy to retry a statement that finds the database locked by another connection,
+/// with an exponential backoff.
+///
+/// After the `n`th failed attempt, the connection waits for `initial_delay * 2^n`, up to
+/// `max_delay`; with jitter, the wait is picked at random between half of that and all of
+/// it, so that connections that wait for the same lock do not retry all at once.
+///
+/// See [`SqliteConnection::set_busy_retry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SqliteBusyRetry {
+    max_attempts: u32,
+    initial_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+}
+
+impl SqliteBusyRetry {
+    /// Returns the default policy: up to 10 retries, waiting from 1 millisecond up to 100
+    /// milliseconds, with jitter.
+    pub fn new() -> Self {
+        Self {
+            max_attempts: 10,
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(100),
+            jitter: true,
+        }
+    }
+
+    /// Sets the number of retries before the statement fails with `SQLITE_BUSY`.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Sets the wait before the first retry.
+    pub fn initial_delay(mut self, delay: Duration) -> Self {
+        self.initial_delay = delay;
+        self
+    }
+
+    /// Sets the longest wait before a retry.
+    pub fn max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Sets whether the waits are picked at random.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    // The wait after the given number of failed attempts, without jitter
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1_u32.checked_shl(attempt).unwrap_or(u32::MAX);
+
+        self.initial_delay
+            .checked_mul(factor)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay)
+    }
+
+    fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self.backoff(attempt);
+
+        if self.jitter {
+            thread_rng().gen_range(backoff / 2, backoff + Duration::from_nanos(1))
+        } else {
+            backoff
+        }
+    }
+}
+
+impl Default for SqliteBusyRetry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SqliteConnection {
+    /// Sets a handler that is called when a statement finds the database locked by another
+    /// connection, with the number of times it was called before for the same lock; the
+    /// statement is retried if it returns `true`, and fails with `SQLITE_BUSY` otherwise.
+    ///
+    /// The handler runs on the thread of the connection and may block it, e.g. to wait
+    /// before the retry. It must not use the connection. Setting a handler replaces the
+    /// previous one.
+    ///
+    /// The handler is not called for the locks of tables in a shared cache, which fail with
+    /// `SQLITE_LOCKED` right away.
+    ///
+    /// ```rust,ignore
+    /// conn.set_busy_handler(|attempt| {
+    ///     thread::sleep(Duration::from_millis(10));
+    ///     attempt < 5
+    /// });
+    /// ```
+    pub fn set_busy_handler<F>(&mut self, handler: F)
+    where
+        F: FnMut(u32) -> bool + Send + 'static,
+    {
+        let mut handler: Box<BusyHandler> = Box::new(Box::new(handler));
+        let data = &mut *handler as *mut BusyHandler as *mut c_void;
+
+        // https://www.sqlite.org/c3ref/busy_handler.html
+
+        // The previous handler is dropped after SQLite stops calling it
+        unsafe {
+            let _ = sqlite3_busy_handler(self.handle(), Some(call), data);
+        }
+
+        self.busy_handler = Some(handler);
+    }
+
+    /// Retries the statements that find the database locked by another connection,
+    /// following `retry`; a statement fails with `SQLITE_BUSY` once the attempts run out.
+    ///
+    /// ```rust,ignore
+    /// conn.set_busy_retry(
+    ///     SqliteBusyRetry::new()
+    ///         .max_attempts(20)
+    ///         .max_delay(Duration::from_millis(250)),
+    /// );
+    /// ```
+    pub fn set_busy_retry(&mut self, retry: SqliteBusyRetry) {
+        self.set_busy_handler(move |attempt| {
+            if attempt >= retry.max_attempts {
+                return false;
+            }
+
+            thread::sleep(retry.delay(attempt));
+
+            true
+        });
+    }
+
+    /// Removes the handler set by [`set_busy_handler`](#method.set_busy_handler) or
+    /// [`set_busy_retry`](#method.set_busy_retry); a statement that finds the database
+    /// locked then fails with `SQLITE_BUSY` right away.
+    pub fn clear_busy_handler(&mut self) {
+        unsafe {
+            let _ = sqlite3_busy_handler(self.handle(), None, null_mut());
+        }
+
+        self.busy_handler = None;
+    }
+}
+
+unsafe extern "C" fn call(data: *mut c_void, attempt: c_int) -> c_int {
+    let handler = &mut *(data as *mut BusyHandler);
+
+    // A panic must not unwind into SQLite, and fails the statement
+    match catch_unwind(AssertUnwindSafe(|| handler(attempt.max(0) as u32))) {
+        Ok(true) => 1,
+        Ok(false) | Err(_) => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SqliteBusyRetry;
+    use std::time::Duration;
+
+    #[test]
+    fn it_backs_off_exponentially() {
+        let retry = SqliteBusyRetry::new()
+            .initial_delay(Duration::from_millis(2))
+            .max_delay(Duration::from_millis(50));
+
+        assert_eq!(retry.backoff(0), Duration::from_millis(2));
+        assert_eq!(retry.backoff(1), Duration::from_millis(4));
+        assert_eq!(retry.backoff(4), Duration::from_millis(32));
+        assert_eq!(retry.backoff(5), Duration::from_millis(50));
+        assert_eq!(retry.backoff(40), Duration::from_millis(50));
+    }
+
+    #[test]
+    fn it_jitters_within_the_backoff() {
+        let retry = SqliteBusyRetry::new().initial_delay(Duration::from_millis(8));
+
+        for attempt in 0..10 {
+            let backoff = retry.backoff(attempt);
+            let delay = retry.delay(attempt);
+
+            assert!(delay >= backoff / 2 && delay <= backoff);
+        }
+
+        assert_eq!(retry.jitter(false).delay(2), Duration::from_millis(32));
+    }
+}
@@ -0,0 +1,78 @@
+use core::ptr::null;
+
+use std::os::raw::c_int;
+
+use libsqlite3_sys::{
+    sqlite3_wal_checkpoint_v2, SQLITE_CHECKPOINT_FULL, SQLITE_CHECKPOINT_PASSIVE,
+    SQLITE_CHECKPOINT_RESTART, SQLITE_CHECKPOINT_TRUNCATE, SQLITE_OK,
+};
+
+use crate::sqlite::{SqliteConnection, SqliteError};
+
+/// How a checkpoint waits for other connections of the database.
+///
+/// See [`SqliteConnection::checkpoint`] and <https://www.sqlite.org/c3ref/wal_checkpoint_v2.html>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqliteCheckpointMode {
+    /// Checkpoint as many frames as possible without waiting for readers or writers.
+    Passive,
+
+    /// Wait for writers, then checkpoint all frames, waiting for readers as needed.
+    Full,
+
+    /// As `Full`, then wait for readers so that the next writer starts the log over.
+    Restart,
+
+    /// As `Restart`, then also truncate the log file to zero bytes.
+    Truncate,
+}
+
+impl SqliteCheckpointMode {
+    fn code(self) -> c_int {
+        match self {
+            SqliteCheckpointMode::Passive => SQLITE_CHECKPOINT_PASSIVE,
+            SqliteCheckpointMode::Full => SQLITE_CHECKPOINT_FULL,
+            SqliteCheckpointMode::Restart => SQLITE_CHECKPOINT_RESTART,
+            SqliteCheckpointMode::Truncate => SQLITE_CHECKPOINT_TRUNCATE,
+        }
+    }
+}
+
+impl SqliteConnection {
+    /// Copies the frames of the write-ahead log into the database files, to keep the log
+    /// from growing in an application that is never without readers.
+    ///
+    /// Returns the number of frames in the log and the number of them that were
+    /// checkpointed; both are `-1` if the database is not in WAL mode. A checkpoint that has
+    /// to wait fails with `SQLITE_BUSY` once the busy timeout is over.
+    pub async fn checkpoint(&mut self, mode: SqliteCheckpointMode) -> crate::Result<(i32, i32)> {
+        let handle = self.handle;
+
+        self.worker
+            .run(move || {
+                let mut log: c_int = -1;
+                let mut checkpointed: c_int = -1;
+
+                // https://www.sqlite.org/c3ref/wal_checkpoint_v2.html
+
+                // SAFE: the connection is not used elsewhere while the worker runs; a null
+                // database name checkpoints all attached databases
+                let status = unsafe {
+                    sqlite3_wal_checkpoint_v2(
+                        handle.0.as_ptr(),
+                        null(),
+                        mode.code(),
+                        &mut log,
+                        &mut checkpointed,
+                    )
+                };
+
+                if status != SQLITE_OK {
+                    return Err(SqliteError::from_connection(handle.0.as_ptr()).into());
+                }
+
+                Ok((log, checkpointed))
+            })
+            .await
+    }
+}
@@ -0,0 +1,98 @@
+use core::cmp::Ordering;
+use core::slice;
+
+use std::ffi::CString;
+use std::os::raw::{c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use libsqlite3_sys::{sqlite3_create_collation_v2, SQLITE_OK, SQLITE_UTF8};
+
+use crate::sqlite::{SqliteConnection, SqliteError};
+
+// The boxed comparison of a collation, owned by SQLite from its registration until the
+// collation is replaced or the connection is closed
+type Collation = Box<dyn Fn(&str, &str) -> Ordering + Send>;
+
+impl SqliteConnection {
+    /// Registers a Rust comparison function as the collation `name`, for use in `COLLATE`
+    /// clauses of queries, columns and indexes on this connection.
+    ///
+    /// Text that is not valid UTF-8 is compared lossily. A comparison must be consistent
+    /// (e.g. a total order) for indexes and `UNIQUE` constraints that use the collation to
+    /// work. Registering a collation again replaces it.
+    ///
+    /// ```rust,ignore
+    /// conn.create_collation("nocase_unicode", |a, b| a.to_lowercase().cmp(&b.to_lowercase()))?;
+    ///
+    /// let names: Vec<(String,)> = sqlx::query_as("SELECT name FROM users ORDER BY name COLLATE nocase_unicode")
+    ///     .fetch_all(&mut conn)
+    ///     .await?;
+    /// ```
+    pub fn create_collation<F>(&mut self, name: &str, compare: F) -> crate::Result<()>
+    where
+        F: Fn(&str, &str) -> Ordering + Send + 'static,
+    {
+        let name = CString::new(name).map_err(|_| {
+            crate::Error::from(protocol_err!("collation name {:?} contains a NUL", name))
+        })?;
+
+        let collation: Collation = Box::new(compare);
+
+        // https://www.sqlite.org/c3ref/create_collation.html
+
+        // As for functions, SQLite calls [destroy] when the collation is deleted, and also if
+        // registering it fails
+        let status = unsafe {
+            sqlite3_create_collation_v2(
+                self.handle(),
+                name.as_ptr(),
+                SQLITE_UTF8,
+                Box::into_raw(Box::new(collation)) as *mut c_void,
+                Some(compare_text),
+                Some(destroy),
+            )
+        };
+
+        if status != SQLITE_OK {
+            return Err(SqliteError::from_connection(self.handle()).into());
+        }
+
+        Ok(())
+    }
+}
+
+unsafe extern "C" fn compare_text(
+    collation: *mut c_void,
+    a_len: c_int,
+    a: *const c_void,
+    b_len: c_int,
+    b: *const c_void,
+) -> c_int {
+    let collation = &*(collation as *const Collation);
+
+    let a = String::from_utf8_lossy(text(a, a_len));
+    let b = String::from_utf8_lossy(text(b, b_len));
+
+    // A panic must not unwind into SQLite; a comparison can not fail, so the values are
+    // considered equal instead
+    match catch_unwind(AssertUnwindSafe(|| collation(&a, &b))) {
+        Ok(Ordering::Less) => -1,
+        Ok(Ordering::Greater) => 1,
+        Ok(Ordering::Equal) | Err(_) => 0,
+    }
+}
+
+unsafe fn text<'a>(ptr: *const c_void, len: c_int) -> &'a [u8] {
+    if ptr.is_null() || len <= 0 {
+        // Empty text may be received as a null pointer
+        return &[];
+    }
+
+    slice::from_raw_parts(ptr as *const u8, len as usize)
+}
+
+unsafe extern "C" fn destroy(collation: *mut c_void) {
+    if !collation.is_null() {
+        drop(Box::from_raw(collation as *mut Collation));
+    }
+}
@@ -7,8 +7,9 @@ use std::ffi::CString;
 use futures_core::future::BoxFuture;
 use futures_util::future;
 use libsqlite3_sys::{
-    sqlite3, sqlite3_close, sqlite3_extended_result_codes, sqlite3_open_v2, SQLITE_OK,
-    SQLITE_OPEN_CREATE, SQLITE_OPEN_NOMUTEX, SQLITE_OPEN_READWRITE, SQLITE_OPEN_SHAREDCACHE,
+    sqlite3, sqlite3_close, sqlite3_db_readonly, sqlite3_extended_result_codes, sqlite3_open_v2,
+    SQLITE_OK, SQLITE_OPEN_CREATE, SQLITE_OPEN_NOMUTEX, SQLITE_OPEN_READWRITE,
+    SQLITE_OPEN_SHAREDCACHE, SQLITE_OPEN_URI,
 };
 
 use crate::connection::{Connect, Connection};
@@ -16,7 +17,7 @@ use crate::executor::Executor;
 use crate::sqlite::statement::Statement;
 use crate::sqlite::worker::Worker;
 
-use crate::sqlite::SqliteError;
+use crate::sqlite::{SqliteError, SqliteInterruptHandle, SqliteLimit};
 use crate::url::Url;
 
 /// Thin wrapper around [sqlite3] to impl `Send`.
@@ -24,6 +25,98 @@ use crate::url::Url;
 pub(super) struct SqliteConnectionHandle(pub(super) NonNull<sqlite3>);
 
 /// A connection to a [Sqlite](struct.Sqlite.html) database.
+///
+/// ### Encryption
+///
+/// With the `sqlcipher` feature, SQLx links against [SQLCipher] instead of SQLite, and
+/// the `key` parameter of the URL is the passphrase of an encrypted database. The database
+/// is decrypted as it is opened; a wrong key fails to connect. For example:
+///
+/// ```text
+/// sqlite://data.db?key=correct%20horse
+/// ```
+///
+/// [SQLCipher]: https://www.zetetic.net/sqlcipher/
+///
+/// ### Write-Ahead Log
+///
+/// Databases are opened in [WAL mode], where SQLite copies the log into the database
+/// (a checkpoint) when a commit grows it past 1000 pages. The `wal_autocheckpoint`
+/// parameter of the URL sets that number of pages, and `0` turns automatic checkpoints off;
+/// [`checkpoint`](#method.checkpoint) runs one explicitly.
+///
+/// [WAL mode]: https://www.sqlite.org/wal.html
+///
+/// ### PRAGMAs
+///
+/// Each `pragma.<name>=<value>` parameter of the URL sets a [PRAGMA] as the connection
+/// connects, in the order of the URL and after the defaults of SQLx, so that they can be
+/// overridden. For example:
+///
+/// ```text
+/// sqlite://data.db?pragma.mmap_size=268435456&pragma.cache_size=-64000&pragma.temp_store=memory
+/// ```
+///
+/// [PRAGMA]: https://www.sqlite.org/pragma.html
+///
+/// ### Limits
+///
+/// For connections that run SQL of untrusted sources, each `limit.<name>=<value>` parameter
+/// of the URL sets a [run-time limit], with its name in lower case and without the
+/// `SQLITE_LIMIT_` prefix, and `defensive=true` turns on the [defensive mode]. For example:
+///
+/// ```text
+/// sqlite://data.db?limit.sql_length=100000&limit.expr_depth=100&limit.attached=0&defensive=true
+/// ```
+///
+/// [run-time limit]: https://www.sqlite.org/c3ref/c_limit_attached.html
+/// [defensive mode]: https://www.sqlite.org/c3ref/c_dbconfig_defensive.html#sqlitedbconfigdefensive
+///
+/// ### Attached Databases
+///
+/// Each `attach` parameter of the URL, `<schema>:<path>`, attaches another database file
+/// to the connection as it connects (after the `main` database is set up), so all
+/// connections of a pool have the same databases attached. For example:
+///
+/// ```text
+/// sqlite://data.db?attach=archive:archive.db&attach=cache:%3Amemory%3A
+/// ```
+///
+/// ### Shared In-Memory Databases
+///
+/// Each connection to `:memory:` has its own, empty database, so the connections of a
+/// pool do not see each other's data. A [URI filename] that names an in-memory database
+/// with a shared cache is shared by all connections of the process that open it:
+///
+/// ```text
+/// sqlite:file:memdb1?mode=memory&cache=shared
+/// ```
+///
+/// The database is deleted when its last connection is closed; set a `min_size` on the
+/// pool to keep it.
+///
+/// [URI filename]: https://www.sqlite.org/uri.html
+///
+/// ### Read-Only Connections
+///
+/// A [URI filename] with `mode=ro` opens the database read-only, and one with `immutable=1`
+/// also reads it without locks, for a database that nothing changes (e.g. on read-only
+/// media). Read-only connections do not set the journal mode of the database.
+///
+/// As SQLite allows a single writer at a time, a pool of one connection can write to a
+/// database while a larger pool of read-only connections reads it, in WAL mode:
+///
+/// ```rust,ignore
+/// let writer = SqlitePool::builder()
+///     .max_size(1)
+///     .build("sqlite:file:data.db")
+///     .await?;
+///
+/// let readers = SqlitePool::builder()
+///     .max_size(8)
+///     .build("sqlite:file:data.db?mode=ro")
+///     .await?;
+/// ```
 pub struct SqliteConnection {
     pub(super) handle: SqliteConnectionHandle,
     pub(super) worker: Worker,
@@ -32,6 +125,17 @@ pub struct SqliteConnection {
     // Storage of persistent statements
     pub(super) statements: Vec<Statement>,
     pub(super) statement_by_query: HashMap<String, usize>,
+    // Shared with the handles of [SqliteConnection::interrupt_handle]
+    pub(super) interrupt: SqliteInterruptHandle,
+    // The handler set by [SqliteConnection::set_progress_handler]
+    pub(super) progress_handler: Option<Box<crate::sqlite::interrupt::ProgressHandler>>,
+    // The authorizer set by [SqliteConnection::set_authorizer]
+    pub(super) authorizer: Option<Box<crate::sqlite::authorizer::Authorizer>>,
+    // The handler set by [SqliteConnection::set_busy_handler]
+    pub(super) busy_handler: Option<Box<crate::sqlite::busy::BusyHandler>>,
+    // The hook set by [SqliteConnection::set_preupdate_hook]
+    #[cfg(feature = "sqlite-preupdate-hook")]
+    pub(super) preupdate_hook: Option<Box<crate::sqlite::preupdate::PreupdateHook>>,
 }
 
 // A SQLite3 handle is safe to send between threads, provided not more than
@@ -45,12 +149,12 @@ pub struct SqliteConnection {
 
 unsafe impl Send for SqliteConnectionHandle {}
 
-async fn establish(url: Result<Url, url::ParseError>) -> crate::Result<SqliteConnection> {
+async fn establish(url: Url) -> crate::Result<SqliteConnection> {
     let mut worker = Worker::new();
 
     // By default, we connect to an in-memory database.
     // TODO: Handle the error when there are internal NULs in the database URL
-    let filename = CString::new(url?.path_decoded().to_string()).unwrap();
+    let filename = CString::new(url.path_decoded().to_string()).unwrap();
 
     let handle = worker
         .run(move || -> crate::Result<SqliteConnectionHandle> {
@@ -58,10 +162,14 @@ async fn establish(url: Result<Url, url::ParseError>) -> crate::Result<SqliteCon
 
             // [SQLITE_OPEN_NOMUTEX] will instruct [sqlite3_open_v2] to return an error if it
             // cannot satisfy our wish for a thread-safe, lock-free connection object
+
+            // [SQLITE_OPEN_URI] interprets a filename of `file:...` as a URI, whether or
+            // not SQLite is compiled with SQLITE_USE_URI
             let flags = SQLITE_OPEN_READWRITE
                 | SQLITE_OPEN_CREATE
                 | SQLITE_OPEN_NOMUTEX
-                | SQLITE_OPEN_SHAREDCACHE;
+                | SQLITE_OPEN_SHAREDCACHE
+                | SQLITE_OPEN_URI;
 
             // <https://www.sqlite.org/c3ref/open.html>
             let status = unsafe { sqlite3_open_v2(filename.as_ptr(), &mut handle, flags, null()) };
@@ -97,6 +205,12 @@ async fn establish(url: Result<Url, url::ParseError>) -> crate::Result<SqliteCon
         statement: None,
         statements: Vec::with_capacity(10),
         statement_by_query: HashMap::with_capacity(10),
+        interrupt: SqliteInterruptHandle::new(handle),
+        progress_handler: None,
+        authorizer: None,
+        busy_handler: None,
+        #[cfg(feature = "sqlite-preupdate-hook")]
+        preupdate_hook: None,
     })
 }
 
@@ -105,6 +219,13 @@ impl SqliteConnection {
     pub(super) fn handle(&mut self) -> *mut sqlite3 {
         self.handle.0.as_ptr()
     }
+
+    /// Returns `true` if the `main` database of the connection is read-only, e.g. as it is
+    /// opened with `mode=ro` or `immutable=1`.
+    pub fn is_read_only(&self) -> bool {
+        // https://www.sqlite.org/c3ref/db_readonly.html
+        unsafe { sqlite3_db_readonly(self.handle.0.as_ptr(), b"main\0".as_ptr() as *const _) == 1 }
+    }
 }
 
 impl Connect for SqliteConnection {
@@ -116,24 +237,171 @@ impl Connect for SqliteConnection {
         let url = url.try_into();
 
         Box::pin(async move {
+            let mut url = url?;
+
+            // the parameters are not part of the filename
+            let key = url.take_param("key");
+            let attach = url.take_params("attach");
+            let pragmas = url.take_params_with_prefix("pragma.");
+            let limits = url.take_params_with_prefix("limit.");
+            let defensive = match url.take_param("defensive").as_deref() {
+                Some("true") | Some("1") => Some(true),
+                Some("false") | Some("0") => Some(false),
+                Some(value) => {
+                    return Err(protocol_err!("invalid `defensive` value: {:?}", value).into());
+                }
+                None => None,
+            };
+            let wal_autocheckpoint = match url.take_param("wal_autocheckpoint") {
+                Some(value) => Some(value.parse::<u32>().map_err(|_| {
+                    crate::Error::from(protocol_err!(
+                        "invalid `wal_autocheckpoint` value: {:?}",
+                        value
+                    ))
+                })?),
+
+                None => None,
+            };
+
             let mut conn = establish(url).await?;
 
+            // the limits apply to all statements, including those that set up the connection
+            for (name, value) in limits {
+                let limit = SqliteLimit::from_name(&name).ok_or_else(|| {
+                    crate::Error::from(protocol_err!("unknown SQLite limit: {:?}", name))
+                })?;
+
+                let value = value.parse::<u32>().map_err(|_| {
+                    crate::Error::from(protocol_err!("invalid `limit.{}` value: {:?}", name, value))
+                })?;
+
+                conn.set_limit(limit, value);
+            }
+
+            if let Some(defensive) = defensive {
+                conn.set_defensive(defensive)?;
+            }
+
+            if let Some(key) = key {
+                set_key(&mut conn, &key).await?;
+            }
+
             // https://www.sqlite.org/wal.html
 
-            // language=SQLite
-            conn.execute(
-                r#"
+            // The journal mode is kept in the database file, so a read-only connection can not
+            // set it, and reads it as it was set by the writers
+            if !conn.is_read_only() {
+                // language=SQLite
+                conn.execute(
+                    r#"
 PRAGMA journal_mode = WAL;
 PRAGMA synchronous = NORMAL;
-                "#,
-            )
-            .await?;
+                    "#,
+                )
+                .await?;
+            }
+
+            // https://www.sqlite.org/pragma.html#pragma_wal_autocheckpoint
+            if let Some(pages) = wal_autocheckpoint {
+                conn.execute(&*format!("PRAGMA wal_autocheckpoint = {}", pages))
+                    .await?;
+            }
+
+            for (name, value) in pragmas {
+                set_pragma(&mut conn, &name, &value).await?;
+            }
+
+            for attach in attach {
+                attach_database(&mut conn, &attach).await?;
+            }
 
             Ok(conn)
         })
     }
 }
 
+// Sets a PRAGMA of a `pragma.<name>=<value>` parameter; the value is passed as it is if it is a
+// number or a keyword, and as a string otherwise
+
+// https://www.sqlite.org/pragma.html
+async fn set_pragma(conn: &mut SqliteConnection, name: &str, value: &str) -> crate::Result<()> {
+    fn is_word(value: &str) -> bool {
+        !value.is_empty()
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '+' | '.'))
+    }
+
+    // the name may be qualified by the schema, e.g. `aux.cache_size`
+    if !is_word(name) || name.contains(&['-', '+'][..]) {
+        return Err(protocol_err!("invalid PRAGMA name: {:?}", name).into());
+    }
+
+    let statement = if is_word(value) {
+        format!("PRAGMA {} = {}", name, value)
+    } else {
+        format!("PRAGMA {} = '{}'", name, value.replace('\'', "''"))
+    };
+
+    conn.execute(&*statement).await?;
+
+    Ok(())
+}
+
+// Attaches the database of an `attach` parameter, `<schema>:<path>`
+
+// https://www.sqlite.org/lang_attach.html
+async fn attach_database(conn: &mut SqliteConnection, attach: &str) -> crate::Result<()> {
+    let mut parts = attach.splitn(2, ':');
+
+    let (schema, path) = match (parts.next(), parts.next()) {
+        (Some(schema), Some(path)) if !schema.is_empty() && !path.is_empty() => (schema, path),
+
+        _ => {
+            return Err(protocol_err!(
+                "invalid `attach` value: {:?}; expected `<schema>:<path>`",
+                attach
+            )
+            .into());
+        }
+    };
+
+    let statement = format!(
+        "ATTACH DATABASE '{}' AS \"{}\"",
+        path.replace('\'', "''"),
+        schema.replace('"', "\"\"")
+    );
+
+    conn.execute(&*statement).await?;
+
+    Ok(())
+}
+
+// Decrypts the database with SQLCipher; this must come before anything else reads it
+
+// https://www.zetetic.net/sqlcipher/sqlcipher-api/#PRAGMA_key
+#[cfg(feature = "sqlcipher")]
+async fn set_key(conn: &mut SqliteConnection, key: &str) -> crate::Result<()> {
+    // the key is not kept in the statement cache
+    let pragma = format!("PRAGMA key = '{}'", key.replace('\'', "''"));
+
+    conn.execute(crate::query::query(&pragma).persistent(false))
+        .await?;
+
+    // a wrong key is only noticed when the database is read, as
+    // "file is not a database"
+    conn.execute("SELECT count(*) FROM sqlite_master").await?;
+
+    Ok(())
+}
+
+// Without SQLCipher, `PRAGMA key` is ignored and the database would be read (or created)
+// unencrypted
+#[cfg(not(feature = "sqlcipher"))]
+async fn set_key(_conn: &mut SqliteConnection, _key: &str) -> crate::Result<()> {
+    Err(protocol_err!("the `key` parameter requires the `sqlcipher` feature of SQLx").into())
+}
+
 impl Connection for SqliteConnection {
     fn close(self) -> BoxFuture<'static, crate::Result<()>> {
         // All necessary behavior is handled on drop
@@ -152,6 +420,9 @@ impl Drop for SqliteConnection {
         self.statements.clear();
         drop(self.statement.take());
 
+        // Interrupt handles must not use the connection once it is closed
+        self.interrupt.close();
+
         // Next close the statement
         // https://sqlite.org/c3ref/close.html
         unsafe {
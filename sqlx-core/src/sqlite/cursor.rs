@@ -11,6 +11,7 @@ pub struct SqliteCursor<'c, 'q> {
     pub(super) source: ConnectionSource<'c, SqliteConnection>,
     query: &'q str,
     arguments: Option<SqliteArguments>,
+    persistent: bool,
     pub(super) statement: Option<Option<usize>>,
 }
 
@@ -25,6 +26,7 @@ impl<'c, 'q> Cursor<'c, 'q> for SqliteCursor<'c, 'q> {
         Self: Sized,
         E: Execute<'q, Sqlite>,
     {
+        let persistent = query.persistent();
         let (query, arguments) = query.into_parts();
 
         Self {
@@ -32,6 +34,7 @@ impl<'c, 'q> Cursor<'c, 'q> for SqliteCursor<'c, 'q> {
             statement: None,
             query,
             arguments,
+            persistent,
         }
     }
 
@@ -41,6 +44,7 @@ impl<'c, 'q> Cursor<'c, 'q> for SqliteCursor<'c, 'q> {
         Self: Sized,
         E: Execute<'q, Sqlite>,
     {
+        let persistent = query.persistent();
         let (query, arguments) = query.into_parts();
 
         Self {
@@ -48,6 +52,7 @@ impl<'c, 'q> Cursor<'c, 'q> for SqliteCursor<'c, 'q> {
             statement: None,
             query,
             arguments,
+            persistent,
         }
     }
 
@@ -63,7 +68,10 @@ async fn next<'a, 'c: 'a, 'q: 'a>(
 
     loop {
         if cursor.statement.is_none() {
-            let key = conn.prepare(&mut cursor.query, cursor.arguments.is_some())?;
+            let key = conn.prepare(
+                &mut cursor.query,
+                cursor.arguments.is_some() && cursor.persistent,
+            )?;
 
             if let Some(arguments) = &mut cursor.arguments {
                 conn.statement_mut(key).bind(arguments)?;
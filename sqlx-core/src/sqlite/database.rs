@@ -6,6 +6,7 @@ use crate::sqlite::{
     SqliteArgumentValue, SqliteArguments, SqliteConnection, SqliteCursor, SqliteRow,
     SqliteTypeInfo, SqliteValue,
 };
+use crate::transaction::{TransactionBehavior, TransactionOptions};
 use crate::value::HasRawValue;
 
 /// **Sqlite** database driver.
@@ -24,6 +25,21 @@ impl Database for Sqlite {
     type RawBuffer = Vec<SqliteArgumentValue>;
 
     type Error = SqliteError;
+
+    // transactions are always serializable
+    fn begin_transaction(options: &TransactionOptions) -> crate::Result<Vec<String>> {
+        if options.read_only {
+            return Err(protocol_err!("SQLite does not support read-only transactions").into());
+        }
+
+        Ok(vec![match options.behavior {
+            None => "BEGIN",
+            Some(TransactionBehavior::Deferred) => "BEGIN DEFERRED",
+            Some(TransactionBehavior::Immediate) => "BEGIN IMMEDIATE",
+            Some(TransactionBehavior::Exclusive) => "BEGIN EXCLUSIVE",
+        }
+        .to_owned()])
+    }
 }
 
 impl<'c> HasRow<'c> for Sqlite {
@@ -1,7 +1,9 @@
 use crate::error::DatabaseError;
 
 use bitflags::_core::str::from_utf8_unchecked;
-use libsqlite3_sys::{sqlite3, sqlite3_errmsg, sqlite3_extended_errcode};
+use libsqlite3_sys::{
+    sqlite3, sqlite3_errmsg, sqlite3_extended_errcode, SQLITE_BUSY, SQLITE_LOCKED,
+};
 use std::error::Error as StdError;
 use std::ffi::CStr;
 use std::fmt::{self, Display};
@@ -32,6 +34,30 @@ impl SqliteError {
             message: message.to_owned(),
         }
     }
+
+    /// Returns `true` if the database is locked by another connection (`SQLITE_BUSY`), e.g.
+    /// by a write transaction, and the busy handler of the connection gave up.
+    ///
+    /// See [`SqliteConnection::set_busy_retry`][set_busy_retry].
+    ///
+    /// [set_busy_retry]: struct.SqliteConnection.html#method.set_busy_retry
+    pub fn is_busy(&self) -> bool {
+        self.primary_code() == Some(SQLITE_BUSY)
+    }
+
+    /// Returns `true` if a table is locked by a connection with the same shared cache, or by
+    /// another statement of the same connection (`SQLITE_LOCKED`); this is not retried by the
+    /// busy handler.
+    pub fn is_locked(&self) -> bool {
+        self.primary_code() == Some(SQLITE_LOCKED)
+    }
+
+    // The extended result code without its extension, e.g. SQLITE_BUSY for SQLITE_BUSY_SNAPSHOT
+
+    // https://www.sqlite.org/rescode.html#primary_result_codes_versus_extended_result_codes
+    fn primary_code(&self) -> Option<c_int> {
+        self.code.parse::<c_int>().ok().map(|code| code & 0xff)
+    }
 }
 
 impl Display for SqliteError {
@@ -93,3 +119,21 @@ fn test_error_downcasting() {
         "SQLITE_ERR_SOMETHING"
     );
 }
+
+#[test]
+fn test_error_is_busy_or_locked() {
+    let error = |code: c_int| SqliteError {
+        code: code.to_string(),
+        message: "database is locked".into(),
+    };
+
+    assert!(error(SQLITE_BUSY).is_busy());
+    assert!(!error(SQLITE_BUSY).is_locked());
+
+    // SQLITE_LOCKED_SHAREDCACHE
+    assert!(error(SQLITE_LOCKED | (1 << 8)).is_locked());
+    assert!(!error(SQLITE_LOCKED | (1 << 8)).is_busy());
+
+    assert!(!error(1).is_busy());
+    assert!(!error(1).is_locked());
+}
@@ -92,11 +92,12 @@ impl Executor for SqliteConnection {
         E: Execute<'q, Self::Database>,
     {
         log_execution!(query, {
+            let persistent = query.persistent();
             let (mut query, mut arguments) = query.into_parts();
 
             Box::pin(async move {
                 loop {
-                    let key = self.prepare(&mut query, arguments.is_some())?;
+                    let key = self.prepare(&mut query, arguments.is_some() && persistent)?;
                     let statement = self.statement_mut(key);
 
                     if let Some(arguments) = &mut arguments {
@@ -0,0 +1,168 @@
+use crate::cursor::Cursor;
+use crate::executor::Execute;
+use crate::row::Row;
+use crate::sqlite::{Sqlite, SqliteConnection};
+
+// https://www.sqlite.org/eqp.html
+
+impl SqliteConnection {
+    /// Show the plan that SQLite chooses for a query, with `EXPLAIN QUERY PLAN`.
+    ///
+    /// The query is not run. The plan is a tree of the steps of the query, e.g. to assert in a
+    /// test that an index is used:
+    ///
+    /// ```rust,ignore
+    /// let plan = conn
+    ///     .explain_query_plan(sqlx::query("SELECT * FROM users WHERE email = ?").bind(email))
+    ///     .await?;
+    ///
+    /// assert!(plan.iter().any(|node| node.index() == Some("users_email")));
+    /// ```
+    pub async fn explain_query_plan<'q, E>(&mut self, query: E) -> crate::Result<SqliteQueryPlan>
+    where
+        E: Execute<'q, Sqlite>,
+    {
+        let (query, arguments) = query.into_parts();
+        let statement = format!("EXPLAIN QUERY PLAN {}", query);
+
+        // the statement is different for every query explained and is not worth caching
+        let mut cursor = match arguments {
+            Some(arguments) => crate::query::query(&statement)
+                .bind_all(arguments)
+                .persistent(false)
+                .fetch(&mut *self),
+
+            None => crate::executor::Executor::fetch(&mut *self, &*statement),
+        };
+
+        let mut rows = Vec::new();
+
+        // the columns are `id`, `parent`, `notused` and `detail`
+        while let Some(row) = cursor.next().await? {
+            rows.push((
+                row.try_get::<i32, _>(0)?,
+                row.try_get::<i32, _>(1)?,
+                row.try_get::<String, _>(3)?,
+            ));
+        }
+
+        Ok(SqliteQueryPlan::from_rows(rows))
+    }
+}
+
+/// The plan of a query, from [`SqliteConnection::explain_query_plan`].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct SqliteQueryPlan {
+    /// The top-level steps of the plan, in the order they are run.
+    pub nodes: Vec<SqlitePlanNode>,
+}
+
+/// A step of the plan of a query, such as the scan of a table or a subquery.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct SqlitePlanNode {
+    /// The identifier of the step within the plan.
+    pub id: i32,
+
+    /// The description of the step, e.g. `SCAN TABLE users` or
+    /// `SEARCH TABLE users USING INDEX users_email (email=?)`.
+    pub detail: String,
+
+    /// The steps that are part of this one.
+    pub children: Vec<SqlitePlanNode>,
+}
+
+impl SqliteQueryPlan {
+    // Builds the tree from the `(id, parent, detail)` of each row; a row comes after its
+    // parent, and the parent of a top-level row is `0`
+    fn from_rows(rows: Vec<(i32, i32, String)>) -> Self {
+        fn children(rows: &[(i32, i32, String)], parent: i32) -> Vec<SqlitePlanNode> {
+            rows.iter()
+                .filter(|row| row.1 == parent)
+                .map(|(id, _, detail)| SqlitePlanNode {
+                    id: *id,
+                    detail: detail.clone(),
+                    children: children(rows, *id),
+                })
+                .collect()
+        }
+
+        SqliteQueryPlan {
+            nodes: children(&rows, 0),
+        }
+    }
+
+    /// Returns an iterator over all nodes of the plan, depth-first.
+    pub fn iter(&self) -> impl Iterator<Item = &SqlitePlanNode> {
+        let mut stack: Vec<_> = self.nodes.iter().rev().collect();
+
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            stack.extend(node.children.iter().rev());
+
+            Some(node)
+        })
+    }
+}
+
+impl SqlitePlanNode {
+    /// Returns true if the step visits every row of a table or an index, rather than
+    /// searching for a subset of them.
+    pub fn is_scan(&self) -> bool {
+        self.detail.starts_with("SCAN ")
+    }
+
+    /// Returns the name of the index that the step uses, if any.
+    pub fn index(&self) -> Option<&str> {
+        let start = self
+            .detail
+            .find("USING INDEX ")
+            .map(|i| i + "USING INDEX ".len())
+            .or_else(|| {
+                self.detail
+                    .find("USING COVERING INDEX ")
+                    .map(|i| i + "USING COVERING INDEX ".len())
+            })?;
+
+        self.detail[start..].split(' ').next()
+    }
+}
+
+#[test]
+fn test_plan_from_rows() {
+    let plan = SqliteQueryPlan::from_rows(vec![
+        (
+            2,
+            0,
+            "SEARCH TABLE a USING INDEX a_name (name=?)".to_owned(),
+        ),
+        (6, 0, "SCAN TABLE b".to_owned()),
+        (9, 0, "CORRELATED SCALAR SUBQUERY 1".to_owned()),
+        (
+            13,
+            9,
+            "SEARCH TABLE c USING COVERING INDEX c_b (b=?)".to_owned(),
+        ),
+        (18, 0, "USE TEMP B-TREE FOR ORDER BY".to_owned()),
+    ]);
+
+    assert_eq!(plan.nodes.len(), 4);
+    assert_eq!(plan.nodes[2].children.len(), 1);
+
+    let nodes: Vec<_> = plan
+        .iter()
+        .map(|node| (node.id, node.is_scan(), node.index()))
+        .collect();
+
+    assert_eq!(
+        nodes,
+        vec![
+            (2, false, Some("a_name")),
+            (6, true, None),
+            (9, false, None),
+            (13, false, Some("c_b")),
+            (18, false, None),
+        ]
+    );
+}
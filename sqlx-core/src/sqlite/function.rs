@@ -0,0 +1,438 @@
+use core::mem::size_of;
+use core::ptr::null_mut;
+use core::slice;
+
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use libsqlite3_sys::{
+    sqlite3_aggregate_context, sqlite3_context, sqlite3_create_function_v2,
+    sqlite3_create_window_function, sqlite3_result_blob, sqlite3_result_double,
+    sqlite3_result_error, sqlite3_result_error_nomem, sqlite3_result_int, sqlite3_result_int64,
+    sqlite3_result_null, sqlite3_result_text, sqlite3_user_data, sqlite3_value,
+    SQLITE_DETERMINISTIC, SQLITE_OK, SQLITE_TRANSIENT, SQLITE_UTF8,
+};
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::sqlite::{Sqlite, SqliteArgumentValue, SqliteConnection, SqliteError, SqliteValue};
+use crate::types::Type;
+
+/// The arguments of a call to a user-defined function.
+///
+/// See [`SqliteConnection::create_function`].
+pub struct SqliteFunctionArguments<'a> {
+    values: &'a [*mut sqlite3_value],
+}
+
+impl<'a> SqliteFunctionArguments<'a> {
+    /// Returns the number of arguments of the call.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns true if the function was called without arguments.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Decodes the argument at `index`, as a column of a row is decoded.
+    pub fn get<T>(&self, index: usize) -> crate::Result<T>
+    where
+        T: Type<Sqlite>,
+        T: Decode<'a, Sqlite>,
+    {
+        let value = self
+            .values
+            .get(index)
+            .ok_or_else(|| crate::Error::ColumnIndexOutOfBounds {
+                index,
+                len: self.values.len(),
+            })?;
+
+        // SAFE: the argument is valid for the duration of the call
+        T::decode(SqliteValue::argument(unsafe { &**value }))
+    }
+}
+
+/// A user-defined aggregate function, computed over the rows of each group of a query.
+///
+/// A new value of the aggregate is created for each group, passed the arguments of each of
+/// its rows in [`step`](#tymethod.step) and then turned into the result of the group by
+/// [`finalize`](#tymethod.finalize).
+///
+/// An aggregate that also implements [`inverse`](#method.inverse) and
+/// [`value`](#method.value) may be registered as a window function, with
+/// [`SqliteConnection::create_window_function`], and used with an `OVER` clause.
+pub trait Aggregate: Send + 'static {
+    /// The type of the result of the aggregate.
+    type Output: Type<Sqlite> + Encode<Sqlite>;
+
+    /// Adds the arguments of a row to the aggregate.
+    fn step(&mut self, arguments: &SqliteFunctionArguments<'_>) -> crate::Result<()>;
+
+    /// Returns the result of the aggregate, after its last row.
+    fn finalize(self) -> crate::Result<Self::Output>;
+
+    /// Removes the arguments of a row, which were passed to [`step`](#tymethod.step)
+    /// before, as the row leaves the window frame.
+    fn inverse(&mut self, arguments: &SqliteFunctionArguments<'_>) -> crate::Result<()> {
+        let _ = arguments;
+
+        Err(protocol_err!("aggregate function can not be used as a window function").into())
+    }
+
+    /// Returns the result of the aggregate for the current window frame, without ending it.
+    fn value(&self) -> crate::Result<Self::Output> {
+        Err(protocol_err!("aggregate function can not be used as a window function").into())
+    }
+}
+
+// The boxed closure of a function, owned by SQLite from its registration until the function
+// is replaced or the connection is closed
+type Function =
+    Box<dyn Fn(&SqliteFunctionArguments<'_>) -> crate::Result<SqliteArgumentValue> + Send>;
+
+impl SqliteConnection {
+    /// Registers a Rust closure as a scalar SQL function, `name`, of `arity` arguments (or
+    /// any number of arguments when `arity` is `-1`).
+    ///
+    /// The closure is called for each invocation of the function in a query on this
+    /// connection and returns its result; an `Err` (or a panic) fails the query with the
+    /// message of the error. Registering a function again (of the same name and arity)
+    /// replaces it.
+    ///
+    /// ```rust,ignore
+    /// conn.create_function("add_one", 1, |args| Ok(args.get::<i64>(0)? + 1))?;
+    ///
+    /// let (value,): (i64,) = sqlx::query_as("SELECT add_one(41)")
+    ///     .fetch_one(&mut conn)
+    ///     .await?;
+    /// ```
+    pub fn create_function<F, R>(&mut self, name: &str, arity: i32, f: F) -> crate::Result<()>
+    where
+        F: Fn(&SqliteFunctionArguments<'_>) -> crate::Result<R> + Send + 'static,
+        R: Type<Sqlite> + Encode<Sqlite>,
+    {
+        self.register_function(name, arity, false, f)
+    }
+
+    /// Registers a Rust closure as a deterministic scalar SQL function, one that always
+    /// returns the same result for the same arguments.
+    ///
+    /// Unlike other functions, a deterministic function may be used in the expressions of
+    /// indexes, generated columns and `CHECK` constraints, and SQLite may evaluate it fewer
+    /// times than it appears in a query. See [`create_function`](#method.create_function).
+    pub fn create_deterministic_function<F, R>(
+        &mut self,
+        name: &str,
+        arity: i32,
+        f: F,
+    ) -> crate::Result<()>
+    where
+        F: Fn(&SqliteFunctionArguments<'_>) -> crate::Result<R> + Send + 'static,
+        R: Type<Sqlite> + Encode<Sqlite>,
+    {
+        self.register_function(name, arity, true, f)
+    }
+
+    /// Registers an aggregate SQL function, `name`, of `arity` arguments (or any number of
+    /// arguments when `arity` is `-1`), computed by the [`Aggregate`] that `init` returns for
+    /// each group.
+    ///
+    /// ```rust,ignore
+    /// conn.create_aggregate("median", 1, Median::default)?;
+    ///
+    /// let (median,): (f64,) = sqlx::query_as("SELECT median(price) FROM items")
+    ///     .fetch_one(&mut conn)
+    ///     .await?;
+    /// ```
+    pub fn create_aggregate<F, A>(&mut self, name: &str, arity: i32, init: F) -> crate::Result<()>
+    where
+        F: Fn() -> A + Send + 'static,
+        A: Aggregate,
+    {
+        self.register_aggregate(name, arity, false, init)
+    }
+
+    /// Registers an aggregate SQL function that may also be used as a window function, with
+    /// an `OVER` clause.
+    ///
+    /// The [`Aggregate`] must implement [`inverse`](trait.Aggregate.html#method.inverse) and
+    /// [`value`](trait.Aggregate.html#method.value). See
+    /// [`create_aggregate`](#method.create_aggregate).
+    pub fn create_window_function<F, A>(
+        &mut self,
+        name: &str,
+        arity: i32,
+        init: F,
+    ) -> crate::Result<()>
+    where
+        F: Fn() -> A + Send + 'static,
+        A: Aggregate,
+    {
+        self.register_aggregate(name, arity, true, init)
+    }
+
+    fn register_function<F, R>(
+        &mut self,
+        name: &str,
+        arity: i32,
+        deterministic: bool,
+        f: F,
+    ) -> crate::Result<()>
+    where
+        F: Fn(&SqliteFunctionArguments<'_>) -> crate::Result<R> + Send + 'static,
+        R: Type<Sqlite> + Encode<Sqlite>,
+    {
+        let name = function_name(name)?;
+
+        let function: Function = Box::new(move |arguments| f(arguments).map(encode_result));
+
+        let mut flags = SQLITE_UTF8;
+        if deterministic {
+            flags |= SQLITE_DETERMINISTIC;
+        }
+
+        // https://www.sqlite.org/c3ref/create_function.html
+
+        // The user data is a thin pointer to the boxed closure; SQLite calls [destroy] when the
+        // function is deleted, and also if registering it fails
+        let status = unsafe {
+            sqlite3_create_function_v2(
+                self.handle(),
+                name.as_ptr(),
+                arity,
+                flags,
+                Box::into_raw(Box::new(function)) as *mut c_void,
+                Some(call),
+                None,
+                None,
+                Some(destroy::<Function>),
+            )
+        };
+
+        if status != SQLITE_OK {
+            return Err(SqliteError::from_connection(self.handle()).into());
+        }
+
+        Ok(())
+    }
+
+    fn register_aggregate<F, A>(
+        &mut self,
+        name: &str,
+        arity: i32,
+        window: bool,
+        init: F,
+    ) -> crate::Result<()>
+    where
+        F: Fn() -> A + Send + 'static,
+        A: Aggregate,
+    {
+        let name = function_name(name)?;
+
+        let init: Init<A> = Box::new(init);
+        let init = Box::into_raw(Box::new(init)) as *mut c_void;
+
+        // https://www.sqlite.org/c3ref/create_function.html
+        let status = unsafe {
+            if window {
+                sqlite3_create_window_function(
+                    self.handle(),
+                    name.as_ptr(),
+                    arity,
+                    SQLITE_UTF8,
+                    init,
+                    Some(step::<A>),
+                    Some(finalize::<A>),
+                    Some(value::<A>),
+                    Some(inverse::<A>),
+                    Some(destroy::<Init<A>>),
+                )
+            } else {
+                sqlite3_create_function_v2(
+                    self.handle(),
+                    name.as_ptr(),
+                    arity,
+                    SQLITE_UTF8,
+                    init,
+                    None,
+                    Some(step::<A>),
+                    Some(finalize::<A>),
+                    Some(destroy::<Init<A>>),
+                )
+            }
+        };
+
+        if status != SQLITE_OK {
+            return Err(SqliteError::from_connection(self.handle()).into());
+        }
+
+        Ok(())
+    }
+}
+
+// The boxed constructor of the aggregate of each group, owned by SQLite like a [Function]
+type Init<A> = Box<dyn Fn() -> A + Send>;
+
+fn function_name(name: &str) -> crate::Result<CString> {
+    CString::new(name).map_err(|_| protocol_err!("function name {:?} contains a NUL", name).into())
+}
+
+pub(super) fn encode_result<R>(result: R) -> SqliteArgumentValue
+where
+    R: Type<Sqlite> + Encode<Sqlite>,
+{
+    let mut values = Vec::with_capacity(1);
+    if let IsNull::Yes = result.encode_nullable(&mut values) {
+        values.push(SqliteArgumentValue::Null);
+    }
+
+    values.pop().unwrap_or(SqliteArgumentValue::Null)
+}
+
+unsafe fn arguments<'a>(argc: c_int, argv: *mut *mut sqlite3_value) -> SqliteFunctionArguments<'a> {
+    let values = if argc > 0 {
+        slice::from_raw_parts(argv, argc as usize)
+    } else {
+        &[]
+    };
+
+    SqliteFunctionArguments { values }
+}
+
+// A panic must not unwind into SQLite
+fn guard<T>(f: impl FnOnce() -> crate::Result<T>) -> crate::Result<T> {
+    catch_unwind(AssertUnwindSafe(f))
+        .unwrap_or_else(|_| Err(protocol_err!("user-defined function panicked").into()))
+}
+
+unsafe extern "C" fn call(ctx: *mut sqlite3_context, argc: c_int, argv: *mut *mut sqlite3_value) {
+    // https://www.sqlite.org/c3ref/user_data.html
+    let function = &*(sqlite3_user_data(ctx) as *const Function);
+    let arguments = arguments(argc, argv);
+
+    set_result(ctx, guard(|| function(&arguments)));
+}
+
+// The aggregate of the current group is boxed, and a pointer to it is kept in the aggregate
+// context; the context is zeroed when it is allocated, by the first row of the group
+
+// https://www.sqlite.org/c3ref/aggregate_context.html
+
+unsafe fn aggregate<A>(ctx: *mut sqlite3_context) -> Option<*mut *mut A> {
+    let state = sqlite3_aggregate_context(ctx, size_of::<*mut A>() as c_int) as *mut *mut A;
+
+    if state.is_null() {
+        sqlite3_result_error_nomem(ctx);
+
+        return None;
+    }
+
+    if (*state).is_null() {
+        let init = &*(sqlite3_user_data(ctx) as *const Init<A>);
+
+        match guard(|| Ok(init())) {
+            Ok(aggregate) => *state = Box::into_raw(Box::new(aggregate)),
+
+            Err(error) => {
+                set_result(ctx, Err(error));
+
+                return None;
+            }
+        }
+    }
+
+    Some(state)
+}
+
+unsafe extern "C" fn step<A: Aggregate>(
+    ctx: *mut sqlite3_context,
+    argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    if let Some(state) = aggregate::<A>(ctx) {
+        let arguments = arguments(argc, argv);
+
+        if let Err(error) = guard(|| (**state).step(&arguments)) {
+            set_result(ctx, Err(error));
+        }
+    }
+}
+
+unsafe extern "C" fn inverse<A: Aggregate>(
+    ctx: *mut sqlite3_context,
+    argc: c_int,
+    argv: *mut *mut sqlite3_value,
+) {
+    if let Some(state) = aggregate::<A>(ctx) {
+        let arguments = arguments(argc, argv);
+
+        if let Err(error) = guard(|| (**state).inverse(&arguments)) {
+            set_result(ctx, Err(error));
+        }
+    }
+}
+
+unsafe extern "C" fn value<A: Aggregate>(ctx: *mut sqlite3_context) {
+    if let Some(state) = aggregate::<A>(ctx) {
+        set_result(ctx, guard(|| (**state).value().map(encode_result)));
+    }
+}
+
+unsafe extern "C" fn finalize<A: Aggregate>(ctx: *mut sqlite3_context) {
+    // An aggregate query without rows finalizes a new aggregate; this is also called after
+    // an error, to drop the aggregate
+    if let Some(state) = aggregate::<A>(ctx) {
+        let aggregate = Box::from_raw(*state);
+        *state = null_mut();
+
+        set_result(ctx, guard(|| aggregate.finalize().map(encode_result)));
+    }
+}
+
+// https://www.sqlite.org/c3ref/result_blob.html
+pub(super) unsafe fn set_result(
+    ctx: *mut sqlite3_context,
+    result: crate::Result<SqliteArgumentValue>,
+) {
+    match result {
+        Ok(SqliteArgumentValue::Null) => sqlite3_result_null(ctx),
+
+        Ok(SqliteArgumentValue::Text(value)) => sqlite3_result_text(
+            ctx,
+            value.as_ptr() as *const c_char,
+            value.len() as c_int,
+            SQLITE_TRANSIENT(),
+        ),
+
+        Ok(SqliteArgumentValue::Blob(value)) => sqlite3_result_blob(
+            ctx,
+            value.as_ptr() as *const c_void,
+            value.len() as c_int,
+            SQLITE_TRANSIENT(),
+        ),
+
+        Ok(SqliteArgumentValue::Double(value)) => sqlite3_result_double(ctx, value),
+        Ok(SqliteArgumentValue::Int(value)) => sqlite3_result_int(ctx, value),
+        Ok(SqliteArgumentValue::Int64(value)) => sqlite3_result_int64(ctx, value),
+
+        Err(error) => {
+            let message = error.to_string();
+
+            sqlite3_result_error(
+                ctx,
+                message.as_ptr() as *const c_char,
+                message.len() as c_int,
+            );
+        }
+    }
+}
+
+pub(super) unsafe extern "C" fn destroy<T>(data: *mut c_void) {
+    if !data.is_null() {
+        drop(Box::from_raw(data as *mut T));
+    }
+}
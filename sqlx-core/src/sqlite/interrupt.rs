@@ -0,0 +1,125 @@
+use core::ptr::null_mut;
+
+use std::os::raw::{c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+
+use libsqlite3_sys::{sqlite3_interrupt, sqlite3_progress_handler};
+
+use crate::sqlite::connection::SqliteConnectionHandle;
+use crate::sqlite::SqliteConnection;
+
+// The boxed progress handler of a connection, kept in the connection while it is set
+pub(super) type ProgressHandler = Box<dyn FnMut() -> bool + Send>;
+
+/// A handle to interrupt the statements of a connection, from any task or thread.
+///
+/// Created by [`SqliteConnection::interrupt_handle`].
+#[derive(Clone)]
+pub struct SqliteInterruptHandle {
+    // `None` once the connection is closed
+    handle: Arc<Mutex<Option<SqliteConnectionHandle>>>,
+}
+
+impl SqliteInterruptHandle {
+    pub(super) fn new(handle: SqliteConnectionHandle) -> Self {
+        Self {
+            handle: Arc::new(Mutex::new(Some(handle))),
+        }
+    }
+
+    // Called as the connection is closed, so that it is not interrupted after
+    pub(super) fn close(&self) {
+        self.handle.lock().unwrap().take();
+    }
+
+    /// Stops the statements that are running on the connection as soon as possible; they
+    /// fail with an error (`SQLITE_INTERRUPT`), and a transaction that they are part of may
+    /// be rolled back. Statements that start after this returns are not interrupted.
+    ///
+    /// Does nothing once the connection is closed.
+    pub fn interrupt(&self) {
+        // https://www.sqlite.org/c3ref/interrupt.html
+
+        // SAFE: this is safe to call from any thread while the connection is open, which
+        // the lock ensures
+        if let Some(handle) = &*self.handle.lock().unwrap() {
+            unsafe {
+                sqlite3_interrupt(handle.0.as_ptr());
+            }
+        }
+    }
+}
+
+impl SqliteConnection {
+    /// Returns a handle to interrupt the statements of this connection from another task or
+    /// thread, e.g. to cancel a query that takes too long.
+    ///
+    /// ```rust,ignore
+    /// let handle = conn.interrupt_handle();
+    ///
+    /// task::spawn(async move {
+    ///     task::sleep(Duration::from_secs(5)).await;
+    ///     handle.interrupt();
+    /// });
+    ///
+    /// conn.execute(long_running_query).await?;
+    /// ```
+    pub fn interrupt_handle(&self) -> SqliteInterruptHandle {
+        self.interrupt.clone()
+    }
+
+    /// Sets a handler that is called about every `instructions` virtual machine instructions
+    /// of a running statement; the statement is interrupted if it returns `false`.
+    ///
+    /// A long statement keeps the thread of the connection busy until it ends, so this
+    /// allows to stop runaway queries, e.g. after a deadline. The handler must not use the
+    /// connection. Setting a handler replaces the previous one.
+    ///
+    /// ```rust,ignore
+    /// let deadline = Instant::now() + Duration::from_secs(5);
+    ///
+    /// conn.set_progress_handler(1000, move || Instant::now() < deadline);
+    /// ```
+    pub fn set_progress_handler<F>(&mut self, instructions: u32, handler: F)
+    where
+        F: FnMut() -> bool + Send + 'static,
+    {
+        let mut handler: Box<ProgressHandler> = Box::new(Box::new(handler));
+        let data = &mut *handler as *mut ProgressHandler as *mut c_void;
+
+        // https://www.sqlite.org/c3ref/progress_handler.html
+
+        // The previous handler is dropped after SQLite stops calling it; a number of
+        // instructions below 1 turns the handler off
+        unsafe {
+            sqlite3_progress_handler(
+                self.handle(),
+                instructions.max(1).min(c_int::MAX as u32) as c_int,
+                Some(call),
+                data,
+            );
+        }
+
+        self.progress_handler = Some(handler);
+    }
+
+    /// Removes the handler set by [`set_progress_handler`](#method.set_progress_handler).
+    pub fn clear_progress_handler(&mut self) {
+        unsafe {
+            sqlite3_progress_handler(self.handle(), 0, None, null_mut());
+        }
+
+        self.progress_handler = None;
+    }
+}
+
+unsafe extern "C" fn call(data: *mut c_void) -> c_int {
+    let handler = &mut *(data as *mut ProgressHandler);
+
+    // A panic must not unwind into SQLite, and interrupts the statement
+    match catch_unwind(AssertUnwindSafe(handler)) {
+        Ok(true) => 0,
+        Ok(false) | Err(_) => 1,
+    }
+}
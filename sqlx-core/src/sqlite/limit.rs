@@ -0,0 +1,162 @@
+use core::ptr::null_mut;
+
+use std::os::raw::c_int;
+
+use libsqlite3_sys::{
+    sqlite3_db_config, sqlite3_limit, SQLITE_DBCONFIG_DEFENSIVE, SQLITE_LIMIT_ATTACHED,
+    SQLITE_LIMIT_COLUMN, SQLITE_LIMIT_COMPOUND_SELECT, SQLITE_LIMIT_EXPR_DEPTH,
+    SQLITE_LIMIT_FUNCTION_ARG, SQLITE_LIMIT_LENGTH, SQLITE_LIMIT_LIKE_PATTERN_LENGTH,
+    SQLITE_LIMIT_SQL_LENGTH, SQLITE_LIMIT_TRIGGER_DEPTH, SQLITE_LIMIT_VARIABLE_NUMBER,
+    SQLITE_LIMIT_VDBE_OP, SQLITE_LIMIT_WORKER_THREADS, SQLITE_OK,
+};
+
+use crate::sqlite::{SqliteConnection, SqliteError};
+
+/// A run-time limit of a connection.
+///
+/// See [`SqliteConnection::set_limit`] and <https://www.sqlite.org/c3ref/c_limit_attached.html>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqliteLimit {
+    /// The largest string, blob or row, in bytes.
+    Length,
+
+    /// The longest SQL statement, in bytes.
+    SqlLength,
+
+    /// The most columns of a table, index, result or `ORDER BY` / `GROUP BY` clause.
+    Column,
+
+    /// The deepest tree of an expression.
+    ExprDepth,
+
+    /// The most terms of a compound `SELECT`.
+    CompoundSelect,
+
+    /// The most instructions of the program of a statement.
+    VdbeOp,
+
+    /// The most arguments of a function.
+    FunctionArg,
+
+    /// The most attached databases.
+    Attached,
+
+    /// The longest pattern of `LIKE` or `GLOB`, in bytes.
+    LikePatternLength,
+
+    /// The largest index of a parameter.
+    VariableNumber,
+
+    /// The deepest recursion of triggers.
+    TriggerDepth,
+
+    /// The most worker threads of a statement.
+    WorkerThreads,
+}
+
+impl SqliteLimit {
+    fn code(self) -> c_int {
+        match self {
+            SqliteLimit::Length => SQLITE_LIMIT_LENGTH,
+            SqliteLimit::SqlLength => SQLITE_LIMIT_SQL_LENGTH,
+            SqliteLimit::Column => SQLITE_LIMIT_COLUMN,
+            SqliteLimit::ExprDepth => SQLITE_LIMIT_EXPR_DEPTH,
+            SqliteLimit::CompoundSelect => SQLITE_LIMIT_COMPOUND_SELECT,
+            SqliteLimit::VdbeOp => SQLITE_LIMIT_VDBE_OP,
+            SqliteLimit::FunctionArg => SQLITE_LIMIT_FUNCTION_ARG,
+            SqliteLimit::Attached => SQLITE_LIMIT_ATTACHED,
+            SqliteLimit::LikePatternLength => SQLITE_LIMIT_LIKE_PATTERN_LENGTH,
+            SqliteLimit::VariableNumber => SQLITE_LIMIT_VARIABLE_NUMBER,
+            SqliteLimit::TriggerDepth => SQLITE_LIMIT_TRIGGER_DEPTH,
+            SqliteLimit::WorkerThreads => SQLITE_LIMIT_WORKER_THREADS,
+        }
+    }
+
+    // The name of a limit in a `limit.<name>` parameter of the URL
+    pub(super) fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "length" => SqliteLimit::Length,
+            "sql_length" => SqliteLimit::SqlLength,
+            "column" => SqliteLimit::Column,
+            "expr_depth" => SqliteLimit::ExprDepth,
+            "compound_select" => SqliteLimit::CompoundSelect,
+            "vdbe_op" => SqliteLimit::VdbeOp,
+            "function_arg" => SqliteLimit::FunctionArg,
+            "attached" => SqliteLimit::Attached,
+            "like_pattern_length" => SqliteLimit::LikePatternLength,
+            "variable_number" => SqliteLimit::VariableNumber,
+            "trigger_depth" => SqliteLimit::TriggerDepth,
+            "worker_threads" => SqliteLimit::WorkerThreads,
+
+            _ => return None,
+        })
+    }
+}
+
+impl SqliteConnection {
+    /// Returns the current value of a run-time limit.
+    pub fn limit(&mut self, limit: SqliteLimit) -> i32 {
+        // https://www.sqlite.org/c3ref/limit.html
+
+        // a negative value leaves the limit as it is
+        unsafe { sqlite3_limit(self.handle(), limit.code(), -1) }
+    }
+
+    /// Sets a run-time limit of the connection, returning its previous value; a value
+    /// above the limit that SQLite is compiled with is lowered to it.
+    ///
+    /// Lower limits, with [`set_defensive`](#method.set_defensive), harden a connection
+    /// that runs SQL of untrusted sources against statements that take too much memory or
+    /// stack. The `limit.<name>` parameters of the URL set them as the connection connects.
+    pub fn set_limit(&mut self, limit: SqliteLimit, value: u32) -> i32 {
+        let value = value.min(c_int::MAX as u32) as c_int;
+
+        unsafe { sqlite3_limit(self.handle(), limit.code(), value) }
+    }
+
+    /// Turns the [defensive mode] of the connection on or off; it is off by default.
+    ///
+    /// In defensive mode, SQL can not corrupt the database file, e.g. with
+    /// `PRAGMA writable_schema` or by writing to the shadow tables of virtual tables.
+    /// The `defensive` parameter of the URL sets it as the connection connects.
+    ///
+    /// [defensive mode]: https://www.sqlite.org/c3ref/c_dbconfig_defensive.html#sqlitedbconfigdefensive
+    pub fn set_defensive(&mut self, defensive: bool) -> crate::Result<()> {
+        // https://www.sqlite.org/c3ref/db_config.html
+
+        // the last argument receives the new setting, and may be null
+        let status = unsafe {
+            sqlite3_db_config(
+                self.handle(),
+                SQLITE_DBCONFIG_DEFENSIVE,
+                defensive as c_int,
+                null_mut::<c_int>(),
+            )
+        };
+
+        if status != SQLITE_OK {
+            return Err(SqliteError::from_connection(self.handle()).into());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SqliteLimit;
+
+    #[test]
+    fn it_parses_limit_names() {
+        assert_eq!(
+            SqliteLimit::from_name("sql_length"),
+            Some(SqliteLimit::SqlLength)
+        );
+        assert_eq!(
+            SqliteLimit::from_name("worker_threads"),
+            Some(SqliteLimit::WorkerThreads)
+        );
+        assert_eq!(SqliteLimit::from_name("SQL_LENGTH"), None);
+        assert_eq!(SqliteLimit::from_name("sql-length"), None);
+    }
+}
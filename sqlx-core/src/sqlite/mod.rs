@@ -6,26 +6,54 @@
 #![allow(unsafe_code)]
 
 mod arguments;
+mod authorizer;
+mod blob;
+mod busy;
+mod checkpoint;
+mod collation;
 mod connection;
 mod cursor;
 mod database;
 mod error;
 mod executor;
+mod explain;
+mod function;
+mod interrupt;
+mod limit;
+#[cfg(feature = "sqlite-preupdate-hook")]
+mod preupdate;
 mod row;
+mod serialize;
+#[cfg(feature = "sqlite-snapshot")]
+mod snapshot;
 mod statement;
 mod type_info;
 pub mod types;
 mod value;
+mod vtab;
 mod worker;
 
 pub use arguments::{SqliteArgumentValue, SqliteArguments};
+pub use authorizer::{SqliteAuthorization, SqliteAuthorizerAction, SqliteAuthorizerRequest};
+pub use blob::SqliteBlob;
+pub use busy::SqliteBusyRetry;
+pub use checkpoint::SqliteCheckpointMode;
 pub use connection::SqliteConnection;
 pub use cursor::SqliteCursor;
 pub use database::Sqlite;
 pub use error::SqliteError;
+pub use explain::{SqlitePlanNode, SqliteQueryPlan};
+pub use function::{Aggregate, SqliteFunctionArguments};
+pub use interrupt::SqliteInterruptHandle;
+pub use limit::SqliteLimit;
+#[cfg(feature = "sqlite-preupdate-hook")]
+pub use preupdate::{SqliteOperation, SqlitePreupdate};
 pub use row::SqliteRow;
+#[cfg(feature = "sqlite-snapshot")]
+pub use snapshot::SqliteSnapshot;
 pub use type_info::SqliteTypeInfo;
 pub use value::SqliteValue;
+pub use vtab::{SqliteVTabColumn, VTab, VTabCursor};
 
 /// An alias for [`Pool`][crate::pool::Pool], specialized for **Sqlite**.
 #[cfg_attr(docsrs, doc(cfg(feature = "sqlite")))]
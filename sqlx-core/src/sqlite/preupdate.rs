@@ -0,0 +1,225 @@
+use core::ptr::null_mut;
+
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use libsqlite3_sys::{
+    sqlite3, sqlite3_int64, sqlite3_value, SQLITE_DELETE, SQLITE_INSERT, SQLITE_OK, SQLITE_UPDATE,
+};
+
+use crate::decode::Decode;
+use crate::sqlite::{Sqlite, SqliteConnection, SqliteError, SqliteValue};
+use crate::types::Type;
+
+// The bundled bindings are generated without [SQLITE_ENABLE_PREUPDATE_HOOK], which the
+// `preupdate_hook` feature of `libsqlite3-sys` compiles SQLite with
+
+// https://www.sqlite.org/c3ref/preupdate_count.html
+extern "C" {
+    fn sqlite3_preupdate_hook(
+        db: *mut sqlite3,
+        hook: Option<
+            unsafe extern "C" fn(
+                *mut c_void,
+                *mut sqlite3,
+                c_int,
+                *const c_char,
+                *const c_char,
+                sqlite3_int64,
+                sqlite3_int64,
+            ),
+        >,
+        data: *mut c_void,
+    ) -> *mut c_void;
+
+    fn sqlite3_preupdate_old(
+        db: *mut sqlite3,
+        index: c_int,
+        value: *mut *mut sqlite3_value,
+    ) -> c_int;
+
+    fn sqlite3_preupdate_new(
+        db: *mut sqlite3,
+        index: c_int,
+        value: *mut *mut sqlite3_value,
+    ) -> c_int;
+
+    fn sqlite3_preupdate_count(db: *mut sqlite3) -> c_int;
+
+    fn sqlite3_preupdate_depth(db: *mut sqlite3) -> c_int;
+}
+
+// The boxed hook of a connection, kept in the connection while it is set
+pub(super) type PreupdateHook = Box<dyn FnMut(&SqlitePreupdate<'_>) + Send>;
+
+/// The kind of change to a row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SqliteOperation {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// A change to a row of a table, before it is made.
+///
+/// See [`SqliteConnection::set_preupdate_hook`].
+pub struct SqlitePreupdate<'a> {
+    db: *mut sqlite3,
+    operation: SqliteOperation,
+    database: &'a str,
+    table: &'a str,
+    old_rowid: i64,
+    new_rowid: i64,
+}
+
+impl<'a> SqlitePreupdate<'a> {
+    /// Returns whether the row is inserted, updated or deleted.
+    pub fn operation(&self) -> SqliteOperation {
+        self.operation
+    }
+
+    /// Returns the name of the database of the table (e.g. `main` or `temp`).
+    pub fn database(&self) -> &'a str {
+        self.database
+    }
+
+    /// Returns the name of the table.
+    pub fn table(&self) -> &'a str {
+        self.table
+    }
+
+    /// Returns the rowid of the row before an update or delete.
+    pub fn old_rowid(&self) -> i64 {
+        self.old_rowid
+    }
+
+    /// Returns the rowid of the row after an insert or update.
+    pub fn new_rowid(&self) -> i64 {
+        self.new_rowid
+    }
+
+    /// Returns the number of columns of the row.
+    pub fn column_count(&self) -> usize {
+        unsafe { sqlite3_preupdate_count(self.db) as usize }
+    }
+
+    /// Returns the depth of the change in triggers: `0` for a change of a statement, `1` for
+    /// a change of a trigger of that statement, and so on.
+    pub fn depth(&self) -> i32 {
+        unsafe { sqlite3_preupdate_depth(self.db) }
+    }
+
+    /// Decodes the value of the column at `index` before an update or delete.
+    pub fn old_value<T>(&self, index: usize) -> crate::Result<T>
+    where
+        T: Type<Sqlite>,
+        T: Decode<'a, Sqlite>,
+    {
+        self.value(sqlite3_preupdate_old, index)
+    }
+
+    /// Decodes the value of the column at `index` after an insert or update.
+    pub fn new_value<T>(&self, index: usize) -> crate::Result<T>
+    where
+        T: Type<Sqlite>,
+        T: Decode<'a, Sqlite>,
+    {
+        self.value(sqlite3_preupdate_new, index)
+    }
+
+    fn value<T>(
+        &self,
+        get: unsafe extern "C" fn(*mut sqlite3, c_int, *mut *mut sqlite3_value) -> c_int,
+        index: usize,
+    ) -> crate::Result<T>
+    where
+        T: Decode<'a, Sqlite>,
+    {
+        let len = self.column_count();
+
+        if index >= len {
+            return Err(crate::Error::ColumnIndexOutOfBounds { index, len });
+        }
+
+        let mut value = null_mut();
+
+        // Fails with SQLITE_MISUSE for the old values of an insert or the new values of a
+        // delete; the value is valid until the hook returns
+        let status = unsafe { get(self.db, index as c_int, &mut value) };
+
+        if status != SQLITE_OK || value.is_null() {
+            return Err(SqliteError::from_connection(self.db).into());
+        }
+
+        T::decode(SqliteValue::argument(unsafe { &*value }))
+    }
+}
+
+impl SqliteConnection {
+    /// Sets a hook that is called before each row of a table of the connection is
+    /// inserted, updated or deleted, with the values of the row before and after the change.
+    ///
+    /// This allows to capture all changes to a database (e.g. to replicate them); the hook
+    /// must not use the connection. It is not called for tables `WITHOUT ROWID`. Setting a
+    /// hook replaces the previous one.
+    ///
+    /// Requires the `sqlite-preupdate-hook` feature.
+    pub fn set_preupdate_hook<F>(&mut self, hook: F)
+    where
+        F: FnMut(&SqlitePreupdate<'_>) + Send + 'static,
+    {
+        let mut hook: Box<PreupdateHook> = Box::new(Box::new(hook));
+        let data = &mut *hook as *mut PreupdateHook as *mut c_void;
+
+        // https://www.sqlite.org/c3ref/preupdate_count.html
+
+        // The previous hook is dropped after SQLite stops calling it
+        unsafe {
+            sqlite3_preupdate_hook(self.handle(), Some(call), data);
+        }
+
+        self.preupdate_hook = Some(hook);
+    }
+
+    /// Removes the hook set by [`set_preupdate_hook`](#method.set_preupdate_hook).
+    pub fn clear_preupdate_hook(&mut self) {
+        unsafe {
+            sqlite3_preupdate_hook(self.handle(), None, null_mut());
+        }
+
+        self.preupdate_hook = None;
+    }
+}
+
+unsafe extern "C" fn call(
+    data: *mut c_void,
+    db: *mut sqlite3,
+    operation: c_int,
+    database: *const c_char,
+    table: *const c_char,
+    old_rowid: sqlite3_int64,
+    new_rowid: sqlite3_int64,
+) {
+    let hook = &mut *(data as *mut PreupdateHook);
+
+    let operation = match operation {
+        SQLITE_INSERT => SqliteOperation::Insert,
+        SQLITE_UPDATE => SqliteOperation::Update,
+        SQLITE_DELETE => SqliteOperation::Delete,
+
+        _ => return,
+    };
+
+    let preupdate = SqlitePreupdate {
+        db,
+        operation,
+        database: CStr::from_ptr(database).to_str().unwrap_or_default(),
+        table: CStr::from_ptr(table).to_str().unwrap_or_default(),
+        old_rowid,
+        new_rowid,
+    };
+
+    // A panic must not unwind into SQLite, and the change can not be stopped
+    let _ = catch_unwind(AssertUnwindSafe(|| hook(&preupdate)));
+}
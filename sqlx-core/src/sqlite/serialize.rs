@@ -0,0 +1,155 @@
+use core::ptr::{null, null_mut};
+
+use std::env;
+use std::ffi::CString;
+use std::fs;
+use std::os::raw::c_int;
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use libsqlite3_sys::{
+    sqlite3, sqlite3_backup_finish, sqlite3_backup_init, sqlite3_backup_step, sqlite3_close,
+    sqlite3_open_v2, SQLITE_DONE, SQLITE_OK, SQLITE_OPEN_CREATE, SQLITE_OPEN_READONLY,
+    SQLITE_OPEN_READWRITE,
+};
+
+use crate::sqlite::{SqliteConnection, SqliteError};
+
+// The bundled SQLite is not compiled with [SQLITE_ENABLE_DESERIALIZE], so [sqlite3_serialize]
+// and [sqlite3_deserialize] are not available. Instead, the database is copied to or from a
+// temporary file, with the online backup API, and the file is read or written whole.
+
+// <https://www.sqlite.org/backup.html>
+
+impl SqliteConnection {
+    /// Returns the contents of the `main` database of the connection, as the bytes of a
+    /// database file.
+    ///
+    /// This is mostly useful to save an in-memory database (e.g. a cache, or the state of a
+    /// test), to restore it later with [`deserialize`](#method.deserialize).
+    pub async fn serialize(&mut self) -> crate::Result<Vec<u8>> {
+        let handle = self.handle;
+
+        self.worker
+            .run(move || {
+                let file = TempFile::new();
+
+                // SAFE: the connection is not used elsewhere while the worker runs
+                unsafe {
+                    let target = open(&file, SQLITE_OPEN_READWRITE | SQLITE_OPEN_CREATE)?;
+                    let result = backup(target, handle.0.as_ptr());
+
+                    let _ = sqlite3_close(target);
+
+                    result?;
+                }
+
+                Ok(fs::read(&file.0)?)
+            })
+            .await
+    }
+
+    /// Replaces the contents of the `main` database of the connection with `data`, the bytes
+    /// of a database file (e.g. from [`serialize`](#method.serialize)).
+    pub async fn deserialize(&mut self, data: &[u8]) -> crate::Result<()> {
+        let handle = self.handle;
+        let data = data.to_vec();
+
+        // Statements prepared against the previous schema are prepared again when used
+        self.worker
+            .run(move || {
+                let file = TempFile::new();
+
+                fs::write(&file.0, data)?;
+
+                // SAFE: the connection is not used elsewhere while the worker runs
+                unsafe {
+                    let source = open(&file, SQLITE_OPEN_READONLY)?;
+                    let result = backup(handle.0.as_ptr(), source);
+
+                    let _ = sqlite3_close(source);
+
+                    result
+                }
+            })
+            .await
+    }
+}
+
+// A uniquely named file in the temporary directory, removed on drop with the log files that
+// SQLite may create next to it (e.g. when the database copied into it is in WAL mode)
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new() -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+        let name = format!(
+            "sqlx-sqlite-{}-{}.db",
+            process::id(),
+            NEXT.fetch_add(1, Ordering::SeqCst)
+        );
+
+        TempFile(env::temp_dir().join(name))
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        for suffix in &["", "-journal", "-wal", "-shm"] {
+            let mut path = self.0.clone().into_os_string();
+            path.push(suffix);
+
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+// <https://www.sqlite.org/c3ref/open.html>
+unsafe fn open(file: &TempFile, flags: c_int) -> crate::Result<*mut sqlite3> {
+    let filename = CString::new(file.0.to_string_lossy().as_bytes()).map_err(|_| {
+        crate::Error::from(protocol_err!("temporary file {:?} contains a NUL", file.0))
+    })?;
+
+    let mut handle = null_mut();
+    let status = sqlite3_open_v2(filename.as_ptr(), &mut handle, flags, null());
+
+    if handle.is_null() {
+        // Failed to allocate memory
+        panic!("SQLite is unable to allocate memory to hold the sqlite3 object");
+    }
+
+    if status != SQLITE_OK {
+        let error = SqliteError::from_connection(handle);
+        let _ = sqlite3_close(handle);
+
+        return Err(error.into());
+    }
+
+    Ok(handle)
+}
+
+// Copies the `main` database of `source` over the `main` database of `target`; errors are
+// reported on the target connection
+
+// <https://www.sqlite.org/c3ref/backup_finish.html>
+unsafe fn backup(target: *mut sqlite3, source: *mut sqlite3) -> crate::Result<()> {
+    let main = b"main\0".as_ptr() as *const _;
+
+    let backup = sqlite3_backup_init(target, main, source, main);
+
+    if backup.is_null() {
+        return Err(SqliteError::from_connection(target).into());
+    }
+
+    // Copy all pages in one step
+    let status = sqlite3_backup_step(backup, -1);
+    let finished = sqlite3_backup_finish(backup);
+
+    if status != SQLITE_DONE || finished != SQLITE_OK {
+        return Err(SqliteError::from_connection(target).into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use core::ptr::{null_mut, NonNull};
+
+use libsqlite3_sys::{
+    sqlite3, sqlite3_snapshot, sqlite3_snapshot_free, sqlite3_snapshot_get, sqlite3_snapshot_open,
+    SQLITE_OK,
+};
+
+use crate::error::DatabaseError;
+use crate::sqlite::{SqliteConnection, SqliteError};
+
+// The bundled SQLite is not compiled with [SQLITE_ENABLE_SNAPSHOT], and `libsqlite3-sys` has
+// no feature for it; the `sqlite-snapshot` feature requires SQLite to be built with it (e.g.
+// with `CFLAGS=-DSQLITE_ENABLE_SNAPSHOT` for the bundled SQLite)
+
+// https://www.sqlite.org/c3ref/snapshot.html
+
+/// A point in the history of a database in WAL mode, that read transactions of other
+/// connections to the database can be pinned to.
+///
+/// Created by [`SqliteConnection::snapshot`], and freed on drop.
+pub struct SqliteSnapshot(NonNull<sqlite3_snapshot>);
+
+// A snapshot is not changed after it is created, and may be opened by any connection
+unsafe impl Send for SqliteSnapshot {}
+unsafe impl Sync for SqliteSnapshot {}
+
+impl SqliteConnection {
+    /// Returns the snapshot of the `main` database that the current read transaction of the
+    /// connection sees.
+    ///
+    /// The connection must be in a transaction that has read from the database (e.g. after
+    /// `BEGIN` and a `SELECT`), and the database must be in WAL mode, as it is by default.
+    ///
+    /// Requires the `sqlite-snapshot` feature.
+    pub async fn snapshot(&mut self) -> crate::Result<SqliteSnapshot> {
+        let handle = self.handle;
+
+        self.worker
+            .run(move || {
+                let mut snapshot = null_mut();
+
+                // https://www.sqlite.org/c3ref/snapshot_get.html
+
+                // SAFE: the connection is not used elsewhere while the worker runs
+                let status = unsafe {
+                    sqlite3_snapshot_get(
+                        handle.0.as_ptr(),
+                        b"main\0".as_ptr() as *const _,
+                        &mut snapshot,
+                    )
+                };
+
+                match NonNull::new(snapshot) {
+                    Some(snapshot) if status == SQLITE_OK => Ok(SqliteSnapshot(snapshot)),
+
+                    _ => Err(snapshot_error(handle.0.as_ptr(), status, "get")),
+                }
+            })
+            .await
+    }
+
+    /// Pins the current transaction of the connection to `snapshot`, so that it reads the
+    /// `main` database as it was when the snapshot was taken, by any connection to it.
+    ///
+    /// This allows several connections (e.g. of a pool) to read the same state of the
+    /// database, without a transaction that blocks writers for as long. The connection
+    /// must be in a transaction that has not read from the database yet (e.g. right after
+    /// `BEGIN`). Opening a snapshot fails once it is checkpointed out of the write-ahead
+    /// log.
+    ///
+    /// Connections are opened with a shared cache, where a read transaction locks out
+    /// writers of the tables it reads; open the connections of the database with a
+    /// private cache instead, with a `file:` [URI filename]:
+    ///
+    /// ```text
+    /// sqlite:file:data.db?cache=private
+    /// ```
+    ///
+    /// ```rust,ignore
+    /// let mut reader = pool.begin().await?;
+    /// reader.open_snapshot(&snapshot).await?;
+    /// ```
+    ///
+    /// Requires the `sqlite-snapshot` feature.
+    ///
+    /// [URI filename]: https://www.sqlite.org/uri.html
+    pub async fn open_snapshot(&mut self, snapshot: &SqliteSnapshot) -> crate::Result<()> {
+        let handle = self.handle;
+        let snapshot = SnapshotPtr(snapshot.0);
+
+        self.worker
+            .run(move || {
+                // https://www.sqlite.org/c3ref/snapshot_open.html
+
+                // SAFE: the connection is not used elsewhere while the worker runs, and the
+                // snapshot is borrowed until the worker returns
+                let status = unsafe {
+                    sqlite3_snapshot_open(
+                        handle.0.as_ptr(),
+                        b"main\0".as_ptr() as *const _,
+                        snapshot.0.as_ptr(),
+                    )
+                };
+
+                if status != SQLITE_OK {
+                    return Err(snapshot_error(handle.0.as_ptr(), status, "open"));
+                }
+
+                Ok(())
+            })
+            .await
+    }
+}
+
+impl Drop for SqliteSnapshot {
+    fn drop(&mut self) {
+        // https://www.sqlite.org/c3ref/snapshot_free.html
+        unsafe {
+            sqlite3_snapshot_free(self.0.as_ptr());
+        }
+    }
+}
+
+// A pointer to a snapshot, sent to the worker of the connection that opens it
+struct SnapshotPtr(NonNull<sqlite3_snapshot>);
+
+unsafe impl Send for SnapshotPtr {}
+
+// The snapshot functions do not always set the error of the connection (e.g. when it is not
+// in a transaction), so the code is reported as well
+fn snapshot_error(handle: *mut sqlite3, status: i32, action: &str) -> crate::Error {
+    let error = SqliteError::from_connection(handle);
+
+    protocol_err!(
+        "failed to {} the snapshot (SQLite error code {}): {}",
+        action,
+        status,
+        error.message()
+    )
+    .into()
+}
@@ -11,7 +11,7 @@ use libsqlite3_sys::{
     sqlite3_column_database_name, sqlite3_column_decltype, sqlite3_column_name,
     sqlite3_column_origin_name, sqlite3_column_table_name, sqlite3_data_count, sqlite3_finalize,
     sqlite3_prepare_v3, sqlite3_reset, sqlite3_step, sqlite3_stmt, sqlite3_table_column_metadata,
-    SQLITE_DONE, SQLITE_OK, SQLITE_PREPARE_NO_VTAB, SQLITE_PREPARE_PERSISTENT, SQLITE_ROW,
+    SQLITE_DONE, SQLITE_OK, SQLITE_PREPARE_PERSISTENT, SQLITE_ROW,
 };
 
 use crate::sqlite::connection::SqliteConnectionHandle;
@@ -59,7 +59,9 @@ impl Statement {
         let query_ptr = query.as_bytes().as_ptr() as *const c_char;
         let query_len = query.len() as i32;
         let mut statement_handle: *mut sqlite3_stmt = null_mut();
-        let mut flags = SQLITE_PREPARE_NO_VTAB;
+        // Virtual tables (e.g. of FTS5) are allowed; [SQLITE_PREPARE_NO_VTAB] would fail to
+        // prepare any statement that uses one
+        let mut flags = 0;
         let mut tail: *const c_char = null();
 
         if persistent {
@@ -0,0 +1,231 @@
+use crate::decode::Decode;
+use crate::encode::Encode;
+use crate::error::UnexpectedNullError;
+use crate::sqlite::{Sqlite, SqliteArgumentValue, SqliteTypeInfo, SqliteValue};
+use crate::types::Type;
+
+// https://www.sqlite.org/fts5.html#full_text_query_syntax
+
+/// A full-text query of [FTS5], to bind as the right-hand side of `MATCH`.
+///
+/// The constructors quote the words of user input, so that characters such as `"`, `*`,
+/// `-` or `:` and words such as `OR` or `NEAR` are searched for as text, instead of being
+/// a syntax error or changing the meaning of the query.
+///
+/// ```rust,ignore
+/// let rows = sqlx::query("SELECT title FROM docs WHERE docs MATCH ? ORDER BY rank")
+///     .bind(MatchQuery::words(&input))
+///     .fetch_all(&mut conn)
+///     .await?;
+/// ```
+///
+/// [FTS5]: https://www.sqlite.org/fts5.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchQuery(String);
+
+impl MatchQuery {
+    /// Matches rows that contain all of the words of `text`, in any order.
+    pub fn words(text: &str) -> Self {
+        MatchQuery(join(text.split_whitespace().map(quote)))
+    }
+
+    /// Matches rows that contain all of the words of `text`, in any order, where the last
+    /// word may be the start of a longer word (e.g. for search as you type).
+    pub fn prefix(text: &str) -> Self {
+        let mut words: Vec<_> = text.split_whitespace().map(quote).collect();
+
+        if let Some(last) = words.last_mut() {
+            last.push('*');
+        }
+
+        MatchQuery(join(words))
+    }
+
+    /// Matches rows that contain the words of `text` next to each other and in order.
+    pub fn phrase(text: &str) -> Self {
+        MatchQuery(quote(text))
+    }
+
+    /// A query in the syntax of FTS5, which is not escaped; only for queries that are not
+    /// from user input.
+    pub fn raw(query: impl Into<String>) -> Self {
+        MatchQuery(query.into())
+    }
+
+    /// Returns the query in the syntax of FTS5.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+// A string of FTS5, in which a `"` is escaped by doubling it
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\"\""))
+}
+
+// Terms separated by whitespace must all match; an empty query is an empty phrase, which
+// matches no rows, as it is not a syntax error
+fn join(terms: impl IntoIterator<Item = String>) -> String {
+    let query = terms.into_iter().collect::<Vec<_>>().join(" ");
+
+    if query.is_empty() {
+        "\"\"".to_owned()
+    } else {
+        query
+    }
+}
+
+impl Type<Sqlite> for MatchQuery {
+    fn type_info() -> SqliteTypeInfo {
+        <str as Type<Sqlite>>::type_info()
+    }
+}
+
+impl Encode<Sqlite> for MatchQuery {
+    fn encode(&self, values: &mut Vec<SqliteArgumentValue>) {
+        <str as Encode<Sqlite>>::encode(&self.0, values)
+    }
+}
+
+/// The text returned by the `highlight()` function of FTS5, split into the parts that match
+/// the query and the parts that do not.
+///
+/// The matches must be marked with [`Highlight::OPEN`] and [`Highlight::CLOSE`], which are
+/// the control characters `char(2)` and `char(3)`:
+///
+/// ```rust,ignore
+/// let (highlight,): (Highlight,) =
+///     sqlx::query_as("SELECT highlight(docs, 0, char(2), char(3)) FROM docs WHERE docs MATCH ?")
+///         .bind(MatchQuery::words(&input))
+///         .fetch_one(&mut conn)
+///         .await?;
+///
+/// let html = highlight.wrap("<mark>", "</mark>");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Highlight {
+    // the text of each part, and whether it is a match
+    parts: Vec<(String, bool)>,
+}
+
+impl Highlight {
+    /// The marker of the start of a match.
+    pub const OPEN: &'static str = "\u{2}";
+
+    /// The marker of the end of a match.
+    pub const CLOSE: &'static str = "\u{3}";
+
+    fn parse(text: &str) -> Self {
+        let mut parts = Vec::new();
+        let mut rest = text;
+
+        while let Some(start) = rest.find(Self::OPEN) {
+            let (before, after) = (&rest[..start], &rest[start + Self::OPEN.len()..]);
+            let end = after.find(Self::CLOSE).unwrap_or(after.len());
+
+            parts.push((before.to_owned(), false));
+            parts.push((after[..end].to_owned(), true));
+
+            rest = after.get(end + Self::CLOSE.len()..).unwrap_or_default();
+        }
+
+        parts.push((rest.to_owned(), false));
+        parts.retain(|(text, _)| !text.is_empty());
+
+        Highlight { parts }
+    }
+
+    /// Returns the parts of the text in order, with whether each one is a match.
+    pub fn parts(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.parts.iter().map(|(text, matched)| (&**text, *matched))
+    }
+
+    /// Returns the parts of the text that match the query.
+    pub fn matches(&self) -> impl Iterator<Item = &str> {
+        self.parts()
+            .filter(|(_, matched)| *matched)
+            .map(|(text, _)| text)
+    }
+
+    /// Returns the text without the markers.
+    pub fn text(&self) -> String {
+        self.wrap("", "")
+    }
+
+    /// Returns the text with each match between `open` and `close` (e.g. `<mark>` and
+    /// `</mark>`). The text is not escaped.
+    pub fn wrap(&self, open: &str, close: &str) -> String {
+        let mut text = String::new();
+
+        for (part, matched) in self.parts() {
+            if matched {
+                text.push_str(open);
+                text.push_str(part);
+                text.push_str(close);
+            } else {
+                text.push_str(part);
+            }
+        }
+
+        text
+    }
+}
+
+impl Type<Sqlite> for Highlight {
+    fn type_info() -> SqliteTypeInfo {
+        <str as Type<Sqlite>>::type_info()
+    }
+}
+
+impl<'de> Decode<'de, Sqlite> for Highlight {
+    fn decode(value: SqliteValue<'de>) -> crate::Result<Self> {
+        value
+            .text()
+            .map(Highlight::parse)
+            .ok_or_else(|| crate::Error::decode(UnexpectedNullError))
+    }
+}
+
+#[test]
+fn test_match_query_quotes_words() {
+    assert_eq!(
+        MatchQuery::words("sqlite  \"fts5\" OR NEAR").as_str(),
+        r#""sqlite" """fts5""" "OR" "NEAR""#
+    );
+
+    assert_eq!(MatchQuery::prefix("full tex").as_str(), r#""full" "tex"*"#);
+    assert_eq!(MatchQuery::phrase("a -b").as_str(), r#""a -b""#);
+    assert_eq!(MatchQuery::words(" ").as_str(), r#""""#);
+    assert_eq!(MatchQuery::prefix("").as_str(), r#""""#);
+}
+
+#[test]
+fn test_highlight_parses_matches() {
+    let highlight = Highlight::parse("the \u{2}quick\u{3} brown \u{2}fox\u{3}");
+
+    assert_eq!(
+        highlight.parts().collect::<Vec<_>>(),
+        vec![
+            ("the ", false),
+            ("quick", true),
+            (" brown ", false),
+            ("fox", true)
+        ]
+    );
+
+    assert_eq!(
+        highlight.matches().collect::<Vec<_>>(),
+        vec!["quick", "fox"]
+    );
+    assert_eq!(highlight.text(), "the quick brown fox");
+    assert_eq!(
+        highlight.wrap("<b>", "</b>"),
+        "the <b>quick</b> brown <b>fox</b>"
+    );
+
+    // a match that is not closed runs to the end
+    assert_eq!(
+        Highlight::parse("a \u{2}b").parts().collect::<Vec<_>>(),
+        vec![("a ", false), ("b", true)]
+    );
+}
@@ -13,6 +13,15 @@
 //! | `&str`, `String`                      | TEXT                                                 |
 //! | `&[u8]`, `Vec<u8>`                    | BLOB                                                 |
 //!
+//! ### [FTS5](https://www.sqlite.org/fts5.html)
+//!
+//! | Rust type                             | SQLite type(s)                                       |
+//! |---------------------------------------|------------------------------------------------------|
+//! | [`MatchQuery`]                        | TEXT (the right-hand side of `MATCH`)                |
+//! | [`Highlight`]                         | TEXT (the output of `highlight()`)                   |
+//!
+//! The `bm25()` rank of a row decodes to `f64`.
+//!
 //! # Nullable
 //!
 //! In addition, `Option<T>` is supported where `T` implements `Type`. An `Option<T>` represents
@@ -26,9 +35,12 @@ use crate::sqlite::Sqlite;
 mod bool;
 mod bytes;
 mod float;
+mod fts5;
 mod int;
 mod str;
 
+pub use fts5::{Highlight, MatchQuery};
+
 impl<'de, T> Decode<'de, Sqlite> for Option<T>
 where
     T: Decode<'de, Sqlite>,
@@ -1,11 +1,14 @@
 use core::slice;
 
 use std::ffi::CStr;
+use std::os::raw::{c_char, c_int, c_void};
 use std::str::from_utf8_unchecked;
 
 use libsqlite3_sys::{
     sqlite3_column_blob, sqlite3_column_bytes, sqlite3_column_double, sqlite3_column_int,
-    sqlite3_column_int64, sqlite3_column_text, sqlite3_column_type, SQLITE_BLOB, SQLITE_FLOAT,
+    sqlite3_column_int64, sqlite3_column_text, sqlite3_column_type, sqlite3_value,
+    sqlite3_value_blob, sqlite3_value_bytes, sqlite3_value_double, sqlite3_value_int,
+    sqlite3_value_int64, sqlite3_value_text, sqlite3_value_type, SQLITE_BLOB, SQLITE_FLOAT,
     SQLITE_INTEGER, SQLITE_NULL, SQLITE_TEXT,
 };
 
@@ -15,8 +18,19 @@ use crate::sqlite::{Sqlite, SqliteTypeInfo};
 use crate::value::RawValue;
 
 pub struct SqliteValue<'c> {
-    pub(super) index: i32,
-    pub(super) statement: &'c Statement,
+    pub(super) handle: SqliteValueHandle<'c>,
+}
+
+#[derive(Clone, Copy)]
+pub(super) enum SqliteValueHandle<'c> {
+    // A column of the current result row of a statement
+    Column {
+        statement: &'c Statement,
+        index: i32,
+    },
+
+    // An argument of a user-defined function, valid for the duration of the call
+    Argument(&'c sqlite3_value),
 }
 
 // https://www.sqlite.org/c3ref/column_blob.html
@@ -24,7 +38,24 @@ pub struct SqliteValue<'c> {
 
 // These routines return information about a single column of the current result row of a query.
 
+// https://www.sqlite.org/c3ref/value_blob.html
+
+// These routines return information about an argument of a user-defined function, in the
+// same way.
+
 impl<'c> SqliteValue<'c> {
+    pub(super) fn column(statement: &'c Statement, index: i32) -> Self {
+        Self {
+            handle: SqliteValueHandle::Column { statement, index },
+        }
+    }
+
+    pub(super) fn argument(value: &'c sqlite3_value) -> Self {
+        Self {
+            handle: SqliteValueHandle::Argument(value),
+        }
+    }
+
     /// Returns true if the value should be intrepreted as NULL.
     pub(super) fn is_null(&self) -> bool {
         self.r#type().is_none()
@@ -32,11 +63,17 @@ impl<'c> SqliteValue<'c> {
 
     fn r#type(&self) -> Option<SqliteType> {
         let type_code = unsafe {
-            if let Some(handle) = self.statement.handle() {
-                sqlite3_column_type(handle, self.index)
-            } else {
-                // unreachable: null statements do not have any values to type
-                return None;
+            match self.handle {
+                SqliteValueHandle::Column { statement, index } => {
+                    if let Some(handle) = statement.handle() {
+                        sqlite3_column_type(handle, index)
+                    } else {
+                        // unreachable: null statements do not have any values to type
+                        return None;
+                    }
+                }
+
+                SqliteValueHandle::Argument(value) => sqlite3_value_type(value_ptr(value)),
             }
         };
 
@@ -55,62 +92,89 @@ impl<'c> SqliteValue<'c> {
     /// Returns the 32-bit INTEGER result.
     pub(super) fn int(&self) -> i32 {
         unsafe {
-            self.statement
-                .handle()
-                .map_or(0, |handle| sqlite3_column_int(handle, self.index))
+            match self.handle {
+                SqliteValueHandle::Column { statement, index } => statement
+                    .handle()
+                    .map_or(0, |handle| sqlite3_column_int(handle, index)),
+
+                SqliteValueHandle::Argument(value) => sqlite3_value_int(value_ptr(value)),
+            }
         }
     }
 
     /// Returns the 64-bit INTEGER result.
     pub(super) fn int64(&self) -> i64 {
         unsafe {
-            self.statement
-                .handle()
-                .map_or(0, |handle| sqlite3_column_int64(handle, self.index))
+            match self.handle {
+                SqliteValueHandle::Column { statement, index } => statement
+                    .handle()
+                    .map_or(0, |handle| sqlite3_column_int64(handle, index)),
+
+                SqliteValueHandle::Argument(value) => sqlite3_value_int64(value_ptr(value)),
+            }
         }
     }
 
     /// Returns the 64-bit, REAL result.
     pub(super) fn double(&self) -> f64 {
         unsafe {
-            self.statement
-                .handle()
-                .map_or(0.0, |handle| sqlite3_column_double(handle, self.index))
+            match self.handle {
+                SqliteValueHandle::Column { statement, index } => statement
+                    .handle()
+                    .map_or(0.0, |handle| sqlite3_column_double(handle, index)),
+
+                SqliteValueHandle::Argument(value) => sqlite3_value_double(value_ptr(value)),
+            }
         }
     }
 
     /// Returns the UTF-8 TEXT result.
     pub(super) fn text(&self) -> Option<&'c str> {
-        unsafe {
-            self.statement.handle().and_then(|handle| {
-                let ptr = sqlite3_column_text(handle, self.index);
+        let ptr: *const c_char = unsafe {
+            match self.handle {
+                SqliteValueHandle::Column { statement, index } => match statement.handle() {
+                    Some(handle) => sqlite3_column_text(handle, index) as _,
+                    None => return None,
+                },
+
+                SqliteValueHandle::Argument(value) => sqlite3_value_text(value_ptr(value)) as _,
+            }
+        };
 
-                if ptr.is_null() {
-                    None
-                } else {
-                    Some(from_utf8_unchecked(CStr::from_ptr(ptr as _).to_bytes()))
-                }
-            })
+        if ptr.is_null() {
+            None
+        } else {
+            Some(unsafe { from_utf8_unchecked(CStr::from_ptr(ptr).to_bytes()) })
         }
     }
 
     fn bytes(&self) -> usize {
         // Returns the size of the result in bytes.
-        unsafe {
-            self.statement
-                .handle()
-                .map_or(0, |handle| sqlite3_column_bytes(handle, self.index)) as usize
-        }
+        let bytes: c_int = unsafe {
+            match self.handle {
+                SqliteValueHandle::Column { statement, index } => statement
+                    .handle()
+                    .map_or(0, |handle| sqlite3_column_bytes(handle, index)),
+
+                SqliteValueHandle::Argument(value) => sqlite3_value_bytes(value_ptr(value)),
+            }
+        };
+
+        bytes as usize
     }
 
     /// Returns the BLOB result.
     pub(super) fn blob(&self) -> &'c [u8] {
-        let ptr = unsafe {
-            if let Some(handle) = self.statement.handle() {
-                sqlite3_column_blob(handle, self.index)
-            } else {
-                // Null statements do not exist
-                return &[];
+        let ptr: *const c_void = unsafe {
+            match self.handle {
+                SqliteValueHandle::Column { statement, index } => match statement.handle() {
+                    Some(handle) => sqlite3_column_blob(handle, index),
+
+                    // Null statements do not exist
+                    None => return &[],
+                },
+
+                SqliteValueHandle::Argument(value) => sqlite3_value_blob(value_ptr(value)),
             }
         };
 
@@ -123,6 +187,12 @@ impl<'c> SqliteValue<'c> {
     }
 }
 
+// The value functions take a mutable pointer, as they may convert the value in place (e.g.
+// to TEXT), which SQLite allows for the arguments of a function
+fn value_ptr(value: &sqlite3_value) -> *mut sqlite3_value {
+    value as *const sqlite3_value as *mut sqlite3_value
+}
+
 impl<'c> RawValue<'c> for SqliteValue<'c> {
     type Database = Sqlite;
 
@@ -0,0 +1,363 @@
+use core::mem::zeroed;
+use core::slice;
+
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use libsqlite3_sys::{
+    sqlite3, sqlite3_context, sqlite3_create_module_v2, sqlite3_declare_vtab, sqlite3_free,
+    sqlite3_index_info, sqlite3_int64, sqlite3_module, sqlite3_mprintf, sqlite3_value,
+    sqlite3_vtab, sqlite3_vtab_cursor, SQLITE_ERROR, SQLITE_OK,
+};
+
+use crate::encode::Encode;
+use crate::sqlite::function::{destroy, encode_result, set_result};
+use crate::sqlite::{Sqlite, SqliteArgumentValue, SqliteConnection, SqliteError};
+use crate::types::Type;
+
+/// A virtual table, whose rows are provided by Rust code instead of being stored in the
+/// database.
+///
+/// This allows to query data of the application with SQL (e.g. an in-memory collection, or
+/// the results of an API), and to join it with the tables of the database. Virtual tables
+/// of this trait are read-only; each scan of the table reads all of its rows, and SQLite
+/// filters and sorts them as needed.
+///
+/// ```rust,ignore
+/// struct Numbers(Arc<Vec<i64>>);
+///
+/// impl VTab for Numbers {
+///     type Cursor = NumbersCursor;
+///
+///     fn schema(&self) -> String {
+///         "CREATE TABLE x (value INTEGER)".into()
+///     }
+///
+///     fn open(&self) -> sqlx::Result<NumbersCursor> {
+///         Ok(NumbersCursor { numbers: self.0.clone(), index: 0 })
+///     }
+/// }
+///
+/// conn.create_module("numbers", |_| Ok(Numbers(numbers.clone())))?;
+///
+/// let (sum,): (i64,) = sqlx::query_as("SELECT sum(value) FROM numbers")
+///     .fetch_one(&mut conn)
+///     .await?;
+/// ```
+pub trait VTab: Send + 'static {
+    /// The cursor of a scan of the table.
+    type Cursor: VTabCursor;
+
+    /// Returns the columns of the table, as a `CREATE TABLE` statement (the name of the
+    /// table in the statement is ignored).
+    fn schema(&self) -> String;
+
+    /// Opens a cursor for a new scan of the table.
+    fn open(&self) -> crate::Result<Self::Cursor>;
+}
+
+/// A cursor over the rows of a [`VTab`].
+pub trait VTabCursor: 'static {
+    /// Starts a scan at the first row of the table; a cursor may be used for more than one
+    /// scan.
+    fn filter(&mut self) -> crate::Result<()>;
+
+    /// Moves to the next row of the table.
+    fn next(&mut self) -> crate::Result<()>;
+
+    /// Returns true if the cursor is past the last row of the table.
+    fn eof(&self) -> bool;
+
+    /// Sets the value of the column at `index` of the current row; the column is `NULL` if
+    /// it is not set.
+    fn column(&self, index: usize, column: &mut SqliteVTabColumn) -> crate::Result<()>;
+
+    /// Returns the rowid of the current row.
+    fn rowid(&self) -> crate::Result<i64>;
+}
+
+/// The value of a column of a row of a [`VTab`], which is set by [`VTabCursor::column`].
+pub struct SqliteVTabColumn {
+    value: SqliteArgumentValue,
+}
+
+impl SqliteVTabColumn {
+    /// Sets the value of the column, as an argument of a query is encoded.
+    pub fn set<T>(&mut self, value: T)
+    where
+        T: Type<Sqlite> + Encode<Sqlite>,
+    {
+        self.value = encode_result(value);
+    }
+}
+
+// The module of a [VTab], owned by SQLite from its registration until it is replaced or the
+// connection is closed
+struct Module<T> {
+    module: sqlite3_module,
+    connect: Connect<T>,
+}
+
+// The boxed constructor of the table of each `CREATE VIRTUAL TABLE`, with its arguments
+type Connect<T> = Box<dyn Fn(&[&str]) -> crate::Result<T> + Send>;
+
+// A table and a cursor start with the structures of SQLite, so that pointers to them can be
+// passed to SQLite and cast back in the methods of the module
+#[repr(C)]
+struct Table<T> {
+    base: sqlite3_vtab,
+    vtab: T,
+}
+
+#[repr(C)]
+struct Cursor<C> {
+    base: sqlite3_vtab_cursor,
+    cursor: C,
+}
+
+impl SqliteConnection {
+    /// Registers a module of virtual tables of type `T`, `name`.
+    ///
+    /// A table of the module is created with `CREATE VIRTUAL TABLE <table> USING
+    /// <name>(<arguments>)`, which calls `connect` with the arguments; the module can also be
+    /// queried as a table of the same name, which calls `connect` without arguments. The
+    /// tables are only usable on this connection. Registering a module again replaces it.
+    ///
+    /// See [`VTab`].
+    pub fn create_module<T, F>(&mut self, name: &str, connect: F) -> crate::Result<()>
+    where
+        T: VTab,
+        F: Fn(&[&str]) -> crate::Result<T> + Send + 'static,
+    {
+        let name = CString::new(name).map_err(|_| {
+            crate::Error::from(protocol_err!("module name {:?} contains a NUL", name))
+        })?;
+
+        // https://www.sqlite.org/vtab.html#the_virtual_table_method_table
+
+        // SAFE: a module without methods is all zeroes
+        let mut module: sqlite3_module = unsafe { zeroed() };
+
+        module.iVersion = 1;
+        // the same method for both makes the module eponymous
+        module.xCreate = Some(connect_vtab::<T>);
+        module.xConnect = Some(connect_vtab::<T>);
+        module.xBestIndex = Some(best_index);
+        module.xDisconnect = Some(disconnect_vtab::<T>);
+        module.xDestroy = Some(disconnect_vtab::<T>);
+        module.xOpen = Some(open::<T>);
+        module.xClose = Some(close::<T>);
+        module.xFilter = Some(filter::<T>);
+        module.xNext = Some(next::<T>);
+        module.xEof = Some(eof::<T>);
+        module.xColumn = Some(column::<T>);
+        module.xRowid = Some(rowid::<T>);
+
+        let module = Box::into_raw(Box::new(Module::<T> {
+            module,
+            connect: Box::new(connect),
+        }));
+
+        // https://www.sqlite.org/c3ref/create_module.html
+
+        // The client data is the module itself; SQLite calls [destroy] when the module is
+        // replaced or the connection is closed, and also if registering it fails
+        let status = unsafe {
+            sqlite3_create_module_v2(
+                self.handle(),
+                name.as_ptr(),
+                &(*module).module,
+                module as *mut c_void,
+                Some(destroy::<Module<T>>),
+            )
+        };
+
+        if status != SQLITE_OK {
+            return Err(SqliteError::from_connection(self.handle()).into());
+        }
+
+        Ok(())
+    }
+}
+
+// A panic must not unwind into SQLite
+fn guard<T>(f: impl FnOnce() -> crate::Result<T>) -> crate::Result<T> {
+    catch_unwind(AssertUnwindSafe(f))
+        .unwrap_or_else(|_| Err(protocol_err!("virtual table panicked").into()))
+}
+
+// Returns the message of an error to SQLite, which frees it
+unsafe fn error_message(error: &crate::Error) -> *mut c_char {
+    let message = CString::new(error.to_string().replace('\0', "")).unwrap_or_default();
+
+    sqlite3_mprintf(b"%s\0".as_ptr() as *const c_char, message.as_ptr())
+}
+
+unsafe fn set_error(vtab: *mut sqlite3_vtab, error: &crate::Error) -> c_int {
+    sqlite3_free((*vtab).zErrMsg as *mut c_void);
+    (*vtab).zErrMsg = error_message(error);
+
+    SQLITE_ERROR
+}
+
+unsafe extern "C" fn connect_vtab<T: VTab>(
+    db: *mut sqlite3,
+    data: *mut c_void,
+    argc: c_int,
+    argv: *const *const c_char,
+    vtab: *mut *mut sqlite3_vtab,
+    error: *mut *mut c_char,
+) -> c_int {
+    let module = &*(data as *const Module<T>);
+
+    // the first 3 arguments are the names of the module, the database and the table
+    let arguments: Vec<_> = slice::from_raw_parts(argv, argc as usize)
+        .iter()
+        .skip(3)
+        .map(|argument| CStr::from_ptr(*argument).to_string_lossy())
+        .collect();
+
+    let arguments: Vec<&str> = arguments.iter().map(|argument| &**argument).collect();
+
+    let table = guard(|| {
+        let table = (module.connect)(&arguments)?;
+        let schema = CString::new(table.schema())
+            .map_err(|_| protocol_err!("virtual table schema contains a NUL"))?;
+
+        Ok((table, schema))
+    });
+
+    let (table, schema) = match table {
+        Ok(table) => table,
+
+        Err(err) => {
+            *error = error_message(&err);
+
+            return SQLITE_ERROR;
+        }
+    };
+
+    // https://www.sqlite.org/c3ref/declare_vtab.html
+    let status = sqlite3_declare_vtab(db, schema.as_ptr());
+
+    if status != SQLITE_OK {
+        *error = error_message(&SqliteError::from_connection(db).into());
+
+        return status;
+    }
+
+    let table = Box::new(Table {
+        base: zeroed(),
+        vtab: table,
+    });
+
+    *vtab = Box::into_raw(table) as *mut sqlite3_vtab;
+
+    SQLITE_OK
+}
+
+unsafe extern "C" fn disconnect_vtab<T: VTab>(vtab: *mut sqlite3_vtab) -> c_int {
+    drop(Box::from_raw(vtab as *mut Table<T>));
+
+    SQLITE_OK
+}
+
+// Every scan reads all rows; the cost is high so that SQLite prefers other tables of a join
+// as the outer loop
+unsafe extern "C" fn best_index(_vtab: *mut sqlite3_vtab, info: *mut sqlite3_index_info) -> c_int {
+    (*info).estimatedCost = 1_000_000.0;
+
+    SQLITE_OK
+}
+
+unsafe extern "C" fn open<T: VTab>(
+    vtab: *mut sqlite3_vtab,
+    cursor: *mut *mut sqlite3_vtab_cursor,
+) -> c_int {
+    let table = &*(vtab as *const Table<T>);
+
+    match guard(|| table.vtab.open()) {
+        Ok(inner) => {
+            let inner = Box::new(Cursor {
+                base: zeroed(),
+                cursor: inner,
+            });
+
+            *cursor = Box::into_raw(inner) as *mut sqlite3_vtab_cursor;
+
+            SQLITE_OK
+        }
+
+        Err(error) => set_error(vtab, &error),
+    }
+}
+
+unsafe extern "C" fn close<T: VTab>(cursor: *mut sqlite3_vtab_cursor) -> c_int {
+    drop(Box::from_raw(cursor as *mut Cursor<T::Cursor>));
+
+    SQLITE_OK
+}
+
+// Runs a method of the cursor; an error is set on its table
+unsafe fn with_cursor<T: VTab>(
+    cursor: *mut sqlite3_vtab_cursor,
+    f: impl FnOnce(&mut T::Cursor) -> crate::Result<()>,
+) -> c_int {
+    let inner = &mut *(cursor as *mut Cursor<T::Cursor>);
+
+    match guard(|| f(&mut inner.cursor)) {
+        Ok(()) => SQLITE_OK,
+        Err(error) => set_error((*cursor).pVtab, &error),
+    }
+}
+
+unsafe extern "C" fn filter<T: VTab>(
+    cursor: *mut sqlite3_vtab_cursor,
+    _index: c_int,
+    _index_name: *const c_char,
+    _argc: c_int,
+    _argv: *mut *mut sqlite3_value,
+) -> c_int {
+    with_cursor::<T>(cursor, |cursor| cursor.filter())
+}
+
+unsafe extern "C" fn next<T: VTab>(cursor: *mut sqlite3_vtab_cursor) -> c_int {
+    with_cursor::<T>(cursor, |cursor| cursor.next())
+}
+
+unsafe extern "C" fn eof<T: VTab>(cursor: *mut sqlite3_vtab_cursor) -> c_int {
+    let inner = &*(cursor as *const Cursor<T::Cursor>);
+
+    // a panic ends the scan
+    catch_unwind(AssertUnwindSafe(|| inner.cursor.eof())).unwrap_or(true) as c_int
+}
+
+unsafe extern "C" fn column<T: VTab>(
+    cursor: *mut sqlite3_vtab_cursor,
+    ctx: *mut sqlite3_context,
+    index: c_int,
+) -> c_int {
+    let inner = &*(cursor as *const Cursor<T::Cursor>);
+
+    let mut column = SqliteVTabColumn {
+        value: SqliteArgumentValue::Null,
+    };
+
+    let result = guard(|| inner.cursor.column(index as usize, &mut column));
+
+    set_result(ctx, result.map(|_| column.value));
+
+    SQLITE_OK
+}
+
+unsafe extern "C" fn rowid<T: VTab>(
+    cursor: *mut sqlite3_vtab_cursor,
+    rowid: *mut sqlite3_int64,
+) -> c_int {
+    with_cursor::<T>(cursor, |cursor| {
+        *rowid = cursor.rowid()?;
+
+        Ok(())
+    })
+}
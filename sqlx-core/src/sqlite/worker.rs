@@ -2,26 +2,32 @@ use crossbeam_queue::ArrayQueue;
 use futures_channel::oneshot;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::thread::{park, spawn, JoinHandle};
+use std::thread::{park, Builder, JoinHandle};
 
 // After tinkering with this, I believe the safest solution is to spin up a discrete thread per
 // SQLite connection and perform all I/O operations for SQLite on _that_ thread. To this effect
 // we have a worker struct that is a thin message passing API to run messages on the worker thread.
 
+// Connections never share a worker, so the statements of the connections of a pool run in
+// parallel; the thread of a worker exits once the worker, and each of its clones, is dropped.
+
+type Message = Box<dyn FnOnce() + Send>;
+
 #[derive(Clone)]
 pub(crate) struct Worker {
     running: Arc<AtomicBool>,
-    queue: Arc<ArrayQueue<Box<dyn FnOnce() + Send>>>,
+    queue: Arc<ArrayQueue<Message>>,
     handle: Arc<JoinHandle<()>>,
 }
 
 impl Worker {
     pub(crate) fn new() -> Self {
-        let queue: Arc<ArrayQueue<Box<dyn FnOnce() + Send>>> = Arc::new(ArrayQueue::new(1));
+        let queue: Arc<ArrayQueue<Message>> = Arc::new(ArrayQueue::new(1));
         let running = Arc::new(AtomicBool::new(true));
 
-        Self {
-            handle: Arc::new(spawn({
+        let handle = Builder::new()
+            .name("sqlx-sqlite-worker".into())
+            .spawn({
                 let queue = queue.clone();
                 let running = running.clone();
 
@@ -34,7 +40,11 @@ impl Worker {
                         park();
                     }
                 }
-            })),
+            })
+            .expect("failed to spawn the worker thread of a SQLite connection");
+
+        Self {
+            handle: Arc::new(handle),
             queue,
             running,
         }
@@ -62,6 +72,40 @@ impl Drop for Worker {
     fn drop(&mut self) {
         if Arc::strong_count(&self.handle) == 1 {
             self.running.store(false, Ordering::SeqCst);
+
+            // the thread is parked until it is woken up to exit
+            self.handle.thread().unpark();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Worker;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn it_stops_the_thread_on_drop() {
+        let worker = Worker::new();
+
+        // the thread holds a reference to the queue until it exits
+        let queue = worker.queue.clone();
+
+        // a clone keeps the thread running
+        drop(worker.clone());
+        thread::sleep(Duration::from_millis(50));
+
+        assert_eq!(Arc::strong_count(&queue), 3);
+
+        drop(worker);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+
+        while Arc::strong_count(&queue) > 1 {
+            assert!(Instant::now() < deadline, "the worker thread did not exit");
+            thread::sleep(Duration::from_millis(1));
         }
     }
 }
@@ -40,24 +40,55 @@ where
 {
     inner: Option<C>,
     depth: u32,
+
+    // the name of the save point of a nested transaction
+    savepoint: Option<String>,
 }
 
 impl<C> Transaction<C>
 where
     C: Connection,
 {
-    pub(crate) async fn new(depth: u32, mut inner: C) -> crate::Result<Self> {
-        if depth == 0 {
-            inner.execute("BEGIN").await?;
+    pub(crate) async fn with_options(
+        mut inner: C,
+        options: TransactionOptions,
+    ) -> crate::Result<Self> {
+        for stmt in <C::Database as Database>::begin_transaction(&options)? {
+            inner.execute(&*stmt).await?;
+        }
+
+        Ok(Self {
+            inner: Some(inner),
+            depth: 1,
+            savepoint: None,
+        })
+    }
+
+    pub(crate) async fn new(depth: u32, inner: C) -> crate::Result<Self> {
+        let savepoint = if depth == 0 {
+            None
         } else {
-            let stmt = format!("SAVEPOINT _sqlx_savepoint_{}", depth);
+            Some(format!("_sqlx_savepoint_{}", depth))
+        };
 
-            inner.execute(&*stmt).await?;
+        Self::with_savepoint(depth, inner, savepoint).await
+    }
+
+    async fn with_savepoint(
+        depth: u32,
+        mut inner: C,
+        savepoint: Option<String>,
+    ) -> crate::Result<Self> {
+        if let Some(name) = &savepoint {
+            inner.execute(&*format!("SAVEPOINT {}", name)).await?;
+        } else {
+            inner.execute("BEGIN").await?;
         }
 
         Ok(Self {
             inner: Some(inner),
             depth: depth + 1,
+            savepoint,
         })
     }
 
@@ -67,18 +98,74 @@ where
         Transaction::new(self.depth, self).await
     }
 
+    /// Creates a new save point named `name` in the current transaction and returns
+    /// a new `Transaction` object to manage its scope, like [`begin`].
+    ///
+    /// The name must be an unquoted identifier: ASCII letters, digits and underscores,
+    /// not starting with a digit. While the save point is in scope, the changes made since
+    /// it was created can be discarded with [`rollback_to`], without ending its scope.
+    ///
+    /// ```rust,ignore
+    /// let mut tx = conn.begin().await?;
+    /// let mut sp = tx.savepoint("before_import").await?;
+    ///
+    /// for row in rows {
+    ///     if import(&mut sp, row).await.is_err() {
+    ///         sp.rollback_to("before_import").await?;
+    ///     }
+    /// }
+    ///
+    /// tx = sp.commit().await?;
+    /// ```
+    ///
+    /// [`begin`]: #method.begin
+    /// [`rollback_to`]: #method.rollback_to
+    pub async fn savepoint(self, name: &str) -> crate::Result<Transaction<Transaction<C>>> {
+        check_savepoint_name(name)?;
+
+        Transaction::with_savepoint(self.depth, self, Some(name.to_owned())).await
+    }
+
+    /// Discards the changes made since the save point named `name` was created, in this
+    /// transaction or an outer one. The save point is kept and can be rolled back to again.
+    pub async fn rollback_to(&mut self, name: &str) -> crate::Result<()> {
+        check_savepoint_name(name)?;
+
+        self.deref_mut()
+            .execute(&*format!("ROLLBACK TO SAVEPOINT {}", name))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Releases the save point named `name`, keeping the changes made since it was created.
+    ///
+    /// The save point, and any created after it, can no longer be rolled back to. This is
+    /// for save points created with `SAVEPOINT` directly; a save point created with
+    /// [`savepoint`] is released by committing the `Transaction` returned.
+    ///
+    /// [`savepoint`]: #method.savepoint
+    pub async fn release(&mut self, name: &str) -> crate::Result<()> {
+        check_savepoint_name(name)?;
+
+        self.deref_mut()
+            .execute(&*format!("RELEASE SAVEPOINT {}", name))
+            .await?;
+
+        Ok(())
+    }
+
     /// Commits the current transaction or save point.
     /// Returns the inner connection or transaction.
     pub async fn commit(mut self) -> crate::Result<C> {
         let mut inner = self.inner.take().expect(ERR_FINALIZED);
-        let depth = self.depth;
 
-        if depth == 1 {
-            inner.execute("COMMIT").await?;
+        if let Some(name) = &self.savepoint {
+            inner
+                .execute(&*format!("RELEASE SAVEPOINT {}", name))
+                .await?;
         } else {
-            let stmt = format!("RELEASE SAVEPOINT _sqlx_savepoint_{}", depth - 1);
-
-            inner.execute(&*stmt).await?;
+            inner.execute("COMMIT").await?;
         }
 
         Ok(inner)
@@ -88,22 +175,173 @@ where
     /// Returns the inner connection or transaction.
     pub async fn rollback(mut self) -> crate::Result<C> {
         let mut inner = self.inner.take().expect(ERR_FINALIZED);
-        let depth = self.depth;
 
-        if depth == 1 {
-            inner.execute("ROLLBACK").await?;
+        if let Some(name) = &self.savepoint {
+            inner
+                .execute(&*format!("ROLLBACK TO SAVEPOINT {}", name))
+                .await?;
         } else {
-            let stmt = format!("ROLLBACK TO SAVEPOINT _sqlx_savepoint_{}", depth - 1);
+            inner.execute("ROLLBACK").await?;
+        }
 
-            inner.execute(&*stmt).await?;
+        Ok(inner)
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl<C> Transaction<C>
+where
+    C: Connection<Database = crate::postgres::Postgres>,
+{
+    /// Prepares the current transaction for two-phase commit under the global
+    /// identifier `gid`, and returns the inner connection.
+    ///
+    /// The transaction is dissociated from the connection and stored by the server;
+    /// it is later finished with [`PgConnection::commit_prepared`] or
+    /// [`PgConnection::rollback_prepared`], from any connection. This requires
+    /// `max_prepared_transactions` to be set on the server and can not be used in
+    /// a save point.
+    ///
+    /// [`PgConnection::commit_prepared`]: crate::postgres::PgConnection::commit_prepared
+    /// [`PgConnection::rollback_prepared`]: crate::postgres::PgConnection::rollback_prepared
+    pub async fn prepare_twophase(mut self, gid: &str) -> crate::Result<C> {
+        if self.depth != 1 {
+            return Err(protocol_err!(
+                "a save point can not be prepared for two-phase commit; prepare the outer transaction"
+            )
+            .into());
         }
 
+        let stmt = format!(
+            "PREPARE TRANSACTION {}",
+            crate::postgres::two_phase::quote_gid(gid)?
+        );
+
+        let mut inner = self.inner.take().expect(ERR_FINALIZED);
+
+        inner.execute(&*stmt).await?;
+
         Ok(inner)
     }
 }
 
+/// The isolation level of a transaction, which determines how it is affected by
+/// concurrent transactions.
+///
+/// A database may run a transaction at a stricter level than requested; SQLite always runs
+/// transactions at `Serializable`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IsolationLevel {
+    ReadUncommitted,
+    ReadCommitted,
+    RepeatableRead,
+    Serializable,
+}
+
+impl IsolationLevel {
+    pub(crate) fn as_sql(self) -> &'static str {
+        match self {
+            IsolationLevel::ReadUncommitted => "READ UNCOMMITTED",
+            IsolationLevel::ReadCommitted => "READ COMMITTED",
+            IsolationLevel::RepeatableRead => "REPEATABLE READ",
+            IsolationLevel::Serializable => "SERIALIZABLE",
+        }
+    }
+}
+
+/// When a SQLite transaction locks the database.
+///
+/// See <https://www.sqlite.org/lang_transaction.html>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionBehavior {
+    /// Lock the database when it is first read or written to; the default.
+    Deferred,
+
+    /// Lock the database for writing right away, so the transaction does not fail later
+    /// on because another connection is writing.
+    Immediate,
+
+    /// Like `Immediate`, but also prevent other connections from reading unless the
+    /// database is in WAL mode.
+    Exclusive,
+}
+
+/// Options for beginning a transaction with [`Connection::begin_with`].
+///
+/// ```rust,ignore
+/// let mut tx = conn
+///     .begin_with(
+///         TransactionOptions::new()
+///             .isolation(IsolationLevel::Serializable)
+///             .read_only(true)
+///             .deferrable(true),
+///     )
+///     .await?;
+/// ```
+///
+/// Options that a database does not support are ignored, except for `read_only` on SQLite,
+/// which fails to begin the transaction.
+///
+/// [`Connection::begin_with`]: crate::connection::Connection::begin_with
+#[derive(Debug, Clone, Default)]
+pub struct TransactionOptions {
+    pub(crate) isolation: Option<IsolationLevel>,
+    pub(crate) read_only: bool,
+    pub(crate) deferrable: bool,
+    pub(crate) behavior: Option<TransactionBehavior>,
+}
+
+impl TransactionOptions {
+    /// Options for a transaction like one begun with [`Connection::begin`].
+    ///
+    /// [`Connection::begin`]: crate::connection::Connection::begin
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the isolation level of the transaction. Defaults to the isolation level of
+    /// the session.
+    pub fn isolation(mut self, level: IsolationLevel) -> Self {
+        self.isolation = Some(level);
+        self
+    }
+
+    /// Prevent the transaction from writing to tables (Postgres and MySQL).
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Let a `Serializable`, `read_only` transaction wait until it can run without
+    /// the risk of a serialization failure (Postgres).
+    pub fn deferrable(mut self, deferrable: bool) -> Self {
+        self.deferrable = deferrable;
+        self
+    }
+
+    /// Set when the transaction locks the database (SQLite).
+    pub fn behavior(mut self, behavior: TransactionBehavior) -> Self {
+        self.behavior = Some(behavior);
+        self
+    }
+}
+
 const ERR_FINALIZED: &str = "(bug) transaction already finalized";
 
+// Save point names are not quoted, as the quoting of identifiers differs between databases
+fn check_savepoint_name(name: &str) -> crate::Result<()> {
+    let mut chars = name.chars();
+
+    let valid = matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if valid {
+        Ok(())
+    } else {
+        Err(protocol_err!("invalid save point name: {:?}", name).into())
+    }
+}
+
 impl<C> Deref for Transaction<C>
 where
     C: Connection,
@@ -156,6 +394,19 @@ where
     fn ping(&mut self) -> BoxFuture<'_, crate::Result<()>> {
         self.deref_mut().ping()
     }
+
+    // The options of a transaction can not be changed once it has begun
+    fn begin_with(
+        self,
+        _options: TransactionOptions,
+    ) -> BoxFuture<'static, crate::Result<Transaction<Self>>> {
+        Box::pin(async {
+            Err(protocol_err!(
+                "a transaction can not be begun with options inside another transaction"
+            )
+            .into())
+        })
+    }
 }
 
 impl<DB, C> Executor for Transaction<C>
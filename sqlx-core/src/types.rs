@@ -35,12 +35,25 @@ pub mod time {
 #[cfg_attr(docsrs, doc(cfg(feature = "bigdecimal")))]
 pub use bigdecimal::BigDecimal;
 
+#[cfg(feature = "decimal")]
+#[cfg_attr(docsrs, doc(cfg(feature = "decimal")))]
+pub use rust_decimal::Decimal;
+
 #[cfg(feature = "ipnetwork")]
 #[cfg_attr(docsrs, doc(cfg(feature = "ipnetwork")))]
 pub mod ipnetwork {
     pub use ipnetwork::{IpNetwork, Ipv4Network, Ipv6Network};
 }
 
+#[cfg(any(feature = "postgis", feature = "mysql-spatial"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "postgis", feature = "mysql-spatial"))))]
+pub mod geo_types {
+    pub use geo_types::{
+        Coord, Geometry, GeometryCollection, Line, LineString, MultiLineString, MultiPoint,
+        MultiPolygon, Point, Polygon, Rect, Triangle,
+    };
+}
+
 #[cfg(feature = "json")]
 pub mod json {
     use crate::database::Database;
@@ -0,0 +1,338 @@
+//! Reading and writing of geometries in Well-Known Binary (WKB), shared by the
+//! spatial types of Postgres (PostGIS) and MySQL.
+
+use std::convert::TryInto;
+
+use geo_types::{
+    Coord, Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon,
+    Point, Polygon,
+};
+
+// <https://postgis.net/docs/using_postgis_dbmanagement.html#EWKB_EWKT>
+// <https://github.com/postgis/postgis/blob/master/doc/ZMSgeoms.txt>
+
+// Flags set in the geometry type of Extended WKB
+const EWKB_Z: u32 = 0x8000_0000;
+const EWKB_M: u32 = 0x4000_0000;
+const EWKB_SRID: u32 = 0x2000_0000;
+
+const POINT: u32 = 1;
+const LINE_STRING: u32 = 2;
+const POLYGON: u32 = 3;
+const MULTI_POINT: u32 = 4;
+const MULTI_LINE_STRING: u32 = 5;
+const MULTI_POLYGON: u32 = 6;
+const GEOMETRY_COLLECTION: u32 = 7;
+
+// Write a geometry in (little-endian) WKB, or in Extended WKB with an SRID
+pub(crate) fn write_geometry(buf: &mut Vec<u8>, geometry: &Geometry<f64>, srid: Option<u32>) {
+    match geometry {
+        Geometry::Point(point) => {
+            write_header(buf, POINT, srid);
+            write_coord(buf, point.0);
+        }
+
+        Geometry::Line(line) => {
+            write_header(buf, LINE_STRING, srid);
+            write_u32(buf, 2);
+            write_coord(buf, line.start);
+            write_coord(buf, line.end);
+        }
+
+        Geometry::LineString(line) => {
+            write_header(buf, LINE_STRING, srid);
+            write_coords(buf, line);
+        }
+
+        Geometry::Polygon(polygon) => {
+            write_header(buf, POLYGON, srid);
+            write_polygon_rings(buf, polygon);
+        }
+
+        Geometry::Rect(rect) => {
+            write_header(buf, POLYGON, srid);
+            write_polygon_rings(buf, &rect.to_polygon());
+        }
+
+        Geometry::Triangle(triangle) => {
+            write_header(buf, POLYGON, srid);
+            write_polygon_rings(buf, &triangle.to_polygon());
+        }
+
+        Geometry::MultiPoint(points) => {
+            write_header(buf, MULTI_POINT, srid);
+            write_u32(buf, points.0.len() as u32);
+
+            for point in &points.0 {
+                write_header(buf, POINT, None);
+                write_coord(buf, point.0);
+            }
+        }
+
+        Geometry::MultiLineString(lines) => {
+            write_header(buf, MULTI_LINE_STRING, srid);
+            write_u32(buf, lines.0.len() as u32);
+
+            for line in &lines.0 {
+                write_header(buf, LINE_STRING, None);
+                write_coords(buf, line);
+            }
+        }
+
+        Geometry::MultiPolygon(polygons) => {
+            write_header(buf, MULTI_POLYGON, srid);
+            write_u32(buf, polygons.0.len() as u32);
+
+            for polygon in &polygons.0 {
+                write_header(buf, POLYGON, None);
+                write_polygon_rings(buf, polygon);
+            }
+        }
+
+        Geometry::GeometryCollection(geometries) => {
+            write_header(buf, GEOMETRY_COLLECTION, srid);
+            write_u32(buf, geometries.0.len() as u32);
+
+            for geometry in &geometries.0 {
+                write_geometry(buf, geometry, None);
+            }
+        }
+    }
+}
+
+fn write_header(buf: &mut Vec<u8>, kind: u32, srid: Option<u32>) {
+    // little-endian (NDR)
+    buf.push(1);
+
+    match srid {
+        Some(srid) => {
+            write_u32(buf, kind | EWKB_SRID);
+            write_u32(buf, srid);
+        }
+
+        None => {
+            write_u32(buf, kind);
+        }
+    }
+}
+
+fn write_polygon_rings(buf: &mut Vec<u8>, polygon: &Polygon<f64>) {
+    // an empty polygon has no rings
+    if polygon.exterior().0.is_empty() && polygon.interiors().is_empty() {
+        write_u32(buf, 0);
+        return;
+    }
+
+    write_u32(buf, 1 + polygon.interiors().len() as u32);
+    write_coords(buf, polygon.exterior());
+
+    for ring in polygon.interiors() {
+        write_coords(buf, ring);
+    }
+}
+
+fn write_coords(buf: &mut Vec<u8>, line: &LineString<f64>) {
+    write_u32(buf, line.0.len() as u32);
+
+    for coord in &line.0 {
+        write_coord(buf, *coord);
+    }
+}
+
+fn write_coord(buf: &mut Vec<u8>, coord: Coord<f64>) {
+    buf.extend_from_slice(&coord.x.to_le_bytes());
+    buf.extend_from_slice(&coord.y.to_le_bytes());
+}
+
+fn write_u32(buf: &mut Vec<u8>, value: u32) {
+    buf.extend_from_slice(&value.to_le_bytes());
+}
+
+// Read a geometry in Extended WKB (or ISO WKB) and return it with its SRID, if any
+pub(crate) fn read_geometry(buf: &mut &[u8]) -> crate::Result<(Geometry<f64>, Option<u32>)> {
+    let mut reader = Reader::new(buf)?;
+    let kind = reader.u32()?;
+
+    let srid = if kind & EWKB_SRID != 0 {
+        Some(reader.u32()?)
+    } else {
+        None
+    };
+
+    // ISO WKB adds 1000 (Z), 2000 (M) or 3000 (ZM) to the geometry type instead of flags
+    let iso = (kind & 0x0FFF_FFFF) / 1000;
+    let has_z = kind & EWKB_Z != 0 || iso == 1 || iso == 3;
+    let has_m = kind & EWKB_M != 0 || iso == 2 || iso == 3;
+
+    reader.dims = 2 + has_z as usize + has_m as usize;
+
+    let geometry = match (kind & 0x0FFF_FFFF) % 1000 {
+        POINT => {
+            let coord = reader.coord()?;
+
+            if coord.x.is_nan() && coord.y.is_nan() {
+                return Err(decode_err!("wkb: empty points are not supported"));
+            }
+
+            Geometry::Point(Point(coord))
+        }
+
+        LINE_STRING => Geometry::LineString(reader.line_string()?),
+        POLYGON => Geometry::Polygon(reader.polygon()?),
+
+        MULTI_POINT => Geometry::MultiPoint(MultiPoint(reader.collection(
+            |geometry| match geometry {
+                Geometry::Point(point) => Some(point),
+                _ => None,
+            },
+        )?)),
+
+        MULTI_LINE_STRING => Geometry::MultiLineString(MultiLineString(reader.collection(
+            |geometry| match geometry {
+                Geometry::LineString(line) => Some(line),
+                _ => None,
+            },
+        )?)),
+
+        MULTI_POLYGON => {
+            Geometry::MultiPolygon(MultiPolygon(reader.collection(
+                |geometry| match geometry {
+                    Geometry::Polygon(polygon) => Some(polygon),
+                    _ => None,
+                },
+            )?))
+        }
+
+        GEOMETRY_COLLECTION => {
+            Geometry::GeometryCollection(GeometryCollection(reader.collection(Some)?))
+        }
+
+        kind => {
+            return Err(decode_err!("wkb: unsupported geometry type: {}", kind));
+        }
+    };
+
+    *buf = reader.buf;
+
+    Ok((geometry, srid))
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    little_endian: bool,
+
+    // the number of coordinates per point
+    dims: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8]) -> crate::Result<Self> {
+        let little_endian = match buf.first() {
+            Some(0) => false,
+            Some(1) => true,
+
+            order => {
+                return Err(decode_err!("wkb: invalid byte order: {:?}", order));
+            }
+        };
+
+        Ok(Reader {
+            buf: &buf[1..],
+            little_endian,
+            dims: 2,
+        })
+    }
+
+    fn take(&mut self, len: usize) -> crate::Result<&'a [u8]> {
+        if self.buf.len() < len {
+            return Err(decode_err!("wkb: unexpected end of data"));
+        }
+
+        let (bytes, rest) = self.buf.split_at(len);
+        self.buf = rest;
+
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> crate::Result<u32> {
+        let bytes = self.take(4)?.try_into().unwrap();
+
+        Ok(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn f64(&mut self) -> crate::Result<f64> {
+        let bytes = self.take(8)?.try_into().unwrap();
+
+        Ok(if self.little_endian {
+            f64::from_le_bytes(bytes)
+        } else {
+            f64::from_be_bytes(bytes)
+        })
+    }
+
+    fn coord(&mut self) -> crate::Result<Coord<f64>> {
+        let coord = Coord {
+            x: self.f64()?,
+            y: self.f64()?,
+        };
+
+        // Z and M are not supported by `geo-types`
+        for _ in 2..self.dims {
+            self.f64()?;
+        }
+
+        Ok(coord)
+    }
+
+    fn line_string(&mut self) -> crate::Result<LineString<f64>> {
+        let len = self.u32()?;
+        let mut coords = Vec::with_capacity(len.min(1024) as usize);
+
+        for _ in 0..len {
+            coords.push(self.coord()?);
+        }
+
+        Ok(LineString(coords))
+    }
+
+    fn polygon(&mut self) -> crate::Result<Polygon<f64>> {
+        let len = self.u32()?;
+
+        if len == 0 {
+            return Ok(Polygon::new(LineString(Vec::new()), Vec::new()));
+        }
+
+        let exterior = self.line_string()?;
+        let mut interiors = Vec::with_capacity((len - 1).min(1024) as usize);
+
+        for _ in 1..len {
+            interiors.push(self.line_string()?);
+        }
+
+        Ok(Polygon::new(exterior, interiors))
+    }
+
+    // Read the members of a multi-geometry or collection, each with their own header
+    fn collection<T>(
+        &mut self,
+        member: impl Fn(Geometry<f64>) -> Option<T>,
+    ) -> crate::Result<Vec<T>> {
+        let len = self.u32()?;
+        let mut members = Vec::with_capacity(len.min(1024) as usize);
+
+        for _ in 0..len {
+            let (geometry, _) = read_geometry(&mut self.buf)?;
+
+            members.push(
+                member(geometry)
+                    .ok_or_else(|| decode_err!("wkb: unexpected member of multi-geometry"))?,
+            );
+        }
+
+        Ok(members)
+    }
+}
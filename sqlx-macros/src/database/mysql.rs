@@ -19,6 +19,9 @@ impl_database_ext! {
         // BINARY, VAR_BINARY, BLOB
         Vec<u8>,
 
+        // SET
+        Vec<String>,
+
         #[cfg(all(feature = "chrono", not(feature = "time")))]
         sqlx::types::chrono::NaiveTime,
 
@@ -45,6 +48,12 @@ impl_database_ext! {
 
         #[cfg(feature = "bigdecimal")]
         sqlx::types::BigDecimal,
+
+        #[cfg(all(feature = "decimal", not(feature = "bigdecimal")))]
+        sqlx::types::Decimal,
+
+        #[cfg(feature = "mysql-spatial")]
+        sqlx::types::geo_types::Geometry<f64>,
     },
     ParamChecking::Weak,
     feature-types: info => info.type_feature_gate(),
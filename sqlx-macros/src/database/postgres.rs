@@ -14,6 +14,26 @@ impl_database_ext! {
 
         Vec<u8> | &[u8],
 
+        sqlx::postgres::types::PgMoney,
+
+        sqlx::postgres::types::PgInterval,
+
+        sqlx::postgres::types::PgLsn,
+
+        sqlx::postgres::types::PgXid8,
+
+        sqlx::postgres::types::PgHstore,
+
+        sqlx::postgres::types::PgLtree,
+
+        sqlx::postgres::types::PgLquery,
+
+        sqlx::postgres::types::PgJsonPath,
+
+        sqlx::postgres::types::PgTsVector,
+
+        sqlx::postgres::types::PgTsQuery,
+
         #[cfg(feature = "uuid")]
         sqlx::types::Uuid,
 
@@ -50,6 +70,61 @@ impl_database_ext! {
         #[cfg(feature = "json")]
         serde_json::Value,
 
+        #[cfg(feature = "postgis")]
+        sqlx::types::geo_types::Geometry<f64>,
+
+        // Ranges
+
+        sqlx::postgres::types::PgRange<i32>,
+
+        sqlx::postgres::types::PgRange<i64>,
+
+        #[cfg(feature = "bigdecimal")]
+        sqlx::postgres::types::PgRange<sqlx::types::BigDecimal>,
+
+        #[cfg(feature = "chrono")]
+        sqlx::postgres::types::PgRange<sqlx::types::chrono::NaiveDate>,
+
+        #[cfg(feature = "chrono")]
+        sqlx::postgres::types::PgRange<sqlx::types::chrono::NaiveDateTime>,
+
+        #[cfg(feature = "chrono")]
+        sqlx::postgres::types::PgRange<sqlx::types::chrono::DateTime<sqlx::types::chrono::Utc>>,
+
+        #[cfg(feature = "time")]
+        sqlx::postgres::types::PgRange<sqlx::types::time::Date>,
+
+        #[cfg(feature = "time")]
+        sqlx::postgres::types::PgRange<sqlx::types::time::PrimitiveDateTime>,
+
+        #[cfg(feature = "time")]
+        sqlx::postgres::types::PgRange<sqlx::types::time::OffsetDateTime>,
+
+        sqlx::postgres::types::PgMultirange<i32>,
+
+        sqlx::postgres::types::PgMultirange<i64>,
+
+        #[cfg(feature = "bigdecimal")]
+        sqlx::postgres::types::PgMultirange<sqlx::types::BigDecimal>,
+
+        #[cfg(feature = "chrono")]
+        sqlx::postgres::types::PgMultirange<sqlx::types::chrono::NaiveDate>,
+
+        #[cfg(feature = "chrono")]
+        sqlx::postgres::types::PgMultirange<sqlx::types::chrono::NaiveDateTime>,
+
+        #[cfg(feature = "chrono")]
+        sqlx::postgres::types::PgMultirange<sqlx::types::chrono::DateTime<sqlx::types::chrono::Utc>>,
+
+        #[cfg(feature = "time")]
+        sqlx::postgres::types::PgMultirange<sqlx::types::time::Date>,
+
+        #[cfg(feature = "time")]
+        sqlx::postgres::types::PgMultirange<sqlx::types::time::PrimitiveDateTime>,
+
+        #[cfg(feature = "time")]
+        sqlx::postgres::types::PgMultirange<sqlx::types::time::OffsetDateTime>,
+
         // Arrays
         Vec<bool> | &[bool],
         Vec<String> | &[String],
@@ -60,6 +135,11 @@ impl_database_ext! {
         Vec<i64> | &[i64],
         Vec<f32> | &[f32],
         Vec<f64> | &[f64],
+        Vec<sqlx::postgres::types::PgMoney> | &[sqlx::postgres::types::PgMoney],
+        Vec<sqlx::postgres::types::PgInterval> | &[sqlx::postgres::types::PgInterval],
+        Vec<sqlx::postgres::types::PgLsn> | &[sqlx::postgres::types::PgLsn],
+        Vec<sqlx::postgres::types::PgXid8> | &[sqlx::postgres::types::PgXid8],
+        Vec<sqlx::postgres::types::PgJsonPath> | &[sqlx::postgres::types::PgJsonPath],
 
 
         #[cfg(feature = "uuid")]
@@ -85,6 +85,15 @@ pub fn parse_container_attributes(input: &[Attribute]) -> syn::Result<SqlxContai
                                 ..
                             }) if path.is_ident("rename") => try_set!(rename, val.value(), value),
 
+                            // on a type, `rename` is the name of the SQL type
+                            Meta::NameValue(MetaNameValue {
+                                path,
+                                lit: Lit::Str(val),
+                                ..
+                            }) if path.is_ident("type_name") => {
+                                try_set!(rename, val.value(), value)
+                            }
+
                             u => fail!(u, "unexpected attribute"),
                         },
                         u => fail!(u, "unexpected attribute"),
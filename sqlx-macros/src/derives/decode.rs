@@ -173,10 +173,12 @@ fn expand_derive_decode_struct(
 
         let predicates = &mut generics.make_where_clause().predicates;
 
+        // fields are decoded from the record and cannot borrow from it
         for field in fields {
             let ty = &field.ty;
 
-            predicates.push(parse_quote!(#ty: sqlx::decode::Decode<'de, sqlx::Postgres>));
+            predicates
+                .push(parse_quote!(#ty: for<'rec> sqlx::decode::Decode<'rec, sqlx::Postgres>));
             predicates.push(parse_quote!(#ty: sqlx::types::Type<sqlx::Postgres>));
         }
 
@@ -118,6 +118,7 @@ fn expand_derive_has_sql_type_strong_enum(
 
     if cfg!(feature = "postgres") {
         let ty_name = attributes.rename.unwrap_or_else(|| ident.to_string());
+        let array_ty_name = format!("_{}", ty_name);
 
         tts.extend(quote!(
             impl sqlx::Type< sqlx::Postgres > for #ident {
@@ -125,6 +126,12 @@ fn expand_derive_has_sql_type_strong_enum(
                     sqlx::postgres::PgTypeInfo::with_name(#ty_name)
                 }
             }
+
+            impl sqlx::postgres::types::PgHasArrayType for #ident {
+                fn array_type_info() -> sqlx::postgres::PgTypeInfo {
+                    sqlx::postgres::PgTypeInfo::with_name(#array_ty_name)
+                }
+            }
         ));
     }
 
@@ -152,6 +159,7 @@ fn expand_derive_has_sql_type_struct(
 
     if cfg!(feature = "postgres") {
         let ty_name = attributes.rename.unwrap_or_else(|| ident.to_string());
+        let array_ty_name = format!("_{}", ty_name);
 
         tts.extend(quote!(
             impl sqlx::types::Type< sqlx::Postgres > for #ident {
@@ -159,6 +167,12 @@ fn expand_derive_has_sql_type_struct(
                     sqlx::postgres::PgTypeInfo::with_name(#ty_name)
                 }
             }
+
+            impl sqlx::postgres::types::PgHasArrayType for #ident {
+                fn array_type_info() -> sqlx::postgres::PgTypeInfo {
+                    sqlx::postgres::PgTypeInfo::with_name(#array_ty_name)
+                }
+            }
         ));
     }
 
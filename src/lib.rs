@@ -9,7 +9,9 @@ pub use sqlx_core::pool::{self, Pool};
 pub use sqlx_core::query::{self, query, Query};
 pub use sqlx_core::query_as::{query_as, QueryAs};
 pub use sqlx_core::row::{self, FromRow, Row};
-pub use sqlx_core::transaction::Transaction;
+pub use sqlx_core::transaction::{
+    IsolationLevel, Transaction, TransactionBehavior, TransactionOptions,
+};
 pub use sqlx_core::value;
 
 #[doc(hidden)]
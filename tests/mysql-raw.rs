@@ -1,8 +1,19 @@
 //! Tests for the raw (unprepared) query API for MySql.
 
-use sqlx::{Cursor, Executor, MySql, Row};
+use sqlx::{Connect, Cursor, Executor, MySql, MySqlConnection, Row};
 use sqlx_test::new;
 
+async fn connect_without_multi_statements() -> anyhow::Result<MySqlConnection> {
+    let url = dotenv::var("DATABASE_URL")?;
+    let url = format!(
+        "{}{}multi-statements=false",
+        url,
+        if url.contains('?') { '&' } else { '?' }
+    );
+
+    Ok(MySqlConnection::connect(&*url).await?)
+}
+
 /// Test a simple select expression. This should return the row.
 #[cfg_attr(feature = "runtime-async-std", async_std::test)]
 #[cfg_attr(feature = "runtime-tokio", tokio::test)]
@@ -54,3 +65,46 @@ SELECT id, text FROM messages;
 
     Ok(())
 }
+
+/// Test that the rows of a query of multiple statements are returned
+/// with the index of the statement that they belong to.
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn test_multi_result_index() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let mut cursor = conn.fetch(
+        "
+SELECT 1 UNION ALL SELECT 2;
+DO 0;
+SELECT 3;
+        ",
+    );
+
+    let mut rows = Vec::new();
+
+    while let Some((index, row)) = cursor.next_with_index().await? {
+        rows.push((index, row.try_get::<i64, _>(0)?));
+    }
+
+    assert_eq!(rows, vec![(0, 1), (0, 2), (2, 3)]);
+
+    Ok(())
+}
+
+/// Test that a query of multiple statements fails when they are turned off.
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn test_multi_statements_can_be_turned_off() -> anyhow::Result<()> {
+    let mut conn = connect_without_multi_statements().await?;
+
+    assert!(conn.execute("DO 0; DO 1").await.is_err());
+
+    // the connection is still usable afterwards
+    let mut cursor = conn.fetch("SELECT 5");
+    let row = cursor.next().await?.unwrap();
+
+    assert!(5i32 == row.try_get::<i32, _>(0)?);
+
+    Ok(())
+}
@@ -20,7 +20,13 @@ test_type!(i16(MySql, i16, "21415" == 21415_i16));
 test_type!(u32(MySql, u32, "CAST(2141512 AS UNSIGNED)" == 2141512_u32));
 test_type!(i32(MySql, i32, "2141512" == 2141512_i32));
 
-test_type!(u64(MySql, u64, "CAST(2141512 AS UNSIGNED)" == 2141512_u64));
+test_type!(u64(
+    MySql,
+    u64,
+    "CAST(2141512 AS UNSIGNED)" == 2141512_u64,
+    "CAST(9223372036854775808 AS UNSIGNED)" == 9223372036854775808_u64,
+    "CAST(18446744073709551615 AS UNSIGNED)" == u64::MAX
+));
 test_type!(i64(MySql, i64, "2141512" == 2141512_i64));
 
 test_type!(double(MySql, f64, "3.14159265E0" == 3.14159265f64));
@@ -137,6 +143,18 @@ test_type!(decimal(
     "CAST(12345.6789 AS DECIMAL(9, 4))" == "12345.6789".parse::<sqlx::types::BigDecimal>().unwrap(),
 ));
 
+#[cfg(feature = "decimal")]
+test_type!(rust_decimal(
+    MySql,
+    sqlx::types::Decimal,
+    "CAST(0 as DECIMAL(0, 0))" == sqlx::types::Decimal::from(0),
+    "CAST(-1 AS DECIMAL(1, 0))" == sqlx::types::Decimal::from(-1),
+    "CAST(10000 AS DECIMAL(5, 0))" == sqlx::types::Decimal::from(10000),
+    "CAST(0.01234 AS DECIMAL(6, 5))" == sqlx::types::Decimal::new(1234, 5),
+    "CAST(-12345.6789 AS DECIMAL(9, 4))" == sqlx::types::Decimal::new(-123456789, 4),
+    "CAST(1 AS DECIMAL(65, 30))" == sqlx::types::Decimal::from(1),
+));
+
 #[cfg(feature = "json")]
 mod json_tests {
     use super::*;
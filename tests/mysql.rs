@@ -232,3 +232,869 @@ async fn test_fetch_one_and_ping() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_connects_with_compression() -> anyhow::Result<()> {
+    use sqlx::{Connect, MySqlConnection};
+
+    let url = dotenv::var("DATABASE_URL")?;
+    let url = format!(
+        "{}{}compression-algorithms=zlib",
+        url,
+        if url.contains('?') { '&' } else { '?' }
+    );
+
+    let mut conn = MySqlConnection::connect(&*url).await?;
+
+    // a result large enough to be compressed, spread over several compressed packets
+    let (text,): (String,) = sqlx::query_as("SELECT REPEAT('sqlx', 10000)")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(text, "sqlx".repeat(10000));
+
+    let (_, compression): (String, String) =
+        sqlx::query_as("SHOW SESSION STATUS LIKE 'Compression'")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(compression, "ON");
+
+    conn.ping().await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_connects_with_collation() -> anyhow::Result<()> {
+    use sqlx::{Connect, MySqlConnection};
+
+    let url = dotenv::var("DATABASE_URL")?;
+    let url = format!(
+        "{}{}collation=utf8mb4_bin",
+        url,
+        if url.contains('?') { '&' } else { '?' }
+    );
+
+    let mut conn = MySqlConnection::connect(&*url).await?;
+
+    let (charset, collation): (String, String) =
+        sqlx::query_as("SELECT @@character_set_connection, @@collation_connection")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(charset, "utf8mb4");
+    assert_eq!(collation, "utf8mb4_bin");
+
+    // a case-sensitive collation
+    let (equal,): (i64,) = sqlx::query_as("SELECT 'a' = 'A'")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(equal, 0);
+
+    Ok(())
+}
+
+#[cfg(feature = "chrono")]
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_connects_with_timezone() -> anyhow::Result<()> {
+    use sqlx::types::chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+    use sqlx::{Connect, MySqlConnection};
+
+    let url = dotenv::var("DATABASE_URL")?;
+    let url = format!(
+        "{}{}timezone=%2B02:00",
+        url,
+        if url.contains('?') { '&' } else { '?' }
+    );
+
+    let mut conn = MySqlConnection::connect(&*url).await?;
+
+    let (time_zone,): (String,) = sqlx::query_as("SELECT @@session.time_zone")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(time_zone, "+02:00");
+
+    // midnight UTC is 2 AM in the session time zone
+    let (local,): (NaiveDateTime,) = sqlx::query_as("SELECT FROM_UNIXTIME(0)")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(local, NaiveDate::from_ymd(1970, 1, 1).and_hms(2, 0, 0));
+
+    conn.execute("CREATE TEMPORARY TABLE _sqlx_timezone_test (ts TIMESTAMP)")
+        .await?;
+
+    sqlx::query("INSERT INTO _sqlx_timezone_test (ts) VALUES (?)")
+        .bind(NaiveDate::from_ymd(2020, 1, 1).and_hms(12, 0, 0))
+        .execute(&mut conn)
+        .await?;
+
+    let (ts, utc): (NaiveDateTime, i64) =
+        sqlx::query_as("SELECT ts, UNIX_TIMESTAMP(ts) FROM _sqlx_timezone_test")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(ts, NaiveDate::from_ymd(2020, 1, 1).and_hms(12, 0, 0));
+    assert_eq!(utc, 1577872800); // 2020-01-01 10:00:00 UTC
+
+    // the types of UTC timestamps are rejected instead of being shifted by 2 hours
+    let decoded = sqlx::query_as::<_, (DateTime<Utc>,)>("SELECT ts FROM _sqlx_timezone_test")
+        .fetch_one(&mut conn)
+        .await;
+
+    assert!(decoded.is_err());
+
+    let bound = sqlx::query("SELECT ?")
+        .bind(Utc::now())
+        .execute(&mut conn)
+        .await;
+
+    assert!(bound.is_err());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_sends_connection_attributes() -> anyhow::Result<()> {
+    use sqlx::{Connect, MySqlConnection};
+
+    let url = dotenv::var("DATABASE_URL")?;
+    let url = format!(
+        "{}{}connection-attributes=program_name:sqlx-tests",
+        url,
+        if url.contains('?') { '&' } else { '?' }
+    );
+
+    let mut conn = MySqlConnection::connect(&*url).await?;
+
+    // the attributes can only be seen if the performance schema is enabled
+    let attrs: Result<Vec<(String, String)>, _> = sqlx::query_as(
+        "SELECT ATTR_NAME, ATTR_VALUE FROM performance_schema.session_connect_attrs \
+         WHERE PROCESSLIST_ID = CONNECTION_ID() ORDER BY ORDINAL_POSITION",
+    )
+    .fetch_all(&mut conn)
+    .await;
+
+    let attrs = match attrs {
+        Ok(attrs) if !attrs.is_empty() => attrs,
+        _ => return Ok(()),
+    };
+
+    assert!(attrs.contains(&("_client_name".to_owned(), "sqlx".to_owned())));
+    assert!(attrs.contains(&("program_name".to_owned(), "sqlx-tests".to_owned())));
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_calls_procedures_with_out_parameters() -> anyhow::Result<()> {
+    use sqlx::Row;
+
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute("DROP PROCEDURE IF EXISTS sqlx_add_one")
+        .await?;
+
+    conn.execute(
+        r#"
+CREATE PROCEDURE sqlx_add_one(IN n INT, OUT sum INT)
+BEGIN
+    SELECT n;
+    SET sum = n + 1;
+END
+        "#,
+    )
+    .await?;
+
+    // the result set of the OUT parameters follows the result set of the procedure
+    let mut cursor = sqlx::query("CALL sqlx_add_one(?, ?)")
+        .bind(41_i32)
+        .bind(0_i32)
+        .fetch(&mut conn);
+
+    let mut rows = Vec::new();
+
+    while let Some((index, row)) = cursor.next_with_index().await? {
+        rows.push((index, row.try_get::<i32, _>(0)?));
+    }
+
+    assert_eq!(rows, vec![(0, 41), (1, 42)]);
+
+    // the remaining results are skipped when a query is executed or dropped early
+    sqlx::query("CALL sqlx_add_one(?, ?)")
+        .bind(1_i32)
+        .bind(0_i32)
+        .execute(&mut conn)
+        .await?;
+
+    let (n,): (i32,) = sqlx::query_as("CALL sqlx_add_one(?, ?)")
+        .bind(1_i32)
+        .bind(0_i32)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(n, 1);
+
+    conn.ping().await?;
+
+    sqlx::query("CALL sqlx_add_one(?, @sum)")
+        .bind(9_i32)
+        .execute(&mut conn)
+        .await?;
+
+    let (sum,): (i64,) = sqlx::query_as("SELECT @sum").fetch_one(&mut conn).await?;
+
+    assert_eq!(sum, 10);
+
+    conn.execute("DROP PROCEDURE sqlx_add_one").await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_fetches_result_sets_one_at_a_time() -> anyhow::Result<()> {
+    use sqlx::Row;
+
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute("DROP PROCEDURE IF EXISTS sqlx_report").await?;
+
+    conn.execute(
+        r#"
+CREATE PROCEDURE sqlx_report()
+BEGIN
+    SELECT 1 AS a, 'one' AS b;
+    SELECT 2.5 AS c;
+END
+        "#,
+    )
+    .await?;
+
+    let mut results = conn.fetch_results("CALL sqlx_report()");
+
+    let mut set = results.next().await?.unwrap();
+    let names: Vec<_> = set.columns().iter().map(|c| c.name.clone()).collect();
+
+    assert_eq!(set.index(), 0);
+    assert_eq!(names, vec![Some("a".into()), Some("b".into())]);
+
+    let row = set.next().await?.unwrap();
+
+    assert_eq!(row.try_get::<i32, _>("a")?, 1);
+    assert_eq!(row.try_get::<String, _>("b")?, "one");
+    assert!(set.next().await?.is_none());
+
+    // the rows of a result set that are not read are skipped
+    let set = results.next().await?.unwrap();
+
+    assert_eq!(set.index(), 1);
+    assert_eq!(set.columns().len(), 1);
+    assert_eq!(set.columns()[0].name.as_deref(), Some("c"));
+
+    // the status of the CALL
+    let set = results.next().await?.unwrap();
+
+    assert_eq!(set.index(), 2);
+    assert!(set.columns().is_empty());
+
+    assert!(results.next().await?.is_none());
+
+    // results that are not read are skipped before the next query
+    let mut results = conn.fetch_results("CALL sqlx_report()");
+    results.next().await?;
+    drop(results);
+
+    let mut results = conn.fetch_results("CALL sqlx_report()");
+    assert!(results.next().await?.is_some());
+    assert!(results.next().await?.is_some());
+    drop(results);
+
+    let (n,): (i32,) = sqlx::query_as("SELECT 7").fetch_one(&mut conn).await?;
+    assert_eq!(n, 7);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_streams_the_binlog() -> anyhow::Result<()> {
+    use sqlx::mysql::binlog::{MySqlBinlogEvent, MySqlBinlogValue};
+
+    let mut conn = new::<MySql>().await?;
+
+    let (gtid_mode,): (String,) = sqlx::query_as("SELECT @@gtid_mode")
+        .fetch_one(&mut conn)
+        .await?;
+
+    if gtid_mode != "ON" {
+        // the binlog can only be streamed from servers with GTIDs
+        return Ok(());
+    }
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS _sqlx_binlog_test (id INTEGER PRIMARY KEY, name TEXT)",
+    )
+    .await?;
+
+    conn.execute("DELETE FROM _sqlx_binlog_test").await?;
+
+    let (gtid_executed,): (String,) = sqlx::query_as("SELECT @@gtid_executed")
+        .fetch_one(&mut conn)
+        .await?;
+
+    let mut binlog_conn = new::<MySql>().await?;
+    let mut stream = binlog_conn.start_binlog(0xC0DE, &gtid_executed).await?;
+
+    sqlx::query("INSERT INTO _sqlx_binlog_test (id, name) VALUES (?, ?)")
+        .bind(1_i32)
+        .bind("John")
+        .execute(&mut conn)
+        .await?;
+
+    sqlx::query("UPDATE _sqlx_binlog_test SET name = NULL WHERE id = ?")
+        .bind(1_i32)
+        .execute(&mut conn)
+        .await?;
+
+    let mut changes = Vec::new();
+
+    while changes.len() < 2 {
+        match stream.next().await?.expect("the binlog ended") {
+            MySqlBinlogEvent::Insert { table, after } => {
+                assert_eq!(table.name, "_sqlx_binlog_test");
+                changes.push((None, after));
+            }
+
+            MySqlBinlogEvent::Update {
+                table,
+                before,
+                after,
+            } => {
+                assert_eq!(table.name, "_sqlx_binlog_test");
+                changes.push((Some(before), after));
+            }
+
+            _ => {}
+        }
+    }
+
+    assert_eq!(changes[0].0, None);
+    assert_eq!(changes[0].1.get(0), Some(&MySqlBinlogValue::Int(1)));
+    assert_eq!(
+        changes[0].1.get(1),
+        Some(&MySqlBinlogValue::Bytes(b"John".to_vec()))
+    );
+
+    let before = changes[1].0.as_ref().unwrap();
+
+    assert_eq!(
+        before.get(1),
+        Some(&MySqlBinlogValue::Bytes(b"John".to_vec()))
+    );
+    assert_eq!(changes[1].1.get(0), Some(&MySqlBinlogValue::Int(1)));
+    assert_eq!(changes[1].1.get(1), None);
+
+    conn.execute("DROP TABLE _sqlx_binlog_test").await?;
+
+    Ok(())
+}
+
+// The URL of the X Protocol of the server of `DATABASE_URL`, and its schema, or `None`
+// if the X Plugin is not loaded
+async fn mysqlx_url() -> anyhow::Result<Option<(String, String)>> {
+    // connecting over the classic protocol first also lets `SHA256_MEMORY` authenticate
+    // a user of `caching_sha2_password`
+    let mut conn = new::<MySql>().await?;
+
+    let port: Option<(String,)> = sqlx::query_as("SELECT CAST(@@mysqlx_port AS CHAR)")
+        .fetch_one(&mut conn)
+        .await
+        .ok();
+
+    let port = match port {
+        Some((port,)) => port,
+        None => return Ok(None),
+    };
+
+    let url = dotenv::var("DATABASE_URL")?;
+    let url = url.splitn(2, '?').next().unwrap_or_default();
+    let url = url.trim_start_matches("mysql://");
+
+    let (authority, schema) = match url.find('/') {
+        Some(index) => (&url[..index], &url[index + 1..]),
+        None => (url, ""),
+    };
+
+    let host_start = authority.rfind('@').map_or(0, |index| index + 1);
+    let host_end = authority[host_start..]
+        .find(':')
+        .map_or(authority.len(), |index| host_start + index);
+
+    Ok(Some((
+        format!("mysqlx://{}:{}/{}", &authority[..host_end], port, schema),
+        schema.to_owned(),
+    )))
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_connects_over_the_x_protocol() -> anyhow::Result<()> {
+    use sqlx::mysql::x::{MySqlXConnection, MySqlXValue};
+
+    let (url, schema) = match mysqlx_url().await? {
+        Some(url) => url,
+        None => return Ok(()),
+    };
+
+    let mut conn = MySqlXConnection::connect(&*url).await?;
+
+    let rows = conn
+        .fetch_all("SELECT 1 + 1 AS two, 'x' AS x, NULL AS n")
+        .await?;
+
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].get_by_name("two"), Some(&MySqlXValue::Int(2)));
+    assert_eq!(rows[0].get(1), Some(&MySqlXValue::Text("x".into())));
+    assert_eq!(rows[0].get_by_name("n"), None);
+
+    // the statements of a pipeline all run, even after one fails
+    conn.execute("CREATE TEMPORARY TABLE _sqlx_x_test (id INTEGER PRIMARY KEY)")
+        .await?;
+
+    let mut pipeline = conn.pipeline();
+    pipeline.push("INSERT INTO _sqlx_x_test (id) VALUES (1), (2)");
+    pipeline.push("INSERT INTO _sqlx_x_test (id) VALUES (1)");
+    pipeline.push("INSERT INTO _sqlx_x_test (id) VALUES (3)");
+
+    assert!(pipeline.execute().await.is_err());
+
+    let mut pipeline = conn.pipeline();
+    pipeline.push("DELETE FROM _sqlx_x_test WHERE id > 1");
+    pipeline.push("SELECT id FROM _sqlx_x_test");
+
+    let results = pipeline.execute().await?;
+
+    assert_eq!(results[0].rows_affected, 2);
+    assert_eq!(results[1].rows.len(), 1);
+
+    // a collection of documents
+    let _ = conn.drop_collection(&schema, "_sqlx_x_test").await;
+
+    let mut collection = conn.create_collection(&schema, "_sqlx_x_test").await?;
+
+    collection.add(r#"{"_id": "1", "name": "tea"}"#).await?;
+
+    let result = collection.add(r#"{"name": "coffee"}"#).await?;
+    assert_eq!(result.rows_affected, 1);
+    assert_eq!(result.generated_ids.len(), 1);
+
+    assert_eq!(collection.find_all().await?.len(), 2);
+    assert!(collection.find_by_id("1").await?.unwrap().contains("tea"));
+    assert_eq!(collection.remove_by_id("1").await?, 1);
+    assert_eq!(collection.find_by_id("1").await?, None);
+
+    conn.drop_collection(&schema, "_sqlx_x_test").await?;
+    conn.close().await?;
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_decodes_unsigned_integers_of_every_width() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute(
+        "CREATE TEMPORARY TABLE _sqlx_unsigned_test (a TINYINT UNSIGNED, b SMALLINT UNSIGNED, \
+         c MEDIUMINT UNSIGNED, d INT UNSIGNED, e BIGINT UNSIGNED)",
+    )
+    .await?;
+
+    sqlx::query("INSERT INTO _sqlx_unsigned_test VALUES (?, ?, ?, ?, ?)")
+        .bind(u8::MAX)
+        .bind(u16::MAX)
+        .bind(16_777_215_u32)
+        .bind(u32::MAX)
+        .bind(u64::MAX)
+        .execute(&mut conn)
+        .await?;
+
+    let row: (u8, u16, u32, u32, u64) = sqlx::query_as("SELECT * FROM _sqlx_unsigned_test")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(row, (u8::MAX, u16::MAX, 16_777_215, u32::MAX, u64::MAX));
+
+    // any of them can be read as a wider type
+    let row: (u64, u64, u64, u64) = sqlx::query_as("SELECT a, b, c, d FROM _sqlx_unsigned_test")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(row, (255, 65535, 16_777_215, 4_294_967_295));
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_sends_query_attributes() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    // reading query attributes requires the `query_attributes` component of MySQL 8.0.23+
+    let res: Result<(Option<String>,), _> =
+        sqlx::query_as("SELECT mysql_query_attribute_string('trace_id')")
+            .fetch_one(&mut conn)
+            .await;
+
+    if res.is_err() {
+        return Ok(());
+    }
+
+    let (value,): (Option<String>,) =
+        sqlx::query_as("SELECT mysql_query_attribute_string('trace_id') FROM (SELECT ? AS a) AS t")
+            .bind(1_i32)
+            .attribute("trace_id", "4bf92f3577b34da6")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(value.as_deref(), Some("4bf92f3577b34da6"));
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_streams_long_data_parameters() -> anyhow::Result<()> {
+    // larger than one chunk of long data
+    static DATA: [u8; 200_000] = [7; 200_000];
+
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE _sqlx_long_data_test (id INT, a LONGBLOB, b LONGBLOB)")
+        .await?;
+
+    sqlx::query("INSERT INTO _sqlx_long_data_test VALUES (?, ?, ?)")
+        .bind(1_i32)
+        .bind_reader(&DATA[..])
+        .bind_reader(&b""[..])
+        .execute(&mut conn)
+        .await?;
+
+    let (id, a, b): (i32, Vec<u8>, Vec<u8>) =
+        sqlx::query_as("SELECT id, a, b FROM _sqlx_long_data_test")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(id, 1);
+    assert_eq!(a, &DATA[..]);
+    assert!(b.is_empty());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_fetches_with_a_server_side_cursor() -> anyhow::Result<()> {
+    use sqlx::Row;
+
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE _sqlx_cursor_test (i INT)")
+        .await?;
+
+    conn.execute(
+        "INSERT INTO _sqlx_cursor_test VALUES (1), (2), (3), (4), (5), (6), (7), (8), (9), (10)",
+    )
+    .await?;
+
+    let mut cursor = sqlx::query("SELECT i, CAST(i AS CHAR) FROM _sqlx_cursor_test WHERE i <= ?")
+        .bind(10_i32)
+        .fetch_cursor(&mut conn, 3);
+
+    let mut sum = 0;
+
+    while let Some(row) = cursor.next().await? {
+        let (i, s): (i32, String) = (row.get(0), row.get(1));
+
+        assert_eq!(i.to_string(), s);
+        sum += i;
+    }
+
+    assert_eq!(sum, 55);
+    drop(cursor);
+
+    let ids: Vec<(i32,)> =
+        sqlx::query_as::<MySql, (i32,)>("SELECT i FROM _sqlx_cursor_test ORDER BY i LIMIT 6")
+            .fetch_cursor(&mut conn, 2)
+            .try_collect()
+            .await?;
+
+    assert_eq!(ids, vec![(1,), (2,), (3,), (4,), (5,), (6,)]);
+
+    // dropping the cursor early closes it before the next query
+    let mut cursor = sqlx::query("SELECT i FROM _sqlx_cursor_test").fetch_cursor(&mut conn, 2);
+
+    assert!(cursor.next().await?.is_some());
+    drop(cursor);
+
+    let mut cursor = sqlx::query("SELECT i FROM _sqlx_cursor_test").fetch_cursor(&mut conn, 2);
+    let mut count = 0;
+
+    while cursor.next().await?.is_some() {
+        count += 1;
+    }
+
+    assert_eq!(count, 10);
+    drop(cursor);
+
+    // a statement without a result set
+    let mut cursor =
+        sqlx::query("DELETE FROM _sqlx_cursor_test WHERE i > 5").fetch_cursor(&mut conn, 2);
+
+    assert!(cursor.next().await?.is_none());
+    drop(cursor);
+
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM _sqlx_cursor_test")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 5);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_inserts_with_returning() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute(
+        "CREATE TEMPORARY TABLE _sqlx_returning_test (id INT PRIMARY KEY AUTO_INCREMENT, name TEXT)",
+    )
+    .await?;
+
+    let query = "INSERT INTO _sqlx_returning_test (name) VALUES (?) RETURNING id, name";
+
+    if !conn.supports_returning() {
+        let error = conn.describe(query).await.unwrap_err();
+        assert!(error.to_string().contains("MariaDB 10.5"));
+
+        return Ok(());
+    }
+
+    let describe = conn.describe(query).await?;
+    assert_eq!(describe.result_columns.len(), 2);
+
+    let (id, name): (i32, String) = sqlx::query_as(query)
+        .bind("sqlx")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(id, 1);
+    assert_eq!(name, "sqlx");
+
+    Ok(())
+}
+
+#[cfg(feature = "chrono")]
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_decodes_zero_dates() -> anyhow::Result<()> {
+    use sqlx::types::chrono::{NaiveDate, NaiveDateTime};
+    use sqlx::{Connect, Cursor, MySqlConnection, Row};
+
+    async fn connect(zero_dates: &str) -> anyhow::Result<MySqlConnection> {
+        let url = dotenv::var("DATABASE_URL")?;
+        let url = format!(
+            "{}{}zero-dates={}",
+            url,
+            if url.contains('?') { '&' } else { '?' },
+            zero_dates
+        );
+
+        let mut conn = MySqlConnection::connect(&*url).await?;
+
+        // allow zero dates, as in a legacy schema
+        conn.execute("SET SESSION sql_mode = ''").await?;
+        conn.execute("CREATE TEMPORARY TABLE _sqlx_zero_dates (d DATE, dt DATETIME)")
+            .await?;
+        conn.execute("INSERT INTO _sqlx_zero_dates VALUES ('0000-00-00', '0000-00-00 00:00:00')")
+            .await?;
+
+        Ok(conn)
+    }
+
+    let mut conn = connect("null").await?;
+
+    // prepared, in the binary protocol
+    let row: (Option<NaiveDate>, Option<NaiveDateTime>) =
+        sqlx::query_as("SELECT d, dt FROM _sqlx_zero_dates")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(row, (None, None));
+
+    let mut conn = connect("sentinel").await?;
+
+    let sentinel = NaiveDate::from_ymd(1, 1, 1);
+
+    let row: (NaiveDate, NaiveDateTime) = sqlx::query_as("SELECT d, dt FROM _sqlx_zero_dates")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(row, (sentinel, sentinel.and_hms(0, 0, 0)));
+
+    // unprepared, in the text protocol
+    let mut cursor = conn.fetch("SELECT d, dt FROM _sqlx_zero_dates");
+    let row = cursor.next().await?.unwrap();
+
+    assert_eq!(row.get::<NaiveDate, _>(0), sentinel);
+    assert_eq!(row.get::<NaiveDateTime, _>(1), sentinel.and_hms(0, 0, 0));
+
+    drop(cursor);
+
+    let mut conn = connect("error").await?;
+
+    let result: Result<(NaiveDate,), _> = sqlx::query_as("SELECT d FROM _sqlx_zero_dates")
+        .fetch_one(&mut conn)
+        .await;
+
+    assert!(result.is_err());
+
+    // strings are not affected
+    let (text,): (String,) = sqlx::query_as("SELECT CAST(d AS CHAR) FROM _sqlx_zero_dates")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(text, "0000-00-00");
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_encodes_and_decodes_bits() -> anyhow::Result<()> {
+    use sqlx::{Cursor, Row};
+
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE _sqlx_bit_test (flags BIT(3), mask BIT(64))")
+        .await?;
+
+    sqlx::query("INSERT INTO _sqlx_bit_test (flags, mask) VALUES (?, ?)")
+        .bind(5_u64)
+        .bind(u64::MAX)
+        .execute(&mut conn)
+        .await?;
+
+    // prepared, in the binary protocol
+    let (flags, mask, bytes): (u64, u64, Vec<u8>) =
+        sqlx::query_as("SELECT flags, mask, flags FROM _sqlx_bit_test")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(flags, 5);
+    assert_eq!(mask, u64::MAX);
+    assert_eq!(bytes, vec![5]);
+
+    // unprepared, in the text protocol
+    let mut cursor = conn.fetch("SELECT flags, mask FROM _sqlx_bit_test");
+    let row = cursor.next().await?.unwrap();
+
+    assert_eq!(row.get::<u64, _>(0), 5);
+    assert_eq!(row.get::<u64, _>(1), u64::MAX);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_encodes_and_decodes_year_and_set() -> anyhow::Result<()> {
+    use sqlx::{Cursor, Row};
+
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE _sqlx_year_set_test (y YEAR, s SET('a', 'b', 'c'))")
+        .await?;
+
+    sqlx::query("INSERT INTO _sqlx_year_set_test (y, s) VALUES (?, ?)")
+        .bind(2020_u16)
+        .bind(vec!["c".to_owned(), "a".to_owned()])
+        .execute(&mut conn)
+        .await?;
+
+    // prepared, in the binary protocol
+    let (year, set, text): (u16, Vec<String>, String) =
+        sqlx::query_as("SELECT y, s, s FROM _sqlx_year_set_test")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(year, 2020);
+    assert_eq!(set, vec!["a", "c"]);
+    assert_eq!(text, "a,c");
+
+    // unprepared, in the text protocol
+    let mut cursor = conn.fetch("SELECT y, s FROM _sqlx_year_set_test");
+    let row = cursor.next().await?.unwrap();
+
+    assert_eq!(row.get::<u16, _>(0), 2020);
+    assert_eq!(row.get::<Vec<String>, _>(1), vec!["a", "c"]);
+
+    Ok(())
+}
+
+#[cfg(feature = "mysql-spatial")]
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_encodes_and_decodes_geometry() -> anyhow::Result<()> {
+    use sqlx::mysql::types::MySqlGeometry;
+    use sqlx::types::geo_types::{Geometry, LineString, Point};
+
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE _sqlx_geometry_test (id INT PRIMARY KEY, g GEOMETRY)")
+        .await?;
+
+    let point = MySqlGeometry::new(Point::new(13.4, 52.5), 3857);
+
+    sqlx::query("INSERT INTO _sqlx_geometry_test (id, g) VALUES (?, ?), (?, ?)")
+        .bind(1)
+        .bind(&point)
+        .bind(2)
+        .bind(Geometry::LineString(LineString::from(vec![
+            (0.0, 0.0),
+            (1.0, 1.0),
+        ])))
+        .execute(&mut conn)
+        .await?;
+
+    let (text, srid, decoded): (String, u32, MySqlGeometry) =
+        sqlx::query_as("SELECT ST_AsText(g), ST_SRID(g), g FROM _sqlx_geometry_test WHERE id = 1")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(text, "POINT(13.4 52.5)");
+    assert_eq!(srid, 3857);
+    assert_eq!(decoded, point);
+
+    let (line,): (Geometry<f64>,) =
+        sqlx::query_as("SELECT g FROM _sqlx_geometry_test WHERE id = 2")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(
+        line,
+        Geometry::LineString(LineString::from(vec![(0.0, 0.0), (1.0, 1.0)]))
+    );
+
+    Ok(())
+}
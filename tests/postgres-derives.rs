@@ -1,4 +1,4 @@
-use sqlx::{postgres::PgQueryAs, Connection, Cursor, Executor, FromRow, Postgres};
+use sqlx::{postgres::PgQueryAs, Connection, Cursor, Executor, FromRow, Postgres, Row};
 use sqlx_test::{new, test_type};
 use std::fmt::Debug;
 
@@ -63,6 +63,15 @@ enum Mood {
     Sad,
 }
 
+// `type_name` names the custom type like `rename`
+#[derive(PartialEq, Debug, sqlx::Type)]
+#[sqlx(type_name = "weather", rename_all = "snake_case")]
+enum Weather {
+    Sunny,
+    PartlyCloudy,
+    Rain,
+}
+
 // Records must map to a custom type
 // Note that all types are types in Postgres
 #[derive(PartialEq, Debug, sqlx::Type)]
@@ -73,6 +82,22 @@ struct InventoryItem {
     price: Option<i64>,
 }
 
+// Records may contain other records
+#[derive(PartialEq, Debug, sqlx::Type)]
+#[sqlx(rename = "shipment")]
+struct Shipment {
+    item: Option<InventoryItem>,
+    items: Vec<InventoryItem>,
+    note: Option<String>,
+}
+
+#[derive(PartialEq, Debug, sqlx::Type)]
+#[sqlx(rename = "delivery")]
+struct Delivery {
+    shipment: Shipment,
+    returned: Option<Shipment>,
+}
+
 test_type!(transparent(
     Postgres,
     Transparent,
@@ -231,6 +256,51 @@ SELECT $1 = 'RED'::color_upper, $1
     Ok(())
 }
 
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn test_enum_array_type() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute(
+        r#"
+DROP TYPE IF EXISTS weather CASCADE;
+
+CREATE TYPE weather AS ENUM ( 'sunny', 'partly_cloudy', 'rain' );
+    "#,
+    )
+    .await?;
+
+    let value = vec![Weather::Rain, Weather::PartlyCloudy, Weather::Sunny];
+
+    let rec: (bool, Vec<Weather>) = sqlx::query_as(
+        "
+SELECT $1 = '{rain,partly_cloudy,sunny}'::weather[], $1
+        ",
+    )
+    .bind(&value)
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert!(rec.0);
+    assert_eq!(rec.1, value);
+
+    let rec: (Vec<Weather>,) = sqlx::query_as("SELECT '{}'::weather[]")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert!(rec.0.is_empty());
+
+    // decode from the text format
+    let mut cursor = conn.fetch("SELECT '{rain,partly_cloudy,sunny}'::weather[]");
+
+    let row = cursor.next().await?.unwrap();
+    let rec: Vec<Weather> = row.get(0);
+
+    assert_eq!(rec, value);
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "runtime-async-std", async_std::test)]
 #[cfg_attr(feature = "runtime-tokio", tokio::test)]
 async fn test_record_type() -> anyhow::Result<()> {
@@ -274,6 +344,251 @@ END $$;
     Ok(())
 }
 
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn test_record_array_type() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute(
+        r#"
+DO $$ BEGIN
+
+CREATE TYPE inventory_item AS (
+    name            text,
+    supplier_id     int,
+    price           bigint
+);
+
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+    "#,
+    )
+    .await?;
+
+    let value = vec![
+        InventoryItem {
+            name: "fuzzy dice".to_owned(),
+            supplier_id: Some(42),
+            price: Some(199),
+        },
+        InventoryItem {
+            name: "a \"quoted\", (odd) name".to_owned(),
+            supplier_id: None,
+            price: None,
+        },
+    ];
+
+    let rec: (bool, Vec<InventoryItem>) = sqlx::query_as(
+        r#"
+        SELECT $1 = ARRAY[
+            ROW('fuzzy dice', 42, 199),
+            ROW('a "quoted", (odd) name', NULL, NULL)
+        ]::inventory_item[], $1
+        "#,
+    )
+    .bind(&value)
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert!(rec.0);
+    assert_eq!(rec.1, value);
+
+    // decode from the text format
+    let mut cursor = conn.fetch(
+        r#"
+        SELECT ARRAY[
+            ROW('fuzzy dice', 42, 199),
+            ROW('a "quoted", (odd) name', NULL, NULL)
+        ]::inventory_item[]
+        "#,
+    );
+
+    let row = cursor.next().await?.unwrap();
+    let rec: Vec<InventoryItem> = row.get(0);
+
+    assert_eq!(rec, value);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn test_nested_record_type() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute(
+        r#"
+DO $$ BEGIN
+
+CREATE TYPE inventory_item AS (
+    name            text,
+    supplier_id     int,
+    price           bigint
+);
+
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+
+DROP TYPE IF EXISTS delivery;
+DROP TYPE IF EXISTS shipment;
+
+CREATE TYPE shipment AS (
+    item            inventory_item,
+    items           inventory_item[],
+    note            text
+);
+
+CREATE TYPE delivery AS (
+    shipment        shipment,
+    returned        shipment
+);
+    "#,
+    )
+    .await?;
+
+    let value = Delivery {
+        shipment: Shipment {
+            item: Some(InventoryItem {
+                name: r#"a "quoted", (odd) \ name"#.to_owned(),
+                supplier_id: None,
+                price: Some(199),
+            }),
+            items: vec![
+                InventoryItem {
+                    name: "".to_owned(),
+                    supplier_id: Some(42),
+                    price: None,
+                },
+                InventoryItem {
+                    name: "\"".to_owned(),
+                    supplier_id: None,
+                    price: None,
+                },
+            ],
+            note: Some("x,y".to_owned()),
+        },
+        returned: Some(Shipment {
+            item: None,
+            items: Vec::new(),
+            note: None,
+        }),
+    };
+
+    let expr = r#"
+        ROW(
+            ROW(
+                ROW('a "quoted", (odd) \ name', NULL, 199),
+                ARRAY[ROW('', 42, NULL), ROW('"', NULL, NULL)]::inventory_item[],
+                'x,y'
+            )::shipment,
+            ROW(NULL, '{}', NULL)::shipment
+        )::delivery
+    "#;
+
+    let rec: (bool, Delivery) = sqlx::query_as(&format!("SELECT $1 = {}, $1", expr))
+        .bind(&value)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert!(rec.0);
+    assert_eq!(rec.1, value);
+
+    // decode from the text format
+    let stmt = format!("SELECT {}", expr);
+    let mut cursor = conn.fetch(&*stmt);
+
+    let row = cursor.next().await?.unwrap();
+    let rec: Delivery = row.get(0);
+
+    assert_eq!(rec, value);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn test_type_cache() -> anyhow::Result<()> {
+    use sqlx::postgres::{PgPool, PgTypeCache};
+
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute(
+        r#"
+DO $$ BEGIN
+
+CREATE TYPE inventory_item AS (
+    name            text,
+    supplier_id     int,
+    price           bigint
+);
+
+EXCEPTION
+    WHEN duplicate_object THEN null;
+END $$;
+    "#,
+    )
+    .await?;
+
+    let value = InventoryItem {
+        name: "fuzzy dice".to_owned(),
+        supplier_id: Some(42),
+        price: Some(199),
+    };
+
+    let select = "SELECT $1 = ROW('fuzzy dice', 42, 199)::inventory_item";
+
+    // types resolved on a shared cache are added to it
+    let cache = PgTypeCache::new();
+    conn.share_type_cache(cache.clone());
+
+    let (eq,): (bool,) = sqlx::query_as(select)
+        .bind(&value)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert!(eq);
+
+    let oid = conn
+        .type_cache()
+        .types()
+        .into_iter()
+        .find(|(name, _)| name == "inventory_item")
+        .map(|(_, oid)| oid);
+
+    assert!(oid.is_some());
+    assert!(cache
+        .types()
+        .contains(&("inventory_item".to_owned(), oid.unwrap())));
+
+    // the connections of a pool sharing the cache start with its types
+    let shared = PgTypeCache::new();
+    shared.insert("inventory_item", oid.unwrap());
+
+    let pool = PgPool::builder()
+        .max_size(1)
+        .share_type_cache(shared)
+        .build(&dotenv::var("DATABASE_URL")?)
+        .await?;
+
+    let mut conn = pool.acquire().await?;
+
+    assert_eq!(
+        conn.type_cache().types(),
+        vec![("inventory_item".to_owned(), oid.unwrap())]
+    );
+
+    let (eq,): (bool,) = sqlx::query_as(select)
+        .bind(&value)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert!(eq);
+
+    Ok(())
+}
+
 #[cfg(feature = "macros")]
 #[cfg_attr(feature = "runtime-async-std", async_std::test)]
 #[cfg_attr(feature = "runtime-tokio", tokio::test)]
@@ -247,6 +247,52 @@ async fn test_array_from_slice() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn test_range() -> anyhow::Result<()> {
+    use sqlx::postgres::types::PgRange;
+
+    let mut conn = new::<Postgres>().await?;
+
+    let range: PgRange<i32> = (1..10).into();
+
+    let result = sqlx::query!("SELECT $1::int4range as my_range", range)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(result.my_range, Some(range));
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn test_lsn_and_xid8() -> anyhow::Result<()> {
+    use sqlx::postgres::types::{PgLsn, PgXid8};
+
+    let mut conn = new::<Postgres>().await?;
+
+    // requires Postgres 13 for `xid8`
+    let result = sqlx::query!(
+        "SELECT $1::pg_lsn as lsn, $2::xid8 as xid",
+        PgLsn(0x16_B374_D848),
+        PgXid8(42)
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(result.lsn, Some(PgLsn(0x16_B374_D848)));
+    assert_eq!(result.xid, Some(PgXid8(42)));
+
+    let result = sqlx::query!("SELECT pg_current_wal_lsn() as lsn")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert!(result.lsn > Some(PgLsn(0)));
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "runtime-async-std", async_std::test)]
 #[cfg_attr(feature = "runtime-tokio", tokio::test)]
 async fn fetch_is_usable_issue_224() -> anyhow::Result<()> {
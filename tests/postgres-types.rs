@@ -3,6 +3,7 @@ extern crate time_ as time;
 use sqlx::decode::Decode;
 use sqlx::encode::Encode;
 use sqlx::postgres::types::raw::{PgNumeric, PgNumericSign, PgRecordDecoder, PgRecordEncoder};
+use sqlx::postgres::types::{PgInterval, PgJsonPath, PgLsn, PgMoney, PgRange, PgXid8};
 use sqlx::postgres::{PgQueryAs, PgRawBuffer, PgTypeInfo, PgValue};
 use sqlx::{Cursor, Executor, Postgres, Row, Type};
 use sqlx_test::{new, test_prepared_type, test_type};
@@ -300,6 +301,22 @@ mod chrono {
                 Utc,
             )
     ));
+    test_type!(chrono_date_range(
+        Postgres,
+        PgRange<NaiveDate>,
+        "'[2020-01-01,2020-02-01)'::daterange"
+            == PgRange::from(NaiveDate::from_ymd(2020, 1, 1)..NaiveDate::from_ymd(2020, 2, 1))
+    ));
+
+    test_type!(chrono_date_time_tz_range(
+        Postgres,
+        PgRange<DateTime<Utc>>,
+        "'[\"2019-01-02 05:10:20+00\",)'::tstzrange"
+            == PgRange::from(
+                DateTime::<Utc>::from_utc(NaiveDate::from_ymd(2019, 1, 2).and_hms(5, 10, 20), Utc)..
+            )
+    ));
+
     // TODO: Can't seem to get this to work
     // array_macro_test!(chrono_date_time_tz(
     //     DateTime::<Utc>,
@@ -378,6 +395,87 @@ test_type!(i16_vec(Postgres, Vec<i16>,
     "ARRAY[0]::smallint[]" == vec![0_i16]
 ));
 
+test_type!(money(
+    Postgres,
+    PgMoney,
+    "'$1,234.56'::money" == PgMoney(123456),
+    "'-$0.05'::money" == PgMoney(-5),
+));
+
+test_type!(money_vec(Postgres, Vec<PgMoney>,
+    "ARRAY['$1.00', '-$2.50']::money[]" == vec![PgMoney(100), PgMoney(-250)],
+));
+
+test_type!(interval(
+    Postgres,
+    PgInterval,
+    "INTERVAL '1 year 2 months 3 days 04:05:06.789'"
+        == PgInterval {
+            months: 14,
+            days: 3,
+            microseconds: 14_706_789_000
+        },
+    "INTERVAL '-1 day +00:00:00.5'"
+        == PgInterval {
+            months: 0,
+            days: -1,
+            microseconds: 500_000
+        },
+    "INTERVAL '0'" == PgInterval::default(),
+));
+
+test_type!(interval_vec(Postgres, Vec<PgInterval>,
+    "ARRAY[INTERVAL '1 hour', INTERVAL '-2 months']"
+        == vec![
+            PgInterval { months: 0, days: 0, microseconds: 3_600_000_000 },
+            PgInterval { months: -2, days: 0, microseconds: 0 },
+        ],
+));
+
+test_type!(pg_lsn(
+    Postgres,
+    PgLsn,
+    "'16/B374D848'::pg_lsn" == PgLsn(0x16_B374_D848),
+    "'0/0'::pg_lsn" == PgLsn(0),
+));
+
+test_type!(pg_lsn_vec(Postgres, Vec<PgLsn>,
+    "ARRAY['0/1', 'FFFFFFFF/FFFFFFFF']::pg_lsn[]" == vec![PgLsn(1), PgLsn(u64::MAX)],
+));
+
+// jsonpath has no equality operator; paths are compared by their normalized text
+test_type!(json_path(
+    Postgres,
+    PgJsonPath,
+    "SELECT {0}::text = $1::text, $2::text as _1, {0} as _2, $3 as _3",
+    "'$.a[*] ? (@ > 1)'::jsonpath" == PgJsonPath::new("$.\"a\"[*]?(@ > 1)"),
+    "'strict $.a.b'::jsonpath" == PgJsonPath::new("strict $.\"a\".\"b\""),
+));
+
+test_type!(xid8(
+    Postgres,
+    PgXid8,
+    "'42'::xid8" == PgXid8(42),
+    "'18446744073709551615'::xid8" == PgXid8(u64::MAX),
+));
+
+test_type!(xid8_vec(Postgres, Vec<PgXid8>,
+    "ARRAY['1', '2']::xid8[]" == vec![PgXid8(1), PgXid8(2)],
+));
+
+test_type!(int4range(Postgres, PgRange<i32>,
+    "'[1,10)'::int4range" == PgRange::from(1_i32..10),
+    "'(,5]'::int4range" == PgRange::from(..6_i32),
+    "'[3,)'::int4range" == PgRange::from(3_i32..),
+    "'(,)'::int4range" == PgRange::<i32>::from(..),
+    "'empty'::int4range" == PgRange::<i32>::Empty,
+));
+
+test_type!(int8range(Postgres, PgRange<i64>,
+    "'[-10000000000,10000000000]'::int8range"
+        == PgRange::from(-10000000000_i64..10000000001),
+));
+
 test_type!(string_vec(Postgres, Vec<String>,
     "ARRAY['', '\"']::text[]"
         == vec!["".to_string(), "\"".to_string()],
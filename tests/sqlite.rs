@@ -37,6 +37,18 @@ async fn it_fails_to_parse() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[cfg(not(feature = "sqlcipher"))]
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_requires_sqlcipher_for_keys() -> anyhow::Result<()> {
+    // without SQLCipher, the database would silently be unencrypted
+    let res = SqliteConnection::connect("sqlite:%3Amemory%3A?key=secret").await;
+
+    assert!(res.is_err());
+
+    Ok(())
+}
+
 #[cfg_attr(feature = "runtime-async-std", async_std::test)]
 #[cfg_attr(feature = "runtime-tokio", tokio::test)]
 async fn it_handles_empty_queries() -> anyhow::Result<()> {
@@ -210,3 +222,1346 @@ CREATE TEMPORARY TABLE describe_test (
 
     Ok(())
 }
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_can_run_queries_without_caching_statements() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    for index in 1..=3_i32 {
+        let cnt = sqlx::query(&format!("SELECT {} + ?", index))
+            .bind(1_i32)
+            .persistent(false)
+            .execute(&mut conn)
+            .await?;
+
+        assert_eq!(cnt, 0);
+
+        let (value,): (i32,) = sqlx::query_as(&format!("SELECT {} + ?", index))
+            .bind(1_i32)
+            .persistent(false)
+            .fetch_one(&mut conn)
+            .await?;
+
+        assert_eq!(value, index + 1);
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_calls_user_defined_functions() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    conn.create_function("add_one", 1, |args| Ok(args.get::<i64>(0)? + 1))?;
+    conn.create_function("greet", -1, |args| {
+        let mut names = Vec::with_capacity(args.len());
+
+        for index in 0..args.len() {
+            names.push(args.get::<String>(index)?);
+        }
+
+        Ok(format!("hello {}", names.join(" and ")))
+    })?;
+
+    let (value, greeting): (i64, String) = sqlx::query_as("SELECT add_one(?), greet('a', 'b')")
+        .bind(41_i64)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(value, 42);
+    assert_eq!(greeting, "hello a and b");
+
+    // errors and panics fail the query
+    conn.create_function("fail", 0, |_| -> sqlx::Result<i32> {
+        Err(sqlx::Error::Protocol("failed on purpose".into()))
+    })?;
+    conn.create_function("explode", 0, |_| -> sqlx::Result<i32> { panic!("boom") })?;
+
+    let err = sqlx::query("SELECT fail()")
+        .execute(&mut conn)
+        .await
+        .unwrap_err();
+
+    assert!(err.to_string().contains("failed on purpose"));
+    assert!(sqlx::query("SELECT explode()")
+        .execute(&mut conn)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_indexes_deterministic_functions() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    conn.create_function("random_len", 1, |args| {
+        Ok(args.get::<String>(0)?.len() as i64)
+    })?;
+    conn.create_deterministic_function("char_len", 1, |args| {
+        Ok(args.get::<String>(0)?.chars().count() as i64)
+    })?;
+
+    conn.execute(
+        r#"
+CREATE TEMPORARY TABLE _sqlx_words (
+    word TEXT NOT NULL,
+    len INTEGER GENERATED ALWAYS AS (char_len(word))
+);
+CREATE INDEX _sqlx_words_len ON _sqlx_words (char_len(word));
+INSERT INTO _sqlx_words (word) VALUES ('über'), ('a');
+        "#,
+    )
+    .await?;
+
+    let (len,): (i64,) = sqlx::query_as("SELECT len FROM _sqlx_words WHERE char_len(word) = 4")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(len, 4);
+
+    // a function that is not deterministic can not be indexed
+    assert!(conn
+        .execute("CREATE INDEX _sqlx_words_random_len ON _sqlx_words (random_len(word))")
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_calls_user_defined_aggregates() -> anyhow::Result<()> {
+    use sqlx::sqlite::{Aggregate, SqliteFunctionArguments};
+
+    #[derive(Default)]
+    struct Median(Vec<f64>);
+
+    impl Aggregate for Median {
+        type Output = Option<f64>;
+
+        fn step(&mut self, args: &SqliteFunctionArguments<'_>) -> sqlx::Result<()> {
+            self.0.push(args.get(0)?);
+
+            Ok(())
+        }
+
+        fn finalize(mut self) -> sqlx::Result<Option<f64>> {
+            self.0.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+            Ok(self.0.get(self.0.len() / 2).copied())
+        }
+    }
+
+    // a sum over the rows of a window frame
+    #[derive(Default)]
+    struct Total(i64);
+
+    impl Aggregate for Total {
+        type Output = i64;
+
+        fn step(&mut self, args: &SqliteFunctionArguments<'_>) -> sqlx::Result<()> {
+            self.0 += args.get::<i64>(0)?;
+
+            Ok(())
+        }
+
+        fn finalize(self) -> sqlx::Result<i64> {
+            Ok(self.0)
+        }
+
+        fn inverse(&mut self, args: &SqliteFunctionArguments<'_>) -> sqlx::Result<()> {
+            self.0 -= args.get::<i64>(0)?;
+
+            Ok(())
+        }
+
+        fn value(&self) -> sqlx::Result<i64> {
+            Ok(self.0)
+        }
+    }
+
+    let mut conn = new::<Sqlite>().await?;
+
+    conn.create_aggregate("median", 1, Median::default)?;
+    conn.create_window_function("total", 1, Total::default)?;
+
+    conn.execute(
+        r#"
+CREATE TEMPORARY TABLE _sqlx_numbers (n INTEGER NOT NULL);
+INSERT INTO _sqlx_numbers (n) VALUES (5), (1), (4), (2), (3);
+        "#,
+    )
+    .await?;
+
+    let (median, total): (f64, i64) =
+        sqlx::query_as("SELECT median(n), total(n) FROM _sqlx_numbers")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(median, 3.0);
+    assert_eq!(total, 15);
+
+    let (median,): (Option<f64>,) =
+        sqlx::query_as("SELECT median(n) FROM _sqlx_numbers WHERE n > 5")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(median, None);
+
+    // the sum of each row and the row before it
+    let sums: Vec<(i64,)> = sqlx::query_as(
+        "SELECT total(n) OVER (ORDER BY n ROWS BETWEEN 1 PRECEDING AND CURRENT ROW) FROM _sqlx_numbers",
+    )
+    .fetch_all(&mut conn)
+    .await?;
+
+    assert_eq!(sums, vec![(1,), (3,), (5,), (7,), (9,)]);
+
+    // only a window function can be used with OVER
+    assert!(
+        sqlx::query("SELECT median(n) OVER (ORDER BY n) FROM _sqlx_numbers")
+            .execute(&mut conn)
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_sorts_with_custom_collations() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    // compares the numbers in text by their value ("a2" < "a10")
+    conn.create_collation("natural_sort", |a, b| {
+        let key = |s: &str| {
+            let digits = s.trim_start_matches(|c: char| !c.is_ascii_digit());
+            let prefix = &s[..s.len() - digits.len()];
+
+            (prefix.to_owned(), digits.parse::<u64>().unwrap_or(0))
+        };
+
+        key(a).cmp(&key(b))
+    })?;
+
+    conn.execute(
+        r#"
+CREATE TEMPORARY TABLE _sqlx_files (name TEXT NOT NULL UNIQUE COLLATE natural_sort);
+INSERT INTO _sqlx_files (name) VALUES ('a10'), ('a2'), ('a1');
+        "#,
+    )
+    .await?;
+
+    let names: Vec<(String,)> = sqlx::query_as("SELECT name FROM _sqlx_files ORDER BY name")
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(
+        names,
+        vec![("a1".to_owned(),), ("a2".to_owned(),), ("a10".to_owned(),)]
+    );
+
+    // "a02" is equal to "a2" in the collation of the UNIQUE column
+    assert!(conn
+        .execute("INSERT INTO _sqlx_files (name) VALUES ('a02')")
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_serializes_and_deserializes_databases() -> anyhow::Result<()> {
+    // a new in-memory database, `:memory:`
+    let mut conn = SqliteConnection::connect("sqlite:%3Amemory%3A").await?;
+
+    conn.execute("CREATE TABLE _sqlx_snapshot (id INTEGER PRIMARY KEY, name TEXT NOT NULL)")
+        .await?;
+    conn.execute("INSERT INTO _sqlx_snapshot (name) VALUES ('a'), ('b')")
+        .await?;
+
+    let data = conn.serialize().await?;
+
+    assert!(data.starts_with(b"SQLite format 3\0"));
+
+    // the snapshot is restored over the later changes
+    conn.execute("DELETE FROM _sqlx_snapshot").await?;
+    conn.deserialize(&data).await?;
+
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM _sqlx_snapshot")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 2);
+
+    // and into another database
+    let mut other = SqliteConnection::connect("sqlite:%3Amemory%3A").await?;
+    other.deserialize(&data).await?;
+
+    let names: Vec<(String,)> = sqlx::query_as("SELECT name FROM _sqlx_snapshot ORDER BY id")
+        .fetch_all(&mut other)
+        .await?;
+
+    assert_eq!(names, vec![("a".to_owned(),), ("b".to_owned(),)]);
+
+    assert!(other.deserialize(b"not a database").await.is_err());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_checkpoints_the_write_ahead_log() -> anyhow::Result<()> {
+    use sqlx::sqlite::SqliteCheckpointMode;
+
+    // a database file, as an in-memory database has no write-ahead log
+    let path = std::env::temp_dir().join(format!("sqlx-checkpoint-{}.db", std::process::id()));
+    let url = format!("sqlite://{}?wal_autocheckpoint=0", path.display());
+
+    let mut conn = SqliteConnection::connect(&*url).await?;
+
+    let (pages,): (i64,) = sqlx::query_as("PRAGMA wal_autocheckpoint")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(pages, 0);
+
+    conn.execute("CREATE TABLE _sqlx_wal (id INTEGER PRIMARY KEY)")
+        .await?;
+    conn.execute("INSERT INTO _sqlx_wal DEFAULT VALUES").await?;
+
+    // without automatic checkpoints, the frames stay in the log
+    let (log, checkpointed) = conn.checkpoint(SqliteCheckpointMode::Passive).await?;
+
+    assert!(log > 0);
+    assert_eq!(log, checkpointed);
+
+    let (log, _) = conn.checkpoint(SqliteCheckpointMode::Truncate).await?;
+
+    assert_eq!(log, 0);
+
+    drop(conn);
+
+    for suffix in &["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+    }
+
+    assert!(
+        SqliteConnection::connect("sqlite:%3Amemory%3A?wal_autocheckpoint=x")
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_streams_blobs() -> anyhow::Result<()> {
+    #[cfg(feature = "runtime-async-std")]
+    use async_std::io::prelude::{ReadExt, SeekExt, WriteExt};
+    #[cfg(feature = "runtime-tokio")]
+    use tokio::io::{AsyncReadExt as ReadExt, AsyncSeekExt as SeekExt, AsyncWriteExt as WriteExt};
+
+    use std::io::SeekFrom;
+
+    // `blob_open` opens BLOBs of the `main` database
+    let mut conn = SqliteConnection::connect("sqlite:%3Amemory%3A").await?;
+
+    conn.execute("CREATE TABLE _sqlx_blobs (id INTEGER PRIMARY KEY, data BLOB)")
+        .await?;
+
+    assert!(conn.blob_open("_sqlx_blobs", "data", 1).await.is_err());
+
+    sqlx::query("INSERT INTO _sqlx_blobs (data) VALUES (zeroblob(?))")
+        .bind(600_000_i32)
+        .execute(&mut conn)
+        .await?;
+
+    let (id,): (i64,) = sqlx::query_as("SELECT last_insert_rowid()")
+        .fetch_one(&mut conn)
+        .await?;
+
+    let data: Vec<u8> = (0..600_000_u32).map(|i| i as u8).collect();
+
+    {
+        let mut blob = conn.blob_open("_sqlx_blobs", "data", id).await?;
+
+        assert_eq!(blob.len(), 600_000);
+
+        blob.write_all(&data).await?;
+
+        // a BLOB can not grow
+        assert!(blob.write_all(b"more").await.is_err());
+
+        blob.seek(SeekFrom::Start(100)).await?;
+        blob.write_all(b"sqlx").await?;
+
+        blob.seek(SeekFrom::Start(98)).await?;
+
+        let mut buf = [0; 8];
+        blob.read_exact(&mut buf).await?;
+
+        assert_eq!(&buf, &[98, 99, b's', b'q', b'l', b'x', 104, 105]);
+
+        blob.seek(SeekFrom::End(-10)).await?;
+
+        let mut rest = Vec::new();
+        blob.read_to_end(&mut rest).await?;
+
+        assert_eq!(rest, &data[600_000 - 10..]);
+    }
+
+    let (stored,): (Vec<u8>,) = sqlx::query_as("SELECT data FROM _sqlx_blobs WHERE id = ?")
+        .bind(id)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(&stored[..100], &data[..100]);
+    assert_eq!(&stored[100..104], b"sqlx");
+    assert_eq!(&stored[104..], &data[104..]);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_attaches_databases() -> anyhow::Result<()> {
+    use sqlx::SqlitePool;
+
+    let path = std::env::temp_dir().join(format!("sqlx-attach-{}.db", std::process::id()));
+
+    {
+        let url = format!("sqlite:%3Amemory%3A?attach=aux:{}", path.display());
+        let mut conn = SqliteConnection::connect(&*url).await?;
+
+        conn.execute("CREATE TABLE aux._sqlx_attach (id INTEGER PRIMARY KEY)")
+            .await?;
+        conn.execute("INSERT INTO aux._sqlx_attach (id) VALUES (7)")
+            .await?;
+    }
+
+    // every connection of the pool has the database attached
+    let url = format!(
+        "sqlite:%3Amemory%3A?attach=aux:{}&attach=scratch:%3Amemory%3A",
+        path.display()
+    );
+    let pool = SqlitePool::builder().max_size(2).build(&url).await?;
+
+    let mut a = pool.acquire().await?;
+    let mut b = pool.acquire().await?;
+
+    for conn in &mut [&mut a, &mut b] {
+        let (id,): (i64,) = sqlx::query_as("SELECT id FROM aux._sqlx_attach")
+            .fetch_one(&mut **conn)
+            .await?;
+
+        assert_eq!(id, 7);
+
+        conn.execute("CREATE TABLE scratch._sqlx_scratch (id INTEGER)")
+            .await?;
+    }
+
+    drop((a, b));
+    pool.close().await;
+
+    for suffix in &["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+    }
+
+    assert!(SqliteConnection::connect("sqlite:%3Amemory%3A?attach=aux")
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_shares_a_named_in_memory_database() -> anyhow::Result<()> {
+    use sqlx::SqlitePool;
+
+    let url = "sqlite:file:sqlx_memdb?mode=memory&cache=shared";
+    let pool = SqlitePool::builder().max_size(2).build(url).await?;
+
+    let mut a = pool.acquire().await?;
+    let mut b = pool.acquire().await?;
+
+    a.execute("CREATE TABLE _sqlx_memdb (id INTEGER)").await?;
+    a.execute("INSERT INTO _sqlx_memdb (id) VALUES (5)").await?;
+
+    // the other connection of the pool sees the same database
+    let (id,): (i64,) = sqlx::query_as("SELECT id FROM _sqlx_memdb")
+        .fetch_one(&mut b)
+        .await?;
+
+    assert_eq!(id, 5);
+
+    drop((a, b));
+    pool.close().await;
+
+    // no file was created for the database
+    assert!(!std::path::Path::new("file:sqlx_memdb").exists());
+    assert!(!std::path::Path::new("sqlx_memdb").exists());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_explains_query_plans() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE _sqlx_eqp (id INTEGER PRIMARY KEY, name TEXT)")
+        .await?;
+    conn.execute("CREATE INDEX temp._sqlx_eqp_name ON _sqlx_eqp (name)")
+        .await?;
+
+    let plan = conn
+        .explain_query_plan(sqlx::query("SELECT id FROM _sqlx_eqp WHERE name = ?").bind("a"))
+        .await?;
+
+    assert!(
+        plan.iter()
+            .any(|node| node.index() == Some("_sqlx_eqp_name")),
+        "{:?}",
+        plan
+    );
+
+    let plan = conn
+        .explain_query_plan("SELECT * FROM _sqlx_eqp WHERE name LIKE '%a%'")
+        .await?;
+
+    assert!(plan.iter().any(|node| node.is_scan()), "{:?}", plan);
+
+    let plan = conn
+        .explain_query_plan(
+            "SELECT * FROM _sqlx_eqp WHERE id IN (SELECT id FROM _sqlx_eqp WHERE name = 'a')",
+        )
+        .await?;
+
+    assert!(
+        plan.nodes.iter().any(|node| !node.children.is_empty()),
+        "{:?}",
+        plan
+    );
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_authorizes_statements() -> anyhow::Result<()> {
+    use sqlx::sqlite::{SqliteAuthorization, SqliteAuthorizerAction};
+
+    let mut conn = SqliteConnection::connect("sqlite:%3Amemory%3A").await?;
+
+    conn.execute("CREATE TABLE _sqlx_auth (id INTEGER PRIMARY KEY, secret TEXT)")
+        .await?;
+    conn.execute("INSERT INTO _sqlx_auth (id, secret) VALUES (1, 'hunter2')")
+        .await?;
+
+    // prepared, and cached, before the authorizer is set
+    let (secret,): (Option<String>,) = sqlx::query_as("SELECT secret FROM _sqlx_auth")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(secret.as_deref(), Some("hunter2"));
+
+    conn.set_authorizer(|request| {
+        match (
+            request.action(),
+            request.first_argument(),
+            request.second_argument(),
+        ) {
+            (SqliteAuthorizerAction::Read, Some("_sqlx_auth"), Some("secret")) => {
+                SqliteAuthorization::Ignore
+            }
+            (SqliteAuthorizerAction::Delete, Some("_sqlx_auth"), _) => SqliteAuthorization::Deny,
+            (SqliteAuthorizerAction::Pragma, _, _) => SqliteAuthorization::Deny,
+            _ => SqliteAuthorization::Allow,
+        }
+    });
+
+    let (id, secret): (i64, Option<String>) = sqlx::query_as("SELECT id, secret FROM _sqlx_auth")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(id, 1);
+    assert_eq!(secret, None);
+
+    let (secret,): (Option<String>,) = sqlx::query_as("SELECT secret FROM _sqlx_auth")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(secret, None);
+
+    assert!(conn.execute("DELETE FROM _sqlx_auth").await.is_err());
+    assert!(conn.execute("PRAGMA user_version = 1").await.is_err());
+
+    conn.clear_authorizer();
+
+    assert_eq!(conn.execute("DELETE FROM _sqlx_auth").await?, 1);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_interrupts_statements() -> anyhow::Result<()> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    // counts without end
+    const RUNAWAY: &str =
+        "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c) SELECT count(*) FROM c";
+
+    let mut conn = new::<Sqlite>().await?;
+    let handle = conn.interrupt_handle();
+
+    let interrupter = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        handle.interrupt();
+    });
+
+    assert!(conn.execute(RUNAWAY).await.is_err());
+
+    interrupter.join().unwrap();
+
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    conn.set_progress_handler(100, {
+        let calls = calls.clone();
+        move || calls.fetch_add(1, Ordering::SeqCst) < 10
+    });
+
+    assert!(conn.execute(RUNAWAY).await.is_err());
+    assert_eq!(calls.load(Ordering::SeqCst), 11);
+
+    conn.clear_progress_handler();
+
+    // the connection is still usable
+    let (value,): (i32,) = sqlx::query_as("SELECT 1").fetch_one(&mut conn).await?;
+
+    assert_eq!(value, 1);
+
+    // does nothing once the connection is closed
+    let handle = conn.interrupt_handle();
+
+    conn.close().await?;
+    handle.interrupt();
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_sets_pragmas_from_the_url() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect(
+        "sqlite:%3Amemory%3A?pragma.cache_size=-4000&pragma.temp_store=memory&pragma.cache_size=-8000&pragma.application_id=42",
+    )
+    .await?;
+
+    // the parameters are applied in order
+    let (cache_size,): (i64,) = sqlx::query_as("PRAGMA cache_size")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(cache_size, -8000);
+
+    // `memory` is 2
+    let (temp_store,): (i64,) = sqlx::query_as("PRAGMA temp_store")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(temp_store, 2);
+
+    let (application_id,): (i64,) = sqlx::query_as("PRAGMA application_id")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(application_id, 42);
+
+    assert!(
+        SqliteConnection::connect("sqlite:%3Amemory%3A?pragma.cache_size;%20DROP=1")
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_searches_with_fts5() -> anyhow::Result<()> {
+    use sqlx::sqlite::types::{Highlight, MatchQuery};
+
+    let mut conn = SqliteConnection::connect("sqlite:%3Amemory%3A").await?;
+
+    conn.execute("CREATE VIRTUAL TABLE _sqlx_docs USING fts5 (body)")
+        .await?;
+    conn.execute(
+        "INSERT INTO _sqlx_docs (body) VALUES ('the quick brown fox'), ('a lazy dog OR cat'), ('C++ \"quoted\" text')",
+    )
+    .await?;
+
+    let search = |query: MatchQuery| {
+        sqlx::query_as::<_, (String, f64, Highlight)>(
+            "SELECT body, bm25(_sqlx_docs), highlight(_sqlx_docs, 0, char(2), char(3)) FROM _sqlx_docs WHERE _sqlx_docs MATCH ? ORDER BY rank",
+        )
+        .bind(query)
+    };
+
+    let rows = search(MatchQuery::words("fox quick"))
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(rows.len(), 1);
+
+    let (body, rank, highlight) = &rows[0];
+
+    assert_eq!(body, "the quick brown fox");
+    assert!(*rank < 0.0);
+    assert_eq!(
+        highlight.matches().collect::<Vec<_>>(),
+        vec!["quick", "fox"]
+    );
+    assert_eq!(highlight.text(), *body);
+
+    // the operators and quotes of the input are searched for as text
+    for input in &["dog OR", "\"quoted", "C++", "NEAR(", "body:lazy"] {
+        let rows = search(MatchQuery::words(input))
+            .fetch_all(&mut conn)
+            .await?;
+
+        assert!(rows.len() <= 1, "{}", input);
+    }
+
+    assert_eq!(
+        search(MatchQuery::prefix("laz"))
+            .fetch_all(&mut conn)
+            .await?
+            .len(),
+        1
+    );
+    assert_eq!(
+        search(MatchQuery::phrase("fox brown"))
+            .fetch_all(&mut conn)
+            .await?
+            .len(),
+        0
+    );
+    assert_eq!(
+        search(MatchQuery::raw("quick OR lazy"))
+            .fetch_all(&mut conn)
+            .await?
+            .len(),
+        2
+    );
+
+    // the input is not FTS5 syntax
+    assert!(conn
+        .execute(sqlx::query("SELECT * FROM _sqlx_docs WHERE _sqlx_docs MATCH ?").bind("\"quoted"))
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_queries_virtual_tables() -> anyhow::Result<()> {
+    use sqlx::sqlite::{SqliteVTabColumn, VTab, VTabCursor};
+    use std::sync::Arc;
+
+    // the numbers from 1 up to `limit`, with their names
+    struct Numbers {
+        names: Arc<Vec<String>>,
+        limit: usize,
+    }
+
+    struct NumbersCursor {
+        names: Arc<Vec<String>>,
+        limit: usize,
+        index: usize,
+    }
+
+    impl VTab for Numbers {
+        type Cursor = NumbersCursor;
+
+        fn schema(&self) -> String {
+            "CREATE TABLE x (value INTEGER, name TEXT)".into()
+        }
+
+        fn open(&self) -> sqlx::Result<NumbersCursor> {
+            Ok(NumbersCursor {
+                names: self.names.clone(),
+                limit: self.limit,
+                index: 0,
+            })
+        }
+    }
+
+    impl VTabCursor for NumbersCursor {
+        fn filter(&mut self) -> sqlx::Result<()> {
+            self.index = 0;
+            Ok(())
+        }
+
+        fn next(&mut self) -> sqlx::Result<()> {
+            self.index += 1;
+            Ok(())
+        }
+
+        fn eof(&self) -> bool {
+            self.index >= self.limit
+        }
+
+        fn column(&self, index: usize, column: &mut SqliteVTabColumn) -> sqlx::Result<()> {
+            match index {
+                0 => column.set(self.index as i64 + 1),
+                _ => column.set(self.names.get(self.index).cloned()),
+            }
+
+            Ok(())
+        }
+
+        fn rowid(&self) -> sqlx::Result<i64> {
+            Ok(self.index as i64)
+        }
+    }
+
+    let mut conn = SqliteConnection::connect("sqlite:%3Amemory%3A").await?;
+
+    let names = Arc::new(vec!["one".to_owned(), "two".to_owned(), "three".to_owned()]);
+
+    conn.create_module("numbers", move |arguments| {
+        let limit = match arguments.first() {
+            Some(limit) => limit
+                .parse()
+                .map_err(|_| sqlx::Error::Protocol("invalid limit".into()))?,
+            None => names.len(),
+        };
+
+        Ok(Numbers {
+            names: names.clone(),
+            limit,
+        })
+    })?;
+
+    // the module is a table itself
+    let (sum, count): (i64, i64) = sqlx::query_as("SELECT sum(value), count(name) FROM numbers")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!((sum, count), (6, 3));
+
+    conn.execute("CREATE VIRTUAL TABLE temp.five USING numbers(5)")
+        .await?;
+
+    let rows: Vec<(i64, Option<String>)> =
+        sqlx::query_as("SELECT value, name FROM five WHERE value > ? ORDER BY value DESC")
+            .bind(2_i64)
+            .fetch_all(&mut conn)
+            .await?;
+
+    assert_eq!(
+        rows,
+        vec![(5, None), (4, None), (3, Some("three".to_owned()))]
+    );
+
+    // joined with a table of the database
+    conn.execute("CREATE TABLE _sqlx_squares (value INTEGER, square INTEGER)")
+        .await?;
+    conn.execute("INSERT INTO _sqlx_squares VALUES (2, 4), (4, 16), (6, 36)")
+        .await?;
+
+    let (squares,): (i64,) =
+        sqlx::query_as("SELECT sum(square) FROM five JOIN _sqlx_squares USING (value)")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(squares, 20);
+
+    // the tables are read-only, and the errors of `connect` are returned
+    assert!(conn.execute("DELETE FROM five").await.is_err());
+
+    let err = conn
+        .execute("CREATE VIRTUAL TABLE temp.bad USING numbers(x)")
+        .await
+        .unwrap_err();
+
+    assert!(err.to_string().contains("invalid limit"), "{}", err);
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_retries_busy_statements() -> anyhow::Result<()> {
+    use sqlx::sqlite::{SqliteBusyRetry, SqliteError};
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    // a database file, with connections that do not share a cache, where a write transaction
+    // locks the whole database
+    let path = std::env::temp_dir().join(format!("sqlx-busy-{}.db", std::process::id()));
+    let url = format!("sqlite:file:{}?cache=private", path.display());
+
+    let mut writer = SqliteConnection::connect(&*url).await?;
+    let mut other = SqliteConnection::connect(&*url).await?;
+
+    writer
+        .execute("CREATE TABLE _sqlx_busy (id INTEGER PRIMARY KEY)")
+        .await?;
+    writer.execute("BEGIN IMMEDIATE").await?;
+
+    let attempts = Arc::new(AtomicU32::new(0));
+
+    other.set_busy_handler({
+        let attempts = attempts.clone();
+        move |attempt| {
+            attempts.store(attempt + 1, Ordering::SeqCst);
+            attempt < 3
+        }
+    });
+
+    let error = other
+        .execute("INSERT INTO _sqlx_busy DEFAULT VALUES")
+        .await
+        .unwrap_err();
+
+    match error {
+        sqlx::Error::Database(error) => assert!(error.downcast_ref::<SqliteError>().is_busy()),
+        error => panic!("expected a database error, got {:?}", error),
+    }
+
+    assert_eq!(attempts.load(Ordering::SeqCst), 4);
+
+    other.set_busy_retry(
+        SqliteBusyRetry::new()
+            .max_attempts(3)
+            .max_delay(Duration::from_millis(2)),
+    );
+
+    assert!(other
+        .execute("INSERT INTO _sqlx_busy DEFAULT VALUES")
+        .await
+        .is_err());
+
+    // the statement succeeds once the lock is released
+    writer.execute("COMMIT").await?;
+    other.clear_busy_handler();
+    other
+        .execute("INSERT INTO _sqlx_busy DEFAULT VALUES")
+        .await?;
+
+    drop((writer, other));
+
+    for suffix in &["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_opens_read_only_connections() -> anyhow::Result<()> {
+    use sqlx::SqlitePool;
+
+    let path = std::env::temp_dir().join(format!("sqlx-read-only-{}.db", std::process::id()));
+
+    let writer = SqlitePool::builder()
+        .max_size(1)
+        .build(&format!("sqlite:file:{}?cache=private", path.display()))
+        .await?;
+
+    let readers = SqlitePool::builder()
+        .max_size(4)
+        .build(&format!(
+            "sqlite:file:{}?mode=ro&cache=private",
+            path.display()
+        ))
+        .await?;
+
+    let mut conn = writer.acquire().await?;
+
+    assert!(!conn.is_read_only());
+
+    conn.execute("CREATE TABLE _sqlx_read_only (id INTEGER PRIMARY KEY)")
+        .await?;
+    conn.execute("INSERT INTO _sqlx_read_only DEFAULT VALUES")
+        .await?;
+
+    let mut reader = readers.acquire().await?;
+
+    assert!(reader.is_read_only());
+
+    let (count,): (i64,) = sqlx::query_as("SELECT count(*) FROM _sqlx_read_only")
+        .fetch_one(&mut reader)
+        .await?;
+
+    assert_eq!(count, 1);
+    assert!(reader
+        .execute("INSERT INTO _sqlx_read_only DEFAULT VALUES")
+        .await
+        .is_err());
+
+    drop((conn, reader));
+    readers.close().await;
+    writer.close().await;
+
+    // a database with a rollback journal is read as it is, as the journal mode can not be set
+    let mut conn = SqliteConnection::connect(&*format!("sqlite://{}", path.display())).await?;
+
+    conn.execute("PRAGMA journal_mode = DELETE").await?;
+
+    drop(conn);
+
+    // an immutable database is read without locks, as nothing may change it
+    let mut conn =
+        SqliteConnection::connect(&*format!("sqlite:file:{}?immutable=1", path.display())).await?;
+
+    assert!(conn.is_read_only());
+
+    let (count,): (i64,) = sqlx::query_as("SELECT count(*) FROM _sqlx_read_only")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 1);
+
+    drop(conn);
+
+    for suffix in &["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_sets_limits_and_defensive_mode() -> anyhow::Result<()> {
+    use sqlx::sqlite::SqliteLimit;
+
+    let mut conn = SqliteConnection::connect(
+        "sqlite:%3Amemory%3A?limit.sql_length=1000&limit.attached=0&defensive=true",
+    )
+    .await?;
+
+    assert_eq!(conn.limit(SqliteLimit::SqlLength), 1000);
+    assert_eq!(conn.limit(SqliteLimit::Attached), 0);
+
+    let long = format!("SELECT '{}'", "x".repeat(1000));
+
+    assert!(conn.execute(&*long).await.is_err());
+    assert!(conn
+        .execute("ATTACH DATABASE ':memory:' AS other")
+        .await
+        .is_err());
+
+    // the schema can not be written to, even with `writable_schema`
+    conn.execute("PRAGMA writable_schema = ON").await?;
+
+    assert!(conn
+        .execute("UPDATE sqlite_master SET sql = NULL")
+        .await
+        .is_err());
+
+    assert_eq!(conn.set_limit(SqliteLimit::SqlLength, 2000), 1000);
+
+    conn.execute(&*long).await?;
+
+    conn.set_defensive(false)?;
+    conn.execute("CREATE TABLE _sqlx_defensive (id INTEGER)")
+        .await?;
+    conn.execute("UPDATE sqlite_master SET sql = sql").await?;
+
+    for url in &[
+        "sqlite:%3Amemory%3A?limit.sql-length=1000",
+        "sqlite:%3Amemory%3A?limit.sql_length=-1",
+        "sqlite:%3Amemory%3A?defensive=yes",
+    ] {
+        assert!(SqliteConnection::connect(*url).await.is_err());
+    }
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_reports_pool_status() -> anyhow::Result<()> {
+    use sqlx::SqlitePool;
+    use std::time::Duration;
+
+    let pool = SqlitePool::builder()
+        .max_size(1)
+        .connect_timeout(Duration::from_millis(100))
+        .build("sqlite:%3Amemory%3A")
+        .await?;
+
+    let conn = pool.acquire().await?;
+    let status = pool.status();
+
+    assert_eq!((status.size, status.idle, status.in_use), (1, 0, 1));
+    assert_eq!(status.connections_created, 1);
+
+    // a second acquire waits for the connection
+    let mut waiting = Box::pin(pool.acquire());
+
+    assert!(futures::poll!(&mut waiting).is_pending());
+    assert_eq!(pool.status().waiters, 1);
+
+    drop(conn);
+
+    let conn = waiting.await?;
+
+    assert_eq!(pool.status().waiters, 0);
+    assert!(pool.acquire().await.is_err());
+    assert_eq!(pool.status().acquire_timeouts, 1);
+
+    conn.close().await?;
+
+    let status = pool.status();
+
+    assert_eq!((status.size, status.idle, status.in_use), (0, 0, 0));
+    assert_eq!(status.connections_closed, 1);
+    assert_eq!(
+        status
+            .acquire_wait
+            .iter()
+            .map(|(_, count)| count)
+            .sum::<u64>(),
+        2
+    );
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_keeps_the_minimum_pool_size() -> anyhow::Result<()> {
+    use sqlx::SqlitePool;
+    use std::time::{Duration, Instant};
+
+    let pool = SqlitePool::builder()
+        .min_size(2)
+        .max_size(4)
+        .build("sqlite:%3Amemory%3A")
+        .await?;
+
+    assert_eq!((pool.size(), pool.idle()), (2, 2));
+
+    // a closed connection is replaced in the background
+    pool.acquire().await?.close().await?;
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+
+    while pool.size() < 2 {
+        assert!(Instant::now() < deadline, "the connection was not replaced");
+
+        #[cfg(feature = "runtime-async-std")]
+        async_std::task::sleep(Duration::from_millis(10)).await;
+
+        #[cfg(feature = "runtime-tokio")]
+        tokio::time::delay_for(Duration::from_millis(10)).await;
+    }
+
+    // or right away
+    pool.acquire().await?.close().await?;
+    pool.acquire().await?.close().await?;
+    pool.warm_up().await?;
+
+    assert_eq!(pool.size(), 2);
+
+    pool.close().await;
+
+    Ok(())
+}
+
+#[cfg(feature = "sqlite-snapshot")]
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_reads_from_snapshots() -> anyhow::Result<()> {
+    use sqlx::SqlitePool;
+
+    // a database file, as snapshots are of the write-ahead log, and connections that do not
+    // share a cache, which would lock the table while it is read
+    let path = std::env::temp_dir().join(format!("sqlx-snapshot-{}.db", std::process::id()));
+    let pool = SqlitePool::builder()
+        .max_size(3)
+        .build(&format!("sqlite:file:{}?cache=private", path.display()))
+        .await?;
+
+    let mut writer = pool.acquire().await?;
+
+    writer
+        .execute("CREATE TABLE _sqlx_snapshot (id INTEGER PRIMARY KEY)")
+        .await?;
+    writer
+        .execute("INSERT INTO _sqlx_snapshot DEFAULT VALUES")
+        .await?;
+
+    // a snapshot can only be taken in a read transaction
+    assert!(writer.snapshot().await.is_err());
+
+    let mut first = pool.begin().await?;
+    let (count,): (i64,) = sqlx::query_as("SELECT count(*) FROM _sqlx_snapshot")
+        .fetch_one(&mut first)
+        .await?;
+
+    assert_eq!(count, 1);
+
+    let snapshot = first.snapshot().await?;
+
+    writer
+        .execute("INSERT INTO _sqlx_snapshot DEFAULT VALUES")
+        .await?;
+
+    // another connection reads the database as it was when the snapshot was taken
+    let mut second = pool.begin().await?;
+    second.open_snapshot(&snapshot).await?;
+
+    let (count,): (i64,) = sqlx::query_as("SELECT count(*) FROM _sqlx_snapshot")
+        .fetch_one(&mut second)
+        .await?;
+
+    assert_eq!(count, 1);
+
+    let (count,): (i64,) = sqlx::query_as("SELECT count(*) FROM _sqlx_snapshot")
+        .fetch_one(&mut writer)
+        .await?;
+
+    assert_eq!(count, 2);
+
+    first.rollback().await?;
+    second.rollback().await?;
+    drop((writer, snapshot));
+    pool.close().await;
+
+    for suffix in &["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "sqlite-preupdate-hook")]
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_captures_changes_with_the_preupdate_hook() -> anyhow::Result<()> {
+    use sqlx::sqlite::SqliteOperation;
+    use std::sync::{Arc, Mutex};
+
+    let mut conn = new::<Sqlite>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE _sqlx_cdc (id INTEGER PRIMARY KEY, name TEXT)")
+        .await?;
+
+    let changes = Arc::new(Mutex::new(Vec::new()));
+
+    conn.set_preupdate_hook({
+        let changes = changes.clone();
+
+        move |change| {
+            let old: Option<String> = match change.operation() {
+                SqliteOperation::Insert => None,
+                _ => change.old_value(1).unwrap(),
+            };
+
+            let new: Option<String> = match change.operation() {
+                SqliteOperation::Delete => None,
+                _ => change.new_value(1).unwrap(),
+            };
+
+            // the old values of an insert do not exist
+            if change.operation() == SqliteOperation::Insert {
+                assert!(change.old_value::<String>(1).is_err());
+            }
+
+            changes.lock().unwrap().push((
+                change.operation(),
+                change.table().to_owned(),
+                change.new_rowid(),
+                old,
+                new,
+            ));
+        }
+    });
+
+    conn.execute("INSERT INTO _sqlx_cdc (id, name) VALUES (1, 'a')")
+        .await?;
+    conn.execute("UPDATE _sqlx_cdc SET name = 'b' WHERE id = 1")
+        .await?;
+    conn.execute("DELETE FROM _sqlx_cdc").await?;
+
+    conn.clear_preupdate_hook();
+    conn.execute("INSERT INTO _sqlx_cdc (id, name) VALUES (2, 'c')")
+        .await?;
+
+    let table = "_sqlx_cdc".to_owned();
+
+    assert_eq!(
+        *changes.lock().unwrap(),
+        vec![
+            (
+                SqliteOperation::Insert,
+                table.clone(),
+                1,
+                None,
+                Some("a".to_owned())
+            ),
+            (
+                SqliteOperation::Update,
+                table.clone(),
+                1,
+                Some("a".to_owned()),
+                Some("b".to_owned())
+            ),
+            (
+                SqliteOperation::Delete,
+                table,
+                1,
+                Some("b".to_owned()),
+                None
+            ),
+        ]
+    );
+
+    Ok(())
+}
+
+#[cfg_attr(feature = "runtime-async-std", async_std::test)]
+#[cfg_attr(feature = "runtime-tokio", tokio::test)]
+async fn it_can_begin_transactions_with_options() -> anyhow::Result<()> {
+    use sqlx::{TransactionBehavior, TransactionOptions};
+
+    let conn = new::<Sqlite>().await?;
+
+    let mut tx = conn
+        .begin_with(TransactionOptions::new().behavior(TransactionBehavior::Immediate))
+        .await?;
+
+    tx.execute("CREATE TEMPORARY TABLE _sqlx_tx_options (id INTEGER)")
+        .await?;
+
+    let conn = tx.commit().await?;
+
+    // read-only transactions are not supported
+    assert!(conn
+        .begin_with(TransactionOptions::new().read_only(true))
+        .await
+        .is_err());
+
+    Ok(())
+}